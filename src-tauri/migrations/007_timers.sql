@@ -0,0 +1,17 @@
+-- Running time-tracking timers
+-- Rows persist across restarts; elapsed_minutes is recorded whenever a timer is paused
+
+CREATE TABLE IF NOT EXISTS timers (
+    id TEXT PRIMARY KEY,
+    time_entry_id TEXT NOT NULL,
+    matter_id TEXT NOT NULL,
+    attorney_id TEXT NOT NULL,
+    started_at TIMESTAMP NOT NULL,
+    paused_at TIMESTAMP,
+    total_pause_duration_minutes INTEGER NOT NULL DEFAULT 0,
+    elapsed_minutes INTEGER NOT NULL DEFAULT 0,
+    is_running BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_timers_attorney ON timers(attorney_id);
+CREATE INDEX IF NOT EXISTS idx_timers_running ON timers(is_running);
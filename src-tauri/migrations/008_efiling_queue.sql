@@ -0,0 +1,17 @@
+-- E-filing retry queue
+-- Failed submissions are retried with backoff; rows that exhaust retries stay as dead letters
+
+CREATE TABLE IF NOT EXISTS efiling_queue (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    submission TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'retrying',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_efiling_queue_due ON efiling_queue(status, next_attempt_at);
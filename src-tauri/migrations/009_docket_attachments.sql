@@ -0,0 +1,15 @@
+-- Documents attached to dockets locally, such as e-filing acceptance receipts
+
+CREATE TABLE IF NOT EXISTS docket_attachments (
+    id TEXT PRIMARY KEY,
+    docket_id TEXT NOT NULL,
+    submission_id TEXT,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    attachment_type TEXT,
+    size INTEGER,
+    hash TEXT,
+    upload_date TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_docket_attachments_docket ON docket_attachments(docket_id);
@@ -0,0 +1,35 @@
+-- CRM leads and conflict check history
+
+CREATE TABLE IF NOT EXISTS leads (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    phone TEXT NOT NULL DEFAULT '',
+    source TEXT NOT NULL,
+    status TEXT NOT NULL,
+    practice_area TEXT NOT NULL DEFAULT '',
+    opposing_party TEXT,
+    notes TEXT NOT NULL DEFAULT '',
+    contact_attempts INTEGER NOT NULL DEFAULT 0,
+    responses INTEGER NOT NULL DEFAULT 0,
+    first_response_hours REAL,
+    score REAL NOT NULL DEFAULT 0,
+    client_id TEXT,
+    matter_id TEXT,
+    conflict_check_id TEXT,
+    converted_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_leads_status ON leads(status);
+
+CREATE TABLE IF NOT EXISTS conflict_checks (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT,
+    checked_at TIMESTAMP NOT NULL,
+    checked_by TEXT NOT NULL,
+    parties TEXT NOT NULL,
+    conflicts_found TEXT NOT NULL,
+    status TEXT NOT NULL,
+    resolution TEXT
+);
@@ -0,0 +1,38 @@
+-- Email templates, marketing unsubscribes and per-recipient campaign sends
+
+CREATE TABLE IF NOT EXISTS email_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body_html TEXT NOT NULL,
+    variables TEXT NOT NULL DEFAULT '[]', -- JSON array
+    attachments TEXT NOT NULL DEFAULT '[]', -- JSON array
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    usage_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS marketing_unsubscribes (
+    contact_id TEXT PRIMARY KEY,
+    email TEXT,
+    unsubscribed_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_marketing_unsubscribes_email ON marketing_unsubscribes(email);
+
+CREATE TABLE IF NOT EXISTS campaign_sends (
+    id TEXT PRIMARY KEY,
+    template_id TEXT NOT NULL,
+    contact_id TEXT NOT NULL,
+    email TEXT NOT NULL,
+    provider_message_id TEXT,
+    sent_at TIMESTAMP NOT NULL,
+    opened_at TIMESTAMP,
+    clicked_at TIMESTAMP,
+    open_count INTEGER NOT NULL DEFAULT 0,
+    click_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_sends_template ON campaign_sends(template_id);
+CREATE INDEX IF NOT EXISTS idx_campaign_sends_contact ON campaign_sends(contact_id);
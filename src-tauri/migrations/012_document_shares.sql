@@ -0,0 +1,26 @@
+-- Client portal document share links and their access log
+
+CREATE TABLE IF NOT EXISTS document_shares (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    client_id TEXT NOT NULL,
+    document_path TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_shares_matter ON document_shares(matter_id);
+
+CREATE TABLE IF NOT EXISTS document_share_access (
+    id TEXT PRIMARY KEY,
+    share_id TEXT,
+    matter_id TEXT NOT NULL,
+    client_id TEXT NOT NULL,
+    granted BOOLEAN NOT NULL,
+    reason TEXT,
+    accessed_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_share_access_share ON document_share_access(share_id);
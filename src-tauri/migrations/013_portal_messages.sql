@@ -0,0 +1,20 @@
+-- Client portal message threads (one per matter) and read receipts
+
+CREATE TABLE IF NOT EXISTS portal_matter_messages (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    author_role TEXT NOT NULL, -- client, staff
+    author_id TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    body TEXT NOT NULL, -- sanitized HTML
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_portal_matter_messages_matter ON portal_matter_messages(matter_id, created_at);
+
+CREATE TABLE IF NOT EXISTS portal_message_reads (
+    message_id TEXT NOT NULL,
+    reader_id TEXT NOT NULL,
+    read_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (message_id, reader_id)
+);
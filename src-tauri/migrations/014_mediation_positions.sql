@@ -0,0 +1,12 @@
+-- Mediation positions exchanged between parties, one row per move
+
+CREATE TABLE IF NOT EXISTS mediation_positions (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    party TEXT NOT NULL, -- plaintiff, defense
+    amount REAL NOT NULL,
+    round INTEGER NOT NULL,
+    recorded_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_mediation_positions_matter ON mediation_positions(matter_id, round);
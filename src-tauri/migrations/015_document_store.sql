@@ -0,0 +1,18 @@
+-- Content-addressed document blobs, shared across matters and reference counted
+
+CREATE TABLE IF NOT EXISTS document_blobs (
+    hash TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS document_refs (
+    hash TEXT NOT NULL REFERENCES document_blobs(hash),
+    owner TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (hash, owner)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_refs_owner ON document_refs(owner);
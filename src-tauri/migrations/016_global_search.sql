@@ -0,0 +1,42 @@
+-- Unified full-text index across matter notes, emails, contracts and docket captions.
+-- Notes and cached dockets are kept in sync by triggers; emails and contracts are
+-- indexed by their services when saved.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS global_search_index USING fts5(
+    scope UNINDEXED,
+    entity_id UNINDEXED,
+    matter_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS case_notes_search_insert AFTER INSERT ON case_notes BEGIN
+    INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+    VALUES ('matter_note', new.id, new.matter_id, COALESCE(new.title, ''), new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS case_notes_search_delete AFTER DELETE ON case_notes BEGIN
+    DELETE FROM global_search_index WHERE scope = 'matter_note' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS case_notes_search_update AFTER UPDATE ON case_notes BEGIN
+    DELETE FROM global_search_index WHERE scope = 'matter_note' AND entity_id = old.id;
+    INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+    VALUES ('matter_note', new.id, new.matter_id, COALESCE(new.title, ''), new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS docket_cache_search_insert AFTER INSERT ON docket_cache BEGIN
+    INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+    VALUES ('docket', new.id, NULL, new.docket_number, COALESCE(json_extract(new.data, '$.caption'), ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS docket_cache_search_delete AFTER DELETE ON docket_cache BEGIN
+    DELETE FROM global_search_index WHERE scope = 'docket' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS docket_cache_search_update AFTER UPDATE ON docket_cache BEGIN
+    DELETE FROM global_search_index WHERE scope = 'docket' AND entity_id = old.id;
+    INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+    VALUES ('docket', new.id, NULL, new.docket_number, COALESCE(json_extract(new.data, '$.caption'), ''));
+END;
@@ -0,0 +1,38 @@
+-- Optimistic-concurrency versions for records edited from both the UI and
+-- background jobs; saves only succeed against the version that was loaded
+
+ALTER TABLE time_entries ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS invoices (
+    id TEXT PRIMARY KEY,
+    invoice_number TEXT NOT NULL UNIQUE,
+    matter_id TEXT NOT NULL,
+    matter_name TEXT NOT NULL,
+    client_id TEXT NOT NULL,
+    client_name TEXT NOT NULL,
+    billing_period_start TIMESTAMP NOT NULL,
+    billing_period_end TIMESTAMP NOT NULL,
+    issue_date TIMESTAMP NOT NULL,
+    due_date TIMESTAMP NOT NULL,
+    time_entries_json TEXT NOT NULL,
+    expenses_json TEXT NOT NULL,
+    adjustments_json TEXT NOT NULL,
+    subtotal REAL NOT NULL,
+    discount_amount REAL NOT NULL,
+    tax_amount REAL NOT NULL,
+    total REAL NOT NULL,
+    amount_paid REAL NOT NULL,
+    balance REAL NOT NULL,
+    status TEXT NOT NULL,
+    sent_at TIMESTAMP,
+    viewed_at TIMESTAMP,
+    paid_at TIMESTAMP,
+    notes TEXT,
+    terms TEXT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    created_by TEXT NOT NULL,
+    version INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_matter ON invoices(matter_id);
@@ -0,0 +1,16 @@
+-- Queued document drafting jobs; the job body is stored as JSON and the
+-- status columns are updated as the runner processes it
+
+CREATE TABLE IF NOT EXISTS draft_jobs (
+    id TEXT PRIMARY KEY,
+    template_id TEXT NOT NULL,
+    court_id TEXT NOT NULL,
+    job_json TEXT NOT NULL,
+    status TEXT NOT NULL,
+    result_path TEXT,
+    error_message TEXT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_draft_jobs_status ON draft_jobs(status, created_at);
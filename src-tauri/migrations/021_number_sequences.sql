@@ -0,0 +1,28 @@
+-- Counters for generated invoice and matter numbers. Incrementing a row is a
+-- single atomic statement, unlike numbering off COUNT(*), which hands out the
+-- same number to concurrent requests. `period` is the year for schemes that
+-- reset yearly and '' otherwise.
+
+CREATE TABLE IF NOT EXISTS number_sequences (
+    name TEXT NOT NULL,
+    period TEXT NOT NULL DEFAULT '',
+    last_value INTEGER NOT NULL,
+    PRIMARY KEY (name, period)
+);
+
+-- Continue from invoices already numbered INV-NNNNNN
+INSERT OR IGNORE INTO number_sequences (name, period, last_value)
+SELECT 'invoice', '', MAX(CAST(substr(invoice_number, 5) AS INTEGER))
+FROM invoices
+WHERE invoice_number LIKE 'INV-%'
+HAVING COUNT(*) > 0;
+
+-- Continue from matters already numbered TYPE-YYYY-NNNN
+INSERT OR IGNORE INTO number_sequences (name, period, last_value)
+SELECT
+    'matter:' || substr(matter_number, 1, instr(matter_number, '-') - 1),
+    substr(matter_number, instr(matter_number, '-') + 1, 4),
+    MAX(CAST(substr(matter_number, instr(matter_number, '-') + 6) AS INTEGER))
+FROM matters
+WHERE matter_number GLOB '*-[0-9][0-9][0-9][0-9]-*'
+GROUP BY 1, 2;
@@ -0,0 +1,14 @@
+-- Trusted timestamps proving a document's SHA-256 existed at a point in time.
+-- `token` is the authority's signed response (RFC 3161 TimeStampToken, DER),
+-- base64 encoded
+
+CREATE TABLE IF NOT EXISTS document_anchors (
+    id TEXT PRIMARY KEY,
+    document_hash TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    token TEXT NOT NULL,
+    anchored_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_anchors_hash ON document_anchors(document_hash);
@@ -0,0 +1,32 @@
+-- Ingested case law and code sections from bulk sources
+-- Migration 023: normalized records from GovInfo (USLM) and Harvard CAP,
+-- deduplicated by normalized citation
+
+CREATE TABLE IF NOT EXISTS ingested_cases (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL, -- GovInfo, HarvardCaselaw
+    source_id TEXT NOT NULL,
+    title TEXT NOT NULL, -- case name or section heading
+    court TEXT,
+    jurisdiction TEXT,
+    decision_date TEXT,
+    docket_number TEXT,
+    citation TEXT NOT NULL, -- primary normalized citation
+    parallel_citations TEXT NOT NULL DEFAULT '[]', -- JSON array
+    text TEXT,
+    url TEXT,
+    ingested_at TEXT NOT NULL,
+
+    UNIQUE(source, source_id)
+);
+
+-- Every normalized citation of every ingested record; a record sharing any
+-- citation with an existing one is a duplicate
+CREATE TABLE IF NOT EXISTS ingested_case_citations (
+    citation TEXT PRIMARY KEY,
+    case_id TEXT NOT NULL REFERENCES ingested_cases(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingested_cases_citation ON ingested_cases(citation);
+CREATE INDEX IF NOT EXISTS idx_ingested_cases_court ON ingested_cases(court);
+CREATE INDEX IF NOT EXISTS idx_ingested_case_citations_case ON ingested_case_citations(case_id);
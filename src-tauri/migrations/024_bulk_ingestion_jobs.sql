@@ -0,0 +1,23 @@
+-- Bulk ingestion jobs and their resume checkpoints
+-- Migration 024: `checkpoint` counts the records already committed, so a
+-- cancelled or interrupted file ingestion resumes after them
+
+CREATE TABLE IF NOT EXISTS bulk_ingestion_jobs (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,
+    job_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    completed_at TEXT,
+    records_processed INTEGER NOT NULL DEFAULT 0,
+    records_failed INTEGER NOT NULL DEFAULT 0,
+    total_size_bytes INTEGER NOT NULL DEFAULT 0,
+    error_log TEXT NOT NULL DEFAULT '[]', -- JSON array
+
+    -- Resume capability
+    source_path TEXT,
+    total_records INTEGER,
+    checkpoint INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_bulk_ingestion_jobs_source ON bulk_ingestion_jobs(source, status);
@@ -0,0 +1,94 @@
+-- no-transaction
+-- Stored settlement calculations and demand letters
+-- Migration 025: the 006 tables referenced a `cases` table that does not
+-- exist, so with foreign keys enforced no row could ever be inserted. Both
+-- tables are recreated against `matters`. The full calculation, including
+-- its offers and notes, is kept as JSON alongside the summary columns.
+-- Dropping a table whose parent is missing fails while foreign keys are
+-- enforced, and the pragma only takes effect outside a transaction.
+
+PRAGMA foreign_keys = OFF;
+
+DROP TABLE IF EXISTS demand_letters;
+DROP TABLE IF EXISTS settlement_calculations;
+
+CREATE TABLE settlement_calculations (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    case_type TEXT NOT NULL,
+    plaintiff_name TEXT NOT NULL,
+    defendant_name TEXT NOT NULL,
+    incident_date TIMESTAMP,
+
+    -- Damages totals
+    total_economic_damages REAL NOT NULL DEFAULT 0.0,
+    total_non_economic_damages REAL NOT NULL DEFAULT 0.0,
+    total_punitive_damages REAL DEFAULT NULL,
+    total_damages REAL NOT NULL DEFAULT 0.0,
+
+    -- Settlement recommendations
+    recommended_demand REAL NOT NULL,
+    minimum_settlement REAL NOT NULL,
+    target_settlement REAL NOT NULL,
+
+    -- Jurisdiction
+    jurisdiction TEXT NOT NULL,
+    state_code TEXT NOT NULL,
+    adjusted_for_caps BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Attorney fees
+    estimated_attorney_fees REAL NOT NULL DEFAULT 0.0,
+    litigation_costs_to_date REAL NOT NULL DEFAULT 0.0,
+    projected_additional_costs REAL NOT NULL DEFAULT 0.0,
+    net_to_client REAL NOT NULL DEFAULT 0.0,
+
+    -- Negotiation tracking
+    current_negotiation_round INTEGER NOT NULL DEFAULT 0,
+
+    -- Metadata
+    calculated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    calculated_by TEXT NOT NULL,
+    last_updated TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    version TEXT NOT NULL DEFAULT '2.0.0',
+
+    calculation_json TEXT NOT NULL,
+
+    FOREIGN KEY (matter_id) REFERENCES matters(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_matter ON settlement_calculations(matter_id);
+CREATE INDEX IF NOT EXISTS idx_settlement_date ON settlement_calculations(calculated_at);
+CREATE INDEX IF NOT EXISTS idx_settlement_jurisdiction ON settlement_calculations(jurisdiction);
+
+CREATE TABLE demand_letters (
+    id TEXT PRIMARY KEY,
+    settlement_calculation_id TEXT NOT NULL,
+    matter_id TEXT NOT NULL,
+
+    recipient_name TEXT NOT NULL,
+    recipient_address TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    opening_paragraph TEXT NOT NULL,
+    facts_section TEXT NOT NULL,
+    liability_section TEXT NOT NULL,
+    damages_section TEXT NOT NULL,
+    settlement_demand REAL NOT NULL,
+    deadline TIMESTAMP NOT NULL,
+    closing_paragraph TEXT NOT NULL,
+
+    letter_html TEXT NOT NULL,
+    letter_pdf_path TEXT DEFAULT NULL,
+
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by TEXT NOT NULL,
+    sent_at TIMESTAMP DEFAULT NULL,
+
+    FOREIGN KEY (settlement_calculation_id) REFERENCES settlement_calculations(id) ON DELETE CASCADE,
+    FOREIGN KEY (matter_id) REFERENCES matters(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_demand_calc ON demand_letters(settlement_calculation_id);
+CREATE INDEX IF NOT EXISTS idx_demand_matter ON demand_letters(matter_id);
+CREATE INDEX IF NOT EXISTS idx_demand_created ON demand_letters(created_at);
+
+PRAGMA foreign_keys = ON;
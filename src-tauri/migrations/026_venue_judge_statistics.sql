@@ -0,0 +1,48 @@
+-- Venue and judge statistics for settlement analysis
+-- Migration 026: lookup tables read when a matter names its county or judge.
+-- Venue figures are seeded with starting estimates for the firm's main
+-- Pennsylvania venues and should be refined from the firm's own results.
+-- Judge histories are recorded by the firm as it appears before each judge.
+
+CREATE TABLE IF NOT EXISTS venue_statistics (
+    county TEXT PRIMARY KEY COLLATE NOCASE,
+    state_code TEXT NOT NULL,
+    average_plaintiff_verdict REAL NOT NULL,
+    plaintiff_win_rate REAL NOT NULL,
+    median_time_to_trial INTEGER NOT NULL, -- months
+
+    -- Jury pool
+    median_age REAL NOT NULL,
+    median_income REAL NOT NULL,
+    education_level TEXT NOT NULL,
+    urban_rural TEXT NOT NULL CHECK (urban_rural IN ('Urban', 'Suburban', 'Rural', 'Mixed')),
+
+    political_lean TEXT NOT NULL CHECK (political_lean IN ('Liberal', 'Moderate', 'Conservative')),
+    tort_reform_climate TEXT NOT NULL CHECK (tort_reform_climate IN ('ProPlaintiff', 'Balanced', 'ProDefense')),
+
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS judge_histories (
+    judge_name TEXT PRIMARY KEY COLLATE NOCASE,
+    county TEXT,
+    average_plaintiff_verdict REAL NOT NULL,
+    plaintiff_win_rate REAL NOT NULL,
+    median_verdict_ratio REAL NOT NULL,
+    trials_presided INTEGER NOT NULL DEFAULT 0,
+    settlement_encouragement TEXT NOT NULL CHECK (settlement_encouragement IN ('StronglyEncourages', 'Encourages', 'Neutral', 'TrialOriented')),
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_judge_histories_county ON judge_histories(county);
+
+INSERT OR IGNORE INTO venue_statistics (
+    county, state_code, average_plaintiff_verdict, plaintiff_win_rate, median_time_to_trial,
+    median_age, median_income, education_level, urban_rural, political_lean, tort_reform_climate
+) VALUES
+    ('Philadelphia', 'PA', 610000.0, 0.64, 26, 35.0, 52000.0, 'Some college', 'Urban', 'Liberal', 'ProPlaintiff'),
+    ('Allegheny', 'PA', 405000.0, 0.57, 22, 41.0, 68000.0, 'Bachelor''s degree', 'Urban', 'Moderate', 'Balanced'),
+    ('Montgomery', 'PA', 380000.0, 0.55, 20, 42.0, 100000.0, 'Bachelor''s degree', 'Suburban', 'Moderate', 'Balanced'),
+    ('Delaware', 'PA', 350000.0, 0.56, 21, 40.0, 80000.0, 'Some college', 'Suburban', 'Moderate', 'Balanced'),
+    ('Bucks', 'PA', 330000.0, 0.52, 20, 45.0, 95000.0, 'Some college', 'Suburban', 'Moderate', 'Balanced'),
+    ('Lancaster', 'PA', 240000.0, 0.46, 18, 39.0, 72000.0, 'High school', 'Rural', 'Conservative', 'ProDefense');
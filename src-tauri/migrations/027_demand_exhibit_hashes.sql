@@ -0,0 +1,7 @@
+-- Demand letter exhibit attachments
+-- Migration 027: attached exhibits are copied into the document store and
+-- keep the hash of the stored copy. A letter cannot use an exhibit letter twice.
+
+ALTER TABLE demand_exhibits ADD COLUMN content_hash TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exhibits_demand_letter ON demand_exhibits(demand_letter_id, exhibit_letter);
@@ -0,0 +1,9 @@
+-- Demand letter expiration tracking
+-- Migration 028: a demand is Pending until answered; one whose deadline
+-- passes unanswered moves to FollowUp.
+
+ALTER TABLE demand_letters ADD COLUMN status TEXT NOT NULL DEFAULT 'Pending'
+    CHECK (status IN ('Pending', 'Answered', 'FollowUp'));
+ALTER TABLE demand_letters ADD COLUMN responded_at TIMESTAMP DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_demand_status_deadline ON demand_letters(status, deadline);
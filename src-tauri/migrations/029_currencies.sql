@@ -0,0 +1,5 @@
+-- Invoice currency
+-- Migration 029: invoices are billed in a single currency; existing
+-- invoices were all issued in US dollars.
+
+ALTER TABLE invoices ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
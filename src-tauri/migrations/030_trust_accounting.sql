@@ -0,0 +1,77 @@
+-- Trust accounting (IOLTA compliance)
+-- Migration 030: trust accounts, their ledger, reconciliation history and
+-- the record of IOLTA interest remitted to the bar foundation. Entries that
+-- belong to the account rather than a client (interest and its remittance)
+-- are posted with an empty client_id and matter_id.
+
+CREATE TABLE IF NOT EXISTS trust_accounts (
+    id TEXT PRIMARY KEY,
+    account_name TEXT NOT NULL,
+    account_number TEXT NOT NULL,
+    bank_name TEXT NOT NULL,
+    routing_number TEXT NOT NULL,
+    account_type TEXT NOT NULL DEFAULT 'IOLTA', -- IOLTA, Non-IOLTA
+    current_balance REAL NOT NULL DEFAULT 0.0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    opened_date TIMESTAMP NOT NULL,
+    closed_date TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS trust_transactions (
+    id TEXT PRIMARY KEY,
+    trust_account_id TEXT NOT NULL,
+    matter_id TEXT NOT NULL,
+    client_id TEXT NOT NULL,
+    transaction_type TEXT NOT NULL,
+    transaction_date TIMESTAMP NOT NULL,
+    amount REAL NOT NULL, -- negative for money leaving the account
+    description TEXT NOT NULL,
+    reference_number TEXT,
+    is_reconciled BOOLEAN NOT NULL DEFAULT FALSE,
+    reconciled_at TIMESTAMP,
+    bank_statement_date TIMESTAMP,
+    invoice_id TEXT,
+    payment_id TEXT,
+    created_at TIMESTAMP NOT NULL,
+    created_by TEXT NOT NULL,
+    FOREIGN KEY (trust_account_id) REFERENCES trust_accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trust_transactions_account ON trust_transactions(trust_account_id, is_reconciled);
+CREATE INDEX IF NOT EXISTS idx_trust_transactions_client ON trust_transactions(client_id, matter_id);
+
+CREATE TABLE IF NOT EXISTS trust_reconciliations (
+    id TEXT PRIMARY KEY,
+    trust_account_id TEXT NOT NULL,
+    reconciliation_date TIMESTAMP NOT NULL,
+    statement_date TIMESTAMP NOT NULL,
+    statement_balance REAL NOT NULL,
+    book_balance REAL NOT NULL,
+    difference REAL NOT NULL,
+    unreconciled_deposits_json TEXT NOT NULL,
+    unreconciled_withdrawals_json TEXT NOT NULL,
+    is_reconciled BOOLEAN NOT NULL,
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL,
+    created_by TEXT NOT NULL,
+    FOREIGN KEY (trust_account_id) REFERENCES trust_accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trust_reconciliations_account ON trust_reconciliations(trust_account_id, statement_date);
+
+CREATE TABLE IF NOT EXISTS iolta_remittances (
+    id TEXT PRIMARY KEY,
+    trust_account_id TEXT NOT NULL,
+    interest_transaction_id TEXT NOT NULL,
+    remittance_transaction_id TEXT NOT NULL,
+    amount REAL NOT NULL,
+    period_start TIMESTAMP NOT NULL,
+    period_end TIMESTAMP NOT NULL,
+    remitted_to TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    created_by TEXT NOT NULL,
+    UNIQUE (trust_account_id, period_start, period_end),
+    FOREIGN KEY (trust_account_id) REFERENCES trust_accounts(id),
+    FOREIGN KEY (interest_transaction_id) REFERENCES trust_transactions(id),
+    FOREIGN KEY (remittance_transaction_id) REFERENCES trust_transactions(id)
+);
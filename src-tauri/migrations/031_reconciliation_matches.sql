@@ -0,0 +1,6 @@
+-- Bank statement matching
+-- Migration 031: a reconciliation records which book transactions were
+-- matched to statement lines and which statement lines matched nothing.
+
+ALTER TABLE trust_reconciliations ADD COLUMN matched_transaction_ids_json TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE trust_reconciliations ADD COLUMN unmatched_statement_lines_json TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,19 @@
+-- Billing rates
+-- Migration 032: hourly rates by attorney, optionally narrowed to an
+-- activity, matter or client. Firm-wide defaults are stored under the
+-- attorney id 'firm'.
+
+CREATE TABLE IF NOT EXISTS billing_rates (
+    id TEXT PRIMARY KEY,
+    attorney_id TEXT NOT NULL,
+    activity_type TEXT,
+    matter_id TEXT,
+    client_id TEXT,
+    rate_type TEXT NOT NULL DEFAULT 'Standard',
+    hourly_rate REAL NOT NULL CHECK (hourly_rate >= 0),
+    effective_from TIMESTAMP NOT NULL,
+    effective_to TIMESTAMP,
+    is_default BOOLEAN NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_billing_rates_attorney ON billing_rates(attorney_id, effective_from);
@@ -0,0 +1,57 @@
+-- Time entry tracking columns
+-- Migration 033: time entries carry the timer, activity and billing fields
+-- the time tracking service records. The table is rebuilt because the
+-- original day/hours columns were NOT NULL; existing entries keep their
+-- values and are carried over as stopped manual entries.
+
+CREATE TABLE time_entries_new (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    task_id TEXT,
+    attorney_id TEXT,
+    attorney_name TEXT NOT NULL DEFAULT '',
+    start_time TIMESTAMP NOT NULL,
+    end_time TIMESTAMP,
+    duration_minutes INTEGER,
+    billable_minutes INTEGER,
+    activity_type TEXT NOT NULL DEFAULT 'Other',
+    description TEXT NOT NULL,
+    notes TEXT,
+    status TEXT NOT NULL DEFAULT 'Stopped',
+    entry_type TEXT NOT NULL DEFAULT 'Manual',
+    billable_status TEXT NOT NULL DEFAULT 'Billable',
+    hourly_rate REAL,
+    amount REAL,
+    discount_percent REAL,
+    discount_amount REAL,
+    final_amount REAL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    submitted_at TIMESTAMP,
+    approved_at TIMESTAMP,
+    approved_by TEXT,
+    billed_at TIMESTAMP,
+    invoice_id TEXT,
+    version INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (matter_id) REFERENCES matters(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+INSERT INTO time_entries_new
+    (id, matter_id, task_id, attorney_id, start_time, duration_minutes, billable_minutes,
+     description, status, billable_status, hourly_rate, amount, final_amount,
+     created_at, updated_at, invoice_id, version)
+SELECT id, matter_id, task_id, attorney_id, entry_date,
+       CAST(ROUND(hours * 60) AS INTEGER), CAST(ROUND(hours * 60) AS INTEGER),
+       description,
+       CASE WHEN billed THEN 'Billed' ELSE 'Stopped' END,
+       CASE WHEN billable THEN 'Billable' ELSE 'NonBillable' END,
+       rate, rate * hours, rate * hours,
+       created_at, updated_at, invoice_id, version
+FROM time_entries;
+
+DROP TABLE time_entries;
+ALTER TABLE time_entries_new RENAME TO time_entries;
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_matter ON time_entries(matter_id);
+CREATE INDEX IF NOT EXISTS idx_time_entries_attorney ON time_entries(attorney_id, start_time);
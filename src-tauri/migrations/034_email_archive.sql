@@ -0,0 +1,31 @@
+-- Synced emails and documents attached directly to a matter, such as PDF
+-- archives of linked client emails
+
+CREATE TABLE IF NOT EXISTS emails (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    provider_message_id TEXT NOT NULL,
+    thread_id TEXT,
+    matter_id TEXT,
+    subject TEXT NOT NULL,
+    email_date TIMESTAMP NOT NULL,
+    email_json TEXT NOT NULL,
+    synced_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_emails_account ON emails(account_id);
+CREATE INDEX IF NOT EXISTS idx_emails_matter ON emails(matter_id);
+
+CREATE TABLE IF NOT EXISTS matter_attachments (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL,
+    email_id TEXT,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    attachment_type TEXT,
+    size INTEGER,
+    hash TEXT,
+    upload_date TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_matter_attachments_matter ON matter_attachments(matter_id);
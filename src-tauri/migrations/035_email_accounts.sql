@@ -0,0 +1,24 @@
+-- Connected Gmail/Outlook accounts. sync_token is the provider's cursor for
+-- incremental sync: a Gmail historyId or an Outlook delta link.
+
+CREATE TABLE IF NOT EXISTS email_accounts (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    email_address TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    access_token TEXT NOT NULL,
+    refresh_token TEXT,
+    token_expires_at TIMESTAMP NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    sync_enabled BOOLEAN NOT NULL DEFAULT 1,
+    last_sync_at TIMESTAMP,
+    sync_from_date TIMESTAMP,
+    sync_token TEXT,
+    auto_file_emails BOOLEAN NOT NULL DEFAULT 0,
+    auto_link_to_matters BOOLEAN NOT NULL DEFAULT 0,
+    signature TEXT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_emails_provider_message ON emails(account_id, provider_message_id);
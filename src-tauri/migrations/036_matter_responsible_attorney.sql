@@ -0,0 +1,5 @@
+-- Responsible attorney for each matter, filterable in matter lists
+
+ALTER TABLE matters ADD COLUMN responsible_attorney_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_matters_responsible_attorney ON matters(responsible_attorney_id);
@@ -0,0 +1,18 @@
+-- Conflict waivers. A waiver is keyed by the conflict it clears (type,
+-- conflicting matter and party) rather than the conflict's id, which is
+-- regenerated by every check, so later checks find and clear the same conflict
+
+CREATE TABLE IF NOT EXISTS conflict_waivers (
+    id TEXT PRIMARY KEY,
+    conflict_key TEXT NOT NULL,
+    conflict_id TEXT NOT NULL,
+    check_id TEXT NOT NULL,
+    conflict_type TEXT NOT NULL,
+    conflicting_matter_id TEXT NOT NULL,
+    conflicting_party TEXT NOT NULL,
+    approved_by TEXT NOT NULL,
+    basis TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_conflict_waivers_key ON conflict_waivers(conflict_key);
@@ -0,0 +1,13 @@
+-- Record retention for closed matters. Closing a matter schedules its file
+-- to be kept until retain_until, after which it may be reviewed for destruction
+
+CREATE TABLE IF NOT EXISTS record_retention (
+    id TEXT PRIMARY KEY,
+    matter_id TEXT NOT NULL UNIQUE,
+    closed_at TIMESTAMP NOT NULL,
+    retain_until TIMESTAMP NOT NULL,
+    status TEXT NOT NULL DEFAULT 'scheduled', -- scheduled, reviewed, destroyed
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_record_retention_until ON record_retention(retain_until);
@@ -0,0 +1,8 @@
+-- Settlement calculation revisions
+-- Recalculating stores a new row linked to the one it supersedes, leaving
+-- the earlier revision untouched. The unique index keeps each chain linear.
+
+ALTER TABLE settlement_calculations ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE settlement_calculations ADD COLUMN previous_calculation_id TEXT REFERENCES settlement_calculations(id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_settlement_previous ON settlement_calculations(previous_calculation_id);
@@ -0,0 +1,19 @@
+-- Watchlist change tracking
+-- Each watched docket keeps a hash of its last fetched contents, so the
+-- periodic check can tell which dockets changed since it last looked.
+-- Items added from the app go on the default watchlist.
+
+ALTER TABLE watchlist_items ADD COLUMN caption TEXT;
+ALTER TABLE watchlist_items ADD COLUMN court TEXT;
+ALTER TABLE watchlist_items ADD COLUMN county TEXT;
+ALTER TABLE watchlist_items ADD COLUMN notify_on_change INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE watchlist_items ADD COLUMN check_interval INTEGER NOT NULL DEFAULT 60;
+ALTER TABLE watchlist_items ADD COLUMN last_checked DATETIME;
+ALTER TABLE watchlist_items ADD COLUMN last_changed DATETIME;
+ALTER TABLE watchlist_items ADD COLUMN docket_hash TEXT;
+
+INSERT OR IGNORE INTO watchlists (id, name, description)
+VALUES ('default', 'Watchlist', 'Dockets watched from the app');
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_watchlist_items_docket
+    ON watchlist_items(watchlist_id, docket_number);
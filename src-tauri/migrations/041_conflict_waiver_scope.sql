@@ -0,0 +1,9 @@
+-- Scope conflict waivers to the matter, or for intake checks made before a
+-- matter exists, to the prospective client's checked parties. A waiver only
+-- clears the same conflict within that scope. Unscoped waivers recorded
+-- before this migration no longer clear anything and must be re-recorded.
+
+ALTER TABLE conflict_waivers ADD COLUMN matter_id TEXT;
+ALTER TABLE conflict_waivers ADD COLUMN checked_parties TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX IF NOT EXISTS idx_conflict_waivers_scope ON conflict_waivers(conflict_key, matter_id, checked_parties);
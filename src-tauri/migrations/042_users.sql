@@ -0,0 +1,11 @@
+-- Firm staff accounts. Sign-in reads the role from here and checks the
+-- Argon2 password hash, so the caller can no longer choose its own role
+
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    role TEXT NOT NULL,
+    password_hash TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL
+);
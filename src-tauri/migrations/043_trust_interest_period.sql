@@ -0,0 +1,6 @@
+-- One interest posting per trust account and statement period. Interest is
+-- dated at the end of the period it was earned in, which identifies it.
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trust_interest_period
+    ON trust_transactions(trust_account_id, transaction_date)
+    WHERE transaction_type = 'Interest';
@@ -0,0 +1,25 @@
+-- Outgoing drafts, kept until they are sent. The draft itself is stored as
+-- JSON; the columns are what drafts are looked up by.
+
+CREATE TABLE IF NOT EXISTS email_drafts (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    matter_id TEXT,
+    draft_json TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_drafts_account ON email_drafts(account_id);
+
+-- Marketing campaigns that template sends are grouped under
+
+CREATE TABLE IF NOT EXISTS marketing_campaigns (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    campaign_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    budget REAL NOT NULL DEFAULT 0,
+    roi REAL NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL
+);
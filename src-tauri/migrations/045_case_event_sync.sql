@@ -0,0 +1,5 @@
+-- Calendar sync state for case events, so re-syncing an event updates the
+-- provider's copy instead of creating another one
+
+ALTER TABLE case_events ADD COLUMN calendar_provider TEXT;
+ALTER TABLE case_events ADD COLUMN external_id TEXT;
@@ -0,0 +1,21 @@
+-- The firm's clause library. Document assembly suggests clauses for a
+-- template category; contract review offers the firm's clause for a clause
+-- type (category) a contract is missing before any built-in language.
+-- contract_type and jurisdiction narrow where a clause applies.
+
+CREATE TABLE IF NOT EXISTS clause_library (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    template_category TEXT,
+    contract_type TEXT,
+    text TEXT NOT NULL,
+    jurisdiction TEXT,
+    practice_area TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',
+    ai_relevance_score REAL NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_clause_library_category ON clause_library(category);
+CREATE INDEX IF NOT EXISTS idx_clause_library_template_category ON clause_library(template_category);
@@ -0,0 +1,4 @@
+-- The state whose courts hear a matter, e.g. 'NY'. Fee rules and drafted
+-- contract language follow it; NULL means Pennsylvania.
+
+ALTER TABLE matters ADD COLUMN forum_state TEXT;
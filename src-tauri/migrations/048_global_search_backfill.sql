@@ -0,0 +1,10 @@
+-- Index the notes and cached dockets that existed before 016's triggers.
+-- Rows the triggers already indexed are replaced, so none appear twice.
+
+DELETE FROM global_search_index WHERE scope IN ('matter_note', 'docket');
+
+INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+SELECT 'matter_note', id, matter_id, COALESCE(title, ''), content FROM case_notes;
+
+INSERT INTO global_search_index(scope, entity_id, matter_id, title, body)
+SELECT 'docket', id, NULL, docket_number, COALESCE(json_extract(data, '$.caption'), '') FROM docket_cache;
@@ -0,0 +1,104 @@
+// API metrics - request counts, latencies and provider errors for the REST
+// server, rendered in the Prometheus text exposition format
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Service error codes that mean a court portal or other upstream failed
+const PROVIDER_ERROR_CODES: [&str; 3] = ["external", "rate_limited", "unavailable"];
+
+#[derive(Debug, Default)]
+struct RouteStats {
+    /// Status code -> requests
+    responses: BTreeMap<u16, u64>,
+    count: u64,
+    latency_seconds: f64,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    /// (method, route path) -> stats
+    routes: BTreeMap<(String, String), RouteStats>,
+    /// Service error code -> responses
+    provider_errors: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct ApiMetrics {
+    state: Mutex<MetricsState>,
+}
+
+impl ApiMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one handled request; `error_code` is the service error code
+    /// the response carried, if any
+    pub fn record(&self, method: &str, path: &str, status: u16, latency: Duration, error_code: Option<&str>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = state.routes.entry((method.to_string(), path.to_string())).or_default();
+        *stats.responses.entry(status).or_default() += 1;
+        stats.count += 1;
+        stats.latency_seconds += latency.as_secs_f64();
+
+        if let Some(code) = error_code.filter(|code| PROVIDER_ERROR_CODES.contains(code)) {
+            *state.provider_errors.entry(code.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP api_requests_total Requests handled, by route and status\n");
+        out.push_str("# TYPE api_requests_total counter\n");
+        for ((method, path), stats) in &state.routes {
+            for (status, count) in &stats.responses {
+                let _ = writeln!(
+                    out,
+                    "api_requests_total{{method=\"{}\",path=\"{}\",status=\"{}\"}} {}",
+                    method, path, status, count
+                );
+            }
+        }
+
+        out.push_str("# HELP api_request_duration_seconds Time spent handling requests, by route\n");
+        out.push_str("# TYPE api_request_duration_seconds summary\n");
+        for ((method, path), stats) in &state.routes {
+            let labels = format!("method=\"{}\",path=\"{}\"", method, path);
+            let _ = writeln!(out, "api_request_duration_seconds_sum{{{}}} {}", labels, stats.latency_seconds);
+            let _ = writeln!(out, "api_request_duration_seconds_count{{{}}} {}", labels, stats.count);
+        }
+
+        out.push_str("# HELP api_provider_errors_total Requests failed by an upstream provider, by error code\n");
+        out.push_str("# TYPE api_provider_errors_total counter\n");
+        for (code, count) in &state.provider_errors {
+            let _ = writeln!(out, "api_provider_errors_total{{code=\"{}\"}} {}", code, count);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counts_requests_and_provider_errors() {
+        let metrics = ApiMetrics::new();
+        metrics.record("GET", "/api/v1/matters", 200, Duration::from_millis(20), None);
+        metrics.record("GET", "/api/v1/matters", 200, Duration::from_millis(30), None);
+        metrics.record("POST", "/api/v1/dockets/search", 429, Duration::from_millis(5), Some("rate_limited"));
+        metrics.record("GET", "/api/v1/invoices/:id", 404, Duration::from_millis(5), Some("not_found"));
+
+        let text = metrics.render();
+        assert!(text.contains("api_requests_total{method=\"GET\",path=\"/api/v1/matters\",status=\"200\"} 2"));
+        assert!(text.contains("api_request_duration_seconds_count{method=\"GET\",path=\"/api/v1/matters\"} 2"));
+        assert!(text.contains("api_provider_errors_total{code=\"rate_limited\"} 1"));
+        assert!(!text.contains("code=\"not_found\""));
+    }
+}
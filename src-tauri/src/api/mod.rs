@@ -1,6 +1,7 @@
 // API module - REST API server for external integrations
 // Provides comprehensive REST endpoints for all enterprise features
 
+pub mod metrics;
 pub mod rest_api;
 
 // Re-export main API server creation function
@@ -4,32 +4,74 @@
 use axum::{
     routing::{get, post, put, delete},
     Json, Router, Extension,
-    http::{StatusCode, HeaderMap},
-    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    extract::{MatchedPath, Path, Query, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+};
+use crate::api::metrics::ApiMetrics;
+use crate::config::AppConfig;
+use crate::domain::{SearchParams, SearchResult};
+use crate::providers::registry::SharedSearchProvider;
+use crate::services::billing::{BillingService, Invoice};
+use crate::services::service_error::ServiceError;
+use crate::services::system_health::{check_database, HealthStatus};
+use crate::utils::correlation::{
+    accept_correlation_id, current_correlation_id, new_correlation_id, with_correlation_id, CORRELATION_HEADER,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tower_http::cors::CorsLayer;
 use tokio::sync::RwLock;
+use tracing::Instrument;
+use utoipa::openapi::security::{ApiKey as ApiKeyScheme, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use validator::Validate;
 
 // ============= API MODELS =============
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[aliases(
+    JsonResponse = ApiResponse<serde_json::Value>,
+    JsonListResponse = ApiResponse<Vec<serde_json::Value>>,
+    ErrorResponse = ApiResponse<serde_json::Value>,
+    DocketSearchResponse = ApiResponse<Vec<SearchResult>>,
+    InvoiceResponse = ApiResponse<Invoice>,
+    WebhookResponse = ApiResponse<Webhook>,
+    WebhookListResponse = ApiResponse<Vec<Webhook>>
+)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
-    pub error: Option<String>,
+    pub error: Option<ApiError>,
     pub meta: ResponseMeta,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Error envelope: `retryable` tells clients whether the same request may
+/// succeed later, in which case the response also carries `Retry-After`
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+    pub retryable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ResponseMeta {
     pub timestamp: String,
     pub version: String,
+    /// The request's correlation id, also sent as `X-Correlation-ID`
     pub request_id: String,
 }
 
+fn request_id() -> String {
+    current_correlation_id().unwrap_or_else(new_correlation_id)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
@@ -44,8 +86,235 @@ pub struct Pagination {
     pub total_pages: u32,
 }
 
+// ============= ERRORS =============
+
+/// Seconds clients are asked to wait before retrying a retryable error
+pub const RETRY_AFTER_SECONDS: u64 = 30;
+
+/// HTTP status for a service error
+pub fn status_for(err: &ServiceError) -> StatusCode {
+    match err {
+        ServiceError::NotFound { .. } => StatusCode::NOT_FOUND,
+        ServiceError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        ServiceError::Conflict(_) => StatusCode::CONFLICT,
+        ServiceError::Unauthorized(_) => StatusCode::FORBIDDEN,
+        ServiceError::External(_) => StatusCode::BAD_GATEWAY,
+        ServiceError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServiceError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServiceError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let status = status_for(&self);
+        // Internal details stay in the log
+        let message = match &self {
+            ServiceError::Internal(err) => {
+                tracing::error!("API request failed: {:#}", err);
+                "Internal error".to_string()
+            }
+            other => other.to_string(),
+        };
+        let body = ApiResponse::<()> {
+            success: false,
+            data: None,
+            error: Some(ApiError {
+                code: self.code().to_string(),
+                message,
+                retryable: self.is_retryable(),
+            }),
+            meta: ResponseMeta {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                version: "v1".to_string(),
+                request_id: request_id(),
+            },
+        };
+        let mut response = (status, Json(body)).into_response();
+        response.extensions_mut().insert(ErrorCode(self.code()));
+        if self.is_retryable() {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECONDS));
+        }
+        response
+    }
+}
+
+/// Code of the service error a response was built from, for metrics
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCode(pub &'static str);
+
+// ============= TIMEOUTS =============
+
+/// How long each endpoint may run before the client gets a retryable 503,
+/// and which credentials the API accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub default_timeout_seconds: u64,
+    /// Route path (e.g. "/api/v1/research/search") -> timeout override
+    #[serde(default)]
+    pub endpoint_timeouts: HashMap<String, u64>,
+    /// Hex SHA-256 of each accepted API key or bearer token. With none
+    /// configured every `/api/v1` data route is refused.
+    #[serde(default)]
+    pub credential_hashes: Vec<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout_seconds: 30,
+            endpoint_timeouts: HashMap::new(),
+            credential_hashes: Vec::new(),
+        }
+    }
+}
+
+impl ApiConfig {
+    pub fn timeout_for(&self, path: &str) -> Duration {
+        let seconds = self.endpoint_timeouts.get(path).copied().unwrap_or(self.default_timeout_seconds);
+        Duration::from_secs(seconds)
+    }
+
+    pub fn accepts(&self, credential: &str) -> bool {
+        let hash = credential_hash(credential);
+        self.credential_hashes.iter().any(|accepted| accepted.eq_ignore_ascii_case(&hash))
+    }
+}
+
+/// How a credential is stored in `ApiConfig::credential_hashes`
+pub fn credential_hash(credential: &str) -> String {
+    format!("{:x}", Sha256::digest(credential.as_bytes()))
+}
+
+/// Cut a request off at its endpoint's timeout, so a slow court portal
+/// surfaces as a retryable error rather than a hung connection
+async fn enforce_timeout(State(state): State<Arc<ApiState>>, request: Request, next: Next) -> Response {
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let timeout = state.config.timeout_for(&path);
+
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("API request to {} timed out after {:?}", path, timeout);
+            ServiceError::Unavailable(format!("{} timed out after {}s", path, timeout.as_secs())).into_response()
+        }
+    }
+}
+
+// ============= OPERATIONS =============
+
+/// Give every request a correlation id, reusing the client's
+/// `X-Correlation-ID` when it sends a usable one. The id is on the request's
+/// span, on outbound provider calls it makes and on the response.
+async fn correlate(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(CORRELATION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(accept_correlation_id)
+        .unwrap_or_else(new_correlation_id);
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        correlation_id = %id,
+    );
+
+    let mut response = with_correlation_id(id.clone(), next.run(request).instrument(span)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(CORRELATION_HEADER, value);
+    }
+    response
+}
+
+/// Count every routed request, including ones cut off by a timeout
+async fn record_metrics(State(state): State<Arc<ApiState>>, request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    let error_code = response.extensions().get::<ErrorCode>().map(|code| code.0);
+    state
+        .metrics
+        .record(&method, &path, response.status().as_u16(), started.elapsed(), error_code);
+    response
+}
+
+/// Liveness: the process is up and serving
+async fn liveness() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness: the database answers and the configuration has loaded.
+/// Court portals are left out; the server can still serve local data
+/// while one is down.
+async fn readiness(State(state): State<Arc<ApiState>>) -> (StatusCode, Json<serde_json::Value>) {
+    let database = check_database(&state.db).await;
+    let config_loaded = state.app_config.is_some();
+
+    let ready = database.status == HealthStatus::Healthy && config_loaded;
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "database": database,
+            "config_loaded": config_loaded,
+        })),
+    )
+}
+
+async fn metrics(State(state): State<Arc<ApiState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
 // ============= AUTHENTICATION =============
 
+/// Require an accepted `X-API-Key` or bearer token, the two schemes the
+/// OpenAPI spec declares
+async fn authenticate(State(state): State<Arc<ApiState>>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let credential = headers
+        .get("X-API-Key")
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        })
+        .map(str::trim)
+        .filter(|credential| !credential.is_empty());
+
+    match credential {
+        Some(credential) if state.config.accepts(credential) => next.run(request).await,
+        Some(_) => ServiceError::Unauthorized("Invalid API credential".to_string()).into_response(),
+        None => {
+            let mut response =
+                ServiceError::Unauthorized("An API key or bearer token is required".to_string()).into_response();
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiKey {
     pub key: String,
@@ -64,7 +333,7 @@ pub struct OAuth2Token {
 
 // ============= WEBHOOK SYSTEM =============
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Webhook {
     pub id: String,
     pub url: String,
@@ -73,7 +342,7 @@ pub struct Webhook {
     pub active: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum WebhookEvent {
     MatterCreated,
     MatterUpdated,
@@ -98,18 +367,64 @@ pub struct WebhookPayload {
 pub struct ApiState {
     pub db: SqlitePool,
     pub webhooks: Arc<RwLock<Vec<Webhook>>>,
+    pub config: ApiConfig,
+    /// Backs docket search; None when no search provider is configured
+    pub search: Option<SharedSearchProvider>,
+    /// None until the app configuration has loaded; the server is not ready
+    /// without it
+    pub app_config: Option<Arc<AppConfig>>,
+    pub metrics: Arc<ApiMetrics>,
 }
 
-pub async fn create_api_server(db: SqlitePool) -> Router {
-    let state = Arc::new(ApiState {
-        db,
-        webhooks: Arc::new(RwLock::new(Vec::new())),
-    });
+impl ApiState {
+    pub fn new(db: SqlitePool) -> Self {
+        Self {
+            db,
+            webhooks: Arc::new(RwLock::new(Vec::new())),
+            config: ApiConfig::default(),
+            search: None,
+            app_config: None,
+            metrics: Arc::new(ApiMetrics::new()),
+        }
+    }
+
+    pub fn with_config(mut self, config: ApiConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_search(mut self, search: SharedSearchProvider) -> Self {
+        self.search = Some(search);
+        self
+    }
+
+    pub fn with_app_config(mut self, app_config: Arc<AppConfig>) -> Self {
+        self.app_config = Some(app_config);
+        self
+    }
+}
 
-    Router::new()
-        // Health check
+/// The server is only ready once it has the loaded app configuration, so
+/// callers hand it over here
+pub async fn create_api_server(db: SqlitePool, app_config: Arc<AppConfig>) -> Router {
+    create_api_server_with_state(ApiState::new(db).with_app_config(app_config))
+}
+
+pub fn create_api_server_with_state(state: ApiState) -> Router {
+    let state = Arc::new(state);
+
+    // Probes, metrics and the spec stay open; everything else needs a credential
+    let public = Router::new()
         .route("/health", get(health_check))
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+        .route("/metrics", get(metrics))
         .route("/api/v1/status", get(api_status))
+        .route("/api/openapi.json", get(openapi_spec));
+
+    let protected = Router::new()
+        // Dockets
+        .route("/api/v1/dockets/search", post(search_dockets))
 
         // Matters
         .route("/api/v1/matters", get(list_matters).post(create_matter))
@@ -155,13 +470,84 @@ pub async fn create_api_server(db: SqlitePool) -> Router {
         .route("/api/v1/analytics/revenue", get(get_revenue_analytics))
         .route("/api/v1/analytics/performance", get(get_performance_metrics))
         .route("/api/v1/analytics/predictions", get(get_predictive_analytics))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate));
 
+    public
+        .merge(protected)
+        .route_layer(middleware::from_fn_with_state(state.clone(), enforce_timeout))
+        .route_layer(middleware::from_fn_with_state(state.clone(), record_metrics))
+        .layer(middleware::from_fn(correlate))
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
 
+// ============= OPENAPI =============
+
+/// The REST contract, generated from the handlers' `utoipa::path`
+/// annotations and the types they exchange
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "PA eDocket REST API", version = "v1"),
+    paths(
+        health_check, api_status,
+        search_dockets,
+        list_matters, create_matter, get_matter, update_matter, delete_matter,
+        list_clients, create_client, get_client, update_client,
+        list_invoices, create_invoice, get_invoice, create_payment,
+        calculate_settlement, get_settlement, generate_demand_letter,
+        list_documents, upload_document, download_document, delete_document,
+        search_cases, shepardize_citation,
+        automate_case, ai_generate_document, predict_case_outcome,
+        list_webhooks, create_webhook, get_webhook, delete_webhook,
+        ingest_courtlistener, ingest_govinfo, get_ingestion_status,
+        get_revenue_analytics, get_performance_metrics, get_predictive_analytics,
+    ),
+    components(schemas(
+        ApiError, ResponseMeta, Webhook, WebhookEvent,
+        JsonResponse, JsonListResponse, ErrorResponse, DocketSearchResponse,
+        InvoiceResponse, WebhookResponse, WebhookListResponse,
+        SearchParams, SearchResult, crate::domain::CourtLevel, crate::domain::CaseStatus,
+        Invoice, crate::services::billing::InvoiceTimeEntry, crate::services::billing::InvoiceExpense,
+        crate::services::billing::InvoiceAdjustment, crate::services::billing::InvoiceStatus,
+        crate::services::currency::Currency,
+    )),
+    modifiers(&SecurityAddon),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub struct ApiDoc;
+
+/// API keys go in `X-API-Key`; OAuth2 access tokens as bearer tokens
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKeyScheme::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+async fn openapi_spec() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
 // ============= ROUTE HANDLERS =============
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service is up")
+    ),
+    security(())
+)]
 async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "status": "healthy",
@@ -170,6 +556,15 @@ async fn health_check() -> Json<serde_json::Value> {
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    tag = "health",
+    responses(
+        (status = 200, description = "API version and features")
+    ),
+    security(())
+)]
 async fn api_status() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "api_version": "v1",
@@ -189,7 +584,56 @@ async fn api_status() -> Json<serde_json::Value> {
     }))
 }
 
+// Docket endpoints
+#[utoipa::path(
+    post,
+    path = "/api/v1/dockets/search",
+    tag = "dockets",
+    request_body = SearchParams,
+    responses(
+        (status = 200, body = DocketSearchResponse),
+        (status = 422, description = "Invalid search parameters", body = ErrorResponse),
+        (status = 429, description = "Court portal is throttling; retry after Retry-After", body = ErrorResponse),
+        (status = 503, description = "Court portal unavailable or search not configured", body = ErrorResponse)
+    )
+)]
+async fn search_dockets(
+    State(state): State<Arc<ApiState>>,
+    Json(params): Json<SearchParams>,
+) -> Result<Json<ApiResponse<Vec<SearchResult>>>, ServiceError> {
+    params
+        .validate()
+        .map_err(|e| ServiceError::validation(format!("Invalid search parameters: {}", e)))?;
+    let provider = state
+        .search
+        .as_ref()
+        .ok_or_else(|| ServiceError::Unavailable("Docket search is not configured".to_string()))?;
+    let results = provider
+        .search(&params)
+        .await
+        .map_err(|e| ServiceError::from(anyhow::Error::from(e)))?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(results),
+        error: None,
+        meta: ResponseMeta {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            version: "v1".to_string(),
+            request_id: request_id(),
+        },
+    }))
+}
+
 // Matter endpoints
+#[utoipa::path(
+    get,
+    path = "/api/v1/matters",
+    tag = "matters",
+    responses(
+        (status = 200, body = JsonListResponse)
+    )
+)]
 async fn list_matters(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
     Json(ApiResponse {
         success: true,
@@ -198,11 +642,20 @@ async fn list_matters(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Ve
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/matters",
+    tag = "matters",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn create_matter(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -215,11 +668,20 @@ async fn create_matter(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/matters/{id}",
+    tag = "matters",
+    params(("id" = String, Path, description = "Matter id")),
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_matter(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -231,11 +693,21 @@ async fn get_matter(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/matters/{id}",
+    tag = "matters",
+    params(("id" = String, Path, description = "Matter id")),
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn update_matter(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -248,11 +720,20 @@ async fn update_matter(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/matters/{id}",
+    tag = "matters",
+    params(("id" = String, Path, description = "Matter id")),
+    responses(
+        (status = 204, description = "Deleted")
+    )
+)]
 async fn delete_matter(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -261,6 +742,14 @@ async fn delete_matter(
 }
 
 // Client endpoints
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients",
+    tag = "clients",
+    responses(
+        (status = 200, body = JsonListResponse)
+    )
+)]
 async fn list_clients(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
     Json(ApiResponse {
         success: true,
@@ -269,11 +758,20 @@ async fn list_clients(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Ve
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients",
+    tag = "clients",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn create_client(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -285,11 +783,20 @@ async fn create_client(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}",
+    tag = "clients",
+    params(("id" = String, Path, description = "Client id")),
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_client(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -301,11 +808,21 @@ async fn get_client(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/clients/{id}",
+    tag = "clients",
+    params(("id" = String, Path, description = "Client id")),
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn update_client(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -318,12 +835,20 @@ async fn update_client(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // Billing endpoints
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices",
+    tag = "billing",
+    responses(
+        (status = 200, body = JsonListResponse)
+    )
+)]
 async fn list_invoices(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
     Json(ApiResponse {
         success: true,
@@ -332,11 +857,20 @@ async fn list_invoices(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<V
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices",
+    tag = "billing",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn create_invoice(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -348,27 +882,47 @@ async fn create_invoice(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}",
+    tag = "billing",
+    params(("id" = String, Path, description = "Invoice id")),
+    responses(
+        (status = 200, body = InvoiceResponse),
+        (status = 404, description = "No such invoice", body = ErrorResponse)
+    )
+)]
 async fn get_invoice(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
-) -> Json<ApiResponse<serde_json::Value>> {
-    Json(ApiResponse {
+) -> Result<Json<ApiResponse<Invoice>>, ServiceError> {
+    let invoice = BillingService::new(state.db.clone()).get_invoice(&id).await?;
+    Ok(Json(ApiResponse {
         success: true,
-        data: Some(serde_json::json!({"id": id, "amount": 5000.00})),
+        data: Some(invoice),
         error: None,
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
-    })
+    }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments",
+    tag = "billing",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn create_payment(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -380,12 +934,21 @@ async fn create_payment(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // Settlement Calculator
+#[utoipa::path(
+    post,
+    path = "/api/v1/settlements/calculate",
+    tag = "settlements",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn calculate_settlement(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -403,11 +966,20 @@ async fn calculate_settlement(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/settlements/{id}",
+    tag = "settlements",
+    params(("id" = String, Path, description = "Settlement calculation id")),
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_settlement(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -419,11 +991,20 @@ async fn get_settlement(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/demands/generate",
+    tag = "settlements",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn generate_demand_letter(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -439,12 +1020,20 @@ async fn generate_demand_letter(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // Documents
+#[utoipa::path(
+    get,
+    path = "/api/v1/documents",
+    tag = "documents",
+    responses(
+        (status = 200, body = JsonListResponse)
+    )
+)]
 async fn list_documents(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
     Json(ApiResponse {
         success: true,
@@ -453,11 +1042,20 @@ async fn list_documents(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/documents",
+    tag = "documents",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn upload_document(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -469,11 +1067,20 @@ async fn upload_document(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/documents/{id}",
+    tag = "documents",
+    params(("id" = String, Path, description = "Document id")),
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn download_document(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -485,11 +1092,20 @@ async fn download_document(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/documents/{id}",
+    tag = "documents",
+    params(("id" = String, Path, description = "Document id")),
+    responses(
+        (status = 204, description = "Deleted")
+    )
+)]
 async fn delete_document(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -498,6 +1114,15 @@ async fn delete_document(
 }
 
 // Research
+#[utoipa::path(
+    post,
+    path = "/api/v1/research/search",
+    tag = "research",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn search_cases(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -512,11 +1137,20 @@ async fn search_cases(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/research/shepardize",
+    tag = "research",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn shepardize_citation(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -532,12 +1166,21 @@ async fn shepardize_citation(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // AI Automation
+#[utoipa::path(
+    post,
+    path = "/api/v1/ai/automate-case",
+    tag = "ai",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn automate_case(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -553,11 +1196,20 @@ async fn automate_case(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/ai/generate-document",
+    tag = "ai",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn ai_generate_document(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -573,11 +1225,20 @@ async fn ai_generate_document(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/ai/predict-outcome",
+    tag = "ai",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn predict_case_outcome(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -594,12 +1255,20 @@ async fn predict_case_outcome(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // Webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, body = WebhookListResponse)
+    )
+)]
 async fn list_webhooks(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<Vec<Webhook>>> {
     let webhooks = state.webhooks.read().await;
     Json(ApiResponse {
@@ -609,11 +1278,20 @@ async fn list_webhooks(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<V
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    request_body = Webhook,
+    responses(
+        (status = 200, body = WebhookResponse)
+    )
+)]
 async fn create_webhook(
     State(state): State<Arc<ApiState>>,
     Json(webhook): Json<Webhook>,
@@ -628,11 +1306,20 @@ async fn create_webhook(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id")),
+    responses(
+        (status = 200, body = WebhookResponse)
+    )
+)]
 async fn get_webhook(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -647,11 +1334,20 @@ async fn get_webhook(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id")),
+    responses(
+        (status = 204, description = "Deleted")
+    )
+)]
 async fn delete_webhook(
     State(state): State<Arc<ApiState>>,
     Path(id): Path<String>,
@@ -662,6 +1358,15 @@ async fn delete_webhook(
 }
 
 // Bulk Data Ingestion
+#[utoipa::path(
+    post,
+    path = "/api/v1/bulk/ingest/courtlistener",
+    tag = "bulk",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn ingest_courtlistener(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -677,11 +1382,20 @@ async fn ingest_courtlistener(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/bulk/ingest/govinfo",
+    tag = "bulk",
+    request_body = Object,
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn ingest_govinfo(
     State(state): State<Arc<ApiState>>,
     Json(payload): Json<serde_json::Value>,
@@ -696,11 +1410,20 @@ async fn ingest_govinfo(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/bulk/status/{job_id}",
+    tag = "bulk",
+    params(("job_id" = String, Path, description = "Ingestion job id")),
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_ingestion_status(
     State(state): State<Arc<ApiState>>,
     Path(job_id): Path<String>,
@@ -717,12 +1440,20 @@ async fn get_ingestion_status(
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
 // Analytics
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/revenue",
+    tag = "analytics",
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_revenue_analytics(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<serde_json::Value>> {
     Json(ApiResponse {
         success: true,
@@ -735,11 +1466,19 @@ async fn get_revenue_analytics(State(state): State<Arc<ApiState>>) -> Json<ApiRe
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/performance",
+    tag = "analytics",
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_performance_metrics(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<serde_json::Value>> {
     Json(ApiResponse {
         success: true,
@@ -752,11 +1491,19 @@ async fn get_performance_metrics(State(state): State<Arc<ApiState>>) -> Json<Api
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/predictions",
+    tag = "analytics",
+    responses(
+        (status = 200, body = JsonResponse)
+    )
+)]
 async fn get_predictive_analytics(State(state): State<Arc<ApiState>>) -> Json<ApiResponse<serde_json::Value>> {
     Json(ApiResponse {
         success: true,
@@ -769,7 +1516,190 @@ async fn get_predictive_analytics(State(state): State<Arc<ApiState>>) -> Json<Ap
         meta: ResponseMeta {
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: "v1".to_string(),
-            request_id: uuid::Uuid::new_v4().to_string(),
+            request_id: request_id(),
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    #[tokio::test]
+    async fn test_missing_invoice_maps_to_404() {
+        let db = test_database().await;
+        let state = Arc::new(ApiState::new(db));
+
+        let err = get_invoice(State(state), Path("inv-missing".to_string())).await.err().unwrap();
+        assert!(matches!(err, ServiceError::NotFound { entity: "Invoice", .. }));
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_upstream_is_retryable_429() {
+        use crate::providers::ProviderError;
+        use tower::ServiceExt;
+
+        async fn throttled() -> Result<Json<()>, ServiceError> {
+            let err: anyhow::Error = ProviderError::RateLimited.into();
+            Err(err.context("Failed to search dockets").into())
+        }
+
+        let state = Arc::new(ApiState::new(SqlitePool::connect("sqlite::memory:").await.unwrap()));
+        let app = Router::new()
+            .route("/api/v1/dockets/search", get(throttled))
+            .route_layer(middleware::from_fn_with_state(state.clone(), enforce_timeout))
+            .with_state(state);
+
+        let request = Request::builder().uri("/api/v1/dockets/search").body(axum::body::Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], RETRY_AFTER_SECONDS.to_string().as_str());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ApiResponse<()> = serde_json::from_slice(&body).unwrap();
+        let error = body.error.unwrap();
+        assert_eq!(error.code, "rate_limited");
+        assert!(error.retryable);
+    }
+
+    #[tokio::test]
+    async fn test_error_response_carries_correlation_id() {
+        use tower::ServiceExt;
+
+        async fn missing() -> Result<Json<()>, ServiceError> {
+            Err(ServiceError::not_found("Invoice", "inv-missing"))
+        }
+
+        let app: Router = Router::new()
+            .route("/api/v1/invoices/:id", get(missing))
+            .layer(middleware::from_fn(correlate));
+
+        let request = Request::builder()
+            .uri("/api/v1/invoices/inv-missing")
+            .header(CORRELATION_HEADER, "client-req-42")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.headers()[CORRELATION_HEADER], "client-req-42");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ApiResponse<()> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.meta.request_id, "client-req-42");
+        assert_eq!(body.error.unwrap().code, "not_found");
+    }
+
+    #[test]
+    fn test_endpoint_timeout_overrides_default() {
+        let config = ApiConfig {
+            default_timeout_seconds: 30,
+            endpoint_timeouts: [("/api/v1/research/search".to_string(), 90)].into(),
+            credential_hashes: Vec::new(),
+        };
+        assert_eq!(config.timeout_for("/api/v1/research/search"), Duration::from_secs(90));
+        assert_eq!(config.timeout_for("/api/v1/matters"), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_openapi_spec_is_valid_and_covers_docket_search() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        // Parses back into the OpenAPI model
+        serde_json::from_value::<utoipa::openapi::OpenApi>(spec.clone()).unwrap();
+
+        // Every schema reference resolves to a component
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        let json = spec.to_string();
+        for reference in json.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(schemas.contains_key(name), "unresolved schema reference {}", name);
+        }
+
+        let search = &spec["paths"]["/api/v1/dockets/search"]["post"];
+        assert!(search.is_object());
+        assert!(search["requestBody"].is_object());
+        assert!(schemas.contains_key("SearchParams"));
+        assert!(spec["components"]["securitySchemes"]["api_key"].is_object());
+        assert!(spec["paths"]["/api/v1/invoices/{id}"]["get"]["responses"]["404"].is_object());
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_readiness_fails_without_database() {
+        use crate::config::{CourtsConfig, GlobalConfig, ProvidersConfig, SecurityConfig};
+        use tower::ServiceExt;
+
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let config = Arc::new(AppConfig {
+            courts: CourtsConfig::default(),
+            providers: ProvidersConfig::default(),
+            global: GlobalConfig::default(),
+            security: SecurityConfig::default(),
+        });
+        let app = create_api_server(db.clone(), config).await;
+
+        let ready = app.clone().oneshot(get_request("/readyz")).await.unwrap();
+        assert_eq!(ready.status(), StatusCode::OK);
+
+        db.close().await;
+        let response = app.clone().oneshot(get_request("/readyz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["database"]["status"], "Down");
+
+        // Still alive
+        let alive = app.oneshot(get_request("/healthz")).await.unwrap();
+        assert_eq!(alive.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_data_routes_require_an_accepted_credential() {
+        use tower::ServiceExt;
+
+        let db = test_database().await;
+        let config = ApiConfig { credential_hashes: vec![credential_hash("key-123")], ..ApiConfig::default() };
+        let app = create_api_server_with_state(ApiState::new(db).with_config(config));
+        let predictions = "/api/v1/analytics/predictions";
+
+        let response = app.clone().oneshot(get_request(predictions)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let with_header = |name: &str, value: &str| {
+            Request::builder().uri(predictions).header(name, value).body(axum::body::Body::empty()).unwrap()
+        };
+        let response = app.clone().oneshot(with_header("X-API-Key", "wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app.clone().oneshot(with_header("X-API-Key", "key-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(with_header("Authorization", "Bearer key-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Probes and the spec stay open
+        let response = app.oneshot(get_request("/api/openapi.json")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_requests() {
+        use tower::ServiceExt;
+
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let app = create_api_server_with_state(ApiState::new(db));
+
+        for _ in 0..2 {
+            app.clone().oneshot(get_request("/healthz")).await.unwrap();
+        }
+        let response = app.oneshot(get_request("/metrics")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE api_requests_total counter"));
+        assert!(text.contains("api_requests_total{method=\"GET\",path=\"/healthz\",status=\"200\"} 2"));
+    }
+}
@@ -1,15 +1,21 @@
 // Tauri Commands for Document Editor and Citation Features
 // Connects the frontend DocumentEditor to backend services
 
+use crate::config::AppConfig;
 use crate::domain::case_management::*;
-use crate::services::ai_citation_service::{AICitationService, CitationSuggestion, ExtractedCitation};
-use crate::services::case_management::CaseManagementService;
+use crate::services::ai_citation_service::{AICitationService, CaseResult, ResolvedCitation};
+use crate::services::ai_suggestions::{AiSuggestionService, DocumentContext, Suggestion};
+use crate::services::case_management::{CaseManagementService, CreatedMatter};
 use crate::services::pleading_formatter::PleadingFormatter;
+use crate::utils::correlation::new_correlation_id;
+use crate::utils::file_utils::sanitize_filename;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::State;
 use tokio::sync::Mutex;
+use tracing::instrument;
 
 // ============================================================================
 // Command State
@@ -20,6 +26,22 @@ pub struct AppState {
     pub citation_service: Arc<Mutex<AICitationService>>,
     pub case_service: Arc<Mutex<CaseManagementService>>,
     pub pleading_formatter: Arc<Mutex<PleadingFormatter>>,
+    /// Directory courts.yaml, providers.yaml and the other config files were loaded from
+    pub config_dir: PathBuf,
+}
+
+impl AppState {
+    pub fn new(db_pool: Pool<Sqlite>, config: &AppConfig, config_dir: PathBuf) -> Self {
+        let courtlistener_token = std::env::var("COURTLISTENER_API_TOKEN").ok();
+        let case_service = CaseManagementService::new(db_pool.clone()).with_numbering(config.global.numbering.matter.clone());
+        Self {
+            citation_service: Arc::new(Mutex::new(AICitationService::new(courtlistener_token))),
+            case_service: Arc::new(Mutex::new(case_service)),
+            pleading_formatter: Arc::new(Mutex::new(PleadingFormatter::new())),
+            db_pool,
+            config_dir,
+        }
+    }
 }
 
 // ============================================================================
@@ -27,6 +49,7 @@ pub struct AppState {
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_save_document(
     document_id: String,
     content: String,
@@ -46,6 +69,7 @@ pub async fn cmd_save_document(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_export_document(
     document_id: String,
     content: String,
@@ -53,14 +77,15 @@ pub async fn cmd_export_document(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     // Export to PDF or DOCX
+    let file_stem = sanitize_filename(&document_id);
     let output_path = match format.as_str() {
         "pdf" => {
             // TODO: Implement PDF generation
-            format!("/tmp/{}.pdf", document_id)
+            format!("/tmp/{}.pdf", file_stem)
         }
         "docx" => {
             // TODO: Implement DOCX generation
-            format!("/tmp/{}.docx", document_id)
+            format!("/tmp/{}.docx", file_stem)
         }
         _ => return Err("Unsupported format".to_string()),
     };
@@ -76,32 +101,35 @@ pub async fn cmd_export_document(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_search_case_law(
     query: String,
     state: State<'_, AppState>,
-) -> Result<Vec<CitationSuggestion>, String> {
+) -> Result<Vec<CaseResult>, String> {
     let service = state.citation_service.lock().await;
 
     service
-        .suggest_citations("", &query)
+        .search_case_law(&query)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_extract_citations(
     text: String,
     state: State<'_, AppState>,
-) -> Result<Vec<ExtractedCitation>, String> {
+) -> Result<Vec<ResolvedCitation>, String> {
     let service = state.citation_service.lock().await;
 
     service
-        .extract_citations(&text)
+        .resolve_citations(&text)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_format_citations(
     text: String,
     state: State<'_, AppState>,
@@ -115,6 +143,7 @@ pub async fn cmd_format_citations(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_generate_toa(
     content: String,
     state: State<'_, AppState>,
@@ -132,6 +161,7 @@ pub async fn cmd_generate_toa(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_format_as_pleading(
     matter_id: String,
     content: String,
@@ -181,6 +211,8 @@ pub async fn cmd_format_as_pleading(
         table_of_contents: Some(false),
         table_of_authorities: Some(false),
         page_limits: std::collections::HashMap::new(),
+        cover_sheet_required: false,
+        electronic_service: false,
     };
 
     // Parse document type
@@ -207,20 +239,23 @@ pub async fn cmd_format_as_pleading(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_list_matters(
-    folder_id: Option<String>,
-    practice_area_id: Option<String>,
+    filter: Option<MatterFilter>,
+    page: Option<u32>,
+    limit: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<Vec<Matter>, String> {
+) -> Result<Page<MatterSummary>, String> {
     let service = state.case_service.lock().await;
 
     service
-        .list_matters(None, None)
+        .list_matters(filter.unwrap_or_default(), page.unwrap_or(1), limit.unwrap_or(50))
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_matter_summary(
     matter_id: String,
     state: State<'_, AppState>,
@@ -234,6 +269,7 @@ pub async fn cmd_get_matter_summary(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_create_client(
     request: CreateClientRequest,
     state: State<'_, AppState>,
@@ -247,10 +283,11 @@ pub async fn cmd_create_client(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_create_matter(
     request: CreateMatterRequest,
     state: State<'_, AppState>,
-) -> Result<Matter, String> {
+) -> Result<CreatedMatter, String> {
     let service = state.case_service.lock().await;
 
     service
@@ -260,6 +297,7 @@ pub async fn cmd_create_matter(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_generate_document(
     request: GenerateDocumentRequest,
     state: State<'_, AppState>,
@@ -295,6 +333,7 @@ pub struct PracticeArea {
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_case_folders(
     state: State<'_, AppState>,
 ) -> Result<Vec<CaseFolder>, String> {
@@ -333,6 +372,7 @@ pub async fn cmd_get_case_folders(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_practice_areas(
     state: State<'_, AppState>,
 ) -> Result<Vec<PracticeArea>, String> {
@@ -371,21 +411,25 @@ pub async fn cmd_get_practice_areas(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_ai_suggestions(
     matter_id: String,
     context: String,
-    state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    // Placeholder for AI suggestions
-    // In production, this would call an AI service
-    Ok(vec![
-        "Consider adding a citation to support this argument.".to_string(),
-        "This section may benefit from additional factual support.".to_string(),
-        "Review Pa. R.C.P. 1035.2 for summary judgment standards.".to_string(),
-    ])
+    document_type: Option<String>,
+    suggestions: State<'_, AiSuggestionService>,
+) -> Result<Vec<Suggestion>, String> {
+    suggestions
+        .get_ai_suggestions(DocumentContext {
+            matter_id: Some(matter_id),
+            document_type,
+            text: context,
+        })
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_analyze_document(
     content: String,
     document_type: String,
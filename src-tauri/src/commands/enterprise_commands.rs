@@ -3,7 +3,12 @@
 
 use tauri::State;
 use crate::services::*;
+use crate::config::AppConfig;
+use crate::services::permissions::{CurrentUser, Permission};
 use sqlx::SqlitePool;
+use crate::utils::correlation::new_correlation_id;
+use crate::utils::file_utils::expand_home;
+use tracing::instrument;
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -20,24 +25,42 @@ pub struct CalculateSettlementRequest {
     pub jurisdiction: String,
     pub plaintiff_name: String,
     pub defendant_name: String,
+    /// How much risk the client will accept in negotiation; defaults to neutral
+    #[serde(default)]
+    pub risk_profile: settlement_calculator::ClientRiskProfile,
+    /// Insurance and other collateral source payments already received
+    #[serde(default)]
+    pub collateral_source_payments: f64,
+    /// Each defendant's share of fault when there is more than one
+    #[serde(default)]
+    pub defendants: Vec<settlement_calculator::DefendantShare>,
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_calculate_settlement(
     request: CalculateSettlementRequest,
+    current_user: State<'_, CurrentUser>,
     db: State<'_, SqlitePool>,
 ) -> Result<settlement_calculator::SettlementCalculation, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
     let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
 
+    let inputs = settlement_calculator::SettlementInputs {
+        case_type: request.case_type,
+        plaintiff_name: request.plaintiff_name,
+        defendant_name: request.defendant_name,
+        economic_damages: request.economic_damages,
+        injury_details: request.injury_details,
+        liability_percentage: request.liability_percentage,
+        jurisdiction: request.jurisdiction,
+        risk_profile: request.risk_profile,
+        collateral_source_payments: request.collateral_source_payments,
+        defendants: request.defendants,
+    };
+
     service
-        .calculate_settlement(
-            &request.matter_id,
-            request.case_type,
-            request.economic_damages,
-            request.injury_details,
-            request.liability_percentage,
-            &request.jurisdiction,
-        )
+        .calculate_settlement_from_inputs(&request.matter_id, inputs, &session.user_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -50,26 +73,36 @@ pub struct GenerateDemandLetterRequest {
     pub case_facts: String,
     pub liability_description: String,
     pub damages_description: String,
+    /// Days the recipient has to respond; defaults to 30
+    #[serde(default)]
+    pub deadline_days: Option<u32>,
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_generate_demand_letter(
     request: GenerateDemandLetterRequest,
+    current_user: State<'_, CurrentUser>,
     db: State<'_, SqlitePool>,
 ) -> Result<settlement_calculator::DemandLetter, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
     let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
 
     service
         .generate_demand_letter(
             &request.settlement_calculation,
             &request.recipient_name,
+            &request.recipient_address,
             &request.case_facts,
+            request.deadline_days.unwrap_or(settlement_calculator::DEFAULT_DEMAND_DEADLINE_DAYS),
+            &session.user_id,
         )
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_analyze_settlement_offer(
     settlement_calc_id: String,
     offer_amount: f64,
@@ -84,11 +117,170 @@ pub async fn cmd_analyze_settlement_offer(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_record_settlement_offer(
+    calc_id: String,
+    offer_amount: f64,
+    offer_from: String,
+    terms: Vec<settlement_calculator::SettlementTerm>,
+    conditions: Vec<String>,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<settlement_calculator::SettlementOffer, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .record_offer(&calc_id, offer_amount, &offer_from, terms, conditions)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_compare_settlement_offers(
+    calc_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<settlement_calculator::OfferComparison, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .build_offer_comparison(&calc_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_settlement_calculation(
+    calc_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Option<settlement_calculator::SettlementCalculation>, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .get_settlement_calculation(&calc_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_list_settlement_calculations(
+    matter_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<settlement_calculator::SettlementCalculation>, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .list_calculations(&matter_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_recalculate_settlement(
+    calc_id: String,
+    inputs: settlement_calculator::SettlementInputs,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<settlement_calculator::SettlementCalculation, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .recalculate(&calc_id, inputs, &session.user_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_settlement_history(
+    matter_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<settlement_calculator::SettlementCalculation>, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .get_calculation_history(&matter_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_demand_letter(
+    letter_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Option<settlement_calculator::DemandLetter>, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .get_demand_letter(&letter_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Copy a supporting document into the exhibit store and list it as the
+/// letter's next exhibit
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_attach_demand_exhibit(
+    letter_id: String,
+    file_path: String,
+    description: String,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<settlement_calculator::DemandExhibit, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone())
+        .with_exhibit_store(expand_home(&config.global.data_dir).join("exhibits"));
+
+    service
+        .attach_exhibit(&letter_id, std::path::Path::new(&file_path), &description)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Record the recipient's response to a demand so it no longer expires;
+/// `responded_at` defaults to now
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_mark_demand_answered(
+    letter_id: String,
+    responded_at: Option<chrono::DateTime<chrono::Utc>>,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<(), String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = settlement_calculator::SettlementCalculatorService::new(db.inner().clone());
+
+    service
+        .mark_demand_answered(&letter_id, responded_at.unwrap_or_else(chrono::Utc::now))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // CRITICAL FEATURE: Bulk Data Ingestion
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_start_bulk_ingestion_courtlistener(
     db: State<'_, SqlitePool>,
 ) -> Result<bulk_data_ingestion::BulkIngestionJob, String> {
@@ -101,6 +293,7 @@ pub async fn cmd_start_bulk_ingestion_courtlistener(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_start_bulk_ingestion_govinfo(
     db: State<'_, SqlitePool>,
 ) -> Result<bulk_data_ingestion::BulkIngestionJob, String> {
@@ -113,6 +306,7 @@ pub async fn cmd_start_bulk_ingestion_govinfo(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_start_bulk_ingestion_harvard(
     db: State<'_, SqlitePool>,
 ) -> Result<bulk_data_ingestion::BulkIngestionJob, String> {
@@ -125,19 +319,88 @@ pub async fn cmd_start_bulk_ingestion_harvard(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_ingestion_status(
     job_id: String,
+    jobs: State<'_, bulk_data_ingestion::progress::IngestionJobs>,
     db: State<'_, SqlitePool>,
-) -> Result<bulk_data_ingestion::BulkIngestionJob, String> {
-    let service = bulk_data_ingestion::BulkDataIngestionService::new(db.inner().clone());
+) -> Result<bulk_data_ingestion::progress::IngestionProgress, String> {
+    // Live progress while the job runs here, the saved checkpoint otherwise
+    if let Some(progress) = jobs.progress(&job_id) {
+        return Ok(progress);
+    }
 
+    let service = bulk_data_ingestion::BulkDataIngestionService::new(db.inner().clone(), std::env::temp_dir());
     service
-        .get_job_status(&job_id)
+        .get_job_progress(&job_id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Start ingesting a GovInfo or CAP file in the background; returns the job id
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_start_file_ingestion(
+    path: String,
+    source: bulk_data_ingestion::DataSource,
+    max_records_per_sec: Option<u32>,
+    jobs: State<'_, bulk_data_ingestion::progress::IngestionJobs>,
+    db: State<'_, SqlitePool>,
+) -> Result<String, String> {
+    let service = bulk_data_ingestion::BulkDataIngestionService::new(db.inner().clone(), std::env::temp_dir());
+    let job_id = service
+        .create_file_job(std::path::Path::new(&path), source)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    spawn_file_ingestion(service, job_id.clone(), max_records_per_sec, jobs.inner().clone());
+    Ok(job_id)
+}
+
+/// Continue a cancelled or interrupted job from its checkpoint
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_resume_ingestion(
+    job_id: String,
+    max_records_per_sec: Option<u32>,
+    jobs: State<'_, bulk_data_ingestion::progress::IngestionJobs>,
+    db: State<'_, SqlitePool>,
+) -> Result<(), String> {
+    if jobs.progress(&job_id).is_some() {
+        return Err(format!("Ingestion job {} is already running", job_id));
+    }
+
+    let service = bulk_data_ingestion::BulkDataIngestionService::new(db.inner().clone(), std::env::temp_dir());
+    spawn_file_ingestion(service, job_id, max_records_per_sec, jobs.inner().clone());
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_cancel_ingestion(
+    job_id: String,
+    jobs: State<'_, bulk_data_ingestion::progress::IngestionJobs>,
+) -> Result<bool, String> {
+    Ok(jobs.cancel(&job_id))
+}
+
+fn spawn_file_ingestion(
+    service: bulk_data_ingestion::BulkDataIngestionService,
+    job_id: String,
+    max_records_per_sec: Option<u32>,
+    jobs: bulk_data_ingestion::progress::IngestionJobs,
+) {
+    let control = jobs.register(&job_id, max_records_per_sec);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = service.run_file_ingestion(&job_id, &control).await {
+            tracing::error!("Ingestion job {} failed: {}", job_id, e);
+        }
+        jobs.finish(&job_id);
+    });
+}
+
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_search_ingested_cases(
     query: String,
     filters: Option<bulk_data_ingestion::SearchFilters>,
@@ -152,11 +415,32 @@ pub async fn cmd_search_ingested_cases(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_import_bulk_file(
+    path: String,
+    source: bulk_data_ingestion::DataSource,
+    db: State<'_, SqlitePool>,
+) -> Result<bulk_data_ingestion::StoreSummary, String> {
+    let service = bulk_data_ingestion::BulkDataIngestionService::new(db.inner().clone(), std::env::temp_dir());
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    match source {
+        bulk_data_ingestion::DataSource::GovInfo => service.import_govinfo_uslm(&contents).await,
+        bulk_data_ingestion::DataSource::HarvardCaselaw => service.import_harvard_cases(&contents).await,
+        other => Err(anyhow::anyhow!("No file parser for {:?}", other)),
+    }
+    .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // GAME CHANGER: AI Automation Suite
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_automate_case_lifecycle(
     matter_id: String,
     db: State<'_, SqlitePool>,
@@ -170,6 +454,7 @@ pub async fn cmd_automate_case_lifecycle(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_automate_client_management(
     client_id: String,
     db: State<'_, SqlitePool>,
@@ -183,6 +468,7 @@ pub async fn cmd_automate_client_management(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_automate_team_management(
     firm_id: String,
     db: State<'_, SqlitePool>,
@@ -196,6 +482,7 @@ pub async fn cmd_automate_team_management(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_predict_case_outcome(
     matter_id: String,
     db: State<'_, SqlitePool>,
@@ -209,6 +496,7 @@ pub async fn cmd_predict_case_outcome(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_optimize_firm_workflow(
     firm_id: String,
     db: State<'_, SqlitePool>,
@@ -226,6 +514,7 @@ pub async fn cmd_optimize_firm_workflow(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_assemble_document(
     template_id: String,
     matter_id: String,
@@ -241,6 +530,7 @@ pub async fn cmd_assemble_document(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_run_conflict_check(
     client_name: String,
     matter_description: String,
@@ -256,6 +546,24 @@ pub async fn cmd_run_conflict_check(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_record_conflict_waiver(
+    conflict_id: String,
+    basis: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<conflict_checking::ConflictWaiver, String> {
+    let session = current_user.require(Permission::WaiveConflict).map_err(|e| e.to_string())?;
+    let service = conflict_checking::ConflictCheckingService::new(db.inner().clone());
+
+    service
+        .record_conflict_waiver(&conflict_id, &session.user_id, &basis)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_start_time_entry(
     matter_id: String,
     attorney_id: String,
@@ -271,6 +579,7 @@ pub async fn cmd_start_time_entry(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_stop_time_entry(
     entry_id: String,
     db: State<'_, SqlitePool>,
@@ -283,14 +592,101 @@ pub async fn cmd_stop_time_entry(
         .map_err(|e| e.to_string())
 }
 
+/// Record editing or research activity for the signed-in attorney; returns
+/// false when detection of that kind of activity is turned off
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_record_activity(
+    event: activity_monitor::ActivityEvent,
+    current_user: State<'_, CurrentUser>,
+    tracker: State<'_, activity_monitor::ActivityTracker>,
+) -> Result<bool, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    Ok(tracker.record(&session.user_id, event))
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_suggested_time_entries(
+    current_user: State<'_, CurrentUser>,
+    tracker: State<'_, activity_monitor::ActivityTracker>,
+) -> Result<Vec<activity_monitor::SuggestedTimeEntry>, String> {
+    let session = current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    Ok(tracker.suggestions(&session.user_id, chrono::Utc::now()))
+}
+
+/// Bill a suggested entry the attorney has confirmed, optionally with their
+/// own description; the suggestion stays pending if the entry can't be saved
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_confirm_suggested_time_entry(
+    suggestion_id: String,
+    description: Option<String>,
+    current_user: State<'_, CurrentUser>,
+    tracker: State<'_, activity_monitor::ActivityTracker>,
+    db: State<'_, SqlitePool>,
+) -> Result<time_tracking::TimeEntry, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let suggestion = tracker
+        .take(&session.user_id, &suggestion_id)
+        .ok_or_else(|| format!("No suggested time entry {}", suggestion_id))?;
+    let service = time_tracking::TimeTrackingService::new(db.inner().clone());
+
+    match service.confirm_suggested_entry(&suggestion, description).await {
+        Ok(entry) => Ok(entry),
+        Err(e) => {
+            tracker.restore(suggestion);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_dismiss_suggested_time_entry(
+    suggestion_id: String,
+    current_user: State<'_, CurrentUser>,
+    tracker: State<'_, activity_monitor::ActivityTracker>,
+) -> Result<(), String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    tracker
+        .take(&session.user_id, &suggestion_id)
+        .map(|_| ())
+        .ok_or_else(|| format!("No suggested time entry {}", suggestion_id))
+}
+
+/// Seed an attorney's default rates; without an attorney, the firm-wide
+/// rates are brought in line with configuration instead of using `rates`
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_seed_default_rates(
+    attorney_id: Option<String>,
+    rates: Vec<time_tracking::BillingRate>,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<usize, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let service = time_tracking::TimeTrackingService::new(db.inner().clone());
+
+    match attorney_id {
+        Some(attorney_id) => service.seed_default_rates(&attorney_id, rates).await,
+        None => service.sync_firm_rates(&config.global.billing_rates, chrono::Utc::now()).await,
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_generate_invoice(
     matter_id: String,
     billing_period_start: String,
     billing_period_end: String,
+    config: State<'_, AppConfig>,
     db: State<'_, SqlitePool>,
 ) -> Result<billing::Invoice, String> {
-    let service = billing::BillingService::new(db.inner().clone());
+    let service = billing::BillingService::new(db.inner().clone())
+        .with_numbering(config.global.numbering.invoice.clone());
 
     service
         .generate_invoice(&matter_id, &billing_period_start, &billing_period_end)
@@ -299,12 +695,15 @@ pub async fn cmd_generate_invoice(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_process_payment(
     invoice_id: String,
     amount: f64,
     payment_method: billing::PaymentMethod,
+    current_user: State<'_, CurrentUser>,
     db: State<'_, SqlitePool>,
 ) -> Result<billing::Payment, String> {
+    current_user.require(Permission::ProcessPayment).map_err(|e| e.to_string())?;
     let service = billing::BillingService::new(db.inner().clone());
 
     service
@@ -313,16 +712,144 @@ pub async fn cmd_process_payment(
         .map_err(|e| e.to_string())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrustTransferRequest {
+    pub trust_account_id: String,
+    pub matter_id: String,
+    pub client_id: String,
+    pub amount: f64,
+    pub description: String,
+    pub reference_number: Option<String>,
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_trust_deposit(
+    request: TrustTransferRequest,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<billing::TrustTransaction, String> {
+    let session = current_user.require(Permission::TrustTransfer).map_err(|e| e.to_string())?;
+    let service = billing::BillingService::new(db.inner().clone());
+
+    service
+        .create_trust_deposit(
+            &request.trust_account_id,
+            &request.matter_id,
+            &request.client_id,
+            request.amount,
+            &request.description,
+            request.reference_number,
+            &session.user_id,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_trust_withdrawal(
+    request: TrustTransferRequest,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<billing::TrustTransaction, String> {
+    let session = current_user.require(Permission::TrustTransfer).map_err(|e| e.to_string())?;
+    let service = billing::BillingService::new(db.inner().clone());
+
+    service
+        .create_trust_withdrawal(
+            &request.trust_account_id,
+            &request.matter_id,
+            &request.client_id,
+            request.amount,
+            &request.description,
+            request.reference_number,
+            &session.user_id,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_post_trust_interest(
+    trust_account_id: String,
+    amount: f64,
+    period: billing::StatementPeriod,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<billing::InterestPosting, String> {
+    let session = current_user.require(Permission::TrustTransfer).map_err(|e| e.to_string())?;
+    let service = billing::BillingService::new(db.inner().clone())
+        .with_iolta_foundation(config.global.iolta_foundation.clone());
+
+    service
+        .post_interest(&trust_account_id, amount, period, &session.user_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpenseFromReceipt {
+    pub expense: billing::Expense,
+    /// Fields in `needs_review` should be checked before the expense is approved
+    pub receipt: receipts::ParsedReceipt,
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_create_expense_from_receipt(
+    matter_id: String,
+    category: billing::ExpenseCategory,
+    receipt_path: String,
+    is_billable: bool,
+    is_reimbursable: bool,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<ExpenseFromReceipt, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let mut service = billing::BillingService::new(db.inner().clone());
+    if let Some(ocr) = receipts::default_ocr_engine() {
+        service = service.with_ocr_engine(ocr);
+    }
+
+    let (expense, receipt) = service
+        .create_expense_from_receipt(
+            &matter_id,
+            &session.user_id,
+            category,
+            std::path::Path::new(&receipt_path),
+            is_billable,
+            is_reimbursable,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(ExpenseFromReceipt { expense, receipt })
+}
+
 // ============================================================================
 // Tier 1 Features: Email, Contract Review, Legal Research
 // ============================================================================
 
+/// Email service that reaches Gmail and Outlook with the app's OAuth clients
+fn connected_email_service(
+    db: &SqlitePool,
+    config: &AppConfig,
+) -> Result<email_integration::EmailIntegrationService, String> {
+    let mailbox = email_providers::HttpMailboxClient::new(config.global.email_oauth.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(email_integration::EmailIntegrationService::new(db.clone()).with_mailbox(std::sync::Arc::new(mailbox)))
+}
+
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_sync_emails(
     account_id: String,
+    config: State<'_, AppConfig>,
     db: State<'_, SqlitePool>,
-) -> Result<email_integration::EmailSyncResult, String> {
-    let service = email_integration::EmailIntegrationService::new(db.inner().clone());
+) -> Result<u32, String> {
+    let service = connected_email_service(db.inner(), config.inner())?;
 
     service
         .sync_emails(&account_id)
@@ -331,6 +858,7 @@ pub async fn cmd_sync_emails(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_link_email_to_matter(
     email_id: String,
     matter_id: String,
@@ -344,21 +872,109 @@ pub async fn cmd_link_email_to_matter(
         .map_err(|e| e.to_string())
 }
 
+/// Keep a PDF copy of a linked email in its matter's file; returns the
+/// archived PDF's path
 #[tauri::command]
-pub async fn cmd_review_contract(
-    document_path: String,
-    contract_type: contract_review::ContractType,
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_archive_email_as_pdf(
+    email_id: String,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
     db: State<'_, SqlitePool>,
-) -> Result<contract_review::ContractAnalysis, String> {
-    let service = contract_review::ContractReviewService::new(db.inner().clone());
+) -> Result<String, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    // The mailbox supplies inline images that were never downloaded
+    let service = connected_email_service(db.inner(), config.inner())?
+        .with_archive_dir(expand_home(&config.global.data_dir).join("email_archive"));
+
+    let path = service.archive_email_as_pdf(&email_id).await.map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_create_marketing_campaign(
+    name: String,
+    campaign_type: marketing::CampaignType,
+    budget: f64,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<marketing::MarketingCampaign, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = marketing::MarketingService::new(db.inner().clone());
+
+    service
+        .create_campaign(&name, campaign_type, budget)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Send a campaign template to `recipients` through a connected mail account
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_send_campaign(
+    account_id: String,
+    template_id: String,
+    recipients: Vec<String>,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<marketing::CampaignResult, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = marketing::MarketingService::new(db.inner().clone());
+    let mailer = marketing::AccountMailer::new(connected_email_service(db.inner(), config.inner())?, &account_id);
 
     service
-        .analyze_contract(&document_path, contract_type)
+        .send_campaign(&mailer, &template_id, &recipients)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_review_contract(
+    contract_id: String,
+    document_path: String,
+    contract_type: contract_review::ContractType,
+    client_name: Option<String>,
+    matter_id: Option<String>,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<contract_review::ContractAnalysis, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    // Obligations the client owes are scheduled as reminders on the matter's calendar
+    let mut service = contract_review::ContractReviewService::new(db.inner().clone())
+        .with_risk_weights(config.global.risk_weights.clone())
+        .with_calendar(std::sync::Arc::new(calendar_sync::CalendarSyncService::new()));
+    if let Some(client_name) = client_name.filter(|c| !c.trim().is_empty()) {
+        service = service.with_client(client_name);
+    }
+    if let Some(matter_id) = matter_id.filter(|m| !m.trim().is_empty()) {
+        service = service.with_matter(matter_id);
+    }
+
+    // Word files are parsed; anything else is read as plain text
+    let path = std::path::Path::new(&document_path);
+    let is_docx = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("docx"));
+    if is_docx {
+        service
+            .analyze_contract_docx(&contract_id, path, contract_type, &session.user_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        service
+            .analyze_contract(&contract_id, &text, contract_type, &session.user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_research_legal_issue(
     query: String,
     jurisdiction: String,
@@ -378,6 +994,7 @@ pub async fn cmd_research_legal_issue(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_create_discovery_request(
     matter_id: String,
     request_type: discovery::DiscoveryType,
@@ -392,6 +1009,7 @@ pub async fn cmd_create_discovery_request(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_generate_privilege_log(
     matter_id: String,
     db: State<'_, SqlitePool>,
@@ -405,6 +1023,7 @@ pub async fn cmd_generate_privilege_log(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_search_expert_witnesses(
     specialty: String,
     db: State<'_, SqlitePool>,
@@ -418,10 +1037,13 @@ pub async fn cmd_search_expert_witnesses(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_submit_court_filing(
     filing: court_filing::EFiling,
+    current_user: State<'_, CurrentUser>,
     db: State<'_, SqlitePool>,
 ) -> Result<String, String> {
+    current_user.require(Permission::SubmitEFiling).map_err(|e| e.to_string())?;
     let service = court_filing::CourtFilingService::new(db.inner().clone());
 
     service
@@ -431,28 +1053,491 @@ pub async fn cmd_submit_court_filing(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_create_lead(
     name: String,
     email: String,
+    phone: Option<String>,
+    source: Option<crm::LeadSource>,
+    practice_area: Option<String>,
+    opposing_party: Option<String>,
+    notes: Option<String>,
     db: State<'_, SqlitePool>,
 ) -> Result<crm::Lead, String> {
     let service = crm::CRMService::new(db.inner().clone());
 
     service
-        .create_lead(&name, &email)
+        .create_lead(crm::CreateLeadRequest {
+            name,
+            email,
+            phone,
+            source,
+            practice_area,
+            opposing_party,
+            notes,
+        })
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_convert_lead_to_client(
     lead_id: String,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<crm::LeadConversion, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = crm::CRMService::new(db.inner().clone()).with_numbering(config.global.numbering.matter.clone());
+
+    service
+        .convert_lead_to_client(&lead_id, &session.user_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Matter Closing
+// ============================================================================
+
+/// Close a matter once its closing checklist passes, exporting its full file
+/// first; the report lists what blocks a matter that stays open
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_close_matter(
+    matter_id: String,
+    current_user: State<'_, CurrentUser>,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<matter_closing::CloseReport, String> {
+    current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let service = matter_closing::MatterClosingService::new(db.inner().clone())
+        .with_file_export(expand_home(&config.global.data_dir).join("closed_files"));
+
+    service.close_matter(&matter_id).await.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Client Portal
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_share_document(
+    matter_id: String,
+    document_path: String,
+    client_id: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<collaboration::ShareLink, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    tracing::info!("{} sharing a document on matter {} with client {}", session.user_id, matter_id, client_id);
+    let service = collaboration::CollaborationService::new(db.inner().clone());
+
+    service
+        .share_document(&matter_id, std::path::Path::new(&document_path), &client_id, expires_at)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_open_shared_document(
+    token: String,
+    matter_id: String,
+    client_id: String,
     db: State<'_, SqlitePool>,
 ) -> Result<String, String> {
-    let service = crm::CRMService::new(db.inner().clone());
+    let service = collaboration::CollaborationService::new(db.inner().clone());
+
+    service
+        .access_document(&token, &matter_id, &client_id)
+        .await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_revoke_document_share(
+    share_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<bool, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    tracing::info!("{} revoking document share {}", session.user_id, share_id);
+    let service = collaboration::CollaborationService::new(db.inner().clone());
+
+    service
+        .revoke_share(&share_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_post_portal_message(
+    matter_id: String,
+    body: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<client_portal::PortalMessage, String> {
+    let session = current_user.require(Permission::EditRecords).map_err(|e| e.to_string())?;
+    let author = client_portal::PortalParticipant::staff(&session);
+    let service = client_portal::ClientPortalService::new(db.inner().clone());
+
+    service
+        .post_client_message(&matter_id, &author, &body)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_portal_messages(
+    matter_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<client_portal::PortalMessage>, String> {
+    let session = current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
+    let viewer = client_portal::PortalParticipant::staff(&session);
+    let service = client_portal::ClientPortalService::new(db.inner().clone());
 
     service
-        .convert_to_client(&lead_id)
+        .get_messages(&matter_id, &viewer)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Jury Selection
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_evaluate_juror(
+    juror: jury_selection::JurorProfile,
+    case_profile: jury_selection::CaseProfile,
+) -> Result<jury_selection::JurorScore, String> {
+    Ok(jury_selection::score_juror(&juror, &case_profile))
+}
+
+// ============================================================================
+// Mediation
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_record_mediation_position(
+    matter_id: String,
+    party: mediation::MediationParty,
+    amount: f64,
+    db: State<'_, SqlitePool>,
+) -> Result<mediation::MediationPosition, String> {
+    let service = mediation::MediationService::new(db.inner().clone());
+
+    service
+        .record_mediation_position(&matter_id, party, amount)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_mediation_gap(
+    matter_id: String,
+    db: State<'_, SqlitePool>,
+) -> Result<mediation::GapReport, String> {
+    let service = mediation::MediationService::new(db.inner().clone());
+
+    service
+        .mediation_gap(&matter_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Immigration
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_immigration_checklist(
+    case: immigration::ImmigrationCase,
+) -> Result<Vec<immigration::ChecklistItem>, String> {
+    Ok(case.checklist())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_immigration_mark_ready(
+    mut case: immigration::ImmigrationCase,
+) -> Result<immigration::ImmigrationCase, String> {
+    case.mark_ready_to_file().map_err(|e| e.to_string())?;
+    Ok(case)
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_immigration_deadlines(
+    case: immigration::ImmigrationCase,
+) -> Result<Vec<calendar_sync::CalendarEvent>, String> {
+    let calendar = calendar_sync::CalendarSyncService::new();
+
+    Ok(case
+        .deadlines()
+        .into_iter()
+        .map(|deadline| calendar.deadline_to_calendar_event(deadline))
+        .collect())
+}
+
+// ============================================================================
+// Real Estate
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_compute_closing_statement(
+    deal: real_estate::RealEstateDeal,
+) -> Result<real_estate::ClosingStatement, String> {
+    Ok(real_estate::compute_closing_statement(&deal))
+}
+
+// ============================================================================
+// Estate Planning
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_generate_will(
+    testator: estate_planning::Testator,
+    bequests: Vec<estate_planning::Bequest>,
+) -> Result<estate_planning::Document, String> {
+    estate_planning::generate_will(&testator, &bequests).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Workers' Compensation
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_compute_wc_benefits(
+    average_weekly_wage: f64,
+    disability: workers_comp::DisabilityType,
+    state: String,
+) -> Result<workers_comp::WcBenefits, String> {
+    Ok(workers_comp::compute_wc_benefits(average_weekly_wage, disability, &state))
+}
+
+// ============================================================================
+// Patent & Trademark
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_ip_deadlines(
+    ip_matter: patent::IpMatter,
+) -> Result<Vec<calendar_sync::CalendarEvent>, String> {
+    let calendar = calendar_sync::CalendarSyncService::new();
+
+    Ok(ip_matter
+        .to_legal_deadlines()
+        .into_iter()
+        .map(|deadline| calendar.deadline_to_calendar_event(deadline))
+        .collect())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_ip_refresh_status(
+    mut ip_matter: patent::IpMatter,
+) -> Result<patent::IpMatter, String> {
+    ip_matter.refresh_status(chrono::Utc::now());
+    Ok(ip_matter)
+}
+
+// ============================================================================
+// Global Search
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_global_search(
+    query: String,
+    scopes: Vec<global_search::SearchScope>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<global_search::GlobalHit>, String> {
+    let service = global_search::GlobalSearchService::new(db.inner().clone());
+    service.global_search(&query, &scopes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Webhooks
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_add_webhook(
+    url: String,
+    secret: String,
+    events: Vec<webhooks::WebhookEventType>,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<webhooks::WebhookEndpoint, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let dispatcher = webhooks::WebhookDispatcher::new(db.inner().clone());
+    dispatcher.add_endpoint(&url, &secret, events)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_list_webhooks(
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<webhooks::WebhookEndpoint>, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let dispatcher = webhooks::WebhookDispatcher::new(db.inner().clone());
+    dispatcher.list_endpoints()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_remove_webhook(
+    endpoint_id: String,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<bool, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let dispatcher = webhooks::WebhookDispatcher::new(db.inner().clone());
+    dispatcher.remove_endpoint(&endpoint_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_list_webhook_deliveries(
+    endpoint_id: Option<String>,
+    limit: Option<i64>,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<webhooks::WebhookDelivery>, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let dispatcher = webhooks::WebhookDispatcher::new(db.inner().clone());
+    dispatcher.list_deliveries(endpoint_id.as_deref(), limit.unwrap_or(100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Audit Log
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_query_audit_log(
+    filter: audit_log::AuditFilter,
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<Vec<audit_log::AuditEntry>, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let service = audit_log::AuditLogService::new(db.inner().clone());
+    service.query_audit_log(&filter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_verify_audit_log(
+    current_user: State<'_, CurrentUser>,
+    db: State<'_, SqlitePool>,
+) -> Result<audit_log::ChainVerification, String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
+    let service = audit_log::AuditLogService::new(db.inner().clone());
+    service.verify_chain()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Document Import
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_parse_docx(
+    path: String,
+) -> Result<docx_import::ParsedDocument, String> {
+    docx_import::parse_docx(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Document Anchoring
+// ============================================================================
+
+/// The configured timestamp authority; anchoring never reaches the network
+/// unless one is set
+fn timestamp_authority(config: &AppConfig) -> Result<blockchain::Rfc3161Authority, String> {
+    let tsa = config.global.timestamp_authority.as_ref()
+        .ok_or("Document anchoring is off: no timestamp authority is configured")?;
+    blockchain::Rfc3161Authority::from_config(tsa).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_anchor_document(
+    hash: String,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<blockchain::AnchorProof, String> {
+    let authority = timestamp_authority(&config)?;
+    let service = blockchain::BlockchainService::new(db.inner().clone())
+        .with_authority(std::sync::Arc::new(authority));
+    service.anchor_document(&hash)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_verify_anchor(
+    proof: blockchain::AnchorProof,
+    hash: String,
+    config: State<'_, AppConfig>,
+    db: State<'_, SqlitePool>,
+) -> Result<bool, String> {
+    let authority = timestamp_authority(&config)?;
+    let service = blockchain::BlockchainService::new(db.inner().clone())
+        .with_authority(std::sync::Arc::new(authority));
+    service.verify_anchor(&proof, &hash)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Local Dataset Import
+// ============================================================================
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_import_local_dataset(
+    path: String,
+    format: bulk_import_service::DatasetFormat,
+    db: State<'_, SqlitePool>,
+) -> Result<bulk_import_service::ImportSummary, String> {
+    let service = bulk_import_service::BulkImportService::new(db.inner().clone(), None, None);
+    service.import_local_dataset(std::path::Path::new(&path), format)
         .await
         .map_err(|e| e.to_string())
 }
@@ -462,6 +1547,7 @@ pub async fn cmd_convert_lead_to_client(
 // ============================================================================
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_transcribe_audio(
     audio_path: String,
     language: Option<String>,
@@ -476,6 +1562,7 @@ pub async fn cmd_transcribe_audio(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_run_analytics_report(
     report_type: analytics::ReportType,
     date_range: analytics::DateRange,
@@ -490,6 +1577,7 @@ pub async fn cmd_run_analytics_report(
 }
 
 #[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_check_iolta_compliance(
     trust_account_id: String,
     db: State<'_, SqlitePool>,
@@ -8,59 +8,6 @@ use tauri::State;
 
 // ============= SETTLEMENT CALCULATION COMMANDS =============
 
-#[tauri::command]
-pub async fn cmd_calculate_settlement(
-    db: State<'_, SqlitePool>,
-    matter_id: String,
-    case_type: CaseType,
-    plaintiff_name: String,
-    defendant_name: String,
-    economic_damages: EconomicDamages,
-    injury_details: Option<PersonalInjuryDetails>,
-    liability_percentage: f64,
-    jurisdiction: String,
-    calculated_by: String,
-) -> Result<SettlementCalculation, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    service
-        .calculate_settlement(
-            &matter_id,
-            case_type,
-            &plaintiff_name,
-            &defendant_name,
-            economic_damages,
-            injury_details,
-            liability_percentage,
-            &jurisdiction,
-            &calculated_by,
-        )
-        .await
-        .map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn cmd_get_settlement_calculation(
-    db: State<'_, SqlitePool>,
-    calc_id: String,
-) -> Result<Option<SettlementCalculation>, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    // TODO: Implement database retrieval
-    Ok(None)
-}
-
-#[tauri::command]
-pub async fn cmd_list_settlement_calculations(
-    db: State<'_, SqlitePool>,
-    matter_id: String,
-) -> Result<Vec<SettlementCalculation>, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    // TODO: Implement database query
-    Ok(Vec::new())
-}
-
 #[tauri::command]
 pub async fn cmd_update_settlement_calculation(
     db: State<'_, SqlitePool>,
@@ -208,7 +155,7 @@ pub async fn cmd_generate_ai_analysis(
 pub async fn cmd_get_judge_history(
     db: State<'_, SqlitePool>,
     judge_name: String,
-) -> Result<JudgeHistory, String> {
+) -> Result<Option<JudgeHistory>, String> {
     let service = SettlementCalculatorService::new(db.inner().clone());
 
     service
@@ -247,7 +194,7 @@ pub async fn cmd_get_insurance_profile(
 pub async fn cmd_get_venue_statistics(
     db: State<'_, SqlitePool>,
     jurisdiction: String,
-) -> Result<VenueStatistics, String> {
+) -> Result<Option<VenueStatistics>, String> {
     let service = SettlementCalculatorService::new(db.inner().clone());
 
     service
@@ -297,34 +244,6 @@ pub async fn cmd_get_treatment_events(
 
 // ============= NEGOTIATION COMMANDS =============
 
-#[tauri::command]
-pub async fn cmd_record_settlement_offer(
-    db: State<'_, SqlitePool>,
-    calc_id: String,
-    offer_amount: f64,
-    offer_from: String,
-    terms: Vec<SettlementTerm>,
-    conditions: Vec<String>,
-) -> Result<SettlementOffer, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    service
-        .record_offer(&calc_id, offer_amount, &offer_from, terms, conditions)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn cmd_get_settlement_offers(
-    db: State<'_, SqlitePool>,
-    calc_id: String,
-) -> Result<Vec<SettlementOffer>, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    // TODO: Implement database query
-    Ok(Vec::new())
-}
-
 #[tauri::command]
 pub async fn cmd_generate_counteroffer(
     db: State<'_, SqlitePool>,
@@ -366,21 +285,6 @@ pub async fn cmd_update_offer_status(
 
 // ============= DEMAND LETTER COMMANDS =============
 
-#[tauri::command]
-pub async fn cmd_generate_demand_letter(
-    db: State<'_, SqlitePool>,
-    calc_id: String,
-    recipient_name: String,
-    recipient_address: String,
-    facts: String,
-    created_by: String,
-) -> Result<DemandLetter, String> {
-    let service = SettlementCalculatorService::new(db.inner().clone());
-
-    // TODO: Get settlement calculation from database
-    Err("Settlement calculation not found".to_string())
-}
-
 #[tauri::command]
 pub async fn cmd_get_demand_letters(
     db: State<'_, SqlitePool>,
@@ -41,6 +41,41 @@ pub struct CourtConfig {
     pub jurisdiction: String,
     pub formatting: FormattingConfig,
     pub efiling: Option<EFilingConfig>,
+    /// Defaults for every county; a county's own local rules override them
+    #[serde(default)]
+    pub local_rules: LocalRulesConfig,
+    #[serde(default)]
+    pub fee_schedule: FeeScheduleConfig,
+}
+
+/// Filing fees in dollars, keyed by document type ("motion")
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FeeScheduleConfig {
+    /// The published fee bill the amounts are copied from, with its
+    /// effective date. A schedule without one is not used.
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub documents: HashMap<String, DocumentFeeConfig>,
+    /// Charged once on every filing, e.g. a portal convenience fee
+    #[serde(default)]
+    pub service_fees: Vec<ServiceFeeConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentFeeConfig {
+    pub base: f64,
+    #[serde(default)]
+    pub per_page: f64,
+    /// Pages covered by the base fee
+    #[serde(default)]
+    pub included_pages: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServiceFeeConfig {
+    pub name: String,
+    pub amount: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,10 +138,13 @@ pub struct CountyConfig {
     pub local_rules: LocalRulesConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Local rules; a rule left unset is inherited from the court
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LocalRulesConfig {
-    pub cover_sheet_required: bool,
-    pub electronic_service: bool,
+    #[serde(default)]
+    pub cover_sheet_required: Option<bool>,
+    #[serde(default)]
+    pub electronic_service: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,6 +173,10 @@ pub struct ProvidersConfig {
 pub struct ProviderConfig {
     pub name: String,
     pub enabled: bool,
+    /// Registered factory that builds this provider, e.g. "county_efiling"
+    /// for another county's e-filing system. Defaults to the provider's key.
+    #[serde(default)]
+    pub factory: Option<String>,
     pub base_url: String,
     pub rate_limit: RateLimitConfig,
     pub retry: RetryConfig,
@@ -218,6 +260,243 @@ pub struct GlobalConfig {
     pub log_dir: String,
     pub max_log_files: u32,
     pub max_log_size_mb: u64,
+    #[serde(default)]
+    pub numbering: NumberingConfig,
+    #[serde(default)]
+    pub risk_weights: RiskWeights,
+    #[serde(default)]
+    pub exchange_rates: ExchangeRateConfig,
+    #[serde(default)]
+    pub billing_rates: BillingRatesConfig,
+    /// Open a litigation task on the matter when a settlement demand expires unanswered
+    #[serde(default)]
+    pub schedule_litigation_on_expired_demand: bool,
+    /// RFC 3161 authority used to anchor documents; anchoring is off unless set
+    #[serde(default)]
+    pub timestamp_authority: Option<TimestampAuthorityConfig>,
+    /// Which editing and research activity is turned into suggested time entries
+    #[serde(default)]
+    pub time_detection: crate::services::time_tracking::AutomaticTimeDetection,
+    /// Bar foundation that IOLTA interest is remitted to, set for the state
+    /// the firm's trust accounts are held in
+    #[serde(default = "default_iolta_foundation")]
+    pub iolta_foundation: String,
+    /// The app's OAuth client registrations, used to refresh mail tokens
+    #[serde(default)]
+    pub email_oauth: EmailOAuthConfig,
+}
+
+fn default_iolta_foundation() -> String {
+    "Pennsylvania IOLTA Board".to_string()
+}
+
+/// OAuth clients registered with Google and Microsoft for mail access.
+/// Desktop clients are public clients, so Google's secret is not confidential.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct EmailOAuthConfig {
+    pub gmail_client_id: Option<String>,
+    pub gmail_client_secret: Option<String>,
+    pub outlook_client_id: Option<String>,
+}
+
+/// A timestamp authority and the certificate its tokens must be signed with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimestampAuthorityConfig {
+    pub url: String,
+    /// PEM or DER signing certificate of the authority
+    pub certificate_path: String,
+}
+
+/// How generated invoice and matter numbers are laid out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NumberingConfig {
+    pub invoice: NumberingScheme,
+    /// Matter numbers always include the matter type code (CIV, CRIM, ...)
+    /// after the prefix
+    pub matter: NumberingScheme,
+}
+
+impl Default for NumberingConfig {
+    fn default() -> Self {
+        Self {
+            invoice: NumberingScheme {
+                prefix: "INV".to_string(),
+                separator: "-".to_string(),
+                include_year: false,
+                padding: 6,
+                reset_yearly: false,
+            },
+            matter: NumberingScheme {
+                prefix: String::new(),
+                separator: "-".to_string(),
+                include_year: true,
+                padding: 4,
+                reset_yearly: true,
+            },
+        }
+    }
+}
+
+/// e.g. prefix "INV", year segment, padding 3 -> "INV-2024-001"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NumberingScheme {
+    pub prefix: String,
+    pub separator: String,
+    pub include_year: bool,
+    /// Minimum digits in the sequence number
+    pub padding: usize,
+    /// Restart the sequence at 1 each calendar year
+    pub reset_yearly: bool,
+}
+
+impl Default for NumberingScheme {
+    fn default() -> Self {
+        NumberingConfig::default().invoice
+    }
+}
+
+/// Contract risk scoring profile: what each risk, issue and missing clause
+/// adds to the 0-1 risk score, and the score at which each level begins
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskWeights {
+    /// Added per identified risk
+    pub risk: SeverityWeights,
+    /// Added per drafting issue
+    pub issue: SeverityWeights,
+    /// Added per missing clause
+    pub missing_clause: ClauseWeights,
+    pub thresholds: RiskThresholds,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            risk: SeverityWeights {
+                critical: 0.15,
+                high: 0.10,
+                medium: 0.05,
+                low: 0.02,
+            },
+            issue: SeverityWeights {
+                critical: 0.10,
+                high: 0.06,
+                medium: 0.03,
+                low: 0.01,
+            },
+            missing_clause: ClauseWeights::default(),
+            thresholds: RiskThresholds::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SeverityWeights {
+    pub critical: f64,
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ClauseWeights {
+    pub critical: f64,
+    pub important: f64,
+    pub recommended: f64,
+}
+
+impl Default for ClauseWeights {
+    fn default() -> Self {
+        Self {
+            critical: 0.08,
+            important: 0.04,
+            recommended: 0.02,
+        }
+    }
+}
+
+/// Lowest score of each level; anything under `medium` is Low
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RiskThresholds {
+    pub critical: f64,
+    pub high: f64,
+    pub medium: f64,
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            critical: 0.75,
+            high: 0.5,
+            medium: 0.25,
+        }
+    }
+}
+
+impl RiskWeights {
+    /// Weights are non-negative and thresholds ascend from medium to critical
+    pub fn is_valid(&self) -> bool {
+        let weights = [
+            self.risk.critical, self.risk.high, self.risk.medium, self.risk.low,
+            self.issue.critical, self.issue.high, self.issue.medium, self.issue.low,
+            self.missing_clause.critical, self.missing_clause.important, self.missing_clause.recommended,
+        ];
+        let t = &self.thresholds;
+        weights.iter().all(|w| *w >= 0.0) && 0.0 < t.medium && t.medium <= t.high && t.high <= t.critical
+    }
+}
+
+/// Currency used for reporting roll-ups and the rates used to convert into it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExchangeRateConfig {
+    /// ISO code, e.g. "USD"
+    pub reporting_currency: String,
+    /// Units of each currency per US dollar, keyed by ISO code
+    #[serde(default)]
+    pub units_per_usd: HashMap<String, f64>,
+}
+
+impl Default for ExchangeRateConfig {
+    fn default() -> Self {
+        Self {
+            reporting_currency: "USD".to_string(),
+            units_per_usd: HashMap::new(),
+        }
+    }
+}
+
+impl ExchangeRateConfig {
+    /// Known currency codes with positive rates
+    pub fn is_valid(&self) -> bool {
+        use crate::services::currency::Currency;
+        self.reporting_currency.parse::<Currency>().is_ok()
+            && self
+                .units_per_usd
+                .iter()
+                .all(|(code, rate)| code.parse::<Currency>().is_ok() && *rate > 0.0)
+    }
+}
+
+/// Firm-wide hourly rates used when an attorney has no rate of their own.
+/// Without a `firm_hourly_rate`, entries with no matching rate are left
+/// unpriced rather than billed at a guessed rate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BillingRatesConfig {
+    #[serde(default)]
+    pub firm_hourly_rate: Option<f64>,
+    /// Rates for particular activities, keyed by activity name ("Research")
+    #[serde(default)]
+    pub activity_rates: HashMap<String, f64>,
+}
+
+impl BillingRatesConfig {
+    /// Known activity names with non-negative rates
+    pub fn is_valid(&self) -> bool {
+        use crate::services::time_tracking::ActivityType;
+        self.firm_hourly_rate.unwrap_or(0.0) >= 0.0
+            && self.activity_rates.iter().all(|(activity, rate)| {
+                *rate >= 0.0 && serde_json::from_value::<ActivityType>(serde_json::json!(activity)).is_ok()
+            })
+    }
 }
 
 pub struct ConfigManager {
@@ -395,10 +674,17 @@ impl ConfigManager {
     }
 }
 
+/// Config directory used when the app has not resolved its own, e.g. in
+/// tests and command-line tools run from the repository
+pub const DEFAULT_CONFIG_DIR: &str = "config";
+
 // Convenience function for backward compatibility
 pub async fn load_config() -> Result<AppConfig> {
-    let config_dir = PathBuf::from("config");
-    let mut manager = ConfigManager::new(config_dir);
+    load_config_from(Path::new(DEFAULT_CONFIG_DIR)).await
+}
+
+pub async fn load_config_from(config_dir: &Path) -> Result<AppConfig> {
+    let mut manager = ConfigManager::new(config_dir.to_path_buf());
     manager.load_config().await.map(|c| c.clone())
 }
 
@@ -488,6 +774,15 @@ impl Default for GlobalConfig {
             log_dir: "~/.pa-edocket/logs".to_string(),
             max_log_files: 10,
             max_log_size_mb: 100,
+            numbering: NumberingConfig::default(),
+            risk_weights: RiskWeights::default(),
+            exchange_rates: ExchangeRateConfig::default(),
+            billing_rates: BillingRatesConfig::default(),
+            schedule_litigation_on_expired_demand: false,
+            timestamp_authority: None,
+            time_detection: Default::default(),
+            iolta_foundation: default_iolta_foundation(),
+            email_oauth: EmailOAuthConfig::default(),
         }
     }
 }
@@ -550,6 +845,18 @@ impl Validate for GlobalConfig {
             errors.add_field_error("max_log_size_mb", ValidationError::new("min_value"));
         }
 
+        if !self.risk_weights.is_valid() {
+            errors.add_field_error("risk_weights", ValidationError::new("invalid_risk_weights"));
+        }
+
+        if !self.exchange_rates.is_valid() {
+            errors.add_field_error("exchange_rates", ValidationError::new("invalid_exchange_rates"));
+        }
+
+        if !self.billing_rates.is_valid() {
+            errors.add_field_error("billing_rates", ValidationError::new("invalid_billing_rates"));
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
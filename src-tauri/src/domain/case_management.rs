@@ -73,6 +73,8 @@ pub struct Matter {
     pub status: MatterStatus,
     pub outcome: Option<String>,
     pub settlement_amount: Option<f64>,
+    #[serde(default)]
+    pub responsible_attorney_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
@@ -547,6 +549,14 @@ pub struct CreateMatterRequest {
     pub court_name: Option<String>,
     pub county: Option<String>,
     pub opposing_party: Option<String>,
+    #[serde(default)]
+    pub contingency_fee_percentage: Option<f64>,
+    #[serde(default)]
+    pub responsible_attorney_id: Option<String>,
+    /// State whose courts hear the matter, e.g. "NY"; its fee rules apply.
+    /// Pennsylvania when not given.
+    #[serde(default)]
+    pub forum_state: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -579,3 +589,43 @@ pub struct MatterSummary {
     pub total_time: f32,
     pub total_expenses: f32,
 }
+
+/// Which matters to list, and in what order; unset filters match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatterFilter {
+    pub status: Option<MatterStatus>,
+    pub practice_area_id: Option<String>,
+    pub responsible_attorney_id: Option<String>,
+    pub client_id: Option<String>,
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub sort: MatterSort,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatterSort {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    RecentlyUpdated,
+    MatterNumber,
+    Title,
+}
+
+/// One page of a longer listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 1-based
+    pub page: u32,
+    pub limit: u32,
+    /// Matching items across all pages
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    pub fn total_pages(&self) -> u32 {
+        self.total.div_ceil(self.limit.max(1) as u64) as u32
+    }
+}
@@ -0,0 +1,139 @@
+// Charge grade normalization
+// Court systems write grades as "F1", "F-1", "Felony 1", "Misd. 2nd Degree"
+// and so on; all of them map onto the strict ChargeGrade enum here
+
+use super::ChargeGrade;
+use serde::{Deserialize, Deserializer};
+use tracing::warn;
+
+/// Words that carry no information about the grade
+const FILLER: &[&str] = &["of", "the", "degree", "deg", "grade", "gr", "class", "st", "nd", "rd", "th"];
+
+/// Parse a grade as written by any source. Returns `None` for ungraded or
+/// unrecognizable input rather than guessing.
+pub fn parse_charge_grade(s: &str) -> Option<ChargeGrade> {
+    let tokens = tokenize(s);
+    let (class, rest) = tokens.split_first()?;
+
+    let degree = match rest {
+        [] => None,
+        [degree] => Some(parse_degree(degree)?),
+        _ => return None,
+    };
+
+    match (grade_class(class)?, degree) {
+        ('F', Some(1)) => Some(ChargeGrade::F1),
+        ('F', Some(2)) => Some(ChargeGrade::F2),
+        ('F', Some(3)) => Some(ChargeGrade::F3),
+        ('M', Some(1)) => Some(ChargeGrade::M1),
+        ('M', Some(2)) => Some(ChargeGrade::M2),
+        ('M', Some(3)) => Some(ChargeGrade::M3),
+        ('S', None) => Some(ChargeGrade::S),
+        ('V', None) => Some(ChargeGrade::V),
+        _ => None,
+    }
+}
+
+/// Lowercase words and numbers, split at letter/digit boundaries ("F1" ->
+/// "f", "1") with punctuation and filler words removed
+fn tokenize(s: &str) -> Vec<String> {
+    let mut spaced = String::with_capacity(s.len() * 2);
+    let mut previous: Option<char> = None;
+    for c in s.chars().flat_map(char::to_lowercase) {
+        if !c.is_alphanumeric() {
+            spaced.push(' ');
+        } else {
+            if previous.is_some_and(|p| p.is_alphabetic() != c.is_alphabetic()) {
+                spaced.push(' ');
+            }
+            spaced.push(c);
+        }
+        previous = c.is_alphanumeric().then_some(c);
+    }
+
+    spaced
+        .split_whitespace()
+        .filter(|token| !FILLER.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+fn grade_class(token: &str) -> Option<char> {
+    match token {
+        "f" | "fel" | "felony" | "felon" | "feloney" | "felny" | "felonie" => Some('F'),
+        "m" | "mis" | "misd" | "misdemeanor" | "misdemeanour" | "misdemeaner" | "misdemenor" | "misdeamenor"
+        | "misdemeaor" => Some('M'),
+        "s" | "sum" | "summ" | "summary" | "sumary" | "summery" => Some('S'),
+        "v" | "viol" | "violation" => Some('V'),
+        _ => None,
+    }
+}
+
+fn parse_degree(token: &str) -> Option<u8> {
+    match token {
+        "1" | "first" | "one" | "i" => Some(1),
+        "2" | "second" | "two" | "ii" => Some(2),
+        "3" | "third" | "three" | "iii" => Some(3),
+        _ => None,
+    }
+}
+
+/// Deserialize an optional grade leniently, so one oddly written grade does
+/// not fail the whole charge
+pub(crate) fn deserialize_lenient<'de, D>(deserializer: D) -> Result<Option<ChargeGrade>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = Option::<String>::deserialize(deserializer)?;
+    Ok(raw.and_then(|raw| {
+        let grade = parse_charge_grade(&raw);
+        if grade.is_none() && !raw.trim().is_empty() {
+            warn!("Unrecognized charge grade: {}", raw);
+        }
+        grade
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Charge;
+
+    #[test]
+    fn test_common_variants_normalize() {
+        for s in ["F1", "F-1", "f 1", "Felony 1", "FELONY-1", "Felony of the First Degree", "Fel. 1st", "feloney 1"] {
+            assert_eq!(parse_charge_grade(s), Some(ChargeGrade::F1), "{}", s);
+        }
+        for s in ["M2", "M-2", "Misd 2", "Misdemeanor 2nd Degree", "misdemeaner II", "M 2"] {
+            assert_eq!(parse_charge_grade(s), Some(ChargeGrade::M2), "{}", s);
+        }
+        assert_eq!(parse_charge_grade("Summary"), Some(ChargeGrade::S));
+    }
+
+    #[test]
+    fn test_garbage_is_rejected() {
+        for s in ["", "   ", "banana", "F4", "F", "Felony", "M 2 3", "S1", "12"] {
+            assert_eq!(parse_charge_grade(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_charge_deserializes_loose_grade() {
+        let charge: Charge = serde_json::from_value(serde_json::json!({
+            "sequence": 1,
+            "id": null,
+            "statute": "18 § 3502",
+            "grade": "Felony 1",
+            "description": "Burglary",
+            "disposition": null,
+            "disposition_date": null,
+            "sentence": null,
+            "plea": null,
+            "verdict": null,
+            "counts": 1
+        }))
+        .unwrap();
+
+        assert_eq!(charge.grade, Some(ChargeGrade::F1));
+    }
+}
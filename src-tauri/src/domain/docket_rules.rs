@@ -0,0 +1,230 @@
+// Docket business rules
+// Field-level rules live on the structs as `Validate` derives; these are the
+// cross-field invariants a docket from a provider should satisfy. Breaking
+// one is reported as a warning rather than rejecting the docket, since the
+// court's record is what it is.
+
+use super::{CaseStatus, Docket};
+use crate::utils::clock::Clock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DocketRule {
+    /// A charge was disposed before the case was filed
+    DispositionBeforeFiling,
+    /// A closed or disposed case still has events scheduled
+    FutureEventOnClosedCase,
+    /// A closed or disposed case has charges with no disposition
+    UndisposedChargeOnClosedCase,
+    /// More was paid on a fine, cost or fee than was assessed
+    OverpaidFinancial,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Inconsistency {
+    pub rule: DocketRule,
+    /// Where in the docket, e.g. "charges[2].disposition_date"
+    pub field: String,
+    pub message: String,
+}
+
+/// Which rules to check; all of them unless switched off
+#[derive(Debug, Clone, Default)]
+pub struct BusinessRules {
+    disabled: HashSet<DocketRule>,
+}
+
+impl BusinessRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn without(mut self, rule: DocketRule) -> Self {
+        self.disabled.insert(rule);
+        self
+    }
+
+    pub fn is_enabled(&self, rule: DocketRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    /// Check `docket` as of `now`, which decides what counts as a future event
+    pub fn check(&self, docket: &Docket, now: DateTime<Utc>) -> Vec<Inconsistency> {
+        let mut found = Vec::new();
+        let mut report = |rule, field: String, message: String| {
+            if self.is_enabled(rule) {
+                found.push(Inconsistency { rule, field, message });
+            }
+        };
+        let is_closed = matches!(docket.status, CaseStatus::Closed | CaseStatus::Disposed);
+
+        for (i, charge) in docket.charges.iter().enumerate() {
+            if let Some(disposed) = charge.disposition_date.filter(|d| d.date_naive() < docket.filed.date_naive()) {
+                report(
+                    DocketRule::DispositionBeforeFiling,
+                    format!("charges[{}].disposition_date", i),
+                    format!(
+                        "Charge {} was disposed {} but the case was filed {}",
+                        charge.statute,
+                        disposed.format("%Y-%m-%d"),
+                        docket.filed.format("%Y-%m-%d")
+                    ),
+                );
+            }
+            if is_closed && charge.disposition.as_deref().unwrap_or("").trim().is_empty() {
+                report(
+                    DocketRule::UndisposedChargeOnClosedCase,
+                    format!("charges[{}].disposition", i),
+                    format!("Case is {:?} but charge {} has no disposition", docket.status, charge.statute),
+                );
+            }
+        }
+
+        if is_closed {
+            for (i, event) in docket.events.iter().enumerate().filter(|(_, e)| e.when > now) {
+                report(
+                    DocketRule::FutureEventOnClosedCase,
+                    format!("events[{}].when", i),
+                    format!(
+                        "Case is {:?} but a {:?} is scheduled for {}",
+                        docket.status,
+                        event.event_type,
+                        event.when.format("%Y-%m-%d")
+                    ),
+                );
+            }
+        }
+
+        for (i, financial) in docket.financials.iter().enumerate() {
+            if let Some(paid) = financial.paid_amount.filter(|paid| *paid > financial.amount + 0.005) {
+                report(
+                    DocketRule::OverpaidFinancial,
+                    format!("financials[{}].paid_amount", i),
+                    format!(
+                        "{:?} of ${:.2} shows ${:.2} paid",
+                        financial.financial_type, financial.amount, paid
+                    ),
+                );
+            }
+        }
+
+        found
+    }
+}
+
+/// Check every business rule against `docket` as of `clock`'s now
+pub fn validate_business_rules(docket: &Docket, clock: &dyn Clock) -> Vec<Inconsistency> {
+    BusinessRules::default().check(docket, clock.now())
+}
+
+/// Log a provider docket's inconsistencies; returns how many were found
+pub fn warn_on_inconsistencies(provider: &str, docket: &Docket, clock: &dyn Clock) -> usize {
+    let found = validate_business_rules(docket, clock);
+    for inconsistency in &found {
+        warn!(
+            "{} docket {} is inconsistent at {}: {}",
+            provider, docket.id, inconsistency.field, inconsistency.message
+        );
+    }
+    found.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Charge, CourtLevel, Event, EventType};
+    use crate::utils::clock::MockClock;
+    use chrono::TimeZone;
+
+    fn docket(status: CaseStatus) -> Docket {
+        Docket {
+            id: "CP-51-CR-0001234-2024".to_string(),
+            caption: "Commonwealth v. Doe".to_string(),
+            status,
+            court: CourtLevel::Cp,
+            county: "Philadelphia".to_string(),
+            filed: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+            docket_number: None,
+            otn: None,
+            sid: None,
+            judge: None,
+            courtroom: None,
+            division: None,
+            parties: vec![],
+            charges: vec![],
+            events: vec![],
+            filings: vec![],
+            financials: vec![],
+            attachments: None,
+            last_updated: None,
+            source_url: None,
+            source: None,
+            fetched_at: None,
+            hash: None,
+        }
+    }
+
+    fn charge(disposition_date: DateTime<Utc>) -> Charge {
+        Charge {
+            sequence: Some(1),
+            id: None,
+            statute: "18 § 3502".to_string(),
+            grade: None,
+            description: "Burglary".to_string(),
+            disposition: Some("Withdrawn".to_string()),
+            disposition_date: Some(disposition_date),
+            sentence: None,
+            plea: None,
+            verdict: None,
+            counts: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_disposition_before_filing_flagged() {
+        let mut docket = docket(CaseStatus::Disposed);
+        docket.charges.push(charge(Utc.with_ymd_and_hms(2024, 2, 14, 0, 0, 0).unwrap()));
+        docket.charges.push(charge(Utc.with_ymd_and_hms(2024, 5, 20, 0, 0, 0).unwrap()));
+
+        let clock = MockClock::new(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap());
+        let found = validate_business_rules(&docket, &clock);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule, DocketRule::DispositionBeforeFiling);
+        assert_eq!(found[0].field, "charges[0].disposition_date");
+
+        let relaxed = BusinessRules::new().without(DocketRule::DispositionBeforeFiling);
+        assert!(relaxed.check(&docket, clock.now()).is_empty());
+    }
+
+    #[test]
+    fn test_closed_case_with_future_hearing_flagged() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let hearing = |when| Event {
+            description: Some("Status hearing".to_string()),
+            time: None,
+            id: None,
+            event_type: EventType::Hearing,
+            when,
+            location: None,
+            courtroom: None,
+            judge: None,
+            notes: None,
+            result: None,
+            next_date: None,
+        };
+        let mut docket = docket(CaseStatus::Closed);
+        docket.events.push(hearing(Utc.with_ymd_and_hms(2024, 4, 2, 0, 0, 0).unwrap()));
+        docket.events.push(hearing(Utc.with_ymd_and_hms(2024, 7, 9, 0, 0, 0).unwrap()));
+
+        let found = BusinessRules::new().check(&docket, now);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule, DocketRule::FutureEventOnClosedCase);
+        assert_eq!(found[0].field, "events[1].when");
+
+        docket.status = CaseStatus::Active;
+        assert!(BusinessRules::new().check(&docket, now).is_empty());
+    }
+}
@@ -2,14 +2,20 @@
 // Production-ready Rust structs with serde serialization
 
 pub mod case_management;
+pub mod charge_grade;
+pub mod docket_rules;
+
+pub use charge_grade::parse_charge_grade;
+pub use docket_rules::{validate_business_rules, BusinessRules, DocketRule, Inconsistency};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use std::collections::HashMap;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum CourtLevel {
     #[serde(rename = "MDJ")]
     Mdj,
@@ -43,7 +49,7 @@ pub enum ChargeGrade {
     S, V,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum CaseStatus {
     Active,
     Closed,
@@ -76,13 +82,14 @@ pub enum FinancialType {
     Bond,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_search_dates"))]
 pub struct SearchParams {
     pub term: Option<String>,
     pub court: Option<CourtLevel>,
     pub county: Option<String>,
-    pub from: Option<String>, // ISO date string
-    pub to: Option<String>,   // ISO date string
+    pub from: Option<String>, // ISO date or "last N days"
+    pub to: Option<String>,   // ISO date or "last N days"
     pub docket: Option<String>,
     pub otn: Option<String>,
     pub sid: Option<String>,
@@ -92,7 +99,23 @@ pub struct SearchParams {
     pub limit: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SearchParams {
+    /// `from`/`to` as timestamps; relative shorthands ("last 30 days")
+    /// resolve against the current time
+    pub fn date_range(&self) -> anyhow::Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>)> {
+        crate::utils::date::parse_date_range(self.from.as_deref(), self.to.as_deref(), Utc::now())
+    }
+}
+
+fn validate_search_dates(params: &SearchParams) -> Result<(), validator::ValidationError> {
+    params.date_range().map(|_| ()).map_err(|e| {
+        let mut error = validator::ValidationError::new("date_range");
+        error.message = Some(e.to_string().into());
+        error
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchResult {
     pub id: String,
     pub caption: String,
@@ -135,6 +158,7 @@ pub struct Charge {
     pub id: Option<Uuid>,
     #[validate(length(min = 1, max = 100))]
     pub statute: String,
+    #[serde(default, deserialize_with = "charge_grade::deserialize_lenient")]
     pub grade: Option<ChargeGrade>,
     #[validate(length(min = 1, max = 500))]
     pub description: String,
@@ -240,6 +264,9 @@ pub struct Docket {
     pub last_updated: Option<DateTime<Utc>>,
     #[validate(url)]
     pub source_url: Option<String>,
+    /// Name of the provider that served the docket, when failover chose it
+    #[serde(default)]
+    pub source: Option<String>,
     pub fetched_at: Option<DateTime<Utc>>,
     pub hash: Option<String>,
 }
@@ -324,6 +351,9 @@ pub enum SubmissionStatus {
     Rejected,
     #[serde(rename = "error")]
     Error,
+    /// Retries exhausted or the error was not retryable
+    #[serde(rename = "failed")]
+    Failed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -342,6 +372,17 @@ pub struct EFilingSubmission {
     pub processed_at: Option<DateTime<Utc>>,
 }
 
+/// Metadata key naming the court or county a submission is filed in
+pub const COURT_ID_METADATA: &str = "court_id";
+
+impl EFilingSubmission {
+    /// The court or county the submission is filed in. A docket number does
+    /// not identify the court, so there is no fallback to `docket_id`.
+    pub fn court_id(&self) -> Option<&str> {
+        self.metadata.get(COURT_ID_METADATA).and_then(|v| v.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ExportType {
     #[serde(rename = "JSON")]
@@ -497,4 +538,8 @@ pub struct CourtRules {
     pub table_of_contents: Option<bool>,
     pub table_of_authorities: Option<bool>,
     pub page_limits: HashMap<String, u32>,
+    #[serde(default)]
+    pub cover_sheet_required: bool,
+    #[serde(default)]
+    pub electronic_service: bool,
 }
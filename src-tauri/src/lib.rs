@@ -1,8 +1,10 @@
 // PA eDocket Desktop - Production-grade court docket management application
 // Copyright (c) 2024 PA eDocket Team
 
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tauri::Manager;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Module declarations
@@ -15,22 +17,27 @@ pub mod commands;
 pub mod api;
 
 // Import command handlers
+use crate::providers::registry::ProviderRegistry;
 use crate::services::commands::*;
+use crate::services::logs::RollingFileWriter;
+use crate::services::bulk_data_ingestion::progress::IngestionJobs;
+use crate::services::activity_monitor::ActivityTracker;
+use crate::services::ai_suggestions::AiSuggestionService;
+use crate::services::permissions::CurrentUser;
+use crate::services::database::open_database;
+use crate::services::draft_jobs::{self, DraftJobRunner};
+use crate::services::efiling_queue::EFilingQueueService;
+use crate::services::settlement_calculator::SettlementCalculatorService;
+use crate::services::shutdown::ShutdownCoordinator;
+use crate::services::time_tracking::TimeTrackingService;
+use crate::services::watchlist::WatchlistService;
+use crate::services::webhooks::WebhookDispatcher;
+use crate::utils::correlation::CorrelationLayer;
+use crate::utils::file_utils::expand_home;
 use crate::commands::{document_commands::*, enterprise_commands::*};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize structured logging
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "pa_edocket_desktop=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer().json())
-        .init();
-
-    info!("Starting PA eDocket Desktop application");
-
     tauri::Builder::default()
         // Core plugins
         .plugin(tauri_plugin_opener::init())
@@ -54,12 +61,16 @@ pub fn run() {
 
             // Document drafting commands
             cmd_draft,
+            cmd_get_draft_status,
 
             // E-filing commands
             cmd_efiling_capabilities,
             cmd_efiling_login,
             cmd_efiling_submit,
+            cmd_efiling_preview,
             cmd_efiling_status,
+            cmd_retry_filing,
+            cmd_list_failed_filings,
 
             // Watchlist commands
             cmd_watch_add,
@@ -83,6 +94,12 @@ pub fn run() {
             cmd_update_config,
             cmd_get_config,
 
+            // Session commands
+            cmd_sign_in,
+            cmd_create_user,
+            cmd_sign_out,
+            cmd_current_session,
+
             // NEW: Document editor commands
             cmd_save_document,
             cmd_export_document,
@@ -119,13 +136,26 @@ pub fn run() {
             cmd_calculate_settlement,
             cmd_generate_demand_letter,
             cmd_analyze_settlement_offer,
+            cmd_record_settlement_offer,
+            cmd_compare_settlement_offers,
+            cmd_get_settlement_calculation,
+            cmd_list_settlement_calculations,
+            cmd_recalculate_settlement,
+            cmd_get_settlement_history,
+            cmd_get_demand_letter,
+            cmd_attach_demand_exhibit,
+            cmd_mark_demand_answered,
 
             // CRITICAL: Bulk Data Ingestion
             cmd_start_bulk_ingestion_courtlistener,
             cmd_start_bulk_ingestion_govinfo,
             cmd_start_bulk_ingestion_harvard,
             cmd_get_ingestion_status,
+            cmd_start_file_ingestion,
+            cmd_resume_ingestion,
+            cmd_cancel_ingestion,
             cmd_search_ingested_cases,
+            cmd_import_bulk_file,
 
             // GAME CHANGER: AI Automation Suite
             cmd_automate_case_lifecycle,
@@ -137,12 +167,25 @@ pub fn run() {
             // Tier 1: Core Revenue Features
             cmd_assemble_document,
             cmd_run_conflict_check,
+            cmd_record_conflict_waiver,
             cmd_start_time_entry,
             cmd_stop_time_entry,
+            cmd_record_activity,
+            cmd_get_suggested_time_entries,
+            cmd_confirm_suggested_time_entry,
+            cmd_dismiss_suggested_time_entry,
+            cmd_seed_default_rates,
             cmd_generate_invoice,
             cmd_process_payment,
+            cmd_create_expense_from_receipt,
+            cmd_trust_deposit,
+            cmd_trust_withdrawal,
+            cmd_post_trust_interest,
             cmd_sync_emails,
             cmd_link_email_to_matter,
+            cmd_archive_email_as_pdf,
+            cmd_create_marketing_campaign,
+            cmd_send_campaign,
             cmd_review_contract,
             cmd_research_legal_issue,
 
@@ -154,6 +197,64 @@ pub fn run() {
             cmd_create_lead,
             cmd_convert_lead_to_client,
 
+            // Matter Closing
+            cmd_close_matter,
+
+            // Client Portal
+            cmd_share_document,
+            cmd_open_shared_document,
+            cmd_revoke_document_share,
+            cmd_post_portal_message,
+            cmd_get_portal_messages,
+
+            // Jury Selection
+            cmd_evaluate_juror,
+
+            // Mediation
+            cmd_record_mediation_position,
+            cmd_mediation_gap,
+
+            // Immigration
+            cmd_immigration_checklist,
+            cmd_immigration_mark_ready,
+            cmd_immigration_deadlines,
+
+            // Real Estate
+            cmd_compute_closing_statement,
+
+            // Estate Planning
+            cmd_generate_will,
+
+            // Workers' Compensation
+            cmd_compute_wc_benefits,
+
+            // Patent & Trademark
+            cmd_ip_deadlines,
+            cmd_ip_refresh_status,
+
+            // Global Search
+            cmd_global_search,
+
+            // Webhooks
+            cmd_add_webhook,
+            cmd_list_webhooks,
+            cmd_remove_webhook,
+            cmd_list_webhook_deliveries,
+
+            // Audit Log
+            cmd_query_audit_log,
+            cmd_verify_audit_log,
+
+            // Document Import
+            cmd_parse_docx,
+
+            // Document Anchoring
+            cmd_anchor_document,
+            cmd_verify_anchor,
+
+            // Local Dataset Import
+            cmd_import_local_dataset,
+
             // Additional Enterprise Features
             cmd_transcribe_audio,
             cmd_run_analytics_report,
@@ -162,49 +263,208 @@ pub fn run() {
 
         // Setup handler for initialization
         .setup(|app| {
+            // Load configuration; logging is set up from it, so the log
+            // files follow the installed config rather than the working directory
+            let config_dir = resolve_config_dir(app.handle());
+            let config = tauri::async_runtime::block_on(config::load_config_from(&config_dir));
+            match &config {
+                Ok(config) => init_logging(&config.global),
+                // Still record why startup failed, in the default log directory
+                Err(_) => init_logging(&config::GlobalConfig::default()),
+            }
+            info!("Starting PA eDocket Desktop application");
+            let config = config.map_err(|e| {
+                error!("Failed to load configuration from {}: {}", config_dir.display(), e);
+                e
+            })?;
+            info!("Configuration loaded from {}", config_dir.display());
+
             info!("Initializing application services");
 
+            // Shared cancellation for background tasks, triggered on exit
+            let coordinator = ShutdownCoordinator::new();
+            // Ingestion jobs stop at their next checkpoint on exit
+            app.manage(IngestionJobs::new(coordinator.child_token()));
+
+            // Nobody is signed in until the UI starts a session
+            app.manage(CurrentUser::default());
+
             // Initialize database
-            if let Err(e) = setup_database(app.handle()) {
+            let db = tauri::async_runtime::block_on(setup_database(&config.global)).map_err(|e| {
                 error!("Failed to initialize database: {}", e);
-                return Err(e.into());
+                e
+            })?;
+            app.manage(db.clone());
+            if let Err(e) = tauri::async_runtime::block_on(sync_billing_rates(&db, &config.global)) {
+                // Entries can still be priced from rates entered by hand
+                warn!("Failed to update firm billing rates: {}", e);
             }
-
-            // Load configuration
-            if let Err(e) = load_configuration(app.handle()) {
-                error!("Failed to load configuration: {}", e);
-                return Err(e.into());
+            // Nothing is rendering yet, so jobs still marked processing were
+            // cut off by the last exit
+            if let Err(e) = tauri::async_runtime::block_on(draft_jobs::requeue_interrupted(&db)) {
+                warn!("Failed to requeue interrupted draft jobs: {}", e);
             }
+            app.manage(AppState::new(db.clone(), &config, config_dir));
+
+            // Activity is held in memory until it becomes a suggested entry
+            app.manage(ActivityTracker::new(config.global.time_detection.clone()));
+
+            // One dispatcher delivers every outbound webhook
+            let webhooks = Arc::new(WebhookDispatcher::new(db.clone()));
+            app.manage(webhooks.clone());
+
+            // E-filings notify through the dispatcher and are priced from
+            // the courts' fee schedules when the provider cannot price them
+            app.manage(Arc::new(
+                EFilingQueueService::new(db.clone())
+                    .with_webhooks(webhooks)
+                    .with_fee_schedule(Arc::new(config.courts.clone())),
+            ));
 
             // Initialize providers
-            if let Err(e) = initialize_providers(app.handle()) {
+            if let Err(e) = initialize_providers(app.handle(), &config) {
                 error!("Failed to initialize providers: {}", e);
                 return Err(e.into());
             }
 
+            spawn_background_tasks(app.handle(), &db, &config, &coordinator);
+
+            app.manage(config);
+            app.manage(coordinator);
+
             info!("Application initialized successfully");
             Ok(())
         })
 
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                handle_exit(app_handle);
+            }
+        });
+}
+
+// Stop timers and background work before the process exits
+fn handle_exit(app_handle: &tauri::AppHandle) {
+    let (Some(coordinator), Some(state)) = (
+        app_handle.try_state::<ShutdownCoordinator>(),
+        app_handle.try_state::<AppState>(),
+    ) else {
+        return;
+    };
+
+    let report = tauri::async_runtime::block_on(coordinator.on_shutdown(&state.db_pool));
+    info!(
+        "Shutdown complete: {} timer(s) paused, database flushed: {}",
+        report.timers_paused, report.database_flushed
+    );
 }
 
 // Setup functions
-fn setup_database(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-    // TODO: Initialize SQLite database with migrations
+
+/// Structured logging to stdout and to log files rotated per `global`'s limits
+fn init_logging(global: &config::GlobalConfig) {
+    let file_layer = match RollingFileWriter::from_config(global) {
+        Ok(writer) => Some(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_ansi(false)
+                .with_writer(Mutex::new(writer)),
+        ),
+        Err(e) => {
+            eprintln!("Failed to open log directory {}: {}", global.log_dir, e);
+            None
+        }
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "pa_edocket_desktop=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().json())
+        .with(file_layer)
+        .with(CorrelationLayer)
+        .init();
+}
+
+/// Config files live in the app config directory once installed; during
+/// development they are read from the repository's config/ directory
+fn resolve_config_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    match app_handle.path().app_config_dir() {
+        Ok(dir) if dir.join("courts.yaml").exists() => dir,
+        _ => PathBuf::from(config::DEFAULT_CONFIG_DIR),
+    }
+}
+
+async fn setup_database(global: &config::GlobalConfig) -> anyhow::Result<sqlx::SqlitePool> {
+    let db = open_database(&expand_home(&global.data_dir)).await?;
     info!("Database setup completed");
-    Ok(())
+    Ok(db)
 }
 
-fn load_configuration(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-    // TODO: Load courts.yaml and providers.yaml
-    info!("Configuration loaded");
+/// Bring the stored firm-wide rates in line with configuration, so a rate
+/// changed in global.yaml applies from this start onwards
+async fn sync_billing_rates(db: &sqlx::SqlitePool, global: &config::GlobalConfig) -> anyhow::Result<()> {
+    let changed = TimeTrackingService::new(db.clone())
+        .sync_firm_rates(&global.billing_rates, chrono::Utc::now())
+        .await?;
+    info!("Updated {} firm billing rate(s) from configuration", changed);
     Ok(())
 }
 
-fn initialize_providers(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
-    // TODO: Initialize provider clients with rate limiting
+fn initialize_providers(app_handle: &tauri::AppHandle, config: &config::AppConfig) -> anyhow::Result<()> {
+    let registry = ProviderRegistry::from_config(&config.courts, &config.providers)?;
+    app_handle.manage(Arc::new(registry));
+    app_handle.manage(AiSuggestionService::from_config(&config.providers));
     info!("Providers initialized");
     Ok(())
 }
+
+/// Start the long-running loops, each stopped by a child of the shutdown token
+fn spawn_background_tasks(
+    app_handle: &tauri::AppHandle,
+    db: &sqlx::SqlitePool,
+    config: &config::AppConfig,
+    coordinator: &ShutdownCoordinator,
+) {
+    let registry = app_handle.state::<Arc<ProviderRegistry>>().inner().clone();
+    let webhooks = app_handle.state::<Arc<WebhookDispatcher>>().inner().clone();
+
+    // Retry failed e-filings for every configured e-filing provider
+    let efiling_queue = app_handle.state::<Arc<EFilingQueueService>>().inner().clone();
+    for (name, provider) in registry.efiling_providers() {
+        tauri::async_runtime::spawn(efiling_queue.clone().run_retry_loop(
+            name.to_string(),
+            provider,
+            coordinator.child_token(),
+        ));
+    }
+
+    // Re-check watched dockets and report changes
+    let watchlist = Arc::new(WatchlistService::new(db.clone()).with_webhooks(webhooks));
+    tauri::async_runtime::spawn(watchlist.run_check_loop(registry, coordinator.child_token()));
+
+    // Render drafts queued before the last exit
+    let drafts_db = db.clone();
+    let data_dir = expand_home(&config.global.data_dir);
+    let config_dir = app_handle.state::<AppState>().config_dir.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = async {
+            DraftJobRunner::open(drafts_db, &data_dir, &config_dir).await?.run_pending().await
+        };
+        match result.await {
+            Ok(0) => {}
+            Ok(rendered) => info!("Rendered {} queued draft job(s)", rendered),
+            Err(e) => error!("Failed to render queued draft jobs: {}", e),
+        }
+    });
+
+    // Flag settlement demands whose response deadline passed
+    let settlements = Arc::new(SettlementCalculatorService::new(db.clone()));
+    tauri::async_runtime::spawn(settlements.run_expiration_loop(
+        config.global.schedule_litigation_on_expired_demand,
+        coordinator.child_token(),
+    ));
+}
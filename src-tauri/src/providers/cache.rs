@@ -0,0 +1,239 @@
+// Search result cache for providers
+// Wraps a SearchProvider with a TTL + LRU cache keyed by normalized search
+// parameters, sized from the provider's CacheConfig
+
+use crate::config::CacheConfig;
+use crate::domain::*;
+use crate::providers::{ProviderError, SearchProvider};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    results: Vec<SearchResult>,
+    inserted: Instant,
+    /// Recency tick; the entry with the smallest tick is evicted first
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    tick: u64,
+}
+
+pub struct CachedSearchProvider<P> {
+    inner: P,
+    ttl: Duration,
+    max_entries: usize,
+    state: Mutex<CacheState>,
+}
+
+impl<P: SearchProvider + Send + Sync> CachedSearchProvider<P> {
+    pub fn new(inner: P, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            inner,
+            ttl,
+            max_entries: max_entries.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn from_config(inner: P, config: &CacheConfig) -> Self {
+        Self::new(inner, Duration::from_secs(config.ttl_seconds), config.max_entries as usize)
+    }
+
+    /// Search through the cache. `force_refresh` skips the lookup and
+    /// replaces any cached results with a fresh fetch.
+    pub async fn search_with(&self, params: &SearchParams, force_refresh: bool) -> Result<Vec<SearchResult>, ProviderError> {
+        let key = cache_key(params);
+
+        if !force_refresh {
+            let mut state = self.state.lock().await;
+            state.tick += 1;
+            let tick = state.tick;
+            match state.entries.get_mut(&key) {
+                Some(entry) if entry.inserted.elapsed() < self.ttl => {
+                    entry.last_used = tick;
+                    debug!("Search cache hit for {}", key);
+                    return Ok(entry.results.clone());
+                }
+                Some(_) => {
+                    debug!("Search cache entry expired for {}", key);
+                    state.entries.remove(&key);
+                }
+                None => debug!("Search cache miss for {}", key),
+            }
+        } else {
+            debug!("Search cache bypassed for {}", key);
+        }
+
+        // Fetch without holding the lock so other searches are not blocked
+        let results = self.inner.search(params).await?;
+
+        let mut state = self.state.lock().await;
+        state.tick += 1;
+        let entry = CacheEntry {
+            results: results.clone(),
+            inserted: Instant::now(),
+            last_used: state.tick,
+        };
+        state.entries.insert(key, entry);
+
+        while state.entries.len() > self.max_entries {
+            let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            debug!("Evicting search cache entry {}", oldest);
+            state.entries.remove(&oldest);
+        }
+
+        Ok(results)
+    }
+
+    pub async fn clear(&self) {
+        self.state.lock().await.entries.clear();
+    }
+
+    pub async fn len(&self) -> usize {
+        self.state.lock().await.entries.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+#[async_trait]
+impl<P: SearchProvider + Send + Sync> SearchProvider for CachedSearchProvider<P> {
+    async fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        self.search_with(params, false).await
+    }
+
+    async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+        self.inner.get_docket(id).await
+    }
+
+    async fn get_attachments(&self, docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+        self.inner.get_attachments(docket_id).await
+    }
+}
+
+/// Searches that differ only in case, surrounding whitespace or an explicit
+/// first page share a cache entry
+fn cache_key(params: &SearchParams) -> String {
+    let text = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|v| v.trim().to_lowercase())
+            .unwrap_or_default()
+    };
+
+    [
+        text(&params.term),
+        params
+            .court
+            .as_ref()
+            .and_then(|court| serde_json::to_string(court).ok())
+            .unwrap_or_default(),
+        text(&params.county),
+        text(&params.from),
+        text(&params.to),
+        text(&params.docket),
+        text(&params.otn),
+        text(&params.sid),
+        params.page.unwrap_or(1).to_string(),
+        params.limit.map(|l| l.to_string()).unwrap_or_default(),
+    ]
+    .join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingProvider {
+        searches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SearchProvider for CountingProvider {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            self.searches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            Err(ProviderError::InvalidResponse(format!("No docket {}", id)))
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    fn params(term: &str) -> SearchParams {
+        SearchParams {
+            term: Some(term.to_string()),
+            court: Some(CourtLevel::Cp),
+            county: Some("Philadelphia".to_string()),
+            from: None,
+            to: None,
+            docket: None,
+            otn: None,
+            sid: None,
+            page: None,
+            limit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeat_search_within_ttl_hits_cache() {
+        let cache = CachedSearchProvider::new(CountingProvider::default(), Duration::from_secs(60), 10);
+
+        cache.search(&params("Smith")).await.unwrap();
+        cache.search(&params("  smith ")).await.unwrap();
+        assert_eq!(cache.inner.searches.load(Ordering::SeqCst), 1);
+
+        cache.search_with(&params("Smith"), true).await.unwrap();
+        assert_eq!(cache.inner.searches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_refetched() {
+        let cache = CachedSearchProvider::new(CountingProvider::default(), Duration::from_millis(20), 10);
+
+        cache.search(&params("Smith")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        cache.search(&params("Smith")).await.unwrap();
+
+        assert_eq!(cache.inner.searches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_entry_is_evicted() {
+        let cache = CachedSearchProvider::new(CountingProvider::default(), Duration::from_secs(60), 2);
+
+        cache.search(&params("a")).await.unwrap();
+        cache.search(&params("b")).await.unwrap();
+        cache.search(&params("a")).await.unwrap();
+        cache.search(&params("c")).await.unwrap();
+        assert_eq!(cache.len().await, 2);
+
+        // "b" was least recently used, so only it needs a refetch
+        cache.search(&params("a")).await.unwrap();
+        assert_eq!(cache.inner.searches.load(Ordering::SeqCst), 3);
+        cache.search(&params("b")).await.unwrap();
+        assert_eq!(cache.inner.searches.load(Ordering::SeqCst), 4);
+    }
+}
@@ -2,6 +2,7 @@
 // Production-ready client for provider integrations
 
 use crate::providers::{ProviderConfig, ProviderError, ProviderResult, RetryConfig};
+use crate::utils::correlation::with_correlation_header;
 use reqwest::{Client, Response};
 use serde::de::DeserializeOwned;
 use std::time::Duration;
@@ -12,27 +13,63 @@ pub struct ProviderClient {
     config: ProviderConfig,
 }
 
+/// Release builds refuse to run with certificate verification disabled
+const PRODUCTION_BUILD: bool = cfg!(not(debug_assertions));
+
+/// Build the shared HTTP client for a provider, applying its TLS,
+/// connection-pool, timeout and default-header settings
+pub fn build_http_client(config: &ProviderConfig) -> ProviderResult<Client> {
+    check_tls_policy(config, PRODUCTION_BUILD)?;
+
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .pool_max_idle_per_host(config.connection_pool.max_idle_connections as usize)
+        .pool_idle_timeout(Duration::from_secs(config.connection_pool.idle_timeout_seconds))
+        .min_tls_version(parse_tls_version(&config.tls.min_tls_version)?);
+
+    // Only an explicit `verify_certificates: false` turns verification off
+    if !config.tls.verify_certificates {
+        warn!("TLS certificate verification disabled for {}", config.name);
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    // Add default headers
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (key, value) in &config.headers {
+        let header_name = reqwest::header::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|e| ProviderError::Configuration(format!("Invalid header name {}: {}", key, e)))?;
+        let header_value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| ProviderError::Configuration(format!("Invalid header value {}: {}", value, e)))?;
+        headers.insert(header_name, header_value);
+    }
+
+    builder.default_headers(headers).build().map_err(ProviderError::Network)
+}
+
+fn check_tls_policy(config: &ProviderConfig, production: bool) -> ProviderResult<()> {
+    if production && !config.tls.verify_certificates {
+        return Err(ProviderError::Configuration(format!(
+            "Certificate verification cannot be disabled for {} in a production build",
+            config.name
+        )));
+    }
+    Ok(())
+}
+
+fn parse_tls_version(version: &str) -> ProviderResult<reqwest::tls::Version> {
+    match version.trim().trim_start_matches("TLS").trim_start_matches("tls").trim() {
+        "1.2" => Ok(reqwest::tls::Version::TLS_1_2),
+        "1.3" => Ok(reqwest::tls::Version::TLS_1_3),
+        other => Err(ProviderError::Configuration(format!(
+            "Unsupported minimum TLS version: {} (expected 1.2 or 1.3)",
+            other
+        ))),
+    }
+}
+
 impl ProviderClient {
     pub fn new(config: ProviderConfig) -> ProviderResult<Self> {
-        let mut builder = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .pool_max_idle_per_host(10)
-            .pool_idle_timeout(Duration::from_secs(300));
-            
-        // Add default headers
-        let mut headers = reqwest::header::HeaderMap::new();
-        for (key, value) in &config.headers {
-            let header_name = reqwest::header::HeaderName::from_bytes(key.as_bytes())
-                .map_err(|e| ProviderError::Configuration(format!("Invalid header name {}: {}", key, e)))?;
-            let header_value = reqwest::header::HeaderValue::from_str(value)
-                .map_err(|e| ProviderError::Configuration(format!("Invalid header value {}: {}", value, e)))?;
-            headers.insert(header_name, header_value);
-        }
-        
-        builder = builder.default_headers(headers);
-        
-        let client = builder.build().map_err(ProviderError::Network)?;
-        
+        let client = build_http_client(&config)?;
         Ok(Self { client, config })
     }
     
@@ -71,6 +108,12 @@ impl ProviderClient {
         self.parse_text_response(response).await
     }
     
+    pub async fn get_bytes(&self, url: &str) -> ProviderResult<Vec<u8>> {
+        let response = self.get(url).await?;
+        let bytes = response.bytes().await.map_err(ProviderError::Network)?;
+        Ok(bytes.to_vec())
+    }
+    
     async fn request_with_retry<F>(&self, request_fn: F) -> ProviderResult<Response>
     where
         F: Fn() -> reqwest::RequestBuilder,
@@ -84,7 +127,7 @@ impl ProviderClient {
             
             debug!("Making request attempt {} for {}", attempt, self.config.name);
             
-            let request = request_fn().build().map_err(ProviderError::Network)?;
+            let request = with_correlation_header(request_fn()).build().map_err(ProviderError::Network)?;
             let url = request.url().clone();
             
             match self.client.execute(request).await {
@@ -197,8 +240,11 @@ pub fn build_query_string(params: &std::collections::HashMap<String, String>) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{ConnectionPoolConfig, TlsConfig};
     use crate::providers::RateLimitConfig;
     use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio_rustls::rustls::{self, pki_types::PrivatePkcs8KeyDer};
     
     fn create_test_config() -> ProviderConfig {
         ProviderConfig {
@@ -218,6 +264,8 @@ mod tests {
             },
             headers: HashMap::new(),
             timeout_seconds: 30,
+            tls: TlsConfig::default(),
+            connection_pool: ConnectionPoolConfig::default(),
         }
     }
     
@@ -238,4 +286,47 @@ mod tests {
         assert!(query.contains("key1=value1"));
         assert!(query.contains("key2=value%20with%20spaces"));
     }
+
+    #[tokio::test]
+    async fn test_verifying_client_rejects_self_signed_server() {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der());
+        let server_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![certified.cert.der().clone()], key.into())
+        .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server_config));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let _ = acceptor.accept(stream).await;
+            }
+        });
+
+        let client = ProviderClient::new(create_test_config()).unwrap();
+        let err = client
+            .client
+            .get(format!("https://localhost:{}/", port))
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(err.is_connect());
+        assert!(format!("{:?}", err).to_lowercase().contains("certificate"));
+    }
+
+    #[test]
+    fn test_disabled_verification_rejected_in_production() {
+        let mut config = create_test_config();
+        config.tls.verify_certificates = false;
+
+        assert!(check_tls_policy(&config, false).is_ok());
+        assert!(matches!(check_tls_policy(&config, true), Err(ProviderError::Configuration(_))));
+    }
 }
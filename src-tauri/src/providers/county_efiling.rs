@@ -95,6 +95,27 @@ struct CountySubmissionRequest {
     metadata: HashMap<String, serde_json::Value>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct CountyFeeRequest {
+    case_number: Option<String>,
+    document_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_count: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CountyFeeResponse {
+    fees: Vec<CountyFee>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CountyFee {
+    description: String,
+    amount: f64,
+    #[serde(default)]
+    waived: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct CountyDocument {
     filename: String,
@@ -139,6 +160,7 @@ impl CountyEFilingProvider {
                 let mut endpoints = HashMap::new();
                 endpoints.insert("capabilities".to_string(), "/api/capabilities".to_string());
                 endpoints.insert("submit".to_string(), "/api/filing/submit".to_string());
+                endpoints.insert("fees".to_string(), "/api/filing/fees".to_string());
                 endpoints.insert("status".to_string(), "/api/filing/{id}/status".to_string());
                 endpoints.insert("refresh".to_string(), "/api/auth/refresh".to_string());
                 endpoints
@@ -333,10 +355,7 @@ impl EFilingProvider for CountyEFilingProvider {
     async fn submit_filing(&self, submission: &EFilingSubmission) -> Result<String, ProviderError> {
         info!("Submitting filing to county system: {}", submission.id);
 
-        // Extract court ID from submission metadata or docket ID
-        let court_id = submission.metadata.get("court_id")
-            .and_then(|v| v.as_str())
-            .or_else(|| submission.docket_id.as_deref())
+        let court_id = submission.court_id()
             .ok_or_else(|| ProviderError::Configuration("Court ID required for county filing".to_string()))?;
 
         let county_config = self.get_county_from_court_id(court_id)
@@ -490,4 +509,35 @@ impl EFilingProvider for CountyEFilingProvider {
             }
         }
     }
+
+    /// Counties that price filings report `fee_calculation`; the others
+    /// leave the fee to the court's fee schedule
+    #[instrument(skip(self, submission))]
+    async fn calculate_fees(&self, submission: &EFilingSubmission) -> Result<Option<f64>, ProviderError> {
+        info!("Calculating county filing fees for: {}", submission.id);
+
+        let court_id = submission.court_id()
+            .ok_or_else(|| ProviderError::Configuration("Court ID required for county filing".to_string()))?;
+
+        let county_config = self.get_county_from_court_id(court_id)
+            .ok_or_else(|| ProviderError::Configuration(format!("Unknown court: {}", court_id)))?;
+        if !county_config.capabilities.fee_calculation {
+            return Ok(None);
+        }
+
+        let fees_endpoint = county_config.endpoints.get("fees")
+            .ok_or_else(|| ProviderError::Configuration("Fees endpoint not configured".to_string()))?;
+
+        let fee_request = CountyFeeRequest {
+            case_number: submission.docket_id.clone(),
+            document_type: submission.document_type.clone(),
+            page_count: submission.metadata.get("page_count").and_then(|v| v.as_u64()),
+        };
+
+        let url = format!("{}{}", county_config.base_url, fees_endpoint);
+        let response: CountyFeeResponse = self.client.post_json(&url, &fee_request).await?;
+
+        let total: f64 = response.fees.iter().filter(|fee| !fee.waived).map(|fee| fee.amount).sum();
+        Ok(Some((total * 100.0).round() / 100.0))
+    }
 }
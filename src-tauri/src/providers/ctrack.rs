@@ -3,6 +3,7 @@
 
 use crate::domain::*;
 use crate::providers::{client::ProviderClient, ProviderConfig, ProviderError, ProviderResult, SearchProvider};
+use crate::utils::clock::SystemClock;
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
@@ -252,6 +253,7 @@ impl CTrackProvider {
             attachments: None,
             last_updated: Some(chrono::Utc::now()),
             source_url: Some(format!("ctrack://{}", case.case_id)),
+            source: None,
             fetched_at: Some(chrono::Utc::now()),
             hash: None,
         }
@@ -329,6 +331,7 @@ impl SearchProvider for CTrackProvider {
         match self.client.get_json::<CTrackCase>(&url).await {
             Ok(case) => {
                 let docket = self.map_ctrack_case_to_docket(&case);
+                docket_rules::warn_on_inconsistencies("C-Track", &docket, &SystemClock);
                 Ok(docket)
             },
             Err(e) => {
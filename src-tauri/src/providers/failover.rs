@@ -0,0 +1,227 @@
+// Provider failover
+// Wraps an ordered list of search providers, statewide UJS first and county
+// systems after it, and moves on to the next when one is down. Only outages
+// fail over: a provider that answered with something unparseable or refused
+// the credentials would do the same for the next request, so its error is
+// returned as is. A provider that keeps failing has its breaker opened and is
+// skipped until the cooldown passes.
+
+use crate::config::ErrorHandlingConfig;
+use crate::domain::*;
+use crate::providers::registry::SharedSearchProvider;
+use crate::providers::{ProviderError, SearchProvider};
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+pub struct FailoverSearchProvider {
+    /// (name, provider), in the order they are tried
+    sources: Vec<(String, SharedSearchProvider)>,
+    breaker_threshold: u32,
+    breaker_cooldown: Duration,
+    breakers: Mutex<HashMap<String, Breaker>>,
+}
+
+impl FailoverSearchProvider {
+    pub fn new(sources: Vec<(String, SharedSearchProvider)>, error_handling: &ErrorHandlingConfig) -> Self {
+        Self {
+            sources,
+            breaker_threshold: error_handling.circuit_breaker_threshold.max(1),
+            breaker_cooldown: Duration::from_secs(error_handling.circuit_breaker_timeout_seconds),
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `call` against each source in turn until one answers or fails
+    /// with an error that is not an outage. Returns the answering source's name.
+    async fn with_failover<'a, T>(
+        &'a self,
+        operation: &str,
+        call: impl Fn(&'a SharedSearchProvider) -> BoxFuture<'a, Result<T, ProviderError>>,
+    ) -> Result<(&'a str, T), ProviderError> {
+        let mut last_error = None;
+
+        for (name, provider) in &self.sources {
+            if self.breaker_open(name).await {
+                debug!("Skipping {} for {}: breaker open", name, operation);
+                continue;
+            }
+
+            match call(provider).await {
+                Ok(value) => {
+                    self.record_success(name).await;
+                    return Ok((name.as_str(), value));
+                }
+                Err(e) if fails_over(&e) => {
+                    warn!("{} failed for {}, trying the next source: {}", name, operation, e);
+                    self.record_failure(name).await;
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ServiceUnavailable(format!("No docket source available for {}", operation))
+        }))
+    }
+
+    async fn breaker_open(&self, name: &str) -> bool {
+        let breakers = self.breakers.lock().await;
+        // Once the cooldown passes the next call is let through as a trial
+        breakers
+            .get(name)
+            .and_then(|b| b.opened_at)
+            .is_some_and(|opened_at| opened_at.elapsed() < self.breaker_cooldown)
+    }
+
+    async fn record_success(&self, name: &str) {
+        self.breakers.lock().await.remove(name);
+    }
+
+    async fn record_failure(&self, name: &str) {
+        let mut breakers = self.breakers.lock().await;
+        let breaker = breakers.entry(name.to_string()).or_default();
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures >= self.breaker_threshold {
+            warn!("Opening breaker for {} after {} failures", name, breaker.consecutive_failures);
+            breaker.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Outages that another source may not share
+fn fails_over(error: &ProviderError) -> bool {
+    matches!(error, ProviderError::ServiceUnavailable(_) | ProviderError::Network(_))
+}
+
+#[async_trait]
+impl SearchProvider for FailoverSearchProvider {
+    async fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        let (_, results) = self.with_failover("search", |provider| provider.search(params)).await?;
+        Ok(results)
+    }
+
+    async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+        let (source, mut docket) = self.with_failover("get_docket", |provider| provider.get_docket(id)).await?;
+        docket.source = Some(source.to_string());
+        Ok(docket)
+    }
+
+    async fn get_attachments(&self, docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+        let (_, attachments) = self
+            .with_failover("get_attachments", |provider| provider.get_attachments(docket_id))
+            .await?;
+        Ok(attachments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct StubSource {
+        /// None answers with a docket
+        error: Option<fn() -> ProviderError>,
+        calls: AtomicUsize,
+    }
+
+    impl StubSource {
+        fn new(error: Option<fn() -> ProviderError>) -> Arc<Self> {
+            Arc::new(Self { error, calls: AtomicUsize::new(0) })
+        }
+    }
+
+    #[async_trait]
+    impl SearchProvider for StubSource {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+            Ok(Docket {
+                id: id.to_string(),
+                caption: "Commonwealth v. Doe".to_string(),
+                status: CaseStatus::Active,
+                court: CourtLevel::Cp,
+                county: "Allegheny".to_string(),
+                filed: chrono::Utc::now(),
+                docket_number: Some(id.to_string()),
+                otn: None,
+                sid: None,
+                judge: None,
+                courtroom: None,
+                division: None,
+                parties: vec![],
+                charges: vec![],
+                events: vec![],
+                filings: vec![],
+                financials: vec![],
+                attachments: None,
+                last_updated: None,
+                source_url: None,
+                source: None,
+                fetched_at: None,
+                hash: None,
+            })
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    fn failover(primary: Arc<StubSource>, secondary: Arc<StubSource>, threshold: u32) -> FailoverSearchProvider {
+        FailoverSearchProvider::new(
+            vec![("ujs_portal".to_string(), primary), ("allegheny".to_string(), secondary)],
+            &ErrorHandlingConfig {
+                max_retries: 0,
+                circuit_breaker_threshold: threshold,
+                circuit_breaker_timeout_seconds: 60,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_primary_outage_falls_through_to_secondary() {
+        let primary = StubSource::new(Some(|| ProviderError::ServiceUnavailable("HTTP 503".to_string())));
+        let secondary = StubSource::new(None);
+        let provider = failover(primary.clone(), secondary.clone(), 2);
+
+        let docket = provider.get_docket("CP-02-CR-0001234-2024").await.unwrap();
+        assert_eq!(docket.source.as_deref(), Some("allegheny"));
+
+        // The second outage opens the primary's breaker, so the third call
+        // goes straight to the secondary
+        provider.get_docket("CP-02-CR-0001234-2024").await.unwrap();
+        provider.get_docket("CP-02-CR-0001234-2024").await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_does_not_fail_over() {
+        let primary = StubSource::new(Some(|| ProviderError::Parsing("unexpected docket sheet layout".to_string())));
+        let secondary = StubSource::new(None);
+        let provider = failover(primary, secondary.clone(), 2);
+
+        let err = provider.get_docket("CP-02-CR-0001234-2024").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Parsing(_)));
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+    }
+}
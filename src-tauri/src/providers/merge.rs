@@ -0,0 +1,250 @@
+// Docket merging across providers
+// A case fetched from both UJS and a county system is combined into one
+// docket without duplicating the parties, charges and filings both report
+
+use crate::domain::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Combine two views of the same case. `primary` wins wherever both have a
+/// value; gaps are filled from `secondary`, and rows present in both are
+/// merged field by field.
+pub fn merge_dockets(primary: Docket, secondary: Docket) -> Docket {
+    Docket {
+        id: primary.id,
+        caption: prefer_non_empty(primary.caption, secondary.caption),
+        status: primary.status,
+        court: primary.court,
+        county: prefer_non_empty(primary.county, secondary.county),
+        filed: primary.filed,
+        docket_number: primary.docket_number.or(secondary.docket_number),
+        otn: primary.otn.or(secondary.otn),
+        sid: primary.sid.or(secondary.sid),
+        judge: primary.judge.or(secondary.judge),
+        courtroom: primary.courtroom.or(secondary.courtroom),
+        division: primary.division.or(secondary.division),
+
+        parties: union_by(primary.parties, secondary.parties, party_key, merge_party),
+        charges: union_by(primary.charges, secondary.charges, charge_key, merge_charge),
+        events: union_by(primary.events, secondary.events, event_key, merge_event),
+        filings: union_by(primary.filings, secondary.filings, filing_key, merge_filing),
+        financials: if primary.financials.is_empty() { secondary.financials } else { primary.financials },
+        attachments: match (primary.attachments, secondary.attachments) {
+            (Some(a), Some(b)) => Some(union_by(a, b, |a| a.url.clone(), |_, _| {})),
+            (a, b) => a.or(b),
+        },
+
+        last_updated: primary.last_updated.max(secondary.last_updated),
+        source_url: primary.source_url.or(secondary.source_url),
+        source: primary.source.or(secondary.source),
+        fetched_at: primary.fetched_at.max(secondary.fetched_at),
+        // The merged docket matches neither source's content hash
+        hash: None,
+    }
+}
+
+/// Keep `primary`'s order, append rows only `secondary` has, and merge rows
+/// that share a key (including duplicates within one source)
+fn union_by<T, K, F, M>(primary: Vec<T>, secondary: Vec<T>, key: F, merge: M) -> Vec<T>
+where
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+    M: Fn(&mut T, T),
+{
+    let mut merged: Vec<T> = Vec::with_capacity(primary.len() + secondary.len());
+    let mut index: HashMap<K, usize> = HashMap::new();
+
+    for item in primary.into_iter().chain(secondary) {
+        match index.get(&key(&item)) {
+            Some(&i) => merge(&mut merged[i], item),
+            None => {
+                index.insert(key(&item), merged.len());
+                merged.push(item);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Lowercase, drop punctuation and collapse whitespace, so "SMITH, JOHN A."
+/// and "Smith, John A" compare equal
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn fill<T>(target: &mut Option<T>, other: Option<T>) {
+    if target.is_none() {
+        *target = other;
+    }
+}
+
+fn prefer_non_empty(primary: String, secondary: String) -> String {
+    if primary.trim().is_empty() { secondary } else { primary }
+}
+
+fn party_key(party: &Party) -> (String, String) {
+    (normalize(&party.name), format!("{:?}", party.role))
+}
+
+fn merge_party(into: &mut Party, other: Party) {
+    fill(&mut into.id, other.id);
+    fill(&mut into.address, other.address);
+    fill(&mut into.city, other.city);
+    fill(&mut into.state, other.state);
+    fill(&mut into.zip_code, other.zip_code);
+    fill(&mut into.phone, other.phone);
+    fill(&mut into.email, other.email);
+    fill(&mut into.attorney, other.attorney);
+    fill(&mut into.attorney_id, other.attorney_id);
+    fill(&mut into.attorney_phone, other.attorney_phone);
+    fill(&mut into.attorney_email, other.attorney_email);
+    fill(&mut into.date_added, other.date_added);
+}
+
+fn charge_key(charge: &Charge) -> (String, Option<u32>) {
+    (normalize(&charge.statute), charge.sequence)
+}
+
+fn merge_charge(into: &mut Charge, other: Charge) {
+    fill(&mut into.id, other.id);
+    fill(&mut into.grade, other.grade);
+    if into.description.trim().is_empty() {
+        into.description = other.description;
+    }
+    fill(&mut into.disposition, other.disposition);
+    fill(&mut into.disposition_date, other.disposition_date);
+    fill(&mut into.sentence, other.sentence);
+    fill(&mut into.plea, other.plea);
+    fill(&mut into.verdict, other.verdict);
+    fill(&mut into.counts, other.counts);
+}
+
+fn event_key(event: &Event) -> (i64, String, String) {
+    (
+        event.when.timestamp(),
+        format!("{:?}", event.event_type),
+        normalize(event.description.as_deref().unwrap_or_default()),
+    )
+}
+
+fn merge_event(into: &mut Event, other: Event) {
+    fill(&mut into.time, other.time);
+    fill(&mut into.id, other.id);
+    fill(&mut into.location, other.location);
+    fill(&mut into.courtroom, other.courtroom);
+    fill(&mut into.judge, other.judge);
+    fill(&mut into.notes, other.notes);
+    fill(&mut into.result, other.result);
+    fill(&mut into.next_date, other.next_date);
+}
+
+fn filing_key(filing: &Filing) -> (chrono::NaiveDate, String, Option<String>) {
+    (filing.date.date_naive(), normalize(&filing.title), filing.hash.clone())
+}
+
+fn merge_filing(into: &mut Filing, other: Filing) {
+    fill(&mut into.document_url, other.document_url);
+    fill(&mut into.status, other.status);
+    fill(&mut into.id, other.id);
+    fill(&mut into.by, other.by);
+    fill(&mut into.doc_url, other.doc_url);
+    fill(&mut into.doc_type, other.doc_type);
+    fill(&mut into.pages, other.pages);
+    fill(&mut into.size, other.size);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn party(name: &str, role: PartyRole) -> Party {
+        Party {
+            id: None,
+            name: name.to_string(),
+            role,
+            address: None,
+            city: None,
+            state: None,
+            zip_code: None,
+            phone: None,
+            email: None,
+            attorney: None,
+            attorney_id: None,
+            attorney_phone: None,
+            attorney_email: None,
+            date_added: None,
+        }
+    }
+
+    fn docket(parties: Vec<Party>) -> Docket {
+        Docket {
+            id: "CP-51-CR-0001234-2024".to_string(),
+            caption: "Commonwealth v. Smith".to_string(),
+            status: CaseStatus::Active,
+            court: CourtLevel::Cp,
+            county: "Philadelphia".to_string(),
+            filed: chrono::Utc::now(),
+            docket_number: Some("CP-51-CR-0001234-2024".to_string()),
+            otn: None,
+            sid: None,
+            judge: None,
+            courtroom: None,
+            division: None,
+            parties,
+            charges: vec![],
+            events: vec![],
+            filings: vec![],
+            financials: vec![],
+            attachments: None,
+            last_updated: None,
+            source_url: None,
+            source: None,
+            fetched_at: None,
+            hash: None,
+        }
+    }
+
+    #[test]
+    fn test_overlapping_parties_are_deduplicated() {
+        let mut ujs_defendant = party("SMITH, JOHN A.", PartyRole::Defendant);
+        ujs_defendant.attorney = Some("Jane Doe".to_string());
+        let mut county_defendant = party("Smith,  John A", PartyRole::Defendant);
+        county_defendant.address = Some("12 Elm St".to_string());
+        county_defendant.attorney = Some("J. Doe".to_string());
+
+        let primary = docket(vec![party("Commonwealth of Pennsylvania", PartyRole::Plaintiff), ujs_defendant]);
+        let mut secondary = docket(vec![
+            county_defendant,
+            party("Commonwealth of Pennsylvania", PartyRole::Plaintiff),
+            party("Mary Smith", PartyRole::Intervenor),
+        ]);
+        secondary.judge = Some("Hon. A. Jones".to_string());
+
+        let merged = merge_dockets(primary, secondary);
+
+        let names: Vec<&str> = merged.parties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Commonwealth of Pennsylvania", "SMITH, JOHN A.", "Mary Smith"]);
+
+        let defendant = &merged.parties[1];
+        assert_eq!(defendant.attorney.as_deref(), Some("Jane Doe"));
+        assert_eq!(defendant.address.as_deref(), Some("12 Elm St"));
+        assert_eq!(merged.judge.as_deref(), Some("Hon. A. Jones"));
+    }
+
+    #[test]
+    fn test_same_name_with_different_role_is_kept() {
+        let merged = merge_dockets(
+            docket(vec![party("John Smith", PartyRole::Defendant)]),
+            docket(vec![party("John Smith", PartyRole::Appellant)]),
+        );
+
+        assert_eq!(merged.parties.len(), 2);
+    }
+}
@@ -6,7 +6,11 @@ pub mod pacfile;
 pub mod county_efiling;
 pub mod ctrack;
 pub mod rate_limiter;
+pub mod cache;
+pub mod merge;
+pub mod failover;
 pub mod client;
+pub mod registry;
 pub mod courtlistener;
 pub mod govinfo;
 
@@ -14,7 +18,10 @@ pub mod govinfo;
 use crate::domain::*;
 use anyhow::Result;
 use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 pub trait SearchProvider {
@@ -23,13 +30,40 @@ pub trait SearchProvider {
     async fn get_attachments(&self, docket_id: &str) -> Result<Vec<Attachment>, ProviderError>;
 }
 
+/// Lets wrappers such as the search cache hold a shared provider
 #[async_trait]
-pub trait EFilingProvider {
+impl<T: SearchProvider + Send + Sync + ?Sized> SearchProvider for Arc<T> {
+    async fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        (**self).search(params).await
+    }
+
+    async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+        (**self).get_docket(id).await
+    }
+
+    async fn get_attachments(&self, docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+        (**self).get_attachments(docket_id).await
+    }
+}
+
+#[async_trait]
+pub trait EFilingProvider: Send + Sync {
     async fn get_capabilities(&self, court_id: &str) -> Result<Vec<EFilingCapability>, ProviderError>;
     async fn authenticate(&self, credentials: HashMap<String, String>) -> Result<EFilingSession, ProviderError>;
     async fn submit_filing(&self, submission: &EFilingSubmission) -> Result<String, ProviderError>;
     async fn get_status(&self, submission_id: &str) -> Result<EFilingSubmission, ProviderError>;
     async fn refresh_token(&self, session: &EFilingSession) -> Result<EFilingSession, ProviderError>;
+
+    /// Court acceptance receipt for an accepted filing, if the provider issues one
+    async fn download_receipt(&self, _submission_id: &str) -> Result<Option<Vec<u8>>, ProviderError> {
+        Ok(None)
+    }
+
+    /// Fees the court will charge for a submission, from providers whose
+    /// capability reports `fee_calculation`
+    async fn calculate_fees(&self, _submission: &EFilingSubmission) -> Result<Option<f64>, ProviderError> {
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +75,8 @@ pub struct ProviderConfig {
     pub retry: RetryConfig,
     pub headers: HashMap<String, String>,
     pub timeout_seconds: u64,
+    pub tls: crate::config::TlsConfig,
+    pub connection_pool: crate::config::ConnectionPoolConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -83,4 +119,160 @@ pub enum ProviderError {
     Parsing(String),
 }
 
+impl ProviderError {
+    /// Whether retrying the same request later may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::Network(_) | ProviderError::RateLimited | ProviderError::ServiceUnavailable(_)
+        )
+    }
+}
+
+impl ProviderConfig {
+    /// Build a provider client config from the `providers.yaml` entry and
+    /// the file's global HTTP settings
+    pub fn from_app_config(
+        config: &crate::config::ProviderConfig,
+        global: &crate::config::GlobalProviderConfig,
+    ) -> Self {
+        Self {
+            name: config.name.clone(),
+            enabled: config.enabled,
+            base_url: config.base_url.clone(),
+            rate_limit: RateLimitConfig {
+                requests_per_minute: config.rate_limit.requests_per_minute,
+                requests_per_hour: config.rate_limit.requests_per_hour,
+                burst_limit: config.rate_limit.burst_limit,
+            },
+            retry: RetryConfig {
+                max_attempts: config.retry.max_attempts,
+                backoff_multiplier: config.retry.backoff_multiplier,
+                initial_delay_ms: config.retry.initial_delay_ms,
+                max_delay_ms: config.retry.max_delay_ms,
+            },
+            headers: config.headers.clone(),
+            timeout_seconds: global.timeout_seconds,
+            tls: global.tls.clone(),
+            connection_pool: global.connection_pool.clone(),
+        }
+    }
+}
+
 pub type ProviderResult<T> = Result<T, ProviderError>;
+
+/// Retries for a docket fetch rejected by the provider's rate limiter
+const RATE_LIMIT_RETRIES: u32 = 3;
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Fetch many dockets with at most `concurrency` requests in flight. Results
+/// are returned in the order of `ids`, so one failed docket does not hide the
+/// others. Fetches rejected as rate-limited back off and retry.
+pub async fn fetch_dockets(
+    ids: &[String],
+    provider: &dyn SearchProvider,
+    concurrency: usize,
+) -> Vec<Result<Docket, ProviderError>> {
+    let mut results: Vec<(usize, Result<Docket, ProviderError>)> = stream::iter(ids.iter().enumerate())
+        .map(|(index, id)| async move {
+            let mut attempt = 0;
+            loop {
+                match provider.get_docket(id).await {
+                    Err(ProviderError::RateLimited) if attempt < RATE_LIMIT_RETRIES => {
+                        attempt += 1;
+                        tokio::time::sleep(RATE_LIMIT_BACKOFF * attempt).await;
+                    }
+                    result => return (index, result),
+                }
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingProvider {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SearchProvider for CountingProvider {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if id.starts_with("missing") {
+                return Err(ProviderError::InvalidResponse(format!("No docket {}", id)));
+            }
+            Ok(docket(id))
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    fn docket(id: &str) -> Docket {
+        Docket {
+            id: id.to_string(),
+            caption: format!("Commonwealth v. {}", id),
+            status: CaseStatus::Active,
+            court: CourtLevel::Cp,
+            county: "Philadelphia".to_string(),
+            filed: chrono::Utc::now(),
+            docket_number: Some(id.to_string()),
+            otn: None,
+            sid: None,
+            judge: None,
+            courtroom: None,
+            division: None,
+            parties: vec![],
+            charges: vec![],
+            events: vec![],
+            filings: vec![],
+            financials: vec![],
+            attachments: None,
+            last_updated: None,
+            source_url: None,
+            source: None,
+            fetched_at: None,
+            hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_dockets_bounded_with_per_id_errors() {
+        let provider = CountingProvider::default();
+        let ids: Vec<String> = (0..10)
+            .map(|i| if i % 4 == 1 { format!("missing-{}", i) } else { format!("CP-{}", i) })
+            .collect();
+
+        let results = fetch_dockets(&ids, &provider, 3).await;
+
+        assert_eq!(results.len(), 10);
+        assert!(provider.max_in_flight.load(Ordering::SeqCst) <= 3);
+        for (id, result) in ids.iter().zip(&results) {
+            match result {
+                Ok(docket) => assert_eq!(&docket.id, id),
+                Err(_) => assert!(id.starts_with("missing")),
+            }
+        }
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 3);
+    }
+}
@@ -81,6 +81,20 @@ struct PacFileFee {
     waived: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct PacFileFeeRequest {
+    court_id: String,
+    case_id: Option<String>,
+    document_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_count: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PacFileFeeResponse {
+    fees: Vec<PacFileFee>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PacFileStatusResponse {
     submission_id: String,
@@ -165,21 +179,6 @@ impl PacFileProvider {
         
         Ok(documents)
     }
-    
-    #[instrument(skip(self, submission_id))]
-    async fn download_receipt(&self, submission_id: &str, receipt_url: &str) -> ProviderResult<String> {
-        info!("Downloading receipt for submission: {}", submission_id);
-        
-        let receipt_content = self.client.get_text(receipt_url).await?;
-        
-        // Save receipt to temporary file
-        let receipt_path = format!("/tmp/pacfile_receipt_{}.pdf", submission_id);
-        tokio::fs::write(&receipt_path, receipt_content).await.map_err(|e| {
-            ProviderError::Configuration(format!("Failed to save receipt: {}", e))
-        })?;
-        
-        Ok(receipt_path)
-    }
 }
 
 #[async_trait]
@@ -260,7 +259,7 @@ impl EFilingProvider for PacFileProvider {
         
         // Prepare submission request
         let filing_request = PacFileSubmissionRequest {
-            court_id: submission.docket_id.clone().unwrap_or_default(),
+            court_id: required_court_id(submission)?,
             case_id: submission.docket_id.clone(),
             document_type: submission.document_type.clone(),
             filing_party: submission
@@ -305,12 +304,6 @@ impl EFilingProvider for PacFileProvider {
             _ => SubmissionStatus::Pending,
         };
         
-        let receipt_path = if let Some(receipt_url) = &response.receipt_url {
-            Some(self.download_receipt(submission_id, receipt_url).await?)
-        } else {
-            None
-        };
-        
         let submission = EFilingSubmission {
             id: Uuid::parse_str(submission_id).unwrap_or_else(|_| Uuid::new_v4()),
             session_id: Uuid::new_v4(), // TODO: Get from context
@@ -320,7 +313,7 @@ impl EFilingProvider for PacFileProvider {
             metadata: HashMap::new(),
             status,
             submission_id: Some(response.submission_id),
-            receipt_path,
+            receipt_path: None,
             error_message: if response.messages.is_empty() {
                 None
             } else {
@@ -354,4 +347,43 @@ impl EFilingProvider for PacFileProvider {
         info!("Token refresh successful for session: {}", session.id);
         Ok(new_session)
     }
+    
+    #[instrument(skip(self, submission_id))]
+    async fn download_receipt(&self, submission_id: &str) -> Result<Option<Vec<u8>>, ProviderError> {
+        info!("Downloading receipt for submission: {}", submission_id);
+        
+        let url = format!("{}/api/filing/{}/status", self.config.base_url, submission_id);
+        let response: PacFileStatusResponse = self.client.get_json(&url).await?;
+        
+        match response.receipt_url {
+            Some(receipt_url) => Ok(Some(self.client.get_bytes(&receipt_url).await?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// PACFile prices a filing before submission; waived fees are not charged
+    #[instrument(skip(self, submission))]
+    async fn calculate_fees(&self, submission: &EFilingSubmission) -> Result<Option<f64>, ProviderError> {
+        info!("Calculating PACFile fees for: {}", submission.id);
+        
+        let fee_request = PacFileFeeRequest {
+            court_id: required_court_id(submission)?,
+            case_id: submission.docket_id.clone(),
+            document_type: submission.document_type.clone(),
+            page_count: submission.metadata.get("page_count").and_then(|v| v.as_u64()),
+        };
+        
+        let url = format!("{}/api/filing/fees", self.config.base_url);
+        let response: PacFileFeeResponse = self.client.post_json(&url, &fee_request).await?;
+        
+        let total: f64 = response.fees.iter().filter(|fee| !fee.waived).map(|fee| fee.amount).sum();
+        Ok(Some((total * 100.0).round() / 100.0))
+    }
+}
+
+fn required_court_id(submission: &EFilingSubmission) -> ProviderResult<String> {
+    submission
+        .court_id()
+        .map(str::to_string)
+        .ok_or_else(|| ProviderError::Configuration("Court ID required for PACFile filing".to_string()))
 }
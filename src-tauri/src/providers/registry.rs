@@ -0,0 +1,560 @@
+// Provider registry
+// Maps court and county ids from courts.yaml to the provider instances built
+// from providers.yaml, so commands can pick the backend for a given docket.
+// Each provider is built by a named factory, so another county running a
+// supported system is enabled by adding its entry to providers.yaml.
+
+use crate::config::{CourtsConfig, EFilingConfig, ErrorHandlingConfig, ProvidersConfig};
+use crate::providers::cache::CachedSearchProvider;
+use crate::providers::county_efiling::CountyEFilingProvider;
+use crate::providers::ctrack::CTrackProvider;
+use crate::providers::failover::FailoverSearchProvider;
+use crate::providers::pacfile::PacFileProvider;
+use crate::providers::ujs_portal::UjsPortalProvider;
+use crate::providers::{EFilingProvider, ProviderConfig, ProviderError, SearchProvider};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+pub type SharedSearchProvider = Arc<dyn SearchProvider + Send + Sync>;
+pub type SharedEFilingProvider = Arc<dyn EFilingProvider>;
+
+pub type SearchProviderFactory =
+    Arc<dyn Fn(ProviderConfig) -> Result<SharedSearchProvider, ProviderError> + Send + Sync>;
+pub type EFilingProviderFactory =
+    Arc<dyn Fn(ProviderConfig) -> Result<SharedEFilingProvider, ProviderError> + Send + Sync>;
+
+#[derive(Clone)]
+pub enum ProviderFactory {
+    Search(SearchProviderFactory),
+    EFiling(EFilingProviderFactory),
+}
+
+/// Factory name -> constructor for the provider implementations compiled in
+#[derive(Clone, Default)]
+pub struct ProviderFactories {
+    factories: BTreeMap<String, ProviderFactory>,
+}
+
+impl ProviderFactories {
+    pub fn builtin() -> Self {
+        let mut factories = Self::default();
+        factories.register_search("ujs_portal", |config| Ok(Arc::new(UjsPortalProvider::new(config)?)));
+        factories.register_search("ctrack", |config| Ok(Arc::new(CTrackProvider::new(config)?)));
+        factories.register_efiling("pacfile", |config| Ok(Arc::new(PacFileProvider::new(config)?)));
+        factories.register_efiling("county_efiling", |config| Ok(Arc::new(CountyEFilingProvider::new(config)?)));
+        factories
+    }
+
+    pub fn register_search(
+        &mut self,
+        name: &str,
+        factory: impl Fn(ProviderConfig) -> Result<SharedSearchProvider, ProviderError> + Send + Sync + 'static,
+    ) {
+        self.factories.insert(name.to_string(), ProviderFactory::Search(Arc::new(factory)));
+    }
+
+    pub fn register_efiling(
+        &mut self,
+        name: &str,
+        factory: impl Fn(ProviderConfig) -> Result<SharedEFilingProvider, ProviderError> + Send + Sync + 'static,
+    ) {
+        self.factories.insert(name.to_string(), ProviderFactory::EFiling(Arc::new(factory)));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ProviderFactory> {
+        self.factories.get(name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.factories.keys().map(String::as_str).collect()
+    }
+}
+
+/// Docket search for every PA court and county tries the UJS portal first
+/// and the other search providers, by name, when it is down
+const STATEWIDE_SEARCH_PROVIDER: &str = "ujs_portal";
+
+/// providers.yaml entries built by their own services rather than a factory
+const SERVICE_PROVIDERS: &[&str] = &["courtlistener", "govinfo"];
+
+#[derive(Default)]
+pub struct ProviderRegistry {
+    search_providers: HashMap<String, SharedSearchProvider>,
+    /// Every search provider behind one failover, in the order they are tried
+    statewide_search: Option<SharedSearchProvider>,
+    search_order: Vec<String>,
+    /// Breaker settings for `statewide_search`
+    error_handling: ErrorHandlingConfig,
+    efiling_providers: HashMap<String, SharedEFilingProvider>,
+    /// Known court and county ids, lowercased
+    court_ids: HashSet<String>,
+    /// Court or county id -> name of its configured e-filing provider
+    efiling_routes: HashMap<String, String>,
+    /// Court or county id -> e-filing provider it names that was not built
+    unroutable: BTreeMap<String, String>,
+}
+
+impl ProviderRegistry {
+    /// Build every enabled provider and route the configured courts and counties to them
+    pub fn from_config(courts: &CourtsConfig, providers: &ProvidersConfig) -> Result<Self, ProviderError> {
+        Self::from_config_with(courts, providers, &ProviderFactories::builtin())
+    }
+
+    /// As `from_config`, building providers with `factories`. Each search
+    /// provider is put behind a cache sized by its `cache` settings. Fails when a
+    /// provider has no registered factory and is not built by its own
+    /// service, or when a court e-files through a provider providers.yaml
+    /// does not name. A court that e-files through a disabled provider is
+    /// logged and left without an e-filing route.
+    pub fn from_config_with(
+        courts: &CourtsConfig,
+        providers: &ProvidersConfig,
+        factories: &ProviderFactories,
+    ) -> Result<Self, ProviderError> {
+        let mut registry = Self { error_handling: providers.global.error_handling.clone(), ..Self::default() };
+
+        for (name, app_config) in providers.providers.iter().filter(|(_, c)| c.enabled) {
+            let factory_name = app_config.factory.as_deref().unwrap_or(name);
+            let config = ProviderConfig::from_app_config(app_config, &providers.global);
+            match factories.get(factory_name) {
+                Some(ProviderFactory::Search(factory)) => {
+                    let provider = CachedSearchProvider::from_config(factory(config)?, &app_config.cache);
+                    registry.register_search_provider(name, Arc::new(provider))
+                }
+                Some(ProviderFactory::EFiling(factory)) => registry.register_efiling_provider(name, factory(config)?),
+                // Research APIs are configured here but built by their own services
+                None if app_config.factory.is_none() && SERVICE_PROVIDERS.contains(&name.as_str()) => {
+                    debug!("{} is built by its own service, skipping", name)
+                }
+                None => {
+                    return Err(ProviderError::Configuration(format!(
+                        "Provider '{}' uses unknown factory '{}'; registered factories: {}",
+                        name,
+                        factory_name,
+                        factories.names().join(", ")
+                    )));
+                }
+            }
+        }
+
+        registry.add_routes(courts);
+        registry.drop_unbuilt_routes(providers)?;
+        info!(
+            "Provider registry ready: {} search, {} e-filing provider(s), {} court(s)",
+            registry.search_providers.len(),
+            registry.efiling_providers.len(),
+            registry.court_ids.len()
+        );
+        Ok(registry)
+    }
+
+    /// Courts that e-file through a disabled provider keep search but lose
+    /// their e-filing route, so turning a provider off doesn't stop startup.
+    /// A provider name providers.yaml doesn't know is a typo and an error.
+    fn drop_unbuilt_routes(&mut self, providers: &ProvidersConfig) -> Result<(), ProviderError> {
+        let mut unknown: Vec<String> = self
+            .efiling_routes
+            .iter()
+            .filter(|(_, provider)| !providers.providers.contains_key(provider.as_str()))
+            .map(|(court, provider)| format!("{} -> {}", court, provider))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort();
+            let mut configured: Vec<&str> = providers.providers.keys().map(String::as_str).collect();
+            configured.sort();
+            return Err(ProviderError::Configuration(format!(
+                "E-filing is routed through providers that are not in providers.yaml: {}; configured providers: {}",
+                unknown.join(", "),
+                configured.join(", ")
+            )));
+        }
+
+        let built = &self.efiling_providers;
+        let (routed, unbuilt): (HashMap<_, _>, HashMap<_, _>) = self
+            .efiling_routes
+            .drain()
+            .partition(|(_, provider)| built.contains_key(provider.as_str()));
+        self.efiling_routes = routed;
+        self.unroutable.extend(unbuilt);
+        if !self.unroutable.is_empty() {
+            let disabled: Vec<String> = self
+                .unroutable
+                .iter()
+                .map(|(court, provider)| format!("{} -> {}", court, provider))
+                .collect();
+            warn!(
+                "E-filing is enabled through providers that are disabled, turning it off for: {}",
+                disabled.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Courts whose configured e-filing provider was not built, with that provider's name
+    pub fn unroutable_courts(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.unroutable.iter().map(|(court, provider)| (court.as_str(), provider.as_str()))
+    }
+
+    pub fn register_search_provider(&mut self, name: &str, provider: SharedSearchProvider) {
+        self.search_providers.insert(name.to_string(), provider);
+        self.build_statewide_search();
+    }
+
+    /// Put the search providers behind one failover: UJS first, then the
+    /// rest by name. Rebuilt as providers are registered at startup.
+    fn build_statewide_search(&mut self) {
+        let mut order: Vec<String> = self.search_providers.keys().cloned().collect();
+        order.sort_by_key(|name| (name != STATEWIDE_SEARCH_PROVIDER, name.clone()));
+        let sources = order
+            .iter()
+            .map(|name| (name.clone(), self.search_providers[name].clone()))
+            .collect();
+        self.statewide_search = Some(Arc::new(FailoverSearchProvider::new(sources, &self.error_handling)));
+        self.search_order = order;
+    }
+
+    pub fn register_efiling_provider(&mut self, name: &str, provider: SharedEFilingProvider) {
+        self.efiling_providers.insert(name.to_string(), provider);
+    }
+
+    /// Route statewide courts and counties; each uses its own e-filing
+    /// provider when e-filing is enabled for it
+    pub fn add_routes(&mut self, courts: &CourtsConfig) {
+        for (id, court) in &courts.courts {
+            self.add_route(id, court.efiling.as_ref());
+        }
+        for (id, county) in &courts.counties {
+            self.add_route(id, county.efiling.as_ref());
+        }
+    }
+
+    fn add_route(&mut self, id: &str, efiling: Option<&EFilingConfig>) {
+        let id = id.trim().to_lowercase();
+        if let Some(provider) = efiling.filter(|e| e.enabled).and_then(|e| e.provider.clone()) {
+            self.efiling_routes.insert(id.clone(), provider);
+        }
+        self.court_ids.insert(id);
+    }
+
+    /// The search provider tried first for `court_id`
+    pub fn search_provider_name(&self, court_id: &str) -> Option<&str> {
+        if !self.court_ids.contains(&court_id.trim().to_lowercase()) {
+            return None;
+        }
+        self.search_order.first().map(String::as_str)
+    }
+
+    /// Search for `court_id`, failing over between the search providers
+    pub fn search_provider_for(&self, court_id: &str) -> Option<SharedSearchProvider> {
+        self.search_provider_name(court_id)
+            .and(self.statewide_search.clone())
+    }
+
+    pub fn efiling_provider_name(&self, court_id: &str) -> Option<&str> {
+        self.efiling_routes
+            .get(&court_id.trim().to_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn efiling_provider_for(&self, court_id: &str) -> Option<SharedEFilingProvider> {
+        self.efiling_provider_name(court_id)
+            .and_then(|name| self.efiling_providers.get(name).cloned())
+    }
+
+    /// The e-filing provider built under `name` in providers.yaml
+    pub fn efiling_provider(&self, name: &str) -> Option<SharedEFilingProvider> {
+        self.efiling_providers.get(name).cloned()
+    }
+
+    /// Every built e-filing provider with the name it was configured under
+    pub fn efiling_providers(&self) -> impl Iterator<Item = (&str, SharedEFilingProvider)> + '_ {
+        self.efiling_providers.iter().map(|(name, provider)| (name.as_str(), provider.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StubSearch;
+
+    /// Counts the searches that reach it
+    #[derive(Default)]
+    struct CountingSearch {
+        searches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SearchProvider for CountingSearch {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            self.searches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            Err(ProviderError::InvalidResponse(format!("No docket {}", id)))
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    #[async_trait]
+    impl SearchProvider for StubSearch {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            Err(ProviderError::InvalidResponse(format!("No docket {}", id)))
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    /// A search source that is down
+    struct DownSearch;
+
+    #[async_trait]
+    impl SearchProvider for DownSearch {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            Err(ProviderError::ServiceUnavailable("HTTP 503".to_string()))
+        }
+
+        async fn get_docket(&self, _id: &str) -> Result<Docket, ProviderError> {
+            Err(ProviderError::ServiceUnavailable("HTTP 503".to_string()))
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Err(ProviderError::ServiceUnavailable("HTTP 503".to_string()))
+        }
+    }
+
+    struct StubEFiling;
+
+    #[async_trait]
+    impl EFilingProvider for StubEFiling {
+        async fn get_capabilities(&self, _court_id: &str) -> Result<Vec<EFilingCapability>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn authenticate(&self, _credentials: HashMap<String, String>) -> Result<EFilingSession, ProviderError> {
+            Err(ProviderError::AuthenticationFailed("stub".to_string()))
+        }
+
+        async fn submit_filing(&self, _submission: &EFilingSubmission) -> Result<String, ProviderError> {
+            Ok("stub".to_string())
+        }
+
+        async fn get_status(&self, submission_id: &str) -> Result<EFilingSubmission, ProviderError> {
+            Err(ProviderError::InvalidResponse(format!("No submission {}", submission_id)))
+        }
+
+        async fn refresh_token(&self, _session: &EFilingSession) -> Result<EFilingSession, ProviderError> {
+            Err(ProviderError::AuthenticationFailed("stub".to_string()))
+        }
+    }
+
+    const COURTS_YAML: &str = r#"
+courts:
+  mdj:
+    name: "Magisterial District Courts"
+    level: "MDJ"
+    jurisdiction: "Pennsylvania"
+    formatting:
+      margins: { top: "1.0in", bottom: "1.0in", left: "1.0in", right: "1.0in" }
+      font: { family: "Times New Roman", size: "12pt", line_spacing: "double" }
+      caption:
+        format: "standard_pa"
+        include_docket: true
+        include_court: true
+        include_county: true
+        include_judge: false
+      signature:
+        attorney_name: true
+        attorney_id: true
+        firm_name: true
+        address: true
+        phone: true
+        email: true
+      service_certificate: true
+      page_limits: {}
+    efiling: { enabled: false, provider: null, endpoint: null }
+counties:
+  philadelphia:
+    name: "Philadelphia County"
+    cp_court_id: "51"
+    efiling: { enabled: true, provider: "county_efiling", endpoint: "https://fjdefile.phila.gov" }
+    local_rules: { cover_sheet_required: true, electronic_service: true }
+templates: {}
+"#;
+
+    #[test]
+    fn test_mdj_resolves_to_ujs_and_county_to_its_efiling_provider() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+        let mut registry = ProviderRegistry::default();
+        registry.register_search_provider("ujs_portal", Arc::new(StubSearch));
+        registry.register_efiling_provider("county_efiling", Arc::new(StubEFiling));
+        registry.add_routes(&courts);
+
+        assert_eq!(registry.search_provider_name("MDJ"), Some("ujs_portal"));
+        assert!(registry.search_provider_for("mdj").is_some());
+        assert!(registry.efiling_provider_for("mdj").is_none());
+
+        assert_eq!(registry.efiling_provider_name("philadelphia"), Some("county_efiling"));
+        assert!(registry.efiling_provider_for("philadelphia").is_some());
+        assert_eq!(registry.search_provider_name("philadelphia"), Some("ujs_portal"));
+
+        assert!(registry.search_provider_for("no-such-court").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_fails_over_from_ujs_to_county_source() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+        let mut registry = ProviderRegistry::default();
+        registry.register_search_provider("ctrack", Arc::new(StubSearch));
+        registry.register_search_provider("ujs_portal", Arc::new(DownSearch));
+        registry.add_routes(&courts);
+
+        assert_eq!(registry.search_provider_name("mdj"), Some("ujs_portal"));
+        let provider = registry.search_provider_for("mdj").unwrap();
+        assert_eq!(provider.get_attachments("MJ-05201-CR-0000123-2024").await.unwrap().len(), 0);
+    }
+
+    fn provider_yaml(name: &str, factory: Option<&str>) -> String {
+        format!(
+            r#"
+{name}:
+  name: "{name}"
+  enabled: true
+  factory: {factory}
+  base_url: "https://efiling.example.gov"
+  rate_limit: {{ requests_per_minute: 10, requests_per_hour: 100, burst_limit: 2 }}
+  retry: {{ max_attempts: 1, backoff_multiplier: 2.0, initial_delay_ms: 10, max_delay_ms: 100 }}
+  endpoints: {{}}
+  headers: {{}}
+  cache: {{ ttl_seconds: 60, max_entries: 10 }}
+"#,
+            factory = factory.map(|f| format!("\"{}\"", f)).unwrap_or_else(|| "null".to_string())
+        )
+    }
+
+    fn providers(entries: &[(&str, Option<&str>)]) -> ProvidersConfig {
+        let yaml: String = entries.iter().map(|(name, factory)| provider_yaml(name, *factory)).collect();
+        ProvidersConfig {
+            providers: serde_yaml::from_str(&yaml).unwrap(),
+            global: crate::config::GlobalProviderConfig::default(),
+        }
+    }
+
+    fn stub_factories() -> ProviderFactories {
+        let mut factories = ProviderFactories::default();
+        factories.register_search("ujs_portal", |_| Ok(Arc::new(StubSearch)));
+        factories.register_efiling("county_efiling", |_| Ok(Arc::new(StubEFiling)));
+        factories
+    }
+
+    #[test]
+    fn test_unknown_provider_factory_fails_startup() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+        let config = providers(&[("county_efiling", None), ("bucks_efiling", Some("odyssey"))]);
+
+        let err = ProviderRegistry::from_config_with(&courts, &config, &stub_factories())
+            .err()
+            .expect("unknown factory must fail");
+
+        let message = err.to_string();
+        assert!(matches!(err, ProviderError::Configuration(_)));
+        assert!(message.contains("'bucks_efiling' uses unknown factory 'odyssey'"), "{}", message);
+        assert!(message.contains("county_efiling, ujs_portal"), "{}", message);
+
+        // An entry with neither a factory nor its own service is a typo, not a research API
+        let err = ProviderRegistry::from_config_with(&courts, &providers(&[("county_efilng", None)]), &stub_factories())
+            .err()
+            .expect("unknown provider must fail");
+        assert!(err.to_string().contains("'county_efilng' uses unknown factory 'county_efilng'"), "{}", err);
+    }
+
+    #[test]
+    fn test_court_routed_to_disabled_provider_is_left_unroutable() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+
+        // Philadelphia e-files through county_efiling, which is turned off
+        let mut config = providers(&[("ujs_portal", None), ("county_efiling", None)]);
+        config.providers.get_mut("county_efiling").unwrap().enabled = false;
+        let registry = ProviderRegistry::from_config_with(&courts, &config, &stub_factories()).unwrap();
+
+        assert!(registry.efiling_provider_name("philadelphia").is_none());
+        assert!(registry.efiling_provider_for("philadelphia").is_none());
+        assert_eq!(registry.search_provider_name("philadelphia"), Some("ujs_portal"));
+        assert_eq!(registry.unroutable_courts().collect::<Vec<_>>(), vec![("philadelphia", "county_efiling")]);
+    }
+
+    #[test]
+    fn test_court_routed_to_unknown_provider_fails_startup() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+
+        // Philadelphia names county_efiling, which providers.yaml doesn't have
+        let err = ProviderRegistry::from_config_with(&courts, &providers(&[("ujs_portal", None)]), &stub_factories())
+            .err()
+            .expect("unknown e-filing provider must fail");
+
+        assert!(matches!(err, ProviderError::Configuration(_)));
+        assert!(err.to_string().contains("philadelphia -> county_efiling"), "{}", err);
+    }
+
+    #[test]
+    fn test_known_factory_builds_provider_from_config() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+        let config = providers(&[
+            ("county_efiling", None),
+            ("montgomery_efiling", Some("county_efiling")),
+            ("courtlistener", None),
+        ]);
+
+        let registry = ProviderRegistry::from_config_with(&courts, &config, &stub_factories()).unwrap();
+
+        assert!(registry.efiling_providers.contains_key("montgomery_efiling"));
+        assert!(registry.efiling_provider_for("philadelphia").is_some());
+        // Entries without a factory are left to their own services
+        assert!(!registry.search_providers.contains_key("courtlistener"));
+        assert!(ProviderFactories::builtin().get("county_efiling").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_search_is_served_from_the_provider_cache() {
+        let courts: CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+        let counting = Arc::new(CountingSearch::default());
+        let mut factories = stub_factories();
+        let source = counting.clone();
+        factories.register_search("ujs_portal", move |_| Ok(source.clone()));
+
+        let config = providers(&[("ujs_portal", None), ("county_efiling", None)]);
+        let registry = ProviderRegistry::from_config_with(&courts, &config, &factories).unwrap();
+        let provider = registry.search_provider_for("mdj").unwrap();
+
+        let params = SearchParams {
+            term: Some("Smith".to_string()),
+            court: Some(CourtLevel::Mdj),
+            county: None,
+            from: None,
+            to: None,
+            docket: None,
+            otn: None,
+            sid: None,
+            page: None,
+            limit: None,
+        };
+        provider.search(&params).await.unwrap();
+        provider.search(&params).await.unwrap();
+
+        assert_eq!(counting.searches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -2,6 +2,9 @@
 // Production-ready integration with Pennsylvania Unified Judicial System
 
 use crate::domain::*;
+use crate::providers::client::build_http_client;
+use crate::utils::clock::SystemClock;
+use crate::utils::date::format_date_api;
 use crate::providers::{ProviderConfig, ProviderError, ProviderResult, SearchProvider};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -24,11 +27,7 @@ impl UjsPortalProvider {
         let base_url = Url::parse(&config.base_url)
             .map_err(|e| ProviderError::Configuration(format!("Invalid base URL: {}", e)))?;
             
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-            .user_agent("PA-eDocket-Desktop/1.0")
-            .build()
-            .map_err(ProviderError::Network)?;
+        let client = build_http_client(&config)?;
             
         Ok(Self {
             client,
@@ -214,6 +213,7 @@ impl UjsPortalProvider {
             attachments: None,
             last_updated: Some(Utc::now()),
             source_url: Some(format!("{}?docketNumber={}", self.base_url, docket_id)),
+            source: None,
             fetched_at: Some(Utc::now()),
             hash: None,
         };
@@ -323,13 +323,14 @@ impl SearchProvider for UjsPortalProvider {
             query_params.insert("court".to_string(), court_code.to_string());
         }
         
-        // Add date range if provided
-        if let Some(from) = &params.from {
-            query_params.insert("dateFrom".to_string(), from.clone());
+        // Add date range if provided, with relative shorthands resolved
+        let (from, to) = params.date_range().map_err(|e| ProviderError::Parsing(e.to_string()))?;
+        if let Some(from) = from {
+            query_params.insert("dateFrom".to_string(), format_date_api(&from));
         }
         
-        if let Some(to) = &params.to {
-            query_params.insert("dateTo".to_string(), to.clone());
+        if let Some(to) = to {
+            query_params.insert("dateTo".to_string(), format_date_api(&to));
         }
         
         let html = self.make_request("/Report/CpSearch", &query_params).await?;
@@ -347,6 +348,7 @@ impl SearchProvider for UjsPortalProvider {
         
         let html = self.make_request("/Report/CpDocketSheet", &params).await?;
         let docket = self.parse_docket_detail(&html, id)?;
+        docket_rules::warn_on_inconsistencies("UJS Portal", &docket, &SystemClock);
         
         Ok(docket)
     }
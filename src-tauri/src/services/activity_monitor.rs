@@ -0,0 +1,453 @@
+// Activity Monitor - automatic time capture from editor and research activity
+// Activity is grouped into blocks per matter, split wherever the attorney
+// was idle longer than the configured timeout. Blocks become suggested time
+// entries; nothing is billed until the attorney confirms one.
+
+use crate::services::time_tracking::{ActivityType, AutomaticTimeDetection};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ActivityKind {
+    DocumentEditing,
+    Research,
+    Email,
+}
+
+impl ActivityKind {
+    fn activity_type(&self) -> ActivityType {
+        match self {
+            ActivityKind::DocumentEditing => ActivityType::Drafting,
+            ActivityKind::Research => ActivityType::Research,
+            ActivityKind::Email => ActivityType::Email,
+        }
+    }
+}
+
+/// One observed action: a save or keystroke burst in a document, a search
+/// run, an email read or sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub at: DateTime<Utc>,
+    pub kind: ActivityKind,
+    pub matter_id: String,
+    /// Document name, search terms or email subject
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedTimeEntry {
+    pub id: String,
+    pub attorney_id: String,
+    pub matter_id: String,
+    /// The kind of work most of the block's activity was
+    pub activity_type: ActivityType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub duration_minutes: i64,
+    pub description: String,
+    pub event_count: usize,
+}
+
+/// How captured blocks are cleaned up before they are suggested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapturePolicy {
+    /// Blocks shorter than this are dropped as noise
+    pub min_duration_minutes: i64,
+    /// Blocks on the same matter this close together are merged, without
+    /// the idle time between them
+    pub merge_within_minutes: i64,
+    /// Durations are rounded up to a multiple of this (6 for tenths of an hour)
+    pub billing_increment_minutes: i64,
+}
+
+impl CapturePolicy {
+    /// The policy the detection settings in global.yaml ask for
+    pub fn from_settings(settings: &AutomaticTimeDetection) -> Self {
+        Self {
+            min_duration_minutes: settings.min_activity_duration_minutes,
+            merge_within_minutes: settings.merge_within_minutes,
+            billing_increment_minutes: settings.billing_increment_minutes,
+        }
+    }
+
+    fn round_up(&self, minutes: i64) -> i64 {
+        let increment = self.billing_increment_minutes.max(1);
+        (minutes + increment - 1) / increment * increment
+    }
+}
+
+/// Collects one attorney's activity until it is turned into suggestions
+pub struct ActivityMonitor {
+    attorney_id: String,
+    settings: AutomaticTimeDetection,
+    policy: CapturePolicy,
+    events: Vec<ActivityEvent>,
+}
+
+impl ActivityMonitor {
+    pub fn new(attorney_id: &str, settings: AutomaticTimeDetection) -> Self {
+        Self {
+            attorney_id: attorney_id.to_string(),
+            policy: CapturePolicy::from_settings(&settings),
+            settings,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CapturePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record an event; returns false when detection of its kind is off
+    pub fn record(&mut self, event: ActivityEvent) -> bool {
+        let detected = self.settings.enabled
+            && match event.kind {
+                ActivityKind::DocumentEditing => self.settings.detect_document_editing,
+                ActivityKind::Research => self.settings.detect_research_activity,
+                ActivityKind::Email => self.settings.detect_email_activity,
+            };
+        if detected {
+            self.events.push(event);
+        }
+        detected
+    }
+
+    /// Suggested entries for the activity recorded so far, in time order,
+    /// after the capture policy has merged, dropped and rounded blocks
+    pub fn suggestions(&self) -> Vec<SuggestedTimeEntry> {
+        let mut by_matter: HashMap<&str, Vec<&ActivityEvent>> = HashMap::new();
+        for event in &self.events {
+            by_matter.entry(event.matter_id.as_str()).or_default().push(event);
+        }
+
+        let mut suggestions: Vec<SuggestedTimeEntry> = by_matter
+            .into_values()
+            .flat_map(|mut events| {
+                events.sort_by_key(|e| e.at);
+                self.split_on_idle(&events)
+            })
+            .filter(|block| block.duration_minutes >= self.policy.min_duration_minutes)
+            .map(|mut block| {
+                block.duration_minutes = self.policy.round_up(block.duration_minutes);
+                block
+            })
+            .collect();
+        suggestions.sort_by_key(|s| s.start_time);
+        suggestions
+    }
+
+    /// Hand over the suggestions and start collecting afresh
+    pub fn take_suggestions(&mut self) -> Vec<SuggestedTimeEntry> {
+        let suggestions = self.suggestions();
+        self.events.clear();
+        suggestions
+    }
+
+    /// Hand over suggestions for the matters the attorney has gone idle on
+    /// as of `now`, leaving blocks still in progress to keep growing
+    pub fn take_idle_suggestions(&mut self, now: DateTime<Utc>) -> Vec<SuggestedTimeEntry> {
+        let idle_after = self.settings.idle_timeout_minutes.max(self.policy.merge_within_minutes);
+        let mut last_seen: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for event in &self.events {
+            let last = last_seen.entry(event.matter_id.clone()).or_insert(event.at);
+            *last = (*last).max(event.at);
+        }
+
+        let (idle, active): (Vec<_>, Vec<_>) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|e| (now - last_seen[&e.matter_id]).num_minutes() > idle_after);
+        self.events = idle;
+        let suggestions = self.suggestions();
+        self.events = active;
+        suggestions
+    }
+
+    /// Split a matter's events wherever the attorney was idle. Pieces close
+    /// enough for the policy to merge become one block, but only the time
+    /// worked in each piece is counted, not the idle time between them.
+    fn split_on_idle(&self, events: &[&ActivityEvent]) -> Vec<SuggestedTimeEntry> {
+        let idle_after = self.settings.idle_timeout_minutes;
+        let merge_within = idle_after.max(self.policy.merge_within_minutes);
+        // (events, minutes worked) per block
+        let mut blocks: Vec<(&[&ActivityEvent], i64)> = Vec::new();
+        let (mut block_start, mut piece_start, mut worked) = (0, 0, 0);
+        for i in 1..events.len() {
+            let gap = (events[i].at - events[i - 1].at).num_minutes();
+            if gap > idle_after {
+                worked += (events[i - 1].at - events[piece_start].at).num_minutes();
+                piece_start = i;
+                if gap > merge_within {
+                    blocks.push((&events[block_start..i], worked));
+                    block_start = i;
+                    worked = 0;
+                }
+            }
+        }
+        if let Some(last) = events.last() {
+            worked += (last.at - events[piece_start].at).num_minutes();
+            blocks.push((&events[block_start..], worked));
+        }
+        blocks.into_iter().map(|(block, worked)| self.suggest(block, worked)).collect()
+    }
+
+    fn suggest(&self, block: &[&ActivityEvent], worked_minutes: i64) -> SuggestedTimeEntry {
+        let first = block[0];
+        let last = block[block.len() - 1];
+
+        let mut counts: HashMap<ActivityKind, usize> = HashMap::new();
+        for event in block {
+            *counts.entry(event.kind).or_default() += 1;
+        }
+        let main_kind = counts
+            .into_iter()
+            .max_by_key(|(kind, count)| (*count, *kind == first.kind))
+            .map(|(kind, _)| kind)
+            .unwrap_or(first.kind);
+
+        let mut subjects: Vec<&str> = Vec::new();
+        for event in block.iter().filter(|e| !e.subject.trim().is_empty()) {
+            if !subjects.contains(&event.subject.as_str()) {
+                subjects.push(&event.subject);
+            }
+        }
+
+        let activity_type = main_kind.activity_type();
+        SuggestedTimeEntry {
+            id: Uuid::new_v4().to_string(),
+            attorney_id: self.attorney_id.clone(),
+            matter_id: first.matter_id.clone(),
+            description: format!("{:?}: {}", activity_type, subjects.join(", ")),
+            activity_type,
+            start_time: first.at,
+            end_time: last.at,
+            duration_minutes: worked_minutes,
+            event_count: block.len(),
+        }
+    }
+}
+
+/// Every attorney's monitor, and the suggestions waiting for them to
+/// confirm or dismiss; managed as app state and fed by the commands
+pub struct ActivityTracker {
+    settings: AutomaticTimeDetection,
+    monitors: Mutex<HashMap<String, ActivityMonitor>>,
+    pending: Mutex<HashMap<String, SuggestedTimeEntry>>,
+}
+
+impl ActivityTracker {
+    pub fn new(settings: AutomaticTimeDetection) -> Self {
+        Self {
+            settings,
+            monitors: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record an attorney's activity on a matter; returns false when
+    /// detection of its kind is off
+    pub fn record(&self, attorney_id: &str, event: ActivityEvent) -> bool {
+        let mut monitors = self.monitors.lock().unwrap_or_else(|e| e.into_inner());
+        monitors
+            .entry(attorney_id.to_string())
+            .or_insert_with(|| ActivityMonitor::new(attorney_id, self.settings.clone()))
+            .record(event)
+    }
+
+    /// The attorney's unconfirmed suggestions as of `now`, in time order
+    pub fn suggestions(&self, attorney_id: &str, now: DateTime<Utc>) -> Vec<SuggestedTimeEntry> {
+        let finished = self
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(attorney_id)
+            .map(|monitor| monitor.take_idle_suggestions(now))
+            .unwrap_or_default();
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.extend(finished.into_iter().map(|s| (s.id.clone(), s)));
+        let mut suggestions: Vec<SuggestedTimeEntry> =
+            pending.values().filter(|s| s.attorney_id == attorney_id).cloned().collect();
+        suggestions.sort_by_key(|s| s.start_time);
+        suggestions
+    }
+
+    /// Remove one of the attorney's suggestions, to confirm or dismiss it
+    pub fn take(&self, attorney_id: &str, suggestion_id: &str) -> Option<SuggestedTimeEntry> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.get(suggestion_id).is_some_and(|s| s.attorney_id == attorney_id) {
+            pending.remove(suggestion_id)
+        } else {
+            None
+        }
+    }
+
+    /// Put back a suggestion that could not be confirmed
+    pub fn restore(&self, suggestion: SuggestedTimeEntry) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.insert(suggestion.id.clone(), suggestion);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn monitor() -> ActivityMonitor {
+        ActivityMonitor::new(
+            "atty-1",
+            AutomaticTimeDetection {
+                enabled: true,
+                detect_document_editing: true,
+                detect_email_activity: false,
+                detect_research_activity: true,
+                min_activity_duration_minutes: 6,
+                idle_timeout_minutes: 15,
+                merge_within_minutes: 0,
+                billing_increment_minutes: 1,
+            },
+        )
+    }
+
+    /// An event every five minutes from `from` to `to` minutes past nine
+    fn record_span(monitor: &mut ActivityMonitor, kind: ActivityKind, from: i64, to: i64) {
+        let nine = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        for minute in (from..=to).step_by(5) {
+            monitor.record(ActivityEvent {
+                at: nine + Duration::minutes(minute),
+                kind,
+                matter_id: "matter-1".to_string(),
+                subject: "Motion to Compel.docx".to_string(),
+            });
+        }
+    }
+
+    #[test]
+    fn test_idle_gap_splits_into_two_blocks() {
+        let mut monitor = monitor();
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 0, 40);
+        record_span(&mut monitor, ActivityKind::Research, 70, 100);
+
+        let suggestions = monitor.suggestions();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].activity_type, ActivityType::Drafting);
+        assert_eq!(suggestions[0].duration_minutes, 40);
+        assert_eq!(suggestions[1].activity_type, ActivityType::Research);
+        assert_eq!(suggestions[1].duration_minutes, 30);
+    }
+
+    #[test]
+    fn test_short_gap_stays_one_block() {
+        let mut monitor = monitor();
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 0, 40);
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 50, 60);
+
+        let suggestions = monitor.take_suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].duration_minutes, 60);
+        assert_eq!(suggestions[0].description, "Drafting: Motion to Compel.docx");
+        assert!(monitor.suggestions().is_empty());
+    }
+
+    fn policy(merge_within_minutes: i64) -> CapturePolicy {
+        CapturePolicy {
+            min_duration_minutes: 6,
+            merge_within_minutes,
+            billing_increment_minutes: 6,
+        }
+    }
+
+    #[test]
+    fn test_sub_minimum_blocks_dropped_and_rest_rounded() {
+        let mut monitor = monitor().with_policy(policy(0));
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 0, 20);
+        // A stray one-off save an hour later
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 80, 81);
+
+        let suggestions = monitor.suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].duration_minutes, 24);
+    }
+
+    #[test]
+    fn test_adjacent_same_matter_blocks_merge_within_threshold() {
+        let mut monitor = monitor().with_policy(policy(25));
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 0, 20);
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 40, 50);
+        record_span(&mut monitor, ActivityKind::Research, 90, 120);
+
+        let suggestions = monitor.suggestions();
+        assert_eq!(suggestions.len(), 2);
+        // 20 + 10 minutes worked; the 20 idle minutes between them are not billed
+        assert_eq!(suggestions[0].duration_minutes, 30);
+        assert_eq!(suggestions[0].end_time - suggestions[0].start_time, Duration::minutes(50));
+        assert_eq!(suggestions[1].activity_type, ActivityType::Research);
+    }
+
+    #[test]
+    fn test_policy_is_read_from_the_detection_settings() {
+        let settings: AutomaticTimeDetection = serde_yaml::from_str(
+            "enabled: true
+detect_document_editing: true
+detect_email_activity: false
+detect_research_activity: true
+min_activity_duration_minutes: 6
+idle_timeout_minutes: 15
+merge_within_minutes: 25
+billing_increment_minutes: 6",
+        )
+        .unwrap();
+        assert_eq!(CapturePolicy::from_settings(&settings), policy(25));
+
+        let mut monitor = ActivityMonitor::new("atty-1", settings);
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 0, 20);
+        record_span(&mut monitor, ActivityKind::DocumentEditing, 40, 50);
+        assert_eq!(monitor.suggestions()[0].duration_minutes, 30);
+    }
+
+    #[test]
+    fn test_tracker_suggests_only_blocks_the_attorney_has_left() {
+        let tracker = ActivityTracker::new(monitor().settings);
+        let nine = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        for (minute, matter) in [
+            (0, "matter-1"),
+            (5, "matter-1"),
+            (10, "matter-1"),
+            (30, "matter-2"),
+            (40, "matter-2"),
+            (50, "matter-2"),
+        ] {
+            tracker.record(
+                "atty-1",
+                ActivityEvent {
+                    at: nine + Duration::minutes(minute),
+                    kind: ActivityKind::DocumentEditing,
+                    matter_id: matter.to_string(),
+                    subject: "Brief.docx".to_string(),
+                },
+            );
+        }
+
+        // At 9:55 matter-1 has been idle past the timeout; matter-2 is still being worked
+        let suggestions = tracker.suggestions("atty-1", nine + Duration::minutes(55));
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].matter_id, "matter-1");
+        assert_eq!(suggestions[0].duration_minutes, 10);
+        assert!(tracker.suggestions("atty-2", nine + Duration::minutes(55)).is_empty());
+
+        let id = suggestions[0].id.clone();
+        assert!(tracker.take("atty-2", &id).is_none(), "another attorney cannot take it");
+        let taken = tracker.take("atty-1", &id).unwrap();
+        assert!(tracker.take("atty-1", &id).is_none());
+
+        tracker.restore(taken);
+        let later = tracker.suggestions("atty-1", nine + Duration::minutes(120));
+        let matters: Vec<&str> = later.iter().map(|s| s.matter_id.as_str()).collect();
+        assert_eq!(matters, vec!["matter-1", "matter-2"]);
+    }
+}
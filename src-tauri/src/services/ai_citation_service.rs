@@ -3,11 +3,14 @@
 
 use crate::domain::*;
 use crate::providers::courtlistener::{CourtListenerProvider, SearchQuery};
-use crate::services::citations::CitationService;
+use crate::services::citations::{extract_citations, normalize_citation, CitationService};
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{debug, info, instrument, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,19 +44,57 @@ pub struct TableOfAuthoritiesEntry {
     pub sort_key: String,
 }
 
+/// A reported case returned by a case-law search
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaseResult {
+    pub case_name: String,
+    /// Preferred citation: federal, then state, then neutral
+    pub citation: String,
+    /// Every citation the case is reported under, including `citation`
+    pub parallel_citations: Vec<String>,
+    pub court: String,
+    pub year: String,
+    pub url: Option<String>,
+    pub opinion_id: Option<u32>,
+}
+
+/// A citation found in a document and the reported case it points to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedCitation {
+    pub citation: Citation,
+    pub case: Option<CaseResult>,
+    /// A case citation that no reported case carries, so it may be
+    /// mistyped or may not exist at all
+    pub unresolved: bool,
+}
+
+/// Case-law searches in flight at once while resolving a document
+const CASE_LAW_LOOKUP_CONCURRENCY: usize = 4;
+
+#[async_trait]
+pub trait CaseLawSource: Send + Sync {
+    async fn search(&self, query: &str) -> Result<Vec<CaseResult>>;
+}
+
 pub struct AICitationService {
     citation_service: CitationService,
-    courtlistener: Option<CourtListenerProvider>,
+    case_law: Option<Arc<dyn CaseLawSource>>,
 }
 
 impl AICitationService {
     pub fn new(courtlistener_token: Option<String>) -> Self {
         Self {
             citation_service: CitationService::new(),
-            courtlistener: courtlistener_token.map(|token| CourtListenerProvider::new(token)),
+            case_law: courtlistener_token
+                .map(|token| Arc::new(CourtListenerProvider::new(token)) as Arc<dyn CaseLawSource>),
         }
     }
 
+    pub fn with_case_law(mut self, source: Arc<dyn CaseLawSource>) -> Self {
+        self.case_law = Some(source);
+        self
+    }
+
     // ========================================================================
     // Citation Extraction
     // ========================================================================
@@ -86,7 +127,7 @@ impl AICitationService {
             let bluebook = self.format_case_citation(volume, reporter, page).await?;
 
             // Get suggestions from CourtListener
-            let suggestions = self.get_citation_suggestions(citation_text).await?;
+            let suggestions = self.suggest_citations("", citation_text).await?;
 
             extracted.push(ExtractedCitation {
                 text: citation_text.to_string(),
@@ -139,6 +180,98 @@ impl AICitationService {
         Ok(extracted)
     }
 
+    // ========================================================================
+    // Case Law Search and Citation Resolution
+    // ========================================================================
+
+    /// Cases matching `query` from the configured case-law provider; none
+    /// when no provider is configured
+    #[instrument(skip(self))]
+    pub async fn search_case_law(&self, query: &str) -> Result<Vec<CaseResult>> {
+        let Some(case_law) = &self.case_law else {
+            debug!("No case-law provider configured");
+            return Ok(Vec::new());
+        };
+        case_law.search(query).await.context("Failed to search case law")
+    }
+
+    /// Every citation in `text`, each case citation linked to the reported
+    /// case it names. A case citation no search result carries is flagged as
+    /// unresolved; one whose lookup fails is left unflagged, since a failed
+    /// search says nothing about the citation.
+    #[instrument(skip(self, text))]
+    pub async fn resolve_citations(&self, text: &str) -> Result<Vec<ResolvedCitation>> {
+        let citations = extract_citations(text);
+        let cites: Vec<Option<String>> = citations
+            .iter()
+            .map(|citation| match (&citation.volume, &citation.reporter, &citation.page) {
+                (Some(volume), Some(reporter), Some(page))
+                    if citation.citation_type == CitationType::Case && self.case_law.is_some() =>
+                {
+                    normalize_citation(&format!("{} {} {}", volume, reporter, page))
+                }
+                _ => None,
+            })
+            .collect();
+
+        // One search per distinct citation, a few at a time; None for a failed search
+        let mut distinct: Vec<&String> = cites.iter().flatten().collect();
+        distinct.sort();
+        distinct.dedup();
+        let searches: HashMap<&String, Option<Vec<CaseResult>>> = stream::iter(distinct)
+            .map(|cite| async move {
+                match self.search_case_law(cite).await {
+                    Ok(results) => (cite, Some(results)),
+                    Err(e) => {
+                        warn!("Could not look up {}: {:#}", cite, e);
+                        (cite, None)
+                    }
+                }
+            })
+            .buffer_unordered(CASE_LAW_LOOKUP_CONCURRENCY)
+            .collect()
+            .await;
+
+        let resolved: Vec<ResolvedCitation> = citations
+            .into_iter()
+            .zip(&cites)
+            .map(|(mut citation, cite)| {
+                let searched = cite.as_ref().and_then(|cite| Some((cite, searches.get(cite)?.as_ref()?)));
+                let Some((cite, results)) = searched else {
+                    return ResolvedCitation {
+                        citation,
+                        case: None,
+                        unresolved: false,
+                    };
+                };
+
+                let case = results
+                    .iter()
+                    .find(|case| {
+                        case.parallel_citations
+                            .iter()
+                            .any(|parallel| normalize_citation(parallel).as_deref() == Some(cite.as_str()))
+                    })
+                    .cloned();
+                if case.is_none() {
+                    citation.is_valid = false;
+                    citation
+                        .errors
+                        .push(format!("No reported case is cited as {}; check that it exists", cite));
+                }
+                ResolvedCitation {
+                    unresolved: case.is_none(),
+                    citation,
+                    case,
+                }
+            })
+            .collect();
+
+        let unresolved = resolved.iter().filter(|r| r.unresolved).count();
+        info!("Resolved {} citations, {} unresolved", resolved.len(), unresolved);
+        Ok(resolved)
+    }
+
     // ========================================================================
     // AI-Powered Citation Suggestions
     // ========================================================================
@@ -147,49 +280,22 @@ impl AICitationService {
     pub async fn suggest_citations(&self, context: &str, query: &str) -> Result<Vec<CitationSuggestion>> {
         info!("Getting AI citation suggestions for: {}", query);
 
-        let mut suggestions = Vec::new();
-
-        if let Some(ref courtlistener) = self.courtlistener {
-            // Search CourtListener for relevant cases
-            let search_result = courtlistener
-                .search_opinions(SearchQuery {
-                    q: Some(query.to_string()),
-                    case_name: None,
-                    court: None,
-                    docket_number: None,
-                    filed_after: None,
-                    filed_before: None,
-                    cited_gt: None,
-                    cited_lt: None,
-                    status: Some("Precedential".to_string()),
-                    order_by: Some("-citeCount".to_string()), // Most cited first
-                })
-                .await?;
-
-            // Convert to suggestions
-            for (idx, opinion) in search_result.results.iter().enumerate().take(10) {
-                // Get cluster for citation info
-                if let Ok(cluster_id) = self.extract_cluster_id(&opinion.cluster) {
-                    if let Ok(cluster) = courtlistener.get_opinion_cluster(cluster_id).await {
-                        let citation = cluster.federal_cite_one
-                            .or(cluster.state_cite_one)
-                            .or(cluster.neutral_cite)
-                            .unwrap_or_else(|| cluster.case_name.clone());
-
-                        suggestions.push(CitationSuggestion {
-                            original_text: query.to_string(),
-                            suggested_citation: citation.clone(),
-                            case_name: cluster.case_name.clone(),
-                            court: self.get_court_name(&cluster.docket),
-                            year: cluster.date_filed.split('-').next().unwrap_or("").to_string(),
-                            relevance_score: 1.0 - (idx as f32 * 0.1),
-                            full_text_url: Some(opinion.absolute_url.clone()),
-                            opinion_id: Some(opinion.id),
-                        });
-                    }
-                }
-            }
-        }
+        let cases = self.search_case_law(query).await?;
+        let suggestions = cases
+            .into_iter()
+            .take(10)
+            .enumerate()
+            .map(|(idx, case)| CitationSuggestion {
+                original_text: query.to_string(),
+                suggested_citation: case.citation,
+                case_name: case.case_name,
+                court: case.court,
+                year: case.year,
+                relevance_score: 1.0 - (idx as f32 * 0.1),
+                full_text_url: case.url,
+                opinion_id: case.opinion_id,
+            })
+            .collect();
 
         Ok(suggestions)
     }
@@ -344,28 +450,6 @@ impl AICitationService {
         }
     }
 
-    fn extract_cluster_id(&self, cluster_url: &str) -> Result<u32> {
-        let parts: Vec<&str> = cluster_url.split('/').collect();
-        parts
-            .iter()
-            .find_map(|&part| part.parse::<u32>().ok())
-            .ok_or_else(|| anyhow::anyhow!("Could not extract cluster ID"))
-    }
-
-    fn get_court_name(&self, docket_url: &str) -> String {
-        if docket_url.contains("pasuper") {
-            "Pa. Super. Ct.".to_string()
-        } else if docket_url.contains("pacommw") {
-            "Pa. Commw. Ct.".to_string()
-        } else if docket_url.contains("/pa/") {
-            "Pa.".to_string()
-        } else if docket_url.contains("ca3") {
-            "3d Cir.".to_string()
-        } else {
-            "Unknown".to_string()
-        }
-    }
-
     fn find_page_references(&self, citation: &ExtractedCitation, document: &str) -> Vec<u32> {
         // Simple page reference calculation (would need actual page break detection)
         let lines_before = document[..citation.start_index].lines().count();
@@ -409,9 +493,96 @@ impl AICitationService {
     }
 }
 
+#[async_trait]
+impl CaseLawSource for CourtListenerProvider {
+    async fn search(&self, query: &str) -> Result<Vec<CaseResult>> {
+        let search_result = self
+            .search_opinions(SearchQuery {
+                q: Some(query.to_string()),
+                case_name: None,
+                court: None,
+                docket_number: None,
+                filed_after: None,
+                filed_before: None,
+                cited_gt: None,
+                cited_lt: None,
+                status: Some("Precedential".to_string()),
+                order_by: Some("-citeCount".to_string()), // Most cited first
+            })
+            .await?;
+
+        let mut cases = Vec::new();
+        for opinion in search_result.results.iter().take(10) {
+            // Get cluster for citation info
+            let Ok(cluster_id) = extract_cluster_id(&opinion.cluster) else {
+                continue;
+            };
+            let Ok(cluster) = self.get_opinion_cluster(cluster_id).await else {
+                continue;
+            };
+
+            let parallel_citations: Vec<String> = [
+                &cluster.federal_cite_one,
+                &cluster.federal_cite_two,
+                &cluster.federal_cite_three,
+                &cluster.state_cite_one,
+                &cluster.state_cite_two,
+                &cluster.state_cite_three,
+                &cluster.state_cite_regional,
+                &cluster.specialty_cite_one,
+                &cluster.neutral_cite,
+            ]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+            let citation = cluster
+                .federal_cite_one
+                .clone()
+                .or_else(|| cluster.state_cite_one.clone())
+                .or_else(|| cluster.neutral_cite.clone())
+                .unwrap_or_else(|| cluster.case_name.clone());
+
+            cases.push(CaseResult {
+                case_name: cluster.case_name.clone(),
+                citation,
+                parallel_citations,
+                court: court_from_docket_url(&cluster.docket),
+                year: cluster.date_filed.split('-').next().unwrap_or("").to_string(),
+                url: Some(opinion.absolute_url.clone()),
+                opinion_id: Some(opinion.id),
+            });
+        }
+        Ok(cases)
+    }
+}
+
+fn extract_cluster_id(cluster_url: &str) -> Result<u32> {
+    let parts: Vec<&str> = cluster_url.split('/').collect();
+    parts
+        .iter()
+        .find_map(|&part| part.parse::<u32>().ok())
+        .ok_or_else(|| anyhow::anyhow!("Could not extract cluster ID"))
+}
+
+fn court_from_docket_url(docket_url: &str) -> String {
+    if docket_url.contains("pasuper") {
+        "Pa. Super. Ct.".to_string()
+    } else if docket_url.contains("pacommw") {
+        "Pa. Commw. Ct.".to_string()
+    } else if docket_url.contains("/pa/") {
+        "Pa.".to_string()
+    } else if docket_url.contains("ca3") {
+        "3d Cir.".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn test_extract_citations() {
@@ -431,4 +602,85 @@ mod tests {
         assert!(toa.contains("TABLE OF AUTHORITIES"));
         assert!(toa.contains("Cases") || toa.contains("Statutes"));
     }
+
+    /// Knows one reported case, Smith v. Jones
+    struct FakeCaseLaw;
+
+    #[async_trait]
+    impl CaseLawSource for FakeCaseLaw {
+        async fn search(&self, query: &str) -> Result<Vec<CaseResult>> {
+            if query != "123 F.3d 456" {
+                return Ok(Vec::new());
+            }
+            Ok(vec![CaseResult {
+                case_name: "Smith v. Jones".to_string(),
+                citation: "123 F.3d 456".to_string(),
+                parallel_citations: vec!["123 F.3d 456".to_string()],
+                court: "3d Cir.".to_string(),
+                year: "2020".to_string(),
+                url: None,
+                opinion_id: Some(1),
+            }])
+        }
+    }
+
+    const BRIEF: &str = "In Smith v. Jones, 123 F.3d 456, 460 (3d Cir. 2020), the court held otherwise. \
+        See also 42 U.S.C. § 1983; Commonwealth v. Doe, 250 A.3d 1020 (Pa. Super. 2021).";
+
+    #[test]
+    fn test_extract_citations_finds_each_citation() {
+        let citations = extract_citations(BRIEF);
+        assert_eq!(citations.len(), 3);
+
+        assert_eq!(citations[0].citation_type, CitationType::Case);
+        assert_eq!(citations[0].title.as_deref(), Some("Smith v. Jones"));
+        assert_eq!(citations[0].pin_cite.as_deref(), Some("460"));
+        assert_eq!(citations[0].court.as_deref(), Some("3d Cir."));
+        assert_eq!(citations[1].citation_type, CitationType::Statute);
+        assert_eq!(citations[1].full_citation, "42 U.S.C. § 1983");
+        assert_eq!(citations[2].title.as_deref(), Some("Commonwealth v. Doe"));
+        assert_eq!(citations[2].year.as_deref(), Some("2021"));
+    }
+
+    /// Counts searches and answers none of them
+    #[derive(Default)]
+    struct CountingCaseLaw {
+        searches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CaseLawSource for CountingCaseLaw {
+        async fn search(&self, _query: &str) -> Result<Vec<CaseResult>> {
+            self.searches.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_citation_is_searched_once() {
+        let case_law = Arc::new(CountingCaseLaw::default());
+        let service = AICitationService::new(None).with_case_law(case_law.clone());
+        let text = "See 123 F.3d 456; 123 F.3d 456, 460; 250 A.3d 1020; 42 U.S.C. § 1983.";
+
+        let resolved = service.resolve_citations(text).await.unwrap();
+
+        assert_eq!(resolved.len(), 4);
+        assert_eq!(case_law.searches.load(Ordering::SeqCst), 2);
+        assert!(resolved[0].unresolved && resolved[1].unresolved && resolved[2].unresolved);
+        assert!(!resolved[3].unresolved);
+    }
+
+    #[tokio::test]
+    async fn test_unresolvable_case_citation_is_flagged() {
+        let service = AICitationService::new(None).with_case_law(Arc::new(FakeCaseLaw));
+        let resolved = service.resolve_citations(BRIEF).await.unwrap();
+
+        assert!(!resolved[0].unresolved);
+        assert_eq!(resolved[0].case.as_ref().unwrap().case_name, "Smith v. Jones");
+        // Statutes are not looked up
+        assert!(!resolved[1].unresolved);
+        assert!(resolved[2].unresolved);
+        assert!(!resolved[2].citation.is_valid);
+        assert_eq!(resolved[2].citation.errors.len(), 1);
+    }
 }
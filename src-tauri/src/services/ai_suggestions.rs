@@ -0,0 +1,312 @@
+// AI suggestions - drafting suggestions for the document editor
+// Suggestions come from a pluggable backend: local heuristics by default, or
+// an LLM endpoint configured under the `ai_suggestions` provider. The editor
+// treats suggestions as optional, so a failing backend yields none rather
+// than an error.
+
+use crate::config::ProvidersConfig;
+use crate::providers::client::ProviderClient;
+use crate::providers::ProviderConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use tracing::{debug, warn};
+
+/// Key of the LLM endpoint in the providers config
+pub const SUGGESTIONS_PROVIDER: &str = "ai_suggestions";
+
+/// What the editor knows about the text being drafted
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentContext {
+    pub matter_id: Option<String>,
+    /// e.g. "motion", "brief", "agreement"
+    pub document_type: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    ClauseImprovement,
+    CitationFix,
+    Tone,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub message: String,
+    /// Text to put in place of `start..end`, if the suggestion has one
+    pub replacement: Option<String>,
+    /// Offsets into the context text in UTF-16 code units, as the editor
+    /// counts them
+    pub start: usize,
+    pub end: usize,
+}
+
+#[async_trait]
+pub trait SuggestionBackend: Send + Sync {
+    /// Suggestions positioned by byte offsets into `context.text`
+    async fn suggest(&self, context: &DocumentContext) -> Result<Vec<Suggestion>>;
+}
+
+pub struct AiSuggestionService {
+    backend: Arc<dyn SuggestionBackend>,
+}
+
+impl Default for AiSuggestionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiSuggestionService {
+    pub fn new() -> Self {
+        Self {
+            backend: Arc::new(HeuristicBackend),
+        }
+    }
+
+    /// The configured LLM endpoint if it is enabled, otherwise the heuristics
+    pub fn from_config(providers: &ProvidersConfig) -> Self {
+        match LlmBackend::from_config(providers) {
+            Ok(Some(llm)) => Self::new().with_backend(Arc::new(llm)),
+            Ok(None) => Self::new(),
+            Err(e) => {
+                warn!("AI suggestion endpoint unavailable, using heuristics: {}", e);
+                Self::new()
+            }
+        }
+    }
+
+    pub fn with_backend(mut self, backend: Arc<dyn SuggestionBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Suggestions ordered by position, in the editor's UTF-16 offsets. A
+    /// failing backend, or one returning positions outside the text, never
+    /// fails the caller.
+    pub async fn get_ai_suggestions(&self, context: DocumentContext) -> Result<Vec<Suggestion>> {
+        let mut suggestions = match self.backend.suggest(&context).await {
+            Ok(suggestions) => suggestions,
+            Err(e) => {
+                warn!("AI suggestion backend failed: {:#}", e);
+                return Ok(Vec::new());
+            }
+        };
+
+        let text = context.text.as_str();
+        suggestions.retain(|s| {
+            let in_text = s.start <= s.end && text.is_char_boundary(s.start) && text.is_char_boundary(s.end);
+            if !in_text {
+                debug!("Dropping suggestion outside the text: {}..{}", s.start, s.end);
+            }
+            in_text
+        });
+        suggestions.sort_by_key(|s| (s.start, s.end));
+        for suggestion in &mut suggestions {
+            suggestion.start = utf16_offset(text, suggestion.start);
+            suggestion.end = utf16_offset(text, suggestion.end);
+        }
+        Ok(suggestions)
+    }
+}
+
+/// The UTF-16 offset of byte offset `byte` in `text`
+fn utf16_offset(text: &str, byte: usize) -> usize {
+    text[..byte].encode_utf16().count()
+}
+
+/// Pattern-based suggestions that need no network
+pub struct HeuristicBackend;
+
+/// (pattern, replacement, message)
+const CLAUSE_RULES: [(&str, &str, &str); 4] = [
+    (r"(?i)\band/or\b", "either or both", "\"and/or\" is ambiguous; say which is meant"),
+    (
+        r"(?i)\bincluding\b(?:, without limitation,)?",
+        "including, without limitation,",
+        "Make clear that a list after \"including\" is not exhaustive",
+    ),
+    (
+        r"(?i)\bbest efforts\b",
+        "commercially reasonable efforts",
+        "\"Best efforts\" can require action against a party's own interest",
+    ),
+    (
+        r"(?i)\bas soon as possible\b",
+        "within ten (10) days",
+        "Give a definite deadline instead of an open-ended one",
+    ),
+];
+
+const CITATION_RULES: [(&str, &str, &str); 5] = [
+    (r"\bv\s", "v. ", "Case names take \"v.\" with a period"),
+    (r"\bvs\.?\s", "v. ", "Use \"v.\" rather than \"vs.\" in case names"),
+    (r"\bPa\.Super\.", "Pa. Super.", "Superior Court reporter is cited \"Pa. Super.\""),
+    (r"\bPa\.Cmwlth\.", "Pa. Cmwlth.", "Commonwealth Court reporter is cited \"Pa. Cmwlth.\""),
+    (r"\bF\.Supp\.", "F. Supp.", "Federal Supplement is cited \"F. Supp.\""),
+];
+
+const TONE_RULES: [(&str, &str, &str); 7] = [
+    (r"(?i)\bdon't\b", "do not", "Avoid contractions in formal filings"),
+    (r"(?i)\bcan't\b", "cannot", "Avoid contractions in formal filings"),
+    (r"(?i)\bwon't\b", "will not", "Avoid contractions in formal filings"),
+    (r"(?i)\bdoesn't\b", "does not", "Avoid contractions in formal filings"),
+    (r"(?i)\bisn't\b", "is not", "Avoid contractions in formal filings"),
+    (r"(?i)\b(?:clearly|obviously)\b,?\s*", "", "Intensifiers weaken an argument; let the facts carry it"),
+    (r"(?i)\bgonna\b", "going to", "Informal wording"),
+];
+
+struct HeuristicRule {
+    kind: SuggestionKind,
+    regex: Regex,
+    replacement: &'static str,
+    message: &'static str,
+}
+
+/// Every rule, compiled on first use
+fn heuristic_rules() -> &'static [HeuristicRule] {
+    static RULES: OnceLock<Vec<HeuristicRule>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        [
+            (SuggestionKind::ClauseImprovement, &CLAUSE_RULES[..]),
+            (SuggestionKind::CitationFix, &CITATION_RULES[..]),
+            (SuggestionKind::Tone, &TONE_RULES[..]),
+        ]
+        .into_iter()
+        .flat_map(|(kind, rules)| {
+            rules.iter().map(move |&(pattern, replacement, message)| HeuristicRule {
+                kind,
+                regex: Regex::new(pattern).expect("Invalid suggestion pattern"),
+                replacement,
+                message,
+            })
+        })
+        .collect()
+    })
+}
+
+#[async_trait]
+impl SuggestionBackend for HeuristicBackend {
+    async fn suggest(&self, context: &DocumentContext) -> Result<Vec<Suggestion>> {
+        let mut suggestions = Vec::new();
+        for rule in heuristic_rules() {
+            for found in rule.regex.find_iter(&context.text) {
+                // Already in the suggested form
+                if found.as_str().eq_ignore_ascii_case(rule.replacement) {
+                    continue;
+                }
+                suggestions.push(Suggestion {
+                    kind: rule.kind,
+                    message: rule.message.to_string(),
+                    replacement: Some(rule.replacement.to_string()),
+                    start: found.start(),
+                    end: found.end(),
+                });
+            }
+        }
+        Ok(suggestions)
+    }
+}
+
+/// An external model behind a JSON endpoint: posts the context and reads
+/// back a list of suggestions
+pub struct LlmBackend {
+    client: ProviderClient,
+    url: String,
+}
+
+impl LlmBackend {
+    /// `None` when no enabled `ai_suggestions` provider is configured
+    pub fn from_config(providers: &ProvidersConfig) -> Result<Option<Self>> {
+        let Some(config) = providers.providers.get(SUGGESTIONS_PROVIDER).filter(|c| c.enabled) else {
+            return Ok(None);
+        };
+        let path = config.endpoints.get("suggestions").map(String::as_str).unwrap_or("/suggestions");
+        let url = format!("{}{}", config.base_url.trim_end_matches('/'), path);
+        let client = ProviderClient::new(ProviderConfig::from_app_config(config, &providers.global))?;
+        Ok(Some(Self { client, url }))
+    }
+}
+
+#[async_trait]
+impl SuggestionBackend for LlmBackend {
+    async fn suggest(&self, context: &DocumentContext) -> Result<Vec<Suggestion>> {
+        let suggestions = self
+            .client
+            .post_json(&self.url, context)
+            .await
+            .context("Failed to fetch AI suggestions")?;
+        Ok(suggestions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingBackend;
+
+    #[async_trait]
+    impl SuggestionBackend for FailingBackend {
+        async fn suggest(&self, _context: &DocumentContext) -> Result<Vec<Suggestion>> {
+            anyhow::bail!("model endpoint returned 503")
+        }
+    }
+
+    fn context(text: &str) -> DocumentContext {
+        DocumentContext {
+            text: text.to_string(),
+            ..DocumentContext::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_backend_suggests_with_positions() {
+        let text = "Defendant clearly breached. See Smith vs. Jones, 123 Pa.Super. 45. We don't agree.";
+        let suggestions = AiSuggestionService::new().get_ai_suggestions(context(text)).await.unwrap();
+
+        let kinds: Vec<_> = suggestions.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SuggestionKind::Tone,
+                SuggestionKind::CitationFix,
+                SuggestionKind::CitationFix,
+                SuggestionKind::Tone
+            ]
+        );
+        let reporter = &suggestions[2];
+        assert_eq!(&text[reporter.start..reporter.end], "Pa.Super.");
+        assert_eq!(reporter.replacement.as_deref(), Some("Pa. Super."));
+    }
+
+    #[tokio::test]
+    async fn test_positions_are_utf16_offsets() {
+        // "é" is two bytes but one UTF-16 unit; the emoji is four bytes, two units
+        let text = "Café 🍰 — we don't agree.";
+        let suggestions = AiSuggestionService::new().get_ai_suggestions(context(text)).await.unwrap();
+
+        assert_eq!(suggestions.len(), 1);
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let flagged = String::from_utf16(&units[suggestions[0].start..suggestions[0].end]).unwrap();
+        assert_eq!(flagged, "don't");
+        assert_eq!(suggestions[0].start, 13);
+    }
+
+    #[test]
+    fn test_every_rule_compiles() {
+        assert_eq!(heuristic_rules().len(), CLAUSE_RULES.len() + CITATION_RULES.len() + TONE_RULES.len());
+    }
+
+    #[tokio::test]
+    async fn test_backend_error_yields_no_suggestions() {
+        let service = AiSuggestionService::new().with_backend(Arc::new(FailingBackend));
+        let suggestions = service.get_ai_suggestions(context("We don't agree.")).await.unwrap();
+        assert!(suggestions.is_empty());
+    }
+}
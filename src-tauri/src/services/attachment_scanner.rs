@@ -0,0 +1,248 @@
+// Attachment scanning - checks a file before it is filed in a client's records
+// or sent out. The type an attachment claims by its extension has to match
+// what its leading bytes say it is; executables are refused whatever they
+// are named, and the policy limits the allowed types and the size.
+
+use crate::utils::file_utils::get_mime_type;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Largest attachment Gmail and Outlook will send
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Bytes read from the start of a file to identify it
+const SNIFF_BYTES: usize = 512;
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentPolicy {
+    pub allowed_mime_types: Vec<String>,
+    pub max_size_bytes: u64,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_mime_types: [
+                "application/pdf",
+                "application/msword",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                "text/plain",
+                "text/csv",
+                "image/png",
+                "image/jpeg",
+                "image/gif",
+            ]
+            .iter()
+            .map(|mime| mime.to_string())
+            .collect(),
+            max_size_bytes: DEFAULT_MAX_ATTACHMENT_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ScanFinding {
+    /// Windows, ELF or Mach-O binary, or a script with a `#!` line
+    Executable { detected_mime: String },
+    /// The content is not what the file's extension claims
+    MimeMismatch { declared_mime: String, detected_mime: String },
+    /// The policy does not allow the file's type
+    DisallowedType { mime_type: String },
+    TooLarge { size: u64, max_size: u64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanResult {
+    pub path: PathBuf,
+    /// Type claimed by the file's extension
+    pub declared_mime: String,
+    /// Type identified from the file's leading bytes
+    pub detected_mime: String,
+    pub size: u64,
+    pub findings: Vec<ScanFinding>,
+}
+
+impl ScanResult {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// The result if nothing was found, otherwise an `AttachmentRejected` error
+    pub fn require_clean(self) -> Result<Self> {
+        if self.is_clean() {
+            return Ok(self);
+        }
+        Err(AttachmentRejected {
+            path: self.path,
+            findings: self.findings,
+        }
+        .into())
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("Attachment {} was rejected: {findings:?}", .path.display())]
+pub struct AttachmentRejected {
+    pub path: PathBuf,
+    pub findings: Vec<ScanFinding>,
+}
+
+/// Scan the file at `path` against `policy`. Errors only when the file cannot
+/// be read; what the scan finds is reported on the result.
+pub fn scan_attachment(path: &Path, policy: &AttachmentPolicy) -> Result<ScanResult> {
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read attachment {}", path.display()))?
+        .len();
+
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)
+        .with_context(|| format!("Failed to open attachment {}", path.display()))?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .with_context(|| format!("Failed to read attachment {}", path.display()))?;
+
+    let declared_mime = get_mime_type(path);
+    let detected_mime = sniff_mime_type(&head);
+    let mut findings = Vec::new();
+
+    if is_executable(detected_mime) {
+        findings.push(ScanFinding::Executable {
+            detected_mime: detected_mime.to_string(),
+        });
+    } else if !content_matches(declared_mime, detected_mime) {
+        findings.push(ScanFinding::MimeMismatch {
+            declared_mime: declared_mime.to_string(),
+            detected_mime: detected_mime.to_string(),
+        });
+    }
+    if !policy.allowed_mime_types.iter().any(|allowed| allowed == declared_mime) {
+        findings.push(ScanFinding::DisallowedType {
+            mime_type: declared_mime.to_string(),
+        });
+    }
+    if size > policy.max_size_bytes {
+        findings.push(ScanFinding::TooLarge {
+            size,
+            max_size: policy.max_size_bytes,
+        });
+    }
+
+    Ok(ScanResult {
+        path: path.to_path_buf(),
+        declared_mime: declared_mime.to_string(),
+        detected_mime: detected_mime.to_string(),
+        size,
+        findings,
+    })
+}
+
+/// Identify content by its magic bytes; anything without a known signature
+/// that is not text is an octet stream
+fn sniff_mime_type(head: &[u8]) -> &'static str {
+    const SIGNATURES: [(&[u8], &str); 14] = [
+        (b"MZ", "application/x-msdownload"),
+        (b"\x7fELF", "application/x-executable"),
+        (b"\xfe\xed\xfa\xce", "application/x-mach-binary"),
+        (b"\xfe\xed\xfa\xcf", "application/x-mach-binary"),
+        (b"\xce\xfa\xed\xfe", "application/x-mach-binary"),
+        (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+        (b"#!", "text/x-shellscript"),
+        (b"%PDF-", "application/pdf"),
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "application/msword"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return *mime;
+    }
+    let is_text = !head.contains(&0)
+        && match std::str::from_utf8(head) {
+            Ok(_) => true,
+            // The sample may end part-way through a multi-byte character
+            Err(e) => e.error_len().is_none(),
+        };
+    if is_text {
+        "text/plain"
+    } else {
+        OCTET_STREAM
+    }
+}
+
+fn is_executable(mime: &str) -> bool {
+    matches!(
+        mime,
+        "application/x-msdownload" | "application/x-executable" | "application/x-mach-binary" | "text/x-shellscript"
+    )
+}
+
+/// Whether content detected as `detected` can honestly carry the `declared` type
+fn content_matches(declared: &str, detected: &str) -> bool {
+    match detected {
+        _ if declared == detected => true,
+        // DOCX is a ZIP package
+        "application/zip" => declared == "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "text/plain" => declared.starts_with("text/") || declared == "application/json",
+        _ => declared == OCTET_STREAM,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_renamed_executable_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "settlement.pdf", b"MZ\x90\x00\x03\x00\x00\x00This program cannot be run in DOS mode");
+
+        let result = scan_attachment(&path, &AttachmentPolicy::default()).unwrap();
+        assert_eq!(result.declared_mime, "application/pdf");
+        assert_eq!(
+            result.findings,
+            vec![ScanFinding::Executable {
+                detected_mime: "application/x-msdownload".to_string()
+            }]
+        );
+
+        let err = result.require_clean().unwrap_err();
+        assert!(err.downcast_ref::<AttachmentRejected>().is_some());
+    }
+
+    #[test]
+    fn test_matching_pdf_passes_and_policy_limits_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = write(dir.path(), "settlement.pdf", b"%PDF-1.4 settlement agreement");
+        let zip = write(dir.path(), "exhibits.zip", b"PK\x03\x04 exhibits");
+
+        assert!(scan_attachment(&pdf, &AttachmentPolicy::default()).unwrap().is_clean());
+
+        let small = AttachmentPolicy {
+            max_size_bytes: 10,
+            ..AttachmentPolicy::default()
+        };
+        let findings = scan_attachment(&pdf, &small).unwrap().findings;
+        assert_eq!(findings, vec![ScanFinding::TooLarge { size: 29, max_size: 10 }]);
+
+        let findings = scan_attachment(&zip, &AttachmentPolicy::default()).unwrap().findings;
+        assert_eq!(
+            findings,
+            vec![ScanFinding::DisallowedType {
+                mime_type: "application/zip".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,389 @@
+// Audit log - tamper-evident record of privileged actions
+// Entries are append-only and hash-chained: each hash covers the entry's
+// fields plus the previous entry's hash, so any edit or deletion is detectable
+
+use crate::utils::crypto::calculate_sha256_string;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// `prev_hash` of the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Actor recorded for changes made by the app itself rather than a signed-in user
+pub const SYSTEM_ACTOR: &str = "system";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    PaymentRecorded,
+    PaymentProcessed,
+    TrustDeposit,
+    TrustWithdrawal,
+    TrustInterest,
+    SettingChanged,
+    EFilingSubmitted,
+    ConflictWaived,
+    ConflictCleared,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::PaymentRecorded => "payment_recorded",
+            AuditAction::PaymentProcessed => "payment_processed",
+            AuditAction::TrustDeposit => "trust_deposit",
+            AuditAction::TrustWithdrawal => "trust_withdrawal",
+            AuditAction::TrustInterest => "trust_interest",
+            AuditAction::SettingChanged => "setting_changed",
+            AuditAction::EFilingSubmitted => "efiling_submitted",
+            AuditAction::ConflictWaived => "conflict_waived",
+            AuditAction::ConflictCleared => "conflict_cleared",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "payment_recorded" => Some(AuditAction::PaymentRecorded),
+            "payment_processed" => Some(AuditAction::PaymentProcessed),
+            "trust_deposit" => Some(AuditAction::TrustDeposit),
+            "trust_withdrawal" => Some(AuditAction::TrustWithdrawal),
+            "trust_interest" => Some(AuditAction::TrustInterest),
+            "setting_changed" => Some(AuditAction::SettingChanged),
+            "efiling_submitted" => Some(AuditAction::EFilingSubmitted),
+            "conflict_waived" => Some(AuditAction::ConflictWaived),
+            "conflict_cleared" => Some(AuditAction::ConflictCleared),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: i64,
+    pub id: String,
+    pub actor: String,
+    pub action: AuditAction,
+    pub target_id: String,
+    pub details: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Hash of this entry's content chained to `prev_hash`. The fields are
+    /// JSON-encoded as an array so no field boundary is ambiguous.
+    fn compute_hash(&self) -> String {
+        let content = serde_json::json!([
+            self.prev_hash,
+            self.seq,
+            self.id,
+            self.actor,
+            self.action.as_str(),
+            self.target_id,
+            self.details,
+            self.created_at.to_rfc3339(),
+        ]);
+        calculate_sha256_string(&content.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub target_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainVerification {
+    pub valid: bool,
+    pub entries_checked: usize,
+    /// First entry whose hash or link to its predecessor does not match
+    pub first_invalid_seq: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct AuditLogService {
+    db: SqlitePool,
+}
+
+impl AuditLogService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Append an entry chained to the current head of the log
+    pub async fn record(
+        &self,
+        actor: &str,
+        action: AuditAction,
+        target_id: &str,
+        details: Option<serde_json::Value>,
+    ) -> Result<AuditEntry> {
+        // Immediate, so the write lock is held from reading the head onwards
+        // and concurrent appends cannot both chain to the same entry
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let entry = Self::record_in(&mut *tx, actor, action, target_id, details).await?;
+        tx.commit().await?;
+
+        info!("Audit: {} {} {}", entry.actor, entry.action.as_str(), entry.target_id);
+        Ok(entry)
+    }
+
+    /// Append an entry inside the caller's transaction, so it commits or
+    /// rolls back with the change it records. The transaction must have been
+    /// opened with `BEGIN IMMEDIATE`.
+    pub async fn record_in(
+        conn: &mut SqliteConnection,
+        actor: &str,
+        action: AuditAction,
+        target_id: &str,
+        details: Option<serde_json::Value>,
+    ) -> Result<AuditEntry> {
+        let head = sqlx::query("SELECT seq, hash FROM audit_log ORDER BY seq DESC LIMIT 1")
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to read audit log head")?;
+        let (prev_seq, prev_hash) = match head {
+            Some(row) => (row.try_get::<i64, _>("seq")?, row.try_get::<String, _>("hash")?),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+
+        let mut entry = AuditEntry {
+            seq: prev_seq + 1,
+            id: Uuid::new_v4().to_string(),
+            actor: actor.to_string(),
+            action,
+            target_id: target_id.to_string(),
+            details,
+            created_at: Utc::now(),
+            prev_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (seq, id, actor, action, target_id, details, created_at, prev_hash, hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(entry.seq)
+        .bind(&entry.id)
+        .bind(&entry.actor)
+        .bind(entry.action.as_str())
+        .bind(&entry.target_id)
+        .bind(entry.details.as_ref().map(|d| d.to_string()))
+        .bind(entry.created_at)
+        .bind(&entry.prev_hash)
+        .bind(&entry.hash)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to append audit log entry")?;
+
+        Ok(entry)
+    }
+
+    /// Entries matching `filter`, newest first
+    pub async fn query_audit_log(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM audit_log
+            WHERE (?1 IS NULL OR actor = ?1)
+              AND (?2 IS NULL OR action = ?2)
+              AND (?3 IS NULL OR target_id = ?3)
+              AND (?4 IS NULL OR created_at >= ?4)
+              AND (?5 IS NULL OR created_at <= ?5)
+            ORDER BY seq DESC
+            LIMIT ?6
+            "#,
+        )
+        .bind(&filter.actor)
+        .bind(filter.action.map(|a| a.as_str()))
+        .bind(&filter.target_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.limit.unwrap_or(-1))
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to query audit log")?;
+
+        rows.iter().map(row_to_entry).collect()
+    }
+
+    /// Walk the whole log from the first entry and recompute every hash
+    pub async fn verify_chain(&self) -> Result<ChainVerification> {
+        let rows = sqlx::query("SELECT * FROM audit_log ORDER BY seq")
+            .fetch_all(&self.db)
+            .await
+            .context("Failed to read audit log")?;
+
+        let mut expected_prev = GENESIS_HASH.to_string();
+        for (checked, row) in rows.iter().enumerate() {
+            let entry = row_to_entry(row)?;
+            if entry.prev_hash != expected_prev || entry.compute_hash() != entry.hash {
+                warn!("Audit log chain broken at entry {}", entry.seq);
+                return Ok(ChainVerification {
+                    valid: false,
+                    entries_checked: checked + 1,
+                    first_invalid_seq: Some(entry.seq),
+                });
+            }
+            expected_prev = entry.hash;
+        }
+
+        Ok(ChainVerification {
+            valid: true,
+            entries_checked: rows.len(),
+            first_invalid_seq: None,
+        })
+    }
+}
+
+fn row_to_entry(row: &sqlx::sqlite::SqliteRow) -> Result<AuditEntry> {
+    let action: String = row.try_get("action")?;
+    let details: Option<String> = row.try_get("details")?;
+
+    Ok(AuditEntry {
+        seq: row.try_get("seq")?,
+        id: row.try_get("id")?,
+        actor: row.try_get("actor")?,
+        action: AuditAction::parse(&action)
+            .ok_or_else(|| anyhow::anyhow!("Unknown audit action: {}", action))?,
+        target_id: row.try_get("target_id")?,
+        details: details
+            .map(|d| serde_json::from_str(&d))
+            .transpose()
+            .context("Invalid audit entry details")?,
+        created_at: row.try_get("created_at")?,
+        prev_hash: row.try_get("prev_hash")?,
+        hash: row.try_get("hash")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::{test_database, test_database_at};
+
+    async fn service() -> AuditLogService {
+        let db = test_database().await;
+        AuditLogService::new(db)
+    }
+
+    async fn seed(audit: &AuditLogService) {
+        audit
+            .record("jdoe", AuditAction::TrustDeposit, "txn-1", Some(serde_json::json!({ "amount": 5000.0 })))
+            .await
+            .unwrap();
+        audit
+            .record("jdoe", AuditAction::TrustWithdrawal, "txn-2", Some(serde_json::json!({ "amount": 1200.0 })))
+            .await
+            .unwrap();
+        audit
+            .record(SYSTEM_ACTOR, AuditAction::SettingChanged, "retention_days", None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tampered_intermediate_entry_breaks_chain() {
+        let audit = service().await;
+        seed(&audit).await;
+        assert!(audit.verify_chain().await.unwrap().valid);
+
+        // Ordinary writes are refused outright
+        assert!(sqlx::query("UPDATE audit_log SET actor = 'mallory' WHERE seq = 2")
+            .execute(&audit.db)
+            .await
+            .is_err());
+
+        // Someone editing the database file directly can bypass the triggers
+        sqlx::raw_sql(
+            "DROP TRIGGER audit_log_no_update; \
+             UPDATE audit_log SET details = '{\"amount\":12.0}' WHERE seq = 2;",
+        )
+        .execute(&audit.db)
+        .await
+        .unwrap();
+
+        let verification = audit.verify_chain().await.unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.first_invalid_seq, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_rehashed_entry_is_caught_by_next_link() {
+        let audit = service().await;
+        seed(&audit).await;
+
+        // Recomputing the forged entry's own hash still leaves entry 3 pointing at the original
+        let mut forged = audit
+            .query_audit_log(&AuditFilter { target_id: Some("txn-2".to_string()), ..Default::default() })
+            .await
+            .unwrap()
+            .remove(0);
+        forged.actor = "mallory".to_string();
+        forged.hash = forged.compute_hash();
+        sqlx::raw_sql("DROP TRIGGER audit_log_no_update;").execute(&audit.db).await.unwrap();
+        sqlx::query("UPDATE audit_log SET actor = ?, hash = ? WHERE seq = 2")
+            .bind(&forged.actor)
+            .bind(&forged.hash)
+            .execute(&audit.db)
+            .await
+            .unwrap();
+
+        let verification = audit.verify_chain().await.unwrap();
+        assert_eq!(verification.first_invalid_seq, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_by_actor_and_action() {
+        let audit = service().await;
+        seed(&audit).await;
+
+        let by_actor = audit
+            .query_audit_log(&AuditFilter { actor: Some("jdoe".to_string()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_actor.len(), 2);
+        assert_eq!(by_actor[0].target_id, "txn-2");
+
+        let settings = audit
+            .query_audit_log(&AuditFilter { action: Some(AuditAction::SettingChanged), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings[0].actor, SYSTEM_ACTOR);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_appends_keep_one_chain() {
+        // A file database, so the appends run on separate connections
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("audit.db").display());
+        let audit = AuditLogService::new(test_database_at(&url).await);
+
+        let appends = (0..8).map(|i| {
+            let audit = audit.clone();
+            tokio::spawn(async move {
+                audit.record("jdoe", AuditAction::TrustDeposit, &format!("txn-{i}"), None).await
+            })
+        });
+        for append in futures_util::future::join_all(appends).await {
+            append.unwrap().unwrap();
+        }
+
+        let verification = audit.verify_chain().await.unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.entries_checked, 8);
+    }
+}
@@ -0,0 +1,287 @@
+// Database backups - encrypted snapshots of the SQLite database.
+// The snapshot is taken online with `VACUUM INTO`, which reads through the
+// WAL inside a single read transaction, so the app keeps running and the copy
+// is consistent. It is then sealed with AES-256-GCM under a key derived from
+// the passphrase, and a manifest beside it records the checksums that
+// `restore_backup` verifies before anything is written.
+
+use crate::services::service_error::ServiceError;
+use crate::utils::crypto::calculate_sha256;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Identifies a backup file and its format version
+const MAGIC: &[u8; 8] = b"PAEDBK01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Magic, KDF iterations, salt and nonce. Authenticated along with the data.
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+
+/// PBKDF2-HMAC-SHA256 rounds for new backups (OWASP 2023 guidance)
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupManifest {
+    pub created_at: DateTime<Utc>,
+    /// Backup file name; the manifest sits beside it
+    pub file: String,
+    pub size: u64,
+    /// SHA-256 of the encrypted backup file
+    pub sha256: String,
+    /// SHA-256 of the database snapshot before encryption
+    pub database_sha256: String,
+    pub algorithm: String,
+    pub kdf: String,
+    pub kdf_iterations: u32,
+}
+
+pub struct BackupService {
+    db: SqlitePool,
+    kdf_iterations: u32,
+}
+
+impl BackupService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db, kdf_iterations: DEFAULT_KDF_ITERATIONS }
+    }
+
+    pub fn with_kdf_iterations(mut self, iterations: u32) -> Self {
+        self.kdf_iterations = iterations.max(1);
+        self
+    }
+
+    /// Snapshot the database, encrypt it to `out` and write its manifest
+    /// to `<out>.manifest.json`
+    pub async fn create_backup(&self, out: &Path, passphrase: &str) -> Result<BackupManifest> {
+        if passphrase.is_empty() {
+            return Err(ServiceError::validation("A backup passphrase is required").into());
+        }
+
+        // The unencrypted snapshot goes in a private (0700) directory beside
+        // the backup, which is removed on every path out of this function
+        let parent = out.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let snapshot_dir = tempfile::Builder::new()
+            .prefix(".backup-snapshot")
+            .tempdir_in(parent)
+            .with_context(|| format!("Failed to create snapshot directory in {}", parent.display()))?;
+        let snapshot = snapshot_dir.path().join("snapshot.db");
+        let snapshot_path = snapshot
+            .to_str()
+            .ok_or_else(|| ServiceError::validation(format!("Backup path {} is not valid UTF-8", out.display())))?;
+        sqlx::query("VACUUM INTO ?")
+            .bind(snapshot_path)
+            .execute(&self.db)
+            .await
+            .context("Failed to snapshot database")?;
+
+        // The KDF is deliberately slow, so keep it and the encryption off the
+        // async workers
+        let passphrase = passphrase.to_string();
+        let iterations = self.kdf_iterations;
+        let (sealed, database_sha256) = tokio::task::spawn_blocking(move || -> Result<(Vec<u8>, String)> {
+            let database =
+                std::fs::read(&snapshot).with_context(|| format!("Failed to read snapshot {}", snapshot.display()))?;
+            drop(snapshot_dir);
+            Ok((seal(&database, &passphrase, iterations)?, calculate_sha256(&database)))
+        })
+        .await
+        .context("Backup encryption task failed")??;
+
+        std::fs::write(out, &sealed).with_context(|| format!("Failed to write backup {}", out.display()))?;
+
+        let manifest = BackupManifest {
+            created_at: Utc::now(),
+            file: out.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+            size: sealed.len() as u64,
+            sha256: calculate_sha256(&sealed),
+            database_sha256,
+            algorithm: "AES-256-GCM".to_string(),
+            kdf: "PBKDF2-HMAC-SHA256".to_string(),
+            kdf_iterations: self.kdf_iterations,
+        };
+        let manifest_path = manifest_path(out);
+        std::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write manifest {}", manifest_path.display()))?;
+
+        info!("Backed up database to {} ({} bytes)", out.display(), manifest.size);
+        Ok(manifest)
+    }
+}
+
+/// Encrypt a database snapshot under a key derived from `passphrase`,
+/// prefixed with the header that authenticates it
+fn seal(database: &[u8], passphrase: &str, iterations: u32) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&iterations.to_be_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, iterations));
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: database, aad: &header })
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt backup"))?;
+
+    let mut sealed = header;
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Verify `backup` against its manifest, decrypt it and write the database
+/// to `target`. Nothing is written unless every check passes. The app must
+/// not have `target` open; restore to a new path and swap it in on restart.
+pub fn restore_backup(backup: &Path, passphrase: &str, target: &Path) -> Result<BackupManifest> {
+    let manifest_path = manifest_path(backup);
+    let manifest: BackupManifest = serde_json::from_slice(
+        &std::fs::read(&manifest_path).with_context(|| format!("Failed to read manifest {}", manifest_path.display()))?,
+    )
+    .context("Invalid backup manifest")?;
+    let sealed = std::fs::read(backup).with_context(|| format!("Failed to read backup {}", backup.display()))?;
+
+    if calculate_sha256(&sealed) != manifest.sha256 {
+        return Err(ServiceError::validation(format!(
+            "Backup {} does not match its manifest checksum",
+            backup.display()
+        ))
+        .into());
+    }
+    if sealed.len() < HEADER_LEN || &sealed[..MAGIC.len()] != MAGIC {
+        return Err(ServiceError::validation(format!("{} is not a database backup", backup.display())).into());
+    }
+
+    let (header, ciphertext) = sealed.split_at(HEADER_LEN);
+    let iterations = u32::from_be_bytes(header[MAGIC.len()..MAGIC.len() + 4].try_into()?);
+    let salt = &header[MAGIC.len() + 4..MAGIC.len() + 4 + SALT_LEN];
+    let nonce = Nonce::from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt, iterations));
+    // GCM authenticates the data and header, so a wrong passphrase and
+    // tampering look the same
+    let database = cipher
+        .decrypt(nonce, Payload { msg: ciphertext, aad: header })
+        .map_err(|_| ServiceError::validation("Backup could not be decrypted: wrong passphrase or corrupted file"))?;
+    if calculate_sha256(&database) != manifest.database_sha256 {
+        return Err(ServiceError::validation("Restored database does not match its manifest checksum").into());
+    }
+
+    // Write beside the target and rename so a failed write leaves no partial database
+    let staged = with_suffix(target, ".restoring");
+    std::fs::write(&staged, &database).with_context(|| format!("Failed to write {}", staged.display()))?;
+    std::fs::rename(&staged, target).with_context(|| format!("Failed to restore to {}", target.display()))?;
+
+    info!("Restored backup {} to {}", backup.display(), target.display());
+    Ok(manifest)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Key<Aes256Gcm> {
+    let mut key = Key::<Aes256Gcm>::default();
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+/// "backups/firm.bak" -> "backups/firm.bak.manifest.json"
+pub fn manifest_path(backup: &Path) -> PathBuf {
+    with_suffix(backup, ".manifest.json")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    /// A WAL-mode database with rows still in the WAL when the backup runs
+    async fn database(path: &Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let db = SqlitePool::connect_with(options).await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE matters (id TEXT PRIMARY KEY, title TEXT NOT NULL);
+             INSERT INTO matters VALUES ('matter-1', 'Byron v. Lovelace'), ('matter-2', 'Estate of Babbage');",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn matters(db: &SqlitePool) -> Vec<(String, String)> {
+        sqlx::query_as("SELECT id, title FROM matters ORDER BY id").fetch_all(db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backup_round_trips_to_identical_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = database(&dir.path().join("live.db")).await;
+        let backup = dir.path().join("firm.bak");
+
+        let manifest = BackupService::new(db.clone())
+            .with_kdf_iterations(1_000)
+            .create_backup(&backup, PASSPHRASE)
+            .await
+            .unwrap();
+        assert!(manifest_path(&backup).exists());
+        // Only the backup and its manifest are left beside the database
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with("live.db"))
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["firm.bak", "firm.bak.manifest.json"]);
+        // Encrypted: the data is not readable in the backup
+        assert!(!String::from_utf8_lossy(&std::fs::read(&backup).unwrap()).contains("Lovelace"));
+
+        let restored_path = dir.path().join("restored.db");
+        restore_backup(&backup, PASSPHRASE, &restored_path).unwrap();
+
+        assert_eq!(calculate_sha256(&std::fs::read(&restored_path).unwrap()), manifest.database_sha256);
+        let restored = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&restored_path))
+            .await
+            .unwrap();
+        assert_eq!(matters(&restored).await, matters(&db).await);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_backup_fails_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = database(&dir.path().join("live.db")).await;
+        let backup = dir.path().join("firm.bak");
+        BackupService::new(db)
+            .with_kdf_iterations(1_000)
+            .create_backup(&backup, PASSPHRASE)
+            .await
+            .unwrap();
+
+        let mut sealed = std::fs::read(&backup).unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xff;
+        std::fs::write(&backup, &sealed).unwrap();
+
+        let target = dir.path().join("restored.db");
+        let err = restore_backup(&backup, PASSPHRASE, &target).unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "validation");
+        assert!(!target.exists());
+    }
+}
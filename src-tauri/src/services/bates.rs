@@ -0,0 +1,439 @@
+// Bates numbering - stamps every page of a document set with a sequential
+// label ("SMITH000001") so each produced page can be cited unambiguously.
+// Numbering runs across the set in the order given; each stamped copy is
+// written beside its source and the original is left untouched.
+
+use crate::services::service_error::ServiceError;
+use anyhow::{Context, Result};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Resource name of the font the labels are set in
+const BATES_FONT: &str = "FBates";
+
+/// US Letter, used when a page declares no media box
+const DEFAULT_PAGE_SIZE: (f32, f32) = (612.0, 792.0);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatesPosition {
+    BottomRight,
+    BottomCenter,
+    BottomLeft,
+    TopRight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatesOptions {
+    pub position: BatesPosition,
+    /// Zero-padded width of the number
+    pub digits: usize,
+    /// Between prefix and number, e.g. "-" for "SMITH-000001"
+    pub separator: String,
+    pub font_size: f32,
+    /// Distance from the page edges, in points
+    pub margin: f32,
+    /// Appended to the source file name for the stamped copy
+    pub output_suffix: String,
+}
+
+impl Default for BatesOptions {
+    fn default() -> Self {
+        Self {
+            position: BatesPosition::BottomRight,
+            digits: 6,
+            separator: String::new(),
+            font_size: 10.0,
+            margin: 24.0,
+            output_suffix: "_bates".to_string(),
+        }
+    }
+}
+
+impl BatesOptions {
+    pub fn label(&self, prefix: &str, number: u64) -> Result<String> {
+        bates_label(&format!("{}{}", prefix, self.separator), number, self.digits)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatesStamped {
+    pub source: PathBuf,
+    pub output: PathBuf,
+    pub page_count: usize,
+    pub first_number: u64,
+    pub last_number: u64,
+    pub bates_begin: String,
+    pub bates_end: String,
+}
+
+/// "ABC", 42, 6 -> "ABC000042". Errors when the number needs more digits.
+pub fn bates_label(prefix: &str, number: u64, digits: usize) -> Result<String> {
+    let label = format!("{}{:0width$}", prefix, number, width = digits);
+    if label.len() > prefix.len() + digits {
+        return Err(ServiceError::validation(format!(
+            "Bates number {} does not fit in {} digits",
+            number, digits
+        ))
+        .into());
+    }
+    Ok(label)
+}
+
+/// Stamp `docs` with Bates numbers starting at `start`, using the default
+/// position and format
+pub fn apply_bates(docs: &[PathBuf], prefix: &str, start: u64) -> Result<Vec<BatesStamped>> {
+    apply_bates_with(docs, prefix, start, &BatesOptions::default())
+}
+
+pub fn apply_bates_with(
+    docs: &[PathBuf],
+    prefix: &str,
+    start: u64,
+    options: &BatesOptions,
+) -> Result<Vec<BatesStamped>> {
+    let mut next = start;
+    let mut stamped = Vec::with_capacity(docs.len());
+
+    for source in docs {
+        let mut doc = Document::load(source).with_context(|| format!("Failed to open PDF {}", source.display()))?;
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+
+        let first_number = next;
+        let mut labels = Vec::new();
+        for (_, page_id) in doc.get_pages() {
+            let label = options.label(prefix, next)?;
+            stamp_page(&mut doc, page_id, font_id, &label, options)
+                .with_context(|| format!("Failed to stamp {} on {}", label, source.display()))?;
+            labels.push(label);
+            next += 1;
+        }
+
+        let (Some(bates_begin), Some(bates_end)) = (labels.first().cloned(), labels.last().cloned()) else {
+            return Err(ServiceError::validation(format!("{} has no pages", source.display())).into());
+        };
+
+        let output = stamped_path(source, &options.output_suffix);
+        doc.save(&output)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        info!("Bates stamped {} as {}-{}", source.display(), bates_begin, bates_end);
+
+        stamped.push(BatesStamped {
+            source: source.clone(),
+            output,
+            page_count: labels.len(),
+            first_number,
+            last_number: next - 1,
+            bates_begin,
+            bates_end,
+        });
+    }
+    Ok(stamped)
+}
+
+fn stamp_page(doc: &mut Document, page_id: ObjectId, font_id: ObjectId, label: &str, options: &BatesOptions) -> Result<()> {
+    add_font(doc, page_id, font_id)?;
+
+    // Positions are worked out on the page as displayed, then mapped back
+    // through its rotation so the label sits in the chosen corner upright
+    let frame = page_frame(doc, page_id);
+    let (width, height) = frame.displayed_size();
+    // Helvetica digits and capitals average about half the font size wide
+    let text_width = label.len() as f32 * options.font_size * 0.5;
+    let x = match options.position {
+        BatesPosition::BottomLeft => options.margin,
+        BatesPosition::BottomCenter => (width - text_width) / 2.0,
+        BatesPosition::BottomRight | BatesPosition::TopRight => width - options.margin - text_width,
+    };
+    let y = match options.position {
+        BatesPosition::TopRight => height - options.margin - options.font_size,
+        _ => options.margin,
+    };
+
+    let content = Content {
+        operations: vec![
+            Operation::new("q", vec![]),
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![BATES_FONT.into(), options.font_size.into()]),
+            Operation::new("Tm", frame.text_matrix(x, y).into_iter().map(Object::from).collect()),
+            Operation::new("Tj", vec![Object::string_literal(label)]),
+            Operation::new("ET", vec![]),
+            Operation::new("Q", vec![]),
+        ],
+    };
+    append_isolated(doc, page_id, content.encode()?)?;
+    Ok(())
+}
+
+/// Append `stamp` to the page, with the page's own content wrapped in q/Q
+/// so a transform or colour it leaves set cannot move or hide the label
+fn append_isolated(doc: &mut Document, page_id: ObjectId, stamp: Vec<u8>) -> Result<()> {
+    let existing: Vec<Object> = match doc.get_dictionary(page_id)?.get(b"Contents") {
+        Ok(Object::Reference(id)) => match doc.get_object(*id)? {
+            Object::Array(streams) => streams.clone(),
+            _ => vec![Object::Reference(*id)],
+        },
+        Ok(Object::Array(streams)) => streams.clone(),
+        _ => Vec::new(),
+    };
+
+    let open = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+    // The newline ends whatever token the page's last stream stopped on
+    let mut close = b"\nQ\n".to_vec();
+    close.extend(stamp);
+    let close = doc.add_object(Stream::new(Dictionary::new(), close));
+
+    let mut contents = Vec::with_capacity(existing.len() + 2);
+    contents.push(Object::Reference(open));
+    contents.extend(existing);
+    contents.push(Object::Reference(close));
+    doc.get_object_mut(page_id)?.as_dict_mut()?.set("Contents", contents);
+    Ok(())
+}
+
+/// Register the label font on the page. Resources the page inherits from
+/// its page tree are copied onto it first, so they stay visible.
+fn add_font(doc: &mut Document, page_id: ObjectId, font_id: ObjectId) -> Result<()> {
+    let mut resources = match inherited(doc, page_id, b"Resources") {
+        Some(Object::Reference(id)) => doc.get_dictionary(id)?.clone(),
+        Some(Object::Dictionary(resources)) => resources,
+        _ => Dictionary::new(),
+    };
+    let mut fonts = match resources.get(b"Font") {
+        Ok(Object::Reference(id)) => doc.get_dictionary(*id)?.clone(),
+        Ok(Object::Dictionary(fonts)) => fonts.clone(),
+        _ => Dictionary::new(),
+    };
+    fonts.set(BATES_FONT, font_id);
+    resources.set("Font", fonts);
+
+    doc.get_object_mut(page_id)?.as_dict_mut()?.set("Resources", resources);
+    Ok(())
+}
+
+/// The visible area of a page and how far it is turned when displayed
+struct PageFrame {
+    x0: f32,
+    y0: f32,
+    width: f32,
+    height: f32,
+    /// Clockwise degrees: 0, 90, 180 or 270
+    rotate: i64,
+}
+
+impl PageFrame {
+    /// Width and height as the page is displayed
+    fn displayed_size(&self) -> (f32, f32) {
+        match self.rotate {
+            90 | 270 => (self.height, self.width),
+            _ => (self.width, self.height),
+        }
+    }
+
+    /// Text matrix for upright text at (x, y), measured from the bottom-left
+    /// corner of the page as displayed
+    fn text_matrix(&self, x: f32, y: f32) -> [f32; 6] {
+        let (width, height) = (self.width, self.height);
+        let (a, b, c, d, dx, dy) = match self.rotate {
+            90 => (0.0, 1.0, -1.0, 0.0, width - y, x),
+            180 => (-1.0, 0.0, 0.0, -1.0, width - x, height - y),
+            270 => (0.0, -1.0, 1.0, 0.0, y, height - x),
+            _ => (1.0, 0.0, 0.0, 1.0, x, y),
+        };
+        [a, b, c, d, self.x0 + dx, self.y0 + dy]
+    }
+}
+
+/// The page's crop box, which is what viewers and printers show, falling
+/// back to its media box
+fn page_frame(doc: &Document, page_id: ObjectId) -> PageFrame {
+    let (x0, y0, width, height) = page_box(doc, page_id, b"CropBox")
+        .or_else(|| page_box(doc, page_id, b"MediaBox"))
+        .unwrap_or((0.0, 0.0, DEFAULT_PAGE_SIZE.0, DEFAULT_PAGE_SIZE.1));
+    let rotate = match inherited(doc, page_id, b"Rotate") {
+        Some(Object::Integer(degrees)) if degrees % 90 == 0 => degrees.rem_euclid(360),
+        _ => 0,
+    };
+    PageFrame { x0, y0, width, height, rotate }
+}
+
+/// (left, bottom, width, height) of the page box `key`
+fn page_box(doc: &Document, page_id: ObjectId, key: &[u8]) -> Option<(f32, f32, f32, f32)> {
+    let bounds = match inherited(doc, page_id, key)? {
+        Object::Reference(id) => doc.get_object(id).ok()?.clone(),
+        other => other,
+    };
+    let Object::Array(bounds) = bounds else {
+        return None;
+    };
+    let numbers: Vec<f32> = bounds.iter().filter_map(number).collect();
+    match numbers[..] {
+        [x0, y0, x1, y1] => Some((x0.min(x1), y0.min(y1), (x1 - x0).abs(), (y1 - y0).abs())),
+        _ => None,
+    }
+}
+
+/// `key` from the page, or from the nearest ancestor in the page tree that has it
+fn inherited(doc: &Document, page_id: ObjectId, key: &[u8]) -> Option<Object> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    loop {
+        if let Ok(value) = node.get(key) {
+            return Some(value.clone());
+        }
+        let parent = node.get(b"Parent").ok()?.as_reference().ok()?;
+        node = doc.get_dictionary(parent).ok()?;
+    }
+}
+
+fn number(object: &Object) -> Option<f32> {
+    match object {
+        Object::Integer(i) => Some(*i as f32),
+        Object::Real(r) => Some(*r as f32),
+        _ => None,
+    }
+}
+
+/// "exhibits/a.pdf" -> "exhibits/a_bates.pdf"
+fn stamped_path(source: &Path, suffix: &str) -> PathBuf {
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("document");
+    source.with_file_name(format!("{}{}.pdf", stem, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A PDF whose pages inherit their font and media box from the page tree
+    fn write_pdf(path: &Path, pages: usize) {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let kids: Vec<Object> = (1..=pages)
+            .map(|n| {
+                let content = Content {
+                    operations: vec![
+                        Operation::new("BT", vec![]),
+                        Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                        Operation::new("Td", vec![72.into(), 720.into()]),
+                        Operation::new("Tj", vec![Object::string_literal(format!("Page {}", n))]),
+                        Operation::new("ET", vec![]),
+                    ],
+                };
+                let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => pages as i64,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_documents_get_contiguous_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let complaint = dir.path().join("complaint.pdf");
+        let exhibit = dir.path().join("exhibit.pdf");
+        write_pdf(&complaint, 3);
+        write_pdf(&exhibit, 2);
+
+        let stamped = apply_bates(&[complaint.clone(), exhibit], "PREFIX", 1).unwrap();
+
+        assert_eq!(stamped[0].bates_begin, "PREFIX000001");
+        assert_eq!(stamped[0].bates_end, "PREFIX000003");
+        assert_eq!(stamped[1].bates_begin, "PREFIX000004");
+        assert_eq!(stamped[1].bates_end, "PREFIX000005");
+        assert_eq!(stamped[1].last_number, 5);
+        assert_eq!(stamped[0].output, dir.path().join("complaint_bates.pdf"));
+
+        let output = Document::load(&stamped[0].output).unwrap();
+        let pages: Vec<ObjectId> = output.get_pages().into_values().collect();
+        let last_page = String::from_utf8_lossy(&output.get_page_content(pages[2]).unwrap()).into_owned();
+        assert!(last_page.contains("(PREFIX000003) Tj"));
+        // The original page content is still there
+        assert!(last_page.contains("(Page 3) Tj"));
+
+        // The source is untouched
+        let source = Document::load(&complaint).unwrap();
+        let source_page = source.get_pages()[&1];
+        let original = String::from_utf8_lossy(&source.get_page_content(source_page).unwrap()).into_owned();
+        assert!(!original.contains("PREFIX"));
+    }
+
+    #[test]
+    fn test_label_follows_crop_box_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let scan = dir.path().join("scan.pdf");
+        write_pdf(&scan, 1);
+        let mut doc = Document::load(&scan).unwrap();
+        let page_id = doc.get_pages()[&1];
+        let page = doc.get_object_mut(page_id).unwrap().as_dict_mut().unwrap();
+        page.set("Rotate", 90);
+        page.set("CropBox", vec![36.into(), 36.into(), 576.into(), 756.into()]);
+        doc.save(&scan).unwrap();
+
+        let stamped = apply_bates(&[scan], "ABC", 1).unwrap();
+
+        let output = Document::load(&stamped[0].output).unwrap();
+        let page_id = output.get_pages()[&1];
+        let operations = Content::decode(&output.get_page_content(page_id).unwrap()).unwrap().operations;
+        // The page's own content is wrapped so its state cannot leak into the label
+        assert_eq!(operations[0].operator, "q");
+        let stamp = operations
+            .iter()
+            .position(|op| op.operator == "Tf" && op.operands.first().and_then(|o| o.as_name_str().ok()) == Some(BATES_FONT))
+            .unwrap();
+        let wrapped: Vec<&str> = operations[stamp - 3..stamp].iter().map(|op| op.operator.as_str()).collect();
+        assert_eq!(wrapped, vec!["Q", "q", "BT"]);
+
+        // Displayed 720 x 540: bottom right is 24pt in from the displayed
+        // right edge, which is the crop box's top
+        let matrix: Vec<f32> = operations[stamp + 1].operands.iter().filter_map(number).collect();
+        let label_width = 9.0 * 10.0 * 0.5;
+        assert_eq!(matrix, vec![0.0, 1.0, -1.0, 0.0, 36.0 + 540.0 - 24.0, 36.0 + 720.0 - 24.0 - label_width]);
+    }
+
+    #[test]
+    fn test_label_format_is_configurable() {
+        let options = BatesOptions {
+            separator: "-".to_string(),
+            digits: 4,
+            ..BatesOptions::default()
+        };
+        assert_eq!(options.label("SMITH", 12).unwrap(), "SMITH-0012");
+        assert!(options.label("SMITH", 12_345).is_err());
+    }
+}
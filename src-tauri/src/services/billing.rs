@@ -1,14 +1,27 @@
 // Billing Service - Invoice generation, payment processing, and trust accounting
 // Supports Stripe/LawPay integration and IOLTA compliance
 
+use crate::config::NumberingScheme;
+use crate::services::audit_log::{AuditAction, AuditLogService};
+use crate::services::currency::{self, Currency, ExchangeRateSource};
+use crate::services::database::Conflict;
+use crate::services::numbering;
+use crate::services::receipts::{self, OcrEngine, ParsedReceipt};
+use crate::services::service_error::ServiceError;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use utoipa::ToSchema;
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments};
+use sqlx::{Row, SqliteExecutor, SqlitePool};
+use tracing::error;
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum InvoiceStatus {
     Draft,
     Pending,
@@ -43,7 +56,7 @@ pub enum PaymentStatus {
     Disputed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Invoice {
     pub id: String,
     pub invoice_number: String,
@@ -63,7 +76,9 @@ pub struct Invoice {
     pub expenses: Vec<InvoiceExpense>,
     pub adjustments: Vec<InvoiceAdjustment>,
 
-    // Amounts
+    // Amounts, all in the invoice currency
+    #[serde(default)]
+    pub currency: Currency,
     pub subtotal: f64,
     pub discount_amount: f64,
     pub tax_amount: f64,
@@ -83,9 +98,12 @@ pub struct Invoice {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub created_by: String,
+    /// Bumped on every save; a save against a stale version is rejected
+    #[serde(default)]
+    pub version: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceTimeEntry {
     pub time_entry_id: String,
     pub date: DateTime<Utc>,
@@ -94,19 +112,23 @@ pub struct InvoiceTimeEntry {
     pub hours: f64,
     pub rate: f64,
     pub amount: f64,
+    #[serde(default)]
+    pub currency: Currency,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceExpense {
     pub expense_id: String,
     pub date: DateTime<Utc>,
     pub description: String,
     pub category: String,
     pub amount: f64,
+    #[serde(default)]
+    pub currency: Currency,
     pub is_reimbursable: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceAdjustment {
     pub description: String,
     pub amount: f64,
@@ -122,6 +144,8 @@ pub struct Payment {
 
     // Payment details
     pub amount: f64,
+    #[serde(default)]
+    pub currency: Currency,
     pub payment_method: PaymentMethod,
     pub payment_date: DateTime<Utc>,
     pub reference_number: Option<String>,
@@ -195,6 +219,50 @@ pub enum ExpenseStatus {
     Reimbursed,
 }
 
+impl ExpenseStatus {
+    /// Pending -> Approved or Rejected, Approved -> Billed -> Reimbursed
+    pub fn can_transition_to(&self, next: &ExpenseStatus) -> bool {
+        use ExpenseStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Approved) | (Pending, Rejected) | (Approved, Billed) | (Billed, Reimbursed)
+        )
+    }
+}
+
+/// An expense asked to move to a status its lifecycle does not allow
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("Expense {expense_id} cannot move from {from:?} to {to:?}")]
+pub struct InvalidExpenseTransition {
+    pub expense_id: String,
+    pub from: ExpenseStatus,
+    pub to: ExpenseStatus,
+}
+
+impl Expense {
+    /// Move to `next`, stamping the matching timestamp. The expense is left
+    /// unchanged when the transition is not allowed.
+    pub fn transition_to(&mut self, next: ExpenseStatus, now: DateTime<Utc>) -> Result<(), InvalidExpenseTransition> {
+        if !self.status.can_transition_to(&next) {
+            return Err(InvalidExpenseTransition {
+                expense_id: self.id.clone(),
+                from: self.status.clone(),
+                to: next,
+            });
+        }
+
+        match next {
+            ExpenseStatus::Approved => self.approved_at = Some(now),
+            ExpenseStatus::Billed => self.billed_at = Some(now),
+            ExpenseStatus::Reimbursed => self.reimbursed_at = Some(now),
+            ExpenseStatus::Pending | ExpenseStatus::Rejected => {}
+        }
+        self.status = next;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
 // ============= Trust Accounting (IOLTA Compliance) =============
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -206,6 +274,7 @@ pub enum TrustTransactionType {
     Interest,          // Interest earned (IOLTA)
     Fee_transfer,      // Transfer to operating account for earned fees
     Refund,            // Refund to client
+    Remittance,        // IOLTA interest swept to the bar foundation
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -275,6 +344,12 @@ pub struct TrustReconciliation {
     // Transactions
     pub unreconciled_deposits: Vec<TrustTransaction>,
     pub unreconciled_withdrawals: Vec<TrustTransaction>,
+    /// Book transactions cleared by this statement
+    #[serde(default)]
+    pub matched_transaction_ids: Vec<String>,
+    /// Statement lines with no matching book transaction
+    #[serde(default)]
+    pub unmatched_statement_lines: Vec<BankStatementLine>,
 
     // Status
     pub is_reconciled: bool,
@@ -283,6 +358,47 @@ pub struct TrustReconciliation {
     pub created_by: String,
 }
 
+/// One line of a bank statement for a trust account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BankStatementLine {
+    pub date: DateTime<Utc>,
+    /// Positive for credits, negative for debits, as in the book
+    pub amount: f64,
+    pub description: String,
+}
+
+/// How long a book entry may take to clear the bank and still match
+pub const STATEMENT_MATCH_WINDOW_DAYS: i64 = 5;
+
+/// Dates covered by an interest posting or bank statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// IOLTA interest swept from the account to the bar foundation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IoltaRemittance {
+    pub id: String,
+    pub trust_account_id: String,
+    pub interest_transaction_id: String,
+    pub remittance_transaction_id: String,
+    pub amount: f64,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub remitted_to: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestPosting {
+    pub interest: TrustTransaction,
+    /// Present for IOLTA accounts, whose interest never belongs to a client
+    pub remittance: Option<IoltaRemittance>,
+}
+
 // ============= Payment Processing Integration =============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -309,11 +425,38 @@ pub struct PaymentIntent {
 
 pub struct BillingService {
     db: SqlitePool,
+    audit: AuditLogService,
+    numbering: NumberingScheme,
+    ocr: Option<Arc<dyn OcrEngine>>,
+    iolta_foundation: Option<String>,
 }
 
 impl BillingService {
     pub fn new(db: SqlitePool) -> Self {
-        Self { db }
+        Self {
+            audit: AuditLogService::new(db.clone()),
+            db,
+            numbering: NumberingScheme::default(),
+            ocr: None,
+            iolta_foundation: None,
+        }
+    }
+
+    pub fn with_numbering(mut self, numbering: NumberingScheme) -> Self {
+        self.numbering = numbering;
+        self
+    }
+
+    pub fn with_ocr_engine(mut self, ocr: Arc<dyn OcrEngine>) -> Self {
+        self.ocr = Some(ocr);
+        self
+    }
+
+    /// The bar foundation IOLTA interest is remitted to; interest cannot be
+    /// posted to an IOLTA account without one
+    pub fn with_iolta_foundation(mut self, foundation: impl Into<String>) -> Self {
+        self.iolta_foundation = Some(foundation.into());
+        self
     }
 
     // ============= Invoice Management =============
@@ -347,12 +490,15 @@ impl BillingService {
         // Fetch expenses
         let expenses = self.fetch_expenses_for_invoice(&expense_ids).await?;
 
+        // An invoice is billed in a single currency
+        let currency = line_item_currency(&time_entries, &expenses)?;
+
         // Calculate totals
         let time_total: f64 = time_entries.iter().map(|e| e.amount).sum();
         let expense_total: f64 = expenses.iter().map(|e| e.amount).sum();
         let subtotal = time_total + expense_total;
 
-        let invoice = Invoice {
+        let mut invoice = Invoice {
             id: invoice_id.clone(),
             invoice_number,
             matter_id: matter_id.to_string(),
@@ -366,6 +512,7 @@ impl BillingService {
             time_entries,
             expenses,
             adjustments: Vec::new(),
+            currency,
             subtotal,
             discount_amount: 0.0,
             tax_amount: 0.0,
@@ -381,9 +528,10 @@ impl BillingService {
             created_at: now,
             updated_at: now,
             created_by: created_by.to_string(),
+            version: 0,
         };
 
-        self.save_invoice(&invoice).await?;
+        self.save_invoice(&mut invoice).await?;
 
         // Mark time entries and expenses as billed
         self.mark_time_entries_billed(&time_entry_ids, &invoice.id).await?;
@@ -433,7 +581,7 @@ impl BillingService {
         invoice.balance = invoice.total - invoice.amount_paid;
         invoice.updated_at = Utc::now();
 
-        self.save_invoice(&invoice).await?;
+        self.save_invoice(&mut invoice).await?;
 
         Ok(invoice)
     }
@@ -447,7 +595,7 @@ impl BillingService {
             invoice.sent_at = Some(Utc::now());
             invoice.updated_at = Utc::now();
 
-            self.save_invoice(&invoice).await?;
+            self.save_invoice(&mut invoice).await?;
 
             // TODO: Send email to client
             // self.send_invoice_email(&invoice).await?;
@@ -465,7 +613,7 @@ impl BillingService {
             invoice.status = InvoiceStatus::Viewed;
             invoice.updated_at = Utc::now();
 
-            self.save_invoice(&invoice).await?;
+            self.save_invoice(&mut invoice).await?;
         }
 
         Ok(invoice)
@@ -476,13 +624,13 @@ impl BillingService {
         let mut invoice = self.get_invoice(invoice_id).await?;
 
         if invoice.status == InvoiceStatus::Paid {
-            return Err(anyhow::anyhow!("Cannot cancel paid invoice"));
+            return Err(ServiceError::conflict("Cannot cancel paid invoice").into());
         }
 
         invoice.status = InvoiceStatus::Cancelled;
         invoice.updated_at = Utc::now();
 
-        self.save_invoice(&invoice).await?;
+        self.save_invoice(&mut invoice).await?;
 
         // Unmark time entries and expenses as billed
         let time_entry_ids: Vec<String> = invoice.time_entries.iter()
@@ -514,11 +662,11 @@ impl BillingService {
         let invoice = self.get_invoice(invoice_id).await?;
 
         if amount <= 0.0 {
-            return Err(anyhow::anyhow!("Payment amount must be positive"));
+            return Err(ServiceError::validation("Payment amount must be positive").into());
         }
 
         if amount > invoice.balance {
-            return Err(anyhow::anyhow!("Payment amount exceeds invoice balance"));
+            return Err(ServiceError::validation("Payment amount exceeds invoice balance").into());
         }
 
         let payment_id = Uuid::new_v4().to_string();
@@ -529,6 +677,7 @@ impl BillingService {
             matter_id: invoice.matter_id.clone(),
             client_id: invoice.client_id.clone(),
             amount,
+            currency: invoice.currency,
             payment_method,
             payment_date,
             reference_number,
@@ -552,6 +701,8 @@ impl BillingService {
             self.create_trust_withdrawal_for_payment(&payment).await?;
         }
 
+        self.audit_payment(AuditAction::PaymentRecorded, &payment).await;
+
         Ok(payment)
     }
 
@@ -573,6 +724,7 @@ impl BillingService {
             matter_id: invoice.matter_id.clone(),
             client_id: invoice.client_id.clone(),
             amount,
+            currency: invoice.currency,
             payment_method: PaymentMethod::Stripe,
             payment_date: Utc::now(),
             reference_number: Some(payment_method_id.to_string()),
@@ -591,6 +743,8 @@ impl BillingService {
         // Simulate successful processing
         self.complete_payment(&payment.id).await?;
 
+        self.audit_payment(AuditAction::PaymentProcessed, &payment).await;
+
         Ok(payment)
     }
 
@@ -612,6 +766,7 @@ impl BillingService {
             matter_id: invoice.matter_id.clone(),
             client_id: invoice.client_id.clone(),
             amount,
+            currency: invoice.currency,
             payment_method: PaymentMethod::LawPay,
             payment_date: Utc::now(),
             reference_number: Some(payment_method_id.to_string()),
@@ -630,9 +785,26 @@ impl BillingService {
         // Simulate successful processing
         self.complete_payment(&payment.id).await?;
 
+        self.audit_payment(AuditAction::PaymentProcessed, &payment).await;
+
         Ok(payment)
     }
 
+    /// Runs once the payment is committed, so a failed audit write is logged
+    /// rather than reported as a failed payment
+    async fn audit_payment(&self, action: AuditAction, payment: &Payment) {
+        let details = serde_json::json!({
+            "invoice_id": payment.invoice_id,
+            "amount": payment.amount,
+            "currency": payment.currency,
+            "payment_method": payment.payment_method,
+            "from_trust_account": payment.from_trust_account,
+        });
+        if let Err(e) = self.audit.record(&payment.created_by, action, &payment.id, Some(details)).await {
+            error!("Failed to audit payment {}: {}", payment.id, e);
+        }
+    }
+
     async fn complete_payment(&self, payment_id: &str) -> Result<Payment> {
         let mut payment = self.get_payment(payment_id).await?;
         payment.status = PaymentStatus::Completed;
@@ -658,7 +830,7 @@ impl BillingService {
 
         invoice.updated_at = Utc::now();
 
-        self.save_invoice(&invoice).await?;
+        self.save_invoice(&mut invoice).await?;
 
         Ok(())
     }
@@ -697,10 +869,12 @@ impl BillingService {
             created_by: created_by.to_string(),
         };
 
-        self.save_trust_transaction(&transaction).await?;
+        Self::save_trust_transaction(&self.db, &transaction).await?;
 
         // Update trust account balance
-        self.update_trust_account_balance(trust_account_id, amount).await?;
+        Self::update_trust_account_balance(&self.db, trust_account_id, amount).await?;
+
+        self.audit_trust_transaction(AuditAction::TrustDeposit, &transaction).await;
 
         Ok(transaction)
     }
@@ -719,7 +893,7 @@ impl BillingService {
         // Check sufficient balance
         let client_balance = self.get_client_trust_balance(client_id, matter_id).await?;
         if client_balance < amount {
-            return Err(anyhow::anyhow!("Insufficient trust balance for client"));
+            return Err(ServiceError::conflict("Insufficient trust balance for client").into());
         }
 
         let transaction_id = Uuid::new_v4().to_string();
@@ -743,10 +917,12 @@ impl BillingService {
             created_by: created_by.to_string(),
         };
 
-        self.save_trust_transaction(&transaction).await?;
+        Self::save_trust_transaction(&self.db, &transaction).await?;
 
         // Update trust account balance
-        self.update_trust_account_balance(trust_account_id, -amount).await?;
+        Self::update_trust_account_balance(&self.db, trust_account_id, -amount).await?;
+
+        self.audit_trust_transaction(AuditAction::TrustWithdrawal, &transaction).await;
 
         Ok(transaction)
     }
@@ -774,11 +950,170 @@ impl BillingService {
             created_by: payment.created_by.clone(),
         };
 
-        self.save_trust_transaction(&transaction).await?;
+        Self::save_trust_transaction(&self.db, &transaction).await?;
+
+        self.audit_trust_transaction(AuditAction::TrustWithdrawal, &transaction).await;
 
         Ok(transaction)
     }
 
+    /// Like `audit_payment`, runs after the ledger write has committed
+    async fn audit_trust_transaction(&self, action: AuditAction, transaction: &TrustTransaction) {
+        let details = trust_audit_details(transaction);
+        if let Err(e) = self.audit.record(&transaction.created_by, action, &transaction.id, Some(details)).await {
+            error!("Failed to audit trust transaction {}: {}", transaction.id, e);
+        }
+    }
+
+    /// Post the bank's interest for `period`. Interest is an account-level
+    /// entry, never credited to a client ledger; on an IOLTA account it is
+    /// swept straight to the bar foundation and the remittance recorded.
+    /// The ledger rows, remittance and audit entries commit together.
+    pub async fn post_interest(
+        &self,
+        trust_account_id: &str,
+        amount: f64,
+        period: StatementPeriod,
+        created_by: &str,
+    ) -> Result<InterestPosting> {
+        if amount <= 0.0 {
+            return Err(ServiceError::validation("Interest amount must be positive").into());
+        }
+        if period.end <= period.start {
+            return Err(ServiceError::validation("Interest period must end after it starts").into());
+        }
+        let account = self.get_trust_account(trust_account_id).await?;
+        if !account.is_active {
+            return Err(ServiceError::conflict(format!("Trust account {} is closed", account.account_name)).into());
+        }
+        let foundation = if account.account_type.eq_ignore_ascii_case("IOLTA") {
+            let foundation = self
+                .iolta_foundation
+                .clone()
+                .context("No IOLTA foundation configured to remit interest to")?;
+            Some(foundation)
+        } else {
+            None
+        };
+
+        // Immediate, so a concurrent posting for the same period waits and
+        // then finds this one
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let already_posted: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trust_transactions
+             WHERE trust_account_id = ? AND transaction_type = 'Interest' AND transaction_date = ?",
+        )
+        .bind(trust_account_id)
+        .bind(period.end)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to check posted interest")?;
+        if already_posted > 0 {
+            return Err(ServiceError::conflict(format!(
+                "Interest for {} to {} has already been posted",
+                period.start.format("%Y-%m-%d"),
+                period.end.format("%Y-%m-%d")
+            ))
+            .into());
+        }
+
+        let now = Utc::now();
+        let account_entry = |transaction_type, amount, description: String| TrustTransaction {
+            id: Uuid::new_v4().to_string(),
+            trust_account_id: trust_account_id.to_string(),
+            matter_id: String::new(),
+            client_id: String::new(),
+            transaction_type,
+            transaction_date: period.end,
+            amount,
+            description,
+            reference_number: None,
+            is_reconciled: false,
+            reconciled_at: None,
+            bank_statement_date: None,
+            invoice_id: None,
+            payment_id: None,
+            created_at: now,
+            created_by: created_by.to_string(),
+        };
+
+        let interest = account_entry(
+            TrustTransactionType::Interest,
+            amount,
+            format!(
+                "Interest {} to {}",
+                period.start.format("%Y-%m-%d"),
+                period.end.format("%Y-%m-%d")
+            ),
+        );
+        Self::save_trust_transaction(&mut *tx, &interest).await?;
+        Self::update_trust_account_balance(&mut *tx, trust_account_id, amount).await?;
+        AuditLogService::record_in(
+            &mut *tx,
+            created_by,
+            AuditAction::TrustInterest,
+            &interest.id,
+            Some(trust_audit_details(&interest)),
+        )
+        .await?;
+
+        let Some(foundation) = foundation else {
+            tx.commit().await?;
+            return Ok(InterestPosting { interest, remittance: None });
+        };
+
+        let sweep = account_entry(
+            TrustTransactionType::Remittance,
+            -amount,
+            format!("IOLTA interest remitted to {}", foundation),
+        );
+        Self::save_trust_transaction(&mut *tx, &sweep).await?;
+        Self::update_trust_account_balance(&mut *tx, trust_account_id, -amount).await?;
+        AuditLogService::record_in(
+            &mut *tx,
+            created_by,
+            AuditAction::TrustWithdrawal,
+            &sweep.id,
+            Some(trust_audit_details(&sweep)),
+        )
+        .await?;
+
+        let remittance = IoltaRemittance {
+            id: Uuid::new_v4().to_string(),
+            trust_account_id: trust_account_id.to_string(),
+            interest_transaction_id: interest.id.clone(),
+            remittance_transaction_id: sweep.id.clone(),
+            amount,
+            period_start: period.start,
+            period_end: period.end,
+            remitted_to: foundation,
+            created_at: now,
+            created_by: created_by.to_string(),
+        };
+        sqlx::query(
+            "INSERT INTO iolta_remittances
+             (id, trust_account_id, interest_transaction_id, remittance_transaction_id, amount,
+              period_start, period_end, remitted_to, created_at, created_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&remittance.id)
+        .bind(&remittance.trust_account_id)
+        .bind(&remittance.interest_transaction_id)
+        .bind(&remittance.remittance_transaction_id)
+        .bind(remittance.amount)
+        .bind(remittance.period_start)
+        .bind(remittance.period_end)
+        .bind(&remittance.remitted_to)
+        .bind(remittance.created_at)
+        .bind(&remittance.created_by)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record IOLTA remittance")?;
+        tx.commit().await?;
+
+        Ok(InterestPosting { interest, remittance: Some(remittance) })
+    }
+
     /// Get client trust balance
     pub async fn get_client_trust_balance(&self, client_id: &str, matter_id: &str) -> Result<f64> {
         let result = sqlx::query!(
@@ -830,24 +1165,65 @@ impl BillingService {
         trust_account_id: &str,
         statement_date: DateTime<Utc>,
         statement_balance: f64,
+    ) -> Result<TrustReconciliation> {
+        self.reconcile_bank_statement(trust_account_id, statement_date, statement_balance, &[], "system")
+            .await
+    }
+
+    /// Match the statement's lines to unreconciled book transactions and run
+    /// the three-way check. The matches are marked cleared as of
+    /// `statement_date` only when the statement reconciles; what is left
+    /// unmatched on either side is reported as an exception.
+    pub async fn reconcile_bank_statement(
+        &self,
+        trust_account_id: &str,
+        statement_date: DateTime<Utc>,
+        statement_balance: f64,
+        statement_lines: &[BankStatementLine],
+        created_by: &str,
     ) -> Result<TrustReconciliation> {
         let reconciliation_id = Uuid::new_v4().to_string();
 
+        // Immediate, so no transaction is posted between the balance check
+        // and marking the matches cleared
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+
+        let outstanding = Self::get_unreconciled_transactions(&mut *tx, trust_account_id).await?;
+        let matches = match_statement_lines(statement_lines, &outstanding);
+
+        let mut matched_transaction_ids = Vec::new();
+        let mut unmatched_statement_lines = Vec::new();
+        for (line, matched) in statement_lines.iter().zip(&matches) {
+            match matched {
+                Some(index) => matched_transaction_ids.push(outstanding[*index].id.clone()),
+                None => unmatched_statement_lines.push(line.clone()),
+            }
+        }
+
         // Get book balance from transactions
-        let book_balance = self.get_trust_account_book_balance(trust_account_id).await?;
+        let book_balance = Self::get_trust_account_book_balance(&mut *tx, trust_account_id).await?;
 
         // Get sum of all client balances
-        let client_balances_sum = self.get_client_balances_sum().await?;
+        let client_balances_sum = Self::get_client_balances_sum(&mut *tx).await?;
 
         // Check three-way reconciliation
         let difference = (statement_balance - book_balance).abs();
-        let is_reconciled = difference < 0.01 && (book_balance - client_balances_sum).abs() < 0.01;
+        let is_reconciled = difference < 0.01
+            && (book_balance - client_balances_sum).abs() < 0.01
+            && unmatched_statement_lines.is_empty();
 
-        // Get unreconciled transactions
-        let unreconciled = self.get_unreconciled_transactions(trust_account_id).await?;
+        let now = Utc::now();
+        if is_reconciled {
+            for transaction_id in &matched_transaction_ids {
+                Self::mark_trust_transaction_reconciled(&mut *tx, transaction_id, statement_date, now).await?;
+            }
+        }
 
-        let (deposits, withdrawals): (Vec<TrustTransaction>, Vec<TrustTransaction>) =
-            unreconciled.into_iter().partition(|t| t.amount > 0.0);
+        // Transactions still not on a reconciled statement
+        let (deposits, withdrawals): (Vec<TrustTransaction>, Vec<TrustTransaction>) = outstanding
+            .into_iter()
+            .filter(|t| !(is_reconciled && matched_transaction_ids.contains(&t.id)))
+            .partition(|t| t.amount > 0.0);
 
         let reconciliation = TrustReconciliation {
             id: reconciliation_id,
@@ -862,21 +1238,80 @@ impl BillingService {
             is_reconciled,
             notes: if !is_reconciled {
                 Some(format!(
-                    "Reconciliation failed. Statement: ${:.2}, Book: ${:.2}, Clients: ${:.2}",
-                    statement_balance, book_balance, client_balances_sum
+                    "Reconciliation failed. Statement: ${:.2}, Book: ${:.2}, Clients: ${:.2}, Unmatched statement lines: {}",
+                    statement_balance,
+                    book_balance,
+                    client_balances_sum,
+                    unmatched_statement_lines.len()
                 ))
             } else {
                 None
             },
+            matched_transaction_ids,
+            unmatched_statement_lines,
             created_at: Utc::now(),
-            created_by: "system".to_string(),
+            created_by: created_by.to_string(),
         };
 
-        self.save_trust_reconciliation(&reconciliation).await?;
+        Self::save_trust_reconciliation(&mut *tx, &reconciliation).await?;
+        tx.commit().await?;
 
         Ok(reconciliation)
     }
 
+    /// Past reconciliations of an account, most recent statement first
+    pub async fn get_reconciliation_history(&self, trust_account_id: &str) -> Result<Vec<TrustReconciliation>> {
+        let rows = sqlx::query(
+            "SELECT * FROM trust_reconciliations WHERE trust_account_id = ?
+             ORDER BY statement_date DESC, reconciliation_date DESC",
+        )
+        .bind(trust_account_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to query reconciliation history")?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(TrustReconciliation {
+                    id: row.try_get("id")?,
+                    trust_account_id: row.try_get("trust_account_id")?,
+                    reconciliation_date: row.try_get("reconciliation_date")?,
+                    statement_date: row.try_get("statement_date")?,
+                    statement_balance: row.try_get("statement_balance")?,
+                    book_balance: row.try_get("book_balance")?,
+                    difference: row.try_get("difference")?,
+                    unreconciled_deposits: serde_json::from_str(row.try_get("unreconciled_deposits_json")?)?,
+                    unreconciled_withdrawals: serde_json::from_str(row.try_get("unreconciled_withdrawals_json")?)?,
+                    matched_transaction_ids: serde_json::from_str(row.try_get("matched_transaction_ids_json")?)?,
+                    unmatched_statement_lines: serde_json::from_str(row.try_get("unmatched_statement_lines_json")?)?,
+                    is_reconciled: row.try_get("is_reconciled")?,
+                    notes: row.try_get("notes")?,
+                    created_at: row.try_get("created_at")?,
+                    created_by: row.try_get("created_by")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn mark_trust_transaction_reconciled(
+        db: impl SqliteExecutor<'_>,
+        transaction_id: &str,
+        statement_date: DateTime<Utc>,
+        reconciled_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE trust_transactions SET is_reconciled = 1, reconciled_at = ?, bank_statement_date = ?
+             WHERE id = ?",
+        )
+        .bind(reconciled_at)
+        .bind(statement_date)
+        .bind(transaction_id)
+        .execute(db)
+        .await
+        .context("Failed to mark trust transaction reconciled")?;
+        Ok(())
+    }
+
     // ============= Expense Management =============
 
     /// Create expense
@@ -922,28 +1357,81 @@ impl BillingService {
         Ok(expense)
     }
 
-    /// Approve expense
+    /// Read the total, date and vendor off a receipt
+    pub async fn parse_receipt(&self, path: &Path) -> Result<ParsedReceipt> {
+        receipts::parse_receipt(path, self.ocr.as_deref()).await
+    }
+
+    /// Create a pending expense pre-filled from its receipt. The parsed
+    /// receipt is returned alongside so low-confidence fields can be shown
+    /// for review before the expense is approved.
+    pub async fn create_expense_from_receipt(
+        &self,
+        matter_id: &str,
+        attorney_id: &str,
+        category: ExpenseCategory,
+        receipt: &Path,
+        is_billable: bool,
+        is_reimbursable: bool,
+    ) -> Result<(Expense, ParsedReceipt)> {
+        let parsed = self.parse_receipt(receipt).await?;
+        let amount = parsed
+            .total
+            .with_context(|| format!("No total found on receipt {}", receipt.display()))?;
+        let description = match &parsed.vendor {
+            Some(vendor) => format!("Receipt from {}", vendor),
+            None => "Receipt".to_string(),
+        };
+
+        let expense = self
+            .create_expense(
+                matter_id,
+                attorney_id,
+                category,
+                &description,
+                amount,
+                parsed.date.unwrap_or_else(Utc::now),
+                is_billable,
+                is_reimbursable,
+                Some(receipt.to_string_lossy().to_string()),
+                parsed.vendor.clone(),
+            )
+            .await?;
+        Ok((expense, parsed))
+    }
+
+    /// Approve a pending expense
     pub async fn approve_expense(&self, expense_id: &str, approved_by: &str) -> Result<Expense> {
         let mut expense = self.get_expense(expense_id).await?;
 
-        expense.status = ExpenseStatus::Approved;
-        expense.approved_at = Some(Utc::now());
+        expense.transition_to(ExpenseStatus::Approved, Utc::now())?;
         expense.approved_by = Some(approved_by.to_string());
-        expense.updated_at = Utc::now();
 
         self.save_expense(&expense).await?;
 
         Ok(expense)
     }
 
+    /// Reject a pending expense
+    pub async fn reject_expense(&self, expense_id: &str) -> Result<Expense> {
+        let mut expense = self.get_expense(expense_id).await?;
+        expense.transition_to(ExpenseStatus::Rejected, Utc::now())?;
+        self.save_expense(&expense).await?;
+        Ok(expense)
+    }
+
+    /// Record that the firm has reimbursed a billed expense
+    pub async fn reimburse_expense(&self, expense_id: &str) -> Result<Expense> {
+        let mut expense = self.get_expense(expense_id).await?;
+        expense.transition_to(ExpenseStatus::Reimbursed, Utc::now())?;
+        self.save_expense(&expense).await?;
+        Ok(expense)
+    }
+
     // ============= Helper Methods =============
 
     async fn generate_invoice_number(&self) -> Result<String> {
-        let count = sqlx::query!("SELECT COUNT(*) as count FROM invoices")
-            .fetch_one(&self.db)
-            .await?;
-
-        Ok(format!("INV-{:06}", count.count + 1))
+        numbering::next_number(&self.db, &self.numbering, "invoice", None, Utc::now()).await
     }
 
     async fn get_matter_name(&self, matter_id: &str) -> Result<String> {
@@ -984,6 +1472,28 @@ impl BillingService {
         Ok(())
     }
 
+    async fn get_trust_account(&self, account_id: &str) -> Result<TrustAccount> {
+        let row = sqlx::query("SELECT * FROM trust_accounts WHERE id = ?")
+            .bind(account_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load trust account")?
+            .ok_or_else(|| ServiceError::not_found("Trust account", account_id))?;
+
+        Ok(TrustAccount {
+            id: row.try_get("id")?,
+            account_name: row.try_get("account_name")?,
+            account_number: row.try_get("account_number")?,
+            bank_name: row.try_get("bank_name")?,
+            routing_number: row.try_get("routing_number")?,
+            account_type: row.try_get("account_type")?,
+            current_balance: row.try_get("current_balance")?,
+            is_active: row.try_get("is_active")?,
+            opened_date: row.try_get("opened_date")?,
+            closed_date: row.try_get("closed_date")?,
+        })
+    }
+
     async fn get_default_trust_account(&self) -> Result<TrustAccount> {
         // Stub - would query trust_accounts table
         Ok(TrustAccount {
@@ -1000,7 +1510,7 @@ impl BillingService {
         })
     }
 
-    async fn update_trust_account_balance(&self, account_id: &str, amount: f64) -> Result<()> {
+    async fn update_trust_account_balance(db: impl SqliteExecutor<'_>, account_id: &str, amount: f64) -> Result<()> {
         sqlx::query!(
             r#"
             UPDATE trust_accounts
@@ -1010,14 +1520,14 @@ impl BillingService {
             amount,
             account_id
         )
-        .execute(&self.db)
+        .execute(db)
         .await
         .context("Failed to update trust account balance")?;
 
         Ok(())
     }
 
-    async fn get_trust_account_book_balance(&self, account_id: &str) -> Result<f64> {
+    async fn get_trust_account_book_balance(db: impl SqliteExecutor<'_>, account_id: &str) -> Result<f64> {
         let result = sqlx::query!(
             r#"
             SELECT COALESCE(SUM(amount), 0) as balance
@@ -1026,14 +1536,14 @@ impl BillingService {
             "#,
             account_id
         )
-        .fetch_one(&self.db)
+        .fetch_one(db)
         .await
         .context("Failed to query trust account balance")?;
 
         Ok(result.balance.unwrap_or(0.0))
     }
 
-    async fn get_client_balances_sum(&self) -> Result<f64> {
+    async fn get_client_balances_sum(db: impl SqliteExecutor<'_>) -> Result<f64> {
         let result = sqlx::query!(
             r#"
             SELECT COALESCE(SUM(balance), 0) as total
@@ -1044,14 +1554,14 @@ impl BillingService {
             )
             "#
         )
-        .fetch_one(&self.db)
+        .fetch_one(db)
         .await
         .context("Failed to sum client balances")?;
 
         Ok(result.total.unwrap_or(0.0))
     }
 
-    async fn get_unreconciled_transactions(&self, account_id: &str) -> Result<Vec<TrustTransaction>> {
+    async fn get_unreconciled_transactions(db: impl SqliteExecutor<'_>, account_id: &str) -> Result<Vec<TrustTransaction>> {
         let results = sqlx::query_as!(
             TrustTransaction,
             r#"
@@ -1066,89 +1576,146 @@ impl BillingService {
             "#,
             account_id
         )
-        .fetch_all(&self.db)
+        .fetch_all(db)
         .await
         .context("Failed to query unreconciled transactions")?;
 
         Ok(results)
     }
 
-    async fn save_invoice(&self, invoice: &Invoice) -> Result<()> {
-        // Serialize complex fields
-        let time_entries_json = serde_json::to_string(&invoice.time_entries)?;
-        let expenses_json = serde_json::to_string(&invoice.expenses)?;
-        let adjustments_json = serde_json::to_string(&invoice.adjustments)?;
-        let status_str = format!("{:?}", invoice.status);
-
-        sqlx::query!(
-            r#"
-            INSERT OR REPLACE INTO invoices
-            (id, invoice_number, matter_id, matter_name, client_id, client_name,
-             billing_period_start, billing_period_end, issue_date, due_date,
-             time_entries_json, expenses_json, adjustments_json,
-             subtotal, discount_amount, tax_amount, total, amount_paid, balance,
-             status, sent_at, viewed_at, paid_at, notes, terms,
-             created_at, updated_at, created_by)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-            invoice.id,
-            invoice.invoice_number,
-            invoice.matter_id,
-            invoice.matter_name,
-            invoice.client_id,
-            invoice.client_name,
-            invoice.billing_period_start,
-            invoice.billing_period_end,
-            invoice.issue_date,
-            invoice.due_date,
-            time_entries_json,
-            expenses_json,
-            adjustments_json,
-            invoice.subtotal,
-            invoice.discount_amount,
-            invoice.tax_amount,
-            invoice.total,
-            invoice.amount_paid,
-            invoice.balance,
-            status_str,
-            invoice.sent_at,
-            invoice.viewed_at,
-            invoice.paid_at,
-            invoice.notes,
-            invoice.terms,
-            invoice.created_at,
-            invoice.updated_at,
-            invoice.created_by
-        )
+    /// Insert a new invoice or update an existing one at the version it was
+    /// loaded with. Fails with [`Conflict`] when another writer saved first.
+    async fn save_invoice(&self, invoice: &mut Invoice) -> Result<()> {
+        check_invoice_currency(invoice)?;
+
+        let updated = bind_invoice_fields(
+            sqlx::query(
+                r#"
+                UPDATE invoices SET
+                    invoice_number = ?, matter_id = ?, matter_name = ?, client_id = ?, client_name = ?,
+                    billing_period_start = ?, billing_period_end = ?, issue_date = ?, due_date = ?,
+                    time_entries_json = ?, expenses_json = ?, adjustments_json = ?,
+                    subtotal = ?, discount_amount = ?, tax_amount = ?, total = ?, amount_paid = ?, balance = ?,
+                    status = ?, sent_at = ?, viewed_at = ?, paid_at = ?, notes = ?, terms = ?,
+                    created_at = ?, updated_at = ?, created_by = ?, currency = ?,
+                    version = version + 1
+                WHERE id = ? AND version = ?
+                "#,
+            ),
+            invoice,
+        )?
+        .bind(&invoice.id)
+        .bind(invoice.version)
         .execute(&self.db)
         .await
-        .context("Failed to save invoice")?;
+        .context("Failed to save invoice")?
+        .rows_affected();
+
+        if updated == 0 {
+            let exists = sqlx::query("SELECT 1 FROM invoices WHERE id = ?")
+                .bind(&invoice.id)
+                .fetch_optional(&self.db)
+                .await?
+                .is_some();
+            if exists {
+                return Err(Conflict {
+                    entity: "Invoice",
+                    id: invoice.id.clone(),
+                    expected_version: invoice.version,
+                }
+                .into());
+            }
+
+            bind_invoice_fields(
+                sqlx::query(
+                    r#"
+                    INSERT INTO invoices
+                    (invoice_number, matter_id, matter_name, client_id, client_name,
+                     billing_period_start, billing_period_end, issue_date, due_date,
+                     time_entries_json, expenses_json, adjustments_json,
+                     subtotal, discount_amount, tax_amount, total, amount_paid, balance,
+                     status, sent_at, viewed_at, paid_at, notes, terms,
+                     created_at, updated_at, created_by, currency, id, version)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                ),
+                invoice,
+            )?
+            .bind(&invoice.id)
+            .bind(invoice.version + 1)
+            .execute(&self.db)
+            .await
+            .context("Failed to save invoice")?;
+        }
 
+        invoice.version += 1;
         Ok(())
     }
 
-    async fn get_invoice(&self, invoice_id: &str) -> Result<Invoice> {
-        // Stub - would query invoices table and deserialize JSON fields
-        Err(anyhow::anyhow!("Not implemented"))
+    /// Load an invoice; a missing one is `ServiceError::NotFound`
+    pub async fn get_invoice(&self, invoice_id: &str) -> Result<Invoice> {
+        let row = sqlx::query("SELECT * FROM invoices WHERE id = ?")
+            .bind(invoice_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load invoice")?
+            .ok_or_else(|| ServiceError::not_found("Invoice", invoice_id))?;
+
+        let status: String = row.try_get("status")?;
+        let currency: String = row.try_get("currency")?;
+        Ok(Invoice {
+            id: row.try_get("id")?,
+            invoice_number: row.try_get("invoice_number")?,
+            matter_id: row.try_get("matter_id")?,
+            matter_name: row.try_get("matter_name")?,
+            client_id: row.try_get("client_id")?,
+            client_name: row.try_get("client_name")?,
+            billing_period_start: row.try_get("billing_period_start")?,
+            billing_period_end: row.try_get("billing_period_end")?,
+            issue_date: row.try_get("issue_date")?,
+            due_date: row.try_get("due_date")?,
+            time_entries: serde_json::from_str(row.try_get("time_entries_json")?)?,
+            expenses: serde_json::from_str(row.try_get("expenses_json")?)?,
+            adjustments: serde_json::from_str(row.try_get("adjustments_json")?)?,
+            currency: currency.parse()?,
+            subtotal: row.try_get("subtotal")?,
+            discount_amount: row.try_get("discount_amount")?,
+            tax_amount: row.try_get("tax_amount")?,
+            total: row.try_get("total")?,
+            amount_paid: row.try_get("amount_paid")?,
+            balance: row.try_get("balance")?,
+            status: serde_json::from_value(serde_json::json!(status))?,
+            sent_at: row.try_get("sent_at")?,
+            viewed_at: row.try_get("viewed_at")?,
+            paid_at: row.try_get("paid_at")?,
+            notes: row.try_get("notes")?,
+            terms: row.try_get("terms")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            created_by: row.try_get("created_by")?,
+            version: row.try_get("version")?,
+        })
     }
 
     async fn save_payment(&self, payment: &Payment) -> Result<()> {
+        let currency_code = payment.currency.code();
         let payment_method_str = format!("{:?}", payment.payment_method);
         let status_str = format!("{:?}", payment.status);
 
         sqlx::query!(
             r#"
             INSERT OR REPLACE INTO payments
-            (id, invoice_id, matter_id, client_id, amount, payment_method, payment_date,
+            (id, invoice_id, matter_id, client_id, amount, currency, payment_method, payment_date,
              reference_number, status, processor_transaction_id, processor_fee,
              from_trust_account, trust_transaction_id, notes, created_at, created_by)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             payment.id,
             payment.invoice_id,
             payment.matter_id,
             payment.client_id,
             payment.amount,
+            currency_code,
             payment_method_str,
             payment.payment_date,
             payment.reference_number,
@@ -1173,7 +1740,7 @@ impl BillingService {
         Err(anyhow::anyhow!("Not implemented"))
     }
 
-    async fn save_trust_transaction(&self, transaction: &TrustTransaction) -> Result<()> {
+    async fn save_trust_transaction(db: impl SqliteExecutor<'_>, transaction: &TrustTransaction) -> Result<()> {
         let transaction_type_str = format!("{:?}", transaction.transaction_type);
 
         sqlx::query!(
@@ -1201,25 +1768,28 @@ impl BillingService {
             transaction.created_at,
             transaction.created_by
         )
-        .execute(&self.db)
+        .execute(db)
         .await
         .context("Failed to save trust transaction")?;
 
         Ok(())
     }
 
-    async fn save_trust_reconciliation(&self, reconciliation: &TrustReconciliation) -> Result<()> {
+    async fn save_trust_reconciliation(db: impl SqliteExecutor<'_>, reconciliation: &TrustReconciliation) -> Result<()> {
         // Serialize transaction lists
         let deposits_json = serde_json::to_string(&reconciliation.unreconciled_deposits)?;
         let withdrawals_json = serde_json::to_string(&reconciliation.unreconciled_withdrawals)?;
+        let matched_json = serde_json::to_string(&reconciliation.matched_transaction_ids)?;
+        let unmatched_lines_json = serde_json::to_string(&reconciliation.unmatched_statement_lines)?;
 
         sqlx::query!(
             r#"
             INSERT INTO trust_reconciliations
             (id, trust_account_id, reconciliation_date, statement_date, statement_balance,
              book_balance, difference, unreconciled_deposits_json, unreconciled_withdrawals_json,
+             matched_transaction_ids_json, unmatched_statement_lines_json,
              is_reconciled, notes, created_at, created_by)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             reconciliation.id,
             reconciliation.trust_account_id,
@@ -1230,12 +1800,14 @@ impl BillingService {
             reconciliation.difference,
             deposits_json,
             withdrawals_json,
+            matched_json,
+            unmatched_lines_json,
             reconciliation.is_reconciled,
             reconciliation.notes,
             reconciliation.created_at,
             reconciliation.created_by
         )
-        .execute(&self.db)
+        .execute(db)
         .await
         .context("Failed to save trust reconciliation")?;
 
@@ -1287,3 +1859,495 @@ impl BillingService {
         Err(anyhow::anyhow!("Not implemented"))
     }
 }
+
+/// Bind every invoice column except `id` and `version`, in table order
+fn bind_invoice_fields<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    invoice: &'q Invoice,
+) -> Result<Query<'q, Sqlite, SqliteArguments<'q>>> {
+    Ok(query
+        .bind(&invoice.invoice_number)
+        .bind(&invoice.matter_id)
+        .bind(&invoice.matter_name)
+        .bind(&invoice.client_id)
+        .bind(&invoice.client_name)
+        .bind(invoice.billing_period_start)
+        .bind(invoice.billing_period_end)
+        .bind(invoice.issue_date)
+        .bind(invoice.due_date)
+        .bind(serde_json::to_string(&invoice.time_entries)?)
+        .bind(serde_json::to_string(&invoice.expenses)?)
+        .bind(serde_json::to_string(&invoice.adjustments)?)
+        .bind(invoice.subtotal)
+        .bind(invoice.discount_amount)
+        .bind(invoice.tax_amount)
+        .bind(invoice.total)
+        .bind(invoice.amount_paid)
+        .bind(invoice.balance)
+        .bind(format!("{:?}", invoice.status))
+        .bind(invoice.sent_at)
+        .bind(invoice.viewed_at)
+        .bind(invoice.paid_at)
+        .bind(&invoice.notes)
+        .bind(&invoice.terms)
+        .bind(invoice.created_at)
+        .bind(invoice.updated_at)
+        .bind(&invoice.created_by)
+        .bind(invoice.currency.code()))
+}
+
+/// The currency shared by every line item; USD for an invoice with none
+fn line_item_currency(time_entries: &[InvoiceTimeEntry], expenses: &[InvoiceExpense]) -> Result<Currency> {
+    let mut currencies = time_entries
+        .iter()
+        .map(|e| e.currency)
+        .chain(expenses.iter().map(|e| e.currency));
+    let Some(first) = currencies.next() else {
+        return Ok(Currency::default());
+    };
+    match currencies.find(|c| *c != first) {
+        Some(other) => Err(ServiceError::validation(format!("Invoice mixes currencies: {} and {}", first, other)).into()),
+        None => Ok(first),
+    }
+}
+
+/// Every line item must be billed in the invoice currency
+fn check_invoice_currency(invoice: &Invoice) -> Result<()> {
+    let mismatched = invoice
+        .time_entries
+        .iter()
+        .map(|e| e.currency)
+        .chain(invoice.expenses.iter().map(|e| e.currency))
+        .find(|c| *c != invoice.currency);
+    if let Some(other) = mismatched {
+        return Err(ServiceError::validation(format!(
+            "Invoice {} is billed in {} but has a line item in {}",
+            invoice.invoice_number, invoice.currency, other
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Outstanding balance across invoices, converted into the reporting currency
+pub fn outstanding_balance(invoices: &[Invoice], reporting: Currency, rates: &dyn ExchangeRateSource) -> Result<f64> {
+    currency::roll_up(invoices.iter().map(|i| (i.balance, i.currency)), reporting, rates)
+}
+
+/// Printable invoice with every amount in the invoice currency
+pub fn render_invoice_html(invoice: &Invoice) -> String {
+    let money = |amount: f64| invoice.currency.format(amount);
+
+    let mut rows = String::new();
+    for entry in &invoice.time_entries {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{} - {}</td><td>{:.2} h @ {}</td><td>{}</td></tr>\n",
+            entry.date.format("%m/%d/%Y"),
+            entry.attorney_name,
+            entry.activity_description,
+            entry.hours,
+            money(entry.rate),
+            money(entry.amount)
+        ));
+    }
+    for expense in &invoice.expenses {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            expense.date.format("%m/%d/%Y"),
+            expense.description,
+            expense.category,
+            money(expense.amount)
+        ));
+    }
+
+    format!(
+        r#"<html>
+<body>
+<h1>Invoice {}</h1>
+<p>{}<br>{}</p>
+<p>Issued {} &middot; Due {}</p>
+<table>
+{}</table>
+<p>Subtotal: {}<br>Discount: {}<br>Tax: {}<br><strong>Total: {}</strong><br>Paid: {}<br><strong>Balance due: {}</strong></p>
+</body>
+</html>
+"#,
+        invoice.invoice_number,
+        invoice.client_name,
+        invoice.matter_name,
+        invoice.issue_date.format("%B %d, %Y"),
+        invoice.due_date.format("%B %d, %Y"),
+        rows,
+        money(invoice.subtotal),
+        money(invoice.discount_amount),
+        money(invoice.tax_amount),
+        money(invoice.total),
+        money(invoice.amount_paid),
+        money(invoice.balance)
+    )
+}
+
+/// Pair each statement line with an unreconciled transaction of the same
+/// amount that cleared within the match window, nearest date first. Each
+/// transaction matches at most one line.
+fn trust_audit_details(transaction: &TrustTransaction) -> serde_json::Value {
+    serde_json::json!({
+        "trust_account_id": transaction.trust_account_id,
+        "matter_id": transaction.matter_id,
+        "client_id": transaction.client_id,
+        "amount": transaction.amount,
+        "payment_id": transaction.payment_id,
+    })
+}
+
+fn match_statement_lines(lines: &[BankStatementLine], transactions: &[TrustTransaction]) -> Vec<Option<usize>> {
+    let mut taken = vec![false; transactions.len()];
+    lines
+        .iter()
+        .map(|line| {
+            let found = transactions
+                .iter()
+                .enumerate()
+                .filter(|(i, txn)| {
+                    !taken[*i]
+                        && (txn.amount - line.amount).abs() < 0.005
+                        && (line.date - txn.transaction_date).num_days().abs() <= STATEMENT_MATCH_WINDOW_DAYS
+                })
+                .min_by_key(|(_, txn)| (line.date - txn.transaction_date).num_seconds().abs())
+                .map(|(i, _)| i);
+            if let Some(i) = found {
+                taken[i] = true;
+            }
+            found
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn service() -> BillingService {
+        let db = test_database().await;
+        BillingService::new(db)
+    }
+
+    fn invoice() -> Invoice {
+        let now = Utc::now();
+        Invoice {
+            id: Uuid::new_v4().to_string(),
+            invoice_number: "INV-0001".to_string(),
+            matter_id: "matter-1".to_string(),
+            matter_name: "Smith v. Jones".to_string(),
+            client_id: "client-1".to_string(),
+            client_name: "Jane Smith".to_string(),
+            billing_period_start: now,
+            billing_period_end: now,
+            issue_date: now,
+            due_date: now,
+            time_entries: Vec::new(),
+            expenses: Vec::new(),
+            adjustments: Vec::new(),
+            currency: Currency::USD,
+            subtotal: 1_000.0,
+            discount_amount: 0.0,
+            tax_amount: 0.0,
+            total: 1_000.0,
+            amount_paid: 0.0,
+            balance: 1_000.0,
+            status: InvoiceStatus::Draft,
+            sent_at: None,
+            viewed_at: None,
+            paid_at: None,
+            notes: None,
+            terms: None,
+            created_at: now,
+            updated_at: now,
+            created_by: "attorney-1".to_string(),
+            version: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_invoice_write_is_rejected() {
+        let billing = service().await;
+        let mut invoice = invoice();
+        billing.save_invoice(&mut invoice).await.unwrap();
+        assert_eq!(invoice.version, 1);
+
+        // Two writers load the same version
+        let mut ui_copy = invoice.clone();
+        let mut background_copy = invoice.clone();
+
+        ui_copy.notes = Some("Courtesy discount applied".to_string());
+        billing.save_invoice(&mut ui_copy).await.unwrap();
+        assert_eq!(ui_copy.version, 2);
+
+        background_copy.status = InvoiceStatus::Overdue;
+        let err = billing.save_invoice(&mut background_copy).await.unwrap_err();
+        let conflict = err.downcast_ref::<Conflict>().expect("conflict error");
+        assert_eq!(conflict.expected_version, 1);
+        assert_eq!(background_copy.version, 1);
+
+        let status: String = sqlx::query_scalar("SELECT status FROM invoices WHERE id = ?")
+            .bind(&invoice.id)
+            .fetch_one(&billing.db)
+            .await
+            .unwrap();
+        assert_eq!(status, "Draft");
+    }
+
+    #[tokio::test]
+    async fn test_missing_invoice_is_not_found() {
+        let billing = service().await;
+        let mut invoice = invoice();
+        invoice.currency = Currency::EUR;
+        billing.save_invoice(&mut invoice).await.unwrap();
+
+        let loaded = billing.get_invoice(&invoice.id).await.unwrap();
+        assert_eq!(loaded.currency, Currency::EUR);
+        assert_eq!(loaded.version, 1);
+
+        let err = ServiceError::from(billing.get_invoice("inv-missing").await.unwrap_err());
+        assert!(matches!(err, ServiceError::NotFound { entity: "Invoice", ref id } if id == "inv-missing"));
+    }
+
+    #[test]
+    fn test_eur_invoice_renders_euro_symbol() {
+        let mut invoice = invoice();
+        invoice.currency = Currency::EUR;
+        invoice.balance = 1_250.5;
+
+        let html = render_invoice_html(&invoice);
+        assert!(html.contains("Total: €1,000.00"), "{}", html);
+        assert!(html.contains("Balance due: €1,250.50"));
+        assert!(!html.contains('$'));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_currency_invoice_rejected() {
+        let billing = service().await;
+        let expense = |currency| InvoiceExpense {
+            expense_id: Uuid::new_v4().to_string(),
+            date: Utc::now(),
+            description: "Court reporter".to_string(),
+            category: "Court_reporter".to_string(),
+            amount: 300.0,
+            currency,
+            is_reimbursable: true,
+        };
+
+        let mut invoice = invoice();
+        invoice.expenses = vec![expense(Currency::USD), expense(Currency::EUR)];
+        let err = billing.save_invoice(&mut invoice).await.unwrap_err();
+        assert!(err.to_string().contains("EUR"), "{}", err);
+        assert!(line_item_currency(&[], &invoice.expenses).is_err());
+
+        invoice.expenses.pop();
+        billing.save_invoice(&mut invoice).await.unwrap();
+    }
+
+    fn expense(status: ExpenseStatus) -> Expense {
+        let now = Utc::now();
+        Expense {
+            id: "exp-1".to_string(),
+            matter_id: "matter-1".to_string(),
+            attorney_id: "attorney-1".to_string(),
+            date: now,
+            category: ExpenseCategory::Filing_fees,
+            description: "Complaint filing fee".to_string(),
+            amount: 350.0,
+            is_reimbursable: true,
+            is_billable: true,
+            receipt_url: None,
+            vendor: None,
+            status,
+            approved_at: None,
+            approved_by: None,
+            billed_at: None,
+            invoice_id: None,
+            reimbursed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_approving_billed_expense_fails() {
+        let mut billed = expense(ExpenseStatus::Billed);
+        let err = billed.transition_to(ExpenseStatus::Approved, Utc::now()).unwrap_err();
+        assert_eq!(err.from, ExpenseStatus::Billed);
+        assert_eq!(err.to_string(), "Expense exp-1 cannot move from Billed to Approved");
+        assert_eq!(billed.status, ExpenseStatus::Billed);
+        assert!(billed.approved_at.is_none());
+
+        for status in [ExpenseStatus::Approved, ExpenseStatus::Rejected, ExpenseStatus::Reimbursed] {
+            assert!(expense(status).transition_to(ExpenseStatus::Approved, Utc::now()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_expense_full_lifecycle() {
+        let mut expense = expense(ExpenseStatus::Pending);
+        for next in [ExpenseStatus::Approved, ExpenseStatus::Billed, ExpenseStatus::Reimbursed] {
+            expense.transition_to(next, Utc::now()).unwrap();
+        }
+        assert!(expense.approved_at.is_some() && expense.billed_at.is_some() && expense.reimbursed_at.is_some());
+        assert!(!ExpenseStatus::Rejected.can_transition_to(&ExpenseStatus::Billed));
+    }
+
+    async fn service_with_trust_account(account_type: &str) -> BillingService {
+        let billing = service().await;
+        sqlx::query(
+            "INSERT INTO trust_accounts (id, account_name, account_number, bank_name, routing_number, account_type, opened_date)
+             VALUES ('trust-1', 'Client Trust', '000123', 'Keystone Bank', '031000000', ?, ?)",
+        )
+        .bind(account_type)
+        .bind(Utc::now())
+        .execute(&billing.db)
+        .await
+        .unwrap();
+        billing.with_iolta_foundation("Pennsylvania IOLTA Board")
+    }
+
+    fn march() -> StatementPeriod {
+        use chrono::TimeZone;
+        StatementPeriod {
+            start: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_iolta_interest_remitted_not_allocated_to_clients() {
+        let billing = service_with_trust_account("IOLTA").await;
+        billing
+            .create_trust_deposit("trust-1", "matter-1", "client-1", 10_000.0, "Retainer", None, "attorney-1")
+            .await
+            .unwrap();
+
+        let posting = billing.post_interest("trust-1", 12.34, march(), "attorney-1").await.unwrap();
+        let remittance = posting.remittance.expect("IOLTA interest is remitted");
+        assert_eq!(remittance.amount, 12.34);
+        assert_eq!(remittance.remitted_to, "Pennsylvania IOLTA Board");
+        assert_eq!(remittance.interest_transaction_id, posting.interest.id);
+        assert!(posting.interest.client_id.is_empty());
+
+        // The client's ledger and the account's book balance are untouched
+        assert_eq!(billing.get_client_trust_balance("client-1", "matter-1").await.unwrap(), 10_000.0);
+        let allocated: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trust_transactions WHERE transaction_type IN ('Interest', 'Remittance') AND client_id <> ''",
+        )
+        .fetch_one(&billing.db)
+        .await
+        .unwrap();
+        assert_eq!(allocated, 0);
+        assert!((BillingService::get_trust_account_book_balance(&billing.db, "trust-1").await.unwrap() - 10_000.0).abs() < 1e-9);
+
+        // The same period cannot be remitted twice, and the refused posting
+        // leaves no ledger rows behind
+        assert!(billing.post_interest("trust-1", 12.34, march(), "attorney-1").await.is_err());
+        let interest_rows: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trust_transactions WHERE transaction_type IN ('Interest', 'Remittance')",
+        )
+        .fetch_one(&billing.db)
+        .await
+        .unwrap();
+        assert_eq!(interest_rows, 2);
+        assert!((BillingService::get_trust_account_book_balance(&billing.db, "trust-1").await.unwrap() - 10_000.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_non_iolta_interest_has_no_remittance() {
+        let billing = service_with_trust_account("Non-IOLTA").await;
+        let posting = billing.post_interest("trust-1", 40.0, march(), "attorney-1").await.unwrap();
+        assert!(posting.remittance.is_none());
+        assert_eq!(posting.interest.transaction_type, TrustTransactionType::Interest);
+
+        // Interest is posted once per period whether or not it is remitted
+        assert!(billing.post_interest("trust-1", 40.0, march(), "attorney-1").await.is_err());
+        assert!((BillingService::get_trust_account_book_balance(&billing.db, "trust-1").await.unwrap() - 40.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_iolta_interest_needs_a_configured_foundation() {
+        let billing = service_with_trust_account("IOLTA").await;
+        let billing = BillingService::new(billing.db.clone());
+        assert!(billing.post_interest("trust-1", 12.34, march(), "attorney-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_statement_lines_reconcile_matching_transactions() {
+        let billing = service_with_trust_account("IOLTA").await;
+        let retainer = billing
+            .create_trust_deposit("trust-1", "matter-1", "client-1", 5_000.0, "Retainer", None, "attorney-1")
+            .await
+            .unwrap();
+        let costs = billing
+            .create_trust_deposit("trust-1", "matter-1", "client-1", 750.0, "Cost advance", None, "attorney-1")
+            .await
+            .unwrap();
+
+        let statement_date = retainer.transaction_date + chrono::Duration::days(2);
+        let lines = vec![
+            BankStatementLine {
+                date: retainer.transaction_date + chrono::Duration::days(1),
+                amount: 5_000.0,
+                description: "DEPOSIT".to_string(),
+            },
+            BankStatementLine {
+                date: retainer.transaction_date + chrono::Duration::days(1),
+                amount: -25.0,
+                description: "SERVICE CHARGE".to_string(),
+            },
+        ];
+        let reconciliation = billing
+            .reconcile_bank_statement("trust-1", statement_date, 4_975.0, &lines, "attorney-1")
+            .await
+            .unwrap();
+
+        assert_eq!(reconciliation.matched_transaction_ids, vec![retainer.id.clone()]);
+        assert_eq!(reconciliation.unmatched_statement_lines, vec![lines[1].clone()]);
+        assert!(!reconciliation.is_reconciled);
+
+        // A statement that does not reconcile clears nothing
+        let cleared_state = |id: String| {
+            let db = billing.db.clone();
+            async move {
+                sqlx::query_as::<_, (bool, Option<DateTime<Utc>>)>(
+                    "SELECT is_reconciled, bank_statement_date FROM trust_transactions WHERE id = ?",
+                )
+                .bind(id)
+                .fetch_one(&db)
+                .await
+                .unwrap()
+            }
+        };
+        assert_eq!(cleared_state(retainer.id.clone()).await, (false, None));
+        assert_eq!(reconciliation.unreconciled_deposits.len(), 2);
+
+        let lines = vec![
+            lines[0].clone(),
+            BankStatementLine {
+                date: costs.transaction_date + chrono::Duration::days(1),
+                amount: 750.0,
+                description: "DEPOSIT".to_string(),
+            },
+        ];
+        let reconciliation = billing
+            .reconcile_bank_statement("trust-1", statement_date, 5_750.0, &lines, "attorney-1")
+            .await
+            .unwrap();
+
+        assert!(reconciliation.is_reconciled);
+        assert_eq!(reconciliation.matched_transaction_ids, vec![retainer.id.clone(), costs.id.clone()]);
+        assert!(reconciliation.unreconciled_deposits.is_empty());
+        assert_eq!(cleared_state(retainer.id.clone()).await, (true, Some(statement_date)));
+
+        let history = billing.get_reconciliation_history("trust-1").await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, reconciliation.id);
+        assert_eq!(history[1].unmatched_statement_lines.len(), 1);
+    }
+}
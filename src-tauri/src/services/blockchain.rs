@@ -0,0 +1,536 @@
+// Document anchoring - trusted timestamps for integrity proofs
+// A document's SHA-256 is sent to a timestamp authority (RFC 3161 TSA by
+// when one is configured); the signed token proves the document existed
+// unchanged at that time
+
+use crate::config::TimestampAuthorityConfig;
+use crate::utils::file_utils::expand_home;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+use uuid::Uuid;
+
+/// DER AlgorithmIdentifier for SHA-256 (OID 2.16.840.1.101.3.4.2.1, NULL params)
+const SHA256_ALGORITHM: &[u8] = &[
+    0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00,
+];
+
+/// DER OID for SHA-256 alone, as AlgorithmIdentifier params may be NULL or absent
+const SHA256_OID: &[u8] = &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+
+/// id-ct-TSTInfo (1.2.840.113549.1.9.16.1.4)
+const TST_INFO_OID: &[u8] = &[
+    0x06, 0x0b, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x01, 0x04,
+];
+
+/// id-messageDigest signed attribute (1.2.840.113549.1.9.4)
+const MESSAGE_DIGEST_OID: &[u8] = &[0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x04];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnchorProof {
+    pub id: String,
+    /// Lowercase hex SHA-256 of the document
+    pub document_hash: String,
+    pub authority: String,
+    /// Signed token from the authority, base64 encoded
+    pub token: String,
+    /// Time asserted by the authority
+    pub anchored_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimestampToken {
+    pub token: Vec<u8>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A trusted third party that signs (digest, time) pairs
+#[async_trait]
+pub trait TimestampAuthority: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn timestamp(&self, digest: &[u8]) -> Result<TimestampToken>;
+
+    /// Whether `token` was issued for `digest`
+    fn verify(&self, token: &[u8], digest: &[u8]) -> Result<bool>;
+}
+
+pub struct BlockchainService {
+    db: SqlitePool,
+    authority: Option<Arc<dyn TimestampAuthority>>,
+}
+
+impl BlockchainService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db, authority: None }
+    }
+
+    pub fn with_authority(mut self, authority: Arc<dyn TimestampAuthority>) -> Self {
+        self.authority = Some(authority);
+        self
+    }
+
+    fn authority(&self) -> Result<&Arc<dyn TimestampAuthority>> {
+        self.authority
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No timestamp authority configured"))
+    }
+
+    /// Timestamp a document's SHA-256 (hex) and keep the proof
+    pub async fn anchor_document(&self, hash: &str) -> Result<AnchorProof> {
+        let authority = self.authority()?;
+        let digest = decode_sha256_hex(hash)?;
+        let token = authority.timestamp(&digest).await?;
+
+        let proof = AnchorProof {
+            id: Uuid::new_v4().to_string(),
+            document_hash: hash.trim().to_lowercase(),
+            authority: authority.name().to_string(),
+            token: general_purpose::STANDARD.encode(&token.token),
+            anchored_at: token.timestamp,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO document_anchors (id, document_hash, authority, token, anchored_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&proof.id)
+        .bind(&proof.document_hash)
+        .bind(&proof.authority)
+        .bind(&proof.token)
+        .bind(proof.anchored_at)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await
+        .context("Failed to save document anchor")?;
+
+        info!("Anchored document {} via {} at {}", proof.document_hash, proof.authority, proof.anchored_at);
+        Ok(proof)
+    }
+
+    /// Whether `proof` shows that a document with SHA-256 `hash` existed at
+    /// `proof.anchored_at`
+    pub async fn verify_anchor(&self, proof: &AnchorProof, hash: &str) -> Result<bool> {
+        let digest = decode_sha256_hex(hash)?;
+        if !hash.trim().eq_ignore_ascii_case(&proof.document_hash) {
+            return Ok(false);
+        }
+
+        let token = general_purpose::STANDARD
+            .decode(&proof.token)
+            .context("Invalid anchor token encoding")?;
+        self.authority()?.verify(&token, &digest)
+    }
+
+    pub async fn list_anchors(&self, hash: &str) -> Result<Vec<AnchorProof>> {
+        let rows = sqlx::query("SELECT * FROM document_anchors WHERE document_hash = ? ORDER BY anchored_at")
+            .bind(hash.trim().to_lowercase())
+            .fetch_all(&self.db)
+            .await
+            .context("Failed to load document anchors")?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AnchorProof {
+                    id: row.try_get("id")?,
+                    document_hash: row.try_get("document_hash")?,
+                    authority: row.try_get("authority")?,
+                    token: row.try_get("token")?,
+                    anchored_at: row.try_get("anchored_at")?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn decode_sha256_hex(hash: &str) -> Result<Vec<u8>> {
+    let hash = hash.trim();
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Expected a SHA-256 hex digest, got {:?}", hash);
+    }
+    Ok((0..64)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hash[i..i + 2], 16).unwrap_or_default())
+        .collect())
+}
+
+// ============= RFC 3161 =============
+
+/// RFC 3161 Time-Stamp Protocol over HTTP. Tokens are only accepted when
+/// signed by the authority's configured certificate.
+pub struct Rfc3161Authority {
+    url: String,
+    public_key: RsaPublicKey,
+    client: reqwest::Client,
+}
+
+impl Rfc3161Authority {
+    /// `certificate` is the authority's DER signing certificate
+    pub fn new(url: &str, certificate: &[u8]) -> Result<Self> {
+        let spki = certificate_public_key(certificate)
+            .ok_or_else(|| anyhow::anyhow!("Malformed timestamp authority certificate"))?;
+        let public_key = RsaPublicKey::from_public_key_der(spki)
+            .context("Timestamp authority certificate does not hold an RSA key")?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Ok(Self {
+            url: url.to_string(),
+            public_key,
+            client,
+        })
+    }
+
+    pub fn from_config(config: &TimestampAuthorityConfig) -> Result<Self> {
+        let path = expand_home(&config.certificate_path);
+        let certificate = std::fs::read(&path)
+            .with_context(|| format!("Failed to read timestamp authority certificate {:?}", path))?;
+        Self::new(&config.url, &certificate_der(&certificate)?)
+    }
+}
+
+#[async_trait]
+impl TimestampAuthority for Rfc3161Authority {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn timestamp(&self, digest: &[u8]) -> Result<TimestampToken> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/timestamp-query")
+            .body(timestamp_request(digest, Uuid::new_v4().as_bytes()))
+            .send()
+            .await
+            .context("Timestamp authority request failed")?
+            .error_for_status()
+            .context("Timestamp authority rejected the request")?;
+        let body = response.bytes().await?;
+
+        let token = parse_timestamp_response(&body)?;
+        let timestamp = verified_time(&token, digest, &self.public_key).ok_or_else(|| {
+            anyhow::anyhow!("Timestamp token is not signed by the authority or does not cover the submitted digest")
+        })?;
+        Ok(TimestampToken { token, timestamp })
+    }
+
+    fn verify(&self, token: &[u8], digest: &[u8]) -> Result<bool> {
+        Ok(verified_time(token, digest, &self.public_key).is_some())
+    }
+}
+
+/// DER bytes of a PEM or DER certificate file
+fn certificate_der(contents: &[u8]) -> Result<Vec<u8>> {
+    let Ok(text) = std::str::from_utf8(contents) else {
+        return Ok(contents.to_vec());
+    };
+    if !text.trim_start().starts_with("-----BEGIN") {
+        return Ok(contents.to_vec());
+    }
+    let body: String = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("-----BEGIN"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END"))
+        .collect();
+    general_purpose::STANDARD
+        .decode(body)
+        .context("Invalid PEM certificate")
+}
+
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// Read one TLV at the start of `data`: (tag, content, total length)
+fn read_der(data: &[u8]) -> Option<(u8, &[u8], usize)> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, header) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 || rest.len() < n {
+            return None;
+        }
+        (rest[..n].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize), 2 + n)
+    };
+    let content = data.get(header..header + len)?;
+    Some((tag, content, header + len))
+}
+
+#[derive(Clone, Copy)]
+struct Tlv<'a> {
+    tag: u8,
+    content: &'a [u8],
+    /// The whole element, header included
+    raw: &'a [u8],
+}
+
+/// The TLVs that make up a constructed element's content
+fn der_elements(mut data: &[u8]) -> Option<Vec<Tlv<'_>>> {
+    let mut elements = Vec::new();
+    while !data.is_empty() {
+        let (tag, content, len) = read_der(data)?;
+        elements.push(Tlv { tag, content, raw: &data[..len] });
+        data = &data[len..];
+    }
+    Some(elements)
+}
+
+/// SubjectPublicKeyInfo of a DER X.509 certificate
+fn certificate_public_key(certificate: &[u8]) -> Option<&[u8]> {
+    let (_, certificate, _) = read_der(certificate)?;
+    let (_, tbs, _) = read_der(certificate)?;
+    let fields = der_elements(tbs)?;
+    // [0] version is optional; then serialNumber, signature, issuer,
+    // validity, subject, subjectPublicKeyInfo
+    let skip = usize::from(fields.first()?.tag == 0xa0);
+    fields.get(skip + 5).map(|field| field.raw)
+}
+
+fn message_imprint(digest: &[u8]) -> Vec<u8> {
+    der(0x30, &[SHA256_ALGORITHM, &der(0x04, digest)].concat())
+}
+
+/// DER TimeStampReq (v1, SHA-256 imprint, nonce, certReq)
+fn timestamp_request(digest: &[u8], nonce: &[u8]) -> Vec<u8> {
+    // Leading zero keeps the nonce a positive INTEGER
+    let nonce = [&[0u8][..], nonce].concat();
+    der(
+        0x30,
+        &[
+            der(0x02, &[1]),
+            message_imprint(digest),
+            der(0x02, &nonce),
+            der(0x01, &[0xff]),
+        ]
+        .concat(),
+    )
+}
+
+/// The TimeStampToken from a granted TimeStampResp
+fn parse_timestamp_response(body: &[u8]) -> Result<Vec<u8>> {
+    let invalid = || anyhow::anyhow!("Malformed timestamp response");
+    let (_, response, _) = read_der(body).ok_or_else(invalid)?;
+    let (_, status_info, status_len) = read_der(response).ok_or_else(invalid)?;
+    let (_, status, _) = read_der(status_info).ok_or_else(invalid)?;
+
+    // 0 = granted, 1 = granted with modifications
+    if !matches!(status, [0] | [1]) {
+        anyhow::bail!("Timestamp authority refused the request (status {:?})", status);
+    }
+
+    let (_, _, token_len) = read_der(&response[status_len..]).ok_or_else(invalid)?;
+    Ok(response[status_len..status_len + token_len].to_vec())
+}
+
+/// The parts of a CMS SignedData TimeStampToken needed to check it
+struct SignedToken<'a> {
+    /// DER TSTInfo
+    tst_info: &'a [u8],
+    /// Signed attributes re-tagged as a SET, which is what the signature covers
+    signed_attributes: Vec<u8>,
+    message_digest: &'a [u8],
+    signature: &'a [u8],
+}
+
+fn parse_signed_token(token: &[u8]) -> Option<SignedToken<'_>> {
+    // ContentInfo: contentType, [0] EXPLICIT SignedData
+    let (_, content_info, _) = read_der(token)?;
+    let &[_, explicit] = der_elements(content_info)?.as_slice() else {
+        return None;
+    };
+    let (_, signed_data, _) = read_der(explicit.content)?;
+
+    // version, digestAlgorithms, encapContentInfo, [0] certificates, [1] crls, signerInfos
+    let fields = der_elements(signed_data)?;
+    let encap = der_elements(fields.get(2)?.content)?;
+    if encap.first()?.raw != TST_INFO_OID {
+        return None;
+    }
+    let (_, tst_info, _) = read_der(encap.get(1)?.content)?;
+
+    // A timestamp token carries exactly one signer:
+    // version, sid, digestAlgorithm, [0] signedAttrs, signatureAlgorithm, signature
+    let &[signer] = der_elements(fields.last()?.content)?.as_slice() else {
+        return None;
+    };
+    let signer = der_elements(signer.content)?;
+    let &[_, _, digest_algorithm, attributes, _, signature, ..] = signer.as_slice() else {
+        return None;
+    };
+    if !digest_algorithm.content.starts_with(SHA256_OID) || attributes.tag != 0xa0 || signature.tag != 0x04 {
+        return None;
+    }
+
+    let message_digest = der_elements(attributes.content)?.into_iter().find_map(|attribute| {
+        let attribute = der_elements(attribute.content)?;
+        if attribute.first()?.raw != MESSAGE_DIGEST_OID {
+            return None;
+        }
+        let (_, value, _) = read_der(attribute.get(1)?.content)?;
+        Some(value)
+    })?;
+
+    Some(SignedToken {
+        tst_info,
+        signed_attributes: [&[0x31][..], &attributes.raw[1..]].concat(),
+        message_digest,
+        signature: signature.content,
+    })
+}
+
+/// genTime of `token` if it is signed by `key` and its message imprint is `digest`
+fn verified_time(token: &[u8], digest: &[u8], key: &RsaPublicKey) -> Option<DateTime<Utc>> {
+    let signed = parse_signed_token(token)?;
+    if Sha256::digest(signed.tst_info).as_slice() != signed.message_digest {
+        return None;
+    }
+
+    let signature = Signature::try_from(signed.signature).ok()?;
+    VerifyingKey::<Sha256>::new(key.clone())
+        .verify(&signed.signed_attributes, &signature)
+        .ok()?;
+
+    gen_time(signed.tst_info, digest)
+}
+
+/// genTime of a DER TSTInfo whose message imprint is `digest`
+fn gen_time(tst_info: &[u8], digest: &[u8]) -> Option<DateTime<Utc>> {
+    // version, policy, messageImprint, serialNumber, genTime, ...
+    let (_, tst_info, _) = read_der(tst_info)?;
+    let &[_, _, imprint, serial, time, ..] = der_elements(tst_info)?.as_slice() else {
+        return None;
+    };
+    if imprint.raw != message_imprint(digest) || serial.tag != 0x02 || time.tag != 0x18 {
+        return None;
+    }
+
+    // GeneralizedTime: YYYYMMDDHHMMSS[.fff]Z
+    let time = std::str::from_utf8(time.content).ok()?.trim_end_matches('Z');
+    let whole = time.split('.').next()?;
+    NaiveDateTime::parse_from_str(whole, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use crate::utils::crypto::{calculate_sha256_string, hmac_sha256_hex};
+
+    /// Signs digest + time with a fixed key instead of calling a TSA
+    struct MockAuthority;
+
+    const KEY: &[u8] = b"mock-tsa-key";
+
+    #[async_trait]
+    impl TimestampAuthority for MockAuthority {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn timestamp(&self, digest: &[u8]) -> Result<TimestampToken> {
+            let timestamp = Utc::now();
+            let message = [digest, timestamp.to_rfc3339().as_bytes()].concat();
+            let token = format!("{}|{}", timestamp.to_rfc3339(), hmac_sha256_hex(KEY, &message));
+            Ok(TimestampToken {
+                token: token.into_bytes(),
+                timestamp,
+            })
+        }
+
+        fn verify(&self, token: &[u8], digest: &[u8]) -> Result<bool> {
+            let token = std::str::from_utf8(token)?;
+            let (time, signature) = token.split_once('|').context("Malformed mock token")?;
+            let message = [digest, time.as_bytes()].concat();
+            Ok(hmac_sha256_hex(KEY, &message) == signature)
+        }
+    }
+
+    async fn service() -> BlockchainService {
+        let db = test_database().await;
+        BlockchainService::new(db).with_authority(Arc::new(MockAuthority))
+    }
+
+    #[tokio::test]
+    async fn test_anchor_verifies_only_for_the_original_hash() {
+        let service = service().await;
+        let original = calculate_sha256_string("Demand letter: $250,000 by June 1");
+        let modified = calculate_sha256_string("Demand letter: $25,000 by June 1");
+
+        let proof = service.anchor_document(&original).await.unwrap();
+
+        assert!(service.verify_anchor(&proof, &original).await.unwrap());
+        assert!(!service.verify_anchor(&proof, &modified).await.unwrap());
+
+        // Re-pointing the proof at the modified hash doesn't fool the token
+        let forged = AnchorProof { document_hash: modified.clone(), ..proof.clone() };
+        assert!(!service.verify_anchor(&forged, &modified).await.unwrap());
+
+        assert_eq!(service.list_anchors(&original).await.unwrap(), vec![proof]);
+    }
+
+    /// Token from `openssl ts -reply` for the demand letter below, signed by tsa_cert.pem
+    const TSA_TOKEN: &[u8] = include_bytes!("../../tests/fixtures/tsa_token.der");
+    const TSA_CERT: &[u8] = include_bytes!("../../tests/fixtures/tsa_cert.pem");
+    const OTHER_TSA_CERT: &[u8] = include_bytes!("../../tests/fixtures/tsa_other_cert.pem");
+
+    fn configured_authority(certificate: &[u8]) -> Rfc3161Authority {
+        Rfc3161Authority::new("https://tsa.example.test", &certificate_der(certificate).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_rfc3161_token_is_verified_against_the_authority_certificate() {
+        let digest = decode_sha256_hex(&calculate_sha256_string("Demand letter: $250,000 by June 1")).unwrap();
+        let authority = configured_authority(TSA_CERT);
+
+        assert!(authority.verify(TSA_TOKEN, &digest).unwrap());
+        assert!(!authority.verify(TSA_TOKEN, &[8u8; 32]).unwrap());
+        assert_eq!(
+            verified_time(TSA_TOKEN, &digest, &authority.public_key).unwrap().to_rfc3339(),
+            "2026-10-16T03:56:54+00:00"
+        );
+
+        // Same token, but the configured authority did not sign it
+        assert!(!configured_authority(OTHER_TSA_CERT).verify(TSA_TOKEN, &digest).unwrap());
+
+        // Altering the signed TSTInfo breaks the signature
+        let imprint = message_imprint(&digest);
+        let at = TSA_TOKEN.windows(imprint.len()).position(|w| w == imprint.as_slice()).unwrap();
+        let mut tampered = TSA_TOKEN.to_vec();
+        tampered[at + imprint.len() + 2] ^= 0x01;
+        assert!(!authority.verify(&tampered, &digest).unwrap());
+
+        let request = timestamp_request(&digest, &[0xff; 16]);
+        assert_eq!(read_der(&request).map(|(tag, _, len)| (tag, len)), Some((0x30, request.len())));
+    }
+}
@@ -3,13 +3,22 @@
 // Processes millions of cases, statutes, regulations for AI training and search
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use uuid::Uuid;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use futures::StreamExt;
+use tracing::{info, warn};
+
+pub mod parsers;
+pub mod progress;
+
+use progress::{IngestionControl, IngestionProgress, RateTracker};
+
+/// Records stored per transaction (and per checkpoint) by file ingestion jobs
+const DEFAULT_BATCH_SIZE: usize = 500;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkIngestionJob {
@@ -52,6 +61,7 @@ pub enum IngestionStatus {
     Completed,
     Failed,
     PartialSuccess,
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,11 +170,46 @@ pub struct Statute {
     pub last_updated: DateTime<Utc>,
 }
 
+/// A case or code section in the ingested-case schema, whatever its source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseLawDocument {
+    pub id: String,
+    pub source: DataSource,
+    /// The record's id at the source (CAP case id, USLM identifier)
+    pub source_id: String,
+    pub title: String,
+    pub court: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub decision_date: Option<NaiveDate>,
+    pub docket_number: Option<String>,
+    /// Primary normalized citation
+    pub citation: String,
+    pub parallel_citations: Vec<String>,
+    pub text: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StoreSummary {
+    pub inserted: u64,
+    /// Records sharing a citation with an existing row, skipped
+    pub duplicates: u64,
+}
+
 pub struct BulkDataIngestionService {
     db: SqlitePool,
     download_path: PathBuf,
     courtlistener_api_key: Option<String>,
     govinfo_api_key: Option<String>,
+    batch_size: usize,
+}
+
+/// A file ingestion job as persisted, enough to resume it
+struct FileJob {
+    source: DataSource,
+    source_path: Option<String>,
+    error_log: Vec<String>,
+    progress: IngestionProgress,
 }
 
 impl BulkDataIngestionService {
@@ -174,9 +219,15 @@ impl BulkDataIngestionService {
             download_path,
             courtlistener_api_key: std::env::var("COURTLISTENER_API_KEY").ok(),
             govinfo_api_key: std::env::var("GOVINFO_API_KEY").ok(),
+            batch_size: DEFAULT_BATCH_SIZE,
         }
     }
 
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
     // ============= COURTLISTENER BULK INGESTION =============
 
     /// Download complete CourtListener bulk data (6.7M+ opinions, 20M+ dockets)
@@ -513,6 +564,281 @@ impl BulkDataIngestionService {
         Ok(())
     }
 
+    // ============= PARSED RECORD STORAGE =============
+
+    /// Parse a GovInfo USLM title and store its sections
+    pub async fn import_govinfo_uslm(&self, xml: &str) -> Result<StoreSummary> {
+        let documents = parsers::parse_govinfo_uslm(xml)?;
+        self.store_documents(&documents).await
+    }
+
+    /// Parse a Harvard CAP export and store its cases
+    pub async fn import_harvard_cases(&self, json: &str) -> Result<StoreSummary> {
+        let documents = parsers::parse_harvard_cases(json)?;
+        self.store_documents(&documents).await
+    }
+
+    /// Insert documents into the ingested-case tables, skipping any whose
+    /// primary or parallel citations are already present
+    pub async fn store_documents(&self, documents: &[CaseLawDocument]) -> Result<StoreSummary> {
+        let mut summary = StoreSummary::default();
+        let mut tx = self.db.begin().await?;
+
+        for document in documents {
+            let citations: Vec<&String> = std::iter::once(&document.citation)
+                .chain(&document.parallel_citations)
+                .collect();
+
+            let mut existing = None;
+            for citation in &citations {
+                existing = sqlx::query("SELECT case_id FROM ingested_case_citations WHERE citation = ?")
+                    .bind(citation.as_str())
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .map(|row| row.try_get::<String, _>("case_id"))
+                    .transpose()?;
+                if existing.is_some() {
+                    break;
+                }
+            }
+            if existing.is_some() {
+                summary.duplicates += 1;
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO ingested_cases
+                (id, source, source_id, title, court, jurisdiction, decision_date, docket_number,
+                 citation, parallel_citations, text, url, ingested_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&document.id)
+            .bind(format!("{:?}", document.source))
+            .bind(&document.source_id)
+            .bind(&document.title)
+            .bind(&document.court)
+            .bind(&document.jurisdiction)
+            .bind(document.decision_date.map(|d| d.to_string()))
+            .bind(&document.docket_number)
+            .bind(&document.citation)
+            .bind(serde_json::to_string(&document.parallel_citations)?)
+            .bind(&document.text)
+            .bind(&document.url)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to store {}", document.citation))?;
+
+            for citation in citations {
+                sqlx::query("INSERT OR IGNORE INTO ingested_case_citations (citation, case_id) VALUES (?, ?)")
+                    .bind(citation.as_str())
+                    .bind(&document.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            summary.inserted += 1;
+        }
+
+        tx.commit().await?;
+        Ok(summary)
+    }
+
+    // ============= FILE INGESTION JOBS =============
+
+    /// Record a queued job ingesting a GovInfo USLM or Harvard CAP file
+    pub async fn create_file_job(&self, path: &Path, source: DataSource) -> Result<String> {
+        if !matches!(source, DataSource::GovInfo | DataSource::HarvardCaselaw) {
+            return Err(anyhow::anyhow!("No file parser for {:?}", source));
+        }
+        let size = fs::metadata(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .len();
+
+        let job_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO bulk_ingestion_jobs
+            (id, source, job_type, status, started_at, total_size_bytes, source_path)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&job_id)
+        .bind(format!("{:?}", source))
+        .bind(format!("{:?}", IngestionType::SpecificDataset))
+        .bind(format!("{:?}", IngestionStatus::Queued))
+        .bind(Utc::now())
+        .bind(size as i64)
+        .bind(path.to_string_lossy().to_string())
+        .execute(&self.db)
+        .await
+        .context("Failed to create ingestion job")?;
+
+        Ok(job_id)
+    }
+
+    /// Ingest a job's file a batch at a time from its checkpoint until the
+    /// file is done or `control` is cancelled. Running a cancelled or failed
+    /// job again resumes after the last committed batch; a batch stored just
+    /// before an interruption is skipped as duplicates on resume.
+    pub async fn run_file_ingestion(&self, job_id: &str, control: &IngestionControl) -> Result<IngestionProgress> {
+        let job = self.load_file_job(job_id).await?;
+        if job.progress.status == IngestionStatus::Completed {
+            return Ok(job.progress);
+        }
+        let mut progress = job.progress;
+        let mut error_log = job.error_log;
+
+        let path = job
+            .source_path
+            .ok_or_else(|| anyhow::anyhow!("Ingestion job {} has no source file", job_id))?;
+        let parsed = match fs::read_to_string(&path).await {
+            Ok(contents) => match job.source {
+                DataSource::GovInfo => parsers::parse_govinfo_uslm(&contents),
+                DataSource::HarvardCaselaw => parsers::parse_harvard_cases(&contents),
+                other => Err(anyhow::anyhow!("No file parser for {:?}", other)),
+            },
+            Err(e) => Err(anyhow::Error::new(e).context(format!("Failed to read {}", path))),
+        };
+        let documents = match parsed {
+            Ok(documents) => documents,
+            Err(e) => {
+                progress.status = IngestionStatus::Failed;
+                error_log.push(e.to_string());
+                self.save_progress(&progress, &error_log).await?;
+                control.progress.send_replace(progress);
+                return Err(e);
+            }
+        };
+
+        let total = documents.len() as u64;
+        progress.total_records = Some(total);
+        progress.status = IngestionStatus::Processing;
+        let tracker = RateTracker::new(progress.checkpoint);
+        self.save_progress(&progress, &error_log).await?;
+        control.progress.send_replace(progress.clone());
+
+        let start = (progress.checkpoint as usize).min(documents.len());
+        for batch in documents[start..].chunks(self.batch_size) {
+            if control.cancel.is_cancelled() {
+                break;
+            }
+
+            match self.store_documents(batch).await {
+                Ok(_) => progress.records_processed += batch.len() as u64,
+                Err(e) => {
+                    warn!("Ingestion job {} batch at {} failed: {}", job_id, progress.checkpoint, e);
+                    progress.records_failed += batch.len() as u64;
+                    error_log.push(format!(
+                        "Records {}-{}: {}",
+                        progress.checkpoint + 1,
+                        progress.checkpoint + batch.len() as u64,
+                        e
+                    ));
+                }
+            }
+            progress.checkpoint += batch.len() as u64;
+            tracker.update(&mut progress);
+            self.save_progress(&progress, &error_log).await?;
+            control.progress.send_replace(progress.clone());
+
+            let done = progress.checkpoint - start as u64;
+            if let Some(delay) = control.throttle_delay(done, tracker.elapsed()) {
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = control.cancel.cancelled() => {}
+                }
+            }
+        }
+
+        progress.status = if progress.checkpoint < total {
+            IngestionStatus::Cancelled
+        } else if progress.records_failed > 0 {
+            IngestionStatus::PartialSuccess
+        } else {
+            IngestionStatus::Completed
+        };
+        progress.eta_seconds = None;
+        self.save_progress(&progress, &error_log).await?;
+        control.progress.send_replace(progress.clone());
+
+        info!(
+            "Ingestion job {} {:?} at {}/{} record(s)",
+            job_id, progress.status, progress.checkpoint, total
+        );
+        Ok(progress)
+    }
+
+    /// Last persisted progress of a job
+    pub async fn get_job_progress(&self, job_id: &str) -> Result<IngestionProgress> {
+        Ok(self.load_file_job(job_id).await?.progress)
+    }
+
+    async fn load_file_job(&self, job_id: &str) -> Result<FileJob> {
+        let row = sqlx::query(
+            r#"
+            SELECT source, status, source_path, total_records, records_processed,
+                   records_failed, checkpoint, error_log
+            FROM bulk_ingestion_jobs WHERE id = ?
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Ingestion job not found: {}", job_id))?;
+
+        let source: String = row.try_get("source")?;
+        let status: String = row.try_get("status")?;
+        let error_log: String = row.try_get("error_log")?;
+
+        let mut progress = IngestionProgress::new(job_id);
+        progress.status = serde_json::from_value(serde_json::Value::String(status))
+            .context("Unknown ingestion status")?;
+        progress.total_records = row.try_get::<Option<i64>, _>("total_records")?.map(|t| t as u64);
+        progress.records_processed = row.try_get::<i64, _>("records_processed")? as u64;
+        progress.records_failed = row.try_get::<i64, _>("records_failed")? as u64;
+        progress.checkpoint = row.try_get::<i64, _>("checkpoint")? as u64;
+
+        Ok(FileJob {
+            source: serde_json::from_value(serde_json::Value::String(source))
+                .context("Unknown ingestion source")?,
+            source_path: row.try_get("source_path")?,
+            error_log: serde_json::from_str(&error_log).unwrap_or_default(),
+            progress,
+        })
+    }
+
+    async fn save_progress(&self, progress: &IngestionProgress, error_log: &[String]) -> Result<()> {
+        let finished = matches!(
+            progress.status,
+            IngestionStatus::Completed | IngestionStatus::PartialSuccess
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE bulk_ingestion_jobs
+            SET status = ?, records_processed = ?, records_failed = ?, total_records = ?,
+                checkpoint = ?, error_log = ?, completed_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(format!("{:?}", progress.status))
+        .bind(progress.records_processed as i64)
+        .bind(progress.records_failed as i64)
+        .bind(progress.total_records.map(|t| t as i64))
+        .bind(progress.checkpoint as i64)
+        .bind(serde_json::to_string(error_log)?)
+        .bind(finished.then(Utc::now))
+        .bind(&progress.job_id)
+        .execute(&self.db)
+        .await
+        .context("Failed to save ingestion checkpoint")?;
+
+        Ok(())
+    }
+
     // ============= INCREMENTAL UPDATES =============
 
     /// Run daily/weekly incremental updates instead of full re-download
@@ -681,3 +1007,116 @@ pub struct IngestionStats {
     pub last_updated: DateTime<Utc>,
     pub index_size_bytes: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::{test_database, test_database_at};
+
+    async fn service() -> BulkDataIngestionService {
+        let db = test_database().await;
+        BulkDataIngestionService::new(db, std::env::temp_dir())
+    }
+
+    const USLM: &str = r#"<uscDoc xmlns="http://xml.house.gov/schemas/uslm/1.0"><main><title identifier="/us/usc/t42">
+        <section identifier="/us/usc/t42/s1983"><num value="1983">§ 1983.</num><heading>Civil action for deprivation of rights</heading><content>Every person...</content></section>
+        <section identifier="/us/usc/t42/s1985"><num value="1985">§ 1985.</num><heading>Conspiracy to interfere with civil rights</heading><content>If two or more persons...</content></section>
+    </title></main></uscDoc>"#;
+
+    #[tokio::test]
+    async fn test_dedupes_by_normalized_citation() {
+        let service = service().await;
+
+        assert_eq!(
+            service.import_govinfo_uslm(USLM).await.unwrap(),
+            StoreSummary { inserted: 2, duplicates: 0 }
+        );
+        assert_eq!(
+            service.import_govinfo_uslm(USLM).await.unwrap(),
+            StoreSummary { inserted: 0, duplicates: 2 }
+        );
+
+        // The second case is the first reported under a differently spelled
+        // parallel citation; the third appears twice in the same export
+        let cap = r#"{"id": 1, "name": "Commonwealth v. Smith", "citations": [{"type": "official", "cite": "440 Pa. Super. 250"}, {"type": "parallel", "cite": "654 A.2d 1108"}]}
+{"id": 2, "name": "Com. v. Smith", "citations": [{"type": "official", "cite": "654 A2d 1108"}]}
+{"id": 3, "name": "Jones v. Brown", "citations": [{"type": "official", "cite": "12 F.3d 34"}]}
+{"id": 3, "name": "Jones v. Brown", "citations": [{"type": "official", "cite": "12 F3d 34"}]}"#;
+        assert_eq!(
+            service.import_harvard_cases(cap).await.unwrap(),
+            StoreSummary { inserted: 2, duplicates: 2 }
+        );
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ingested_cases")
+            .fetch_one(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+
+        let stored: (String, String) =
+            sqlx::query_as("SELECT title, parallel_citations FROM ingested_cases WHERE citation = '440 Pa. Super. 250'")
+                .fetch_one(&service.db)
+                .await
+                .unwrap();
+        assert_eq!(stored, ("Commonwealth v. Smith".to_string(), r#"["654 A.2d 1108"]"#.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_mid_run_leaves_resumable_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        // A file database, so the spawned run and the test share one store
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("ingest.db").display());
+        let db = test_database_at(&url).await;
+
+        let path = dir.path().join("cases.jsonl");
+        let cases: Vec<String> = (1..=10)
+            .map(|i| format!(r#"{{"id": {i}, "name": "Case {i}", "citations": [{{"type": "official", "cite": "{i} F.3d 100"}}]}}"#))
+            .collect();
+        std::fs::write(&path, cases.join("\n")).unwrap();
+
+        let service = BulkDataIngestionService::new(db.clone(), dir.path().to_path_buf()).with_batch_size(1);
+        let job_id = service.create_file_job(&path, DataSource::HarvardCaselaw).await.unwrap();
+
+        // Throttled to 20 records/sec so the run is still going when cancelled
+        let control = IngestionControl::new(&job_id).with_max_records_per_sec(Some(20));
+        let mut updates = control.subscribe();
+        let cancel = control.cancel.clone();
+        let run = tokio::spawn({
+            let job_id = job_id.clone();
+            async move { service.run_file_ingestion(&job_id, &control).await.unwrap() }
+        });
+
+        updates.wait_for(|p| p.checkpoint >= 3).await.unwrap();
+        cancel.cancel();
+        let stopped = run.await.unwrap();
+
+        assert_eq!(stopped.status, IngestionStatus::Cancelled);
+        assert!(stopped.checkpoint >= 3 && stopped.checkpoint < 10);
+        assert!(stopped.records_per_sec > 0.0);
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ingested_cases")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(stored as u64, stopped.checkpoint);
+
+        let service = BulkDataIngestionService::new(db.clone(), dir.path().to_path_buf()).with_batch_size(1);
+        let persisted = service.get_job_progress(&job_id).await.unwrap();
+        assert_eq!(persisted.checkpoint, stopped.checkpoint);
+        assert_eq!(persisted.status, IngestionStatus::Cancelled);
+
+        // Resuming picks up after the checkpoint and finishes the file
+        let finished = service
+            .run_file_ingestion(&job_id, &IngestionControl::new(&job_id))
+            .await
+            .unwrap();
+        assert_eq!(finished.status, IngestionStatus::Completed);
+        assert_eq!(finished.checkpoint, 10);
+        assert_eq!(finished.records_processed, 10);
+        assert_eq!(finished.records_failed, 0);
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ingested_cases")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(stored, 10);
+    }
+}
@@ -0,0 +1,372 @@
+// Format-specific parsers for bulk ingestion
+// GovInfo publishes the U.S. Code as USLM XML; Harvard CAP publishes cases as
+// JSON (one object per line in bulk exports). Both map onto CaseLawDocument
+// with citations normalized so records can be deduplicated across sources.
+
+use super::{CaseLawDocument, DataSource};
+use crate::services::citations::normalize_citation;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Deserialize;
+use tracing::warn;
+use uuid::Uuid;
+
+// ============= GOVINFO (USLM XML) =============
+
+enum UslmField {
+    Num,
+    Heading,
+    Content,
+}
+
+struct UslmSection {
+    identifier: String,
+    num: String,
+    heading: String,
+    content: String,
+}
+
+impl UslmSection {
+    /// `/us/usc/t42/s1983` -> 42 U.S.C. § 1983
+    fn citation(&self) -> Option<String> {
+        let mut parts = self.identifier.trim_start_matches('/').split('/');
+        if parts.next()? != "us" || parts.next()? != "usc" {
+            return None;
+        }
+        let title = parts.next()?.strip_prefix('t')?;
+        let section = parts.next()?.strip_prefix('s')?;
+        normalize_citation(&format!("{} U.S.C. § {}", title, section))
+    }
+
+    fn into_document(self, published: Option<NaiveDate>) -> Option<CaseLawDocument> {
+        let Some(citation) = self.citation() else {
+            warn!("Skipping USLM section without a U.S.C. identifier: {}", self.identifier);
+            return None;
+        };
+        let heading = collapse_whitespace(&self.heading);
+        let title = if heading.is_empty() { collapse_whitespace(&self.num) } else { heading };
+
+        Some(CaseLawDocument {
+            id: Uuid::new_v4().to_string(),
+            source: DataSource::GovInfo,
+            source_id: self.identifier,
+            title,
+            court: None,
+            jurisdiction: Some("United States".to_string()),
+            decision_date: published,
+            docket_number: None,
+            citation,
+            parallel_citations: vec![],
+            text: Some(collapse_whitespace(&self.content)).filter(|t| !t.is_empty()),
+            url: None,
+        })
+    }
+}
+
+/// One document per `<section>` of a USLM title. The section's heading
+/// becomes the title and the text of its subsections the body.
+pub fn parse_govinfo_uslm(xml: &str) -> Result<Vec<CaseLawDocument>> {
+    let mut reader = Reader::from_str(xml);
+    let mut documents = Vec::new();
+    let mut published: Option<NaiveDate> = None;
+    let mut in_date = false;
+
+    let mut section: Option<UslmSection> = None;
+    let mut depth = 0usize;
+    let mut field = UslmField::Content;
+
+    loop {
+        let event = reader.read_event().context("Invalid USLM XML")?;
+
+        if let Some(current) = section.as_mut() {
+            match event {
+                Event::Start(e) => {
+                    depth += 1;
+                    if depth == 1 {
+                        field = match e.local_name().as_ref() {
+                            b"num" => UslmField::Num,
+                            b"heading" => UslmField::Heading,
+                            _ => UslmField::Content,
+                        };
+                    }
+                    current.content.push(' ');
+                }
+                Event::Text(t) => {
+                    let text = t.unescape().context("Invalid text in USLM XML")?;
+                    match field {
+                        UslmField::Num => current.num.push_str(&text),
+                        UslmField::Heading => current.heading.push_str(&text),
+                        UslmField::Content => current.content.push_str(&text),
+                    }
+                }
+                Event::End(_) if depth == 0 => {
+                    documents.extend(section.take().and_then(|s| s.into_document(published)));
+                }
+                Event::End(_) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        field = UslmField::Content;
+                    }
+                    current.content.push(' ');
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) if e.local_name().as_ref() == b"section" => {
+                if let Some(identifier) = attr(&e, b"identifier") {
+                    section = Some(UslmSection {
+                        identifier,
+                        num: String::new(),
+                        heading: String::new(),
+                        content: String::new(),
+                    });
+                    depth = 0;
+                    field = UslmField::Content;
+                }
+            }
+            // dcterms:created / dc:date in the document's <meta>
+            Event::Start(e) if published.is_none() && matches!(e.local_name().as_ref(), b"created" | b"date") => {
+                in_date = true;
+            }
+            Event::Text(t) if in_date => {
+                let text = t.unescape().context("Invalid text in USLM XML")?;
+                published = parse_date(&text);
+            }
+            Event::End(_) => in_date = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(documents)
+}
+
+fn attr(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+// ============= HARVARD CASELAW (CAP JSON) =============
+
+#[derive(Debug, Deserialize)]
+struct CapCase {
+    id: u64,
+    name: String,
+    name_abbreviation: Option<String>,
+    decision_date: Option<String>,
+    docket_number: Option<String>,
+    #[serde(default)]
+    citations: Vec<CapCitation>,
+    court: Option<CapCourt>,
+    jurisdiction: Option<CapJurisdiction>,
+    frontend_url: Option<String>,
+    casebody: Option<CapCasebody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapCitation {
+    #[serde(rename = "type")]
+    citation_type: String,
+    cite: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapCourt {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapJurisdiction {
+    name_long: Option<String>,
+    name: String,
+}
+
+/// Case text either as `data.text` (API responses) or as a list of
+/// opinions (bulk exports)
+#[derive(Debug, Default, Deserialize)]
+struct CapCasebody {
+    data: Option<CapCasebodyData>,
+    #[serde(default)]
+    opinions: Vec<CapOpinion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapCasebodyData {
+    text: Option<String>,
+    #[serde(default)]
+    opinions: Vec<CapOpinion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapOpinion {
+    text: String,
+}
+
+impl CapCasebody {
+    fn text(self) -> Option<String> {
+        let (text, opinions) = match self.data {
+            Some(data) => (data.text, data.opinions),
+            None => (None, self.opinions),
+        };
+        text.or_else(|| {
+            let joined: Vec<String> = opinions.into_iter().map(|o| o.text).collect();
+            Some(joined.join("\n\n")).filter(|t| !t.trim().is_empty())
+        })
+    }
+}
+
+impl CapCase {
+    fn into_document(self) -> Option<CaseLawDocument> {
+        // The official reporter citation is primary; the rest are parallel
+        let mut citations: Vec<(bool, String)> = self
+            .citations
+            .iter()
+            .filter_map(|c| normalize_citation(&c.cite).map(|n| (c.citation_type == "official", n)))
+            .collect();
+        citations.sort_by_key(|(official, _)| !official);
+        citations.dedup_by(|a, b| a.1 == b.1);
+
+        let mut citations = citations.into_iter().map(|(_, c)| c);
+        let Some(citation) = citations.next() else {
+            warn!("Skipping CAP case {} without a usable citation", self.id);
+            return None;
+        };
+
+        Some(CaseLawDocument {
+            id: Uuid::new_v4().to_string(),
+            source: DataSource::HarvardCaselaw,
+            source_id: self.id.to_string(),
+            title: self.name_abbreviation.unwrap_or(self.name),
+            court: self.court.map(|c| c.name),
+            jurisdiction: self.jurisdiction.map(|j| j.name_long.unwrap_or(j.name)),
+            decision_date: self.decision_date.as_deref().and_then(parse_date),
+            docket_number: self.docket_number.filter(|d| !d.trim().is_empty()),
+            citation,
+            parallel_citations: citations.collect(),
+            text: self.casebody.and_then(CapCasebody::text),
+            url: self.frontend_url,
+        })
+    }
+}
+
+/// Cases from a CAP export: either a JSON array or one case per line
+pub fn parse_harvard_cases(json: &str) -> Result<Vec<CaseLawDocument>> {
+    let cases: Vec<CapCase> = if json.trim_start().starts_with('[') {
+        serde_json::from_str(json).context("Invalid CAP JSON")?
+    } else {
+        json.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| format!("Invalid CAP JSON on line {}", i + 1))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    Ok(cases.into_iter().filter_map(CapCase::into_document).collect())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    value
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USLM_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<uscDoc xmlns="http://xml.house.gov/schemas/uslm/1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" identifier="/us/usc/t42">
+  <meta>
+    <dc:title>Title 42</dc:title>
+    <dcterms:created>2024-01-05T08:00:00</dcterms:created>
+  </meta>
+  <main>
+    <title identifier="/us/usc/t42">
+      <num value="42">Title 42&#8212;</num>
+      <heading>THE PUBLIC HEALTH AND WELFARE</heading>
+      <section identifier="/us/usc/t42/s1983">
+        <num value="1983">§ 1983.</num>
+        <heading>Civil action for deprivation of rights</heading>
+        <content>Every person who, under color of any statute &amp; ordinance, subjects any citizen
+          <i>shall be liable</i> to the party injured.</content>
+      </section>
+      <section identifier="/us/usc/t42/s1988">
+        <num value="1988">§ 1988.</num>
+        <heading>Proceedings in vindication of civil rights</heading>
+        <subsection identifier="/us/usc/t42/s1988/b">
+          <num value="b">(b)</num>
+          <heading>Attorney's fees</heading>
+          <content>The court may allow a reasonable attorney's fee.</content>
+        </subsection>
+      </section>
+    </title>
+  </main>
+</uscDoc>"#;
+
+    const CAP_FIXTURE: &str = r#"{"id": 1234567, "name": "Commonwealth of Pennsylvania v. John Smith", "name_abbreviation": "Commonwealth v. Smith", "decision_date": "1995-06-30", "docket_number": "No. 123 Phila. 1994", "citations": [{"type": "parallel", "cite": "654 A2d 1108"}, {"type": "official", "cite": "440 Pa.Super. 250"}], "court": {"name": "Superior Court of Pennsylvania"}, "jurisdiction": {"name": "Pa.", "name_long": "Pennsylvania"}, "frontend_url": "https://cite.case.law/pa-super/440/250/", "casebody": {"opinions": [{"text": "OPINION. The judgment is affirmed.", "type": "majority"}]}}
+{"id": 7654321, "name": "Jones v. Brown", "decision_date": "2001-02", "citations": [{"type": "official", "cite": "not a citation"}]}
+"#;
+
+    #[test]
+    fn test_parse_govinfo_uslm_sections() {
+        let documents = parse_govinfo_uslm(USLM_FIXTURE).unwrap();
+        assert_eq!(documents.len(), 2);
+
+        let s1983 = &documents[0];
+        assert_eq!(s1983.source, DataSource::GovInfo);
+        assert_eq!(s1983.source_id, "/us/usc/t42/s1983");
+        assert_eq!(s1983.citation, "42 U.S.C. § 1983");
+        assert_eq!(s1983.title, "Civil action for deprivation of rights");
+        assert_eq!(s1983.decision_date, NaiveDate::from_ymd_opt(2024, 1, 5));
+        assert_eq!(
+            s1983.text.as_deref(),
+            Some("Every person who, under color of any statute & ordinance, subjects any citizen shall be liable to the party injured.")
+        );
+
+        // Subsection numbers and headings stay in the body text
+        let s1988 = &documents[1];
+        assert_eq!(s1988.citation, "42 U.S.C. § 1988");
+        assert_eq!(
+            s1988.text.as_deref(),
+            Some("(b) Attorney's fees The court may allow a reasonable attorney's fee.")
+        );
+    }
+
+    #[test]
+    fn test_parse_harvard_cases() {
+        let documents = parse_harvard_cases(CAP_FIXTURE).unwrap();
+        // The second case has no usable citation
+        assert_eq!(documents.len(), 1);
+
+        let case = &documents[0];
+        assert_eq!(case.source, DataSource::HarvardCaselaw);
+        assert_eq!(case.source_id, "1234567");
+        assert_eq!(case.title, "Commonwealth v. Smith");
+        assert_eq!(case.citation, "440 Pa. Super. 250");
+        assert_eq!(case.parallel_citations, vec!["654 A.2d 1108".to_string()]);
+        assert_eq!(case.court.as_deref(), Some("Superior Court of Pennsylvania"));
+        assert_eq!(case.jurisdiction.as_deref(), Some("Pennsylvania"));
+        assert_eq!(case.decision_date, NaiveDate::from_ymd_opt(1995, 6, 30));
+        assert_eq!(case.docket_number.as_deref(), Some("No. 123 Phila. 1994"));
+        assert_eq!(case.text.as_deref(), Some("OPINION. The judgment is affirmed."));
+
+        // The array form of the export parses the same way
+        let array = format!("[{}]", CAP_FIXTURE.lines().next().unwrap());
+        assert_eq!(parse_harvard_cases(&array).unwrap()[0].citation, "440 Pa. Super. 250");
+    }
+}
@@ -0,0 +1,172 @@
+// Progress reporting and cancellation for long-running ingestion jobs
+// A running job publishes its latest progress on a watch channel and stops
+// at the next batch boundary once its cancellation token fires
+
+use super::IngestionStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestionProgress {
+    pub job_id: String,
+    pub status: IngestionStatus,
+    pub records_processed: u64,
+    pub records_failed: u64,
+    pub total_records: Option<u64>,
+    /// Throughput of the current run
+    pub records_per_sec: f64,
+    pub eta_seconds: Option<u64>,
+    /// Records committed so far; a resumed run starts after these
+    pub checkpoint: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IngestionProgress {
+    pub fn new(job_id: &str) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            status: IngestionStatus::Queued,
+            records_processed: 0,
+            records_failed: 0,
+            total_records: None,
+            records_per_sec: 0.0,
+            eta_seconds: None,
+            checkpoint: 0,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Handed to a job run: how to stop it, where to report, how fast to go
+pub struct IngestionControl {
+    pub cancel: CancellationToken,
+    pub progress: watch::Sender<IngestionProgress>,
+    /// Upper bound on throughput; the run sleeps between batches to stay under it
+    pub max_records_per_sec: Option<u32>,
+}
+
+impl IngestionControl {
+    pub fn new(job_id: &str) -> Self {
+        let (progress, _) = watch::channel(IngestionProgress::new(job_id));
+        Self {
+            cancel: CancellationToken::new(),
+            progress,
+            max_records_per_sec: None,
+        }
+    }
+
+    pub fn with_max_records_per_sec(mut self, limit: Option<u32>) -> Self {
+        self.max_records_per_sec = limit.filter(|l| *l > 0);
+        self
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<IngestionProgress> {
+        self.progress.subscribe()
+    }
+
+    /// How long to wait so that `records` over `elapsed` stays under the limit
+    pub(super) fn throttle_delay(&self, records: u64, elapsed: Duration) -> Option<Duration> {
+        let limit = self.max_records_per_sec?;
+        let target = Duration::from_secs_f64(records as f64 / limit as f64);
+        target.checked_sub(elapsed).filter(|d| !d.is_zero())
+    }
+}
+
+/// Rate and ETA for one run of a job, measured from when the run started
+pub(super) struct RateTracker {
+    started: Instant,
+    start_checkpoint: u64,
+}
+
+impl RateTracker {
+    pub(super) fn new(start_checkpoint: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            start_checkpoint,
+        }
+    }
+
+    pub(super) fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub(super) fn update(&self, progress: &mut IngestionProgress) {
+        let done = progress.checkpoint.saturating_sub(self.start_checkpoint);
+        let secs = self.started.elapsed().as_secs_f64();
+        progress.records_per_sec = if secs > 0.0 { done as f64 / secs } else { 0.0 };
+        progress.eta_seconds = match progress.total_records {
+            Some(total) if progress.records_per_sec > 0.0 => {
+                let remaining = total.saturating_sub(progress.checkpoint);
+                Some((remaining as f64 / progress.records_per_sec).ceil() as u64)
+            }
+            _ => None,
+        };
+        progress.updated_at = Utc::now();
+    }
+}
+
+struct RunningIngestion {
+    cancel: CancellationToken,
+    progress: watch::Receiver<IngestionProgress>,
+}
+
+/// Ingestion jobs running in this process, managed as Tauri state. Job
+/// tokens are children of the shutdown token, so exiting stops every job
+/// at its next checkpoint.
+#[derive(Clone, Default)]
+pub struct IngestionJobs {
+    shutdown: CancellationToken,
+    running: Arc<Mutex<HashMap<String, RunningIngestion>>>,
+}
+
+impl IngestionJobs {
+    pub fn new(shutdown: CancellationToken) -> Self {
+        Self {
+            shutdown,
+            running: Arc::default(),
+        }
+    }
+
+    /// Control for a new run of `job_id`
+    pub fn register(&self, job_id: &str, max_records_per_sec: Option<u32>) -> IngestionControl {
+        let mut control = IngestionControl::new(job_id).with_max_records_per_sec(max_records_per_sec);
+        control.cancel = self.shutdown.child_token();
+
+        self.running.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            job_id.to_string(),
+            RunningIngestion {
+                cancel: control.cancel.clone(),
+                progress: control.subscribe(),
+            },
+        );
+        control
+    }
+
+    pub fn progress(&self, job_id: &str) -> Option<IngestionProgress> {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.get(job_id).map(|job| job.progress.borrow().clone())
+    }
+
+    /// Ask a running job to stop; false if it is not running here
+    pub fn cancel(&self, job_id: &str) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        match running.get(job_id) {
+            Some(job) => {
+                info!("Cancelling ingestion job {}", job_id);
+                job.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self, job_id: &str) {
+        self.running.lock().unwrap_or_else(|e| e.into_inner()).remove(job_id);
+    }
+}
@@ -2,19 +2,25 @@
 // Automated REST API import from CourtListener, GovInfo, and other sources
 // Keeps local database synchronized with latest case law and statutes
 
+use crate::domain::{CaseStatus, CourtLevel, Docket};
 use crate::providers::courtlistener::{CourtListenerProvider, Opinion, OpinionCluster};
 use crate::providers::govinfo::GovInfoProvider;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
+use validator::Validate;
 
 const BATCH_SIZE: usize = 100;
 const CONCURRENT_DOWNLOADS: usize = 5;
+/// Row errors kept in an import report; later failures are only counted
+const MAX_REPORTED_ERRORS: usize = 1000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncJob {
@@ -346,6 +352,102 @@ impl BulkImportService {
         }
     }
 
+    // ========================================================================
+    // Local Dataset Import
+    // ========================================================================
+
+    /// Stream dockets from a local CSV or NDJSON dump into the docket cache.
+    /// Rows that fail to parse or validate are reported and skipped; the rest
+    /// are upserted by court and docket number.
+    #[instrument(skip(self))]
+    pub async fn import_local_dataset(&self, path: &Path, format: DatasetFormat) -> Result<ImportSummary> {
+        info!("Importing local {:?} dataset from {}", format, path.display());
+
+        let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+        // Parse on a blocking thread; the bounded channel keeps memory flat
+        // however large the file is
+        let (tx, mut rx) = mpsc::channel::<ParsedRow>(BATCH_SIZE * 2);
+        let reader = tokio::task::spawn_blocking(move || read_dataset(file, format, tx));
+
+        let mut summary = ImportSummary {
+            path: path.to_path_buf(),
+            ..ImportSummary::default()
+        };
+        let mut batch: Vec<(usize, Docket)> = Vec::with_capacity(BATCH_SIZE);
+
+        while let Some((row, parsed)) = rx.recv().await {
+            summary.total_rows += 1;
+            match parsed {
+                Ok(docket) => batch.push((row, docket)),
+                Err(message) => summary.record_error(row, message),
+            }
+            if batch.len() >= BATCH_SIZE {
+                self.upsert_dockets(std::mem::take(&mut batch), &mut summary).await?;
+            }
+        }
+        self.upsert_dockets(batch, &mut summary).await?;
+
+        reader.await.context("Dataset reader panicked")??;
+
+        info!(
+            "Imported {}/{} row(s) from {} ({} failed)",
+            summary.imported,
+            summary.total_rows,
+            path.display(),
+            summary.failed
+        );
+        Ok(summary)
+    }
+
+    async fn upsert_dockets(&self, batch: Vec<(usize, Docket)>, summary: &mut ImportSummary) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.db_pool.begin().await?;
+        for (row, docket) in batch {
+            let docket_number = docket.docket_number.clone().unwrap_or_else(|| docket.id.clone());
+            let court_id = court_id(&docket.court);
+            let data = serde_json::to_string(&docket)?;
+
+            let updated = sqlx::query(
+                r#"
+                UPDATE docket_cache SET data = ?, last_updated = CURRENT_TIMESTAMP
+                WHERE docket_number = ? AND court_id = ?
+                "#,
+            )
+            .bind(&data)
+            .bind(&docket_number)
+            .bind(&court_id)
+            .execute(&mut *tx)
+            .await;
+
+            let result = match updated {
+                Ok(done) if done.rows_affected() > 0 => Ok(()),
+                Ok(_) => sqlx::query(
+                    "INSERT INTO docket_cache (id, docket_number, court_id, data) VALUES (?, ?, ?, ?)",
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(&docket_number)
+                .bind(&court_id)
+                .bind(&data)
+                .execute(&mut *tx)
+                .await
+                .map(|_| ()),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => summary.imported += 1,
+                Err(e) => summary.record_error(row, format!("Database error: {}", e)),
+            }
+        }
+        tx.commit().await.context("Failed to commit imported dockets")?;
+
+        Ok(())
+    }
+
     // ========================================================================
     // Scheduled Sync
     // ========================================================================
@@ -540,6 +642,144 @@ impl BulkImportService {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DatasetFormat {
+    /// One docket per row with the columns of `CsvDocketRow`
+    Csv,
+    /// One JSON docket per line
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowError {
+    /// 1-based data row (the CSV header is not counted)
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub path: PathBuf,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub failed: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportSummary {
+    pub(crate) fn record_error(&mut self, row: usize, message: String) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(RowError { row, message });
+        }
+    }
+}
+
+type ParsedRow = (usize, Result<Docket, String>);
+
+/// Flat docket columns in a CSV dump; related records are not included
+#[derive(Debug, Deserialize)]
+struct CsvDocketRow {
+    id: String,
+    docket_number: Option<String>,
+    caption: String,
+    status: CaseStatus,
+    court: CourtLevel,
+    county: String,
+    filed: String,
+    otn: Option<String>,
+    sid: Option<String>,
+    judge: Option<String>,
+    courtroom: Option<String>,
+    division: Option<String>,
+}
+
+impl CsvDocketRow {
+    fn into_docket(self) -> Result<Docket, String> {
+        let filed = DateTime::parse_from_rfc3339(&self.filed)
+            .map(|d| d.with_timezone(&Utc))
+            .or_else(|_| crate::utils::date::parse_date_flexible(&self.filed))
+            .map_err(|_| format!("Invalid filed date: {}", self.filed))?;
+        let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+
+        Ok(Docket {
+            id: self.id,
+            caption: self.caption,
+            status: self.status,
+            court: self.court,
+            county: self.county,
+            filed,
+            docket_number: non_empty(self.docket_number),
+            otn: non_empty(self.otn),
+            sid: non_empty(self.sid),
+            judge: non_empty(self.judge),
+            courtroom: non_empty(self.courtroom),
+            division: non_empty(self.division),
+            parties: vec![],
+            charges: vec![],
+            events: vec![],
+            filings: vec![],
+            financials: vec![],
+            attachments: None,
+            last_updated: None,
+            source_url: None,
+            source: None,
+            fetched_at: Some(Utc::now()),
+            hash: None,
+        })
+    }
+}
+
+fn validated(docket: Docket) -> Result<Docket, String> {
+    docket
+        .validate()
+        .map_err(|e| format!("Validation failed: {}", e))?;
+    Ok(docket)
+}
+
+/// Parse every row of `file`, sending results in order until the file ends
+/// or the receiver is dropped
+fn read_dataset(file: std::fs::File, format: DatasetFormat, tx: mpsc::Sender<ParsedRow>) -> Result<()> {
+    match format {
+        DatasetFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(file);
+            for (index, record) in reader.deserialize::<CsvDocketRow>().enumerate() {
+                let parsed = record
+                    .map_err(|e| format!("Malformed row: {}", e))
+                    .and_then(CsvDocketRow::into_docket)
+                    .and_then(validated);
+                if tx.blocking_send((index + 1, parsed)).is_err() {
+                    break;
+                }
+            }
+        }
+        DatasetFormat::Ndjson => {
+            let mut row = 0;
+            for line in BufReader::new(file).lines() {
+                let line = line.context("Failed to read dataset")?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                row += 1;
+                let parsed = serde_json::from_str::<Docket>(&line)
+                    .map_err(|e| format!("Malformed JSON: {}", e))
+                    .and_then(validated);
+                if tx.blocking_send((row, parsed)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn court_id(court: &CourtLevel) -> String {
+    serde_json::to_value(court)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[derive(Debug)]
 struct SyncSchedule {
     id: String,
@@ -548,3 +788,79 @@ struct SyncSchedule {
     schedule_type: String,
     last_run_at: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn service() -> BulkImportService {
+        let db = test_database().await;
+        BulkImportService::new(db, None, None)
+    }
+
+    async fn cached_count(service: &BulkImportService) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM docket_cache")
+            .fetch_one(&service.db_pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_malformed_row_is_reported_and_rest_imported() {
+        let service = service().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dockets.ndjson");
+        let docket = |number: &str| {
+            serde_json::json!({
+                "id": number, "caption": "Commonwealth v. Smith", "status": "Active", "court": "CP",
+                "county": "Philadelphia", "filed": "2024-01-15T00:00:00Z", "docket_number": number,
+                "otn": null, "sid": null, "judge": null, "courtroom": null, "division": null,
+                "parties": [], "charges": [], "events": [], "filings": [], "financials": [],
+                "attachments": null, "last_updated": null, "source_url": null, "fetched_at": null, "hash": null
+            })
+            .to_string()
+        };
+        let lines = [
+            docket("CP-51-CR-0000001-2024"),
+            r#"{"id": "broken", "caption": "#.to_string(),
+            docket("CP-51-CR-0000003-2024"),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let summary = service.import_local_dataset(&path, DatasetFormat::Ndjson).await.unwrap();
+
+        assert_eq!(summary.total_rows, 3);
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.errors[0].row, 2);
+        assert!(summary.errors[0].message.starts_with("Malformed JSON"));
+        assert_eq!(cached_count(&service).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_csv_invalid_row_is_skipped_and_reimport_upserts() {
+        let service = service().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dockets.csv");
+        std::fs::write(
+            &path,
+            "id,docket_number,caption,status,court,county,filed,otn,sid,judge,courtroom,division\n\
+             1,MJ-1,Commonwealth v. Doe,Active,MDJ,Dauphin,2024-02-01,,,,,\n\
+             2,MJ-2,Commonwealth v. Roe,Sleeping,MDJ,Dauphin,2024-02-02,,,,,\n\
+             3,MJ-3,,Closed,MDJ,Dauphin,02/03/2024,,,,,\n\
+             4,MJ-4,Commonwealth v. Poe,Closed,MDJ,Dauphin,02/04/2024,,,Hon. A. Judge,,\n",
+        )
+        .unwrap();
+
+        let summary = service.import_local_dataset(&path, DatasetFormat::Csv).await.unwrap();
+
+        assert_eq!(summary.imported, 2);
+        let failed_rows: Vec<usize> = summary.errors.iter().map(|e| e.row).collect();
+        assert_eq!(failed_rows, [2, 3]);
+        assert!(summary.errors[1].message.starts_with("Validation failed"));
+
+        service.import_local_dataset(&path, DatasetFormat::Csv).await.unwrap();
+        assert_eq!(cached_count(&service).await, 2);
+    }
+}
@@ -3,11 +3,14 @@
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Datelike, Utc, Duration};
 use reqwest::Client;
 use std::collections::HashMap;
 use tracing::{info, warn, error};
 
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::utils::date::{is_court_holiday, roll_forward_to_business_day, CourtCalendar};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarEvent {
     pub id: String,
@@ -92,7 +95,7 @@ pub enum DeadlineType {
     Trial,
     Appeal,
     Settlement,
-    Statute OfLimitations,
+    StatuteOfLimitations,
     Custom,
 }
 
@@ -108,6 +111,7 @@ pub struct CalendarSyncService {
     client: Client,
     google_credentials: Option<GoogleCalendarCredentials>,
     outlook_credentials: Option<OutlookCalendarCredentials>,
+    clock: SharedClock,
 }
 
 #[derive(Clone)]
@@ -133,9 +137,21 @@ impl CalendarSyncService {
             client: Client::new(),
             google_credentials: None,
             outlook_credentials: None,
+            clock: system_clock(),
         }
     }
 
+    /// Time source for telling upcoming deadlines from past ones
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The current time on this calendar's clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
     pub fn with_google(mut self, credentials: GoogleCalendarCredentials) -> Self {
         self.google_credentials = Some(credentials);
         self
@@ -146,6 +162,18 @@ impl CalendarSyncService {
         self
     }
 
+    /// The connected provider new events go to: Google, then Outlook,
+    /// otherwise the local calendar
+    pub fn default_provider(&self) -> CalendarProvider {
+        if self.google_credentials.is_some() {
+            CalendarProvider::Google
+        } else if self.outlook_credentials.is_some() {
+            CalendarProvider::Outlook
+        } else {
+            CalendarProvider::Local
+        }
+    }
+
     /// Sync event to calendar provider
     pub async fn sync_event(&self, event: CalendarEvent) -> Result<CalendarEvent> {
         match event.calendar_provider {
@@ -190,10 +218,14 @@ impl CalendarSyncService {
             }
         });
 
+        // An event that was synced before is updated in place
         let url = "https://www.googleapis.com/calendar/v3/calendars/primary/events";
+        let request = match &event.external_id {
+            Some(external_id) => self.client.patch(format!("{}/{}", url, external_id)),
+            None => self.client.post(url),
+        };
 
-        let response = self.client
-            .post(url)
+        let response = request
             .header("Authorization", format!("Bearer {}", creds.access_token))
             .header("Content-Type", "application/json")
             .json(&google_event)
@@ -205,11 +237,11 @@ impl CalendarSyncService {
             return Err(anyhow!("Google Calendar API error: {}", error_text));
         }
 
-        let created_event: serde_json::Value = response.json().await?;
-        let external_id = created_event["id"].as_str()
+        let synced_event: serde_json::Value = response.json().await?;
+        let external_id = synced_event["id"].as_str()
             .ok_or_else(|| anyhow!("No event ID in response"))?;
 
-        info!("Google Calendar event created: {}", external_id);
+        info!("Google Calendar event synced: {}", external_id);
 
         Ok(CalendarEvent {
             external_id: Some(external_id.to_string()),
@@ -253,9 +285,12 @@ impl CalendarSyncService {
         });
 
         let url = "https://graph.microsoft.com/v1.0/me/events";
+        let request = match &event.external_id {
+            Some(external_id) => self.client.patch(format!("{}/{}", url, external_id)),
+            None => self.client.post(url),
+        };
 
-        let response = self.client
-            .post(url)
+        let response = request
             .header("Authorization", format!("Bearer {}", creds.access_token))
             .header("Content-Type", "application/json")
             .json(&outlook_event)
@@ -267,11 +302,11 @@ impl CalendarSyncService {
             return Err(anyhow!("Outlook Calendar API error: {}", error_text));
         }
 
-        let created_event: serde_json::Value = response.json().await?;
-        let external_id = created_event["id"].as_str()
+        let synced_event: serde_json::Value = response.json().await?;
+        let external_id = synced_event["id"].as_str()
             .ok_or_else(|| anyhow!("No event ID in response"))?;
 
-        info!("Outlook Calendar event created: {}", external_id);
+        info!("Outlook Calendar event synced: {}", external_id);
 
         Ok(CalendarEvent {
             external_id: Some(external_id.to_string()),
@@ -302,12 +337,7 @@ impl CalendarSyncService {
         let mut deadline = event_date;
         let mut days_added = 0;
 
-        // Pennsylvania court holidays (simplified list)
-        let court_holidays = vec![
-            "01-01", // New Year's Day
-            "07-04", // Independence Day
-            "12-25", // Christmas
-        ];
+        let calendar = CourtCalendar::pennsylvania();
 
         while days_added < days_to_add {
             deadline = deadline + Duration::days(1);
@@ -321,16 +351,18 @@ impl CalendarSyncService {
             }
 
             // Skip court holidays
-            if exclude_court_holidays {
-                let date_str = deadline.format("%m-%d").to_string();
-                if court_holidays.contains(&date_str.as_str()) {
-                    continue;
-                }
+            if exclude_court_holidays && is_court_holiday(deadline, &calendar) {
+                continue;
             }
 
             days_added += 1;
         }
 
+        // A deadline landing on a weekend or holiday runs to the next business day
+        if exclude_weekends && exclude_court_holidays {
+            deadline = roll_forward_to_business_day(deadline, &calendar);
+        }
+
         Ok(deadline)
     }
 
@@ -357,7 +389,7 @@ impl CalendarSyncService {
                     minutes_before: 60, // 1 hour before
                 },
             ],
-            calendar_provider: CalendarProvider::Local,
+            calendar_provider: self.default_provider(),
             external_id: None,
             sync_status: SyncStatus::Pending,
         }
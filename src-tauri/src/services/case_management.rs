@@ -1,21 +1,50 @@
 // Case Management Service - Manages clients, matters, and automated document generation
 
+use crate::config::NumberingScheme;
 use crate::domain::case_management::*;
+use crate::services::numbering;
+use crate::services::settlement_calculator::{
+    validate_fee_agreement, CaseType, FeeAgreementStatus, SettlementCalculatorService,
+};
 use anyhow::{Context, Result};
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Sqlite, SqliteConnection};
 use std::collections::HashMap;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
+/// Largest page `list_matters` will return
+pub const MAX_MATTER_PAGE_SIZE: u32 = 200;
+
+/// Forum assumed for a matter that names none
+pub const DEFAULT_FORUM_STATE: &str = "PA";
+
+/// A new matter and, when it has a contingency fee, whether the forum
+/// requires the court to approve the fee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedMatter {
+    pub matter: Matter,
+    pub fee_agreement: Option<FeeAgreementStatus>,
+}
+
 pub struct CaseManagementService {
     db_pool: Pool<Sqlite>,
+    numbering: NumberingScheme,
 }
 
 impl CaseManagementService {
     pub fn new(db_pool: Pool<Sqlite>) -> Self {
-        Self { db_pool }
+        Self {
+            db_pool,
+            numbering: crate::config::NumberingConfig::default().matter,
+        }
+    }
+
+    pub fn with_numbering(mut self, numbering: NumberingScheme) -> Self {
+        self.numbering = numbering;
+        self
     }
 
     // ========================================================================
@@ -24,6 +53,12 @@ impl CaseManagementService {
 
     #[instrument(skip(self, request))]
     pub async fn create_client(&self, request: CreateClientRequest) -> Result<Client> {
+        let mut conn = self.db_pool.acquire().await?;
+        self.create_client_in(&mut conn, request).await
+    }
+
+    /// [`Self::create_client`] on `conn`, e.g. inside a caller's transaction
+    pub async fn create_client_in(&self, conn: &mut SqliteConnection, request: CreateClientRequest) -> Result<Client> {
         info!("Creating new client: {} {}", request.first_name, request.last_name);
 
         let client = Client {
@@ -70,7 +105,7 @@ impl CaseManagementService {
             client.updated_at.to_rfc3339(),
             serde_json::to_string(&client.status)?
         )
-        .execute(&self.db_pool)
+        .execute(&mut *conn)
         .await
         .context("Failed to create client")?;
 
@@ -82,40 +117,13 @@ impl CaseManagementService {
     pub async fn get_client(&self, client_id: &str) -> Result<Client> {
         debug!("Fetching client: {}", client_id);
 
-        let row = sqlx::query!(
-            r#"SELECT * FROM clients WHERE id = ?"#,
-            client_id
-        )
-        .fetch_one(&self.db_pool)
-        .await
-        .context("Client not found")?;
+        let row = sqlx::query("SELECT * FROM clients WHERE id = ?")
+            .bind(client_id)
+            .fetch_one(&self.db_pool)
+            .await
+            .context("Client not found")?;
 
-        Ok(Client {
-            id: row.id,
-            first_name: row.first_name,
-            last_name: row.last_name,
-            email: row.email,
-            phone: row.phone,
-            address: row.address,
-            city: row.city,
-            state: row.state,
-            zip_code: row.zip_code,
-            date_of_birth: None, // TODO: Parse from row
-            ssn_encrypted: row.ssn_encrypted,
-            notes: row.notes,
-            client_type: serde_json::from_str(&row.client_type.unwrap_or("\"individual\"".to_string()))?,
-            business_name: row.business_name,
-            contact_person: row.contact_person,
-            created_at: chrono::DateTime::parse_from_rfc3339(&row.created_at)
-                .ok()
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(Utc::now),
-            updated_at: chrono::DateTime::parse_from_rfc3339(&row.updated_at)
-                .ok()
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(Utc::now),
-            status: serde_json::from_str(&row.status.unwrap_or("\"active\"".to_string()))?,
-        })
+        self.row_to_client(row)
     }
 
     #[instrument(skip(self))]
@@ -175,14 +183,47 @@ impl CaseManagementService {
     // ========================================================================
 
     #[instrument(skip(self, request))]
-    pub async fn create_matter(&self, request: CreateMatterRequest) -> Result<Matter> {
+    pub async fn create_matter(&self, request: CreateMatterRequest) -> Result<CreatedMatter> {
+        let mut conn = self.db_pool.acquire().await?;
+        self.create_matter_in(&mut conn, request).await
+    }
+
+    /// [`Self::create_matter`] on `conn`, e.g. inside a caller's transaction
+    pub async fn create_matter_in(&self, conn: &mut SqliteConnection, request: CreateMatterRequest) -> Result<CreatedMatter> {
         info!("Creating new matter for client: {}", request.client_id);
 
         // Verify client exists
-        self.get_client(&request.client_id).await?;
+        let client_id: String = sqlx::query_scalar("SELECT id FROM clients WHERE id = ?")
+            .bind(&request.client_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .context("Client not found")?;
+
+        // Fee rules are the forum's, wherever the client lives
+        let fee_agreement = match request.contingency_fee_percentage {
+            Some(percentage) => {
+                let forum = request.forum_state.as_deref().unwrap_or(DEFAULT_FORUM_STATE);
+                let rules = SettlementCalculatorService::new(self.db_pool.clone())
+                    .load_jurisdiction_rules(forum)
+                    .await?;
+                let case_type = request.case_type.as_deref().and_then(settlement_case_type);
+
+                let status = validate_fee_agreement(percentage, case_type.as_ref(), &rules.attorney_fee_rules)?;
+                if status == FeeAgreementStatus::RequiresCourtApproval {
+                    warn!(
+                        "Contingency fee of {:.2}% for client {} requires court approval in {}",
+                        percentage * 100.0,
+                        client_id,
+                        rules.jurisdiction
+                    );
+                }
+                Some(status)
+            }
+            None => None,
+        };
 
         // Generate unique matter number
-        let matter_number = self.generate_matter_number(&request.matter_type).await?;
+        let matter_number = self.generate_matter_number(&mut *conn, &request.matter_type).await?;
 
         let matter = Matter {
             id: Uuid::new_v4().to_string(),
@@ -206,6 +247,7 @@ impl CaseManagementService {
             status: MatterStatus::Active,
             outcome: None,
             settlement_amount: None,
+            responsible_attorney_id: request.responsible_attorney_id.clone(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
             closed_at: None,
@@ -215,8 +257,9 @@ impl CaseManagementService {
             r#"
             INSERT INTO matters (
                 id, client_id, matter_number, title, description, matter_type, case_type,
-                court_level, court_name, county, opposing_party, status, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                court_level, court_name, county, opposing_party, status, responsible_attorney_id,
+                forum_state, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             matter.id,
             matter.client_id,
@@ -230,15 +273,17 @@ impl CaseManagementService {
             matter.county,
             matter.opposing_party,
             serde_json::to_string(&matter.status)?,
+            matter.responsible_attorney_id,
+            request.forum_state,
             matter.created_at.to_rfc3339(),
             matter.updated_at.to_rfc3339()
         )
-        .execute(&self.db_pool)
+        .execute(&mut *conn)
         .await
         .context("Failed to create matter")?;
 
         info!("Matter created successfully: {}", matter.id);
-        Ok(matter)
+        Ok(CreatedMatter { matter, fee_agreement })
     }
 
     #[instrument(skip(self))]
@@ -261,6 +306,11 @@ impl CaseManagementService {
         debug!("Fetching matter summary: {}", matter_id);
 
         let matter = self.get_matter(matter_id).await?;
+        self.summarize(matter).await
+    }
+
+    async fn summarize(&self, matter: Matter) -> Result<MatterSummary> {
+        let matter_id = matter.id.as_str();
         let client = self.get_client(&matter.client_id).await?;
 
         // Get counts and statistics
@@ -289,7 +339,7 @@ impl CaseManagementService {
         .unwrap_or(0) as i32;
 
         let total_time = sqlx::query_scalar!(
-            r#"SELECT COALESCE(SUM(hours), 0) as total FROM time_entries WHERE matter_id = ?"#,
+            r#"SELECT COALESCE(SUM(duration_minutes), 0) / 60.0 as total FROM time_entries WHERE matter_id = ?"#,
             matter_id
         )
         .fetch_one(&self.db_pool)
@@ -319,52 +369,133 @@ impl CaseManagementService {
         })
     }
 
+    /// One page of the matters matching `filter`, with the total across all
+    /// pages. `page` is 1-based; `limit` is capped at `MAX_MATTER_PAGE_SIZE`.
     #[instrument(skip(self))]
-    pub async fn list_matters(&self, client_id: Option<&str>, status: Option<MatterStatus>) -> Result<Vec<Matter>> {
+    pub async fn list_matters(&self, filter: MatterFilter, page: u32, limit: u32) -> Result<Page<MatterSummary>> {
+        use sqlx::Row;
+
         debug!("Listing matters");
+        let page = page.max(1);
+        let limit = limit.clamp(1, MAX_MATTER_PAGE_SIZE);
+
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut params: Vec<String> = Vec::new();
+        if let Some(status) = &filter.status {
+            conditions.push("m.status = ?");
+            params.push(serde_json::to_string(status)?);
+        }
+        if let Some(client_id) = &filter.client_id {
+            conditions.push("m.client_id = ?");
+            params.push(client_id.clone());
+        }
+        if let Some(attorney_id) = &filter.responsible_attorney_id {
+            conditions.push("m.responsible_attorney_id = ?");
+            params.push(attorney_id.clone());
+        }
+        if let Some(practice_area_id) = &filter.practice_area_id {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM matter_practice_areas pa WHERE pa.matter_id = m.id AND pa.practice_area_id = ?)",
+            );
+            params.push(practice_area_id.clone());
+        }
+        if let Some(folder_id) = &filter.folder_id {
+            conditions.push("EXISTS (SELECT 1 FROM matter_folders f WHERE f.matter_id = m.id AND f.folder_id = ?)");
+            params.push(folder_id.clone());
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
 
-        let query = match (client_id, status) {
-            (Some(cid), Some(stat)) => {
-                let status_str = serde_json::to_string(&stat)?;
-                sqlx::query!(
-                    r#"SELECT * FROM matters WHERE client_id = ? AND status = ? ORDER BY created_at DESC"#,
-                    cid,
-                    status_str
-                )
-                .fetch_all(&self.db_pool)
-                .await?
-            }
-            (Some(cid), None) => {
-                sqlx::query!(
-                    r#"SELECT * FROM matters WHERE client_id = ? ORDER BY created_at DESC"#,
-                    cid
-                )
-                .fetch_all(&self.db_pool)
-                .await?
+        let count_sql = format!("SELECT COUNT(*) FROM matters m{}", where_clause);
+        let mut count = sqlx::query_scalar::<_, i64>(&count_sql);
+        for param in &params {
+            count = count.bind(param);
+        }
+        let total = count.fetch_one(&self.db_pool).await.context("Failed to count matters")?;
+
+        // The summary figures come back with each matter, so a page is one query
+        let page_sql = format!(
+            "SELECT m.*,
+                    (SELECT COUNT(*) FROM case_events e WHERE e.matter_id = m.id) AS events_count,
+                    (SELECT COUNT(*) FROM case_documents d WHERE d.matter_id = m.id) AS documents_count,
+                    (SELECT COUNT(*) FROM tasks t WHERE t.matter_id = m.id AND t.status != 'completed') AS tasks_pending,
+                    (SELECT CAST(COALESCE(SUM(te.duration_minutes), 0) AS REAL) / 60.0
+                     FROM time_entries te WHERE te.matter_id = m.id) AS total_time,
+                    (SELECT CAST(COALESCE(SUM(x.amount), 0) AS REAL) FROM expenses x WHERE x.matter_id = m.id) AS total_expenses
+             FROM matters m{} ORDER BY {} LIMIT ? OFFSET ?",
+            where_clause,
+            matter_order_by(filter.sort)
+        );
+        let mut query = sqlx::query(&page_sql);
+        for param in &params {
+            query = query.bind(param);
+        }
+        let rows = query
+            .bind(limit as i64)
+            .bind((page - 1) as i64 * limit as i64)
+            .fetch_all(&self.db_pool)
+            .await
+            .context("Failed to list matters")?;
+
+        // Clients for the whole page in one query rather than one per matter
+        let mut client_ids: Vec<String> = Vec::new();
+        for row in &rows {
+            let client_id: String = row.try_get("client_id")?;
+            if !client_ids.contains(&client_id) {
+                client_ids.push(client_id);
             }
-            (None, Some(stat)) => {
-                let status_str = serde_json::to_string(&stat)?;
-                sqlx::query!(
-                    r#"SELECT * FROM matters WHERE status = ? ORDER BY created_at DESC"#,
-                    status_str
-                )
-                .fetch_all(&self.db_pool)
-                .await?
+        }
+        let mut clients = HashMap::new();
+        if !client_ids.is_empty() {
+            let client_sql = format!(
+                "SELECT * FROM clients WHERE id IN ({})",
+                vec!["?"; client_ids.len()].join(", ")
+            );
+            let mut query = sqlx::query(&client_sql);
+            for client_id in &client_ids {
+                query = query.bind(client_id);
             }
-            (None, None) => {
-                sqlx::query!(r#"SELECT * FROM matters ORDER BY created_at DESC"#)
-                    .fetch_all(&self.db_pool)
-                    .await?
+            for row in query.fetch_all(&self.db_pool).await.context("Failed to load clients")? {
+                let client = self.row_to_client(row)?;
+                clients.insert(client.id.clone(), client);
             }
-        };
+        }
 
-        let matters: Vec<Matter> = query
-            .into_iter()
-            .filter_map(|row| self.row_to_matter(row).ok())
-            .collect();
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            let events_count: i64 = row.try_get("events_count")?;
+            let documents_count: i64 = row.try_get("documents_count")?;
+            let tasks_pending: i64 = row.try_get("tasks_pending")?;
+            let total_time: f64 = row.try_get("total_time")?;
+            let total_expenses: f64 = row.try_get("total_expenses")?;
+            let matter = self.row_to_matter(row)?;
+            let client = clients
+                .get(&matter.client_id)
+                .cloned()
+                .with_context(|| format!("Client {} not found", matter.client_id))?;
+            items.push(MatterSummary {
+                matter,
+                client,
+                events_count: events_count as i32,
+                documents_count: documents_count as i32,
+                tasks_pending: tasks_pending as i32,
+                // Only a single matter's summary looks up its next deadline
+                next_deadline: None,
+                total_time: total_time as f32,
+                total_expenses: total_expenses as f32,
+            });
+        }
 
-        info!("Found {} matters", matters.len());
-        Ok(matters)
+        info!("Found {} matters; returning {} from page {}", total, items.len(), page);
+        Ok(Page {
+            items,
+            page,
+            limit,
+            total: total as u64,
+        })
     }
 
     // ========================================================================
@@ -447,7 +578,7 @@ impl CaseManagementService {
     // Helper Methods
     // ========================================================================
 
-    async fn generate_matter_number(&self, matter_type: &MatterType) -> Result<String> {
+    async fn generate_matter_number(&self, conn: &mut SqliteConnection, matter_type: &MatterType) -> Result<String> {
         let prefix = match matter_type {
             MatterType::Civil => "CIV",
             MatterType::Criminal => "CRIM",
@@ -464,16 +595,15 @@ impl CaseManagementService {
             MatterType::Other => "OTH",
         };
 
-        let year = Utc::now().format("%Y");
-        let count = sqlx::query_scalar!(
-            r#"SELECT COUNT(*) as count FROM matters WHERE matter_number LIKE ?"#,
-            format!("{}-{}-", prefix, year)
+        // Each matter type counts separately
+        numbering::next_number(
+            conn,
+            &self.numbering,
+            &format!("matter:{}", prefix),
+            Some(prefix),
+            Utc::now(),
         )
-        .fetch_one(&self.db_pool)
-        .await?
-        .unwrap_or(0);
-
-        Ok(format!("{}-{}-{:04}", prefix, year, count + 1))
+        .await
     }
 
     async fn get_template(&self, template_id: &str) -> Result<DocumentTemplate> {
@@ -593,6 +723,39 @@ impl CaseManagementService {
         Err(anyhow::anyhow!("Not implemented"))
     }
 
+    fn row_to_client(&self, row: sqlx::sqlite::SqliteRow) -> Result<Client> {
+        use sqlx::Row;
+
+        let client_type: Option<String> = row.try_get("client_type")?;
+        let status: Option<String> = row.try_get("status")?;
+        Ok(Client {
+            id: row.try_get("id")?,
+            first_name: row.try_get("first_name")?,
+            last_name: row.try_get("last_name")?,
+            email: row.try_get("email")?,
+            phone: row.try_get("phone")?,
+            address: row.try_get("address")?,
+            city: row.try_get("city")?,
+            state: row.try_get("state")?,
+            zip_code: row.try_get("zip_code")?,
+            date_of_birth: None, // TODO: Parse from row
+            ssn_encrypted: row.try_get("ssn_encrypted")?,
+            notes: row.try_get("notes")?,
+            client_type: serde_json::from_str(&client_type.unwrap_or_else(|| "\"individual\"".to_string()))?,
+            business_name: row.try_get("business_name")?,
+            contact_person: row.try_get("contact_person")?,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.try_get::<String, _>("created_at")?)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now),
+            updated_at: chrono::DateTime::parse_from_rfc3339(&row.try_get::<String, _>("updated_at")?)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now),
+            status: serde_json::from_str(&status.unwrap_or_else(|| "\"active\"".to_string()))?,
+        })
+    }
+
     fn row_to_matter(&self, row: sqlx::sqlite::SqliteRow) -> Result<Matter> {
         use sqlx::Row;
 
@@ -618,6 +781,7 @@ impl CaseManagementService {
             status: serde_json::from_str(&row.try_get::<String, _>("status")?)?,
             outcome: row.try_get("outcome")?,
             settlement_amount: row.try_get("settlement_amount")?,
+            responsible_attorney_id: row.try_get("responsible_attorney_id")?,
             created_at: chrono::DateTime::parse_from_rfc3339(&row.try_get::<String, _>("created_at")?)
                 .ok()
                 .map(|dt| dt.with_timezone(&Utc))
@@ -630,3 +794,130 @@ impl CaseManagementService {
         })
     }
 }
+
+/// ORDER BY for a matter sort; ties fall back to the id so pages are stable
+fn matter_order_by(sort: MatterSort) -> &'static str {
+    match sort {
+        MatterSort::NewestFirst => "m.created_at DESC, m.id",
+        MatterSort::OldestFirst => "m.created_at ASC, m.id",
+        MatterSort::RecentlyUpdated => "m.updated_at DESC, m.id",
+        MatterSort::MatterNumber => "m.matter_number ASC",
+        MatterSort::Title => "m.title COLLATE NOCASE ASC, m.id",
+    }
+}
+
+/// The settlement case type a matter's free-text case type names, e.g.
+/// "Medical Malpractice" or "medical_malpractice"
+fn settlement_case_type(case_type: &str) -> Option<CaseType> {
+    let pascal: String = case_type
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect();
+    serde_json::from_value(json!(pascal)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn service() -> CaseManagementService {
+        let db = test_database().await;
+        sqlx::query(
+            r#"INSERT INTO clients (id, first_name, last_name, client_type, status, created_at, updated_at)
+               VALUES ('client-1', 'Ada', 'Byron', '"individual"', '"active"', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"#,
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        CaseManagementService::new(db)
+    }
+
+    async fn insert_matter(service: &CaseManagementService, number: u32, status: &str) {
+        sqlx::query(
+            "INSERT INTO matters (id, client_id, matter_number, title, matter_type, status, created_at, updated_at)
+             VALUES (?, 'client-1', ?, ?, '\"civil\"', ?, ?, ?)",
+        )
+        .bind(format!("matter-{}", number))
+        .bind(format!("2026-{:04}", number))
+        .bind(format!("Matter {}", number))
+        .bind(format!("\"{}\"", status))
+        .bind(format!("2026-01-{:02}T00:00:00Z", number))
+        .bind(format!("2026-01-{:02}T00:00:00Z", number))
+        .execute(&service.db_pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_matters_filters_by_status_and_pages() {
+        let service = service().await;
+        for number in 1..=5 {
+            insert_matter(&service, number, "active").await;
+        }
+        insert_matter(&service, 6, "closed").await;
+        sqlx::query(
+            "INSERT INTO tasks (id, matter_id, title, status, created_at, updated_at)
+             VALUES ('task-1', 'matter-3', 'Draft answer', 'pending', '2026-01-03', '2026-01-03')",
+        )
+        .execute(&service.db_pool)
+        .await
+        .unwrap();
+
+        let filter = MatterFilter {
+            status: Some(MatterStatus::Active),
+            sort: MatterSort::OldestFirst,
+            ..MatterFilter::default()
+        };
+        let page = service.list_matters(filter.clone(), 2, 2).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.total_pages(), 3);
+        let ids: Vec<_> = page.items.iter().map(|summary| summary.matter.id.as_str()).collect();
+        assert_eq!(ids, vec!["matter-3", "matter-4"]);
+        assert_eq!(page.items[0].client.last_name, "Byron");
+        assert_eq!(page.items[0].tasks_pending, 1);
+        assert_eq!(page.items[1].tasks_pending, 0);
+
+        let last = service.list_matters(filter, 3, 2).await.unwrap();
+        let ids: Vec<_> = last.items.iter().map(|summary| summary.matter.id.as_str()).collect();
+        assert_eq!(ids, vec!["matter-5"]);
+
+        let everything = service.list_matters(MatterFilter::default(), 1, 50).await.unwrap();
+        assert_eq!(everything.total, 6);
+        assert_eq!(everything.items[0].matter.id, "matter-6");
+    }
+
+    fn contingency_matter(case_type: &str, forum_state: &str) -> CreateMatterRequest {
+        CreateMatterRequest {
+            client_id: "client-1".to_string(),
+            title: "Byron v. Mercy Hospital".to_string(),
+            description: None,
+            matter_type: MatterType::PersonalInjury,
+            case_type: Some(case_type.to_string()),
+            court_level: None,
+            court_name: None,
+            county: None,
+            opposing_party: None,
+            contingency_fee_percentage: Some(0.30),
+            responsible_attorney_id: None,
+            forum_state: Some(forum_state.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_contingency_fee_checked_against_the_forum_and_case_type() {
+        let service = service().await;
+
+        // New York's sliding scale binds only medical malpractice
+        let created = service.create_matter(contingency_matter("Personal Injury", "NY")).await.unwrap();
+        assert_eq!(created.fee_agreement, Some(FeeAgreementStatus::RequiresCourtApproval));
+        assert!(service.create_matter(contingency_matter("Medical Malpractice", "NY")).await.is_err());
+
+        let created = service.create_matter(contingency_matter("Medical Malpractice", "PA")).await.unwrap();
+        assert_eq!(created.fee_agreement, Some(FeeAgreementStatus::Permitted));
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::domain::*;
 use anyhow::Result;
+use chrono::Datelike;
+use regex::Regex;
+use std::sync::OnceLock;
 use tracing::{info, instrument};
 
 pub struct CitationService;
@@ -15,8 +18,7 @@ impl CitationService {
     pub async fn parse_citations(&self, text: &str, style: Option<&str>) -> Result<Vec<Citation>> {
         info!("Parsing citations from text");
         
-        // TODO: Implement Bluebook citation parsing
-        Ok(vec![])
+        Ok(extract_citations(text))
     }
     
     #[instrument(skip(self, citation))]
@@ -31,8 +33,18 @@ impl CitationService {
     pub async fn validate_citations(&self, citations: &[Citation], court_rules: Option<&CourtRules>) -> Result<Vec<CitationValidationResult>> {
         info!("Validating {} citations", citations.len());
         
-        // TODO: Implement citation validation
-        Ok(vec![])
+        Ok(citations
+            .iter()
+            .map(|citation| {
+                let validation = validate_citation(citation);
+                CitationValidationResult {
+                    citation: citation.clone(),
+                    is_valid: validation.is_valid,
+                    errors: validation.errors,
+                    suggestions: validation.suggestions,
+                }
+            })
+            .collect())
     }
     
     #[instrument(skip(self, citations))]
@@ -44,6 +56,388 @@ impl CitationService {
     }
 }
 
+/// Bluebook reporter and code abbreviations, keyed by the abbreviation with
+/// periods and spaces removed so "F3d", "F. 3d" and "F.3d" all match
+const REPORTER_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("us", "U.S."),
+    ("sct", "S. Ct."),
+    ("led", "L. Ed."),
+    ("led2d", "L. Ed. 2d"),
+    ("f", "F."),
+    ("f2d", "F.2d"),
+    ("f3d", "F.3d"),
+    ("f4th", "F.4th"),
+    ("fsupp", "F. Supp."),
+    ("fsupp2d", "F. Supp. 2d"),
+    ("fsupp3d", "F. Supp. 3d"),
+    ("a", "A."),
+    ("a2d", "A.2d"),
+    ("a3d", "A.3d"),
+    ("p", "P."),
+    ("p2d", "P.2d"),
+    ("p3d", "P.3d"),
+    ("ne", "N.E."),
+    ("ne2d", "N.E.2d"),
+    ("ne3d", "N.E.3d"),
+    ("nw", "N.W."),
+    ("nw2d", "N.W.2d"),
+    ("se", "S.E."),
+    ("se2d", "S.E.2d"),
+    ("sw", "S.W."),
+    ("sw2d", "S.W.2d"),
+    ("sw3d", "S.W.3d"),
+    ("so", "So."),
+    ("so2d", "So. 2d"),
+    ("so3d", "So. 3d"),
+    ("calrptr", "Cal. Rptr."),
+    ("calrptr2d", "Cal. Rptr. 2d"),
+    ("calrptr3d", "Cal. Rptr. 3d"),
+    ("nys", "N.Y.S."),
+    ("nys2d", "N.Y.S.2d"),
+    ("nys3d", "N.Y.S.3d"),
+    ("pa", "Pa."),
+    ("pasuper", "Pa. Super."),
+    ("pacmwlth", "Pa. Cmwlth."),
+    ("pad&c", "Pa. D. & C."),
+    ("usc", "U.S.C."),
+    ("cfr", "C.F.R."),
+    ("pacs", "Pa.C.S."),
+    ("ps", "P.S."),
+];
+
+/// Bluebook form of a reporter abbreviation; unknown reporters are returned
+/// with whitespace collapsed
+pub fn normalize_reporter(reporter: &str) -> String {
+    let key = reporter_key(reporter);
+
+    REPORTER_ABBREVIATIONS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, form)| form.to_string())
+        .unwrap_or_else(|| reporter.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Canonical form of a single reporter ("123 F3d 456" -> "123 F.3d 456") or
+/// code ("42 USC §1983" -> "42 U.S.C. § 1983") citation, used as the dedupe
+/// key for ingested records. Returns None for text that is not a citation.
+pub fn normalize_citation(citation: &str) -> Option<String> {
+    let citation = citation.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    static CODE_RE: OnceLock<Regex> = OnceLock::new();
+    static REPORTER_RE: OnceLock<Regex> = OnceLock::new();
+
+    let code = CODE_RE.get_or_init(|| Regex::new(r"^(\d+)\s+(.+?)\s*§+\s*([\w.\-]+)$").unwrap());
+    if let Some(cap) = code.captures(&citation) {
+        return Some(format!("{} {} § {}", &cap[1], normalize_reporter(&cap[2]), &cap[3]));
+    }
+
+    let reporter = REPORTER_RE.get_or_init(|| Regex::new(r"^(\d+)\s+(.+?)\s+(\d+)$").unwrap());
+    let cap = reporter.captures(&citation)?;
+    Some(format!("{} {} {}", &cap[1], normalize_reporter(&cap[2]), &cap[3]))
+}
+
+/// Reporter abbreviation: a capitalised token ("F.3d", "Pa.", "A3d") followed
+/// by more tokens or series ordinals ("F. Supp. 2d", "Pa. D. & C.")
+const REPORTER_PATTERN: &str = r"[A-Z][A-Za-z0-9.&']*(?:\s+(?:[A-Z][A-Za-z0-9.&']*|&|\d+(?:d|th)\b))*";
+
+/// Every case, statute and regulation citation in `text`, in document order.
+/// Case citations pick up a case name directly before them ("Smith v. Jones,")
+/// and a court/year parenthetical after them; nothing is checked against a
+/// reporter or a court here.
+pub fn extract_citations(text: &str) -> Vec<Citation> {
+    static CASE_RE: OnceLock<Regex> = OnceLock::new();
+    static CODE_CITATION_RE: OnceLock<Regex> = OnceLock::new();
+    static CASE_NAME_RE: OnceLock<Regex> = OnceLock::new();
+
+    let case_pattern = CASE_RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"\b(\d{{1,4}})\s+({})\s+(\d{{1,5}})\b(?:,\s*(\d+(?:[-–]\d+)?))?(?:\s*\(([^()]*?)\s*(\d{{4}})\))?",
+            REPORTER_PATTERN
+        ))
+        .expect("valid case citation pattern")
+    });
+    let code_pattern = CODE_CITATION_RE.get_or_init(|| {
+        Regex::new(r"\b(\d+)\s+([A-Z][A-Za-z.]*(?:\s+[A-Z][A-Za-z.]*)*)\s*§+\s*(\d[\w\-]*(?:\.\w+)*)")
+            .expect("valid code citation pattern")
+    });
+    let case_name_pattern = CASE_NAME_RE.get_or_init(|| {
+        Regex::new(r"((?:[A-Z][\w'&\-]*\.?\s+)+v\.\s+[^,;()]+),\s*$").expect("valid case name pattern")
+    });
+
+    let mut found: Vec<(usize, Citation)> = Vec::new();
+
+    for cap in case_pattern.captures_iter(text) {
+        let reporter = &cap[2];
+        // Without a period the "reporter" is usually ordinary prose ("12 March 2020")
+        if !reporter.contains('.') && !is_known_reporter(reporter) {
+            continue;
+        }
+        let full_match = cap.get(0).unwrap();
+        let title = case_name_pattern
+            .captures(&text[..full_match.start()])
+            .map(|name| strip_leading_signal(&name[1]));
+        let court = cap.get(5).map(|m| m.as_str().trim().to_string()).filter(|c| !c.is_empty());
+
+        let mut citation = new_citation(CitationType::Case, full_match.as_str());
+        citation.title = title;
+        citation.volume = Some(cap[1].to_string());
+        citation.reporter = Some(reporter.to_string());
+        citation.page = Some(cap[3].to_string());
+        citation.pin_cite = cap.get(4).map(|m| m.as_str().to_string());
+        citation.court = court;
+        citation.year = cap.get(6).map(|m| m.as_str().to_string());
+        found.push((full_match.start(), citation));
+    }
+
+    for cap in code_pattern.captures_iter(text) {
+        let full_match = cap.get(0).unwrap();
+        let code = normalize_reporter(&cap[2]);
+        let citation_type = if code == "C.F.R." {
+            CitationType::Regulation
+        } else {
+            CitationType::Statute
+        };
+
+        let mut citation = new_citation(citation_type, full_match.as_str());
+        citation.volume = Some(cap[1].to_string());
+        citation.reporter = Some(code);
+        citation.page = Some(cap[3].to_string());
+        found.push((full_match.start(), citation));
+    }
+
+    found.sort_by_key(|(start, _)| *start);
+    found.into_iter().map(|(_, citation)| citation).collect()
+}
+
+fn new_citation(citation_type: CitationType, full_citation: &str) -> Citation {
+    Citation {
+        id: None,
+        citation_type,
+        full_citation: full_citation.trim().to_string(),
+        short_form: None,
+        pin_cite: None,
+        parenthetical: None,
+        signal: None,
+        title: None,
+        reporter: None,
+        volume: None,
+        page: None,
+        year: None,
+        court: None,
+        jurisdiction: None,
+        is_valid: true,
+        errors: Vec::new(),
+        suggestions: Vec::new(),
+    }
+}
+
+/// Words that open a sentence or signal rather than name a party, e.g. the
+/// "See" in "See Smith v. Jones"
+const LEADING_WORDS: &[&str] = &["In", "See", "Also", "Cf.", "But", "Accord", "And", "Under", "As", "E.g."];
+
+fn strip_leading_signal(case_name: &str) -> String {
+    let mut name = case_name.trim();
+    while let Some((first, rest)) = name.split_once(' ') {
+        if !LEADING_WORDS.contains(&first) {
+            break;
+        }
+        name = rest.trim_start();
+    }
+    name.to_string()
+}
+
+fn reporter_key(reporter: &str) -> String {
+    reporter
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '.')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_known_reporter(reporter: &str) -> bool {
+    let key = reporter_key(reporter);
+    REPORTER_ABBREVIATIONS.iter().any(|(k, _)| *k == key)
+}
+
+/// Which courts a reporter publishes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CourtSystem {
+    UsSupreme,
+    FederalAppellate,
+    FederalDistrict,
+    State,
+}
+
+struct ReporterAuthority {
+    /// Bluebook form, as in `REPORTER_ABBREVIATIONS`
+    abbreviation: &'static str,
+    name: &'static str,
+    courts: CourtSystem,
+    first_year: i32,
+    /// None while the series is still being published
+    last_year: Option<i32>,
+}
+
+const REPORTERS: &[ReporterAuthority] = &[
+    ReporterAuthority { abbreviation: "U.S.", name: "United States Reports", courts: CourtSystem::UsSupreme, first_year: 1790, last_year: None },
+    ReporterAuthority { abbreviation: "S. Ct.", name: "Supreme Court Reporter", courts: CourtSystem::UsSupreme, first_year: 1882, last_year: None },
+    ReporterAuthority { abbreviation: "L. Ed.", name: "Lawyers' Edition", courts: CourtSystem::UsSupreme, first_year: 1790, last_year: Some(1956) },
+    ReporterAuthority { abbreviation: "L. Ed. 2d", name: "Lawyers' Edition, Second Series", courts: CourtSystem::UsSupreme, first_year: 1956, last_year: None },
+    ReporterAuthority { abbreviation: "F.", name: "Federal Reporter", courts: CourtSystem::FederalAppellate, first_year: 1880, last_year: Some(1924) },
+    ReporterAuthority { abbreviation: "F.2d", name: "Federal Reporter, Second Series", courts: CourtSystem::FederalAppellate, first_year: 1924, last_year: Some(1993) },
+    ReporterAuthority { abbreviation: "F.3d", name: "Federal Reporter, Third Series", courts: CourtSystem::FederalAppellate, first_year: 1993, last_year: Some(2021) },
+    ReporterAuthority { abbreviation: "F.4th", name: "Federal Reporter, Fourth Series", courts: CourtSystem::FederalAppellate, first_year: 2021, last_year: None },
+    ReporterAuthority { abbreviation: "F. Supp.", name: "Federal Supplement", courts: CourtSystem::FederalDistrict, first_year: 1932, last_year: Some(1998) },
+    ReporterAuthority { abbreviation: "F. Supp. 2d", name: "Federal Supplement, Second Series", courts: CourtSystem::FederalDistrict, first_year: 1998, last_year: Some(2014) },
+    ReporterAuthority { abbreviation: "F. Supp. 3d", name: "Federal Supplement, Third Series", courts: CourtSystem::FederalDistrict, first_year: 2014, last_year: None },
+    ReporterAuthority { abbreviation: "A.", name: "Atlantic Reporter", courts: CourtSystem::State, first_year: 1885, last_year: Some(1938) },
+    ReporterAuthority { abbreviation: "A.2d", name: "Atlantic Reporter, Second Series", courts: CourtSystem::State, first_year: 1938, last_year: Some(2010) },
+    ReporterAuthority { abbreviation: "A.3d", name: "Atlantic Reporter, Third Series", courts: CourtSystem::State, first_year: 2010, last_year: None },
+    ReporterAuthority { abbreviation: "P.", name: "Pacific Reporter", courts: CourtSystem::State, first_year: 1883, last_year: Some(1931) },
+    ReporterAuthority { abbreviation: "P.2d", name: "Pacific Reporter, Second Series", courts: CourtSystem::State, first_year: 1931, last_year: Some(2000) },
+    ReporterAuthority { abbreviation: "P.3d", name: "Pacific Reporter, Third Series", courts: CourtSystem::State, first_year: 2000, last_year: None },
+    ReporterAuthority { abbreviation: "N.E.", name: "North Eastern Reporter", courts: CourtSystem::State, first_year: 1885, last_year: Some(1936) },
+    ReporterAuthority { abbreviation: "N.E.2d", name: "North Eastern Reporter, Second Series", courts: CourtSystem::State, first_year: 1936, last_year: Some(2014) },
+    ReporterAuthority { abbreviation: "N.E.3d", name: "North Eastern Reporter, Third Series", courts: CourtSystem::State, first_year: 2014, last_year: None },
+    ReporterAuthority { abbreviation: "N.W.", name: "North Western Reporter", courts: CourtSystem::State, first_year: 1879, last_year: Some(1941) },
+    ReporterAuthority { abbreviation: "N.W.2d", name: "North Western Reporter, Second Series", courts: CourtSystem::State, first_year: 1941, last_year: None },
+    ReporterAuthority { abbreviation: "S.E.", name: "South Eastern Reporter", courts: CourtSystem::State, first_year: 1887, last_year: Some(1939) },
+    ReporterAuthority { abbreviation: "S.E.2d", name: "South Eastern Reporter, Second Series", courts: CourtSystem::State, first_year: 1939, last_year: None },
+    ReporterAuthority { abbreviation: "S.W.", name: "South Western Reporter", courts: CourtSystem::State, first_year: 1886, last_year: Some(1928) },
+    ReporterAuthority { abbreviation: "S.W.2d", name: "South Western Reporter, Second Series", courts: CourtSystem::State, first_year: 1928, last_year: Some(1999) },
+    ReporterAuthority { abbreviation: "S.W.3d", name: "South Western Reporter, Third Series", courts: CourtSystem::State, first_year: 1999, last_year: None },
+    ReporterAuthority { abbreviation: "So.", name: "Southern Reporter", courts: CourtSystem::State, first_year: 1886, last_year: Some(1941) },
+    ReporterAuthority { abbreviation: "So. 2d", name: "Southern Reporter, Second Series", courts: CourtSystem::State, first_year: 1941, last_year: Some(2008) },
+    ReporterAuthority { abbreviation: "So. 3d", name: "Southern Reporter, Third Series", courts: CourtSystem::State, first_year: 2008, last_year: None },
+    ReporterAuthority { abbreviation: "Cal. Rptr.", name: "California Reporter", courts: CourtSystem::State, first_year: 1959, last_year: Some(1991) },
+    ReporterAuthority { abbreviation: "Cal. Rptr. 2d", name: "California Reporter, Second Series", courts: CourtSystem::State, first_year: 1991, last_year: Some(2003) },
+    ReporterAuthority { abbreviation: "Cal. Rptr. 3d", name: "California Reporter, Third Series", courts: CourtSystem::State, first_year: 2003, last_year: None },
+    ReporterAuthority { abbreviation: "N.Y.S.", name: "New York Supplement", courts: CourtSystem::State, first_year: 1888, last_year: Some(1938) },
+    ReporterAuthority { abbreviation: "N.Y.S.2d", name: "New York Supplement, Second Series", courts: CourtSystem::State, first_year: 1938, last_year: Some(2015) },
+    ReporterAuthority { abbreviation: "N.Y.S.3d", name: "New York Supplement, Third Series", courts: CourtSystem::State, first_year: 2015, last_year: None },
+    ReporterAuthority { abbreviation: "Pa.", name: "Pennsylvania State Reports", courts: CourtSystem::State, first_year: 1845, last_year: None },
+    ReporterAuthority { abbreviation: "Pa. Super.", name: "Pennsylvania Superior Court Reports", courts: CourtSystem::State, first_year: 1895, last_year: Some(1997) },
+    ReporterAuthority { abbreviation: "Pa. Cmwlth.", name: "Pennsylvania Commonwealth Court Reports", courts: CourtSystem::State, first_year: 1970, last_year: Some(1995) },
+    ReporterAuthority { abbreviation: "Pa. D. & C.", name: "Pennsylvania District and County Reports", courts: CourtSystem::State, first_year: 1921, last_year: None },
+];
+
+/// Outcome of checking one citation against the reporter table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CitationValidation {
+    pub is_valid: bool,
+    pub errors: Vec<String>,
+    /// Corrected citations or other fixes to offer the user
+    pub suggestions: Vec<String>,
+}
+
+impl CitationValidation {
+    /// Record the outcome on the citation itself
+    pub fn apply_to(self, citation: &mut Citation) {
+        citation.is_valid = self.is_valid;
+        citation.errors = self.errors;
+        citation.suggestions = self.suggestions;
+    }
+}
+
+/// Check that a case citation's reporter is a real one in its Bluebook form,
+/// that the court in its parenthetical is one the reporter publishes, and
+/// that its year falls while the reporter series was published. Statutes and
+/// other citations are only checked for a known code abbreviation.
+pub fn validate_citation(citation: &Citation) -> CitationValidation {
+    let mut validation = CitationValidation::default();
+    let Some(reporter) = citation.reporter.as_deref() else {
+        if citation.citation_type == CitationType::Case {
+            validation.errors.push("Case citation has no reporter".to_string());
+        }
+        validation.is_valid = validation.errors.is_empty();
+        return validation;
+    };
+
+    let bluebook = normalize_reporter(reporter);
+    if !is_known_reporter(reporter) {
+        validation.errors.push(format!("Unknown reporter abbreviation \"{}\"", reporter));
+    } else if bluebook != reporter {
+        validation
+            .errors
+            .push(format!("Reporter \"{}\" should be written \"{}\"", reporter, bluebook));
+        validation
+            .suggestions
+            .push(citation.full_citation.replacen(reporter, &bluebook, 1));
+    }
+
+    if citation.citation_type == CitationType::Case {
+        if let Some(volume) = citation.volume.as_deref() {
+            if !matches!(volume.parse::<u32>(), Ok(v) if v > 0) {
+                validation.errors.push(format!("Volume \"{}\" is not a positive number", volume));
+            }
+        }
+        if let Some(authority) = REPORTERS.iter().find(|r| r.abbreviation == bluebook) {
+            check_court(authority, citation.court.as_deref(), &mut validation);
+            check_year(authority, citation.year.as_deref(), &mut validation);
+        }
+    }
+
+    validation.is_valid = validation.errors.is_empty();
+    validation
+}
+
+fn check_court(authority: &ReporterAuthority, court: Option<&str>, validation: &mut CitationValidation) {
+    let Some(court) = court.map(str::trim).filter(|c| !c.is_empty()) else {
+        return;
+    };
+    static CIRCUIT_RE: OnceLock<Regex> = OnceLock::new();
+    static DISTRICT_RE: OnceLock<Regex> = OnceLock::new();
+
+    let circuit = CIRCUIT_RE.get_or_init(|| {
+        Regex::new(r"^(?:1st|2d|3d|4th|5th|6th|7th|8th|9th|10th|11th|D\.C\.|Fed\.) Cir\.$")
+            .expect("valid circuit pattern")
+    });
+    let district = DISTRICT_RE.get_or_init(|| Regex::new(r"^(?:[CEMNSW]\.)?D\.").expect("valid district pattern"));
+
+    if court.contains("Cir.") && !circuit.is_match(court) {
+        validation.errors.push(format!("Unknown federal circuit \"{}\"", court));
+        return;
+    }
+    let system = if circuit.is_match(court) {
+        CourtSystem::FederalAppellate
+    } else if district.is_match(court) {
+        CourtSystem::FederalDistrict
+    } else if court == "U.S." {
+        CourtSystem::UsSupreme
+    } else {
+        CourtSystem::State
+    };
+    if system != authority.courts {
+        validation.errors.push(format!(
+            "{} ({}) does not report decisions of {}",
+            authority.name, authority.abbreviation, court
+        ));
+    }
+}
+
+fn check_year(authority: &ReporterAuthority, year: Option<&str>, validation: &mut CitationValidation) {
+    let Some(year) = year else {
+        return;
+    };
+    let Ok(year) = year.trim().parse::<i32>() else {
+        validation.errors.push(format!("Year \"{}\" is not a year", year));
+        return;
+    };
+
+    if year > chrono::Utc::now().year() {
+        validation.errors.push(format!("Year {} is in the future", year));
+    } else if year < authority.first_year || authority.last_year.is_some_and(|last| year > last) {
+        let published = match authority.last_year {
+            Some(last) => format!("{}-{}", authority.first_year, last),
+            None => format!("since {}", authority.first_year),
+        };
+        validation.errors.push(format!(
+            "{} was published {}, so a {} decision cannot be in it",
+            authority.abbreviation, published, year
+        ));
+    }
+}
+
 #[derive(Debug)]
 pub struct CitationValidationResult {
     pub citation: Citation,
@@ -192,4 +586,55 @@ mod tests {
         assert_eq!(citation.citation_type, CitationType::Filing);
         assert_eq!(citation.document_type, Some("Motion".to_string()));
     }
+
+    fn case_citation(text: &str) -> Citation {
+        extract_citations(text).remove(0)
+    }
+
+    #[test]
+    fn test_valid_citation_passes() {
+        let validation = validate_citation(&case_citation("Smith v. Jones, 123 F.3d 456 (3d Cir. 2000)"));
+        assert_eq!(validation, CitationValidation { is_valid: true, ..Default::default() });
+    }
+
+    #[test]
+    fn test_malformed_reporter_suggests_bluebook_form() {
+        let validation = validate_citation(&case_citation("Commonwealth v. Doe, 250 A3d 1020 (Pa. Super. 2021)"));
+        assert!(!validation.is_valid);
+        assert_eq!(validation.errors, vec!["Reporter \"A3d\" should be written \"A.3d\"".to_string()]);
+        assert_eq!(validation.suggestions, vec!["250 A.3d 1020 (Pa. Super. 2021)".to_string()]);
+
+        let unknown = validate_citation(&case_citation("250 Atl.9th 1020 (Pa. 2021)"));
+        assert!(unknown.errors[0].starts_with("Unknown reporter abbreviation"));
+    }
+
+    #[test]
+    fn test_regional_reporters_are_known() {
+        for text in [
+            "Doe v. Roe, 123 P.3d 456 (Cal. 2005)",
+            "Doe v. Roe, 45 N.E.2d 678 (Ill. 1942)",
+            "Doe v. Roe, 300 S.W.3d 12 (Tex. 2009)",
+            "Doe v. Roe, 980 So. 2d 1 (Fla. 2008)",
+            "Doe v. Roe, 150 Cal. Rptr. 3d 200 (Ct. App. 2012)",
+        ] {
+            let validation = validate_citation(&case_citation(text));
+            assert!(validation.is_valid, "{}: {:?}", text, validation.errors);
+        }
+
+        let validation = validate_citation(&case_citation("Doe v. Roe, 300 SW3d 12 (Tex. 2009)"));
+        assert_eq!(validation.errors, vec!["Reporter \"SW3d\" should be written \"S.W.3d\"".to_string()]);
+    }
+
+    #[test]
+    fn test_implausible_year_and_court_are_errors() {
+        let validation = validate_citation(&case_citation("123 F.3d 456 (3d Cir. 1950)"));
+        assert_eq!(
+            validation.errors,
+            vec!["F.3d was published 1993-2021, so a 1950 decision cannot be in it".to_string()]
+        );
+
+        let validation = validate_citation(&case_citation("250 A.3d 1020 (3d Cir. 2015)"));
+        assert!(!validation.is_valid);
+        assert!(validation.errors[0].contains("does not report decisions of 3d Cir."));
+    }
 }
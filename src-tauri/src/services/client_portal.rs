@@ -3,10 +3,12 @@
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use chrono::{DateTime, Utc, Duration};
 use std::collections::HashMap;
 use tracing::{info, warn, error};
+use crate::utils::validation::sanitize_html;
+use crate::services::permissions::UserSession;
 use argon2::{
     password_hash::{
         rand_core::OsRng,
@@ -179,6 +181,58 @@ pub struct DeadlineSummary {
     pub priority: String,
 }
 
+/// Who is posting to or reading a matter's portal thread
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum PortalParticipant {
+    Client { client_id: String, name: String },
+    Staff { user_id: String, name: String },
+}
+
+impl PortalParticipant {
+    /// The signed-in firm user, as they appear in portal threads
+    pub fn staff(session: &UserSession) -> Self {
+        PortalParticipant::Staff { user_id: session.user_id.clone(), name: session.user_id.clone() }
+    }
+
+    fn role(&self) -> &'static str {
+        match self {
+            PortalParticipant::Client { .. } => "client",
+            PortalParticipant::Staff { .. } => "staff",
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            PortalParticipant::Client { client_id, .. } => client_id,
+            PortalParticipant::Staff { user_id, .. } => user_id,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            PortalParticipant::Client { name, .. } | PortalParticipant::Staff { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadReceipt {
+    pub reader_id: String,
+    pub read_at: DateTime<Utc>,
+}
+
+/// A message in a matter's client portal thread; `body` is sanitized HTML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalMessage {
+    pub id: String,
+    pub matter_id: String,
+    pub author: PortalParticipant,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub read_receipts: Vec<ReadReceipt>,
+}
+
 pub struct ClientPortalService {
     db: SqlitePool,
 }
@@ -696,4 +750,207 @@ impl ClientPortalService {
 
         Ok(())
     }
+
+    /// Post a message to a matter's portal thread. The body is sanitized
+    /// before it is stored so the portal never renders client-supplied markup.
+    pub async fn post_client_message(
+        &self,
+        matter_id: &str,
+        author: &PortalParticipant,
+        body: &str,
+    ) -> Result<PortalMessage> {
+        self.authorize_thread_access(matter_id, author).await?;
+
+        let body = sanitize_html(body);
+        if body.trim().is_empty() {
+            return Err(anyhow!("Message body cannot be empty"));
+        }
+
+        let message = PortalMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            matter_id: matter_id.to_string(),
+            author: author.clone(),
+            body,
+            created_at: Utc::now(),
+            read_receipts: Vec::new(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO portal_matter_messages (
+                id, matter_id, author_role, author_id, author_name, body, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&message.id)
+        .bind(&message.matter_id)
+        .bind(author.role())
+        .bind(author.id())
+        .bind(author.name())
+        .bind(&message.body)
+        .bind(message.created_at)
+        .execute(&self.db)
+        .await?;
+
+        info!("Portal message posted on matter {} by {} {}", matter_id, author.role(), author.id());
+        Ok(message)
+    }
+
+    /// Messages in a matter's thread, oldest first. Messages from other
+    /// participants are marked read by `viewer`.
+    pub async fn get_messages(
+        &self,
+        matter_id: &str,
+        viewer: &PortalParticipant,
+    ) -> Result<Vec<PortalMessage>> {
+        self.authorize_thread_access(matter_id, viewer).await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO portal_message_reads (message_id, reader_id, read_at)
+            SELECT id, ?, ? FROM portal_matter_messages
+            WHERE matter_id = ? AND author_id != ?
+            "#,
+        )
+        .bind(viewer.id())
+        .bind(Utc::now())
+        .bind(matter_id)
+        .bind(viewer.id())
+        .execute(&self.db)
+        .await?;
+
+        let rows = sqlx::query(
+            "SELECT * FROM portal_matter_messages WHERE matter_id = ? ORDER BY created_at",
+        )
+        .bind(matter_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let author_id: String = row.try_get("author_id")?;
+            let author_name: String = row.try_get("author_name")?;
+            let author = match row.try_get::<String, _>("author_role")?.as_str() {
+                "client" => PortalParticipant::Client { client_id: author_id, name: author_name },
+                _ => PortalParticipant::Staff { user_id: author_id, name: author_name },
+            };
+
+            let read_receipts = sqlx::query(
+                "SELECT reader_id, read_at FROM portal_message_reads WHERE message_id = ? ORDER BY read_at",
+            )
+            .bind(&id)
+            .fetch_all(&self.db)
+            .await?
+            .iter()
+            .map(|r| Ok(ReadReceipt { reader_id: r.try_get("reader_id")?, read_at: r.try_get("read_at")? }))
+            .collect::<Result<Vec<_>>>()?;
+
+            messages.push(PortalMessage {
+                id,
+                matter_id: row.try_get("matter_id")?,
+                author,
+                body: row.try_get("body")?,
+                created_at: row.try_get("created_at")?,
+                read_receipts,
+            });
+        }
+
+        Ok(messages)
+    }
+
+    /// Clients may only use the thread of a matter they are the client on;
+    /// firm staff may use any matter's thread.
+    async fn authorize_thread_access(&self, matter_id: &str, participant: &PortalParticipant) -> Result<()> {
+        let row = sqlx::query("SELECT client_id FROM matters WHERE id = ?")
+            .bind(matter_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| anyhow!("Matter not found: {}", matter_id))?;
+
+        if let PortalParticipant::Client { client_id, .. } = participant {
+            let owner: String = row.try_get("client_id")?;
+            if &owner != client_id {
+                warn!("Client {} denied access to matter {} thread", client_id, matter_id);
+                return Err(anyhow!("Not authorized to access this matter"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn portal_db() -> SqlitePool {
+        let db = test_database().await;
+
+        for (matter_id, client_id, number) in [("m1", "c1", "2024-001"), ("m2", "c2", "2024-002")] {
+            sqlx::query(
+                "INSERT INTO clients (id, first_name, last_name, created_at, updated_at)
+                 VALUES (?, 'Jane', 'Doe', ?, ?)",
+            )
+            .bind(client_id)
+            .bind(Utc::now().to_rfc3339())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&db)
+            .await
+            .unwrap();
+
+            sqlx::query(
+                "INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+                 VALUES (?, ?, ?, 'Matter', 'civil', ?, ?)",
+            )
+            .bind(matter_id)
+            .bind(client_id)
+            .bind(number)
+            .bind(Utc::now().to_rfc3339())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+        db
+    }
+
+    fn client(id: &str) -> PortalParticipant {
+        PortalParticipant::Client { client_id: id.to_string(), name: "Jane Doe".to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_client_cannot_access_another_matters_thread() {
+        let service = ClientPortalService::new(portal_db().await);
+        let staff = PortalParticipant::Staff { user_id: "u1".to_string(), name: "Attorney".to_string() };
+
+        service.post_client_message("m2", &staff, "Privileged update").await.unwrap();
+
+        assert!(service.get_messages("m2", &client("c1")).await.is_err());
+        assert!(service.post_client_message("m2", &client("c1"), "Hello").await.is_err());
+
+        let own = service.get_messages("m2", &client("c2")).await.unwrap();
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].read_receipts.len(), 1);
+        assert_eq!(own[0].read_receipts[0].reader_id, "c2");
+    }
+
+    #[tokio::test]
+    async fn test_message_html_is_sanitized() {
+        let service = ClientPortalService::new(portal_db().await);
+
+        let message = service
+            .post_client_message(
+                "m1",
+                &client("c1"),
+                "<p onclick=\"steal()\">Hi <b>team</b></p><script>alert(1)</script><img src=x onerror=alert(1)>",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(message.body, "<p>Hi <b>team</b></p>");
+        let stored = service.get_messages("m1", &client("c1")).await.unwrap();
+        assert_eq!(stored[0].body, "<p>Hi <b>team</b></p>");
+    }
 }
@@ -0,0 +1,289 @@
+// Client Collaboration Portal - scoped, expiring document share links
+// Tokens are stored hashed and only grant read access to one document in one matter
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Returned once when a document is shared; the raw token is not stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: String,
+    pub matter_id: String,
+    pub client_id: String,
+    pub document_path: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareAccess {
+    pub id: String,
+    pub share_id: Option<String>,
+    pub matter_id: String,
+    pub client_id: String,
+    pub granted: bool,
+    pub reason: Option<String>,
+    pub accessed_at: DateTime<Utc>,
+}
+
+pub struct CollaborationService {
+    db: SqlitePool,
+}
+
+impl CollaborationService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Mint a token granting `client_id` read access to `doc` within `matter_id`
+    /// until `expires`.
+    pub async fn share_document(
+        &self,
+        matter_id: &str,
+        doc: &Path,
+        client_id: &str,
+        expires: DateTime<Utc>,
+    ) -> Result<ShareLink> {
+        if expires <= Utc::now() {
+            anyhow::bail!("Share expiry must be in the future");
+        }
+        if !doc.is_file() {
+            anyhow::bail!("Document not found: {:?}", doc);
+        }
+
+        let document_path = doc
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve document path {:?}", doc))?
+            .to_string_lossy()
+            .to_string();
+
+        let link = ShareLink {
+            id: Uuid::new_v4().to_string(),
+            matter_id: matter_id.to_string(),
+            client_id: client_id.to_string(),
+            document_path,
+            token: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+            expires_at: expires,
+            created_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO document_shares
+            (id, matter_id, client_id, document_path, token_hash, expires_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&link.id)
+        .bind(&link.matter_id)
+        .bind(&link.client_id)
+        .bind(&link.document_path)
+        .bind(hash_token(&link.token))
+        .bind(link.expires_at)
+        .bind(link.created_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save document share")?;
+
+        info!("Shared {} with client {} on matter {}", link.document_path, client_id, matter_id);
+        Ok(link)
+    }
+
+    /// Resolve a token presented by `client_id` for `matter_id` to the shared
+    /// document path. Every attempt, granted or denied, is logged.
+    pub async fn access_document(&self, token: &str, matter_id: &str, client_id: &str) -> Result<PathBuf> {
+        self.access_document_at(token, matter_id, client_id, Utc::now()).await
+    }
+
+    /// Revoke a share; returns false if it was already revoked or unknown
+    pub async fn revoke_share(&self, share_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE document_shares SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL",
+        )
+        .bind(Utc::now())
+        .bind(share_id)
+        .execute(&self.db)
+        .await
+        .context("Failed to revoke document share")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Access attempts against a share, newest first
+    pub async fn access_log(&self, share_id: &str) -> Result<Vec<ShareAccess>> {
+        let rows = sqlx::query(
+            "SELECT * FROM document_share_access WHERE share_id = ? ORDER BY accessed_at DESC",
+        )
+        .bind(share_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load share access log")?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ShareAccess {
+                    id: row.try_get("id")?,
+                    share_id: row.try_get("share_id")?,
+                    matter_id: row.try_get("matter_id")?,
+                    client_id: row.try_get("client_id")?,
+                    granted: row.try_get("granted")?,
+                    reason: row.try_get("reason")?,
+                    accessed_at: row.try_get("accessed_at")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn access_document_at(
+        &self,
+        token: &str,
+        matter_id: &str,
+        client_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<PathBuf> {
+        let row = sqlx::query(
+            "SELECT id, matter_id, client_id, document_path, expires_at, revoked_at
+             FROM document_shares WHERE token_hash = ?",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to look up share token")?;
+
+        let Some(row) = row else {
+            return self.deny(None, matter_id, client_id, now, "Invalid share token").await;
+        };
+
+        let share_id: String = row.try_get("id")?;
+        let share_matter: String = row.try_get("matter_id")?;
+        let share_client: String = row.try_get("client_id")?;
+        let expires_at: DateTime<Utc> = row.try_get("expires_at")?;
+        let revoked_at: Option<DateTime<Utc>> = row.try_get("revoked_at")?;
+
+        let denial = if share_matter != matter_id {
+            Some("Share token is not valid for this matter")
+        } else if share_client != client_id {
+            Some("Share token was not issued to this client")
+        } else if revoked_at.is_some() {
+            Some("Share has been revoked")
+        } else if expires_at <= now {
+            Some("Share has expired")
+        } else {
+            None
+        };
+
+        if let Some(reason) = denial {
+            return self.deny(Some(&share_id), matter_id, client_id, now, reason).await;
+        }
+
+        self.log_access(Some(&share_id), matter_id, client_id, now, true, None).await?;
+        Ok(PathBuf::from(row.try_get::<String, _>("document_path")?))
+    }
+
+    async fn deny(
+        &self,
+        share_id: Option<&str>,
+        matter_id: &str,
+        client_id: &str,
+        now: DateTime<Utc>,
+        reason: &str,
+    ) -> Result<PathBuf> {
+        warn!("Denied document access for client {} on matter {}: {}", client_id, matter_id, reason);
+        self.log_access(share_id, matter_id, client_id, now, false, Some(reason)).await?;
+        anyhow::bail!("{}", reason)
+    }
+
+    async fn log_access(
+        &self,
+        share_id: Option<&str>,
+        matter_id: &str,
+        client_id: &str,
+        accessed_at: DateTime<Utc>,
+        granted: bool,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_share_access
+            (id, share_id, matter_id, client_id, granted, reason, accessed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(share_id)
+        .bind(matter_id)
+        .bind(client_id)
+        .bind(granted)
+        .bind(reason)
+        .bind(accessed_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to log share access")?;
+        Ok(())
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use chrono::Duration;
+
+    async fn shares_db() -> SqlitePool {
+        let db = test_database().await;
+        db
+    }
+
+    async fn shared(service: &CollaborationService, dir: &Path) -> ShareLink {
+        let doc = dir.join("settlement.pdf");
+        std::fs::write(&doc, b"%PDF-1.4").unwrap();
+        service
+            .share_document("matter-1", &doc, "client-1", Utc::now() + Duration::days(1))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_is_denied() {
+        let service = CollaborationService::new(shares_db().await);
+        let dir = tempfile::tempdir().unwrap();
+        let link = shared(&service, dir.path()).await;
+
+        assert!(service.access_document(&link.token, "matter-1", "client-1").await.is_ok());
+
+        let later = Utc::now() + Duration::days(2);
+        let err = service
+            .access_document_at(&link.token, "matter-1", "client-1", later)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        let log = service.access_log(&link.id).await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert!(!log[0].granted);
+    }
+
+    #[tokio::test]
+    async fn test_cross_matter_token_is_denied() {
+        let service = CollaborationService::new(shares_db().await);
+        let dir = tempfile::tempdir().unwrap();
+        let link = shared(&service, dir.path()).await;
+
+        let err = service
+            .access_document(&link.token, "matter-2", "client-1")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not valid for this matter"));
+    }
+}
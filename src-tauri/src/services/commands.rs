@@ -1,10 +1,29 @@
 // Tauri command handlers for PA eDocket Desktop
 // Production-ready command implementations with proper error handling
 
+use crate::commands::document_commands::AppState;
+use crate::config::AppConfig;
 use crate::domain::*;
+use crate::providers::registry::ProviderRegistry;
+use crate::services::citations::{extract_citations, validate_citation};
+use crate::services::court_rules::resolve_court_rules;
+use crate::services::draft_jobs::{self, DraftJobRunner};
+use crate::services::efiling_receipts::EFilingReceiptService;
+use crate::services::efiling_queue::{EFilingQueueService, FilingPreview, QueuedFiling};
+use crate::services::export::ExportService;
+use crate::services::logs::{get_logs, LogFilter, LogLine};
+use crate::services::permissions::{CurrentUser, Permission, Role, UserSession};
+use crate::services::redaction::RedactionPolicy;
+use crate::services::system_info::{system_info, SystemInfo};
+use crate::services::system_health::{system_health, HealthReport, HealthStatus};
+use crate::services::users::{UserAccount, UserDirectory};
+use crate::services::watchlist::WatchlistService;
+use crate::utils::correlation::{new_correlation_id, with_correlation_header};
+use crate::utils::file_utils::expand_home;
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::State;
 use tracing::{info, warn, error, instrument};
 use uuid::Uuid;
@@ -17,23 +36,19 @@ fn get_api_base() -> String {
     std::env::var("VITE_API_BASE").unwrap_or_else(|_| "http://localhost:3000".to_string())
 }
 
-async fn make_api_request(endpoint: &str) -> Result<reqwest::Response, String> {
-    let api_base = get_api_base();
+async fn make_api_request(api_base: &str, endpoint: &str) -> Result<reqwest::Response, String> {
     let client = reqwest::Client::new();
 
-    client
-        .get(&format!("{}{}", api_base, endpoint))
+    with_correlation_header(client.get(&format!("{}{}", api_base, endpoint)))
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))
 }
 
-async fn make_api_post(endpoint: &str, body: &serde_json::Value) -> Result<reqwest::Response, String> {
-    let api_base = get_api_base();
+async fn make_api_post(api_base: &str, endpoint: &str, body: &serde_json::Value) -> Result<reqwest::Response, String> {
     let client = reqwest::Client::new();
 
-    client
-        .post(&format!("{}{}", api_base, endpoint))
+    with_correlation_header(client.post(&format!("{}{}", api_base, endpoint)))
         .json(body)
         .send()
         .await
@@ -68,7 +83,7 @@ pub struct ApiPaginationInfo {
 // Search and Docket Commands
 
 #[tauri::command]
-#[instrument(skip(params), fields(term = %params.term.as_deref().unwrap_or("")))]
+#[instrument(skip(params), err, fields(correlation_id = %new_correlation_id(), term = %params.term.as_deref().unwrap_or("")))]
 pub async fn cmd_search(params: SearchParams) -> Result<ApiSearchResponse, String> {
     info!("Executing search command");
 
@@ -78,6 +93,12 @@ pub async fn cmd_search(params: SearchParams) -> Result<ApiSearchResponse, Strin
         return Err(format!("Invalid search parameters: {}", e));
     }
 
+    // Reject malformed identifiers before spending a provider call
+    if let Err(e) = crate::utils::validation::validate_search_identifiers(&params) {
+        warn!("Invalid search identifier: {}", e);
+        return Err(e);
+    }
+
     // Build query parameters
     let mut query_params = Vec::new();
 
@@ -110,8 +131,7 @@ pub async fn cmd_search(params: SearchParams) -> Result<ApiSearchResponse, Strin
     let api_base = get_api_base();
     let client = reqwest::Client::new();
 
-    let response = client
-        .get(&format!("{}/api/search", api_base))
+    let response = with_correlation_header(client.get(&format!("{}/api/search", api_base)))
         .query(&query_params)
         .send()
         .await
@@ -133,21 +153,19 @@ pub async fn cmd_search(params: SearchParams) -> Result<ApiSearchResponse, Strin
 #[tauri::command]
 #[instrument(skip(docket_number))]
 pub async fn cmd_get_docket(docket_number: String) -> Result<serde_json::Value, String> {
+    fetch_docket(&get_api_base(), docket_number).await
+}
+
+/// Fetch a docket from the API at `api_base`
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+async fn fetch_docket(api_base: &str, docket_number: String) -> Result<serde_json::Value, String> {
     info!("Fetching docket: {}", docket_number);
 
     if docket_number.is_empty() {
         return Err("Docket number cannot be empty".to_string());
     }
 
-    // Make API request
-    let api_base = get_api_base();
-    let client = reqwest::Client::new();
-
-    let response = client
-        .get(&format!("{}/api/dockets/{}", api_base, docket_number))
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let response = make_api_request(api_base, &format!("/api/dockets/{}", docket_number)).await?;
 
     if !response.status().is_success() {
         return Err(format!("API error: {}", response.status()));
@@ -163,7 +181,7 @@ pub async fn cmd_get_docket(docket_number: String) -> Result<serde_json::Value,
 }
 
 #[tauri::command]
-#[instrument(skip(id))]
+#[instrument(skip(id), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_attachments(id: String) -> Result<Vec<Attachment>, String> {
     info!("Fetching attachments for docket: {}", id);
     
@@ -177,15 +195,21 @@ pub async fn cmd_get_attachments(id: String) -> Result<Vec<Attachment>, String>
 
 // Export Commands
 
+/// Export a docket (JSON or PDF) or search results (JSON or CSV) under the
+/// data directory, masked by `redaction` when given. Returns the file's path.
 #[tauri::command]
-#[instrument(skip(export_type, payload))]
+#[instrument(skip(config, current_user, payload, redaction), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_export(
+    config: State<'_, AppConfig>,
+    current_user: State<'_, CurrentUser>,
     export_type: String,
     payload: Value,
+    redaction: Option<RedactionPolicy>,
 ) -> Result<String, String> {
+    current_user.require(Permission::ViewRecords).map_err(|e| e.to_string())?;
     info!("Executing export command: {}", export_type);
     
-    let export_type = match export_type.as_str() {
+    let export_type = match export_type.to_uppercase().as_str() {
         "JSON" => ExportType::Json,
         "CSV" => ExportType::Csv,
         "PDF" => ExportType::Pdf,
@@ -193,15 +217,43 @@ pub async fn cmd_export(
         _ => return Err("Invalid export type".to_string()),
     };
     
-    // TODO: Implement actual export functionality
-    Err("Export not implemented yet".to_string())
+    let service = ExportService::new(expand_home(&config.global.data_dir).join("exports"));
+    service.initialize().await.map_err(|e| e.to_string())?;
+    let name = format!("export_{}_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"), &Uuid::new_v4().simple().to_string()[..8]);
+    let redaction = redaction.as_ref();
+    
+    let manifest = match export_type {
+        ExportType::Json => service.export_json(&payload, &format!("{}.json", name), redaction).await,
+        ExportType::Csv => {
+            let results: Vec<SearchResult> = serde_json::from_value(payload)
+                .map_err(|e| format!("CSV exports take search results: {}", e))?;
+            service.export_csv(&results, &format!("{}.csv", name), redaction).await
+        }
+        ExportType::Pdf => {
+            let docket: Docket = serde_json::from_value(payload)
+                .map_err(|e| format!("PDF exports take a docket: {}", e))?;
+            service.export_pdf(&docket, &format!("{}.pdf", name), redaction).await
+        }
+        ExportType::Zip => return Err("ZIP exports are not available from this command".to_string()),
+    }
+    .map_err(|e| e.to_string())?;
+    
+    manifest
+        .files
+        .first()
+        .map(|file| file.path.clone())
+        .ok_or_else(|| "Export wrote no files".to_string())
 }
 
 // Document Drafting Commands
 
 #[tauri::command]
-#[instrument(skip(job))]
-pub async fn cmd_draft(job: DraftJob) -> Result<HashMap<String, String>, String> {
+#[instrument(skip(state, config, job), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_draft(
+    state: State<'_, AppState>,
+    config: State<'_, AppConfig>,
+    job: DraftJob,
+) -> Result<HashMap<String, String>, String> {
     info!("Executing draft command for template: {}", job.template_id);
     
     // Validate job
@@ -210,16 +262,42 @@ pub async fn cmd_draft(job: DraftJob) -> Result<HashMap<String, String>, String>
         return Err(format!("Invalid draft job: {}", e));
     }
     
-    // TODO: Implement document drafting
+    let data_dir = expand_home(&config.global.data_dir);
+    let runner = DraftJobRunner::open(state.db_pool.clone(), &data_dir, &state.config_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let job = runner.enqueue(job).await.map_err(|e| e.to_string())?;
+    
+    // Render in the background; progress is polled with cmd_get_draft_status
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = runner.run_pending().await {
+            error!("Draft job runner failed: {}", e);
+        }
+    });
+    
     let mut result = HashMap::new();
-    result.insert("manifestPath".to_string(), "/tmp/manifest.json".to_string());
+    if let Some(id) = job.id {
+        result.insert("jobId".to_string(), id.to_string());
+    }
+    result.insert("status".to_string(), "pending".to_string());
     Ok(result)
 }
 
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_draft_status(state: State<'_, AppState>, job_id: String) -> Result<DraftJob, String> {
+    let job_id = Uuid::parse_str(&job_id).map_err(|e| format!("Invalid job ID: {}", e))?;
+    
+    draft_jobs::find_job(&state.db_pool, job_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No draft job with ID {}", job_id))
+}
+
 // E-filing Commands
 
 #[tauri::command]
-#[instrument(skip(court_id))]
+#[instrument(skip(court_id), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_efiling_capabilities(court_id: String) -> Result<Vec<EFilingCapability>, String> {
     info!("Fetching e-filing capabilities for court: {}", court_id);
     
@@ -232,7 +310,7 @@ pub async fn cmd_efiling_capabilities(court_id: String) -> Result<Vec<EFilingCap
 }
 
 #[tauri::command]
-#[instrument(skip(court_id, provider, credentials))]
+#[instrument(skip(court_id, provider, credentials), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_efiling_login(
     court_id: String,
     provider: String,
@@ -249,77 +327,227 @@ pub async fn cmd_efiling_login(
 }
 
 #[tauri::command]
-#[instrument(skip(session_id, docket_id, document_type, files, metadata))]
+#[instrument(skip(queue, registry, current_user, session_id, docket_id, document_type, files, metadata), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_efiling_submit(
+    queue: State<'_, Arc<EFilingQueueService>>,
+    registry: State<'_, Arc<ProviderRegistry>>,
+    current_user: State<'_, CurrentUser>,
+    court_id: String,
     session_id: String,
     docket_id: Option<String>,
     document_type: String,
     files: Vec<String>,
     metadata: HashMap<String, Value>,
 ) -> Result<EFilingSubmission, String> {
+    current_user.require(Permission::SubmitEFiling).map_err(|e| e.to_string())?;
     info!("E-filing submission for session: {}", session_id);
     
     if session_id.is_empty() || document_type.is_empty() || files.is_empty() {
         return Err("Session ID, document type, and files are required".to_string());
     }
+    let session_id = Uuid::parse_str(&session_id).map_err(|e| format!("Invalid session ID: {}", e))?;
+    
+    let (Some(provider_name), Some(provider)) = (
+        registry.efiling_provider_name(&court_id),
+        registry.efiling_provider_for(&court_id),
+    ) else {
+        return Err(format!("{} does not accept e-filing", court_id));
+    };
+    
+    let submission = new_submission(session_id, &court_id, docket_id, document_type, files, metadata);
     
-    // TODO: Implement e-filing submission
-    Err("E-filing submission not implemented yet".to_string())
+    // A failed portal call is queued and retried by the background loop
+    queue
+        .submit(provider_name, provider.as_ref(), submission)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check a filing against the court's e-filing requirements and price it,
+/// without submitting anything
+#[tauri::command]
+#[instrument(skip(queue, registry, session_id, docket_id, document_type, files, metadata), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_efiling_preview(
+    queue: State<'_, Arc<EFilingQueueService>>,
+    registry: State<'_, Arc<ProviderRegistry>>,
+    court_id: String,
+    session_id: Option<String>,
+    docket_id: Option<String>,
+    document_type: String,
+    files: Vec<String>,
+    metadata: HashMap<String, Value>,
+) -> Result<FilingPreview, String> {
+    info!("Previewing e-filing for court: {}", court_id);
+    
+    let session_id = session_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|e| format!("Invalid session ID: {}", e))?
+        .unwrap_or_else(Uuid::nil);
+    let provider = registry.efiling_provider_for(&court_id)
+        .ok_or_else(|| format!("{} does not accept e-filing", court_id))?;
+    
+    let submission = new_submission(session_id, &court_id, docket_id, document_type, files, metadata);
+    queue
+        .submit_filing_preview(provider.as_ref(), &submission)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A pending submission to `court_id`
+fn new_submission(
+    session_id: Uuid,
+    court_id: &str,
+    docket_id: Option<String>,
+    document_type: String,
+    files: Vec<String>,
+    mut metadata: HashMap<String, Value>,
+) -> EFilingSubmission {
+    metadata.insert(COURT_ID_METADATA.to_string(), Value::String(court_id.to_string()));
+    EFilingSubmission {
+        id: Uuid::new_v4(),
+        session_id,
+        docket_id,
+        document_type,
+        files,
+        metadata,
+        status: SubmissionStatus::Pending,
+        submission_id: None,
+        receipt_path: None,
+        error_message: None,
+        submitted_at: None,
+        processed_at: None,
+    }
 }
 
 #[tauri::command]
-#[instrument(skip(submission_id))]
-pub async fn cmd_efiling_status(submission_id: String) -> Result<EFilingSubmission, String> {
+#[instrument(skip(state, config, queue, registry, submission_id), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_efiling_status(
+    state: State<'_, AppState>,
+    config: State<'_, AppConfig>,
+    queue: State<'_, Arc<EFilingQueueService>>,
+    registry: State<'_, Arc<ProviderRegistry>>,
+    submission_id: String,
+) -> Result<EFilingSubmission, String> {
     info!("Checking e-filing status: {}", submission_id);
-    
+
     if submission_id.is_empty() {
         return Err("Submission ID cannot be empty".to_string());
     }
+
+    let queued = queue.get(&submission_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No e-filing with ID {}", submission_id))?;
+    let provider = registry.efiling_provider(&queued.provider)
+        .ok_or_else(|| format!("E-filing provider {} is not configured", queued.provider))?;
+
+    let receipts = EFilingReceiptService::new(
+        state.db_pool.clone(),
+        expand_home(&config.global.data_dir).join("receipts"),
+    );
+    queue
+        .refresh_status(&submission_id, provider.as_ref(), &receipts)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip(queue, registry, current_user, submission_id), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_retry_filing(
+    queue: State<'_, Arc<EFilingQueueService>>,
+    registry: State<'_, Arc<ProviderRegistry>>,
+    current_user: State<'_, CurrentUser>,
+    submission_id: String,
+) -> Result<QueuedFiling, String> {
+    current_user.require(Permission::SubmitEFiling).map_err(|e| e.to_string())?;
+
+    info!("Retrying e-filing: {}", submission_id);
+
+    if submission_id.is_empty() {
+        return Err("Submission ID cannot be empty".to_string());
+    }
+
+    let queued = queue.get(&submission_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No failed filing with ID {}", submission_id))?;
+
+    let provider = registry.efiling_provider(&queued.provider)
+        .ok_or_else(|| format!("E-filing provider {} is not configured", queued.provider))?;
+    queue.retry_now(&submission_id, provider.as_ref()).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_list_failed_filings(queue: State<'_, Arc<EFilingQueueService>>) -> Result<Vec<QueuedFiling>, String> {
+    info!("Listing failed e-filings");
     
-    // TODO: Implement status checking
-    Err("E-filing status not implemented yet".to_string())
+    queue
+        .list_failed()
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // Watchlist Commands
 
+/// Minutes between checks of a watched docket when none is given
+const DEFAULT_WATCH_INTERVAL_MINUTES: u32 = 60;
+
 #[tauri::command]
-#[instrument(skip(docket_id))]
-pub async fn cmd_watch_add(docket_id: String) -> Result<(), String> {
+#[instrument(skip(state, docket_id, court_id), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_watch_add(
+    state: State<'_, AppState>,
+    docket_id: String,
+    court_id: String,
+    notify_on_change: Option<bool>,
+    check_interval: Option<u32>,
+) -> Result<WatchlistItem, String> {
     info!("Adding docket to watchlist: {}", docket_id);
     
-    if docket_id.is_empty() {
-        return Err("Docket ID cannot be empty".to_string());
+    if docket_id.is_empty() || court_id.is_empty() {
+        return Err("Docket ID and court ID cannot be empty".to_string());
     }
     
-    // TODO: Implement watchlist add
-    Ok(())
+    WatchlistService::new(state.db_pool.clone())
+        .add_to_watchlist(
+            &docket_id,
+            &court_id,
+            notify_on_change.unwrap_or(true),
+            check_interval.unwrap_or(DEFAULT_WATCH_INTERVAL_MINUTES),
+        )
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-#[instrument(skip(docket_id))]
-pub async fn cmd_watch_remove(docket_id: String) -> Result<(), String> {
+#[instrument(skip(state, docket_id), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_watch_remove(state: State<'_, AppState>, docket_id: String) -> Result<(), String> {
     info!("Removing docket from watchlist: {}", docket_id);
     
     if docket_id.is_empty() {
         return Err("Docket ID cannot be empty".to_string());
     }
     
-    // TODO: Implement watchlist remove
-    Ok(())
+    WatchlistService::new(state.db_pool.clone())
+        .remove_from_watchlist(&docket_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn cmd_watch_list() -> Result<Vec<WatchlistItem>, String> {
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_watch_list(state: State<'_, AppState>) -> Result<Vec<WatchlistItem>, String> {
     info!("Fetching watchlist");
     
-    // TODO: Implement watchlist retrieval
-    Ok(vec![])
+    WatchlistService::new(state.db_pool.clone())
+        .get_watchlist()
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // Citation Commands
 
 #[tauri::command]
-#[instrument(skip(text))]
+#[instrument(skip(text), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_citation_parse(text: String, style: Option<String>) -> Result<Vec<Citation>, String> {
     info!("Parsing citations from text");
     
@@ -327,12 +555,11 @@ pub async fn cmd_citation_parse(text: String, style: Option<String>) -> Result<V
         return Err("Text cannot be empty".to_string());
     }
     
-    // TODO: Implement citation parsing
-    Ok(vec![])
+    Ok(extract_citations(&text))
 }
 
 #[tauri::command]
-#[instrument(skip(citation))]
+#[instrument(skip(citation), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_citation_format(
     citation: Citation,
     style: String,
@@ -345,77 +572,101 @@ pub async fn cmd_citation_format(
 }
 
 #[tauri::command]
-#[instrument(skip(citations))]
+#[instrument(skip(citations), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_citation_validate(citations: Vec<Citation>) -> Result<Vec<Citation>, String> {
     info!("Validating {} citations", citations.len());
     
-    // TODO: Implement citation validation
-    Ok(citations)
+    Ok(citations
+        .into_iter()
+        .map(|mut citation| {
+            validate_citation(&citation).apply_to(&mut citation);
+            citation
+        })
+        .collect())
 }
 
 // Court Rules Commands
 
 #[tauri::command]
-#[instrument(skip(court_id))]
-pub async fn cmd_get_court_rules(court_id: String) -> Result<CourtRules, String> {
+#[instrument(skip(config, court_id), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_get_court_rules(
+    config: State<'_, AppConfig>,
+    court_id: String,
+    county: Option<String>,
+) -> Result<CourtRules, String> {
     info!("Fetching court rules for: {}", court_id);
     
     if court_id.is_empty() {
         return Err("Court ID cannot be empty".to_string());
     }
     
-    // TODO: Implement court rules retrieval
-    Err("Court rules not implemented yet".to_string())
+    resolve_court_rules(&config.courts, &court_id, county.as_deref()).map_err(|e| e.to_string())
 }
 
 // System Commands
 
 #[tauri::command]
-pub async fn cmd_system_info() -> Result<HashMap<String, String>, String> {
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_system_info(
+    state: State<'_, AppState>,
+    config: State<'_, AppConfig>,
+) -> Result<SystemInfo, String> {
     info!("Fetching system information");
     
-    let mut info = HashMap::new();
-    info.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_string());
-    info.insert("platform".to_string(), std::env::consts::OS.to_string());
-    info.insert("arch".to_string(), std::env::consts::ARCH.to_string());
-    
-    Ok(info)
+    Ok(system_info(&state.db_pool, &config.global, &state.config_dir).await)
 }
 
 #[tauri::command]
-pub async fn cmd_system_health() -> Result<HashMap<String, Value>, String> {
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_system_health(state: State<'_, AppState>) -> Result<HealthReport, String> {
     info!("Checking system health");
     
-    let mut health = HashMap::new();
-    health.insert("status".to_string(), Value::String("healthy".to_string()));
-    health.insert("timestamp".to_string(), Value::String(chrono::Utc::now().to_rfc3339()));
+    let report = system_health(&state.db_pool, &state.config_dir).await;
+    if report.status != HealthStatus::Healthy {
+        warn!("System health is {:?}", report.status);
+    }
     
-    Ok(health)
+    Ok(report)
 }
 
 #[tauri::command]
-#[instrument(skip(level, target, since, limit))]
+#[instrument(skip(config, level, target, since, limit), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_logs(
+    config: State<'_, AppConfig>,
     level: Option<String>,
     target: Option<String>,
     since: Option<String>,
     limit: Option<u32>,
-) -> Result<Vec<HashMap<String, Value>>, String> {
+) -> Result<Vec<LogLine>, String> {
     info!("Fetching logs");
     
-    // TODO: Implement log retrieval
-    Ok(vec![])
+    let since = since
+        .map(|s| chrono::DateTime::parse_from_rfc3339(&s).map(|d| d.with_timezone(&chrono::Utc)))
+        .transpose()
+        .map_err(|e| format!("Invalid since timestamp: {}", e))?;
+    
+    let filter = LogFilter {
+        level,
+        target,
+        since,
+        limit: limit.map(|l| l as usize),
+        ..Default::default()
+    };
+    
+    get_logs(&expand_home(&config.global.log_dir), &filter).map_err(|e| e.to_string())
 }
 
 // Configuration Commands
 
 #[tauri::command]
-#[instrument(skip(section, key, value))]
+#[instrument(skip(current_user, section, key, value), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_update_config(
+    current_user: State<'_, CurrentUser>,
     section: String,
     key: String,
     value: Value,
 ) -> Result<(), String> {
+    current_user.require(Permission::UpdateConfig).map_err(|e| e.to_string())?;
     info!("Updating configuration: {}.{}", section, key);
     
     if section.is_empty() || key.is_empty() {
@@ -427,10 +678,124 @@ pub async fn cmd_update_config(
 }
 
 #[tauri::command]
-#[instrument(skip(section))]
+#[instrument(skip(section), err, fields(correlation_id = %new_correlation_id()))]
 pub async fn cmd_get_config(section: Option<String>) -> Result<HashMap<String, Value>, String> {
     info!("Fetching configuration");
     
     // TODO: Implement configuration retrieval
     Ok(HashMap::new())
 }
+
+// Session Commands
+
+#[tauri::command]
+#[instrument(skip(current_user, state, password), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_sign_in(
+    current_user: State<'_, CurrentUser>,
+    state: State<'_, AppState>,
+    user_id: String,
+    password: String,
+) -> Result<UserSession, String> {
+    if user_id.trim().is_empty() {
+        return Err("User ID cannot be empty".to_string());
+    }
+    
+    let session = UserDirectory::new(state.db_pool.clone())
+        .authenticate(user_id.trim(), &password)
+        .await
+        .map_err(|e| e.to_string())?;
+    current_user.sign_in(session.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+#[instrument(skip(current_user, state, password), err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_create_user(
+    current_user: State<'_, CurrentUser>,
+    state: State<'_, AppState>,
+    user_id: String,
+    display_name: String,
+    role: Role,
+    password: String,
+) -> Result<UserAccount, String> {
+    UserDirectory::new(state.db_pool.clone())
+        .create_user(current_user.session().as_ref(), &user_id, &display_name, role, &password)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_sign_out(current_user: State<'_, CurrentUser>) -> Result<(), String> {
+    current_user.sign_out();
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(skip_all, err, fields(correlation_id = %new_correlation_id()))]
+pub async fn cmd_current_session(current_user: State<'_, CurrentUser>) -> Result<Option<UserSession>, String> {
+    Ok(current_user.session())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::correlation::{CorrelationLayer, CORRELATION_HEADER};
+    use axum::{extract::Path, http::HeaderMap, routing::get, Json, Router};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Log output collected from the test's subscriber
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_logs_and_provider_request_share_correlation_id() {
+        let seen = Arc::new(Mutex::new(None::<String>));
+        let recorder = seen.clone();
+        let app = Router::new().route(
+            "/api/dockets/:id",
+            get(move |Path(id): Path<String>, headers: HeaderMap| async move {
+                let correlation = headers.get(CORRELATION_HEADER).and_then(|v| v.to_str().ok());
+                *recorder.lock().unwrap() = correlation.map(str::to_string);
+                Json(serde_json::json!({ "id": id }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let logs = Captured::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish()
+            .with(CorrelationLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let docket = fetch_docket(&api_base, "CP-51-CR-0001234-2024".to_string()).await.unwrap();
+        assert_eq!(docket["id"], "CP-51-CR-0001234-2024");
+
+        let correlation_id = seen.lock().unwrap().clone().expect("provider request carried no correlation id");
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let command_lines: Vec<&str> = output.lines().filter(|l| l.contains("services::commands")).collect();
+        assert_eq!(command_lines.len(), 2, "{}", output);
+        for line in command_lines {
+            assert!(line.contains(&format!("correlation_id={}", correlation_id)), "{}", line);
+        }
+    }
+}
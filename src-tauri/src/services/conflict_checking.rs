@@ -1,9 +1,11 @@
 // Conflict of Interest Checking System
 // Automated conflict detection for parties, attorneys, and related entities
 
+use crate::services::audit_log::{AuditAction, AuditLogService};
+use crate::services::service_error::ServiceError;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use tracing::{info, warn, error};
@@ -56,6 +58,21 @@ pub struct Conflict {
     pub relationship: String,
     pub detected_at: DateTime<Utc>,
     pub requires_waiver: bool,
+    /// Set when a recorded waiver clears this conflict
+    #[serde(default)]
+    pub waiver_id: Option<String>,
+}
+
+impl Conflict {
+    /// Identifies the same conflict across checks, which each give it a new id
+    pub fn key(&self) -> String {
+        format!(
+            "{:?}:{}:{}",
+            self.conflict_type,
+            self.conflicting_matter_id,
+            self.conflicting_party.to_lowercase()
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -124,6 +141,60 @@ pub struct CalendarConflict {
     pub conflicting_time: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictWaiver {
+    pub id: String,
+    pub conflict_key: String,
+    /// The conflict as first reported, and the check that reported it
+    pub conflict_id: String,
+    pub check_id: String,
+    pub conflict_type: ConflictType,
+    pub conflicting_matter_id: String,
+    pub conflicting_party: String,
+    /// The matter the waiver was given for, from the check that reported it
+    pub matter_id: Option<String>,
+    /// The parties that check covered (see `party_scope`); scopes waivers
+    /// given at intake, before a matter exists
+    pub checked_parties: String,
+    pub approved_by: String,
+    pub basis: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Normalized, sorted names of the parties a check covered, identifying the
+/// prospective client's engagement across checks
+fn party_scope(parties: &[ConflictParty]) -> String {
+    let mut names: Vec<String> = parties
+        .iter()
+        .map(|p| p.name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase())
+        .collect();
+    names.sort();
+    names.dedup();
+    names.join("; ")
+}
+
+/// Waived conflicts no longer count against the check; one that only had
+/// waived conflicts is `WaiverObtained`
+fn overall_status(conflicts: &[Conflict]) -> ConflictStatus {
+    let outstanding: Vec<&Conflict> = conflicts.iter().filter(|c| c.waiver_id.is_none()).collect();
+    if conflicts.is_empty() {
+        ConflictStatus::Cleared
+    } else if outstanding.is_empty() {
+        ConflictStatus::WaiverObtained
+    } else if outstanding.iter().any(|c| c.severity == ConflictSeverity::Critical) {
+        ConflictStatus::WaiverRequired
+    } else {
+        ConflictStatus::ConflictDetected
+    }
+}
+
+/// The parts of a stored check a waiver is scoped by
+struct WaivedCheck {
+    id: String,
+    matter_id: Option<String>,
+    parties: Vec<ConflictParty>,
+}
+
 pub struct ConflictCheckingService {
     db: SqlitePool,
 }
@@ -173,17 +244,15 @@ impl ConflictCheckingService {
         // Sort by severity
         conflicts.sort_by(|a, b| b.severity.cmp(&a.severity));
 
-        // Determine overall status
-        let status = if conflicts.is_empty() {
-            ConflictStatus::Cleared
-        } else if conflicts.iter().any(|c| c.severity == ConflictSeverity::Critical) {
-            ConflictStatus::WaiverRequired
-        } else {
-            ConflictStatus::ConflictDetected
-        };
+        // Conflicts already waived are cleared rather than flagged again
+        let check_id = uuid::Uuid::new_v4().to_string();
+        self.apply_waivers(&check_id, matter_id.as_deref(), &parties, &mut conflicts, checked_by)
+            .await?;
+
+        let status = overall_status(&conflicts);
 
         let check = ConflictCheck {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: check_id,
             matter_id,
             checked_at: Utc::now(),
             checked_by: checked_by.to_string(),
@@ -261,6 +330,7 @@ impl ConflictCheckingService {
                 relationship: "Same party in different matters".to_string(),
                 detected_at: Utc::now(),
                 requires_waiver: severity == ConflictSeverity::Critical,
+                waiver_id: None,
             });
         }
 
@@ -300,6 +370,7 @@ impl ConflictCheckingService {
                     relationship: "Alias match".to_string(),
                     detected_at: Utc::now(),
                     requires_waiver: true,
+                    waiver_id: None,
                 });
             }
         }
@@ -346,6 +417,7 @@ impl ConflictCheckingService {
                     relationship: "Related entity".to_string(),
                     detected_at: Utc::now(),
                     requires_waiver: false,
+                    waiver_id: None,
                 });
             }
         }
@@ -392,6 +464,7 @@ impl ConflictCheckingService {
                 relationship: "Former client".to_string(),
                 detected_at: Utc::now(),
                 requires_waiver: true,
+                waiver_id: None,
             });
         }
 
@@ -440,6 +513,7 @@ impl ConflictCheckingService {
                         relationship: "Possible family member".to_string(),
                         detected_at: Utc::now(),
                         requires_waiver: false,
+                        waiver_id: None,
                     });
                 }
             }
@@ -489,6 +563,7 @@ impl ConflictCheckingService {
                         relationship: "Corporate affiliate".to_string(),
                         detected_at: Utc::now(),
                         requires_waiver: false,
+                        waiver_id: None,
                     });
                 }
             }
@@ -518,6 +593,7 @@ impl ConflictCheckingService {
                 relationship: "Joint representation".to_string(),
                 detected_at: Utc::now(),
                 requires_waiver: true,
+                waiver_id: None,
             });
         }
 
@@ -551,14 +627,7 @@ impl ConflictCheckingService {
         let mut unique = Vec::new();
 
         for conflict in conflicts {
-            let key = format!(
-                "{}:{}:{}",
-                conflict.conflict_type as u8,
-                conflict.conflicting_matter_id,
-                conflict.conflicting_party
-            );
-
-            if seen.insert(key) {
+            if seen.insert(conflict.key()) {
                 unique.push(conflict);
             }
         }
@@ -566,6 +635,163 @@ impl ConflictCheckingService {
         unique
     }
 
+    /// Record a waiver for a conflict found by an earlier check. Later checks
+    /// for the same matter, or for the same parties when the waiver predates
+    /// the matter, mark that conflict cleared instead of flagging it.
+    /// The basis (informed consent, screening, ...) is required.
+    pub async fn record_conflict_waiver(
+        &self,
+        conflict_id: &str,
+        approved_by: &str,
+        basis: &str,
+    ) -> Result<ConflictWaiver> {
+        if basis.trim().is_empty() {
+            return Err(ServiceError::validation("A conflict cannot be cleared without a recorded basis").into());
+        }
+        if approved_by.trim().is_empty() {
+            return Err(ServiceError::validation("A conflict waiver needs an approver").into());
+        }
+
+        let (check, conflict) = self.find_conflict(conflict_id).await?;
+
+        let waiver = ConflictWaiver {
+            id: uuid::Uuid::new_v4().to_string(),
+            conflict_key: conflict.key(),
+            conflict_id: conflict.id.clone(),
+            check_id: check.id,
+            conflict_type: conflict.conflict_type.clone(),
+            conflicting_matter_id: conflict.conflicting_matter_id.clone(),
+            conflicting_party: conflict.conflicting_party.clone(),
+            matter_id: check.matter_id,
+            checked_parties: party_scope(&check.parties),
+            approved_by: approved_by.trim().to_string(),
+            basis: basis.trim().to_string(),
+            created_at: Utc::now(),
+        };
+
+        // The waiver and its audit entry commit together, so a waiver never
+        // clears conflicts without a record of who approved it
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        sqlx::query(
+            r#"
+            INSERT INTO conflict_waivers (
+                id, conflict_key, conflict_id, check_id, conflict_type,
+                conflicting_matter_id, conflicting_party, matter_id, checked_parties,
+                approved_by, basis, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&waiver.id)
+        .bind(&waiver.conflict_key)
+        .bind(&waiver.conflict_id)
+        .bind(&waiver.check_id)
+        .bind(serde_json::to_string(&waiver.conflict_type)?)
+        .bind(&waiver.conflicting_matter_id)
+        .bind(&waiver.conflicting_party)
+        .bind(&waiver.matter_id)
+        .bind(&waiver.checked_parties)
+        .bind(&waiver.approved_by)
+        .bind(&waiver.basis)
+        .bind(waiver.created_at)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record conflict waiver")?;
+
+        AuditLogService::record_in(
+            &mut *tx,
+            &waiver.approved_by,
+            AuditAction::ConflictWaived,
+            &waiver.conflict_id,
+            Some(serde_json::json!({
+                "waiver_id": waiver.id,
+                "check_id": waiver.check_id,
+                "conflict_key": waiver.conflict_key,
+                "matter_id": waiver.matter_id,
+                "basis": waiver.basis,
+            })),
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!("Conflict {} waived by {}", waiver.conflict_id, waiver.approved_by);
+        Ok(waiver)
+    }
+
+    /// The stored check that reported `conflict_id`, and the conflict itself
+    async fn find_conflict(&self, conflict_id: &str) -> Result<(WaivedCheck, Conflict)> {
+        let rows = sqlx::query(
+            "SELECT id, matter_id, parties, conflicts_found FROM conflict_checks
+             WHERE conflicts_found LIKE '%' || ? || '%'",
+        )
+        .bind(conflict_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to look up conflict")?;
+
+        for row in rows {
+            let conflicts: Vec<Conflict> = serde_json::from_str(&row.try_get::<String, _>("conflicts_found")?)?;
+            if let Some(conflict) = conflicts.into_iter().find(|c| c.id == conflict_id) {
+                let check = WaivedCheck {
+                    id: row.try_get("id")?,
+                    matter_id: row.try_get("matter_id")?,
+                    parties: serde_json::from_str(&row.try_get::<String, _>("parties")?)?,
+                };
+                return Ok((check, conflict));
+            }
+        }
+        Err(ServiceError::not_found("Conflict", conflict_id).into())
+    }
+
+    /// Mark conflicts that have a waiver recorded for this matter, or for
+    /// these parties before the matter existed, as cleared, auditing each
+    async fn apply_waivers(
+        &self,
+        check_id: &str,
+        matter_id: Option<&str>,
+        parties: &[ConflictParty],
+        conflicts: &mut [Conflict],
+        checked_by: &str,
+    ) -> Result<()> {
+        let audit = AuditLogService::new(self.db.clone());
+        let scope = party_scope(parties);
+
+        for conflict in conflicts.iter_mut() {
+            let key = conflict.key();
+            let waiver_id: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT id FROM conflict_waivers
+                WHERE conflict_key = ?
+                  AND (matter_id = ? OR (matter_id IS NULL AND checked_parties = ?))
+                ORDER BY created_at DESC LIMIT 1
+                "#,
+            )
+            .bind(&key)
+            .bind(matter_id)
+            .bind(&scope)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to look up conflict waivers")?;
+
+            let Some(waiver_id) = waiver_id else {
+                continue;
+            };
+            audit
+                .record(
+                    checked_by,
+                    AuditAction::ConflictCleared,
+                    check_id,
+                    Some(serde_json::json!({
+                        "conflict_id": conflict.id,
+                        "conflict_key": key,
+                        "waiver_id": waiver_id,
+                    })),
+                )
+                .await?;
+            conflict.waiver_id = Some(waiver_id);
+        }
+        Ok(())
+    }
+
     /// Save conflict check to database
     async fn save_conflict_check(&self, check: &ConflictCheck) -> Result<()> {
         let parties_json = serde_json::to_string(&check.parties)?;
@@ -630,3 +856,169 @@ impl ConflictCheckingService {
         Ok(conflicts)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::audit_log::AuditFilter;
+    use crate::services::database::test_database;
+
+    async fn service() -> ConflictCheckingService {
+        let db = test_database().await;
+        ConflictCheckingService::new(db)
+    }
+
+    fn adverse_conflict() -> Conflict {
+        Conflict {
+            id: uuid::Uuid::new_v4().to_string(),
+            conflict_type: ConflictType::DirectAdverse,
+            severity: ConflictSeverity::Critical,
+            description: "Party 'Acme Corp' appears in another active matter as client".to_string(),
+            conflicting_matter_id: "matter-1".to_string(),
+            conflicting_matter_name: "Acme v. Widgets".to_string(),
+            conflicting_party: "Acme Corp".to_string(),
+            relationship: "Same party in different matters".to_string(),
+            detected_at: Utc::now(),
+            requires_waiver: true,
+            waiver_id: None,
+        }
+    }
+
+    fn prospective_client() -> Vec<ConflictParty> {
+        vec![ConflictParty {
+            name: "Jane  Roe".to_string(),
+            party_type: PartyType::Client,
+            aliases: vec![],
+            related_entities: vec![],
+            ssn_last4: None,
+            date_of_birth: None,
+            address: None,
+        }]
+    }
+
+    /// Store a check that reported `conflict`, as `perform_conflict_check` would
+    async fn record_check(
+        service: &ConflictCheckingService,
+        conflict: &Conflict,
+        matter_id: Option<&str>,
+    ) -> ConflictCheck {
+        let check = ConflictCheck {
+            id: uuid::Uuid::new_v4().to_string(),
+            matter_id: matter_id.map(str::to_string),
+            checked_at: Utc::now(),
+            checked_by: "intake".to_string(),
+            parties: prospective_client(),
+            conflicts_found: vec![conflict.clone()],
+            status: ConflictStatus::WaiverRequired,
+            resolution: None,
+        };
+        service.save_conflict_check(&check).await.unwrap();
+        check
+    }
+
+    #[tokio::test]
+    async fn test_waived_conflict_no_longer_blocks() {
+        let service = service().await;
+        let first = adverse_conflict();
+        let check = record_check(&service, &first, None).await;
+
+        let waiver = service
+            .record_conflict_waiver(&first.id, "managing.partner", "Informed written consent from both clients")
+            .await
+            .unwrap();
+        assert_eq!(waiver.check_id, check.id);
+
+        // The next check finds the same conflict under a new id
+        let mut again = vec![adverse_conflict()];
+        assert_eq!(overall_status(&again), ConflictStatus::WaiverRequired);
+        service
+            .apply_waivers("check-2", None, &prospective_client(), &mut again, "intake")
+            .await
+            .unwrap();
+
+        assert_eq!(again[0].waiver_id.as_deref(), Some(waiver.id.as_str()));
+        assert_eq!(overall_status(&again), ConflictStatus::WaiverObtained);
+
+        let cleared = AuditLogService::new(service.db.clone())
+            .query_audit_log(&AuditFilter {
+                action: Some(AuditAction::ConflictCleared),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].target_id, "check-2");
+    }
+
+    #[tokio::test]
+    async fn test_clearing_without_basis_errors() {
+        let service = service().await;
+        let conflict = adverse_conflict();
+        record_check(&service, &conflict, None).await;
+
+        let err = service.record_conflict_waiver(&conflict.id, "managing.partner", "  ").await.unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "validation");
+
+        let mut again = vec![adverse_conflict()];
+        service
+            .apply_waivers("check-2", None, &prospective_client(), &mut again, "intake")
+            .await
+            .unwrap();
+        assert_eq!(overall_status(&again), ConflictStatus::WaiverRequired);
+    }
+
+    #[tokio::test]
+    async fn test_waiver_only_clears_its_own_matter_or_prospective_client() {
+        let service = service().await;
+        let conflict = adverse_conflict();
+        record_check(&service, &conflict, Some("matter-7")).await;
+        let waiver = service
+            .record_conflict_waiver(&conflict.id, "managing.partner", "Informed written consent")
+            .await
+            .unwrap();
+        assert_eq!(waiver.matter_id.as_deref(), Some("matter-7"));
+        assert_eq!(waiver.checked_parties, "jane roe");
+
+        // Same matter: cleared
+        let mut same_matter = vec![adverse_conflict()];
+        service
+            .apply_waivers("check-2", Some("matter-7"), &[], &mut same_matter, "intake")
+            .await
+            .unwrap();
+        assert_eq!(same_matter[0].waiver_id.as_deref(), Some(waiver.id.as_str()));
+
+        // Same conflict on another matter, or for the same parties with no
+        // matter, is still flagged
+        let mut other_matter = vec![adverse_conflict()];
+        service
+            .apply_waivers("check-3", Some("matter-8"), &prospective_client(), &mut other_matter, "intake")
+            .await
+            .unwrap();
+        assert_eq!(overall_status(&other_matter), ConflictStatus::WaiverRequired);
+
+        let mut intake = vec![adverse_conflict()];
+        service
+            .apply_waivers("check-4", None, &prospective_client(), &mut intake, "intake")
+            .await
+            .unwrap();
+        assert_eq!(overall_status(&intake), ConflictStatus::WaiverRequired);
+
+        // A waiver given at intake, before the matter existed, follows the
+        // same parties but not different ones
+        let intake_conflict = adverse_conflict();
+        record_check(&service, &intake_conflict, None).await;
+        service
+            .record_conflict_waiver(&intake_conflict.id, "managing.partner", "Informed written consent")
+            .await
+            .unwrap();
+
+        let mut other_client = vec![adverse_conflict()];
+        let mut parties = prospective_client();
+        parties[0].name = "Richard Roe".to_string();
+        service
+            .apply_waivers("check-5", None, &parties, &mut other_client, "intake")
+            .await
+            .unwrap();
+        assert_eq!(overall_status(&other_client), ConflictStatus::WaiverRequired);
+    }
+}
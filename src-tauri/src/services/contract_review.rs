@@ -1,13 +1,25 @@
 // Contract Review & Analysis AI Service
 // Automated contract analysis, clause extraction, risk assessment, and redlining
 
+use crate::config::RiskWeights;
+use crate::services::currency::Currency;
+use crate::services::calendar_sync::{CalendarEvent, CalendarSyncService, DeadlineType, LegalDeadline, Priority};
+use crate::services::case_management::DEFAULT_FORUM_STATE;
+use crate::services::document_assembly::{clause_text, DocumentAssemblyService};
+use crate::services::service_error::ServiceError;
+use crate::services::docx_import::parse_docx;
+use crate::services::global_search::{GlobalSearchService, SearchScope};
+use crate::utils::date::parse_date_flexible;
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
 use uuid::Uuid;
 use std::collections::HashMap;
-use regex::Regex;
+use std::sync::{Arc, OnceLock};
+use regex::{Regex, RegexSet, SetMatches};
+use tracing::error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractAnalysis {
@@ -34,10 +46,16 @@ pub struct ContractAnalysis {
     pub clauses_missing: Vec<MissingClause>,
     pub non_standard_clauses: Vec<NonStandardClause>,
     pub obligations: Vec<Obligation>,
+    /// Calendar reminders for dated obligations the client owes
+    #[serde(default)]
+    pub obligation_reminders: Vec<CalendarEvent>,
 
     // Financial terms
     pub payment_terms: Vec<PaymentTerm>,
+    /// Sum of the payment terms; None when they are in different currencies
     pub total_contract_value: Option<f64>,
+    #[serde(default)]
+    pub contract_currency: Option<Currency>,
 
     // Risks and issues
     pub risks: Vec<ContractRisk>,
@@ -182,7 +200,7 @@ pub struct Obligation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentTerm {
     pub amount: Option<f64>,
-    pub currency: String,
+    pub currency: Currency,
     pub description: String,
     pub due_date: Option<DateTime<Utc>>,
     pub frequency: Option<String>,
@@ -293,11 +311,46 @@ pub struct RedlineDocument {
 
 pub struct ContractReviewService {
     db: SqlitePool,
+    calendar: Option<Arc<CalendarSyncService>>,
+    matter_id: Option<String>,
+    client_name: Option<String>,
+    risk_weights: RiskWeights,
 }
 
 impl ContractReviewService {
     pub fn new(db: SqlitePool) -> Self {
-        Self { db }
+        Self {
+            db,
+            calendar: None,
+            matter_id: None,
+            client_name: None,
+            risk_weights: RiskWeights::default(),
+        }
+    }
+
+    /// Firm-specific scoring profile (see `GlobalConfig::risk_weights`)
+    pub fn with_risk_weights(mut self, risk_weights: RiskWeights) -> Self {
+        self.risk_weights = risk_weights;
+        self
+    }
+
+    /// Schedule reminders for the client's dated obligations on `calendar`
+    pub fn with_calendar(mut self, calendar: Arc<CalendarSyncService>) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// The matter the contract belongs to; obligation reminders are kept
+    /// with its case events, and missing clauses are drafted for its forum
+    pub fn with_matter(mut self, matter_id: impl Into<String>) -> Self {
+        self.matter_id = Some(matter_id.into());
+        self
+    }
+
+    /// The firm's client; parties whose name contains it are marked `is_client`
+    pub fn with_client(mut self, client_name: impl Into<String>) -> Self {
+        self.client_name = Some(client_name.into());
+        self
     }
 
     // ============= Contract Analysis =============
@@ -317,13 +370,20 @@ impl ContractReviewService {
         let dates = self.extract_dates(contract_text).await?;
         let jurisdiction = self.extract_jurisdiction(contract_text).await?;
 
-        // Analyze clauses
+        // Analyze clauses. Missing ones are drafted for the matter's forum;
+        // without a matter, for whatever state the contract names.
         let clauses_found = self.analyze_clauses(contract_text, &contract_type).await?;
-        let clauses_missing = self.find_missing_clauses(&clauses_found, &contract_type).await?;
+        let drafting_jurisdiction = self.matter_forum().await?.or_else(|| jurisdiction.clone());
+        let clauses_missing = self
+            .find_missing_clauses(&clauses_found, &contract_type, drafting_jurisdiction.as_deref())
+            .await?;
         let non_standard = self.identify_non_standard_clauses(contract_text, &clauses_found).await?;
 
         // Extract obligations and payment terms
-        let obligations = self.extract_obligations(contract_text, &parties).await?;
+        let obligations = self.extract_obligations(contract_text, &parties, dates.effective_date).await?;
+        let obligation_reminders = self
+            .schedule_obligation_reminders(contract_id, &parties, &obligations, jurisdiction.as_deref())
+            .await?;
         let payment_terms = self.extract_payment_terms(contract_text).await?;
 
         // Identify risks and issues
@@ -350,9 +410,13 @@ impl ContractReviewService {
             &risks.len(),
         ).await?;
 
-        let total_value = payment_terms.iter()
-            .filter_map(|p| p.amount)
-            .sum::<f64>();
+        let contract_currency = payment_terms.first().map(|p| p.currency);
+        let single_currency = payment_terms.iter().all(|p| Some(p.currency) == contract_currency);
+        let total_value = if single_currency {
+            payment_terms.iter().filter_map(|p| p.amount).sum::<f64>()
+        } else {
+            0.0
+        };
 
         let analysis = ContractAnalysis {
             id: analysis_id,
@@ -372,8 +436,10 @@ impl ContractReviewService {
             clauses_missing,
             non_standard_clauses: non_standard,
             obligations,
+            obligation_reminders,
             payment_terms,
             total_contract_value: if total_value > 0.0 { Some(total_value) } else { None },
+            contract_currency: contract_currency.filter(|_| single_currency),
             risks,
             issues,
             recommendations,
@@ -383,10 +449,25 @@ impl ContractReviewService {
         };
 
         self.save_analysis(&analysis).await?;
+        GlobalSearchService::new(self.db.clone())
+            .index(SearchScope::Contract, contract_id, None, &analysis.contract_name, contract_text)
+            .await?;
 
         Ok(analysis)
     }
 
+    /// Analyze an uploaded Word contract
+    pub async fn analyze_contract_docx(
+        &self,
+        contract_id: &str,
+        path: &std::path::Path,
+        contract_type: ContractType,
+        analyzed_by: &str,
+    ) -> Result<ContractAnalysis> {
+        let parsed = parse_docx(path)?;
+        self.analyze_contract(contract_id, &parsed.to_text(), contract_type, analyzed_by).await
+    }
+
     // ============= Clause Extraction =============
 
     async fn analyze_clauses(
@@ -396,303 +477,256 @@ impl ContractReviewService {
     ) -> Result<Vec<ClauseAnalysis>> {
         let mut clauses = Vec::new();
 
+        // One pass over the document finds which clause headings occur at all
+        let candidates = clause_matchers().candidates.matches(text);
+
         // Extract termination clause
-        if let Some(termination) = self.extract_termination_clause(text).await? {
+        if let Some(termination) = self.extract_termination_clause(text, &candidates).await? {
             clauses.push(termination);
         }
 
         // Extract confidentiality clause
-        if let Some(confidentiality) = self.extract_confidentiality_clause(text).await? {
+        if let Some(confidentiality) = self.extract_confidentiality_clause(text, &candidates).await? {
             clauses.push(confidentiality);
         }
 
         // Extract indemnification clause
-        if let Some(indemnification) = self.extract_indemnification_clause(text).await? {
+        if let Some(indemnification) = self.extract_indemnification_clause(text, &candidates).await? {
             clauses.push(indemnification);
         }
 
         // Extract limitation of liability clause
-        if let Some(limitation) = self.extract_limitation_of_liability_clause(text).await? {
+        if let Some(limitation) = self.extract_limitation_of_liability_clause(text, &candidates).await? {
             clauses.push(limitation);
         }
 
         // Extract governing law clause
-        if let Some(governing_law) = self.extract_governing_law_clause(text).await? {
+        if let Some(governing_law) = self.extract_governing_law_clause(text, &candidates).await? {
             clauses.push(governing_law);
         }
 
         // Extract dispute resolution clause
-        if let Some(dispute) = self.extract_dispute_resolution_clause(text).await? {
+        if let Some(dispute) = self.extract_dispute_resolution_clause(text, &candidates).await? {
             clauses.push(dispute);
         }
 
         Ok(clauses)
     }
 
-    async fn extract_termination_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        // Look for termination section
-        let patterns = vec![
-            r"(?i)(termination|term and termination)[\s\S]{0,500}",
-            r"(?i)either party may terminate[\s\S]{0,300}",
-            r"(?i)this agreement.{0,50}may be terminated[\s\S]{0,300}",
-        ];
-
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                let clause_text = matched.as_str().to_string();
-
-                // Analyze termination terms
-                let mut notes = Vec::new();
-                let mut risk_level = RiskLevel::Low;
-
-                // Check for notice period
-                if !clause_text.to_lowercase().contains("notice") &&
-                   !clause_text.to_lowercase().contains("days") {
-                    notes.push("No clear notice period specified".to_string());
-                    risk_level = RiskLevel::Medium;
-                }
+    async fn extract_termination_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Termination, candidates) {
+            let clause_text = matched.as_str().to_string();
 
-                // Check for termination for convenience
-                if clause_text.to_lowercase().contains("for any reason") ||
-                   clause_text.to_lowercase().contains("without cause") {
-                    notes.push("Allows termination for convenience".to_string());
-                }
+            // Analyze termination terms
+            let mut notes = Vec::new();
+            let mut risk_level = RiskLevel::Low;
+
+            // Check for notice period
+            if !clause_text.to_lowercase().contains("notice") &&
+               !clause_text.to_lowercase().contains("days") {
+                notes.push("No clear notice period specified".to_string());
+                risk_level = RiskLevel::Medium;
+            }
 
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Termination,
-                    text: clause_text,
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: true,
-                    risk_level,
-                    notes,
-                    suggestions: Vec::new(),
-                }));
+            // Check for termination for convenience
+            if clause_text.to_lowercase().contains("for any reason") ||
+               clause_text.to_lowercase().contains("without cause") {
+                notes.push("Allows termination for convenience".to_string());
             }
+
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Termination,
+                text: clause_text,
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: true,
+                risk_level,
+                notes,
+                suggestions: Vec::new(),
+            }));
         }
 
         Ok(None)
     }
 
-    async fn extract_confidentiality_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        let patterns = vec![
-            r"(?i)(confidentiality|confidential information)[\s\S]{0,500}",
-            r"(?i)non-disclosure[\s\S]{0,300}",
-        ];
-
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                let clause_text = matched.as_str().to_string();
-
-                let mut notes = Vec::new();
-                let mut risk_level = RiskLevel::Low;
-
-                // Check for definition of confidential information
-                if !clause_text.to_lowercase().contains("means") &&
-                   !clause_text.to_lowercase().contains("defined as") {
-                    notes.push("Confidential information not clearly defined".to_string());
-                    risk_level = RiskLevel::Medium;
-                }
+    async fn extract_confidentiality_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Confidentiality, candidates) {
+            let clause_text = matched.as_str().to_string();
 
-                // Check for exclusions
-                if !clause_text.to_lowercase().contains("public domain") {
-                    notes.push("Standard exclusions may be missing".to_string());
-                }
+            let mut notes = Vec::new();
+            let mut risk_level = RiskLevel::Low;
+
+            // Check for definition of confidential information
+            if !clause_text.to_lowercase().contains("means") &&
+               !clause_text.to_lowercase().contains("defined as") {
+                notes.push("Confidential information not clearly defined".to_string());
+                risk_level = RiskLevel::Medium;
+            }
 
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Confidentiality,
-                    text: clause_text,
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: true,
-                    risk_level,
-                    notes,
-                    suggestions: Vec::new(),
-                }));
+            // Check for exclusions
+            if !clause_text.to_lowercase().contains("public domain") {
+                notes.push("Standard exclusions may be missing".to_string());
             }
+
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Confidentiality,
+                text: clause_text,
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: true,
+                risk_level,
+                notes,
+                suggestions: Vec::new(),
+            }));
         }
 
         Ok(None)
     }
 
-    async fn extract_indemnification_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        let patterns = vec![
-            r"(?i)(indemnification|indemnify)[\s\S]{0,500}",
-            r"(?i)hold harmless[\s\S]{0,300}",
-        ];
-
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                let clause_text = matched.as_str().to_string();
-
-                let mut notes = Vec::new();
-                let mut risk_level = RiskLevel::Medium;
-
-                // Check if one-sided
-                let indemnify_count = clause_text.to_lowercase().matches("shall indemnify").count();
-                if indemnify_count == 1 {
-                    notes.push("One-sided indemnification - only one party indemnifies".to_string());
-                    risk_level = RiskLevel::High;
-                }
+    async fn extract_indemnification_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Indemnification, candidates) {
+            let clause_text = matched.as_str().to_string();
 
-                // Check for unlimited liability
-                if !clause_text.to_lowercase().contains("except") &&
-                   !clause_text.to_lowercase().contains("limitation") {
-                    notes.push("No limitations on indemnification - potentially unlimited liability".to_string());
-                    risk_level = RiskLevel::High;
-                }
+            let mut notes = Vec::new();
+            let mut risk_level = RiskLevel::Medium;
+
+            // Check if one-sided
+            let indemnify_count = clause_text.to_lowercase().matches("shall indemnify").count();
+            if indemnify_count == 1 {
+                notes.push("One-sided indemnification - only one party indemnifies".to_string());
+                risk_level = RiskLevel::High;
+            }
 
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Indemnification,
-                    text: clause_text,
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: false,  // Indemnification clauses often need customization
-                    risk_level,
-                    notes,
-                    suggestions: vec![
-                        "Consider mutual indemnification".to_string(),
-                        "Add cap on indemnification liability".to_string(),
-                    ],
-                }));
+            // Check for unlimited liability
+            if !clause_text.to_lowercase().contains("except") &&
+               !clause_text.to_lowercase().contains("limitation") {
+                notes.push("No limitations on indemnification - potentially unlimited liability".to_string());
+                risk_level = RiskLevel::High;
             }
+
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Indemnification,
+                text: clause_text,
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: false,  // Indemnification clauses often need customization
+                risk_level,
+                notes,
+                suggestions: vec![
+                    "Consider mutual indemnification".to_string(),
+                    "Add cap on indemnification liability".to_string(),
+                ],
+            }));
         }
 
         Ok(None)
     }
 
-    async fn extract_limitation_of_liability_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        let patterns = vec![
-            r"(?i)(limitation of liability|liability limit)[\s\S]{0,500}",
-            r"(?i)in no event shall[\s\S]{0,300}liable",
-        ];
+    async fn extract_limitation_of_liability_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Limitation_of_liability, candidates) {
+            let clause_text = matched.as_str().to_string();
 
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                let clause_text = matched.as_str().to_string();
+            let mut notes = Vec::new();
+            let risk_level = RiskLevel::Low;
 
-                let mut notes = Vec::new();
-                let risk_level = RiskLevel::Low;
-
-                // Check for cap amount
-                if clause_text.to_lowercase().contains("aggregate") ||
-                   clause_text.to_lowercase().contains("exceed") {
-                    notes.push("Includes liability cap".to_string());
-                }
-
-                // Check for excluded damages
-                if clause_text.to_lowercase().contains("consequential") ||
-                   clause_text.to_lowercase().contains("indirect") {
-                    notes.push("Excludes consequential damages".to_string());
-                }
+            // Check for cap amount
+            if clause_text.to_lowercase().contains("aggregate") ||
+               clause_text.to_lowercase().contains("exceed") {
+                notes.push("Includes liability cap".to_string());
+            }
 
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Limitation_of_liability,
-                    text: clause_text,
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: true,
-                    risk_level,
-                    notes,
-                    suggestions: Vec::new(),
-                }));
+            // Check for excluded damages
+            if clause_text.to_lowercase().contains("consequential") ||
+               clause_text.to_lowercase().contains("indirect") {
+                notes.push("Excludes consequential damages".to_string());
             }
+
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Limitation_of_liability,
+                text: clause_text,
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: true,
+                risk_level,
+                notes,
+                suggestions: Vec::new(),
+            }));
         }
 
         Ok(None)
     }
 
-    async fn extract_governing_law_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        let patterns = vec![
-            r"(?i)(governing law|choice of law)[\s\S]{0,200}",
-            r"(?i)construed in accordance with[\s\S]{0,150}",
-        ];
-
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Governing_law,
-                    text: matched.as_str().to_string(),
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: true,
-                    risk_level: RiskLevel::Low,
-                    notes: Vec::new(),
-                    suggestions: Vec::new(),
-                }));
-            }
+    async fn extract_governing_law_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Governing_law, candidates) {
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Governing_law,
+                text: matched.as_str().to_string(),
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: true,
+                risk_level: RiskLevel::Low,
+                notes: Vec::new(),
+                suggestions: Vec::new(),
+            }));
         }
 
         Ok(None)
     }
 
-    async fn extract_dispute_resolution_clause(&self, text: &str) -> Result<Option<ClauseAnalysis>> {
-        let patterns = vec![
-            r"(?i)(dispute resolution|arbitration)[\s\S]{0,500}",
-            r"(?i)disputes arising[\s\S]{0,300}",
-        ];
-
-        for pattern in patterns {
-            let re = Regex::new(pattern)?;
-            if let Some(matched) = re.find(text) {
-                let clause_text = matched.as_str().to_string();
+    async fn extract_dispute_resolution_clause(&self, text: &str, candidates: &SetMatches) -> Result<Option<ClauseAnalysis>> {
+        if let Some(matched) = find_clause_region(text, &StandardClauseType::Dispute_resolution, candidates) {
+            let clause_text = matched.as_str().to_string();
 
-                let mut notes = Vec::new();
+            let mut notes = Vec::new();
 
-                if clause_text.to_lowercase().contains("arbitration") {
-                    notes.push("Requires arbitration for disputes".to_string());
-                }
-
-                if clause_text.to_lowercase().contains("mediation") {
-                    notes.push("Includes mediation requirement".to_string());
-                }
+            if clause_text.to_lowercase().contains("arbitration") {
+                notes.push("Requires arbitration for disputes".to_string());
+            }
 
-                return Ok(Some(ClauseAnalysis {
-                    clause_type: StandardClauseType::Dispute_resolution,
-                    text: clause_text,
-                    location: ClauseLocation {
-                        section: None,
-                        page: None,
-                        paragraph: None,
-                        start_position: Some(matched.start()),
-                        end_position: Some(matched.end()),
-                    },
-                    is_standard: true,
-                    risk_level: RiskLevel::Low,
-                    notes,
-                    suggestions: Vec::new(),
-                }));
+            if clause_text.to_lowercase().contains("mediation") {
+                notes.push("Includes mediation requirement".to_string());
             }
+
+            return Ok(Some(ClauseAnalysis {
+                clause_type: StandardClauseType::Dispute_resolution,
+                text: clause_text,
+                location: ClauseLocation {
+                    section: None,
+                    page: None,
+                    paragraph: None,
+                    start_position: Some(matched.start()),
+                    end_position: Some(matched.end()),
+                },
+                is_standard: true,
+                risk_level: RiskLevel::Low,
+                notes,
+                suggestions: Vec::new(),
+            }));
         }
 
         Ok(None)
@@ -704,80 +738,77 @@ impl ContractReviewService {
         &self,
         found_clauses: &[ClauseAnalysis],
         contract_type: &ContractType,
+        jurisdiction: Option<&str>,
     ) -> Result<Vec<MissingClause>> {
         let found_types: Vec<StandardClauseType> = found_clauses.iter()
             .map(|c| c.clause_type.clone())
             .collect();
 
+        // The firm's own language is offered before the standard clauses
+        let contract_key = format!("{:?}", contract_type);
+        let firm_clauses = DocumentAssemblyService::new(self.db.clone())
+            .firm_clauses(&contract_key)
+            .await?;
+
         let mut missing = Vec::new();
+        let mut require = |clause_type: StandardClauseType, importance: ClauseImportance, reason: &str| {
+            if !found_types.contains(&clause_type) {
+                missing.push(MissingClause {
+                    template_text: clause_text(
+                        &firm_clauses,
+                        &format!("{:?}", clause_type),
+                        &contract_key,
+                        jurisdiction,
+                    ),
+                    clause_type,
+                    importance,
+                    reason: reason.to_string(),
+                });
+            }
+        };
 
         // Check for critical clauses
-        if !found_types.contains(&StandardClauseType::Termination) {
-            missing.push(MissingClause {
-                clause_type: StandardClauseType::Termination,
-                importance: ClauseImportance::Critical,
-                reason: "Termination clause is essential to define how parties can exit the agreement".to_string(),
-                template_text: Some("Either party may terminate this Agreement upon thirty (30) days written notice to the other party.".to_string()),
-            });
-        }
-
-        if !found_types.contains(&StandardClauseType::Governing_law) {
-            missing.push(MissingClause {
-                clause_type: StandardClauseType::Governing_law,
-                importance: ClauseImportance::Critical,
-                reason: "Governing law clause specifies which jurisdiction's laws apply".to_string(),
-                template_text: Some("This Agreement shall be governed by and construed in accordance with the laws of the Commonwealth of Pennsylvania.".to_string()),
-            });
-        }
-
-        if !found_types.contains(&StandardClauseType::Entire_agreement) {
-            missing.push(MissingClause {
-                clause_type: StandardClauseType::Entire_agreement,
-                importance: ClauseImportance::Important,
-                reason: "Entire agreement clause prevents claims based on prior negotiations".to_string(),
-                template_text: Some("This Agreement constitutes the entire agreement between the parties and supersedes all prior agreements and understandings.".to_string()),
-            });
-        }
-
-        if !found_types.contains(&StandardClauseType::Severability) {
-            missing.push(MissingClause {
-                clause_type: StandardClauseType::Severability,
-                importance: ClauseImportance::Important,
-                reason: "Severability clause ensures rest of contract remains valid if one provision is unenforceable".to_string(),
-                template_text: Some("If any provision of this Agreement is held to be invalid or unenforceable, the remaining provisions shall continue in full force and effect.".to_string()),
-            });
-        }
+        require(
+            StandardClauseType::Termination,
+            ClauseImportance::Critical,
+            "Termination clause is essential to define how parties can exit the agreement",
+        );
+        require(
+            StandardClauseType::Governing_law,
+            ClauseImportance::Critical,
+            "Governing law clause specifies which jurisdiction's laws apply",
+        );
+        require(
+            StandardClauseType::Entire_agreement,
+            ClauseImportance::Important,
+            "Entire agreement clause prevents claims based on prior negotiations",
+        );
+        require(
+            StandardClauseType::Severability,
+            ClauseImportance::Important,
+            "Severability clause ensures rest of contract remains valid if one provision is unenforceable",
+        );
 
         // Contract-specific missing clauses
         match contract_type {
             ContractType::Service_agreement | ContractType::Consulting => {
-                if !found_types.contains(&StandardClauseType::Scope_of_work) {
-                    missing.push(MissingClause {
-                        clause_type: StandardClauseType::Scope_of_work,
-                        importance: ClauseImportance::Critical,
-                        reason: "Service agreements should clearly define the scope of work".to_string(),
-                        template_text: None,
-                    });
-                }
-
-                if !found_types.contains(&StandardClauseType::Payment_terms) {
-                    missing.push(MissingClause {
-                        clause_type: StandardClauseType::Payment_terms,
-                        importance: ClauseImportance::Critical,
-                        reason: "Payment terms must be clearly specified".to_string(),
-                        template_text: None,
-                    });
-                }
+                require(
+                    StandardClauseType::Scope_of_work,
+                    ClauseImportance::Critical,
+                    "Service agreements should clearly define the scope of work",
+                );
+                require(
+                    StandardClauseType::Payment_terms,
+                    ClauseImportance::Critical,
+                    "Payment terms must be clearly specified",
+                );
             }
             ContractType::Non_disclosure => {
-                if !found_types.contains(&StandardClauseType::Confidentiality) {
-                    missing.push(MissingClause {
-                        clause_type: StandardClauseType::Confidentiality,
-                        importance: ClauseImportance::Critical,
-                        reason: "NDA must include confidentiality obligations".to_string(),
-                        template_text: None,
-                    });
-                }
+                require(
+                    StandardClauseType::Confidentiality,
+                    ClauseImportance::Critical,
+                    "NDA must include confidentiality obligations",
+                );
             }
             _ => {}
         }
@@ -795,7 +826,8 @@ impl ContractReviewService {
         let mut non_standard = Vec::new();
 
         // Check for automatic renewal clause
-        let auto_renewal_re = Regex::new(r"(?i)(automatic.{0,20}renew|automatically renew)")?;
+        static AUTO_RENEWAL_RE: OnceLock<Regex> = OnceLock::new();
+        let auto_renewal_re = AUTO_RENEWAL_RE.get_or_init(|| compile_regex(r"(?i)(automatic.{0,20}renew|automatically renew)"));
         if let Some(matched) = auto_renewal_re.find(text) {
             non_standard.push(NonStandardClause {
                 text: matched.as_str().to_string(),
@@ -813,7 +845,8 @@ impl ContractReviewService {
         }
 
         // Check for non-compete clause
-        let non_compete_re = Regex::new(r"(?i)(non-compete|non compete|shall not compete)")?;
+        static NON_COMPETE_RE: OnceLock<Regex> = OnceLock::new();
+        let non_compete_re = NON_COMPETE_RE.get_or_init(|| compile_regex(r"(?i)(non-compete|non compete|shall not compete)"));
         if let Some(matched) = non_compete_re.find(text) {
             non_standard.push(NonStandardClause {
                 text: matched.as_str().to_string(),
@@ -842,7 +875,8 @@ impl ContractReviewService {
         let mut risks = Vec::new();
 
         // Check for unlimited liability
-        let unlimited_liability_re = Regex::new(r"(?i)(unlimited|without limit)")?;
+        static UNLIMITED_LIABILITY_RE: OnceLock<Regex> = OnceLock::new();
+        let unlimited_liability_re = UNLIMITED_LIABILITY_RE.get_or_init(|| compile_regex(r"(?i)(unlimited|without limit)"));
         if unlimited_liability_re.is_match(text) {
             risks.push(ContractRisk {
                 risk_type: RiskType::Unlimited_liability,
@@ -892,7 +926,8 @@ impl ContractReviewService {
         let mut issues = Vec::new();
 
         // Check for vague deadlines
-        let vague_deadline_re = Regex::new(r"(?i)(reasonable time|promptly|as soon as possible)")?;
+        static VAGUE_DEADLINE_RE: OnceLock<Regex> = OnceLock::new();
+        let vague_deadline_re = VAGUE_DEADLINE_RE.get_or_init(|| compile_regex(r"(?i)(reasonable time|promptly|as soon as possible)"));
         if let Some(matched) = vague_deadline_re.find(text) {
             issues.push(ContractIssue {
                 issue_type: IssueType::Vague_deadline,
@@ -910,7 +945,8 @@ impl ContractReviewService {
         }
 
         // Check for ambiguous language
-        let ambiguous_re = Regex::new(r"(?i)(may or may not|if necessary|as needed)")?;
+        static AMBIGUOUS_RE: OnceLock<Regex> = OnceLock::new();
+        let ambiguous_re = AMBIGUOUS_RE.get_or_init(|| compile_regex(r"(?i)(may or may not|if necessary|as needed)"));
         if let Some(matched) = ambiguous_re.find(text) {
             issues.push(ContractIssue {
                 issue_type: IssueType::Ambiguous_language,
@@ -936,35 +972,32 @@ impl ContractReviewService {
         issues: &[ContractIssue],
         missing_clauses: &[MissingClause],
     ) -> Result<f64> {
+        let weights = &self.risk_weights;
+        let severity = |w: &crate::config::SeverityWeights, level: &RiskLevel| match level {
+            RiskLevel::Critical => w.critical,
+            RiskLevel::High => w.high,
+            RiskLevel::Medium => w.medium,
+            RiskLevel::Low => w.low,
+        };
         let mut score = 0.0;
 
-        // Risk contribution (max 0.5)
+        // Risk contribution
         for risk in risks {
-            match risk.severity {
-                RiskLevel::Critical => score += 0.15,
-                RiskLevel::High => score += 0.10,
-                RiskLevel::Medium => score += 0.05,
-                RiskLevel::Low => score += 0.02,
-            }
+            score += severity(&weights.risk, &risk.severity);
         }
 
-        // Issue contribution (max 0.3)
+        // Issue contribution
         for issue in issues {
-            match issue.severity {
-                RiskLevel::Critical => score += 0.10,
-                RiskLevel::High => score += 0.06,
-                RiskLevel::Medium => score += 0.03,
-                RiskLevel::Low => score += 0.01,
-            }
+            score += severity(&weights.issue, &issue.severity);
         }
 
-        // Missing clause contribution (max 0.2)
+        // Missing clause contribution
         for missing in missing_clauses {
-            match missing.importance {
-                ClauseImportance::Critical => score += 0.08,
-                ClauseImportance::Important => score += 0.04,
-                ClauseImportance::Recommended => score += 0.02,
-            }
+            score += match missing.importance {
+                ClauseImportance::Critical => weights.missing_clause.critical,
+                ClauseImportance::Important => weights.missing_clause.important,
+                ClauseImportance::Recommended => weights.missing_clause.recommended,
+            };
         }
 
         // Cap at 1.0
@@ -972,11 +1005,12 @@ impl ContractReviewService {
     }
 
     fn determine_risk_level(&self, score: f64) -> RiskLevel {
-        if score >= 0.75 {
+        let thresholds = &self.risk_weights.thresholds;
+        if score >= thresholds.critical {
             RiskLevel::Critical
-        } else if score >= 0.5 {
+        } else if score >= thresholds.high {
             RiskLevel::High
-        } else if score >= 0.25 {
+        } else if score >= thresholds.medium {
             RiskLevel::Medium
         } else {
             RiskLevel::Low
@@ -989,50 +1023,104 @@ impl ContractReviewService {
         let mut parties = Vec::new();
 
         // Simple party extraction - look for "between X and Y"
-        let between_re = Regex::new(r"(?i)between\s+(.+?)\s+and\s+(.+?)[\.,]")?;
+        static BETWEEN_RE: OnceLock<Regex> = OnceLock::new();
+        let between_re = BETWEEN_RE.get_or_init(|| compile_regex(r"(?i)between\s+(.+?)\s+and\s+(.+?)[\.,]"));
         if let Some(caps) = between_re.captures(text) {
             if let (Some(party1), Some(party2)) = (caps.get(1), caps.get(2)) {
-                parties.push(ContractParty {
-                    name: party1.as_str().trim().to_string(),
-                    role: PartyRole::Other,
-                    address: None,
-                    contact_person: None,
-                    email: None,
-                    is_client: false,
-                });
-
-                parties.push(ContractParty {
-                    name: party2.as_str().trim().to_string(),
-                    role: PartyRole::Other,
-                    address: None,
-                    contact_person: None,
-                    email: None,
-                    is_client: false,
-                });
+                let known_clients = self.known_client_names().await?;
+
+                // The first party's label sits before "and"; the second's may
+                // follow the name ("John Smith, as Employee")
+                let first_context = &text[party1.start()..party2.start()];
+                let second_context: String = text[party2.start()..].chars().take(party2.as_str().len() + 200).collect();
+
+                for (party, context) in [(party1, first_context), (party2, second_context.as_str())] {
+                    let name = party.as_str().trim();
+                    parties.push(ContractParty {
+                        name: name.to_string(),
+                        role: infer_party_role(context),
+                        address: None,
+                        contact_person: None,
+                        email: None,
+                        is_client: self.is_client_name(name)
+                            || known_clients.iter().any(|client| contains_ignore_case(name, client)),
+                    });
+                }
             }
         }
 
         Ok(parties)
     }
 
-    struct ExtractedDates {
-        effective_date: Option<DateTime<Utc>>,
-        expiration_date: Option<DateTime<Utc>>,
-        term_length: Option<String>,
+    fn is_client_name(&self, name: &str) -> bool {
+        self.client_name
+            .as_deref()
+            .is_some_and(|client| contains_ignore_case(name, client))
+    }
+
+    /// Names of the firm's clients (business name, or first and last name)
+    async fn known_client_names(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT first_name, last_name, business_name FROM clients WHERE COALESCE(status, 'active') != 'archived'",
+        )
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load clients")?;
+
+        let mut names = Vec::new();
+        for row in rows {
+            let business: Option<String> = row.try_get("business_name")?;
+            if let Some(business) = business.filter(|b| !b.trim().is_empty()) {
+                names.push(business.trim().to_string());
+            }
+            let first: String = row.try_get("first_name")?;
+            let last: String = row.try_get("last_name")?;
+            if !first.trim().is_empty() && !last.trim().is_empty() {
+                names.push(format!("{} {}", first.trim(), last.trim()));
+            }
+        }
+        Ok(names)
     }
 
     async fn extract_dates(&self, text: &str) -> Result<ExtractedDates> {
-        // Stub - would use date parsing library
+        static EFFECTIVE_RE: OnceLock<Regex> = OnceLock::new();
+        static EXPIRATION_RE: OnceLock<Regex> = OnceLock::new();
+        let effective_re = EFFECTIVE_RE.get_or_init(|| {
+            compile_regex(&format!(
+                r"(?i)(?:effective(?:\s+as\s+of|\s+on|\s+date\s+(?:of|is))?|dated(?:\s+as\s+of)?)\s*:?\s*{}",
+                DATE_PATTERN
+            ))
+        });
+        let expiration_re = EXPIRATION_RE
+            .get_or_init(|| compile_regex(&format!(r"(?i)(?:expire|terminate)s?\s+on\s+{}", DATE_PATTERN)));
+
+        let find = |re: &Regex| re.captures(text).and_then(|caps| parse_contract_date(&caps[1], None));
+
         Ok(ExtractedDates {
-            effective_date: None,
-            expiration_date: None,
+            effective_date: find(effective_re),
+            expiration_date: find(expiration_re),
             term_length: None,
         })
     }
 
+    /// The state whose courts hear the contract's matter, if it belongs to one
+    async fn matter_forum(&self) -> Result<Option<String>> {
+        let Some(matter_id) = &self.matter_id else {
+            return Ok(None);
+        };
+        let forum: Option<String> = sqlx::query_scalar("SELECT forum_state FROM matters WHERE id = ?")
+            .bind(matter_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load the matter's forum")?
+            .ok_or_else(|| ServiceError::not_found("Matter", matter_id.as_str()))?;
+        Ok(Some(forum.unwrap_or_else(|| DEFAULT_FORUM_STATE.to_string())))
+    }
+
     async fn extract_jurisdiction(&self, text: &str) -> Result<Option<String>> {
         // Look for state/jurisdiction mentions
-        let jurisdiction_re = Regex::new(r"(?i)(state of|commonwealth of)\s+([A-Za-z\s]+)")?;
+        static JURISDICTION_RE: OnceLock<Regex> = OnceLock::new();
+        let jurisdiction_re = JURISDICTION_RE.get_or_init(|| compile_regex(r"(?i)(state of|commonwealth of)\s+([A-Za-z\s]+)"));
         if let Some(caps) = jurisdiction_re.captures(text) {
             if let Some(jurisdiction) = caps.get(2) {
                 return Ok(Some(jurisdiction.as_str().trim().to_string()));
@@ -1042,17 +1130,28 @@ impl ContractReviewService {
         Ok(None)
     }
 
-    async fn extract_obligations(&self, text: &str, parties: &[ContractParty]) -> Result<Vec<Obligation>> {
+    async fn extract_obligations(
+        &self,
+        text: &str,
+        parties: &[ContractParty],
+        effective_date: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Obligation>> {
         let mut obligations = Vec::new();
 
         // Look for "shall" obligations
-        let shall_re = Regex::new(r"(?i)([A-Za-z\s]+)\s+shall\s+([^\.]+)\.")?;
+        static SHALL_RE: OnceLock<Regex> = OnceLock::new();
+        let shall_re = SHALL_RE.get_or_init(|| compile_regex(r"(?i)([A-Za-z\s]+)\s+shall\s+([^\.]+)\."));
         for caps in shall_re.captures_iter(text) {
             if let (Some(party), Some(action)) = (caps.get(1), caps.get(2)) {
+                // The deadline may lead the sentence ("Within 30 days ..., Vendor shall")
+                let matched = caps.get(0).unwrap();
+                let sentence_start = text[..matched.start()].rfind('.').map(|i| i + 1).unwrap_or(0);
+                let sentence = &text[sentence_start..matched.end()];
+
                 obligations.push(Obligation {
                     party: party.as_str().trim().to_string(),
                     description: action.as_str().trim().to_string(),
-                    deadline: None,
+                    deadline: resolve_deadline(sentence, effective_date),
                     is_recurring: false,
                     frequency: None,
                     penalty_for_breach: None,
@@ -1067,15 +1166,19 @@ impl ContractReviewService {
     async fn extract_payment_terms(&self, text: &str) -> Result<Vec<PaymentTerm>> {
         let mut payment_terms = Vec::new();
 
-        // Look for dollar amounts
-        let amount_re = Regex::new(r"\$([0-9,]+(?:\.[0-9]{2})?)")?;
+        // Look for amounts marked with a currency symbol or code
+        static AMOUNT_RE: OnceLock<Regex> = OnceLock::new();
+        let amount_re = AMOUNT_RE.get_or_init(|| {
+            compile_regex(r"(CA\$|C\$|A\$|\$|€|£|\b(?:USD|EUR|GBP|CAD|AUD|CHF)\s?)([0-9,]+(?:\.[0-9]{2})?)")
+        });
         for caps in amount_re.captures_iter(text) {
-            if let Some(amount_str) = caps.get(1) {
+            let currency = Currency::from_marker(&caps[1]).unwrap_or_default();
+            if let Some(amount_str) = caps.get(2) {
                 let amount_cleaned = amount_str.as_str().replace(",", "");
                 if let Ok(amount) = amount_cleaned.parse::<f64>() {
                     payment_terms.push(PaymentTerm {
                         amount: Some(amount),
-                        currency: "USD".to_string(),
+                        currency,
                         description: "Payment".to_string(),
                         due_date: None,
                         frequency: None,
@@ -1088,6 +1191,111 @@ impl ContractReviewService {
         Ok(payment_terms)
     }
 
+    // ============= Obligation Reminders =============
+
+    /// Put each upcoming obligation deadline owed by a client party on the
+    /// matter's calendar. Each reminder is keyed by its contract and
+    /// obligation, so reviewing the contract again updates the reminder
+    /// instead of adding another. Calendar failures are logged rather than
+    /// failing the review.
+    async fn schedule_obligation_reminders(
+        &self,
+        contract_id: &str,
+        parties: &[ContractParty],
+        obligations: &[Obligation],
+        jurisdiction: Option<&str>,
+    ) -> Result<Vec<CalendarEvent>> {
+        let (Some(calendar), Some(matter_id)) = (&self.calendar, &self.matter_id) else {
+            return Ok(Vec::new());
+        };
+
+        let now = calendar.now();
+        let mut events = Vec::new();
+        for obligation in obligations {
+            let Some(deadline_date) = obligation.deadline.filter(|d| *d >= now) else {
+                continue;
+            };
+            if !owed_by_client(obligation, parties) {
+                continue;
+            }
+
+            let deadline = LegalDeadline {
+                id: obligation_event_id(contract_id, obligation),
+                matter_id: matter_id.clone(),
+                docket_number: None,
+                deadline_type: DeadlineType::Custom,
+                deadline_date,
+                calculated_from: None,
+                jurisdiction: jurisdiction.map(str::to_string),
+                court_rules: Vec::new(),
+                description: format!("{} shall {}", obligation.party, obligation.description),
+                priority: if deadline_date - now <= Duration::days(14) { Priority::High } else { Priority::Medium },
+                auto_calculated: true,
+            };
+            let mut event = calendar.deadline_to_calendar_event(deadline);
+            event.title = format!("Contract {} - {} obligation", contract_id, obligation.party);
+            event.external_id = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT external_id FROM case_events WHERE id = ? AND calendar_provider = ?",
+            )
+            .bind(&event.id)
+            .bind(serde_json::to_string(&event.calendar_provider)?)
+            .fetch_optional(&self.db)
+            .await?
+            .flatten();
+
+            let event = match calendar.sync_event(event.clone()).await {
+                Ok(synced) => synced,
+                Err(e) => {
+                    error!("Failed to sync obligation reminder: {}", e);
+                    event
+                }
+            };
+            self.save_reminder(matter_id, &event).await?;
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    /// Insert or update the case event behind an obligation reminder
+    async fn save_reminder(&self, matter_id: &str, event: &CalendarEvent) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO case_events (
+                id, matter_id, event_type, title, description, event_date, location,
+                reminder_set, reminder_date, completed, calendar_provider, external_id,
+                created_at, updated_at
+            ) VALUES (?, ?, 'contract_obligation', ?, ?, ?, ?, 1, ?, 0, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                matter_id = excluded.matter_id,
+                title = excluded.title,
+                description = excluded.description,
+                event_date = excluded.event_date,
+                location = excluded.location,
+                reminder_date = excluded.reminder_date,
+                calendar_provider = excluded.calendar_provider,
+                external_id = excluded.external_id,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&event.id)
+        .bind(matter_id)
+        .bind(&event.title)
+        .bind(&event.description)
+        .bind(event.end_time.date_naive().to_string())
+        .bind(&event.location)
+        .bind((event.end_time - Duration::days(1)).date_naive().to_string())
+        .bind(serde_json::to_string(&event.calendar_provider)?)
+        .bind(&event.external_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.db)
+        .await
+        .with_context(|| format!("Failed to save obligation reminder {}", event.id))?;
+        Ok(())
+    }
+
     // ============= Recommendations =============
 
     async fn generate_recommendations(
@@ -1213,3 +1421,616 @@ impl ContractReviewService {
         Ok(())
     }
 }
+
+/// A clause heading and how much of the text after it is taken as the clause
+struct ClausePattern {
+    clause_type: StandardClauseType,
+    head: &'static str,
+    body_chars: usize,
+    /// The clause must run to this word, as in "in no event shall ... liable"
+    ends_with: Option<&'static str>,
+}
+
+/// Tried in order for each clause type; the first pattern found wins
+const CLAUSE_PATTERNS: &[ClausePattern] = &[
+    ClausePattern { clause_type: StandardClauseType::Termination, head: r"termination|term and termination", body_chars: 500, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Termination, head: r"either party may terminate", body_chars: 300, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Termination, head: r"this agreement.{0,50}may be terminated", body_chars: 300, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Confidentiality, head: r"confidentiality|confidential information", body_chars: 500, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Confidentiality, head: r"non-disclosure", body_chars: 300, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Indemnification, head: r"indemnification|indemnify", body_chars: 500, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Indemnification, head: r"hold harmless", body_chars: 300, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Limitation_of_liability, head: r"limitation of liability|liability limit", body_chars: 500, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Limitation_of_liability, head: r"in no event shall", body_chars: 300, ends_with: Some("liable") },
+    ClausePattern { clause_type: StandardClauseType::Governing_law, head: r"governing law|choice of law", body_chars: 200, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Governing_law, head: r"construed in accordance with", body_chars: 150, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Dispute_resolution, head: r"dispute resolution|arbitration", body_chars: 500, ends_with: None },
+    ClausePattern { clause_type: StandardClauseType::Dispute_resolution, head: r"disputes arising", body_chars: 300, ends_with: None },
+];
+
+/// CLAUSE_PATTERNS compiled once. Headings are matched without their
+/// bodies, so scanning a long contract never runs `[\s\S]{0,500}` patterns.
+struct ClauseMatchers {
+    /// Every heading at once: one scan tells which patterns can match
+    candidates: RegexSet,
+    heads: Vec<Regex>,
+    ends: Vec<Option<Regex>>,
+}
+
+static CLAUSE_MATCHERS: OnceLock<ClauseMatchers> = OnceLock::new();
+
+fn clause_matchers() -> &'static ClauseMatchers {
+    CLAUSE_MATCHERS.get_or_init(|| {
+        let heads: Vec<String> = CLAUSE_PATTERNS.iter().map(|p| format!("(?i){}", p.head)).collect();
+        #[cfg(test)]
+        REGEX_COMPILATIONS.with(|n| n.set(n.get() + 1));
+        ClauseMatchers {
+            candidates: RegexSet::new(&heads).expect("invalid clause pattern"),
+            heads: heads.iter().map(|head| compile_regex(head)).collect(),
+            ends: CLAUSE_PATTERNS
+                .iter()
+                .map(|p| p.ends_with.map(|end| compile_regex(&format!("(?i){}", end))))
+                .collect(),
+        }
+    })
+}
+
+#[cfg(test)]
+thread_local! {
+    static REGEX_COMPILATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Compile one of this module's fixed patterns; only called from `OnceLock`
+/// initializers so each pattern is compiled once per process
+fn compile_regex(pattern: &str) -> Regex {
+    #[cfg(test)]
+    REGEX_COMPILATIONS.with(|n| n.set(n.get() + 1));
+    Regex::new(pattern).expect("invalid contract review pattern")
+}
+
+/// Span of a clause found in the contract text
+struct ClauseRegion<'t> {
+    text: &'t str,
+    start: usize,
+    end: usize,
+}
+
+impl<'t> ClauseRegion<'t> {
+    fn as_str(&self) -> &'t str {
+        &self.text[self.start..self.end]
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn end(&self) -> usize {
+        self.end
+    }
+}
+
+/// The clause of `clause_type` from its first pattern that matches. Patterns
+/// the `candidates` scan ruled out are skipped without searching the text.
+fn find_clause_region<'t>(
+    text: &'t str,
+    clause_type: &StandardClauseType,
+    candidates: &SetMatches,
+) -> Option<ClauseRegion<'t>> {
+    let matchers = clause_matchers();
+    for (i, pattern) in CLAUSE_PATTERNS.iter().enumerate() {
+        if &pattern.clause_type != clause_type || !candidates.matched(i) {
+            continue;
+        }
+
+        for head in matchers.heads[i].find_iter(text) {
+            let body_end = advance_chars(text, head.end(), pattern.body_chars);
+            let end = match (&matchers.ends[i], pattern.ends_with) {
+                // The last occurrence that starts within the body
+                (Some(end_re), Some(word)) => {
+                    let window_end = advance_chars(text, body_end, word.chars().count());
+                    end_re
+                        .find_iter(&text[head.end()..window_end])
+                        .last()
+                        .map(|m| head.end() + m.end())
+                }
+                _ => Some(body_end),
+            };
+            if let Some(end) = end {
+                return Some(ClauseRegion {
+                    text,
+                    start: head.start(),
+                    end,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Byte offset `chars` characters past `from`, or the end of the text
+fn advance_chars(text: &str, from: usize, chars: usize) -> usize {
+    text[from..].char_indices().nth(chars).map(|(i, _)| from + i).unwrap_or(text.len())
+}
+
+struct ExtractedDates {
+    effective_date: Option<DateTime<Utc>>,
+    expiration_date: Option<DateTime<Utc>>,
+    term_length: Option<String>,
+}
+
+/// "December 31, 2025", "December 31st", "12/31/2025" or "2025-12-31"
+const DATE_PATTERN: &str = r"((?:January|February|March|April|May|June|July|August|September|October|November|December)\s+\d{1,2}(?:st|nd|rd|th)?(?:,?\s+\d{4})?|\d{1,2}/\d{1,2}/\d{4}|\d{4}-\d{2}-\d{2})";
+
+/// Parse a date matched by DATE_PATTERN. A month and day without a year is
+/// the next such date on or after `not_before`.
+fn parse_contract_date(value: &str, not_before: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    static ORDINAL_RE: OnceLock<Regex> = OnceLock::new();
+    let ordinal_re = ORDINAL_RE.get_or_init(|| compile_regex(r"(\d)(?:st|nd|rd|th)"));
+    let value = ordinal_re.replace_all(value.trim(), "$1").replace(',', " ");
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if let Ok(date) = NaiveDate::parse_from_str(&value, "%B %d %Y") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    if let Ok(date) = parse_date_flexible(&value) {
+        return Some(date);
+    }
+
+    let not_before = not_before?.date_naive();
+    let month_day = NaiveDate::parse_from_str(&format!("{} {}", value, not_before.year()), "%B %d %Y").ok()?;
+    let date = if month_day >= not_before {
+        month_day
+    } else {
+        month_day.with_year(not_before.year() + 1)?
+    };
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+fn number_word(word: &str) -> Option<u32> {
+    const WORDS: &[(&str, u32)] = &[
+        ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6),
+        ("seven", 7), ("eight", 8), ("nine", 9), ("ten", 10), ("fourteen", 14),
+        ("fifteen", 15), ("twenty", 20), ("thirty", 30), ("forty-five", 45),
+        ("sixty", 60), ("ninety", 90),
+    ];
+    word.parse().ok().or_else(|| {
+        let word = word.to_lowercase();
+        WORDS.iter().find(|(w, _)| *w == word).map(|(_, n)| *n)
+    })
+}
+
+/// Deadline stated in an obligation's sentence, either relative to the
+/// Effective Date ("within thirty (30) days after the Effective Date") or
+/// absolute ("by December 31"). Relative deadlines need `effective_date`.
+fn resolve_deadline(sentence: &str, effective_date: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    static RELATIVE_RE: OnceLock<Regex> = OnceLock::new();
+    let relative_re = RELATIVE_RE.get_or_init(|| {
+        compile_regex(
+            r"(?i)(?:within|no\s+later\s+than|not\s+later\s+than)\s+([a-z\-]+|\d+)\s*(?:\((\d+)\)\s*)?(business\s+)?(day|week|month|year)s?\s+(?:of|after|from|following)\s+(?:the\s+)?effective\s+date",
+        )
+    });
+    if let Some(caps) = relative_re.captures(sentence) {
+        let effective = effective_date?;
+        let amount = caps.get(2).and_then(|m| m.as_str().parse().ok()).or_else(|| number_word(&caps[1]))?;
+        return match caps[4].to_lowercase().as_str() {
+            "day" if caps.get(3).is_some() => Some(add_business_days(effective, amount)),
+            "day" => Some(effective + Duration::days(amount as i64)),
+            "week" => Some(effective + Duration::weeks(amount as i64)),
+            "month" => effective.checked_add_months(Months::new(amount)),
+            _ => effective.checked_add_months(Months::new(amount.saturating_mul(12))),
+        };
+    }
+
+    static ABSOLUTE_RE: OnceLock<Regex> = OnceLock::new();
+    let absolute_re = ABSOLUTE_RE.get_or_init(|| {
+        compile_regex(&format!(
+            r"(?i)\b(?:by|on\s+or\s+before|no\s+later\s+than|not\s+later\s+than)\s+{}",
+            DATE_PATTERN
+        ))
+    });
+    let caps = absolute_re.captures(sentence)?;
+    parse_contract_date(&caps[1], Some(effective_date.unwrap_or_else(Utc::now)))
+}
+
+fn add_business_days(start: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    let mut date = start;
+    let mut added = 0;
+    while added < days {
+        date += Duration::days(1);
+        if !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            added += 1;
+        }
+    }
+    date
+}
+
+/// Role from the first recognised label in a party's context: a defined term
+/// ("(the 'Landlord')", "(hereinafter \"Tenant\")") or "as Employer"
+fn infer_party_role(context: &str) -> PartyRole {
+    static LABEL_RE: OnceLock<Regex> = OnceLock::new();
+    let label_re = LABEL_RE.get_or_init(|| {
+        compile_regex(
+            r#"(?i)\(\s*(?:the\s+|hereinafter\s+(?:referred\s+to\s+as\s+)?(?:the\s+)?)?["'“‘]([^"'”’]+)["'”’]\s*\)|\bas\s+(?:the\s+)?["“]?([a-z]+(?:\s+provider)?)"#,
+        )
+    });
+
+    label_re
+        .captures_iter(context)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .find_map(|label| party_role_for_label(label.as_str()))
+        .unwrap_or(PartyRole::Other)
+}
+
+fn party_role_for_label(label: &str) -> Option<PartyRole> {
+    let role = match label.trim().to_lowercase().as_str() {
+        "client" | "customer" => PartyRole::Client,
+        "vendor" | "supplier" => PartyRole::Vendor,
+        "service provider" | "provider" | "contractor" | "consultant" => PartyRole::Service_provider,
+        "employer" => PartyRole::Employer,
+        "employee" | "executive" => PartyRole::Employee,
+        "landlord" | "lessor" => PartyRole::Landlord,
+        "tenant" | "lessee" => PartyRole::Tenant,
+        "buyer" | "purchaser" => PartyRole::Buyer,
+        "seller" => PartyRole::Seller,
+        "licensor" => PartyRole::Licensor,
+        "licensee" => PartyRole::Licensee,
+        _ => return None,
+    };
+    Some(role)
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// Stable case event id for an obligation of a contract
+fn obligation_event_id(contract_id: &str, obligation: &Obligation) -> String {
+    let key = format!("{}\n{}\n{}", contract_id, obligation.party, obligation.description);
+    format!("obligation-{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Whether the obligated party is one of the client parties, matching either
+/// the party's name or its defined term (Acme Corp. ("Client"))
+fn owed_by_client(obligation: &Obligation, parties: &[ContractParty]) -> bool {
+    let obligor = obligation.party.to_lowercase();
+    let obligor = obligor.trim_start_matches("the ").trim();
+    !obligor.is_empty()
+        && parties
+            .iter()
+            .filter(|p| p.is_client)
+            .any(|p| p.name.to_lowercase().contains(obligor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use chrono::TimeZone;
+
+    async fn service() -> ContractReviewService {
+        let db = test_database().await;
+        sqlx::raw_sql(
+            "INSERT INTO clients (id, first_name, last_name, created_at, updated_at) VALUES ('c1', 'Acme', 'Corp', '2024-01-01', '2024-01-01');
+             INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+             VALUES ('m1', 'c1', '2024-001', 'Acme services agreement', 'contract', '2024-01-01', '2024-01-01');",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        ContractReviewService::new(db)
+            .with_client("Acme")
+            .with_matter("m1")
+            .with_calendar(Arc::new(CalendarSyncService::new()))
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_relative_obligation_resolves_and_is_scheduled() {
+        let service = service().await;
+        let text = "This Services Agreement is effective as of March 1, 2030 between Acme Corp. (\"Client\") \
+                    and Beta LLC (\"Vendor\"). Within thirty (30) days after the Effective Date, Client shall \
+                    pay the onboarding fee. Vendor shall deliver the final report by December 31. Client shall \
+                    provide office access.";
+
+        let analysis = service
+            .analyze_contract("K-100", text, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+
+        assert_eq!(analysis.effective_date, Some(date(2030, 3, 1)));
+        assert!(analysis.parties[0].is_client);
+        assert!(!analysis.parties[1].is_client);
+
+        let deadlines: Vec<_> = analysis.obligations.iter().map(|o| (o.party.as_str(), o.deadline)).collect();
+        assert_eq!(
+            deadlines,
+            vec![
+                ("Client", Some(date(2030, 3, 31))),
+                ("Vendor", Some(date(2030, 12, 31))),
+                ("Client", None),
+            ]
+        );
+
+        // Only the client's dated obligation is put on the calendar
+        assert_eq!(analysis.obligation_reminders.len(), 1);
+        let reminder = &analysis.obligation_reminders[0];
+        assert_eq!(reminder.end_time, date(2030, 3, 31));
+        assert_eq!(reminder.description.as_deref(), Some("Client shall pay the onboarding fee"));
+        assert!(!reminder.reminders.is_empty());
+
+        // Reviewing the contract again updates the reminder instead of adding one
+        let revised = text.replace("thirty (30) days", "sixty (60) days");
+        let again = service
+            .analyze_contract("K-100", &revised, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+        assert_eq!(again.obligation_reminders[0].id, reminder.id);
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT event_date, event_type FROM case_events WHERE matter_id = 'm1'",
+        )
+        .fetch_all(&service.db)
+        .await
+        .unwrap();
+        assert_eq!(rows, vec![("2030-04-30".to_string(), "contract_obligation".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_obligations_already_due_on_the_calendar_clock_are_skipped() {
+        use crate::utils::clock::MockClock;
+
+        let clock = Arc::new(MockClock::new(date(2030, 4, 1)));
+        let service = ContractReviewService::new(service().await.db)
+            .with_client("Acme")
+            .with_matter("m1")
+            .with_calendar(Arc::new(CalendarSyncService::new().with_clock(clock.clone())));
+        let text = "This Services Agreement is effective as of March 1, 2030 between Acme Corp. (\"Client\") \
+                    and Beta LLC (\"Vendor\"). Within thirty (30) days after the Effective Date, Client shall \
+                    pay the onboarding fee.";
+
+        // Due March 31, which has passed by April 1
+        let late = service
+            .analyze_contract("K-300", text, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+        assert_eq!(late.obligations[0].deadline, Some(date(2030, 3, 31)));
+        assert!(late.obligation_reminders.is_empty());
+
+        clock.set(date(2030, 3, 15));
+        let timely = service
+            .analyze_contract("K-300", text, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+        assert_eq!(timely.obligation_reminders.len(), 1);
+        assert_eq!(timely.obligation_reminders[0].end_time, date(2030, 3, 31));
+    }
+
+    #[tokio::test]
+    async fn test_lease_parties_get_landlord_and_tenant_roles() {
+        let service = service().await;
+        sqlx::query(
+            "INSERT INTO clients (id, first_name, last_name, created_at, updated_at) \
+             VALUES ('c1', 'Jane', 'Roe', datetime('now'), datetime('now'))",
+        )
+        .execute(&service.db)
+        .await
+        .unwrap();
+
+        let text = "This Lease Agreement is made between Keystone Properties LLC (the \"Landlord\") and \
+                    Jane Roe (hereinafter the \"Tenant\"). Landlord shall maintain the roof.";
+        let parties = service.extract_parties(text).await.unwrap();
+
+        assert_eq!(parties.len(), 2);
+        assert_eq!(parties[0].role, PartyRole::Landlord);
+        assert!(!parties[0].is_client);
+        assert_eq!(parties[1].role, PartyRole::Tenant);
+        assert!(parties[1].is_client, "Jane Roe is a firm client");
+    }
+
+    #[tokio::test]
+    async fn test_employment_parties_get_employer_and_employee_roles() {
+        let service = service().await;
+        let text = "This Employment Agreement is entered into between Acme Corp., a Pennsylvania corporation, \
+                    as Employer, and John Smith, as Employee. Employer shall pay Employee a salary.";
+        let parties = service.extract_parties(text).await.unwrap();
+
+        assert_eq!(parties.len(), 2);
+        assert_eq!(parties[0].role, PartyRole::Employer);
+        assert!(parties[0].is_client);
+        assert_eq!(parties[1].name, "John Smith");
+        assert_eq!(parties[1].role, PartyRole::Employee);
+        assert!(!parties[1].is_client);
+    }
+
+    #[tokio::test]
+    async fn test_missing_governing_law_gets_pa_template() {
+        let service = service().await;
+
+        let missing = service
+            .find_missing_clauses(&[], &ContractType::Service_agreement, Some("Pennsylvania"))
+            .await
+            .unwrap();
+
+        let governing_law = missing
+            .iter()
+            .find(|m| m.clause_type == StandardClauseType::Governing_law)
+            .unwrap();
+        let template = governing_law.template_text.as_deref().unwrap();
+        assert!(template.contains("laws of the Commonwealth of Pennsylvania"));
+        assert!(template.contains("Court of Common Pleas"));
+
+        // Every critical clause comes with language to start from
+        assert!(missing
+            .iter()
+            .filter(|m| m.importance == ClauseImportance::Critical)
+            .all(|m| m.template_text.is_some()));
+    }
+
+    fn governing_law_template(analysis: &ContractAnalysis) -> String {
+        analysis
+            .clauses_missing
+            .iter()
+            .find(|m| m.clause_type == StandardClauseType::Governing_law)
+            .and_then(|m| m.template_text.clone())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_clauses_drafted_for_the_matter_forum() {
+        let service = service().await;
+        let text = "Acme Corp. (\"Client\") engages Beta LLC (\"Vendor\") to perform the Services.";
+
+        // A matter with no forum recorded is heard in Pennsylvania
+        let analysis = service
+            .analyze_contract("K-200", text, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+        assert!(governing_law_template(&analysis).contains("Commonwealth of Pennsylvania"));
+
+        // The forum wins over a state the contract happens to mention
+        sqlx::query("UPDATE matters SET forum_state = 'NY' WHERE id = 'm1'")
+            .execute(&service.db)
+            .await
+            .unwrap();
+        let text = format!("{} Vendor is registered in the State of Texas.", text);
+        let analysis = service
+            .analyze_contract("K-201", &text, ContractType::Service_agreement, "jdoe")
+            .await
+            .unwrap();
+        assert_eq!(analysis.jurisdiction.as_deref(), Some("Texas"));
+        assert!(governing_law_template(&analysis).contains("the laws of the State of New York"));
+    }
+
+    #[tokio::test]
+    async fn test_firm_clause_preferred_for_its_contract_type() {
+        let service = service().await;
+        sqlx::query(
+            "INSERT INTO clause_library (id, name, category, contract_type, text)
+             VALUES ('firm-1', 'Firm termination', 'Termination', 'Consulting', 'Firm termination language.')",
+        )
+        .execute(&service.db)
+        .await
+        .unwrap();
+        let termination = |missing: Vec<MissingClause>| {
+            missing
+                .into_iter()
+                .find(|m| m.clause_type == StandardClauseType::Termination)
+                .and_then(|m| m.template_text)
+                .unwrap()
+        };
+
+        let consulting = service.find_missing_clauses(&[], &ContractType::Consulting, None).await.unwrap();
+        assert_eq!(termination(consulting), "Firm termination language.");
+
+        // The firm's clause is scoped to consulting agreements
+        let lease = service.find_missing_clauses(&[], &ContractType::Lease, None).await.unwrap();
+        assert!(termination(lease).starts_with("Either party may terminate"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_risk_profile_raises_borderline_contract() {
+        let risk = |severity| ContractRisk {
+            risk_type: RiskType::Legal,
+            severity,
+            description: String::new(),
+            affected_clause: None,
+            mitigation: String::new(),
+        };
+        let missing = |clause_type| MissingClause {
+            clause_type,
+            importance: ClauseImportance::Critical,
+            reason: String::new(),
+            template_text: None,
+        };
+        let risks = vec![risk(RiskLevel::High), risk(RiskLevel::Medium)];
+        let missing = vec![missing(StandardClauseType::Governing_law), missing(StandardClauseType::Termination)];
+
+        // 0.10 + 0.05 + 2 x 0.08 = 0.31 under the default profile
+        let default = service().await;
+        let score = default.calculate_risk_score(&risks, &[], &missing).await.unwrap();
+        assert!((score - 0.31).abs() < 1e-9);
+        assert_eq!(default.determine_risk_level(score), RiskLevel::Medium);
+
+        // A transactional profile that treats missing boilerplate as serious
+        let mut weights = RiskWeights::default();
+        weights.missing_clause.critical = 0.15;
+        weights.thresholds.high = 0.4;
+        let transactional = service().await.with_risk_weights(weights);
+        let score = transactional.calculate_risk_score(&risks, &[], &missing).await.unwrap();
+        assert!((score - 0.45).abs() < 1e-9);
+        assert_eq!(transactional.determine_risk_level(score), RiskLevel::High);
+    }
+
+    #[tokio::test]
+    async fn test_large_contract_clause_extraction_reuses_compiled_patterns() {
+        let service = service().await;
+
+        // About 100 pages, with clauses spread through it
+        let filler = "The parties acknowledge the recitals above and agree to the following provisions. ";
+        let mut text = String::new();
+        for page in 0..100 {
+            text.push_str(&filler.repeat(40));
+            match page {
+                10 => text.push_str("TERMINATION. Either party may terminate this Agreement upon thirty (30) days notice. "),
+                35 => text.push_str("Confidential Information means non-public information, excluding the public domain. "),
+                60 => text.push_str("In no event shall either party be liable for consequential damages, and neither party shall be liable for indirect damages. "),
+                99 => text.push_str("GOVERNING LAW. This Agreement is governed by the laws of the Commonwealth of Pennsylvania."),
+                _ => {}
+            }
+        }
+
+        service.analyze_clauses("warm-up", &ContractType::Service_agreement).await.unwrap();
+        let compiled = REGEX_COMPILATIONS.with(|n| n.get());
+
+        let mut clauses = Vec::new();
+        for _ in 0..5 {
+            clauses = service.analyze_clauses(&text, &ContractType::Service_agreement).await.unwrap();
+        }
+        assert_eq!(REGEX_COMPILATIONS.with(|n| n.get()), compiled, "patterns must not be recompiled per call");
+
+        let types: Vec<_> = clauses.iter().map(|c| c.clause_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                StandardClauseType::Termination,
+                StandardClauseType::Confidentiality,
+                StandardClauseType::Limitation_of_liability,
+                StandardClauseType::Governing_law,
+            ]
+        );
+
+        // Same spans as the original whole-body patterns
+        for clause in &clauses {
+            let legacy = CLAUSE_PATTERNS
+                .iter()
+                .filter(|p| p.clause_type == clause.clause_type)
+                .find_map(|p| {
+                    let pattern = format!(r"(?i)(?:{})[\s\S]{{0,{}}}{}", p.head, p.body_chars, p.ends_with.unwrap_or(""));
+                    Regex::new(&pattern).unwrap().find(&text)
+                })
+                .unwrap();
+            assert_eq!(clause.location.start_position, Some(legacy.start()));
+            assert_eq!(clause.location.end_position, Some(legacy.end()));
+        }
+    }
+
+    #[test]
+    fn test_resolve_deadline_forms() {
+        let effective = Some(date(2030, 3, 1)); // a Friday
+
+        assert_eq!(
+            resolve_deadline("within 5 business days of the Effective Date, Vendor shall", effective),
+            Some(date(2030, 3, 8))
+        );
+        assert_eq!(
+            resolve_deadline("no later than six (6) months after the Effective Date", effective),
+            Some(date(2030, 9, 1))
+        );
+        // A month and day already past in the effective year rolls to the next
+        assert_eq!(resolve_deadline("on or before February 15th", effective), Some(date(2031, 2, 15)));
+        assert_eq!(resolve_deadline("by 12/31/2030", None), Some(date(2030, 12, 31)));
+        assert_eq!(resolve_deadline("within 30 days of the Effective Date", None), None);
+    }
+}
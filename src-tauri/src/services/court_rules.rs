@@ -1,6 +1,8 @@
 // Court rules service for PA eDocket Desktop
 
+use crate::config::LocalRulesConfig;
 use crate::domain::*;
+use crate::services::service_error::ServiceError;
 use anyhow::{Context, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -415,3 +417,132 @@ impl CourtRulesService {
         }
     }
 }
+
+/// The rules that govern a filing in `court_id`, in `county` when given: the
+/// court's statewide formatting and local-rule defaults, with the county's
+/// local rules layered over them.
+pub fn resolve_court_rules(
+    courts: &crate::config::CourtsConfig,
+    court_id: &str,
+    county: Option<&str>,
+) -> Result<CourtRules> {
+    let court = courts
+        .courts
+        .get(court_id)
+        .ok_or_else(|| ServiceError::not_found("Court", court_id))?;
+    let county_rules = match county {
+        Some(county) => Some(
+            &courts
+                .counties
+                .get(county)
+                .ok_or_else(|| ServiceError::not_found("County", county))?
+                .local_rules,
+        ),
+        None => None,
+    };
+
+    // A county rule wins over the court's default; unset at both levels is off
+    let local_rule = |rule: fn(&LocalRulesConfig) -> Option<bool>| {
+        county_rules
+            .and_then(rule)
+            .or_else(|| rule(&court.local_rules))
+            .unwrap_or(false)
+    };
+
+    let formatting = &court.formatting;
+    Ok(CourtRules {
+        court_id: court_id.to_string(),
+        margins: CourtMargins {
+            top: formatting.margins.top.clone(),
+            bottom: formatting.margins.bottom.clone(),
+            left: formatting.margins.left.clone(),
+            right: formatting.margins.right.clone(),
+        },
+        font: CourtFont {
+            family: formatting.font.family.clone(),
+            size: formatting.font.size.clone(),
+            line_spacing: formatting.font.line_spacing.clone(),
+        },
+        caption: CourtCaption {
+            format: formatting.caption.format.clone(),
+            include_docket: formatting.caption.include_docket,
+            include_court: formatting.caption.include_court,
+            include_county: formatting.caption.include_county,
+            include_judge: formatting.caption.include_judge,
+            include_division: formatting.caption.include_division,
+        },
+        signature: CourtSignature {
+            attorney_name: formatting.signature.attorney_name,
+            attorney_id: formatting.signature.attorney_id,
+            firm_name: formatting.signature.firm_name,
+            address: formatting.signature.address,
+            phone: formatting.signature.phone,
+            email: formatting.signature.email,
+        },
+        service_certificate: formatting.service_certificate,
+        table_of_contents: None,
+        table_of_authorities: None,
+        page_limits: formatting.page_limits.clone(),
+        cover_sheet_required: local_rule(|rules| rules.cover_sheet_required),
+        electronic_service: local_rule(|rules| rules.electronic_service),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COURTS_YAML: &str = r#"
+courts:
+  cp:
+    name: "Court of Common Pleas"
+    level: "CP"
+    jurisdiction: "Pennsylvania"
+    formatting:
+      margins: { top: "1.0in", bottom: "1.0in", left: "1.5in", right: "1.0in" }
+      font: { family: "Times New Roman", size: "12pt", line_spacing: "double" }
+      caption:
+        format: "standard_pa"
+        include_docket: true
+        include_court: true
+        include_county: true
+        include_judge: false
+      signature:
+        attorney_name: true
+        attorney_id: true
+        firm_name: true
+        address: true
+        phone: true
+        email: true
+      service_certificate: true
+      page_limits: { brief: 25 }
+    efiling: { enabled: true, provider: "pacfile", endpoint: null }
+    local_rules: { cover_sheet_required: true, electronic_service: false }
+counties:
+  allegheny:
+    name: "Allegheny County"
+    cp_court_id: "02"
+    efiling: null
+    local_rules: { electronic_service: true }
+templates: {}
+"#;
+
+    #[test]
+    fn test_county_electronic_service_overrides_court_default() {
+        let courts: crate::config::CourtsConfig = serde_yaml::from_str(COURTS_YAML).unwrap();
+
+        let statewide = resolve_court_rules(&courts, "cp", None).unwrap();
+        assert!(!statewide.electronic_service);
+        assert!(statewide.cover_sheet_required);
+
+        let allegheny = resolve_court_rules(&courts, "cp", Some("allegheny")).unwrap();
+        assert!(allegheny.electronic_service);
+        // Not set by the county, so the court default stands
+        assert!(allegheny.cover_sheet_required);
+        assert_eq!(allegheny.margins.left, "1.5in");
+        assert_eq!(allegheny.page_limits.get("brief"), Some(&25));
+
+        let err = resolve_court_rules(&courts, "cp", Some("erie")).unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "not_found");
+    }
+}
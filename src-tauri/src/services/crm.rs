@@ -1,10 +1,18 @@
 // Client Intake & CRM Service - Feature #12
 // Lead tracking, intake forms, client database, pipeline management
 
-use anyhow::Result;
+use crate::config::NumberingScheme;
+use crate::domain::case_management::{ClientType, CreateClientRequest, CreateMatterRequest, MatterType};
+use crate::services::case_management::CaseManagementService;
+use crate::services::conflict_checking::{ConflictCheckingService, ConflictParty, ConflictStatus, PartyType};
+use anyhow::{Context, Result};
+use crate::utils::date::parse_date_flexible;
+use crate::utils::validation::{is_valid_email, normalize_phone};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,7 +24,30 @@ pub struct Lead {
     pub source: LeadSource,
     pub status: LeadStatus,
     pub practice_area: String,
+    #[serde(default)]
+    pub opposing_party: Option<String>,
     pub notes: String,
+
+    // Responsiveness signals
+    #[serde(default)]
+    pub contact_attempts: u32,
+    #[serde(default)]
+    pub responses: u32,
+    #[serde(default)]
+    pub first_response_hours: Option<f64>,
+    #[serde(default)]
+    pub score: f64,
+
+    // Set once the lead is converted
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub matter_id: Option<String>,
+    #[serde(default)]
+    pub conflict_check_id: Option<String>,
+    #[serde(default)]
+    pub converted_at: Option<DateTime<Utc>>,
+
     pub created_at: DateTime<Utc>,
 }
 
@@ -38,38 +69,810 @@ pub enum LeadStatus {
     Declined,
 }
 
+/// Intake form definition; fields may be shown conditionally on earlier answers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntakeForm {
     pub id: String,
+    pub name: String,
+    pub fields: Vec<IntakeField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeField {
+    pub key: String,
+    pub label: String,
+    pub field_type: IntakeFieldType,
+    pub required: bool,
+    /// Field is only shown (and only required) when this condition holds
+    #[serde(default)]
+    pub show_if: Option<ShowIf>,
+    /// Client or matter attribute this answer populates
+    #[serde(default)]
+    pub maps_to: Option<IntakeTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum IntakeFieldType {
+    Text,
+    Email,
+    Phone,
+    Date,
+    Number,
+    Boolean,
+    Select(Vec<String>),
+}
+
+/// Visible when the answer to `field` equals one of `equals` (case-insensitive)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShowIf {
+    pub field: String,
+    pub equals: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum IntakeTarget {
+    ClientFirstName,
+    ClientLastName,
+    ClientEmail,
+    ClientPhone,
+    ClientAddress,
+    ClientCity,
+    ClientState,
+    ClientZip,
+    MatterTitle,
+    MatterDescription,
+    MatterCaseType,
+    OpposingParty,
+    County,
+    IncidentDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum IntakeValue {
+    Text(String),
+    Date(DateTime<Utc>),
+    Number(f64),
+    Boolean(bool),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntakeClientData {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntakeMatterData {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub case_type: Option<String>,
+    pub opposing_party: Option<String>,
+    pub county: Option<String>,
+    pub incident_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeResult {
+    pub form_id: String,
+    /// Normalized answers for visible fields only
+    pub answers: HashMap<String, IntakeValue>,
+    pub hidden_fields: Vec<String>,
+    pub client: IntakeClientData,
+    pub matter: IntakeMatterData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLeadRequest {
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub source: Option<LeadSource>,
+    pub practice_area: Option<String>,
+    pub opposing_party: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadConversion {
     pub lead_id: String,
-    pub fields: std::collections::HashMap<String, String>,
-    pub completed_at: Option<DateTime<Utc>>,
+    pub client_id: String,
+    pub matter_id: String,
+    pub conflict_check_id: Option<String>,
+    /// True when the lead had already been converted and nothing new was created
+    pub already_converted: bool,
 }
 
+/// Practice areas with the highest intake value for the firm
+const HIGH_VALUE_PRACTICE_AREAS: [&str; 5] = [
+    "personal injury",
+    "medical malpractice",
+    "workers comp",
+    "employment",
+    "wrongful death",
+];
+
 pub struct CRMService {
     db: SqlitePool,
+    matter_numbering: NumberingScheme,
 }
 
 impl CRMService {
     pub fn new(db: SqlitePool) -> Self {
-        Self { db }
+        Self {
+            db,
+            matter_numbering: crate::config::NumberingConfig::default().matter,
+        }
+    }
+
+    /// Number matters opened by lead conversion with the firm's scheme
+    pub fn with_numbering(mut self, matter_numbering: NumberingScheme) -> Self {
+        self.matter_numbering = matter_numbering;
+        self
     }
 
-    pub async fn create_lead(&self, name: &str, email: &str) -> Result<Lead> {
-        Ok(Lead {
+    // ============= Leads =============
+
+    pub async fn create_lead(&self, request: CreateLeadRequest) -> Result<Lead> {
+        if request.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Lead name is required"));
+        }
+
+        let mut lead = Lead {
             id: Uuid::new_v4().to_string(),
-            name: name.to_string(),
-            email: email.to_string(),
+            name: request.name.trim().to_string(),
+            email: request.email.trim().to_string(),
+            phone: request.phone.unwrap_or_default(),
+            source: request.source.unwrap_or(LeadSource::Website),
+            status: LeadStatus::New,
+            practice_area: request.practice_area.unwrap_or_default(),
+            opposing_party: request.opposing_party,
+            notes: request.notes.unwrap_or_default(),
+            contact_attempts: 0,
+            responses: 0,
+            first_response_hours: None,
+            score: 0.0,
+            client_id: None,
+            matter_id: None,
+            conflict_check_id: None,
+            converted_at: None,
+            created_at: Utc::now(),
+        };
+        lead.score = score_lead(&lead);
+
+        self.save_lead(&lead).await?;
+        info!("Created lead {} with score {:.1}", lead.id, lead.score);
+        Ok(lead)
+    }
+
+    pub async fn get_lead(&self, lead_id: &str) -> Result<Lead> {
+        let row = sqlx::query("SELECT * FROM leads WHERE id = ?")
+            .bind(lead_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load lead")?
+            .ok_or_else(|| anyhow::anyhow!("Lead not found: {}", lead_id))?;
+
+        row_to_lead(&row)
+    }
+
+    /// Convert a lead into a client with an initial matter.
+    ///
+    /// Runs a conflict check first and refuses conversion when a waiver is
+    /// required. Converting an already-converted lead returns the original
+    /// client and matter without creating duplicates.
+    pub async fn convert_lead_to_client(&self, lead_id: &str, converted_by: &str) -> Result<LeadConversion> {
+        let lead = self.get_lead(lead_id).await?;
+
+        if let (Some(client_id), Some(matter_id)) = (&lead.client_id, &lead.matter_id) {
+            info!("Lead {} already converted to client {}", lead.id, client_id);
+            return Ok(LeadConversion {
+                lead_id: lead.id.clone(),
+                client_id: client_id.clone(),
+                matter_id: matter_id.clone(),
+                conflict_check_id: lead.conflict_check_id.clone(),
+                already_converted: true,
+            });
+        }
+
+        if lead.status == LeadStatus::Declined {
+            return Err(anyhow::anyhow!("Lead {} was declined and cannot be converted", lead.id));
+        }
+
+        // Conflict check on the prospective client and any known adverse party
+        let mut parties = vec![conflict_party(&lead.name, PartyType::Client)];
+        if let Some(opposing) = lead.opposing_party.as_deref().filter(|s| !s.trim().is_empty()) {
+            parties.push(conflict_party(opposing, PartyType::OpposingParty));
+        }
+        let check = ConflictCheckingService::new(self.db.clone())
+            .perform_conflict_check(parties, None, converted_by)
+            .await
+            .context("Conflict check failed")?;
+
+        match check.status {
+            ConflictStatus::WaiverRequired => {
+                return Err(anyhow::anyhow!(
+                    "Conflict check {} requires a waiver before lead {} can be converted",
+                    check.id,
+                    lead.id
+                ));
+            }
+            ConflictStatus::ConflictDetected => {
+                warn!("Conflict check {} flagged potential conflicts for lead {}", check.id, lead.id);
+            }
+            _ => {}
+        }
+
+        // Client, matter and the lead's link to them are written together, so
+        // a failed or lost conversion leaves no orphaned client or matter
+        let case_service = CaseManagementService::new(self.db.clone()).with_numbering(self.matter_numbering.clone());
+        let (first_name, last_name) = split_name(&lead.name);
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let client = case_service
+            .create_client_in(&mut *tx, CreateClientRequest {
+                first_name,
+                last_name,
+                email: Some(lead.email.clone()).filter(|e| !e.is_empty()),
+                phone: Some(lead.phone.clone()).filter(|p| !p.is_empty()),
+                address: None,
+                city: None,
+                state: None,
+                zip_code: None,
+                client_type: ClientType::Individual,
+                business_name: None,
+                notes: Some(lead.notes.clone()).filter(|n| !n.is_empty()),
+            })
+            .await?;
+
+        let matter = case_service
+            .create_matter_in(&mut *tx, CreateMatterRequest {
+                client_id: client.id.clone(),
+                title: matter_title(&lead),
+                description: Some(lead.notes.clone()).filter(|n| !n.is_empty()),
+                matter_type: matter_type_for_practice_area(&lead.practice_area),
+                case_type: Some(lead.practice_area.clone()).filter(|p| !p.is_empty()),
+                court_level: None,
+                court_name: None,
+                county: None,
+                opposing_party: lead.opposing_party.clone(),
+                contingency_fee_percentage: None,
+                responsible_attorney_id: None,
+                forum_state: None,
+            })
+            .await?
+            .matter;
+
+        // Only the first writer wins if two conversions race
+        let updated = sqlx::query(
+            r#"
+            UPDATE leads
+            SET status = ?, client_id = ?, matter_id = ?, conflict_check_id = ?, converted_at = ?
+            WHERE id = ? AND client_id IS NULL
+            "#,
+        )
+        .bind(serde_json::to_string(&LeadStatus::Retained)?)
+        .bind(&client.id)
+        .bind(&matter.id)
+        .bind(&check.id)
+        .bind(Utc::now())
+        .bind(&lead.id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to mark lead converted")?;
+
+        if updated.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Lead {} was converted concurrently", lead.id));
+        }
+        tx.commit().await?;
+
+        info!("Converted lead {} to client {} / matter {}", lead.id, client.id, matter.id);
+        Ok(LeadConversion {
+            lead_id: lead.id,
+            client_id: client.id,
+            matter_id: matter.id,
+            conflict_check_id: Some(check.id),
+            already_converted: false,
+        })
+    }
+
+    async fn save_lead(&self, lead: &Lead) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO leads (
+                id, name, email, phone, source, status, practice_area, opposing_party, notes,
+                contact_attempts, responses, first_response_hours, score,
+                client_id, matter_id, conflict_check_id, converted_at, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&lead.id)
+        .bind(&lead.name)
+        .bind(&lead.email)
+        .bind(&lead.phone)
+        .bind(serde_json::to_string(&lead.source)?)
+        .bind(serde_json::to_string(&lead.status)?)
+        .bind(&lead.practice_area)
+        .bind(&lead.opposing_party)
+        .bind(&lead.notes)
+        .bind(lead.contact_attempts as i64)
+        .bind(lead.responses as i64)
+        .bind(lead.first_response_hours)
+        .bind(lead.score)
+        .bind(&lead.client_id)
+        .bind(&lead.matter_id)
+        .bind(&lead.conflict_check_id)
+        .bind(lead.converted_at)
+        .bind(lead.created_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save lead")?;
+
+        Ok(())
+    }
+}
+
+/// Score a lead from 0 to 100 using its source, practice area, and how
+/// responsive the prospect has been to outreach.
+pub fn score_lead(lead: &Lead) -> f64 {
+    let source_score = match lead.source {
+        LeadSource::Referral => 35.0,
+        LeadSource::Walk_in => 25.0,
+        LeadSource::Website => 20.0,
+        LeadSource::SocialMedia => 12.0,
+        LeadSource::Advertisement => 10.0,
+    };
+
+    let practice_area = lead.practice_area.trim().to_lowercase();
+    let practice_score = if practice_area.is_empty() {
+        0.0
+    } else if HIGH_VALUE_PRACTICE_AREAS.iter().any(|area| practice_area.contains(area)) {
+        30.0
+    } else {
+        15.0
+    };
+
+    // Prospects we have not reached yet get a neutral responsiveness score
+    let responsiveness_score = if lead.contact_attempts == 0 {
+        15.0
+    } else {
+        let response_rate = (lead.responses as f64 / lead.contact_attempts as f64).min(1.0);
+        let speed = match lead.first_response_hours {
+            Some(hours) if hours <= 1.0 => 15.0,
+            Some(hours) if hours <= 24.0 => 10.0,
+            Some(hours) if hours <= 72.0 => 5.0,
+            _ => 0.0,
+        };
+        response_rate * 20.0 + speed
+    };
+
+    (source_score + practice_score + responsiveness_score).min(100.0)
+}
+
+// ============= Intake Forms =============
+
+/// Validate answers against an intake form, honoring conditional visibility.
+///
+/// Hidden fields are never required and their answers are dropped. Every
+/// problem is reported in a single error so the whole form can be corrected.
+pub fn validate_intake(form: &IntakeForm, answers: &HashMap<String, String>) -> Result<IntakeResult> {
+    let mut values = HashMap::new();
+    let mut hidden_fields = Vec::new();
+    let mut errors = Vec::new();
+    let mut client = IntakeClientData::default();
+    let mut matter = IntakeMatterData::default();
+
+    for field in &form.fields {
+        if !is_field_visible(form, field, answers) {
+            hidden_fields.push(field.key.clone());
+            continue;
+        }
+
+        let raw = answers.get(&field.key).map(|a| a.trim()).filter(|a| !a.is_empty());
+        let Some(raw) = raw else {
+            if field.required {
+                errors.push(format!("{} is required", field.label));
+            }
+            continue;
+        };
+
+        match normalize_answer(&field.field_type, raw) {
+            Ok(value) => {
+                if let Some(target) = field.maps_to {
+                    apply_intake_target(target, &value, &mut client, &mut matter);
+                }
+                values.insert(field.key.clone(), value);
+            }
+            Err(e) => errors.push(format!("{}: {}", field.label, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(anyhow::anyhow!("Intake validation failed: {}", errors.join("; ")));
+    }
+
+    Ok(IntakeResult {
+        form_id: form.id.clone(),
+        answers: values,
+        hidden_fields,
+        client,
+        matter,
+    })
+}
+
+/// A field is visible when its condition holds and the controlling field is itself visible
+fn is_field_visible(form: &IntakeForm, field: &IntakeField, answers: &HashMap<String, String>) -> bool {
+    let mut current = field;
+    // Bounded by the field count so a cyclic definition cannot loop forever
+    for _ in 0..=form.fields.len() {
+        let Some(condition) = &current.show_if else {
+            return true;
+        };
+
+        let answer = answers.get(&condition.field).map(|a| a.trim()).unwrap_or_default();
+        if !condition.equals.iter().any(|expected| expected.eq_ignore_ascii_case(answer)) {
+            return false;
+        }
+
+        match form.fields.iter().find(|f| f.key == condition.field) {
+            Some(parent) => current = parent,
+            None => return true,
+        }
+    }
+
+    false
+}
+
+fn normalize_answer(field_type: &IntakeFieldType, raw: &str) -> Result<IntakeValue, String> {
+    match field_type {
+        IntakeFieldType::Text => Ok(IntakeValue::Text(raw.to_string())),
+        IntakeFieldType::Email => {
+            if is_valid_email(raw) {
+                Ok(IntakeValue::Text(raw.to_lowercase()))
+            } else {
+                Err(format!("invalid email address '{}'", raw))
+            }
+        }
+        IntakeFieldType::Phone => normalize_phone(raw)
+            .map(IntakeValue::Text)
+            .ok_or_else(|| format!("invalid phone number '{}'", raw)),
+        IntakeFieldType::Date => parse_date_flexible(raw)
+            .map(IntakeValue::Date)
+            .map_err(|_| format!("invalid date '{}'", raw)),
+        IntakeFieldType::Number => raw
+            .replace([',', '$'], "")
+            .parse::<f64>()
+            .map(IntakeValue::Number)
+            .map_err(|_| format!("invalid number '{}'", raw)),
+        IntakeFieldType::Boolean => match raw.to_lowercase().as_str() {
+            "yes" | "y" | "true" => Ok(IntakeValue::Boolean(true)),
+            "no" | "n" | "false" => Ok(IntakeValue::Boolean(false)),
+            _ => Err(format!("expected yes or no, got '{}'", raw)),
+        },
+        IntakeFieldType::Select(options) => options
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(raw))
+            .map(|option| IntakeValue::Text(option.clone()))
+            .ok_or_else(|| format!("'{}' is not one of {}", raw, options.join(", "))),
+    }
+}
+
+fn apply_intake_target(
+    target: IntakeTarget,
+    value: &IntakeValue,
+    client: &mut IntakeClientData,
+    matter: &mut IntakeMatterData,
+) {
+    let text = match value {
+        IntakeValue::Text(text) => Some(text.clone()),
+        IntakeValue::Number(number) => Some(number.to_string()),
+        IntakeValue::Boolean(flag) => Some(if *flag { "Yes" } else { "No" }.to_string()),
+        IntakeValue::Date(date) => Some(date.format("%Y-%m-%d").to_string()),
+    };
+
+    match target {
+        IntakeTarget::ClientFirstName => client.first_name = text,
+        IntakeTarget::ClientLastName => client.last_name = text,
+        IntakeTarget::ClientEmail => client.email = text,
+        IntakeTarget::ClientPhone => client.phone = text,
+        IntakeTarget::ClientAddress => client.address = text,
+        IntakeTarget::ClientCity => client.city = text,
+        IntakeTarget::ClientState => client.state = text.map(|s| s.to_uppercase()),
+        IntakeTarget::ClientZip => client.zip_code = text,
+        IntakeTarget::MatterTitle => matter.title = text,
+        IntakeTarget::MatterDescription => matter.description = text,
+        IntakeTarget::MatterCaseType => matter.case_type = text,
+        IntakeTarget::OpposingParty => matter.opposing_party = text,
+        IntakeTarget::County => matter.county = text,
+        IntakeTarget::IncidentDate => {
+            if let IntakeValue::Date(date) = value {
+                matter.incident_date = Some(*date);
+            }
+        }
+    }
+}
+
+fn conflict_party(name: &str, party_type: PartyType) -> ConflictParty {
+    ConflictParty {
+        name: name.to_string(),
+        party_type,
+        aliases: vec![],
+        related_entities: vec![],
+        ssn_last4: None,
+        date_of_birth: None,
+        address: None,
+    }
+}
+
+fn split_name(name: &str) -> (String, String) {
+    match name.trim().rsplit_once(' ') {
+        Some((first, last)) => (first.trim().to_string(), last.to_string()),
+        None => (name.trim().to_string(), String::new()),
+    }
+}
+
+fn matter_title(lead: &Lead) -> String {
+    match (&lead.opposing_party, lead.practice_area.is_empty()) {
+        (Some(opposing), _) if !opposing.trim().is_empty() => format!("{} v. {}", lead.name, opposing),
+        (_, false) => format!("{} - {}", lead.name, lead.practice_area),
+        _ => format!("{} - Initial Consultation", lead.name),
+    }
+}
+
+fn matter_type_for_practice_area(practice_area: &str) -> MatterType {
+    let area = practice_area.to_lowercase();
+    if area.contains("injury") || area.contains("malpractice") || area.contains("wrongful death") {
+        MatterType::PersonalInjury
+    } else if area.contains("criminal") || area.contains("dui") {
+        MatterType::Criminal
+    } else if area.contains("family") || area.contains("divorce") || area.contains("custody") {
+        MatterType::Family
+    } else if area.contains("real estate") || area.contains("landlord") {
+        MatterType::RealEstate
+    } else if area.contains("estate") || area.contains("probate") {
+        MatterType::Estate
+    } else if area.contains("employment") || area.contains("workers comp") {
+        MatterType::Employment
+    } else if area.contains("immigration") {
+        MatterType::Immigration
+    } else if area.contains("bankruptcy") {
+        MatterType::Bankruptcy
+    } else if area.contains("business") || area.contains("corporate") {
+        MatterType::Business
+    } else if area.is_empty() {
+        MatterType::Other
+    } else {
+        MatterType::Civil
+    }
+}
+
+fn row_to_lead(row: &sqlx::sqlite::SqliteRow) -> Result<Lead> {
+    let source: String = row.try_get("source")?;
+    let status: String = row.try_get("status")?;
+    let contact_attempts: i64 = row.try_get("contact_attempts")?;
+    let responses: i64 = row.try_get("responses")?;
+
+    Ok(Lead {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        email: row.try_get("email")?,
+        phone: row.try_get("phone")?,
+        source: serde_json::from_str(&source)?,
+        status: serde_json::from_str(&status)?,
+        practice_area: row.try_get("practice_area")?,
+        opposing_party: row.try_get("opposing_party")?,
+        notes: row.try_get("notes")?,
+        contact_attempts: contact_attempts as u32,
+        responses: responses as u32,
+        first_response_hours: row.try_get("first_response_hours")?,
+        score: row.try_get("score")?,
+        client_id: row.try_get("client_id")?,
+        matter_id: row.try_get("matter_id")?,
+        conflict_check_id: row.try_get("conflict_check_id")?,
+        converted_at: row.try_get("converted_at")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    fn lead(source: LeadSource, practice_area: &str) -> Lead {
+        Lead {
+            id: Uuid::new_v4().to_string(),
+            name: "Jane Doe".to_string(),
+            email: "jane@example.com".to_string(),
             phone: String::new(),
-            source: LeadSource::Website,
+            source,
             status: LeadStatus::New,
-            practice_area: String::new(),
+            practice_area: practice_area.to_string(),
+            opposing_party: None,
             notes: String::new(),
+            contact_attempts: 0,
+            responses: 0,
+            first_response_hours: None,
+            score: 0.0,
+            client_id: None,
+            matter_id: None,
+            conflict_check_id: None,
+            converted_at: None,
             created_at: Utc::now(),
-        })
+        }
+    }
+
+    #[test]
+    fn test_lead_scoring_ordering() {
+        let referral_pi = lead(LeadSource::Referral, "Personal Injury");
+        let website_pi = lead(LeadSource::Website, "Personal Injury");
+        let website_other = lead(LeadSource::Website, "Zoning");
+        let ad_blank = lead(LeadSource::Advertisement, "");
+
+        let mut responsive = lead(LeadSource::Website, "Zoning");
+        responsive.contact_attempts = 2;
+        responsive.responses = 2;
+        responsive.first_response_hours = Some(0.5);
+
+        let mut unresponsive = lead(LeadSource::Website, "Zoning");
+        unresponsive.contact_attempts = 4;
+        unresponsive.responses = 0;
+
+        assert!(score_lead(&referral_pi) > score_lead(&website_pi));
+        assert!(score_lead(&website_pi) > score_lead(&website_other));
+        assert!(score_lead(&website_other) > score_lead(&ad_blank));
+        assert!(score_lead(&responsive) > score_lead(&website_other));
+        assert!(score_lead(&website_other) > score_lead(&unresponsive));
+        assert!(score_lead(&responsive) <= 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_conversion_is_idempotent() {
+        let db = test_database().await;
+        let service = CRMService::new(db);
+
+        let mut converted = lead(LeadSource::Referral, "Personal Injury");
+        converted.status = LeadStatus::Retained;
+        converted.client_id = Some("client-1".to_string());
+        converted.matter_id = Some("matter-1".to_string());
+        converted.converted_at = Some(Utc::now());
+        service.save_lead(&converted).await.unwrap();
+
+        let first = service.convert_lead_to_client(&converted.id, "tester").await.unwrap();
+        let second = service.convert_lead_to_client(&converted.id, "tester").await.unwrap();
+
+        assert!(first.already_converted);
+        assert_eq!(first.client_id, "client-1");
+        assert_eq!(first.matter_id, second.matter_id);
+        assert_eq!(first.client_id, second.client_id);
+    }
+
+    #[tokio::test]
+    async fn test_lost_conversion_leaves_no_client_or_matter() {
+        let db = test_database().await;
+        let service = CRMService::new(db.clone());
+
+        // Another conversion has claimed the lead but not yet linked a matter
+        let mut claimed = lead(LeadSource::Referral, "Personal Injury");
+        claimed.client_id = Some("client-1".to_string());
+        service.save_lead(&claimed).await.unwrap();
+
+        assert!(service.convert_lead_to_client(&claimed.id, "tester").await.is_err());
+        let clients: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clients").fetch_one(&db).await.unwrap();
+        let matters: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM matters").fetch_one(&db).await.unwrap();
+        assert_eq!((clients, matters), (0, 0));
+    }
+
+    #[test]
+    fn test_split_name_and_matter_type() {
+        assert_eq!(split_name("Mary Ann Smith"), ("Mary Ann".to_string(), "Smith".to_string()));
+        assert_eq!(split_name("Cher"), ("Cher".to_string(), String::new()));
+        assert!(matches!(matter_type_for_practice_area("Personal Injury"), MatterType::PersonalInjury));
+        assert!(matches!(matter_type_for_practice_area("Real Estate"), MatterType::RealEstate));
     }
 
-    pub async fn convert_to_client(&self, lead_id: &str) -> Result<String> {
-        Ok(Uuid::new_v4().to_string())
+    fn field(key: &str, field_type: IntakeFieldType, required: bool) -> IntakeField {
+        IntakeField {
+            key: key.to_string(),
+            label: key.replace('_', " "),
+            field_type,
+            required,
+            show_if: None,
+            maps_to: None,
+        }
+    }
+
+    fn pi_intake_form() -> IntakeForm {
+        let mut first_name = field("first_name", IntakeFieldType::Text, true);
+        first_name.maps_to = Some(IntakeTarget::ClientFirstName);
+        let mut case_type = field(
+            "case_type",
+            IntakeFieldType::Select(vec!["Personal Injury".to_string(), "Family".to_string()]),
+            true,
+        );
+        case_type.maps_to = Some(IntakeTarget::MatterCaseType);
+        let mut injuries = field("injuries", IntakeFieldType::Text, true);
+        injuries.show_if = Some(ShowIf {
+            field: "case_type".to_string(),
+            equals: vec!["Personal Injury".to_string()],
+        });
+        let mut incident_date = field("incident_date", IntakeFieldType::Date, true);
+        incident_date.show_if = injuries.show_if.clone();
+        incident_date.maps_to = Some(IntakeTarget::IncidentDate);
+
+        IntakeForm {
+            id: "pi-intake".to_string(),
+            name: "General Intake".to_string(),
+            fields: vec![first_name, case_type, injuries, incident_date],
+        }
+    }
+
+    fn answers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_hidden_field_not_required() {
+        let form = pi_intake_form();
+        let result = validate_intake(&form, &answers(&[
+            ("first_name", "Jane"),
+            ("case_type", "family"),
+            ("injuries", "should be dropped"),
+        ]))
+        .unwrap();
+
+        assert_eq!(result.hidden_fields, vec!["injuries".to_string(), "incident_date".to_string()]);
+        assert!(!result.answers.contains_key("injuries"));
+        assert_eq!(result.client.first_name.as_deref(), Some("Jane"));
+        assert_eq!(result.matter.case_type.as_deref(), Some("Family"));
+    }
+
+    #[test]
+    fn test_visible_conditional_field_is_required() {
+        let form = pi_intake_form();
+        let err = validate_intake(&form, &answers(&[
+            ("first_name", "Jane"),
+            ("case_type", "Personal Injury"),
+            ("incident_date", "2024-03-15"),
+        ]))
+        .unwrap_err();
+
+        assert!(err.to_string().contains("injuries is required"));
+    }
+
+    #[test]
+    fn test_malformed_date_errors() {
+        let form = pi_intake_form();
+        let err = validate_intake(&form, &answers(&[
+            ("first_name", "Jane"),
+            ("case_type", "Personal Injury"),
+            ("injuries", "Broken wrist"),
+            ("incident_date", "13/45/2024"),
+        ]))
+        .unwrap_err();
+
+        assert!(err.to_string().contains("invalid date"));
+    }
+
+    #[test]
+    fn test_valid_intake_normalizes_matter_data() {
+        let form = pi_intake_form();
+        let result = validate_intake(&form, &answers(&[
+            ("first_name", "Jane"),
+            ("case_type", "Personal Injury"),
+            ("injuries", "Broken wrist"),
+            ("incident_date", "03/15/2024"),
+        ]))
+        .unwrap();
+
+        assert!(result.hidden_fields.is_empty());
+        assert_eq!(
+            result.matter.incident_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            Some("2024-03-15".to_string())
+        );
     }
 }
@@ -0,0 +1,179 @@
+// Currency - ISO currency codes, amount formatting and conversion
+// Invoices, payments and contract payment terms carry their own currency;
+// roll-ups convert into a single reporting currency through a rate source
+
+use crate::config::ExchangeRateConfig;
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, ToSchema)]
+pub enum Currency {
+    #[default]
+    USD,
+    EUR,
+    GBP,
+    CAD,
+    AUD,
+    CHF,
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::CAD => "CAD",
+            Currency::AUD => "AUD",
+            Currency::CHF => "CHF",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+            Currency::CAD => "CA$",
+            Currency::AUD => "A$",
+            Currency::CHF => "CHF ",
+        }
+    }
+
+    /// The currency written before an amount: a symbol ("€") or a code ("EUR")
+    pub fn from_marker(marker: &str) -> Option<Currency> {
+        match marker.trim() {
+            "$" => Some(Currency::USD),
+            "€" => Some(Currency::EUR),
+            "£" => Some(Currency::GBP),
+            "CA$" | "C$" => Some(Currency::CAD),
+            "A$" => Some(Currency::AUD),
+            code => code.parse().ok(),
+        }
+    }
+
+    /// "€1,234.50"; negative amounts as "-€1,234.50"
+    pub fn format(&self, amount: f64) -> String {
+        let cents = (amount.abs() * 100.0).round() as u64;
+        let whole = (cents / 100).to_string();
+
+        let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+
+        let sign = if amount < 0.0 && cents > 0 { "-" } else { "" };
+        format!("{}{}{}.{:02}", sign, self.symbol(), grouped, cents % 100)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "USD" => Ok(Currency::USD),
+            "EUR" => Ok(Currency::EUR),
+            "GBP" => Ok(Currency::GBP),
+            "CAD" => Ok(Currency::CAD),
+            "AUD" => Ok(Currency::AUD),
+            "CHF" => Ok(Currency::CHF),
+            other => bail!("Unsupported currency '{}'", other),
+        }
+    }
+}
+
+/// Where exchange rates come from
+pub trait ExchangeRateSource: Send + Sync {
+    /// Units of `to` per unit of `from`
+    fn rate(&self, from: Currency, to: Currency) -> Result<f64>;
+}
+
+/// Rates quoted against the US dollar, as set in the firm's configuration
+#[derive(Debug, Clone, Default)]
+pub struct FixedRates {
+    units_per_usd: HashMap<Currency, f64>,
+}
+
+impl FixedRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rate(mut self, currency: Currency, units_per_usd: f64) -> Self {
+        self.units_per_usd.insert(currency, units_per_usd);
+        self
+    }
+
+    pub fn from_config(config: &ExchangeRateConfig) -> Result<Self> {
+        config
+            .units_per_usd
+            .iter()
+            .try_fold(Self::new(), |rates, (code, rate)| Ok(rates.with_rate(code.parse()?, *rate)))
+    }
+
+    fn units_per_usd(&self, currency: Currency) -> Result<f64> {
+        if currency == Currency::USD {
+            return Ok(1.0);
+        }
+        self.units_per_usd
+            .get(&currency)
+            .copied()
+            .filter(|rate| *rate > 0.0)
+            .ok_or_else(|| anyhow!("No exchange rate configured for {}", currency))
+    }
+}
+
+impl ExchangeRateSource for FixedRates {
+    fn rate(&self, from: Currency, to: Currency) -> Result<f64> {
+        if from == to {
+            return Ok(1.0);
+        }
+        Ok(self.units_per_usd(to)? / self.units_per_usd(from)?)
+    }
+}
+
+pub fn convert(amount: f64, from: Currency, to: Currency, rates: &dyn ExchangeRateSource) -> Result<f64> {
+    Ok(amount * rates.rate(from, to)?)
+}
+
+/// Sum amounts held in different currencies in the reporting currency
+pub fn roll_up(
+    amounts: impl IntoIterator<Item = (f64, Currency)>,
+    reporting: Currency,
+    rates: &dyn ExchangeRateSource,
+) -> Result<f64> {
+    amounts
+        .into_iter()
+        .try_fold(0.0, |total, (amount, currency)| Ok(total + convert(amount, currency, reporting, rates)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_roll_up() {
+        assert_eq!(Currency::EUR.format(1_234.5), "€1,234.50");
+        assert_eq!(Currency::USD.format(-1_000_000.0), "-$1,000,000.00");
+        assert_eq!(Currency::from_marker("£"), Some(Currency::GBP));
+
+        let rates = FixedRates::new().with_rate(Currency::EUR, 0.5);
+        let total = roll_up([(100.0, Currency::USD), (50.0, Currency::EUR)], Currency::USD, &rates).unwrap();
+        assert!((total - 200.0).abs() < 1e-9);
+        assert!(roll_up([(1.0, Currency::GBP)], Currency::USD, &rates).is_err());
+    }
+}
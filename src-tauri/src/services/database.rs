@@ -1,10 +1,13 @@
 // Database service for PA eDocket Desktop
 
 use crate::domain::*;
+use crate::services::audit_log::{AuditAction, AuditLogService};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::path::Path;
 use std::collections::HashMap;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
@@ -56,6 +59,55 @@ pub struct ExportRecord {
     pub created_at: DateTime<Utc>,
 }
 
+/// Optimistic-concurrency failure: the record was saved by someone else after
+/// it was loaded. Callers should reload and retry.
+#[derive(Debug, thiserror::Error)]
+#[error("{entity} {id} was modified concurrently (expected version {expected_version}); reload and retry")]
+pub struct Conflict {
+    pub entity: &'static str,
+    pub id: String,
+    pub expected_version: i64,
+}
+
+/// The application database file inside the configured data directory
+pub const DATABASE_FILE: &str = "edocket.db";
+
+/// Open the application database in `data_dir`, creating it on first run,
+/// and bring its schema up to date
+pub async fn open_database(data_dir: &Path) -> Result<SqlitePool> {
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("Failed to create data directory {}", data_dir.display()))?;
+
+    let options = SqliteConnectOptions::new()
+        .filename(data_dir.join(DATABASE_FILE))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .context("Failed to open SQLite database")?;
+
+    sqlx::migrate!("./migrations").run(&pool).await
+        .context("Failed to run database migrations")?;
+
+    info!("Opened database in {}", data_dir.display());
+    Ok(pool)
+}
+
+/// A fresh in-memory database carrying the full migrated schema, so tests
+/// exercise the same tables production does
+#[cfg(test)]
+pub async fn test_database() -> SqlitePool {
+    test_database_at("sqlite::memory:").await
+}
+
+/// Like [`test_database`], for tests that need a file database at `url`
+#[cfg(test)]
+pub async fn test_database_at(url: &str) -> SqlitePool {
+    let pool = SqlitePool::connect(url).await.unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
 pub struct DatabaseService {
     pool: Pool<Sqlite>,
 }
@@ -454,10 +506,13 @@ impl DatabaseService {
     }
 
     // Settings Methods
+    /// Store a setting and audit the change under `actor`, the signed-in
+    /// user's ID, or `SYSTEM_ACTOR` for changes the app makes itself
     #[instrument(skip(self, value))]
-    pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
-        debug!("Setting configuration: {} = {}", key, value);
+    pub async fn set_setting(&self, actor: &str, key: &str, value: &str) -> Result<()> {
+        debug!("Setting configuration: {}", key);
 
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
         sqlx::query!(
             r#"
             INSERT OR REPLACE INTO app_settings (key, value, updated_at)
@@ -466,10 +521,14 @@ impl DatabaseService {
             key,
             value
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to set setting")?;
 
+        // Values may hold credentials, so only the key is audited
+        AuditLogService::record_in(&mut *tx, actor, AuditAction::SettingChanged, key, None).await?;
+        tx.commit().await?;
+
         debug!("Setting updated: {}", key);
         Ok(())
     }
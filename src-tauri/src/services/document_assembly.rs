@@ -5,6 +5,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use chrono::{DateTime, Utc};
 use regex::Regex;
 use tracing::{info, warn, error};
@@ -126,18 +127,252 @@ pub struct ConditionalBlock {
     pub else_content: Option<String>,
 }
 
+/// A clause in the library. Contract review offers the most specific one
+/// for a clause type a contract is missing: `category` names the clause type
+/// ("Governing_law"), and `contract_type` and `jurisdiction` narrow where it
+/// applies.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClauseLibrary {
     pub id: String,
     pub name: String,
     pub category: String,
     pub text: String,
+    /// State name or postal code; applies everywhere when None
     pub jurisdiction: Option<String>,
     pub practice_area: Option<String>,
+    /// Contract type the clause is written for ("Non_disclosure"); applies
+    /// to every type when None
+    #[serde(default)]
+    pub contract_type: Option<String>,
     pub tags: Vec<String>,
     pub ai_relevance_score: f32,
 }
 
+/// Replaced with the governing jurisdiction, e.g. "the State of New York"
+const JURISDICTION_PLACEHOLDER: &str = "{jurisdiction}";
+
+impl ClauseLibrary {
+    fn standard(category: &str, text: &str) -> Self {
+        Self {
+            id: format!("standard-{}", category.to_lowercase()),
+            name: category.replace('_', " "),
+            category: category.to_string(),
+            text: text.to_string(),
+            jurisdiction: None,
+            practice_area: None,
+            contract_type: None,
+            tags: Vec::new(),
+            ai_relevance_score: 0.0,
+        }
+    }
+
+    fn for_contract(mut self, contract_type: &str) -> Self {
+        self.id = format!("{}-{}", self.id, contract_type.to_lowercase());
+        self.contract_type = Some(contract_type.to_string());
+        self
+    }
+
+    fn in_jurisdiction(mut self, jurisdiction: &str) -> Self {
+        self.id = format!("{}-{}", self.id, jurisdiction.to_lowercase());
+        self.jurisdiction = Some(jurisdiction.to_string());
+        self
+    }
+
+    /// How closely this clause fits; None if it does not apply at all
+    fn specificity(&self, category: &str, contract_type: &str, state: Option<&UsState>) -> Option<u8> {
+        if self.category != category {
+            return None;
+        }
+        let mut score = 0;
+        if let Some(ct) = &self.contract_type {
+            if ct != contract_type {
+                return None;
+            }
+            score += 1;
+        }
+        if let Some(jurisdiction) = &self.jurisdiction {
+            if find_state(jurisdiction).map(|s| s.code) != state.map(|s| s.code) {
+                return None;
+            }
+            score += 2;
+        }
+        Some(score)
+    }
+}
+
+/// Language for a clause of `category`, with the governing jurisdiction
+/// filled in. A matching clause from the firm's library is used before any
+/// standard one; within each, a jurisdiction-specific clause beats a
+/// contract-type one, which beats a general one.
+pub fn clause_text(
+    firm_clauses: &[ClauseLibrary],
+    category: &str,
+    contract_type: &str,
+    jurisdiction: Option<&str>,
+) -> Option<String> {
+    let state = jurisdiction.and_then(find_state);
+    let clause = firm_clauses
+        .iter()
+        .map(|c| (true, c))
+        .chain(standard_clauses().iter().map(|c| (false, c)))
+        .filter_map(|(is_firm, c)| {
+            let score = c.specificity(category, contract_type, state)?;
+            Some(((is_firm, score), c))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, c)| c)?;
+    let governing = match state {
+        Some(state) => state.formal_name(),
+        None => "the State of [STATE]".to_string(),
+    };
+    Some(clause.text.replace(JURISDICTION_PLACEHOLDER, &governing))
+}
+
+/// Built-in language for the clauses contract review expects
+fn standard_clauses() -> &'static [ClauseLibrary] {
+    static STANDARD_CLAUSES: OnceLock<Vec<ClauseLibrary>> = OnceLock::new();
+    STANDARD_CLAUSES.get_or_init(|| {
+        vec![
+            ClauseLibrary::standard(
+                "Termination",
+                "Either party may terminate this Agreement upon thirty (30) days written notice to the other party.",
+            ),
+            ClauseLibrary::standard(
+                "Governing_law",
+                "This Agreement shall be governed by and construed in accordance with the laws of {jurisdiction}, \
+                 without regard to its conflict of laws principles.",
+            ),
+            ClauseLibrary::standard(
+                "Governing_law",
+                "This Agreement shall be governed by and construed in accordance with the laws of the Commonwealth of \
+                 Pennsylvania, without regard to its conflict of laws principles. Each party irrevocably submits to the \
+                 exclusive jurisdiction of the Court of Common Pleas of [COUNTY] County, Pennsylvania, and the United \
+                 States District Court for the [Eastern/Middle/Western] District of Pennsylvania for any action arising \
+                 out of or relating to this Agreement.",
+            )
+            .in_jurisdiction("PA"),
+            ClauseLibrary::standard(
+                "Entire_agreement",
+                "This Agreement constitutes the entire agreement between the parties and supersedes all prior agreements and understandings.",
+            ),
+            ClauseLibrary::standard(
+                "Severability",
+                "If any provision of this Agreement is held to be invalid or unenforceable, the remaining provisions shall continue in full force and effect.",
+            ),
+            ClauseLibrary::standard(
+                "Scope_of_work",
+                "Provider shall perform the services described in Exhibit A (the \"Services\") in a professional and \
+                 workmanlike manner. Any change to the Services requires a written change order signed by both parties.",
+            ),
+            ClauseLibrary::standard(
+                "Payment_terms",
+                "Client shall pay each undisputed invoice within thirty (30) days of receipt. Amounts not paid when due \
+                 bear interest at the lesser of one percent (1%) per month or the maximum rate permitted by law.",
+            ),
+            ClauseLibrary::standard(
+                "Confidentiality",
+                "Each party shall hold the other party's Confidential Information in strict confidence, use it solely to \
+                 perform this Agreement, and not disclose it to any third party without prior written consent, except as \
+                 required by law.",
+            ),
+            ClauseLibrary::standard(
+                "Confidentiality",
+                "The Receiving Party shall hold the Disclosing Party's Confidential Information in strict confidence, \
+                 shall use it solely to evaluate the Purpose, and shall not disclose it to any third party other than its \
+                 representatives who are bound by obligations at least as protective as these. These obligations survive \
+                 for three (3) years after disclosure.",
+            )
+            .for_contract("Non_disclosure"),
+        ]
+    })
+}
+
+struct UsState {
+    code: &'static str,
+    name: &'static str,
+}
+
+impl UsState {
+    /// "the Commonwealth of Pennsylvania", "the State of New York"
+    fn formal_name(&self) -> String {
+        let kind = match self.code {
+            "KY" | "MA" | "PA" | "VA" => "Commonwealth",
+            _ => "State",
+        };
+        format!("the {} of {}", kind, self.name)
+    }
+}
+
+const US_STATES: &[UsState] = &[
+    UsState { code: "AL", name: "Alabama" },
+    UsState { code: "AK", name: "Alaska" },
+    UsState { code: "AZ", name: "Arizona" },
+    UsState { code: "AR", name: "Arkansas" },
+    UsState { code: "CA", name: "California" },
+    UsState { code: "CO", name: "Colorado" },
+    UsState { code: "CT", name: "Connecticut" },
+    UsState { code: "DE", name: "Delaware" },
+    UsState { code: "FL", name: "Florida" },
+    UsState { code: "GA", name: "Georgia" },
+    UsState { code: "HI", name: "Hawaii" },
+    UsState { code: "ID", name: "Idaho" },
+    UsState { code: "IL", name: "Illinois" },
+    UsState { code: "IN", name: "Indiana" },
+    UsState { code: "IA", name: "Iowa" },
+    UsState { code: "KS", name: "Kansas" },
+    UsState { code: "KY", name: "Kentucky" },
+    UsState { code: "LA", name: "Louisiana" },
+    UsState { code: "ME", name: "Maine" },
+    UsState { code: "MD", name: "Maryland" },
+    UsState { code: "MA", name: "Massachusetts" },
+    UsState { code: "MI", name: "Michigan" },
+    UsState { code: "MN", name: "Minnesota" },
+    UsState { code: "MS", name: "Mississippi" },
+    UsState { code: "MO", name: "Missouri" },
+    UsState { code: "MT", name: "Montana" },
+    UsState { code: "NE", name: "Nebraska" },
+    UsState { code: "NV", name: "Nevada" },
+    UsState { code: "NH", name: "New Hampshire" },
+    UsState { code: "NJ", name: "New Jersey" },
+    UsState { code: "NM", name: "New Mexico" },
+    UsState { code: "NY", name: "New York" },
+    UsState { code: "NC", name: "North Carolina" },
+    UsState { code: "ND", name: "North Dakota" },
+    UsState { code: "OH", name: "Ohio" },
+    UsState { code: "OK", name: "Oklahoma" },
+    UsState { code: "OR", name: "Oregon" },
+    UsState { code: "PA", name: "Pennsylvania" },
+    UsState { code: "RI", name: "Rhode Island" },
+    UsState { code: "SC", name: "South Carolina" },
+    UsState { code: "SD", name: "South Dakota" },
+    UsState { code: "TN", name: "Tennessee" },
+    UsState { code: "TX", name: "Texas" },
+    UsState { code: "UT", name: "Utah" },
+    UsState { code: "VT", name: "Vermont" },
+    UsState { code: "VA", name: "Virginia" },
+    UsState { code: "WA", name: "Washington" },
+    UsState { code: "WV", name: "West Virginia" },
+    UsState { code: "WI", name: "Wisconsin" },
+    UsState { code: "WY", name: "Wyoming" },
+];
+
+/// The state named in free text ("Commonwealth of Pennsylvania", "a Texas
+/// corporation") or given as a postal code
+fn find_state(jurisdiction: &str) -> Option<&'static UsState> {
+    let trimmed = jurisdiction.trim();
+    if let Some(state) = US_STATES.iter().find(|s| s.code.eq_ignore_ascii_case(trimmed)) {
+        return Some(state);
+    }
+
+    // Longest name first so "West Virginia" is not read as "Virginia"
+    let lower = format!(" {} ", trimmed.to_lowercase());
+    let mut states: Vec<&UsState> = US_STATES.iter().collect();
+    states.sort_by_key(|s| std::cmp::Reverse(s.name.len()));
+    states
+        .into_iter()
+        .find(|s| lower.contains(&format!(" {}", s.name.to_lowercase())))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssemblyRequest {
     pub template_id: String,
@@ -535,7 +770,7 @@ impl DocumentAssemblyService {
 
         let records = sqlx::query!(
             r#"
-            SELECT id, name, category, text, jurisdiction, practice_area, tags, ai_relevance_score
+            SELECT id, name, category, text, jurisdiction, practice_area, contract_type, tags, ai_relevance_score
             FROM clause_library
             WHERE template_category = ?
             ORDER BY ai_relevance_score DESC
@@ -553,6 +788,37 @@ impl DocumentAssemblyService {
             text: r.text,
             jurisdiction: r.jurisdiction,
             practice_area: r.practice_area,
+            contract_type: r.contract_type,
+            tags: serde_json::from_str(&r.tags).unwrap_or_default(),
+            ai_relevance_score: r.ai_relevance_score as f32,
+        }).collect();
+
+        Ok(clauses)
+    }
+
+    /// The firm's clauses that apply to `contract_type` ("Service_agreement"),
+    /// to offer before the standard language in `clause_text`
+    pub async fn firm_clauses(&self, contract_type: &str) -> Result<Vec<ClauseLibrary>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, name, category, text, jurisdiction, practice_area, contract_type, tags, ai_relevance_score
+            FROM clause_library
+            WHERE contract_type IS NULL OR contract_type = ?
+            "#,
+            contract_type
+        )
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load the firm's clause library")?;
+
+        let clauses = records.into_iter().map(|r| ClauseLibrary {
+            id: r.id,
+            name: r.name,
+            category: r.category,
+            text: r.text,
+            jurisdiction: r.jurisdiction,
+            practice_area: r.practice_area,
+            contract_type: r.contract_type,
             tags: serde_json::from_str(&r.tags).unwrap_or_default(),
             ai_relevance_score: r.ai_relevance_score as f32,
         }).collect();
@@ -743,3 +1009,22 @@ impl DocumentAssemblyService {
         Ok(templates)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jurisdiction_specific_clause_wins() {
+        let pa = clause_text(&[], "Governing_law", "Service_agreement", Some("PA")).unwrap();
+        assert!(pa.contains("Commonwealth of Pennsylvania"));
+        assert!(pa.contains("Court of Common Pleas"));
+
+        let ny = clause_text(&[], "Governing_law", "Service_agreement", Some("New York corporation")).unwrap();
+        assert!(ny.contains("the laws of the State of New York"));
+
+        let unknown = clause_text(&[], "Governing_law", "Lease", None).unwrap();
+        assert!(unknown.contains("[STATE]"));
+        assert_eq!(find_state("West Virginia").unwrap().code, "WV");
+    }
+}
@@ -0,0 +1,246 @@
+// Document Store - content-addressed blob storage shared by attachments and exports
+// Each distinct file is written once under its SHA-256 hash; owners (a matter,
+// docket or submission) hold references and the blob is removed with the last one
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::path::PathBuf;
+use tracing::info;
+
+/// A stored blob; the hash is the blob's identity
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlobHandle {
+    pub hash: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+pub struct DocumentStore {
+    db: SqlitePool,
+    root: PathBuf,
+}
+
+impl DocumentStore {
+    pub fn new(db: SqlitePool, root: PathBuf) -> Self {
+        Self { db, root }
+    }
+
+    /// Store `bytes` on behalf of `owner`. Identical content is written only
+    /// once; storing it again for another owner adds a reference, and storing
+    /// it again for the same owner is a no-op.
+    pub async fn put(&self, bytes: &[u8], owner: &str) -> Result<BlobHandle> {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let path = self.blob_path(&hash);
+
+        let mut tx = self.db.begin().await?;
+
+        let exists = sqlx::query("SELECT 1 FROM document_blobs WHERE hash = ?")
+            .bind(&hash)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+
+        if !exists {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await
+                    .context("Failed to create document store directory")?;
+            }
+            tokio::fs::write(&path, bytes).await
+                .context("Failed to write document blob")?;
+
+            sqlx::query(
+                "INSERT INTO document_blobs (hash, path, size, ref_count, created_at) VALUES (?, ?, ?, 0, ?)",
+            )
+            .bind(&hash)
+            .bind(path.to_string_lossy().to_string())
+            .bind(bytes.len() as i64)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let added = sqlx::query(
+            "INSERT OR IGNORE INTO document_refs (hash, owner, created_at) VALUES (?, ?, ?)",
+        )
+        .bind(&hash)
+        .bind(owner)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if added > 0 {
+            sqlx::query("UPDATE document_blobs SET ref_count = ref_count + 1 WHERE hash = ?")
+                .bind(&hash)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(BlobHandle {
+            hash,
+            path,
+            size: bytes.len() as u64,
+        })
+    }
+
+    /// Drop `owner`'s reference to a blob. Returns true when that was the last
+    /// reference and the blob was deleted.
+    pub async fn release(&self, hash: &str, owner: &str) -> Result<bool> {
+        let mut tx = self.db.begin().await?;
+
+        let removed = sqlx::query("DELETE FROM document_refs WHERE hash = ? AND owner = ?")
+            .bind(hash)
+            .bind(owner)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE document_blobs SET ref_count = ref_count - 1 WHERE hash = ?")
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+
+        let row = sqlx::query("SELECT path, ref_count FROM document_blobs WHERE hash = ?")
+            .bind(hash)
+            .fetch_one(&mut *tx)
+            .await?;
+        let ref_count: i64 = row.try_get("ref_count")?;
+
+        if ref_count > 0 {
+            tx.commit().await?;
+            return Ok(false);
+        }
+
+        let path: String = row.try_get("path")?;
+        sqlx::query("DELETE FROM document_blobs WHERE hash = ?")
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to delete document blob"),
+        }
+        info!("Deleted unreferenced document blob {}", hash);
+
+        Ok(true)
+    }
+
+    /// Drop every reference held by `owner`, e.g. when a matter is deleted
+    pub async fn release_owner(&self, owner: &str) -> Result<usize> {
+        let hashes: Vec<String> = sqlx::query("SELECT hash FROM document_refs WHERE owner = ?")
+            .bind(owner)
+            .fetch_all(&self.db)
+            .await?
+            .iter()
+            .map(|row| row.try_get("hash"))
+            .collect::<Result<_, _>>()?;
+
+        let mut deleted = 0;
+        for hash in hashes {
+            if self.release(&hash, owner).await? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    pub async fn get(&self, hash: &str) -> Result<Option<BlobHandle>> {
+        let row = sqlx::query("SELECT path, size FROM document_blobs WHERE hash = ?")
+            .bind(hash)
+            .fetch_optional(&self.db)
+            .await?;
+
+        row.map(|row| {
+            let path: String = row.try_get("path")?;
+            let size: i64 = row.try_get("size")?;
+            Ok(BlobHandle {
+                hash: hash.to_string(),
+                path: PathBuf::from(path),
+                size: size as u64,
+            })
+        })
+        .transpose()
+    }
+
+    pub async fn ref_count(&self, hash: &str) -> Result<i64> {
+        let count = sqlx::query("SELECT ref_count FROM document_blobs WHERE hash = ?")
+            .bind(hash)
+            .fetch_optional(&self.db)
+            .await?
+            .map(|row| row.try_get("ref_count"))
+            .transpose()?;
+        Ok(count.unwrap_or(0))
+    }
+
+    /// Blobs are fanned out by the first two hex digits to keep directories small
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn store(root: PathBuf) -> DocumentStore {
+        let db = test_database().await;
+        DocumentStore::new(db, root)
+    }
+
+    fn blob_files(root: &std::path::Path) -> usize {
+        std::fs::read_dir(root)
+            .map(|dirs| {
+                dirs.flatten()
+                    .map(|dir| std::fs::read_dir(dir.path()).map(|f| f.count()).unwrap_or(0))
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_same_bytes_stored_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path().to_path_buf()).await;
+
+        let first = store.put(b"%PDF-1.4 engagement letter", "matter:A").await.unwrap();
+        let second = store.put(b"%PDF-1.4 engagement letter", "matter:B").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(blob_files(dir.path()), 1);
+        assert_eq!(store.ref_count(&first.hash).await.unwrap(), 2);
+
+        // A repeat put by an existing owner does not add a reference
+        store.put(b"%PDF-1.4 engagement letter", "matter:A").await.unwrap();
+        assert_eq!(store.ref_count(&first.hash).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shared_blob_survives_until_last_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path().to_path_buf()).await;
+
+        let handle = store.put(b"exhibit A", "matter:A").await.unwrap();
+        store.put(b"exhibit A", "matter:B").await.unwrap();
+
+        assert!(!store.release(&handle.hash, "matter:A").await.unwrap());
+        assert!(handle.path.exists());
+        assert!(store.get(&handle.hash).await.unwrap().is_some());
+
+        assert!(store.release(&handle.hash, "matter:B").await.unwrap());
+        assert!(!handle.path.exists());
+        assert!(store.get(&handle.hash).await.unwrap().is_none());
+    }
+}
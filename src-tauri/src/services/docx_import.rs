@@ -0,0 +1,540 @@
+// DOCX import - read an attorney's existing Word file
+// Extracts paragraphs with their styles, heading levels and list numbering,
+// plus tables, so uploaded pleadings and contracts can be reformatted or analyzed
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, info};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParsedDocument {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Block {
+    Paragraph(ParsedParagraph),
+    Table(ParsedTable),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ParsedParagraph {
+    pub text: String,
+    /// Display name of the paragraph style, e.g. "Heading 1"
+    pub style: Option<String>,
+    /// 1 for a top-level heading; None for body text
+    pub heading_level: Option<u8>,
+    pub list: Option<ListItem>,
+    /// "left", "center", "right", "both" (justified) ...
+    pub alignment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListItem {
+    /// Nesting depth, 0 for the outermost list
+    pub level: u8,
+    /// false for bulleted lists
+    pub ordered: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ParsedTable {
+    /// Cell text by row; paragraphs within a cell are joined with newlines
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeadingEntry {
+    pub level: u8,
+    pub text: String,
+}
+
+impl ParsedDocument {
+    /// Body paragraphs, excluding those inside tables
+    pub fn paragraphs(&self) -> impl Iterator<Item = &ParsedParagraph> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Paragraph(p) => Some(p),
+            Block::Table(_) => None,
+        })
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &ParsedTable> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Table(t) => Some(t),
+            Block::Paragraph(_) => None,
+        })
+    }
+
+    pub fn outline(&self) -> Vec<HeadingEntry> {
+        self.paragraphs()
+            .filter_map(|p| {
+                p.heading_level.map(|level| HeadingEntry {
+                    level,
+                    text: p.text.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Plain text for the text-based formatter and contract analysis: one
+    /// paragraph per block separated by blank lines, table cells by " | "
+    pub fn to_text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(|block| match block {
+                Block::Paragraph(p) => match &p.list {
+                    Some(item) => format!(
+                        "{}{} {}",
+                        "  ".repeat(item.level as usize),
+                        if item.ordered { "#" } else { "-" },
+                        p.text
+                    ),
+                    None => p.text.clone(),
+                },
+                Block::Table(t) => t
+                    .rows
+                    .iter()
+                    .map(|row| row.join(" | "))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            })
+            .filter(|text| !text.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Parse a .docx file from disk
+pub fn parse_docx(path: &Path) -> Result<ParsedDocument> {
+    info!("Parsing DOCX: {}", path.display());
+
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).context("Not a Word document: file is not a zip archive")?;
+
+    let document = read_part(&mut archive, "word/document.xml")?
+        .ok_or_else(|| anyhow::anyhow!("Not a Word document: missing word/document.xml"))?;
+    let styles = match read_part(&mut archive, "word/styles.xml")? {
+        Some(xml) => parse_styles(&xml)?,
+        None => Styles::default(),
+    };
+    let numbering = match read_part(&mut archive, "word/numbering.xml")? {
+        Some(xml) => parse_numbering(&xml)?,
+        None => Numbering::default(),
+    };
+
+    let parsed = parse_document(&document, &styles, &numbering)?;
+    debug!(
+        "Parsed {} paragraph(s), {} table(s)",
+        parsed.paragraphs().count(),
+        parsed.tables().count()
+    );
+    Ok(parsed)
+}
+
+fn read_part(archive: &mut zip::ZipArchive<File>, name: &str) -> Result<Option<String>> {
+    let mut part = match archive.by_name(name) {
+        Ok(part) => part,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", name)),
+    };
+    let mut xml = String::new();
+    part.read_to_string(&mut xml)
+        .with_context(|| format!("Failed to read {}", name))?;
+    Ok(Some(xml))
+}
+
+/// Value of the attribute with local name `name` (ignoring the w: prefix)
+fn attr(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+// ============= Styles =============
+
+#[derive(Debug, Default)]
+struct StyleDef {
+    name: Option<String>,
+    based_on: Option<String>,
+    outline_level: Option<u8>,
+}
+
+#[derive(Debug, Default)]
+struct Styles {
+    by_id: HashMap<String, StyleDef>,
+}
+
+impl Styles {
+    fn name(&self, style_id: &str) -> Option<String> {
+        self.by_id.get(style_id).and_then(|s| s.name.clone())
+    }
+
+    /// Heading level from the style's outline level or a "heading N" name,
+    /// following basedOn links
+    fn heading_level(&self, style_id: &str) -> Option<u8> {
+        let mut current = Some(style_id);
+        for _ in 0..10 {
+            let style = self.by_id.get(current?)?;
+            if let Some(level) = style.outline_level {
+                return outline_to_heading(level);
+            }
+            if let Some(level) = style.name.as_deref().and_then(heading_from_name) {
+                return Some(level);
+            }
+            current = style.based_on.as_deref();
+        }
+        None
+    }
+}
+
+/// Outline levels are 0-based; 9 marks body text
+fn outline_to_heading(level: u8) -> Option<u8> {
+    (level < 9).then_some(level + 1)
+}
+
+fn heading_from_name(name: &str) -> Option<u8> {
+    let lower = name.trim().to_lowercase();
+    lower.strip_prefix("heading")?.trim().parse().ok()
+}
+
+fn parse_styles(xml: &str) -> Result<Styles> {
+    let mut reader = Reader::from_str(xml);
+    let mut styles = Styles::default();
+    let mut current: Option<(String, StyleDef)> = None;
+
+    loop {
+        match reader.read_event().context("Invalid word/styles.xml")? {
+            Event::Start(e) if e.local_name().as_ref() == b"style" => {
+                current = attr(&e, b"styleId").map(|id| (id, StyleDef::default()));
+            }
+            Event::Start(e) | Event::Empty(e) => {
+                if let Some((_, style)) = current.as_mut() {
+                    match e.local_name().as_ref() {
+                        b"name" => style.name = attr(&e, b"val"),
+                        b"basedOn" => style.based_on = attr(&e, b"val"),
+                        b"outlineLvl" => style.outline_level = attr(&e, b"val").and_then(|v| v.parse().ok()),
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"style" => {
+                if let Some((id, style)) = current.take() {
+                    styles.by_id.insert(id, style);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(styles)
+}
+
+// ============= Numbering =============
+
+#[derive(Debug, Default)]
+struct Numbering {
+    /// numId -> abstractNumId
+    nums: HashMap<String, String>,
+    /// (abstractNumId, ilvl) -> numFmt
+    formats: HashMap<(String, u8), String>,
+}
+
+impl Numbering {
+    fn is_ordered(&self, num_id: &str, level: u8) -> bool {
+        self.nums
+            .get(num_id)
+            .and_then(|abstract_id| self.formats.get(&(abstract_id.clone(), level)))
+            .is_some_and(|format| format != "bullet" && format != "none")
+    }
+}
+
+fn parse_numbering(xml: &str) -> Result<Numbering> {
+    let mut reader = Reader::from_str(xml);
+    let mut numbering = Numbering::default();
+    let mut abstract_id: Option<String> = None;
+    let mut level: Option<u8> = None;
+    let mut num_id: Option<String> = None;
+
+    loop {
+        match reader.read_event().context("Invalid word/numbering.xml")? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"abstractNum" => abstract_id = attr(&e, b"abstractNumId"),
+                b"lvl" => level = attr(&e, b"ilvl").and_then(|v| v.parse().ok()),
+                b"numFmt" => {
+                    if let (Some(id), Some(lvl), Some(format)) = (&abstract_id, level, attr(&e, b"val")) {
+                        numbering.formats.insert((id.clone(), lvl), format);
+                    }
+                }
+                b"num" => num_id = attr(&e, b"numId"),
+                b"abstractNumId" => {
+                    if let (Some(id), Some(target)) = (num_id.clone(), attr(&e, b"val")) {
+                        numbering.nums.insert(id, target);
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"abstractNum" => abstract_id = None,
+                b"lvl" => level = None,
+                b"num" => num_id = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(numbering)
+}
+
+// ============= Document body =============
+
+#[derive(Debug, Default)]
+struct ParagraphProps {
+    text: String,
+    style_id: Option<String>,
+    outline_level: Option<u8>,
+    list_level: Option<u8>,
+    num_id: Option<String>,
+    alignment: Option<String>,
+}
+
+impl ParagraphProps {
+    fn finish(self, styles: &Styles, numbering: &Numbering) -> ParsedParagraph {
+        let heading_level = match self.outline_level {
+            Some(level) => outline_to_heading(level),
+            None => self.style_id.as_deref().and_then(|id| styles.heading_level(id)),
+        };
+        // numId 0 explicitly removes numbering inherited from the style
+        let list = self.num_id.filter(|id| id != "0").map(|num_id| {
+            let level = self.list_level.unwrap_or(0);
+            ListItem {
+                level,
+                ordered: numbering.is_ordered(&num_id, level),
+            }
+        });
+
+        ParsedParagraph {
+            text: self.text,
+            style: self
+                .style_id
+                .as_deref()
+                .map(|id| styles.name(id).unwrap_or_else(|| id.to_string())),
+            heading_level,
+            list,
+            alignment: self.alignment,
+        }
+    }
+}
+
+fn parse_document(xml: &str, styles: &Styles, numbering: &Numbering) -> Result<ParsedDocument> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks = Vec::new();
+
+    let mut paragraph: Option<ParagraphProps> = None;
+    let mut in_paragraph_props = false;
+    let mut in_text = false;
+
+    // Nested tables are flattened into the text of the outer table's cell
+    let mut table_depth = 0usize;
+    let mut table = ParsedTable::default();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell: Vec<String> = Vec::new();
+
+    loop {
+        let event = reader.read_event().context("Invalid word/document.xml")?;
+        let is_empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                // A self-closing <w:p/> is an empty paragraph
+                b"p" if is_empty => close_paragraph(ParagraphProps::default(), table_depth, &mut cell, &mut blocks, styles, numbering),
+                b"p" => paragraph = Some(ParagraphProps::default()),
+                b"pPr" => in_paragraph_props = !is_empty,
+                b"t" => in_text = !is_empty,
+                b"tab" if !in_paragraph_props => push_text(&mut paragraph, "\t"),
+                b"br" | b"cr" => push_text(&mut paragraph, "\n"),
+                b"tbl" => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        table = ParsedTable::default();
+                    }
+                }
+                b"tr" if table_depth == 1 => row.clear(),
+                b"tc" if table_depth == 1 => cell.clear(),
+                name if in_paragraph_props => {
+                    if let Some(props) = paragraph.as_mut() {
+                        match name {
+                            b"pStyle" => props.style_id = attr(&e, b"val"),
+                            b"outlineLvl" => props.outline_level = attr(&e, b"val").and_then(|v| v.parse().ok()),
+                            b"ilvl" => props.list_level = attr(&e, b"val").and_then(|v| v.parse().ok()),
+                            b"numId" => props.num_id = attr(&e, b"val"),
+                            b"jc" => props.alignment = attr(&e, b"val"),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                let text = t.unescape().context("Invalid text in word/document.xml")?;
+                push_text(&mut paragraph, &text);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"pPr" => in_paragraph_props = false,
+                b"t" => in_text = false,
+                b"p" => {
+                    if let Some(props) = paragraph.take() {
+                        close_paragraph(props, table_depth, &mut cell, &mut blocks, styles, numbering);
+                    }
+                }
+                b"tc" if table_depth == 1 => row.push(cell.join("\n")),
+                b"tr" if table_depth == 1 => table.rows.push(std::mem::take(&mut row)),
+                b"tbl" => {
+                    if table_depth == 1 {
+                        blocks.push(Block::Table(std::mem::take(&mut table)));
+                    }
+                    table_depth = table_depth.saturating_sub(1);
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(ParsedDocument { blocks })
+}
+
+/// Paragraphs inside a table become part of the current cell's text
+fn close_paragraph(
+    props: ParagraphProps,
+    table_depth: usize,
+    cell: &mut Vec<String>,
+    blocks: &mut Vec<Block>,
+    styles: &Styles,
+    numbering: &Numbering,
+) {
+    let parsed = props.finish(styles, numbering);
+    if table_depth > 0 {
+        cell.push(parsed.text);
+    } else {
+        blocks.push(Block::Paragraph(parsed));
+    }
+}
+
+fn push_text(paragraph: &mut Option<ParagraphProps>, text: &str) {
+    if let Some(props) = paragraph.as_mut() {
+        props.text.push_str(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+    fn write_fixture(path: &Path) {
+        let document = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document {W}><w:body>
+  <w:p><w:pPr><w:pStyle w:val="Title"/><w:jc w:val="center"/></w:pPr><w:r><w:t>MOTION TO SUPPRESS</w:t></w:r></w:p>
+  <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>I. FACTS</w:t></w:r></w:p>
+  <w:p><w:r><w:t xml:space="preserve">On March 3, 2024, </w:t></w:r><w:r><w:t>officers &amp; agents arrived.</w:t></w:r></w:p>
+  <w:p><w:pPr><w:pStyle w:val="ListParagraph"/><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>No warrant was shown.</w:t></w:r></w:p>
+  <w:p><w:pPr><w:numPr><w:ilvl w:val="1"/><w:numId w:val="2"/></w:numPr></w:pPr><w:r><w:t>Bulleted detail</w:t></w:r></w:p>
+  <w:tbl>
+    <w:tr><w:tc><w:p><w:r><w:t>Exhibit</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Description</w:t></w:r></w:p></w:tc></w:tr>
+    <w:tr><w:tc><w:p><w:r><w:t>A</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Search</w:t></w:r></w:p><w:p><w:r><w:t>report</w:t></w:r></w:p></w:tc></w:tr>
+  </w:tbl>
+  <w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>A. Standard</w:t></w:r></w:p>
+  <w:p/>
+  <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>II. ARGUMENT</w:t></w:r></w:p>
+</w:body></w:document>"#
+        );
+        let styles = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles {W}>
+  <w:style w:type="paragraph" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:pPr><w:outlineLvl w:val="0"/></w:pPr></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/></w:style>
+  <w:style w:type="paragraph" w:styleId="ListParagraph"><w:name w:val="List Paragraph"/></w:style>
+</w:styles>"#
+        );
+        let numbering = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering {W}>
+  <w:abstractNum w:abstractNumId="10"><w:lvl w:ilvl="0"><w:numFmt w:val="decimal"/></w:lvl></w:abstractNum>
+  <w:abstractNum w:abstractNumId="20"><w:lvl w:ilvl="1"><w:numFmt w:val="bullet"/></w:lvl></w:abstractNum>
+  <w:num w:numId="1"><w:abstractNumId w:val="10"/></w:num>
+  <w:num w:numId="2"><w:abstractNumId w:val="20"/></w:num>
+</w:numbering>"#
+        );
+
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, xml) in [
+            ("word/document.xml", document),
+            ("word/styles.xml", styles),
+            ("word/numbering.xml", numbering),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_fixture_yields_paragraphs_and_outline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("motion.docx");
+        write_fixture(&path);
+
+        let parsed = parse_docx(&path).unwrap();
+
+        assert_eq!(parsed.paragraphs().count(), 8);
+        let outline = parsed.outline();
+        let outline: Vec<(u8, &str)> = outline.iter().map(|h| (h.level, h.text.as_str())).collect();
+        assert_eq!(outline, [(1, "I. FACTS"), (2, "A. Standard"), (1, "II. ARGUMENT")]);
+
+        let paragraphs: Vec<&ParsedParagraph> = parsed.paragraphs().collect();
+        assert_eq!(paragraphs[0].style.as_deref(), Some("Title"));
+        assert_eq!(paragraphs[0].alignment.as_deref(), Some("center"));
+        assert_eq!(paragraphs[2].text, "On March 3, 2024, officers & agents arrived.");
+        assert_eq!(paragraphs[3].list, Some(ListItem { level: 0, ordered: true }));
+        assert_eq!(paragraphs[4].list, Some(ListItem { level: 1, ordered: false }));
+    }
+
+    #[test]
+    fn test_tables_are_kept_separate_from_body_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("motion.docx");
+        write_fixture(&path);
+
+        let parsed = parse_docx(&path).unwrap();
+        let table = parsed.tables().next().unwrap();
+
+        assert_eq!(table.rows, [vec!["Exhibit", "Description"], vec!["A", "Search\nreport"]]);
+        assert!(parsed.to_text().contains("A | Search\nreport"));
+    }
+
+    #[test]
+    fn test_non_docx_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.docx");
+        std::fs::write(&path, "plain text").unwrap();
+
+        assert!(parse_docx(&path).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+// Draft job queue - persists drafting jobs and renders them in the background
+// Jobs move Pending -> Processing -> Completed/Failed; the result path or
+// error message is recorded on the job so the UI can poll its status
+
+use crate::domain::{DraftJob, JobStatus, OutputFormat};
+use crate::services::drafting::DraftingService;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+fn status_str(status: &JobStatus) -> &'static str {
+    match status {
+        JobStatus::Pending => "pending",
+        JobStatus::Processing => "processing",
+        JobStatus::Completed => "completed",
+        JobStatus::Failed => "failed",
+    }
+}
+
+fn parse_status(s: &str) -> JobStatus {
+    match s {
+        "processing" => JobStatus::Processing,
+        "completed" => JobStatus::Completed,
+        "failed" => JobStatus::Failed,
+        _ => JobStatus::Pending,
+    }
+}
+
+pub struct DraftJobRunner {
+    db: SqlitePool,
+    drafting: Arc<DraftingService>,
+}
+
+impl DraftJobRunner {
+    pub fn new(db: SqlitePool, drafting: Arc<DraftingService>) -> Self {
+        Self { db, drafting }
+    }
+
+    /// Runner using the templates and draft output under `data_dir` and the
+    /// courts in `config_dir`
+    pub async fn open(db: SqlitePool, data_dir: &Path, config_dir: &Path) -> Result<Self> {
+        let mut drafting = DraftingService::new(data_dir.join("templates"), data_dir.join("drafts"));
+        drafting.initialize(&config_dir.join("courts.yaml")).await?;
+        Ok(Self::new(db, Arc::new(drafting)))
+    }
+
+    /// Validate and persist a job as pending
+    pub async fn enqueue(&self, mut job: DraftJob) -> Result<DraftJob> {
+        job.validate().context("Invalid draft job")?;
+
+        let now = Utc::now();
+        let id = job.id.unwrap_or_else(Uuid::new_v4);
+        job.id = Some(id);
+        job.created_at = Some(job.created_at.unwrap_or(now));
+        job.status = Some(JobStatus::Pending);
+        job.result_path = None;
+        job.error_message = None;
+
+        sqlx::query(
+            r#"
+            INSERT INTO draft_jobs
+            (id, template_id, court_id, job_json, status, result_path, error_message, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, NULL, NULL, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&job.template_id)
+        .bind(&job.court_id)
+        .bind(serde_json::to_string(&job)?)
+        .bind(status_str(&JobStatus::Pending))
+        .bind(job.created_at)
+        .bind(now)
+        .execute(&self.db)
+        .await
+        .context("Failed to queue draft job")?;
+
+        info!("Queued draft job {} for template {}", id, job.template_id);
+        Ok(job)
+    }
+
+    pub async fn get(&self, job_id: Uuid) -> Result<Option<DraftJob>> {
+        find_job(&self.db, job_id).await
+    }
+
+    /// Render every pending job, oldest first. Returns the number processed.
+    pub async fn run_pending(&self) -> Result<usize> {
+        let ids: Vec<String> = sqlx::query(
+            "SELECT id FROM draft_jobs WHERE status = ? ORDER BY created_at",
+        )
+        .bind(status_str(&JobStatus::Pending))
+        .fetch_all(&self.db)
+        .await?
+        .iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
+
+        let mut processed = 0;
+        for id in ids {
+            let id = Uuid::parse_str(&id)?;
+            // Another runner may have claimed the job since it was listed
+            if !self.claim(id).await? {
+                continue;
+            }
+            let Some(job) = self.get(id).await? else {
+                continue;
+            };
+            self.process(job).await?;
+            processed += 1;
+        }
+
+        Ok(processed)
+    }
+
+    async fn claim(&self, job_id: Uuid) -> Result<bool> {
+        let claimed = sqlx::query(
+            "UPDATE draft_jobs SET status = ?, updated_at = ? WHERE id = ? AND status = ?",
+        )
+        .bind(status_str(&JobStatus::Processing))
+        .bind(Utc::now())
+        .bind(job_id.to_string())
+        .bind(status_str(&JobStatus::Pending))
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+
+        Ok(claimed == 1)
+    }
+
+    async fn process(&self, mut job: DraftJob) -> Result<DraftJob> {
+        match self.drafting.draft_document(&job).await {
+            Ok(result) if !result.validation_errors.is_empty() => {
+                job.status = Some(JobStatus::Failed);
+                job.error_message = Some(result.validation_errors.join("; "));
+            }
+            Ok(result) => {
+                job.status = Some(JobStatus::Completed);
+                job.result_path = match job.output {
+                    OutputFormat::Pdf => result.pdf_path,
+                    OutputFormat::Docx => result.docx_path,
+                    OutputFormat::Both => Some(result.manifest_path),
+                };
+            }
+            Err(e) => {
+                job.status = Some(JobStatus::Failed);
+                job.error_message = Some(format!("{:#}", e));
+            }
+        }
+
+        let status = job.status.clone().unwrap_or(JobStatus::Failed);
+        match &job.error_message {
+            Some(message) => warn!("Draft job {:?} failed: {}", job.id, message),
+            None => info!("Draft job {:?} completed", job.id),
+        }
+
+        sqlx::query(
+            "UPDATE draft_jobs SET status = ?, result_path = ?, error_message = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(status_str(&status))
+        .bind(&job.result_path)
+        .bind(&job.error_message)
+        .bind(Utc::now())
+        .bind(job.id.map(|id| id.to_string()))
+        .execute(&self.db)
+        .await
+        .context("Failed to record draft job result")?;
+
+        Ok(job)
+    }
+}
+
+/// A job and its current status, read without building a runner
+pub async fn find_job(db: &SqlitePool, job_id: Uuid) -> Result<Option<DraftJob>> {
+    let row = sqlx::query(
+        "SELECT job_json, status, result_path, error_message FROM draft_jobs WHERE id = ?",
+    )
+    .bind(job_id.to_string())
+    .fetch_optional(db)
+    .await
+    .context("Failed to load draft job")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let job_json: String = row.try_get("job_json")?;
+    let status: String = row.try_get("status")?;
+    let mut job: DraftJob = serde_json::from_str(&job_json)?;
+    job.status = Some(parse_status(&status));
+    job.result_path = row.try_get("result_path")?;
+    job.error_message = row.try_get("error_message")?;
+    Ok(Some(job))
+}
+
+/// Put jobs left processing by an exit mid-render back in the queue. Only
+/// safe at startup, before any runner has claimed a job.
+pub async fn requeue_interrupted(db: &SqlitePool) -> Result<u64> {
+    let requeued = sqlx::query("UPDATE draft_jobs SET status = ?, updated_at = ? WHERE status = ?")
+        .bind(status_str(&JobStatus::Pending))
+        .bind(Utc::now())
+        .bind(status_str(&JobStatus::Processing))
+        .execute(db)
+        .await
+        .context("Failed to requeue interrupted draft jobs")?
+        .rows_affected();
+
+    if requeued > 0 {
+        warn!("Requeued {} draft job(s) interrupted by the last exit", requeued);
+    }
+    Ok(requeued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use std::collections::HashMap;
+
+    async fn runner(dir: &std::path::Path) -> DraftJobRunner {
+        let db = test_database().await;
+
+        let courts = dir.join("courts.yaml");
+        std::fs::write(&courts, "courts: {}\n").unwrap();
+        let mut drafting = DraftingService::new(dir.join("templates"), dir.join("drafts"));
+        drafting.initialize(&courts).await.unwrap();
+
+        DraftJobRunner::new(db, Arc::new(drafting))
+    }
+
+    fn job(template_id: &str) -> DraftJob {
+        let variables: HashMap<String, serde_json::Value> = [
+            ("case_caption", "Commonwealth v. Smith"),
+            ("docket_number", "CP-51-CR-0001234-2024"),
+            ("motion_title", "Motion to Suppress"),
+            ("movant_name", "John Smith"),
+            ("attorney_name", "Jane Doe"),
+            ("attorney_id", "123456"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), serde_json::Value::from(v)))
+        .collect();
+
+        DraftJob {
+            id: None,
+            court_id: "cp-philadelphia".to_string(),
+            template_id: template_id.to_string(),
+            dockets: vec!["CP-51-CR-0001234-2024".to_string()],
+            variables,
+            output: OutputFormat::Docx,
+            title: None,
+            description: None,
+            created_at: None,
+            status: None,
+            result_path: None,
+            error_message: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_successful_job_reaches_completed() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = runner(dir.path()).await;
+
+        let queued = runner.enqueue(job("motion_basic")).await.unwrap();
+        assert_eq!(queued.status, Some(JobStatus::Pending));
+
+        assert_eq!(runner.run_pending().await.unwrap(), 1);
+
+        let done = runner.get(queued.id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(done.status, Some(JobStatus::Completed));
+        assert_eq!(done.error_message, None);
+        let result_path = done.result_path.expect("result path");
+        assert!(result_path.ends_with(".docx"));
+        assert!(std::path::Path::new(&result_path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_job_interrupted_mid_render_is_requeued() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = runner(dir.path()).await;
+
+        let id = runner.enqueue(job("motion_basic")).await.unwrap().id.unwrap();
+        // Claimed, then the app exited before the result was recorded
+        assert!(runner.claim(id).await.unwrap());
+        assert_eq!(runner.run_pending().await.unwrap(), 0);
+
+        assert_eq!(requeue_interrupted(&runner.db).await.unwrap(), 1);
+        let requeued = find_job(&runner.db, id).await.unwrap().unwrap();
+        assert_eq!(requeued.status, Some(JobStatus::Pending));
+
+        assert_eq!(runner.run_pending().await.unwrap(), 1);
+        let done = find_job(&runner.db, id).await.unwrap().unwrap();
+        assert_eq!(done.status, Some(JobStatus::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_template_error_reaches_failed_with_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = runner(dir.path()).await;
+
+        let queued = runner.enqueue(job("no_such_template")).await.unwrap();
+        runner.run_pending().await.unwrap();
+
+        let failed = runner.get(queued.id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(failed.status, Some(JobStatus::Failed));
+        assert_eq!(failed.result_path, None);
+        assert!(failed
+            .error_message
+            .expect("error message")
+            .contains("Template not found: no_such_template"));
+    }
+}
@@ -4,6 +4,7 @@ use crate::domain::*;
 use crate::services::court_rules::CourtRulesService;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use printpdf::{BuiltinFont, Mm, PdfDocument};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -51,59 +52,79 @@ impl DraftingService {
 
         // Get template
         let template = self.get_template(&job.template_id).await?;
+        let variables = string_variables(&job.variables);
 
-        // Get court rules if specified
-        let court_rules = if let Some(court_id) = &job.court_id {
-            Some(self.court_rules_service.get_court_rules(court_id).await?)
-        } else {
-            None
+        // Courts without configured rules are drafted with default formatting
+        let mut warnings = Vec::new();
+        let court_rules = match self.court_rules_service.get_court_rules(&job.court_id).await {
+            Ok(rules) => Some(rules),
+            Err(e) => {
+                warnings.push(format!("Using default formatting: {}", e));
+                None
+            }
         };
 
         // Validate required variables
-        let validation_errors = self.validate_variables(&template, &job.variables)?;
+        let validation_errors = self.validate_variables(&template, &variables)?;
         if !validation_errors.is_empty() {
             return Ok(DraftResult {
                 pdf_path: None,
                 docx_path: None,
                 manifest_path: String::new(),
                 validation_errors,
-                warnings: vec![],
+                warnings,
             });
         }
 
         // Process template with variables
-        let mut content = self.substitute_variables(&template.content, &job.variables)?;
+        let mut content = self.substitute_variables(&template.content, &variables)?;
 
         // Apply court-specific formatting if rules are available
-        let mut warnings = Vec::new();
         if let Some(rules) = &court_rules {
             content = self.court_rules_service.apply_formatting(rules, &content).await?;
 
             // Validate against court rules
             let rule_violations = self.court_rules_service
-                .validate_document_format(rules, &job.document_type, &content)
+                .validate_document_format(rules, &template.document_type, &content)
                 .await?;
             warnings.extend(rule_violations);
         }
 
-        // Generate output files
-        let job_id = Uuid::new_v4();
+        // Generate output files in the requested formats
+        let job_id = job.id.unwrap_or_else(Uuid::new_v4);
         let base_filename = format!("{}_{}", job.template_id, job_id);
 
-        // Generate DOCX
-        let docx_path = self.generate_docx(&content, &base_filename, court_rules.as_ref()).await?;
+        // DOCX and PDF are rendered from the same layout, never converted from each other
+        let layout = DraftLayout::new(&content, court_rules.as_ref());
 
-        // Generate PDF
-        let pdf_path = self.generate_pdf(&content, &base_filename, court_rules.as_ref()).await?;
+        let docx_path = match job.output {
+            OutputFormat::Docx | OutputFormat::Both => {
+                Some(self.generate_docx(&layout, &base_filename).await?)
+            }
+            OutputFormat::Pdf => None,
+        };
+
+        let pdf_path = match job.output {
+            OutputFormat::Pdf | OutputFormat::Both => {
+                Some(self.generate_pdf(&layout, &base_filename, &template.name).await?)
+            }
+            OutputFormat::Docx => None,
+        };
 
         // Generate manifest
-        let manifest_path = self.generate_manifest(&job, &template, &docx_path, &pdf_path, &warnings).await?;
+        let outputs: Vec<(&str, &str)> = [(docx_path.as_deref(), "docx"), (pdf_path.as_deref(), "pdf")]
+            .into_iter()
+            .filter_map(|(path, format)| path.map(|path| (path, format)))
+            .collect();
+        let manifest_path = self
+            .generate_manifest(job_id, job, &template, &variables, &outputs, &warnings)
+            .await?;
 
         info!("Document drafted successfully: {}", base_filename);
 
         Ok(DraftResult {
-            pdf_path: Some(pdf_path),
-            docx_path: Some(docx_path),
+            pdf_path,
+            docx_path,
             manifest_path,
             validation_errors: vec![],
             warnings,
@@ -300,8 +321,8 @@ impl DraftingService {
         result = var_regex.replace_all(&result, |caps: &regex::Captures| {
             let var_name = &caps[1];
             variables.get(var_name)
-                .map(|v| v.as_str())
-                .unwrap_or(&format!("{{{{MISSING: {}}}}}", var_name))
+                .cloned()
+                .unwrap_or_else(|| format!("{{{{MISSING: {}}}}}", var_name))
         }).to_string();
 
         // Replace conditional blocks {{#if variable}}...{{/if}}
@@ -310,10 +331,10 @@ impl DraftingService {
             let var_name = &caps[1];
             let content = &caps[2];
 
-            if variables.get(var_name).map(|v| !v.is_empty()).unwrap_or(false) {
-                content
+            if variables.get(var_name).is_some_and(|v| !v.is_empty()) {
+                content.to_string()
             } else {
-                ""
+                String::new()
             }
         }).to_string();
 
@@ -325,51 +346,54 @@ impl DraftingService {
         Ok(result)
     }
 
-    async fn generate_docx(&self, content: &str, base_filename: &str, court_rules: Option<&CourtRules>) -> Result<String> {
+    async fn generate_docx(&self, layout: &DraftLayout, base_filename: &str) -> Result<String> {
         let filename = format!("{}.docx", base_filename);
         let output_path = self.output_dir.join(&filename);
 
         // For now, save as RTF which can be opened by Word
-        let rtf_content = self.convert_to_rtf(content, court_rules)?;
+        let rtf_content = self.convert_to_rtf(layout)?;
         fs::write(&output_path, rtf_content)?;
 
         Ok(output_path.to_string_lossy().to_string())
     }
 
-    async fn generate_pdf(&self, content: &str, base_filename: &str, court_rules: Option<&CourtRules>) -> Result<String> {
+    async fn generate_pdf(&self, layout: &DraftLayout, base_filename: &str, title: &str) -> Result<String> {
         let filename = format!("{}.pdf", base_filename);
         let output_path = self.output_dir.join(&filename);
 
-        // For now, save as HTML which can be converted to PDF
-        let html_content = self.convert_to_html(content, court_rules)?;
-        fs::write(&output_path.with_extension("html"), html_content)?;
+        let pdf_bytes = self.render_pdf(layout, title)?;
+        fs::write(&output_path, pdf_bytes)?;
 
-        // In a real implementation, you would use a PDF generation library
-        // For now, just return the HTML path
-        Ok(output_path.with_extension("html").to_string_lossy().to_string())
+        Ok(output_path.to_string_lossy().to_string())
     }
 
-    async fn generate_manifest(&self, job: &DraftJob, template: &DocumentTemplate, docx_path: &str, pdf_path: &str, warnings: &[String]) -> Result<String> {
+    async fn generate_manifest(
+        &self,
+        job_id: Uuid,
+        job: &DraftJob,
+        template: &DocumentTemplate,
+        variables: &HashMap<String, String>,
+        outputs: &[(&str, &str)],
+        warnings: &[String],
+    ) -> Result<String> {
+        let output_files = outputs
+            .iter()
+            .map(|(path, format)| OutputFile {
+                path: path.to_string(),
+                format: format.to_string(),
+                size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            })
+            .collect();
+
         let manifest = DraftManifest {
-            job_id: Uuid::new_v4(),
+            job_id,
             template_id: template.id.clone(),
             template_name: template.name.clone(),
-            document_type: job.document_type.clone(),
-            court_id: job.court_id.clone(),
+            document_type: template.document_type.clone(),
+            court_id: Some(job.court_id.clone()),
             created_at: Utc::now(),
-            variables: job.variables.clone(),
-            output_files: vec![
-                OutputFile {
-                    path: docx_path.to_string(),
-                    format: "docx".to_string(),
-                    size: fs::metadata(docx_path).map(|m| m.len()).unwrap_or(0),
-                },
-                OutputFile {
-                    path: pdf_path.to_string(),
-                    format: "pdf".to_string(),
-                    size: fs::metadata(pdf_path).map(|m| m.len()).unwrap_or(0),
-                },
-            ],
+            variables: variables.clone(),
+            output_files,
             warnings: warnings.to_vec(),
         };
 
@@ -382,72 +406,159 @@ impl DraftingService {
         Ok(manifest_path.to_string_lossy().to_string())
     }
 
-    fn convert_to_rtf(&self, content: &str, court_rules: Option<&CourtRules>) -> Result<String> {
+    fn convert_to_rtf(&self, layout: &DraftLayout) -> Result<String> {
         let mut rtf = String::from(r"{\rtf1\ansi\deff0");
 
         // Add font table
-        if let Some(rules) = court_rules {
-            rtf.push_str(&format!(r"{{\fonttbl{{\f0 {};}}}}", rules.font.family));
-            rtf.push_str(&format!(r"\f0\fs{}", (rules.font.size * 2.0) as u32)); // RTF uses half-points
-        } else {
-            rtf.push_str(r"{\fonttbl{\f0 Times New Roman;}}");
-            rtf.push_str(r"\f0\fs24");
-        }
-
-        // Add margins if available
-        if let Some(rules) = court_rules {
-            rtf.push_str(&format!(r"\margl{}\margr{}\margt{}\margb{}",
-                (rules.margins.left * 20.0) as u32,   // RTF uses twips (1/20 point)
-                (rules.margins.right * 20.0) as u32,
-                (rules.margins.top * 20.0) as u32,
-                (rules.margins.bottom * 20.0) as u32
-            ));
-        }
+        rtf.push_str(&format!(r"{{\fonttbl{{\f0 {};}}}}", layout.font_family));
+        rtf.push_str(&format!(r"\f0\fs{}", (layout.font_size * 2.0) as u32)); // RTF uses half-points
+
+        // Margins and line spacing
+        let [top, right, bottom, left] = layout.margins;
+        rtf.push_str(&format!(r"\margl{}\margr{}\margt{}\margb{}",
+            (left * 20.0) as u32,   // RTF uses twips (1/20 point)
+            (right * 20.0) as u32,
+            (top * 20.0) as u32,
+            (bottom * 20.0) as u32
+        ));
+        rtf.push_str(&format!(r"\sl{}\slmult1 ", (240.0 * layout.line_spacing) as u32));
 
         // Convert content
-        let rtf_content = content
-            .replace('\n', r"\par ")
-            .replace('\t', r"\tab ");
-
-        rtf.push_str(&rtf_content);
+        for paragraph in &layout.paragraphs {
+            let escaped = paragraph
+                .replace('\\', r"\\")
+                .replace('{', r"\{")
+                .replace('}', r"\}")
+                .replace('\t', r"\tab ");
+            rtf.push_str(&escaped);
+            rtf.push_str(r"\par ");
+        }
         rtf.push('}');
 
         Ok(rtf)
     }
 
-    fn convert_to_html(&self, content: &str, court_rules: Option<&CourtRules>) -> Result<String> {
-        let mut html = String::from("<!DOCTYPE html><html><head><meta charset='utf-8'><style>");
-
-        // Add CSS styles based on court rules
-        if let Some(rules) = court_rules {
-            html.push_str(&format!(
-                "body {{ font-family: '{}'; font-size: {}pt; line-height: {}; margin: {}pt {}pt {}pt {}pt; }}",
-                rules.font.family,
-                rules.font.size,
-                rules.font.line_spacing,
-                rules.margins.top,
-                rules.margins.right,
-                rules.margins.bottom,
-                rules.margins.left
-            ));
-        } else {
-            html.push_str("body { font-family: 'Times New Roman'; font-size: 12pt; line-height: 2.0; margin: 72pt; }");
+    fn render_pdf(&self, layout: &DraftLayout, title: &str) -> Result<Vec<u8>> {
+        let page_width = Mm(215.9);
+        let page_height = Mm(279.4);
+        let [top, right, bottom, left] = layout.margins.map(pt_to_mm);
+        let line_height = pt_to_mm(layout.font_size * layout.line_spacing);
+        let max_chars = ((page_width.0 - left - right) / pt_to_mm(layout.font_size * AVERAGE_CHAR_WIDTH)) as usize;
+
+        let (doc, first_page, first_layer) = PdfDocument::new(title, page_width, page_height, "Body");
+        let font = doc
+            .add_builtin_font(builtin_font(&layout.font_family))
+            .context("Failed to load PDF font")?;
+
+        let mut layer = doc.get_page(first_page).get_layer(first_layer);
+        let mut y = page_height.0 - top;
+
+        for paragraph in &layout.paragraphs {
+            for line in wrap_line(&paragraph.replace('\t', "    "), max_chars.max(1)) {
+                if y < bottom {
+                    let (page, page_layer) = doc.add_page(page_width, page_height, "Body");
+                    layer = doc.get_page(page).get_layer(page_layer);
+                    y = page_height.0 - top;
+                }
+                if !line.is_empty() {
+                    layer.use_text(line, layout.font_size, Mm(left), Mm(y), &font);
+                }
+                y -= line_height;
+            }
         }
 
-        html.push_str("</style></head><body>");
+        doc.save_to_bytes().context("Failed to render draft PDF")
+    }
+}
 
-        // Convert content to HTML
-        let html_content = content
-            .replace('\n', "<br>")
-            .replace('\t', "&nbsp;&nbsp;&nbsp;&nbsp;");
+/// Approximate glyph width as a share of the font size, for line wrapping
+const AVERAGE_CHAR_WIDTH: f32 = 0.5;
+
+/// The drafted document after substitution and court formatting. Both output
+/// formats are rendered from this one model so the DOCX and PDF carry the
+/// same text, font and margins.
+#[derive(Debug, Clone)]
+struct DraftLayout {
+    paragraphs: Vec<String>,
+    font_family: String,
+    /// Points
+    font_size: f32,
+    line_spacing: f32,
+    /// Top, right, bottom, left in points
+    margins: [f32; 4],
+}
 
-        html.push_str(&html_content);
-        html.push_str("</body></html>");
+impl DraftLayout {
+    fn new(content: &str, court_rules: Option<&CourtRules>) -> Self {
+        let paragraphs = content.lines().map(str::to_string).collect();
+        match court_rules {
+            Some(rules) => Self {
+                paragraphs,
+                font_family: rules.font.family.clone(),
+                font_size: rules.font.size,
+                line_spacing: rules.font.line_spacing,
+                margins: [rules.margins.top, rules.margins.right, rules.margins.bottom, rules.margins.left],
+            },
+            None => Self {
+                paragraphs,
+                font_family: "Times New Roman".to_string(),
+                font_size: 12.0,
+                line_spacing: 2.0,
+                margins: [72.0; 4],
+            },
+        }
+    }
+}
+
+fn pt_to_mm(points: f32) -> f32 {
+    points * 25.4 / 72.0
+}
 
-        Ok(html)
+/// Closest PDF base-14 font to the court's font family
+fn builtin_font(family: &str) -> BuiltinFont {
+    let family = family.to_lowercase();
+    if family.contains("courier") {
+        BuiltinFont::Courier
+    } else if family.contains("arial") || family.contains("helvetica") {
+        BuiltinFont::Helvetica
+    } else {
+        BuiltinFont::TimesRoman
     }
 }
 
+/// Greedy word wrap; an empty paragraph yields one blank line
+pub(crate) fn wrap_line(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut words = text.split(' ');
+    let mut current = words.next().unwrap_or_default().to_string();
+    for word in words {
+        if current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        } else {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// Template variables as text; JSON strings are used verbatim, other values in
+/// their JSON form, and nulls are treated as missing
+fn string_variables(variables: &HashMap<String, serde_json::Value>) -> HashMap<String, String> {
+    variables
+        .iter()
+        .filter_map(|(name, value)| {
+            let text = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Some((name.clone(), text))
+        })
+        .collect()
+}
+
 // Data structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftResult {
@@ -522,3 +633,59 @@ struct OutputFile {
     pub format: String,
     pub size: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_both_output_yields_docx_and_pdf_with_same_base_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let courts = dir.path().join("courts.yaml");
+        fs::write(&courts, "courts: {}\n").unwrap();
+        let mut drafting = DraftingService::new(dir.path().join("templates"), dir.path().join("drafts"));
+        drafting.initialize(&courts).await.unwrap();
+
+        let variables = [
+            ("case_caption", "Commonwealth v. Smith"),
+            ("docket_number", "CP-51-CR-0001234-2024"),
+            ("motion_title", "Motion to Suppress"),
+            ("movant_name", "John Smith"),
+            ("attorney_name", "Jane Doe"),
+            ("attorney_id", "123456"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), serde_json::Value::from(v)))
+        .collect();
+
+        let job = DraftJob {
+            id: Some(Uuid::new_v4()),
+            court_id: "cp-philadelphia".to_string(),
+            template_id: "motion_basic".to_string(),
+            dockets: vec!["CP-51-CR-0001234-2024".to_string()],
+            variables,
+            output: OutputFormat::Both,
+            title: None,
+            description: None,
+            created_at: None,
+            status: None,
+            result_path: None,
+            error_message: None,
+        };
+
+        let result = drafting.draft_document(&job).await.unwrap();
+        assert!(result.validation_errors.is_empty());
+
+        let docx = PathBuf::from(result.docx_path.expect("docx path"));
+        let pdf = PathBuf::from(result.pdf_path.expect("pdf path"));
+        assert_eq!(docx.file_stem(), pdf.file_stem());
+        assert_eq!(docx.extension().unwrap(), "docx");
+        assert_eq!(pdf.extension().unwrap(), "pdf");
+        assert!(docx.exists());
+        assert!(fs::read(&pdf).unwrap().starts_with(b"%PDF"));
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&result.manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest["output_files"].as_array().map(Vec::len), Some(2));
+    }
+}
@@ -0,0 +1,841 @@
+// E-Filing retry queue - dead-letter handling for failed court submissions
+// Transient portal failures are retried with exponential backoff; exhausted or
+// non-retryable submissions are kept as dead letters for manual review
+
+use crate::config::CourtsConfig;
+use crate::domain::{EFilingCapability, EFilingSubmission, SubmissionStatus};
+use crate::providers::{EFilingProvider, ProviderError};
+use crate::services::audit_log::{AuditAction, AuditLogService};
+use crate::services::efiling_receipts::EFilingReceiptService;
+use crate::services::filing_fees::{calculate_filing_fee, FeeItem};
+use crate::services::webhooks::{WebhookDispatcher, WebhookEvent};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Total submission attempts, including the original, before a filing is dead-lettered
+pub const MAX_FILING_ATTEMPTS: i64 = 5;
+
+/// Delay before the first retry; doubles on each subsequent failure
+pub const INITIAL_RETRY_DELAY_SECONDS: i64 = 60;
+
+/// Upper bound on the delay between retries
+pub const MAX_RETRY_DELAY_SECONDS: i64 = 3600;
+
+/// How often the background loop looks for due retries
+pub const RETRY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum QueueStatus {
+    Retrying,
+    Submitted,
+    DeadLetter,
+}
+
+impl QueueStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QueueStatus::Retrying => "retrying",
+            QueueStatus::Submitted => "submitted",
+            QueueStatus::DeadLetter => "dead_letter",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "submitted" => QueueStatus::Submitted,
+            "dead_letter" => QueueStatus::DeadLetter,
+            _ => QueueStatus::Retrying,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedFiling {
+    pub submission: EFilingSubmission,
+    pub provider: String,
+    pub status: QueueStatus,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What a submission would send, checked against the court's e-filing
+/// capability, without anything being transmitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilingPreview {
+    pub submission_id: Uuid,
+    pub court_id: Option<String>,
+    /// Provider named by the court's capability
+    pub provider: Option<String>,
+    pub docket_id: Option<String>,
+    pub document_type: String,
+    pub files: Vec<PreviewFile>,
+    pub metadata: HashMap<String, serde_json::Value>,
+    /// None when neither the provider nor the court's fee schedule prices it
+    pub fee: Option<f64>,
+    /// Itemized fee when it came from the court's fee schedule
+    pub fee_items: Vec<FeeItem>,
+    /// Problems the court would reject the filing for
+    pub errors: Vec<String>,
+    /// Problems that do not block the filing, e.g. a fee that could not be priced
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl FilingPreview {
+    pub fn is_ready(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewFile {
+    pub path: String,
+    /// None when the file cannot be read
+    pub size: Option<u64>,
+    /// Lower-cased extension
+    pub format: String,
+}
+
+pub struct EFilingQueueService {
+    db: SqlitePool,
+    audit: AuditLogService,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    fee_schedule: Option<Arc<CourtsConfig>>,
+}
+
+impl EFilingQueueService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { audit: AuditLogService::new(db.clone()), db, webhooks: None, fee_schedule: None }
+    }
+
+    /// Send an efiling_status webhook whenever a submission changes status
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Price previews from the courts' fee schedules when the provider
+    /// does not calculate fees itself
+    pub fn with_fee_schedule(mut self, courts: Arc<CourtsConfig>) -> Self {
+        self.fee_schedule = Some(courts);
+        self
+    }
+
+    // ============= Submission =============
+
+    /// Submit a filing, queueing it for retry if the portal call fails
+    pub async fn submit(
+        &self,
+        provider_name: &str,
+        provider: &dyn EFilingProvider,
+        mut submission: EFilingSubmission,
+    ) -> Result<EFilingSubmission> {
+        let now = Utc::now();
+        match provider.submit_filing(&submission).await {
+            Ok(confirmation) => {
+                mark_submitted(&mut submission, confirmation, now);
+                // Kept so the court's later status and receipt can be polled
+                let queued = QueuedFiling {
+                    submission,
+                    provider: provider_name.to_string(),
+                    status: QueueStatus::Submitted,
+                    attempts: 1,
+                    max_attempts: MAX_FILING_ATTEMPTS,
+                    last_error: None,
+                    next_attempt_at: None,
+                    created_at: now,
+                    updated_at: now,
+                };
+                self.save(&queued).await?;
+                self.audit_submitted(provider_name, &queued.submission).await;
+                self.notify(&queued.submission);
+                Ok(queued.submission)
+            }
+            Err(e) => {
+                warn!("E-filing {} failed, queueing for retry: {}", submission.id, e);
+                let mut queued = QueuedFiling {
+                    submission,
+                    provider: provider_name.to_string(),
+                    status: QueueStatus::Retrying,
+                    attempts: 0,
+                    max_attempts: MAX_FILING_ATTEMPTS,
+                    last_error: None,
+                    next_attempt_at: None,
+                    created_at: now,
+                    updated_at: now,
+                };
+                record_failure(&mut queued, &e, now);
+                self.save(&queued).await?;
+                self.notify(&queued.submission);
+                Ok(queued.submission)
+            }
+        }
+    }
+
+    /// Assemble and validate a submission, and compute its fees, without
+    /// submitting or queueing it. The court comes from the submission's
+    /// `court_id` metadata.
+    pub async fn submit_filing_preview(
+        &self,
+        provider: &dyn EFilingProvider,
+        submission: &EFilingSubmission,
+    ) -> Result<FilingPreview> {
+        let court_id = submission.court_id().map(str::to_string);
+
+        let files: Vec<PreviewFile> = submission
+            .files
+            .iter()
+            .map(|path| PreviewFile {
+                path: path.clone(),
+                size: std::fs::metadata(path).ok().map(|m| m.len()),
+                format: Path::new(path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("")
+                    .to_lowercase(),
+            })
+            .collect();
+
+        let mut preview = FilingPreview {
+            submission_id: submission.id,
+            court_id: court_id.clone(),
+            provider: None,
+            docket_id: submission.docket_id.clone(),
+            document_type: submission.document_type.clone(),
+            files,
+            metadata: submission.metadata.clone(),
+            fee: None,
+            fee_items: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        if preview.files.is_empty() {
+            preview.errors.push("No documents to file".to_string());
+        }
+        for file in preview.files.iter().filter(|f| f.size.is_none()) {
+            preview.errors.push(format!("Cannot read {}", file.path));
+        }
+
+        let Some(court_id) = court_id else {
+            preview.errors.push("No court: set court_id metadata".to_string());
+            return Ok(preview);
+        };
+        let capability = provider
+            .get_capabilities(&court_id)
+            .await
+            .with_context(|| format!("Failed to load e-filing capabilities for {}", court_id))?
+            .into_iter()
+            .find(|c| c.court_id == court_id);
+        let Some(capability) = capability else {
+            preview.errors.push(format!("{} does not accept e-filing through this provider", court_id));
+            return Ok(preview);
+        };
+
+        preview.provider = Some(capability.provider.clone());
+        preview.errors.extend(capability_errors(&capability, &preview));
+
+        // An unpriced filing can still be filed, so fee problems are warnings
+        if capability.fee_calculation {
+            match provider.calculate_fees(submission).await {
+                Ok(fee) => preview.fee = fee,
+                Err(e) => preview.warnings.push(format!("Fee calculation failed: {}", e)),
+            }
+        }
+        if let (None, Some(courts)) = (preview.fee, &self.fee_schedule) {
+            // Without a page count only the base and service fees apply
+            let pages = submission
+                .metadata
+                .get("page_count")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as u32;
+            match calculate_filing_fee(courts, &court_id, &submission.document_type, pages) {
+                Ok(fee) => {
+                    preview.fee = Some(fee.total);
+                    preview.fee_items = fee.items;
+                }
+                Err(e) => preview.warnings.push(format!("Fee calculation failed: {}", e)),
+            }
+        }
+
+        info!(
+            "Previewed e-filing {}: {} file(s), {} problem(s), {} warning(s)",
+            submission.id,
+            preview.files.len(),
+            preview.errors.len(),
+            preview.warnings.len()
+        );
+        Ok(preview)
+    }
+
+    /// Retry every queued filing whose backoff has elapsed as of `now`
+    pub async fn process_due(
+        &self,
+        provider_name: &str,
+        provider: &dyn EFilingProvider,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<QueuedFiling>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM efiling_queue
+            WHERE status = ? AND provider = ? AND next_attempt_at <= ?
+            ORDER BY next_attempt_at
+            "#,
+        )
+        .bind(QueueStatus::Retrying.as_str())
+        .bind(provider_name)
+        .bind(now)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load due filings")?;
+
+        let mut processed = Vec::with_capacity(rows.len());
+        for row in rows {
+            let queued = row_to_queued(&row)?;
+            processed.push(self.attempt(queued, provider, now).await?);
+        }
+
+        Ok(processed)
+    }
+
+    /// Retry one filing immediately, including a dead-lettered one
+    pub async fn retry_now(&self, submission_id: &str, provider: &dyn EFilingProvider) -> Result<QueuedFiling> {
+        let mut queued = self.get(submission_id).await?
+            .ok_or_else(|| anyhow::anyhow!("No queued filing {}", submission_id))?;
+
+        if queued.status == QueueStatus::Submitted {
+            return Ok(queued);
+        }
+
+        // A manual retry grants one more attempt past the cap
+        if queued.status == QueueStatus::DeadLetter {
+            queued.max_attempts = queued.max_attempts.max(queued.attempts + 1);
+            queued.status = QueueStatus::Retrying;
+        }
+
+        self.attempt(queued, provider, Utc::now()).await
+    }
+
+    /// Filings still awaiting retry or dead-lettered, newest first
+    pub async fn list_failed(&self) -> Result<Vec<QueuedFiling>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM efiling_queue
+            WHERE status != ?
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .bind(QueueStatus::Submitted.as_str())
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to list failed filings")?;
+
+        rows.iter().map(row_to_queued).collect()
+    }
+
+    pub async fn get(&self, submission_id: &str) -> Result<Option<QueuedFiling>> {
+        let row = sqlx::query("SELECT * FROM efiling_queue WHERE id = ?")
+            .bind(submission_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load queued filing")?;
+
+        row.as_ref().map(row_to_queued).transpose()
+    }
+
+    /// Fetch a submitted filing's status from the court, storing the receipt
+    /// once it is accepted
+    pub async fn refresh_status(
+        &self,
+        submission_id: &str,
+        provider: &dyn EFilingProvider,
+        receipts: &EFilingReceiptService,
+    ) -> Result<EFilingSubmission> {
+        let mut queued = self.get(submission_id).await?
+            .ok_or_else(|| anyhow::anyhow!("No e-filing with ID {}", submission_id))?;
+        if queued.status != QueueStatus::Submitted {
+            anyhow::bail!("E-filing {} has not been accepted for submission yet", submission_id);
+        }
+
+        let previous = queued.submission.status.clone();
+        queued.submission = receipts.refresh_status(provider, queued.submission).await?;
+        queued.updated_at = Utc::now();
+        self.save(&queued).await?;
+
+        if queued.submission.status != previous {
+            self.notify(&queued.submission);
+        }
+        Ok(queued.submission)
+    }
+
+    /// Poll for due retries until `shutdown` is cancelled
+    pub async fn run_retry_loop(
+        self: Arc<Self>,
+        provider_name: String,
+        provider: Arc<dyn EFilingProvider>,
+        shutdown: CancellationToken,
+    ) {
+        info!("Starting e-filing retry loop for {}", provider_name);
+        let mut interval = tokio::time::interval(RETRY_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.process_due(&provider_name, provider.as_ref(), Utc::now()).await {
+                        error!("E-filing retry pass failed: {}", e);
+                    }
+                }
+            }
+        }
+
+        info!("E-filing retry loop for {} stopped", provider_name);
+    }
+
+    // ============= Helpers =============
+
+    async fn attempt(
+        &self,
+        mut queued: QueuedFiling,
+        provider: &dyn EFilingProvider,
+        now: DateTime<Utc>,
+    ) -> Result<QueuedFiling> {
+        match provider.submit_filing(&queued.submission).await {
+            Ok(confirmation) => {
+                info!("Queued e-filing {} submitted on attempt {}", queued.submission.id, queued.attempts + 1);
+                queued.attempts += 1;
+                queued.status = QueueStatus::Submitted;
+                queued.next_attempt_at = None;
+                queued.updated_at = now;
+                mark_submitted(&mut queued.submission, confirmation, now);
+            }
+            Err(e) => {
+                warn!("Retry of e-filing {} failed: {}", queued.submission.id, e);
+                record_failure(&mut queued, &e, now);
+            }
+        }
+
+        self.save(&queued).await?;
+        if queued.status == QueueStatus::Submitted {
+            self.audit_submitted(&queued.provider, &queued.submission).await;
+        }
+        self.notify(&queued.submission);
+        Ok(queued)
+    }
+
+    /// The filer is identified by their e-filing session. Runs after the
+    /// filing is saved: the court already has it, so a failed audit write is
+    /// logged rather than reported as a failed submission.
+    async fn audit_submitted(&self, provider_name: &str, submission: &EFilingSubmission) {
+        let details = serde_json::json!({
+            "provider": provider_name,
+            "docket_id": submission.docket_id,
+            "document_type": submission.document_type,
+            "confirmation": submission.submission_id,
+        });
+        let recorded = self
+            .audit
+            .record(
+                &format!("session:{}", submission.session_id),
+                AuditAction::EFilingSubmitted,
+                &submission.id.to_string(),
+                Some(details),
+            )
+            .await;
+        if let Err(e) = recorded {
+            error!("Failed to audit e-filing {}: {}", submission.id, e);
+        }
+    }
+
+    /// Deliver the status webhook in the background so a slow endpoint never
+    /// holds up the queue
+    fn notify(&self, submission: &EFilingSubmission) {
+        let Some(webhooks) = self.webhooks.clone() else {
+            return;
+        };
+        let event = WebhookEvent::efiling_status(submission);
+        tokio::spawn(async move {
+            if let Err(e) = webhooks.dispatch(&event).await {
+                warn!("Failed to dispatch e-filing status webhook: {}", e);
+            }
+        });
+    }
+
+    async fn save(&self, queued: &QueuedFiling) -> Result<()> {
+        let submission_json = serde_json::to_string(&queued.submission)
+            .context("Failed to serialize submission")?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO efiling_queue
+            (id, provider, submission, status, attempts, max_attempts, last_error,
+             next_attempt_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(queued.submission.id.to_string())
+        .bind(&queued.provider)
+        .bind(submission_json)
+        .bind(queued.status.as_str())
+        .bind(queued.attempts)
+        .bind(queued.max_attempts)
+        .bind(&queued.last_error)
+        .bind(queued.next_attempt_at)
+        .bind(queued.created_at)
+        .bind(queued.updated_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save queued filing")?;
+
+        Ok(())
+    }
+}
+
+/// Ways a previewed filing falls outside what the court accepts electronically
+fn capability_errors(capability: &EFilingCapability, preview: &FilingPreview) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !capability.enabled {
+        errors.push(format!("E-filing is not enabled for {}", capability.court_id));
+    }
+    if !capability.document_types.is_empty()
+        && !capability
+            .document_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&preview.document_type))
+    {
+        errors.push(format!("{} does not accept {} filings", capability.court_id, preview.document_type));
+    }
+    for file in &preview.files {
+        if !capability.allowed_formats.iter().any(|f| f.eq_ignore_ascii_case(&file.format)) {
+            errors.push(format!(
+                "{} is not an accepted format ({})",
+                file.path,
+                capability.allowed_formats.join(", ")
+            ));
+        }
+        if file.size.is_some_and(|size| size > capability.max_file_size) {
+            errors.push(format!(
+                "{} is larger than the {} byte limit",
+                file.path, capability.max_file_size
+            ));
+        }
+    }
+    if capability.requires_cover_sheet && !preview.metadata.get("cover_sheet").is_some_and(|v| !v.is_null()) {
+        errors.push("A cover sheet is required".to_string());
+    }
+    errors
+}
+
+fn mark_submitted(submission: &mut EFilingSubmission, confirmation: String, now: DateTime<Utc>) {
+    submission.status = SubmissionStatus::Submitted;
+    submission.submission_id = Some(confirmation);
+    submission.submitted_at = Some(now);
+    submission.error_message = None;
+}
+
+/// Count a failed attempt and either schedule the next retry or dead-letter the filing
+fn record_failure(queued: &mut QueuedFiling, error: &ProviderError, now: DateTime<Utc>) {
+    queued.attempts += 1;
+    queued.last_error = Some(error.to_string());
+    queued.submission.error_message = Some(error.to_string());
+    queued.updated_at = now;
+
+    if error.is_transient() && queued.attempts < queued.max_attempts {
+        queued.status = QueueStatus::Retrying;
+        queued.submission.status = SubmissionStatus::Error;
+        queued.next_attempt_at = Some(now + retry_delay(queued.attempts));
+    } else {
+        queued.status = QueueStatus::DeadLetter;
+        queued.submission.status = SubmissionStatus::Failed;
+        queued.next_attempt_at = None;
+    }
+}
+
+/// Exponential backoff after `attempts` failures
+pub fn retry_delay(attempts: i64) -> Duration {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    let seconds = INITIAL_RETRY_DELAY_SECONDS.saturating_mul(2i64.pow(exponent));
+    Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
+
+fn row_to_queued(row: &sqlx::sqlite::SqliteRow) -> Result<QueuedFiling> {
+    let submission_json: String = row.try_get("submission")?;
+    let status: String = row.try_get("status")?;
+
+    Ok(QueuedFiling {
+        submission: serde_json::from_str(&submission_json).context("Invalid queued submission")?,
+        provider: row.try_get("provider")?,
+        status: QueueStatus::parse(&status),
+        attempts: row.try_get("attempts")?,
+        max_attempts: row.try_get("max_attempts")?,
+        last_error: row.try_get("last_error")?,
+        next_attempt_at: row.try_get("next_attempt_at")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{EFilingCapability, EFilingSession, COURT_ID_METADATA};
+    use crate::services::database::test_database;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uuid::Uuid;
+
+    /// Fails the first `failures` submissions, then accepts
+    struct FlakyProvider {
+        failures: usize,
+        calls: AtomicUsize,
+        transient: bool,
+    }
+
+    #[async_trait]
+    impl EFilingProvider for FlakyProvider {
+        async fn get_capabilities(&self, _court_id: &str) -> Result<Vec<EFilingCapability>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn authenticate(&self, _credentials: HashMap<String, String>) -> Result<EFilingSession, ProviderError> {
+            Err(ProviderError::AuthenticationFailed("not used".to_string()))
+        }
+
+        async fn submit_filing(&self, _submission: &EFilingSubmission) -> Result<String, ProviderError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                if self.transient {
+                    Err(ProviderError::ServiceUnavailable("portal down".to_string()))
+                } else {
+                    Err(ProviderError::InvalidResponse("missing cover sheet".to_string()))
+                }
+            } else {
+                Ok("CONF-123".to_string())
+            }
+        }
+
+        async fn get_status(&self, submission_id: &str) -> Result<EFilingSubmission, ProviderError> {
+            let mut status = submission();
+            status.submission_id = Some(submission_id.to_string());
+            status.status = SubmissionStatus::Accepted;
+            status.processed_at = Some(Utc::now());
+            Ok(status)
+        }
+
+        async fn refresh_token(&self, session: &EFilingSession) -> Result<EFilingSession, ProviderError> {
+            Ok(session.clone())
+        }
+    }
+
+    async fn queue() -> EFilingQueueService {
+        let db = test_database().await;
+        EFilingQueueService::new(db)
+    }
+
+    fn submission() -> EFilingSubmission {
+        EFilingSubmission {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            docket_id: Some("CP-51-CR-0001234-2024".to_string()),
+            document_type: "motion".to_string(),
+            files: vec!["motion.pdf".to_string()],
+            metadata: HashMap::from([(COURT_ID_METADATA.to_string(), serde_json::json!("philadelphia"))]),
+            status: SubmissionStatus::Pending,
+            submission_id: None,
+            receipt_path: None,
+            error_message: None,
+            submitted_at: None,
+            processed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_twice_then_succeeding_ends_submitted() {
+        let queue = queue().await;
+        let provider = FlakyProvider { failures: 2, calls: AtomicUsize::new(0), transient: true };
+
+        let first = queue.submit("pacfile", &provider, submission()).await.unwrap();
+        assert_eq!(first.status, SubmissionStatus::Error);
+        let id = first.id.to_string();
+
+        // Not yet due
+        let processed = queue.process_due("pacfile", &provider, Utc::now()).await.unwrap();
+        assert!(processed.is_empty());
+
+        let later = Utc::now() + Duration::hours(2);
+        let second = queue.process_due("pacfile", &provider, later).await.unwrap();
+        assert_eq!(second[0].status, QueueStatus::Retrying);
+        assert_eq!(second[0].attempts, 2);
+
+        let third = queue.process_due("pacfile", &provider, later + Duration::hours(2)).await.unwrap();
+        assert_eq!(third[0].status, QueueStatus::Submitted);
+
+        let stored = queue.get(&id).await.unwrap().unwrap();
+        assert_eq!(stored.submission.status, SubmissionStatus::Submitted);
+        assert_eq!(stored.submission.submission_id.as_deref(), Some("CONF-123"));
+        assert_eq!(stored.attempts, 3);
+        assert!(queue.list_failed().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_failure_is_dead_lettered() {
+        let queue = queue().await;
+        let provider = FlakyProvider { failures: 1, calls: AtomicUsize::new(0), transient: false };
+
+        let result = queue.submit("pacfile", &provider, submission()).await.unwrap();
+        assert_eq!(result.status, SubmissionStatus::Failed);
+
+        let failed = queue.list_failed().await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].status, QueueStatus::DeadLetter);
+
+        // Manual retry still goes through
+        let retried = queue.retry_now(&result.id.to_string(), &provider).await.unwrap();
+        assert_eq!(retried.status, QueueStatus::Submitted);
+    }
+
+    /// Accepts PDFs only and charges a flat fee, unless pricing is down
+    struct FeeProvider {
+        submits: AtomicUsize,
+        pricing_down: bool,
+    }
+
+    #[async_trait]
+    impl EFilingProvider for FeeProvider {
+        async fn get_capabilities(&self, court_id: &str) -> Result<Vec<EFilingCapability>, ProviderError> {
+            Ok(vec![EFilingCapability {
+                court_id: court_id.to_string(),
+                enabled: true,
+                provider: "pacfile".to_string(),
+                document_types: vec!["motion".to_string()],
+                max_file_size: 1024 * 1024,
+                allowed_formats: vec!["pdf".to_string()],
+                requires_cover_sheet: false,
+                supports_electronic_service: true,
+                fee_calculation: true,
+            }])
+        }
+
+        async fn authenticate(&self, _credentials: HashMap<String, String>) -> Result<EFilingSession, ProviderError> {
+            Err(ProviderError::AuthenticationFailed("not used".to_string()))
+        }
+
+        async fn submit_filing(&self, _submission: &EFilingSubmission) -> Result<String, ProviderError> {
+            self.submits.fetch_add(1, Ordering::SeqCst);
+            Ok("CONF-123".to_string())
+        }
+
+        async fn get_status(&self, _submission_id: &str) -> Result<EFilingSubmission, ProviderError> {
+            Err(ProviderError::InvalidResponse("not used".to_string()))
+        }
+
+        async fn refresh_token(&self, session: &EFilingSession) -> Result<EFilingSession, ProviderError> {
+            Ok(session.clone())
+        }
+
+        async fn calculate_fees(&self, _submission: &EFilingSubmission) -> Result<Option<f64>, ProviderError> {
+            if self.pricing_down {
+                return Err(ProviderError::InvalidResponse("fee service unavailable".to_string()));
+            }
+            Ok(Some(57.25))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submitted_filing_status_is_refreshed_and_stored() {
+        let queue = queue().await;
+        let provider = FlakyProvider { failures: 0, calls: AtomicUsize::new(0), transient: true };
+        let dir = tempfile::tempdir().unwrap();
+        let receipts = EFilingReceiptService::new(queue.db.clone(), dir.path().join("receipts"));
+
+        let submitted = queue.submit("pacfile", &provider, submission()).await.unwrap();
+        assert_eq!(submitted.status, SubmissionStatus::Submitted);
+        let id = submitted.id.to_string();
+
+        let refreshed = queue.refresh_status(&id, &provider, &receipts).await.unwrap();
+        assert_eq!(refreshed.status, SubmissionStatus::Accepted);
+        assert!(refreshed.processed_at.is_some());
+
+        let stored = queue.get(&id).await.unwrap().unwrap();
+        assert_eq!(stored.status, QueueStatus::Submitted);
+        assert_eq!(stored.submission.status, SubmissionStatus::Accepted);
+        assert!(queue.list_failed().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_errors_and_fee_without_submitting() {
+        let queue = queue().await;
+        let provider = FeeProvider { submits: AtomicUsize::new(0), pricing_down: false };
+        let dir = tempfile::tempdir().unwrap();
+        let motion = dir.path().join("motion.pdf");
+        let exhibit = dir.path().join("exhibit.docx");
+        std::fs::write(&motion, b"%PDF-1.4").unwrap();
+        std::fs::write(&exhibit, b"PK").unwrap();
+
+        let mut filing = submission();
+        filing.files = vec![motion.display().to_string(), exhibit.display().to_string()];
+        let preview = queue.submit_filing_preview(&provider, &filing).await.unwrap();
+
+        assert!(!preview.is_ready());
+        assert_eq!(preview.errors.len(), 1);
+        assert!(preview.errors[0].contains("exhibit.docx is not an accepted format"));
+        assert_eq!(preview.fee, Some(57.25));
+        assert_eq!(preview.provider.as_deref(), Some("pacfile"));
+        assert_eq!(preview.files[0].size, Some(8));
+
+        assert_eq!(provider.submits.load(Ordering::SeqCst), 0);
+        assert!(queue.get(&filing.id.to_string()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unpriced_preview_warns_without_blocking() {
+        let queue = queue().await;
+        let provider = FeeProvider { submits: AtomicUsize::new(0), pricing_down: true };
+        let dir = tempfile::tempdir().unwrap();
+        let motion = dir.path().join("motion.pdf");
+        std::fs::write(&motion, b"%PDF-1.4").unwrap();
+
+        let mut filing = submission();
+        filing.files = vec![motion.display().to_string()];
+        let preview = queue.submit_filing_preview(&provider, &filing).await.unwrap();
+
+        assert!(preview.is_ready(), "{:?}", preview.errors);
+        assert_eq!(preview.fee, None);
+        assert!(preview.warnings[0].starts_with("Fee calculation failed"), "{:?}", preview.warnings);
+    }
+
+    #[tokio::test]
+    async fn test_preview_does_not_take_the_court_from_the_docket_number() {
+        let queue = queue().await;
+        let provider = FeeProvider { submits: AtomicUsize::new(0), pricing_down: false };
+
+        let mut filing = submission();
+        filing.metadata.remove(COURT_ID_METADATA);
+        let preview = queue.submit_filing_preview(&provider, &filing).await.unwrap();
+
+        assert_eq!(preview.court_id, None);
+        assert!(preview.errors.iter().any(|e| e.starts_with("No court")), "{:?}", preview.errors);
+        assert_eq!(preview.fee, None);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), Duration::seconds(60));
+        assert_eq!(retry_delay(2), Duration::seconds(120));
+        assert_eq!(retry_delay(3), Duration::seconds(240));
+        assert_eq!(retry_delay(20), Duration::seconds(MAX_RETRY_DELAY_SECONDS));
+    }
+}
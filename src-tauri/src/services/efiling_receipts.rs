@@ -0,0 +1,216 @@
+// E-Filing receipts - stores court acceptance receipts for accepted filings
+// Receipts are kept in the content-addressed document store and attached to the docket
+
+use crate::domain::{Attachment, EFilingSubmission, SubmissionStatus};
+use crate::providers::EFilingProvider;
+use crate::services::document_store::DocumentStore;
+use crate::utils::file_utils::sanitize_filename;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use std::path::PathBuf;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Attachment type recorded for court acceptance receipts
+pub const RECEIPT_ATTACHMENT_TYPE: &str = "efiling_receipt";
+
+pub struct EFilingReceiptService {
+    db: SqlitePool,
+    store: DocumentStore,
+}
+
+impl EFilingReceiptService {
+    pub fn new(db: SqlitePool, receipts_dir: PathBuf) -> Self {
+        let store = DocumentStore::new(db.clone(), receipts_dir);
+        Self { db, store }
+    }
+
+    /// Refresh a submission's status from the provider. Once accepted, the
+    /// receipt is downloaded, stored, and attached to the submission's docket.
+    pub async fn refresh_status(
+        &self,
+        provider: &dyn EFilingProvider,
+        mut submission: EFilingSubmission,
+    ) -> Result<EFilingSubmission> {
+        let provider_id = submission.submission_id.clone()
+            .ok_or_else(|| anyhow::anyhow!("Submission {} has not been submitted", submission.id))?;
+
+        let status = provider.get_status(&provider_id).await
+            .context("Failed to fetch filing status")?;
+
+        submission.status = status.status;
+        submission.processed_at = status.processed_at;
+        submission.error_message = status.error_message;
+
+        if submission.status == SubmissionStatus::Accepted && submission.receipt_path.is_none() {
+            match provider.download_receipt(&provider_id).await.context("Failed to download receipt")? {
+                Some(receipt) => self.store_receipt(&mut submission, &receipt).await?,
+                None => warn!("Accepted filing {} has no receipt available yet", submission.id),
+            }
+        }
+
+        Ok(submission)
+    }
+
+    /// Attachments recorded against a docket, oldest first
+    pub async fn list_attachments(&self, docket_id: &str) -> Result<Vec<Attachment>> {
+        let rows = sqlx::query(
+            "SELECT * FROM docket_attachments WHERE docket_id = ? ORDER BY upload_date",
+        )
+        .bind(docket_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load docket attachments")?;
+
+        rows.iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                let size: Option<i64> = row.try_get("size")?;
+                Ok(Attachment {
+                    id: Uuid::parse_str(&id).ok(),
+                    name: row.try_get("name")?,
+                    url: row.try_get("url")?,
+                    attachment_type: row.try_get("attachment_type")?,
+                    size: size.map(|s| s as u64),
+                    hash: row.try_get("hash")?,
+                    upload_date: row.try_get::<Option<DateTime<Utc>>, _>("upload_date")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn store_receipt(&self, submission: &mut EFilingSubmission, receipt: &[u8]) -> Result<()> {
+        let file_name = sanitize_filename(&format!("receipt_{}.pdf", submission.id));
+        let blob = self.store.put(receipt, &format!("efiling:{}", submission.id)).await
+            .context("Failed to store receipt")?;
+        let path = blob.path;
+
+        let url = url::Url::from_file_path(&path)
+            .map_err(|_| anyhow::anyhow!("Receipt path is not absolute: {:?}", path))?;
+
+        submission.receipt_path = Some(path.to_string_lossy().to_string());
+        info!("Stored receipt for filing {} at {:?}", submission.id, path);
+
+        let Some(docket_id) = submission.docket_id.clone() else {
+            warn!("Filing {} has no docket; receipt not attached", submission.id);
+            return Ok(());
+        };
+
+        let attachment = Attachment {
+            id: Some(Uuid::new_v4()),
+            name: file_name,
+            url: url.to_string(),
+            attachment_type: Some(RECEIPT_ATTACHMENT_TYPE.to_string()),
+            size: Some(receipt.len() as u64),
+            hash: Some(blob.hash),
+            upload_date: Some(Utc::now()),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO docket_attachments
+            (id, docket_id, submission_id, name, url, attachment_type, size, hash, upload_date)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(attachment.id.map(|id| id.to_string()))
+        .bind(&docket_id)
+        .bind(submission.id.to_string())
+        .bind(&attachment.name)
+        .bind(&attachment.url)
+        .bind(&attachment.attachment_type)
+        .bind(attachment.size.map(|s| s as i64))
+        .bind(&attachment.hash)
+        .bind(attachment.upload_date)
+        .execute(&self.db)
+        .await
+        .context("Failed to record receipt attachment")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{EFilingCapability, EFilingSession};
+    use crate::providers::ProviderError;
+    use crate::services::database::test_database;
+    use async_trait::async_trait;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+
+    const RECEIPT: &[u8] = b"%PDF-1.4 accepted";
+
+    struct AcceptingProvider;
+
+    #[async_trait]
+    impl EFilingProvider for AcceptingProvider {
+        async fn get_capabilities(&self, _court_id: &str) -> Result<Vec<EFilingCapability>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn authenticate(&self, _credentials: HashMap<String, String>) -> Result<EFilingSession, ProviderError> {
+            Err(ProviderError::AuthenticationFailed("not used".to_string()))
+        }
+
+        async fn submit_filing(&self, _submission: &EFilingSubmission) -> Result<String, ProviderError> {
+            Ok("CONF-1".to_string())
+        }
+
+        async fn get_status(&self, submission_id: &str) -> Result<EFilingSubmission, ProviderError> {
+            let mut status = submission(Some(submission_id));
+            status.status = SubmissionStatus::Accepted;
+            status.processed_at = Some(Utc::now());
+            Ok(status)
+        }
+
+        async fn refresh_token(&self, session: &EFilingSession) -> Result<EFilingSession, ProviderError> {
+            Ok(session.clone())
+        }
+
+        async fn download_receipt(&self, _submission_id: &str) -> Result<Option<Vec<u8>>, ProviderError> {
+            Ok(Some(RECEIPT.to_vec()))
+        }
+    }
+
+    fn submission(provider_id: Option<&str>) -> EFilingSubmission {
+        EFilingSubmission {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            docket_id: Some("CP-51-CV-0001234-2024".to_string()),
+            document_type: "complaint".to_string(),
+            files: vec!["complaint.pdf".to_string()],
+            metadata: HashMap::new(),
+            status: SubmissionStatus::Submitted,
+            submission_id: provider_id.map(str::to_string),
+            receipt_path: None,
+            error_message: None,
+            submitted_at: Some(Utc::now()),
+            processed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accepted_submission_stores_receipt_and_attachment() {
+        let db = test_database().await;
+        let dir = tempfile::tempdir().unwrap();
+        let service = EFilingReceiptService::new(db, dir.path().join("receipts"));
+
+        let updated = service
+            .refresh_status(&AcceptingProvider, submission(Some("PF-42")))
+            .await
+            .unwrap();
+
+        assert_eq!(updated.status, SubmissionStatus::Accepted);
+        let receipt_path = updated.receipt_path.expect("receipt path set");
+        assert_eq!(std::fs::read(&receipt_path).unwrap(), RECEIPT);
+
+        let attachments = service.list_attachments("CP-51-CV-0001234-2024").await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].attachment_type.as_deref(), Some(RECEIPT_ATTACHMENT_TYPE));
+        assert_eq!(attachments[0].size, Some(RECEIPT.len() as u64));
+        assert_eq!(attachments[0].hash, Some(format!("{:x}", Sha256::digest(RECEIPT))));
+    }
+}
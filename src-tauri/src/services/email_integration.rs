@@ -1,12 +1,36 @@
 // Email Integration Service - Gmail and Outlook integration with matter linking
 // Supports OAuth2 authentication, email syncing, and automatic case file organization
 
+use crate::domain::Attachment;
+use crate::services::attachment_scanner::{scan_attachment, AttachmentPolicy};
+use crate::services::document_store::DocumentStore;
+use crate::services::drafting::wrap_line;
+use crate::services::global_search::{GlobalSearchService, SearchScope};
+use crate::services::secret_store::{keychain, SharedSecretStore};
+use crate::services::service_error::ServiceError;
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::utils::file_utils::sanitize_filename;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use printpdf::{
+    ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject, IndirectFontRef, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Px,
+};
+use scraper::{ElementRef, Html, Node};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// How far back a newly connected account's first sync reaches
+pub const DEFAULT_SYNC_WINDOW_DAYS: i64 = 30;
+
+/// Attachment type recorded for PDF archives of linked emails
+pub const EMAIL_ARCHIVE_ATTACHMENT_TYPE: &str = "email_archive";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EmailProvider {
@@ -43,6 +67,10 @@ pub struct EmailAccount {
     pub sync_enabled: bool,
     pub last_sync_at: Option<DateTime<Utc>>,
     pub sync_from_date: Option<DateTime<Utc>>,
+    /// Provider cursor for incremental sync: a Gmail historyId or an Outlook
+    /// delta link. None until the first full sync completes.
+    #[serde(default)]
+    pub sync_token: Option<String>,
 
     // Settings
     pub auto_file_emails: bool,
@@ -101,7 +129,7 @@ pub struct EmailAddress {
     pub address: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EmailAttachment {
     pub id: String,
     pub filename: String,
@@ -159,6 +187,52 @@ pub struct EmailTemplate {
     pub usage_count: u32,
 }
 
+impl EmailTemplate {
+    /// Substitute `{{variable}}` placeholders, returning the rendered (subject, body)
+    pub fn render(&self, variables: &HashMap<String, String>) -> (String, String) {
+        let mut subject = self.subject.clone();
+        let mut body = self.body_html.clone();
+        for (key, value) in variables {
+            let placeholder = format!("{{{{{}}}}}", key);
+            subject = subject.replace(&placeholder, value);
+            body = body.replace(&placeholder, value);
+        }
+        (subject, body)
+    }
+}
+
+/// Variables a template declares that were not provided, so rendering it
+/// would leave their `{{placeholders}}` in the email
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("Template {template_id} is missing variables: {}", .missing.join(", "))]
+pub struct MissingTemplateVariables {
+    pub template_id: String,
+    pub missing: Vec<String>,
+}
+
+/// Check `provided` against the variables `template` declares. Any missing
+/// variable fails with `MissingTemplateVariables`; provided variables the
+/// template does not declare are only warned about.
+pub fn validate_template_variables(template: &EmailTemplate, provided: &HashMap<String, String>) -> Result<()> {
+    let mut extra: Vec<&str> = provided.keys()
+        .filter(|name| !template.variables.contains(name))
+        .map(String::as_str)
+        .collect();
+    if !extra.is_empty() {
+        extra.sort();
+        warn!("Template {} does not use variables: {}", template.id, extra.join(", "));
+    }
+
+    let missing: Vec<String> = template.variables.iter()
+        .filter(|name| !provided.contains_key(*name))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(MissingTemplateVariables { template_id: template.id.clone(), missing }.into());
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EmailTemplateCategory {
     ClientCommunication,
@@ -219,13 +293,118 @@ pub struct EmailSearchQuery {
     pub offset: Option<u32>,
 }
 
+/// One batch of messages from a mailbox and the token to continue from
+#[derive(Debug, Clone, Default)]
+pub struct MailboxChanges {
+    pub messages: Vec<Email>,
+    pub sync_token: Option<String>,
+}
+
+/// The provider no longer honours an account's sync token (Gmail answers a
+/// stale historyId with 404, Outlook an expired delta link with 410)
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("Sync token for email account {account_id} has expired")]
+pub struct SyncTokenExpired {
+    pub account_id: String,
+}
+
+/// A new access token; providers that rotate refresh tokens send one too
+#[derive(Debug, Clone)]
+pub struct RefreshedToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: i64,
+}
+
+/// Talks to a connected Gmail or Outlook mailbox
+#[async_trait]
+pub trait MailboxClient: Send + Sync {
+    /// Every message received since `since`, with a token to sync on from
+    async fn fetch_since(&self, account: &EmailAccount, since: DateTime<Utc>) -> Result<MailboxChanges>;
+
+    /// Messages added since `sync_token` was issued. Fails with
+    /// `SyncTokenExpired` once the provider has dropped the token.
+    async fn fetch_changes(&self, account: &EmailAccount, sync_token: &str) -> Result<MailboxChanges>;
+
+    /// Send `draft` from the account, returning the provider message id
+    async fn send(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String>;
+
+    /// The content of one of a message's attachments
+    async fn fetch_attachment(
+        &self,
+        account: &EmailAccount,
+        email: &Email,
+        attachment: &EmailAttachment,
+    ) -> Result<Vec<u8>>;
+
+    /// Exchange the account's refresh token for a new access token
+    async fn refresh_token(&self, account: &EmailAccount) -> Result<RefreshedToken>;
+}
+
 pub struct EmailIntegrationService {
     db: SqlitePool,
+    archive_store: Option<DocumentStore>,
+    mailbox: Option<Arc<dyn MailboxClient>>,
+    /// Holds OAuth tokens; the accounts table keeps none
+    tokens: SharedSecretStore,
+    sync_window_days: i64,
+    attachment_policy: AttachmentPolicy,
+    clock: SharedClock,
 }
 
 impl EmailIntegrationService {
     pub fn new(db: SqlitePool) -> Self {
-        Self { db }
+        Self {
+            db,
+            archive_store: None,
+            mailbox: None,
+            tokens: keychain(),
+            sync_window_days: DEFAULT_SYNC_WINDOW_DAYS,
+            attachment_policy: AttachmentPolicy::default(),
+            clock: system_clock(),
+        }
+    }
+
+    /// Time source for sync windows and message, draft and account timestamps
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Types and sizes allowed for downloaded and outgoing attachments
+    pub fn with_attachment_policy(mut self, policy: AttachmentPolicy) -> Self {
+        self.attachment_policy = policy;
+        self
+    }
+
+    pub fn with_mailbox(mut self, mailbox: Arc<dyn MailboxClient>) -> Self {
+        self.mailbox = Some(mailbox);
+        self
+    }
+
+    /// Where account OAuth tokens are kept; the OS keychain by default
+    pub fn with_secret_store(mut self, tokens: SharedSecretStore) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    fn mailbox(&self) -> Result<&dyn MailboxClient> {
+        self.mailbox
+            .as_deref()
+            .ok_or_else(|| ServiceError::Unavailable("No mailbox client is configured".to_string()).into())
+    }
+
+    /// How far back newly connected accounts sync, and resync once their
+    /// sync token expires
+    pub fn with_sync_window_days(mut self, days: i64) -> Self {
+        self.sync_window_days = days;
+        self
+    }
+
+    /// Keep PDF archives of linked emails in the document store under `dir`
+    pub fn with_archive_dir(mut self, dir: PathBuf) -> Self {
+        self.archive_store = Some(DocumentStore::new(self.db.clone(), dir));
+        self
     }
 
     // ============= Account Management =============
@@ -240,7 +419,7 @@ impl EmailIntegrationService {
         expires_in_seconds: i64,
     ) -> Result<EmailAccount> {
         let account_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+        let now = self.clock.now();
         let token_expires_at = now + chrono::Duration::seconds(expires_in_seconds);
 
         let account = EmailAccount {
@@ -254,7 +433,8 @@ impl EmailIntegrationService {
             is_active: true,
             sync_enabled: true,
             last_sync_at: None,
-            sync_from_date: Some(now - chrono::Duration::days(30)), // Sync last 30 days by default
+            sync_from_date: Some(now - chrono::Duration::days(self.sync_window_days)),
+            sync_token: None,
             auto_file_emails: true,
             auto_link_to_matters: true,
             signature: None,
@@ -277,7 +457,7 @@ impl EmailIntegrationService {
         expires_in_seconds: i64,
     ) -> Result<EmailAccount> {
         let account_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+        let now = self.clock.now();
         let token_expires_at = now + chrono::Duration::seconds(expires_in_seconds);
 
         let account = EmailAccount {
@@ -291,7 +471,8 @@ impl EmailIntegrationService {
             is_active: true,
             sync_enabled: true,
             last_sync_at: None,
-            sync_from_date: Some(now - chrono::Duration::days(30)),
+            sync_from_date: Some(now - chrono::Duration::days(self.sync_window_days)),
+            sync_token: None,
             auto_file_emails: true,
             auto_link_to_matters: true,
             signature: None,
@@ -309,27 +490,17 @@ impl EmailIntegrationService {
         let mut account = self.get_email_account(account_id).await?;
 
         // Check if token is expired or will expire soon
-        let now = Utc::now();
+        let now = self.clock.now();
         if account.token_expires_at > now + chrono::Duration::minutes(5) {
             return Ok(account); // Token still valid
         }
 
-        // Refresh token based on provider
-        match account.provider {
-            EmailProvider::Gmail => {
-                let (new_access_token, expires_in) = self.refresh_gmail_token(&account).await?;
-                account.access_token = new_access_token;
-                account.token_expires_at = now + chrono::Duration::seconds(expires_in);
-            }
-            EmailProvider::Outlook => {
-                let (new_access_token, expires_in) = self.refresh_outlook_token(&account).await?;
-                account.access_token = new_access_token;
-                account.token_expires_at = now + chrono::Duration::seconds(expires_in);
-            }
-            _ => {
-                return Err(anyhow::anyhow!("Unsupported provider for token refresh"));
-            }
+        let refreshed = self.mailbox()?.refresh_token(&account).await?;
+        account.access_token = refreshed.access_token;
+        if let Some(refresh_token) = refreshed.refresh_token {
+            account.refresh_token = Some(refresh_token);
         }
+        account.token_expires_at = now + chrono::Duration::seconds(refreshed.expires_in);
 
         account.updated_at = now;
         self.save_email_account(&account).await?;
@@ -337,22 +508,12 @@ impl EmailIntegrationService {
         Ok(account)
     }
 
-    async fn refresh_gmail_token(&self, account: &EmailAccount) -> Result<(String, i64)> {
-        // Stub - would call Google OAuth2 token refresh endpoint
-        Ok(("new_access_token".to_string(), 3600))
-    }
-
-    async fn refresh_outlook_token(&self, account: &EmailAccount) -> Result<(String, i64)> {
-        // Stub - would call Microsoft OAuth2 token refresh endpoint
-        Ok(("new_access_token".to_string(), 3600))
-    }
-
     /// Disconnect email account
     pub async fn disconnect_account(&self, account_id: &str) -> Result<()> {
         let mut account = self.get_email_account(account_id).await?;
         account.is_active = false;
         account.sync_enabled = false;
-        account.updated_at = Utc::now();
+        account.updated_at = self.clock.now();
 
         self.save_email_account(&account).await?;
 
@@ -361,7 +522,10 @@ impl EmailIntegrationService {
 
     // ============= Email Syncing =============
 
-    /// Sync emails from provider
+    /// Sync emails from provider. Once an account has a sync token only the
+    /// messages since the last sync are fetched; without one, or once the
+    /// provider expires it, the whole sync window is fetched again and
+    /// messages already stored are skipped. Returns how many were new.
     pub async fn sync_emails(&self, account_id: &str) -> Result<u32> {
         let mut account = self.get_email_account(account_id).await?;
 
@@ -369,30 +533,21 @@ impl EmailIntegrationService {
             return Ok(0);
         }
 
-        // Refresh token if needed
-        account = self.refresh_access_token(&account.id).await?;
-
-        let sync_count = match account.provider {
-            EmailProvider::Gmail => self.sync_gmail_emails(&account).await?,
-            EmailProvider::Outlook => self.sync_outlook_emails(&account).await?,
-            _ => 0,
+        let Some(mailbox) = self.mailbox.as_ref() else {
+            warn!("No mailbox client configured; {:?} account {} not synced", account.provider, account.id);
+            return Ok(0);
         };
 
-        // Update last sync time
-        account.last_sync_at = Some(Utc::now());
-        account.updated_at = Utc::now();
-        self.save_email_account(&account).await?;
-
-        Ok(sync_count)
-    }
-
-    async fn sync_gmail_emails(&self, account: &EmailAccount) -> Result<u32> {
-        // Stub - would call Gmail API to fetch messages
-        // GET https://gmail.googleapis.com/gmail/v1/users/me/messages
+        // Refresh token if needed
+        account = self.refresh_access_token(&account.id).await?;
+        let changes = self.fetch_mailbox_changes(mailbox.as_ref(), &account).await?;
 
-        let mock_emails = vec![];
+        let mut sync_count = 0;
+        for email in changes.messages {
+            if self.email_exists(&account.id, &email.provider_message_id).await? {
+                continue;
+            }
 
-        for email in mock_emails {
             self.save_email(&email).await?;
 
             // Auto-link to matters if enabled
@@ -402,16 +557,35 @@ impl EmailIntegrationService {
 
             // Apply email rules
             self.apply_email_rules(&email).await?;
+            sync_count += 1;
         }
 
-        Ok(mock_emails.len() as u32)
+        // Update sync token and last sync time
+        account.sync_token = changes.sync_token;
+        account.last_sync_at = Some(self.clock.now());
+        account.updated_at = self.clock.now();
+        self.save_email_account(&account).await?;
+
+        Ok(sync_count)
     }
 
-    async fn sync_outlook_emails(&self, account: &EmailAccount) -> Result<u32> {
-        // Stub - would call Microsoft Graph API to fetch messages
-        // GET https://graph.microsoft.com/v1.0/me/messages
+    async fn fetch_mailbox_changes(
+        &self,
+        mailbox: &dyn MailboxClient,
+        account: &EmailAccount,
+    ) -> Result<MailboxChanges> {
+        if let Some(token) = account.sync_token.as_deref() {
+            match mailbox.fetch_changes(account, token).await {
+                Err(err) if err.downcast_ref::<SyncTokenExpired>().is_some() => {
+                    warn!("Sync token for account {} expired; running a full resync", account.id);
+                }
+                result => return result,
+            }
+        }
 
-        Ok(0)
+        let since = account.sync_from_date
+            .unwrap_or_else(|| self.clock.now() - chrono::Duration::days(self.sync_window_days));
+        mailbox.fetch_since(account, since).await
     }
 
     /// Download email attachment
@@ -421,62 +595,52 @@ impl EmailIntegrationService {
         attachment_id: &str,
         local_path: &str,
     ) -> Result<EmailAttachment> {
-        let email = self.get_email(email_id).await?;
-        let account = self.get_email_account(&email.account_id).await?;
+        let mut email = self.get_email(email_id).await?;
 
         let mut attachment = email.attachments.iter()
             .find(|a| a.id == attachment_id)
-            .ok_or_else(|| anyhow::anyhow!("Attachment not found"))?
+            .ok_or_else(|| ServiceError::not_found("Attachment", attachment_id))?
             .clone();
 
         if attachment.downloaded {
             return Ok(attachment);
         }
 
-        // Download based on provider
-        match account.provider {
-            EmailProvider::Gmail => {
-                self.download_gmail_attachment(&account, &email, &attachment, local_path).await?;
-            }
-            EmailProvider::Outlook => {
-                self.download_outlook_attachment(&account, &email, &attachment, local_path).await?;
-            }
-            _ => {
-                return Err(anyhow::anyhow!("Unsupported provider for attachment download"));
-            }
+        let account = self.refresh_access_token(&email.account_id).await?;
+        let content = self.mailbox()?.fetch_attachment(&account, &email, &attachment).await?;
+        tokio::fs::write(local_path, &content)
+            .await
+            .with_context(|| format!("Failed to save attachment {}", attachment.filename))?;
+
+        // Nothing unsafe or mislabelled is kept in the client's file
+        let scanned = scan_attachment(Path::new(local_path), &self.attachment_policy)
+            .and_then(|result| result.require_clean());
+        if let Err(err) = scanned {
+            warn!("Discarding attachment {} of email {}: {}", attachment.filename, email.id, err);
+            let _ = tokio::fs::remove_file(local_path).await;
+            return Err(err);
         }
 
         attachment.downloaded = true;
         attachment.local_path = Some(local_path.to_string());
-
-        // Update email with downloaded attachment
-        // Would update in database
+        for stored in email.attachments.iter_mut().filter(|a| a.id == attachment.id) {
+            *stored = attachment.clone();
+        }
+        self.save_email(&email).await?;
 
         Ok(attachment)
     }
 
-    async fn download_gmail_attachment(
-        &self,
-        account: &EmailAccount,
-        email: &Email,
-        attachment: &EmailAttachment,
-        local_path: &str,
-    ) -> Result<()> {
-        // Stub - would call Gmail API
-        // GET https://gmail.googleapis.com/gmail/v1/users/me/messages/{messageId}/attachments/{attachmentId}
-        Ok(())
-    }
-
-    async fn download_outlook_attachment(
-        &self,
-        account: &EmailAccount,
-        email: &Email,
-        attachment: &EmailAttachment,
-        local_path: &str,
-    ) -> Result<()> {
-        // Stub - would call Microsoft Graph API
-        // GET https://graph.microsoft.com/v1.0/me/messages/{messageId}/attachments/{attachmentId}/$value
-        Ok(())
+    /// An inline image's content: the downloaded copy if there is one,
+    /// otherwise fetched from the mailbox
+    async fn inline_image(&self, email: &Email, attachment: &EmailAttachment) -> Result<Vec<u8>> {
+        if let Some(path) = attachment.local_path.as_deref().filter(|_| attachment.downloaded) {
+            return tokio::fs::read(path)
+                .await
+                .with_context(|| format!("Failed to read attachment {}", path));
+        }
+        let account = self.refresh_access_token(&email.account_id).await?;
+        self.mailbox()?.fetch_attachment(&account, email, attachment).await
     }
 
     // ============= Matter Linking =============
@@ -609,6 +773,96 @@ impl EmailIntegrationService {
         Ok(())
     }
 
+    // ============= Archival =============
+
+    /// Render a linked email (headers, body and inline images) to PDF, keep
+    /// it in the document store and attach it to the email's matter. Returns
+    /// the stored PDF's path.
+    pub async fn archive_email_as_pdf(&self, email_id: &str) -> Result<PathBuf> {
+        let store = self.archive_store.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No archive directory configured"))?;
+        let email = self.get_email(email_id).await?;
+        let matter_id = email.matter_id.clone()
+            .ok_or_else(|| ServiceError::validation(format!("Email {} is not linked to a matter", email_id)))?;
+
+        // An image that cannot be had is archived as a named placeholder
+        let mut images = HashMap::new();
+        for attachment in email.attachments.iter().filter(|a| a.is_inline) {
+            match self.inline_image(&email, attachment).await {
+                Ok(content) => {
+                    images.insert(attachment.id.clone(), content);
+                }
+                Err(err) => warn!("Archiving email {} without inline image {}: {}", email.id, attachment.filename, err),
+            }
+        }
+
+        let pdf = render_email_pdf(&email, &images)?;
+        let blob = store.put(&pdf, &format!("email:{}", email.id)).await
+            .context("Failed to store email archive")?;
+        let url = url::Url::from_file_path(&blob.path)
+            .map_err(|_| anyhow::anyhow!("Archive path is not absolute: {:?}", blob.path))?;
+
+        let attachment = Attachment {
+            id: Some(Uuid::new_v4()),
+            name: sanitize_filename(&format!("{} {}.pdf", email.date.format("%Y-%m-%d"), email.subject)),
+            url: url.to_string(),
+            attachment_type: Some(EMAIL_ARCHIVE_ATTACHMENT_TYPE.to_string()),
+            size: Some(blob.size),
+            hash: Some(blob.hash),
+            upload_date: Some(self.clock.now()),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO matter_attachments
+            (id, matter_id, email_id, name, url, attachment_type, size, hash, upload_date)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(attachment.id.map(|id| id.to_string()))
+        .bind(&matter_id)
+        .bind(&email.id)
+        .bind(&attachment.name)
+        .bind(&attachment.url)
+        .bind(&attachment.attachment_type)
+        .bind(attachment.size.map(|s| s as i64))
+        .bind(&attachment.hash)
+        .bind(attachment.upload_date)
+        .execute(&self.db)
+        .await
+        .context("Failed to record email archive attachment")?;
+
+        info!("Archived email {} to matter {} at {:?}", email.id, matter_id, blob.path);
+        Ok(blob.path)
+    }
+
+    /// Attachments recorded against a matter, oldest first
+    pub async fn list_matter_attachments(&self, matter_id: &str) -> Result<Vec<Attachment>> {
+        let rows = sqlx::query(
+            "SELECT * FROM matter_attachments WHERE matter_id = ? ORDER BY upload_date",
+        )
+        .bind(matter_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load matter attachments")?;
+
+        rows.iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                let size: Option<i64> = row.try_get("size")?;
+                Ok(Attachment {
+                    id: Uuid::parse_str(&id).ok(),
+                    name: row.try_get("name")?,
+                    url: row.try_get("url")?,
+                    attachment_type: row.try_get("attachment_type")?,
+                    size: size.map(|s| s as u64),
+                    hash: row.try_get("hash")?,
+                    upload_date: row.try_get::<Option<DateTime<Utc>>, _>("upload_date")?,
+                })
+            })
+            .collect()
+    }
+
     // ============= Email Rules =============
 
     /// Create email rule
@@ -699,8 +953,8 @@ impl EmailIntegrationService {
             matter_id,
             in_reply_to: None,
             references: Vec::new(),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: self.clock.now(),
+            updated_at: self.clock.now(),
         };
 
         self.save_draft(&draft).await?;
@@ -712,15 +966,10 @@ impl EmailIntegrationService {
     pub async fn send_email(&self, draft_id: &str) -> Result<Email> {
         let draft = self.get_draft(draft_id).await?;
         let account = self.get_email_account(&draft.account_id).await?;
+        self.scan_draft_attachments(&draft)?;
 
-        // Send based on provider
-        let provider_message_id = match account.provider {
-            EmailProvider::Gmail => self.send_gmail_email(&account, &draft).await?,
-            EmailProvider::Outlook => self.send_outlook_email(&account, &draft).await?,
-            _ => {
-                return Err(anyhow::anyhow!("Unsupported provider for sending"));
-            }
-        };
+        let account = self.refresh_access_token(&account.id).await?;
+        let provider_message_id = self.mailbox()?.send(&account, &draft).await?;
 
         // Create email record
         let email = Email {
@@ -740,7 +989,7 @@ impl EmailIntegrationService {
             body_text: None,
             body_html: Some(draft.body_html.clone()),
             snippet: None,
-            date: Utc::now(),
+            date: self.clock.now(),
             status: EmailStatus::Sent,
             is_important: false,
             has_attachments: !draft.attachments.is_empty(),
@@ -750,7 +999,7 @@ impl EmailIntegrationService {
             is_client_communication: false,
             confidence_score: None,
             attachments: draft.attachments.clone(),
-            synced_at: Utc::now(),
+            synced_at: self.clock.now(),
             is_deleted: false,
         };
 
@@ -762,16 +1011,14 @@ impl EmailIntegrationService {
         Ok(email)
     }
 
-    async fn send_gmail_email(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
-        // Stub - would call Gmail API
-        // POST https://gmail.googleapis.com/gmail/v1/users/me/messages/send
-        Ok(format!("gmail_{}", Uuid::new_v4()))
-    }
-
-    async fn send_outlook_email(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
-        // Stub - would call Microsoft Graph API
-        // POST https://graph.microsoft.com/v1.0/me/sendMail
-        Ok(format!("outlook_{}", Uuid::new_v4()))
+    /// Every outgoing attachment must be on disk and pass the attachment policy
+    fn scan_draft_attachments(&self, draft: &EmailDraft) -> Result<()> {
+        for attachment in &draft.attachments {
+            let path = attachment.local_path.as_deref()
+                .ok_or_else(|| ServiceError::validation(format!("Attachment {} has not been downloaded", attachment.filename)))?;
+            scan_attachment(Path::new(path), &self.attachment_policy)?.require_clean()?;
+        }
+        Ok(())
     }
 
     // ============= Email Templates =============
@@ -782,31 +1029,23 @@ impl EmailIntegrationService {
         Ok(template)
     }
 
-    /// Apply template to draft
+    /// Apply template to draft; fails without touching the draft when a
+    /// variable the template declares is not provided
     pub async fn apply_template_to_draft(
         &self,
         draft_id: &str,
         template_id: &str,
         variables: HashMap<String, String>,
     ) -> Result<EmailDraft> {
-        let mut draft = self.get_draft(draft_id).await?;
         let template = self.get_template(template_id).await?;
+        validate_template_variables(&template, &variables)?;
+        let mut draft = self.get_draft(draft_id).await?;
 
-        // Replace variables in subject
-        let mut subject = template.subject.clone();
-        for (key, value) in &variables {
-            subject = subject.replace(&format!("{{{{{}}}}}", key), value);
-        }
+        let (subject, body) = template.render(&variables);
         draft.subject = subject;
-
-        // Replace variables in body
-        let mut body = template.body_html.clone();
-        for (key, value) in &variables {
-            body = body.replace(&format!("{{{{{}}}}}", key), value);
-        }
         draft.body_html = body;
 
-        draft.updated_at = Utc::now();
+        draft.updated_at = self.clock.now();
 
         self.save_draft(&draft).await?;
 
@@ -823,34 +1062,41 @@ impl EmailIntegrationService {
 
     // ============= Helper Methods =============
 
+    /// Tokens go to the secret store; the row's token columns stay empty
     async fn save_email_account(&self, account: &EmailAccount) -> Result<()> {
         let provider_str = format!("{:?}", account.provider);
+        self.tokens.put(&access_token_key(&account.id), &account.access_token)?;
+        match &account.refresh_token {
+            Some(refresh_token) => self.tokens.put(&refresh_token_key(&account.id), refresh_token)?,
+            None => self.tokens.delete(&refresh_token_key(&account.id))?,
+        }
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             INSERT OR REPLACE INTO email_accounts
             (id, provider, email_address, display_name, access_token, refresh_token,
-             token_expires_at, is_active, sync_enabled, last_sync_at, sync_from_date,
+             token_expires_at, is_active, sync_enabled, last_sync_at, sync_from_date, sync_token,
              auto_file_emails, auto_link_to_matters, signature, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
-            account.id,
-            provider_str,
-            account.email_address,
-            account.display_name,
-            account.access_token,
-            account.refresh_token,
-            account.token_expires_at,
-            account.is_active,
-            account.sync_enabled,
-            account.last_sync_at,
-            account.sync_from_date,
-            account.auto_file_emails,
-            account.auto_link_to_matters,
-            account.signature,
-            account.created_at,
-            account.updated_at
         )
+        .bind(&account.id)
+        .bind(provider_str)
+        .bind(&account.email_address)
+        .bind(&account.display_name)
+        .bind("")
+        .bind(None::<String>)
+        .bind(account.token_expires_at)
+        .bind(account.is_active)
+        .bind(account.sync_enabled)
+        .bind(account.last_sync_at)
+        .bind(account.sync_from_date)
+        .bind(&account.sync_token)
+        .bind(account.auto_file_emails)
+        .bind(account.auto_link_to_matters)
+        .bind(&account.signature)
+        .bind(account.created_at)
+        .bind(account.updated_at)
         .execute(&self.db)
         .await
         .context("Failed to save email account")?;
@@ -859,18 +1105,116 @@ impl EmailIntegrationService {
     }
 
     async fn get_email_account(&self, account_id: &str) -> Result<EmailAccount> {
-        // Stub - would query database
-        Err(anyhow::anyhow!("Not implemented"))
+        let row = sqlx::query("SELECT * FROM email_accounts WHERE id = ?")
+            .bind(account_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load email account")?
+            .ok_or_else(|| ServiceError::not_found("Email account", account_id))?;
+
+        // Accounts connected before tokens moved out of the table
+        let legacy_access_token: String = row.try_get("access_token")?;
+        if !legacy_access_token.is_empty() {
+            self.move_tokens_to_secret_store(account_id, &legacy_access_token, row.try_get("refresh_token")?)
+                .await?;
+        }
+
+        let provider: String = row.try_get("provider")?;
+        Ok(EmailAccount {
+            id: row.try_get("id")?,
+            provider: serde_json::from_value(serde_json::json!(provider))?,
+            email_address: row.try_get("email_address")?,
+            display_name: row.try_get("display_name")?,
+            access_token: self.tokens.get(&access_token_key(account_id))?
+                .ok_or_else(|| ServiceError::Unauthorized(format!("Email account {} must be reconnected", account_id)))?,
+            refresh_token: self.tokens.get(&refresh_token_key(account_id))?,
+            token_expires_at: row.try_get("token_expires_at")?,
+            is_active: row.try_get("is_active")?,
+            sync_enabled: row.try_get("sync_enabled")?,
+            last_sync_at: row.try_get("last_sync_at")?,
+            sync_from_date: row.try_get("sync_from_date")?,
+            sync_token: row.try_get("sync_token")?,
+            auto_file_emails: row.try_get("auto_file_emails")?,
+            auto_link_to_matters: row.try_get("auto_link_to_matters")?,
+            signature: row.try_get("signature")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
     }
 
-    async fn save_email(&self, email: &Email) -> Result<()> {
-        // Stub - would save to database
+    async fn move_tokens_to_secret_store(
+        &self,
+        account_id: &str,
+        access_token: &str,
+        refresh_token: Option<String>,
+    ) -> Result<()> {
+        self.tokens.put(&access_token_key(account_id), access_token)?;
+        if let Some(refresh_token) = refresh_token {
+            self.tokens.put(&refresh_token_key(account_id), &refresh_token)?;
+        }
+        sqlx::query("UPDATE email_accounts SET access_token = '', refresh_token = NULL WHERE id = ?")
+            .bind(account_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to clear stored email tokens")?;
+        info!("Moved OAuth tokens for email account {} to the secret store", account_id);
         Ok(())
     }
 
+    async fn email_exists(&self, account_id: &str, provider_message_id: &str) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM emails WHERE account_id = ? AND provider_message_id = ?")
+            .bind(account_id)
+            .bind(provider_message_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to look up email")?;
+        Ok(row.is_some())
+    }
+
+    async fn save_email(&self, email: &Email) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO emails
+            (id, account_id, provider_message_id, thread_id, matter_id, subject,
+             email_date, email_json, synced_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&email.id)
+        .bind(&email.account_id)
+        .bind(&email.provider_message_id)
+        .bind(&email.thread_id)
+        .bind(&email.matter_id)
+        .bind(&email.subject)
+        .bind(email.date)
+        .bind(serde_json::to_string(email)?)
+        .bind(email.synced_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save email")?;
+
+        // The search index is kept current
+        let body = email.body_text.as_deref().or(email.snippet.as_deref()).unwrap_or_default();
+        GlobalSearchService::new(self.db.clone())
+            .index(
+                SearchScope::Email,
+                &email.id,
+                email.matter_id.as_deref(),
+                &email.subject,
+                &format!("{}\n{}", email.from.address, body),
+            )
+            .await
+    }
+
     async fn get_email(&self, email_id: &str) -> Result<Email> {
-        // Stub - would query database
-        Err(anyhow::anyhow!("Not implemented"))
+        let row = sqlx::query("SELECT email_json FROM emails WHERE id = ?")
+            .bind(email_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load email")?
+            .ok_or_else(|| ServiceError::not_found("Email", email_id))?;
+
+        serde_json::from_str(row.try_get("email_json")?).context("Failed to parse stored email")
     }
 
     async fn save_email_rule(&self, rule: &EmailRule) -> Result<()> {
@@ -889,28 +1233,90 @@ impl EmailIntegrationService {
     }
 
     async fn save_draft(&self, draft: &EmailDraft) -> Result<()> {
-        // Stub - would save to database
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO email_drafts
+            (id, account_id, matter_id, draft_json, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&draft.id)
+        .bind(&draft.account_id)
+        .bind(&draft.matter_id)
+        .bind(serde_json::to_string(draft)?)
+        .bind(draft.created_at)
+        .bind(draft.updated_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save email draft")?;
         Ok(())
     }
 
     async fn get_draft(&self, draft_id: &str) -> Result<EmailDraft> {
-        // Stub - would query database
-        Err(anyhow::anyhow!("Not implemented"))
+        let draft_json: String = sqlx::query_scalar("SELECT draft_json FROM email_drafts WHERE id = ?")
+            .bind(draft_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load email draft")?
+            .ok_or_else(|| ServiceError::not_found("Email draft", draft_id))?;
+        serde_json::from_str(&draft_json).context("Failed to parse email draft")
     }
 
     async fn delete_draft(&self, draft_id: &str) -> Result<()> {
-        // Stub - would delete from database
+        sqlx::query("DELETE FROM email_drafts WHERE id = ?")
+            .bind(draft_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to delete email draft")?;
         Ok(())
     }
 
     async fn save_template(&self, template: &EmailTemplate) -> Result<()> {
-        // Stub - would save to database
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO email_templates
+            (id, name, category, subject, body_html, variables, attachments,
+             created_at, updated_at, usage_count)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.name)
+        .bind(serde_json::to_string(&template.category)?)
+        .bind(&template.subject)
+        .bind(&template.body_html)
+        .bind(serde_json::to_string(&template.variables)?)
+        .bind(serde_json::to_string(&template.attachments)?)
+        .bind(template.created_at)
+        .bind(template.updated_at)
+        .bind(template.usage_count as i64)
+        .execute(&self.db)
+        .await
+        .context("Failed to save email template")?;
+
         Ok(())
     }
 
-    async fn get_template(&self, template_id: &str) -> Result<EmailTemplate> {
-        // Stub - would query database
-        Err(anyhow::anyhow!("Not implemented"))
+    pub async fn get_template(&self, template_id: &str) -> Result<EmailTemplate> {
+        let row = sqlx::query("SELECT * FROM email_templates WHERE id = ?")
+            .bind(template_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load email template")?
+            .ok_or_else(|| ServiceError::not_found("Email template", template_id))?;
+
+        Ok(EmailTemplate {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            category: serde_json::from_str(row.try_get("category")?)?,
+            subject: row.try_get("subject")?,
+            body_html: row.try_get("body_html")?,
+            variables: serde_json::from_str(row.try_get("variables")?)?,
+            attachments: serde_json::from_str(row.try_get("attachments")?)?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            usage_count: row.try_get::<i64, _>("usage_count")? as u32,
+        })
     }
 
     async fn find_matters_by_email(&self, email_address: &str) -> Result<Vec<String>> {
@@ -948,3 +1354,810 @@ impl EmailIntegrationService {
         Ok(format!("Matter {}", matter_id))
     }
 }
+
+fn access_token_key(account_id: &str) -> String {
+    format!("email-account:{}:access", account_id)
+}
+
+fn refresh_token_key(account_id: &str) -> String {
+    format!("email-account:{}:refresh", account_id)
+}
+
+const PAGE_WIDTH_MM: f32 = 215.9;
+const PAGE_HEIGHT_MM: f32 = 279.4;
+const MARGIN_MM: f32 = 20.0;
+const LINE_HEIGHT_MM: f32 = 5.0;
+
+/// Characters per line of 10pt DejaVu Sans across a letter page's text width
+const ARCHIVE_LINE_CHARS: usize = 88;
+
+/// Embedded in every archive: the PDF base fonts only cover Latin-1, and
+/// names and quoted text in other scripts must survive in the client's file
+const ARCHIVE_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+
+/// Inline images are drawn at screen resolution, shrunk to fit the text
+/// width and this height
+const SCREEN_DPI: f32 = 96.0;
+const MAX_IMAGE_HEIGHT_MM: f32 = 120.0;
+
+/// Render an email as a letter-size PDF: headers, then the body with its
+/// inline images where they appear, and the remaining attachments by name.
+/// `images` holds inline image content by attachment id; an image without
+/// content, or in a format that cannot be embedded, is named instead.
+fn render_email_pdf(email: &Email, images: &HashMap<String, Vec<u8>>) -> Result<Vec<u8>> {
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(&email.subject, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Email");
+    let font = doc.add_external_font(ARCHIVE_FONT).context("Failed to load PDF font")?;
+    let mut pages = ArchivePages {
+        layer: doc.get_page(first_page).get_layer(first_layer),
+        doc: &doc,
+        y: PAGE_HEIGHT_MM - MARGIN_MM,
+    };
+
+    for header in email_headers(email) {
+        pages.text(&header, &font);
+    }
+    pages.text("", &font);
+
+    for line in email_body_lines(email) {
+        match line {
+            BodyLine::Text(text) => pages.text(&text, &font),
+            BodyLine::Image(attachment) => {
+                let image = images.get(&attachment.id).map(|content| inline_image_xobject(content));
+                match image {
+                    Some(Ok(image)) => pages.image(image),
+                    Some(Err(err)) => {
+                        warn!("Archiving inline image {} as a placeholder: {}", attachment.filename, err);
+                        pages.text(&inline_image_placeholder(&attachment.filename), &font);
+                    }
+                    None => pages.text(&inline_image_placeholder(&attachment.filename), &font),
+                }
+            }
+        }
+    }
+
+    doc.save_to_bytes().context("Failed to render email PDF")
+}
+
+/// Lays lines and images down the archive's pages, starting a new page
+/// whenever the next one does not fit
+struct ArchivePages<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    /// Top of the space left on the current page
+    y: f32,
+}
+
+impl ArchivePages<'_> {
+    /// The layer to draw on and the top of `height` millimetres of room
+    fn take(&mut self, height: f32) -> (PdfLayerReference, f32) {
+        let page_top = PAGE_HEIGHT_MM - MARGIN_MM;
+        if self.y - height < MARGIN_MM && self.y < page_top {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Email");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = page_top;
+        }
+        let top = self.y;
+        self.y -= height;
+        (self.layer.clone(), top)
+    }
+
+    fn text(&mut self, text: &str, font: &IndirectFontRef) {
+        for line in wrap_line(text, ARCHIVE_LINE_CHARS) {
+            let (layer, top) = self.take(LINE_HEIGHT_MM);
+            if !line.is_empty() {
+                // Baseline a cap height below the top of the line
+                layer.use_text(line, 10.0, Mm(MARGIN_MM), Mm(top - 3.5), font);
+            }
+        }
+    }
+
+    fn image(&mut self, image: ImageXObject) {
+        let to_mm = |px: usize| px as f32 * 25.4 / SCREEN_DPI;
+        let (width, height) = (to_mm(image.width.0), to_mm(image.height.0));
+        let scale = ((PAGE_WIDTH_MM - 2.0 * MARGIN_MM) / width)
+            .min(MAX_IMAGE_HEIGHT_MM / height)
+            .min(1.0);
+        let (layer, top) = self.take(height * scale + LINE_HEIGHT_MM);
+        Image::from(image).add_to_layer(
+            layer,
+            ImageTransform {
+                translate_x: Some(Mm(MARGIN_MM)),
+                translate_y: Some(Mm(top - height * scale)),
+                scale_x: Some(scale),
+                scale_y: Some(scale),
+                dpi: Some(SCREEN_DPI),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// An inline image as a PDF image. JPEGs are embedded as they are; PNGs are
+/// decoded, with transparent pixels laid over white as a mail client shows them.
+fn inline_image_xobject(content: &[u8]) -> Result<ImageXObject> {
+    if content.starts_with(&[0xFF, 0xD8]) {
+        jpeg_xobject(content)
+    } else if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        png_xobject(content)
+    } else {
+        Err(ServiceError::validation("Only JPEG and PNG images can be archived").into())
+    }
+}
+
+/// A JPEG passed through to the PDF's DCT filter; the frame header gives its
+/// size and colour components
+fn jpeg_xobject(content: &[u8]) -> Result<ImageXObject> {
+    // Every segment ahead of the frame header carries its own length
+    let mut at = 2;
+    while let Some(&[0xFF, marker, high, low]) = content.get(at..at + 4) {
+        if marker == 0xFF {
+            at += 1;
+            continue;
+        }
+        match marker {
+            // Baseline, extended and progressive Huffman frames
+            0xC0..=0xC2 => {
+                let frame = content.get(at + 4..at + 10).context("Truncated JPEG frame header")?;
+                let color_space = match frame[5] {
+                    1 => ColorSpace::Greyscale,
+                    3 => ColorSpace::Rgb,
+                    4 => ColorSpace::Cmyk,
+                    n => anyhow::bail!("JPEG with {} colour components", n),
+                };
+                return Ok(ImageXObject {
+                    width: Px(u16::from_be_bytes([frame[3], frame[4]]) as usize),
+                    height: Px(u16::from_be_bytes([frame[1], frame[2]]) as usize),
+                    color_space,
+                    bits_per_component: ColorBits::Bit8,
+                    interpolate: true,
+                    image_data: content.to_vec(),
+                    image_filter: Some(ImageFilter::DCT),
+                    smask: None,
+                    clipping_bbox: None,
+                });
+            }
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                anyhow::bail!("JPEG coding {:#04X} cannot be embedded in a PDF", marker)
+            }
+            _ => at += 2 + u16::from_be_bytes([high, low]) as usize,
+        }
+    }
+    anyhow::bail!("JPEG has no frame header")
+}
+
+fn png_xobject(content: &[u8]) -> Result<ImageXObject> {
+    let mut decoder = png::Decoder::new(content);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().context("Failed to read PNG image")?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).context("Failed to decode PNG image")?;
+    pixels.truncate(frame.buffer_size());
+
+    let (color_space, channels) = match frame.color_type {
+        png::ColorType::Grayscale => (ColorSpace::Greyscale, 1),
+        png::ColorType::GrayscaleAlpha => (ColorSpace::Greyscale, 2),
+        png::ColorType::Rgb => (ColorSpace::Rgb, 3),
+        png::ColorType::Rgba => (ColorSpace::Rgb, 4),
+        png::ColorType::Indexed => anyhow::bail!("PNG palette was not expanded"),
+    };
+    if channels % 2 == 0 {
+        pixels = pixels
+            .chunks_exact(channels)
+            .flat_map(|pixel| {
+                let (color, alpha) = pixel.split_at(channels - 1);
+                let alpha = alpha[0] as u32;
+                color.iter().map(move |&c| ((c as u32 * alpha + 255 * (255 - alpha)) / 255) as u8)
+            })
+            .collect();
+    }
+
+    Ok(ImageXObject {
+        width: Px(frame.width as usize),
+        height: Px(frame.height as usize),
+        color_space,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: pixels,
+        image_filter: None,
+        smask: None,
+        clipping_bbox: None,
+    })
+}
+
+fn email_headers(email: &Email) -> Vec<String> {
+    let mut headers = vec![
+        format!("From: {}", format_address(&email.from)),
+        format!("To: {}", format_addresses(&email.to)),
+    ];
+    if !email.cc.is_empty() {
+        headers.push(format!("Cc: {}", format_addresses(&email.cc)));
+    }
+    if !email.bcc.is_empty() {
+        headers.push(format!("Bcc: {}", format_addresses(&email.bcc)));
+    }
+    headers.push(format!("Date: {}", email.date.format("%Y-%m-%d %H:%M UTC")));
+    headers.push(format!("Subject: {}", email.subject));
+    headers
+}
+
+/// A line of an archived email's body
+#[derive(Debug, PartialEq)]
+enum BodyLine<'a> {
+    Text(String),
+    /// An inline image, drawn on a line of its own
+    Image(&'a EmailAttachment),
+}
+
+/// Marks where the HTML body places an inline image: the character, then
+/// the image's content id, on a line of its own
+const IMAGE_MARKER: char = '\u{FFFC}';
+
+/// The body as lines; an HTML body is preferred over the plain-text part.
+/// Inline images the body does not place follow it, then the other
+/// attachments by name.
+fn email_body_lines(email: &Email) -> Vec<BodyLine<'_>> {
+    let inline_images: HashMap<&str, &EmailAttachment> = email.attachments.iter()
+        .filter(|a| a.is_inline)
+        .filter_map(|a| {
+            let cid = a.content_id.as_deref()?.trim_matches(|c| c == '<' || c == '>');
+            Some((cid, a))
+        })
+        .collect();
+
+    let body = match email.body_html.as_deref().filter(|html| !html.trim().is_empty()) {
+        Some(html) => html_to_text(html, &inline_images),
+        None => email.body_text.clone().or_else(|| email.snippet.clone()).unwrap_or_default(),
+    };
+
+    let mut lines: Vec<BodyLine> = body
+        .lines()
+        .map(|line| match line.strip_prefix(IMAGE_MARKER).and_then(|cid| inline_images.get(cid).copied()) {
+            Some(image) => BodyLine::Image(image),
+            None => BodyLine::Text(line.to_string()),
+        })
+        .collect();
+    for image in email.attachments.iter().filter(|a| a.is_inline) {
+        if !lines.contains(&BodyLine::Image(image)) {
+            lines.push(BodyLine::Image(image));
+        }
+    }
+
+    let attached: Vec<&EmailAttachment> = email.attachments.iter().filter(|a| !a.is_inline).collect();
+    if !attached.is_empty() {
+        lines.push(BodyLine::Text(String::new()));
+        lines.push(BodyLine::Text("Attachments:".to_string()));
+        for attachment in attached {
+            lines.push(BodyLine::Text(format!(
+                "- {} ({}, {} bytes)",
+                attachment.filename, attachment.mime_type, attachment.size
+            )));
+        }
+    }
+    lines
+}
+
+fn inline_image_placeholder(filename: &str) -> String {
+    format!("[Inline image: {}]", filename)
+}
+
+fn format_address(address: &EmailAddress) -> String {
+    match address.name.as_deref().filter(|name| !name.trim().is_empty()) {
+        Some(name) => format!("{} <{}>", name, address.address),
+        None => address.address.clone(),
+    }
+}
+
+fn format_addresses(addresses: &[EmailAddress]) -> String {
+    addresses.iter().map(format_address).collect::<Vec<_>>().join(", ")
+}
+
+/// Text of an HTML body with block elements on their own lines. An image
+/// whose `cid:` source is one of the inline attachments is marked on a line
+/// of its own; any other image becomes a placeholder named by its alt text.
+fn html_to_text(html: &str, inline_images: &HashMap<&str, &EmailAttachment>) -> String {
+    let document = Html::parse_document(html);
+    let mut text = String::new();
+    append_html_text(document.root_element(), inline_images, &mut text);
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() && matches!(lines.last(), None | Some(&"")) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+fn append_html_text(element: ElementRef, inline_images: &HashMap<&str, &EmailAttachment>, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => {
+                for c in text.chars().filter(|&c| c != IMAGE_MARKER) {
+                    if !c.is_whitespace() {
+                        out.push(c);
+                    } else if !out.ends_with(' ') && !out.ends_with('\n') {
+                        out.push(' ');
+                    }
+                }
+            }
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    append_html_element(child, inline_images, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn append_html_element(element: ElementRef, inline_images: &HashMap<&str, &EmailAttachment>, out: &mut String) {
+    match element.value().name() {
+        "head" | "script" | "style" | "title" => {}
+        "br" => out.push('\n'),
+        "img" => {
+            let src = element.value().attr("src").unwrap_or_default();
+            match src.strip_prefix("cid:").filter(|cid| inline_images.contains_key(cid)) {
+                Some(cid) => {
+                    out.push('\n');
+                    out.push(IMAGE_MARKER);
+                    out.push_str(cid);
+                    out.push('\n');
+                }
+                None => {
+                    let name = element.value().attr("alt").filter(|alt| !alt.trim().is_empty()).unwrap_or(src);
+                    out.push_str(&inline_image_placeholder(name));
+                }
+            }
+        }
+        "td" | "th" => {
+            append_html_text(element, inline_images, out);
+            out.push(' ');
+        }
+        name @ ("p" | "div" | "li" | "tr" | "table" | "ul" | "ol" | "blockquote" | "hr"
+        | "h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+            out.push('\n');
+            if name == "li" {
+                out.push_str("- ");
+            }
+            append_html_text(element, inline_images, out);
+            out.push('\n');
+        }
+        _ => append_html_text(element, inline_images, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use crate::services::secret_store::MemorySecretStore;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    async fn service() -> EmailIntegrationService {
+        let db = test_database().await;
+        EmailIntegrationService::new(db).with_secret_store(Arc::new(MemorySecretStore::default()))
+    }
+
+    fn address(address: &str) -> EmailAddress {
+        EmailAddress { name: None, address: address.to_string() }
+    }
+
+    fn email(body_html: Option<&str>) -> Email {
+        Email {
+            id: "email-1".to_string(),
+            account_id: "account-1".to_string(),
+            provider_message_id: "msg-1".to_string(),
+            thread_id: None,
+            from: EmailAddress { name: Some("Jane Client".to_string()), address: "jane@example.com".to_string() },
+            to: vec![address("counsel@firm.example")],
+            cc: vec![],
+            bcc: vec![],
+            reply_to: None,
+            subject: "Photos of the intersection".to_string(),
+            body_text: Some("Attached are the photos.\nThanks, Jane".to_string()),
+            body_html: body_html.map(str::to_string),
+            snippet: None,
+            date: Utc.with_ymd_and_hms(2024, 3, 4, 15, 30, 0).unwrap(),
+            status: EmailStatus::Read,
+            is_important: false,
+            has_attachments: true,
+            labels: vec![],
+            matter_id: None,
+            matter_name: None,
+            is_client_communication: true,
+            confidence_score: None,
+            attachments: vec![EmailAttachment {
+                id: "att-1".to_string(),
+                filename: "corner.jpg".to_string(),
+                mime_type: "image/jpeg".to_string(),
+                size: 52_113,
+                content_id: Some("<corner@mail>".to_string()),
+                provider_attachment_id: "p-1".to_string(),
+                is_inline: true,
+                downloaded: false,
+                local_path: None,
+            }],
+            synced_at: Utc::now(),
+            is_deleted: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archived_email_attached_to_matter_with_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service().await.with_archive_dir(dir.path().to_path_buf());
+        service.save_email(&email(None)).await.unwrap();
+
+        assert!(service.archive_email_as_pdf("email-1").await.is_err());
+
+        service.link_email_to_matter("email-1", "matter-1", None).await.unwrap();
+        let path = service.archive_email_as_pdf("email-1").await.unwrap();
+
+        let pdf = std::fs::read(&path).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+
+        let attachments = service.list_matter_attachments("matter-1").await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].attachment_type.as_deref(), Some(EMAIL_ARCHIVE_ATTACHMENT_TYPE));
+        assert_eq!(attachments[0].name, "2024-03-04 Photos of the intersection.pdf");
+        assert_eq!(attachments[0].size, Some(pdf.len() as u64));
+        let hash = attachments[0].hash.clone().unwrap();
+        assert_eq!(path.file_name().unwrap().to_string_lossy(), hash);
+    }
+
+    /// A 2x1 PNG: an opaque red pixel and a transparent one
+    fn png_image() -> Vec<u8> {
+        let mut content = Vec::new();
+        let mut encoder = png::Encoder::new(&mut content, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 255, 0, 0, 0, 0]).unwrap();
+        writer.finish().unwrap();
+        content
+    }
+
+    #[tokio::test]
+    async fn test_archive_draws_inline_images_in_an_embedded_font() {
+        let dir = tempfile::tempdir().unwrap();
+        let image_path = dir.path().join("corner.png");
+        std::fs::write(&image_path, png_image()).unwrap();
+        let service = service().await.with_archive_dir(dir.path().to_path_buf());
+
+        let mut message = email(Some("<p>Zoë, the corner:</p><p><img src=\"cid:corner@mail\"></p><p>Спасибо</p>"));
+        message.attachments[0].downloaded = true;
+        message.attachments[0].local_path = Some(image_path.display().to_string());
+        service.save_email(&message).await.unwrap();
+        service.link_email_to_matter("email-1", "matter-1", None).await.unwrap();
+
+        let path = service.archive_email_as_pdf("email-1").await.unwrap();
+        let pdf = lopdf::Document::load(&path).unwrap();
+        let dicts: Vec<&lopdf::Dictionary> = pdf
+            .objects
+            .values()
+            .filter_map(|object| match object {
+                lopdf::Object::Stream(stream) => Some(&stream.dict),
+                lopdf::Object::Dictionary(dict) => Some(dict),
+                _ => None,
+            })
+            .collect();
+        let subtypes: Vec<&str> = dicts
+            .iter()
+            .filter_map(|dict| dict.get(b"Subtype").and_then(|subtype| subtype.as_name_str()).ok())
+            .collect();
+
+        assert!(subtypes.contains(&"Image"));
+        // The font travels with the archive instead of naming a Latin-1 base font
+        assert!(subtypes.contains(&"Type0"));
+        assert!(!subtypes.contains(&"Type1"));
+        assert!(dicts.iter().any(|dict| dict.has(b"FontFile2")));
+    }
+
+    #[test]
+    fn test_inline_images_become_pdf_images() {
+        let png = inline_image_xobject(&png_image()).unwrap();
+        assert_eq!((png.width.0, png.height.0), (2, 1));
+        // Transparency is flattened onto white
+        assert_eq!(png.image_data, vec![255, 0, 0, 255, 255, 255]);
+
+        // SOI, an APP0 segment, then a baseline frame of 640x480 in 3 components
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80,
+            0x03,
+        ];
+        let image = inline_image_xobject(&jpeg).unwrap();
+        assert_eq!((image.width.0, image.height.0), (640, 480));
+        assert!(matches!(image.image_filter, Some(ImageFilter::DCT)));
+        assert_eq!(image.image_data, jpeg.to_vec());
+
+        assert!(inline_image_xobject(b"GIF89a").is_err());
+    }
+
+    fn text(line: &str) -> BodyLine<'static> {
+        BodyLine::Text(line.to_string())
+    }
+
+    #[test]
+    fn test_html_body_places_inline_images() {
+        let html = "<html><head><style>p { color: red }</style></head><body>\
+                    <p>Attached are the <b>photos</b>.</p>\
+                    <p><img src=\"cid:corner@mail\"></p><ul><li>North side</li></ul></body></html>";
+        let message = email(Some(html));
+        let corner = BodyLine::Image(&message.attachments[0]);
+        assert_eq!(
+            email_body_lines(&message),
+            vec![text("Attached are the photos."), text(""), corner, text(""), text("- North side")]
+        );
+
+        let message = email(None);
+        let corner = BodyLine::Image(&message.attachments[0]);
+        assert_eq!(
+            email_body_lines(&message),
+            vec![text("Attached are the photos."), text("Thanks, Jane"), corner]
+        );
+    }
+
+    /// A mailbox whose sync token is the number of messages already handed out
+    #[derive(Default)]
+    struct FakeMailbox {
+        inbox: Mutex<Vec<Email>>,
+        expired: Mutex<bool>,
+        /// ("full" or "delta", messages returned) per fetch
+        fetches: Mutex<Vec<(&'static str, usize)>>,
+        /// (access token used, subject) per send
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    impl FakeMailbox {
+        fn deliver(&self, account_id: &str, n: usize) {
+            let mut message = email(None);
+            message.id = format!("email-{}", n);
+            message.account_id = account_id.to_string();
+            message.provider_message_id = format!("msg-{}", n);
+            message.date = Utc::now();
+            message.attachments.clear();
+            self.inbox.lock().unwrap().push(message);
+        }
+
+        fn changes(&self, kind: &'static str, from: usize) -> MailboxChanges {
+            let inbox = self.inbox.lock().unwrap();
+            let messages = inbox[from.min(inbox.len())..].to_vec();
+            self.fetches.lock().unwrap().push((kind, messages.len()));
+            MailboxChanges {
+                messages,
+                sync_token: Some(inbox.len().to_string()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MailboxClient for FakeMailbox {
+        async fn fetch_since(&self, _account: &EmailAccount, _since: DateTime<Utc>) -> Result<MailboxChanges> {
+            Ok(self.changes("full", 0))
+        }
+
+        async fn fetch_changes(&self, account: &EmailAccount, sync_token: &str) -> Result<MailboxChanges> {
+            if *self.expired.lock().unwrap() {
+                return Err(SyncTokenExpired { account_id: account.id.clone() }.into());
+            }
+            Ok(self.changes("delta", sync_token.parse()?))
+        }
+
+        async fn send(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push((account.access_token.clone(), draft.subject.clone()));
+            Ok(format!("sent-{}", sent.len()))
+        }
+
+        async fn fetch_attachment(
+            &self,
+            _account: &EmailAccount,
+            _email: &Email,
+            attachment: &EmailAttachment,
+        ) -> Result<Vec<u8>> {
+            Err(ServiceError::not_found("Attachment", &attachment.id).into())
+        }
+
+        async fn refresh_token(&self, _account: &EmailAccount) -> Result<RefreshedToken> {
+            Ok(RefreshedToken {
+                access_token: "refreshed".to_string(),
+                refresh_token: Some("rotated".to_string()),
+                expires_in: 3600,
+            })
+        }
+    }
+
+    async fn synced_account() -> (EmailIntegrationService, Arc<FakeMailbox>, String) {
+        let mailbox = Arc::new(FakeMailbox::default());
+        let service = service().await.with_mailbox(mailbox.clone());
+        let account = service
+            .connect_gmail_account("counsel@firm.example", "Counsel", "access", "refresh", 3600)
+            .await
+            .unwrap();
+        mailbox.deliver(&account.id, 1);
+        mailbox.deliver(&account.id, 2);
+        assert_eq!(service.sync_emails(&account.id).await.unwrap(), 2);
+        (service, mailbox, account.id)
+    }
+
+    #[tokio::test]
+    async fn test_second_sync_fetches_only_new_messages() {
+        let (service, mailbox, account_id) = synced_account().await;
+        assert_eq!(
+            service.get_email_account(&account_id).await.unwrap().sync_token.as_deref(),
+            Some("2")
+        );
+
+        mailbox.deliver(&account_id, 3);
+        assert_eq!(service.sync_emails(&account_id).await.unwrap(), 1);
+        assert_eq!(*mailbox.fetches.lock().unwrap(), vec![("full", 2), ("delta", 1)]);
+        assert_eq!(service.get_email("email-3").await.unwrap().subject, "Photos of the intersection");
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_falls_back_to_full_resync() {
+        let (service, mailbox, account_id) = synced_account().await;
+        *mailbox.expired.lock().unwrap() = true;
+
+        mailbox.deliver(&account_id, 3);
+        assert_eq!(service.sync_emails(&account_id).await.unwrap(), 1);
+        assert_eq!(*mailbox.fetches.lock().unwrap(), vec![("full", 2), ("full", 3)]);
+    }
+
+    fn template(variables: &[&str]) -> EmailTemplate {
+        EmailTemplate {
+            id: "status-update".to_string(),
+            name: "Status update".to_string(),
+            category: EmailTemplateCategory::StatusUpdate,
+            subject: "Update on {{case_caption}}".to_string(),
+            body_html: "<p>Dear {{client_name}}, your hearing is on {{hearing_date}}.</p>".to_string(),
+            variables: variables.iter().map(|v| v.to_string()).collect(),
+            attachments: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            usage_count: 0,
+        }
+    }
+
+    fn provided(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_variable_blocks_send() {
+        let service = service().await;
+        let template = service
+            .create_template(template(&["case_caption", "client_name", "hearing_date"]))
+            .await
+            .unwrap();
+        let variables = provided(&[("case_caption", "Commonwealth v. Doe"), ("client_name", "Jane")]);
+
+        let err = validate_template_variables(&template, &variables).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MissingTemplateVariables>(),
+            Some(&MissingTemplateVariables {
+                template_id: "status-update".to_string(),
+                missing: vec!["hearing_date".to_string()],
+            })
+        );
+
+        let err = service
+            .apply_template_to_draft("draft-1", "status-update", variables)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<MissingTemplateVariables>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_drafts_persist_until_deleted() {
+        let service = service().await;
+        let draft = service
+            .create_draft("account-1", vec![address("jane@example.com")], "Hello", "<p>Hi</p>", None)
+            .await
+            .unwrap();
+        service.create_template(template(&["client_name"])).await.unwrap();
+
+        let updated = service
+            .apply_template_to_draft(&draft.id, "status-update", provided(&[("client_name", "Jane")]))
+            .await
+            .unwrap();
+        let stored = service.get_draft(&draft.id).await.unwrap();
+        assert_eq!(stored.subject, updated.subject);
+        assert_eq!(stored.to[0].address, "jane@example.com");
+
+        service.delete_draft(&draft.id).await.unwrap();
+        assert!(service.get_draft(&draft.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tokens_stay_out_of_the_accounts_table() {
+        let (service, mailbox, account_id) = synced_account().await;
+        let (access, refresh): (String, Option<String>) =
+            sqlx::query_as("SELECT access_token, refresh_token FROM email_accounts WHERE id = ?")
+                .bind(&account_id)
+                .fetch_one(&service.db)
+                .await
+                .unwrap();
+        assert_eq!((access.as_str(), refresh), ("", None));
+
+        let draft = service
+            .create_draft(&account_id, vec![address("jane@example.com")], "Hearing moved", "<p>May 2</p>", None)
+            .await
+            .unwrap();
+        let sent = service.send_email(&draft.id).await.unwrap();
+        assert_eq!(sent.provider_message_id, "sent-1");
+        assert_eq!(*mailbox.sent.lock().unwrap(), vec![("access".to_string(), "Hearing moved".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_tokens_move_to_the_secret_store() {
+        let service = service().await;
+        let account = service
+            .connect_outlook_account("counsel@firm.example", "Counsel", "access", "refresh", 3600)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE email_accounts SET access_token = 'legacy', refresh_token = 'legacy-refresh' WHERE id = ?")
+            .bind(&account.id)
+            .execute(&service.db)
+            .await
+            .unwrap();
+
+        let loaded = service.get_email_account(&account.id).await.unwrap();
+        assert_eq!(loaded.access_token, "legacy");
+        assert_eq!(loaded.refresh_token.as_deref(), Some("legacy-refresh"));
+        let access: String = sqlx::query_scalar("SELECT access_token FROM email_accounts WHERE id = ?")
+            .bind(&account.id)
+            .fetch_one(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(access, "");
+    }
+
+    #[tokio::test]
+    async fn test_token_is_refreshed_only_near_expiry() {
+        use crate::utils::clock::{Clock, MockClock};
+
+        let connected_at = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(connected_at));
+        let service = service()
+            .await
+            .with_clock(clock.clone())
+            .with_mailbox(Arc::new(FakeMailbox::default()));
+        let account = service
+            .connect_outlook_account("counsel@firm.example", "Counsel", "access", "refresh", 3600)
+            .await
+            .unwrap();
+
+        // Ten minutes before expiry the token is still used
+        clock.advance(chrono::Duration::minutes(50));
+        let current = service.refresh_access_token(&account.id).await.unwrap();
+        assert_eq!(current.access_token, "access");
+        assert_eq!(current.token_expires_at, connected_at + chrono::Duration::hours(1));
+
+        // Inside the five-minute margin it is refreshed
+        clock.advance(chrono::Duration::minutes(6));
+        let refreshed = service.refresh_access_token(&account.id).await.unwrap();
+        assert_eq!(refreshed.access_token, "refreshed");
+        assert_eq!(refreshed.refresh_token.as_deref(), Some("rotated"));
+        assert_eq!(refreshed.token_expires_at, clock.now() + chrono::Duration::seconds(3600));
+    }
+
+    #[test]
+    fn test_all_variables_provided_passes() {
+        let template = template(&["case_caption", "client_name", "hearing_date"]);
+        let variables = provided(&[
+            ("case_caption", "Commonwealth v. Doe"),
+            ("client_name", "Jane"),
+            ("hearing_date", "May 2"),
+            // Extra variables are warned about, not rejected
+            ("attorney_name", "Sam"),
+        ]);
+        assert!(validate_template_variables(&template, &variables).is_ok());
+    }
+}
@@ -0,0 +1,849 @@
+// Gmail and Outlook mailbox access over their REST APIs
+// Gmail syncs by historyId through the Gmail API; Outlook by delta link
+// through Microsoft Graph. Both send, and refresh tokens, with the account's
+// own OAuth credentials.
+
+use crate::config::EmailOAuthConfig;
+use crate::services::email_integration::{
+    Email, EmailAccount, EmailAddress, EmailAttachment, EmailDraft, EmailProvider, EmailStatus, MailboxChanges,
+    MailboxClient, RefreshedToken, SyncTokenExpired,
+};
+use crate::services::service_error::ServiceError;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+const GMAIL_API: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+const GRAPH_API: &str = "https://graph.microsoft.com/v1.0/me";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const MICROSOFT_TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
+const OUTLOOK_SCOPES: &str = "offline_access Mail.ReadWrite Mail.Send";
+
+pub struct HttpMailboxClient {
+    http: Client,
+    oauth: EmailOAuthConfig,
+}
+
+impl HttpMailboxClient {
+    pub fn new(oauth: EmailOAuthConfig) -> Result<Self> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .context("Failed to build mail HTTP client")?;
+        Ok(Self { http, oauth })
+    }
+
+    async fn get(&self, account: &EmailAccount, url: &str, query: &[(&str, String)]) -> Result<Response> {
+        self.http
+            .get(url)
+            .bearer_auth(&account.access_token)
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("Request to {} failed", url))
+    }
+
+    // ============= Gmail =============
+
+    async fn gmail_fetch_since(&self, account: &EmailAccount, since: DateTime<Utc>) -> Result<MailboxChanges> {
+        // Take the cursor before listing so nothing delivered meanwhile is missed
+        let profile: GmailProfile = json(self.get(account, &format!("{}/profile", GMAIL_API), &[]).await?, "Gmail").await?;
+
+        let mut ids = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = vec![("q", format!("after:{}", since.timestamp())), ("maxResults", "500".to_string())];
+            if let Some(token) = page_token.take() {
+                query.push(("pageToken", token));
+            }
+            let page: GmailMessageList =
+                json(self.get(account, &format!("{}/messages", GMAIL_API), &query).await?, "Gmail").await?;
+            ids.extend(page.messages.into_iter().map(|m| m.id));
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(MailboxChanges {
+            messages: self.gmail_messages(account, ids).await?,
+            sync_token: Some(profile.history_id),
+        })
+    }
+
+    async fn gmail_fetch_changes(&self, account: &EmailAccount, sync_token: &str) -> Result<MailboxChanges> {
+        let mut ids = Vec::new();
+        let mut history_id = sync_token.to_string();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = vec![
+                ("startHistoryId", sync_token.to_string()),
+                ("historyTypes", "messageAdded".to_string()),
+            ];
+            if let Some(token) = page_token.take() {
+                query.push(("pageToken", token));
+            }
+            let response = self.get(account, &format!("{}/history", GMAIL_API), &query).await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Err(SyncTokenExpired { account_id: account.id.clone() }.into());
+            }
+            let page: GmailHistoryList = json(response, "Gmail").await?;
+            for record in page.history {
+                ids.extend(record.messages_added.into_iter().map(|added| added.message.id));
+            }
+            history_id = page.history_id;
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(MailboxChanges {
+            messages: self.gmail_messages(account, ids).await?,
+            sync_token: Some(history_id),
+        })
+    }
+
+    async fn gmail_messages(&self, account: &EmailAccount, ids: Vec<String>) -> Result<Vec<Email>> {
+        let mut messages = Vec::with_capacity(ids.len());
+        for id in ids {
+            let url = format!("{}/messages/{}", GMAIL_API, id);
+            let message: GmailMessage =
+                json(self.get(account, &url, &[("format", "full".to_string())]).await?, "Gmail").await?;
+            messages.push(message.into_email(&account.id));
+        }
+        Ok(messages)
+    }
+
+    async fn gmail_send(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
+        let raw = mime_message(account, draft)?;
+        let response = self
+            .http
+            .post(format!("{}/messages/send", GMAIL_API))
+            .bearer_auth(&account.access_token)
+            .json(&serde_json::json!({ "raw": general_purpose::URL_SAFE_NO_PAD.encode(raw) }))
+            .send()
+            .await
+            .context("Request to Gmail failed")?;
+        let sent: GmailMessageRef = json(response, "Gmail").await?;
+        Ok(sent.id)
+    }
+
+    // ============= Outlook =============
+
+    async fn outlook_delta(&self, account: &EmailAccount, first: Response) -> Result<MailboxChanges> {
+        let mut messages = Vec::new();
+        let mut response = first;
+        loop {
+            let page: GraphDeltaPage = json(response, "Microsoft Graph").await?;
+            messages.extend(
+                page.value
+                    .into_iter()
+                    .filter(|message| message.removed.is_none())
+                    .map(|message| message.into_email(&account.id)),
+            );
+            match (page.next_link, page.delta_link) {
+                (Some(next), _) => response = self.get(account, &next, &[]).await?,
+                (None, delta_link) => return Ok(MailboxChanges { messages, sync_token: delta_link }),
+            }
+        }
+    }
+
+    async fn outlook_send(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
+        let mut attachments = Vec::new();
+        for attachment in &draft.attachments {
+            attachments.push(serde_json::json!({
+                "@odata.type": "#microsoft.graph.fileAttachment",
+                "name": attachment.filename,
+                "contentType": attachment.mime_type,
+                "contentBytes": general_purpose::STANDARD.encode(read_attachment(attachment)?),
+            }));
+        }
+        let recipients = |addresses: &[EmailAddress]| {
+            addresses
+                .iter()
+                .map(|a| serde_json::json!({ "emailAddress": { "name": a.name, "address": a.address } }))
+                .collect::<Vec<_>>()
+        };
+        let message = serde_json::json!({
+            "subject": draft.subject,
+            "body": { "contentType": "HTML", "content": draft.body_html },
+            "toRecipients": recipients(&draft.to),
+            "ccRecipients": recipients(&draft.cc),
+            "bccRecipients": recipients(&draft.bcc),
+            "attachments": attachments,
+        });
+
+        // sendMail returns no id, so create the message and then send it
+        let response = self
+            .http
+            .post(format!("{}/messages", GRAPH_API))
+            .bearer_auth(&account.access_token)
+            .json(&message)
+            .send()
+            .await
+            .context("Request to Microsoft Graph failed")?;
+        let created: GraphMessageRef = json(response, "Microsoft Graph").await?;
+
+        let response = self
+            .http
+            .post(format!("{}/messages/{}/send", GRAPH_API, created.id))
+            .bearer_auth(&account.access_token)
+            .header(reqwest::header::CONTENT_LENGTH, 0)
+            .send()
+            .await
+            .context("Request to Microsoft Graph failed")?;
+        check_status(response, "Microsoft Graph").await?;
+        Ok(created.id)
+    }
+
+    async fn refresh(&self, token_url: &str, form: &[(&str, &str)], provider: &str) -> Result<RefreshedToken> {
+        let response = self
+            .http
+            .post(token_url)
+            .form(form)
+            .send()
+            .await
+            .with_context(|| format!("{} token refresh failed", provider))?;
+        let token: TokenResponse = json(response, provider).await?;
+        Ok(RefreshedToken {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_in: token.expires_in,
+        })
+    }
+}
+
+#[async_trait]
+impl MailboxClient for HttpMailboxClient {
+    async fn fetch_since(&self, account: &EmailAccount, since: DateTime<Utc>) -> Result<MailboxChanges> {
+        match account.provider {
+            EmailProvider::Gmail => self.gmail_fetch_since(account, since).await,
+            EmailProvider::Outlook => {
+                let filter = format!("receivedDateTime ge {}", since.format("%Y-%m-%dT%H:%M:%SZ"));
+                let url = format!("{}/mailFolders/inbox/messages/delta", GRAPH_API);
+                let first = self.get(account, &url, &[("$filter", filter)]).await?;
+                self.outlook_delta(account, first).await
+            }
+            _ => Err(unsupported(account)),
+        }
+    }
+
+    async fn fetch_changes(&self, account: &EmailAccount, sync_token: &str) -> Result<MailboxChanges> {
+        match account.provider {
+            EmailProvider::Gmail => self.gmail_fetch_changes(account, sync_token).await,
+            EmailProvider::Outlook => {
+                let first = self.get(account, sync_token, &[]).await?;
+                if first.status() == StatusCode::GONE {
+                    return Err(SyncTokenExpired { account_id: account.id.clone() }.into());
+                }
+                self.outlook_delta(account, first).await
+            }
+            _ => Err(unsupported(account)),
+        }
+    }
+
+    async fn send(&self, account: &EmailAccount, draft: &EmailDraft) -> Result<String> {
+        match account.provider {
+            EmailProvider::Gmail => self.gmail_send(account, draft).await,
+            EmailProvider::Outlook => self.outlook_send(account, draft).await,
+            _ => Err(unsupported(account)),
+        }
+    }
+
+    async fn fetch_attachment(
+        &self,
+        account: &EmailAccount,
+        email: &Email,
+        attachment: &EmailAttachment,
+    ) -> Result<Vec<u8>> {
+        match account.provider {
+            EmailProvider::Gmail => {
+                let url = format!(
+                    "{}/messages/{}/attachments/{}",
+                    GMAIL_API, email.provider_message_id, attachment.provider_attachment_id
+                );
+                let body: GmailBody = json(self.get(account, &url, &[]).await?, "Gmail").await?;
+                let data = body.data.unwrap_or_default();
+                general_purpose::URL_SAFE_NO_PAD
+                    .decode(data.trim_end_matches('='))
+                    .context("Gmail returned an undecodable attachment")
+            }
+            EmailProvider::Outlook => {
+                let url = format!(
+                    "{}/messages/{}/attachments/{}/$value",
+                    GRAPH_API, email.provider_message_id, attachment.provider_attachment_id
+                );
+                let response = check_status(self.get(account, &url, &[]).await?, "Microsoft Graph").await?;
+                let content = response.bytes().await.context("Failed to read Microsoft Graph attachment")?;
+                Ok(content.to_vec())
+            }
+            _ => Err(unsupported(account)),
+        }
+    }
+
+    async fn refresh_token(&self, account: &EmailAccount) -> Result<RefreshedToken> {
+        let refresh_token = account
+            .refresh_token
+            .as_deref()
+            .ok_or_else(|| ServiceError::validation(format!("Email account {} has no refresh token", account.id)))?;
+        let not_registered =
+            |provider: &str| ServiceError::validation(format!("No {} OAuth client is configured", provider));
+
+        match account.provider {
+            EmailProvider::Gmail => {
+                let client_id = self.oauth.gmail_client_id.as_deref().ok_or_else(|| not_registered("Google"))?;
+                let mut form = vec![
+                    ("client_id", client_id),
+                    ("refresh_token", refresh_token),
+                    ("grant_type", "refresh_token"),
+                ];
+                if let Some(secret) = self.oauth.gmail_client_secret.as_deref() {
+                    form.push(("client_secret", secret));
+                }
+                self.refresh(GOOGLE_TOKEN_URL, &form, "Google").await
+            }
+            EmailProvider::Outlook => {
+                let client_id = self.oauth.outlook_client_id.as_deref().ok_or_else(|| not_registered("Microsoft"))?;
+                let form = [
+                    ("client_id", client_id),
+                    ("refresh_token", refresh_token),
+                    ("grant_type", "refresh_token"),
+                    ("scope", OUTLOOK_SCOPES),
+                ];
+                self.refresh(MICROSOFT_TOKEN_URL, &form, "Microsoft").await
+            }
+            _ => Err(unsupported(account)),
+        }
+    }
+}
+
+fn unsupported(account: &EmailAccount) -> anyhow::Error {
+    ServiceError::validation(format!("{:?} mailboxes are not supported", account.provider)).into()
+}
+
+async fn check_status(response: Response, provider: &str) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("{} returned {}: {}", provider, status, body);
+    Err(match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ServiceError::Unauthorized(message),
+        StatusCode::TOO_MANY_REQUESTS => ServiceError::RateLimited(message),
+        status if status.is_server_error() => ServiceError::Unavailable(message),
+        _ => ServiceError::External(message),
+    }
+    .into())
+}
+
+async fn json<T: DeserializeOwned>(response: Response, provider: &str) -> Result<T> {
+    check_status(response, provider)
+        .await?
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {} response", provider))
+}
+
+fn read_attachment(attachment: &EmailAttachment) -> Result<Vec<u8>> {
+    let path = attachment.local_path.as_deref().ok_or_else(|| {
+        ServiceError::validation(format!("Attachment {} has not been downloaded", attachment.filename))
+    })?;
+    std::fs::read(path).with_context(|| format!("Failed to read attachment {}", path))
+}
+
+// ============= MIME =============
+
+/// An RFC 5322 message for the Gmail API: HTML body, files as
+/// base64 parts, non-ASCII headers as RFC 2047 encoded words
+fn mime_message(account: &EmailAccount, draft: &EmailDraft) -> Result<Vec<u8>> {
+    let mut headers = vec![
+        format!(
+            "From: {}",
+            format_address(&EmailAddress { name: Some(account.display_name.clone()), address: account.email_address.clone() })
+        ),
+        format!("Subject: {}", encode_header(&draft.subject)),
+        "MIME-Version: 1.0".to_string(),
+    ];
+    for (name, addresses) in [("To", &draft.to), ("Cc", &draft.cc), ("Bcc", &draft.bcc)] {
+        if !addresses.is_empty() {
+            let list: Vec<String> = addresses.iter().map(format_address).collect();
+            headers.push(format!("{}: {}", name, list.join(", ")));
+        }
+    }
+    if let Some(in_reply_to) = &draft.in_reply_to {
+        headers.push(format!("In-Reply-To: {}", in_reply_to));
+    }
+    if !draft.references.is_empty() {
+        headers.push(format!("References: {}", draft.references.join(" ")));
+    }
+
+    let html = format!(
+        "Content-Type: text/html; charset=UTF-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        wrap_base64(draft.body_html.as_bytes())
+    );
+
+    let mut message = headers.join("\r\n");
+    if draft.attachments.is_empty() {
+        message.push_str("\r\n");
+        message.push_str(&html);
+    } else {
+        let boundary = format!("=_{}", Uuid::new_v4().simple());
+        message.push_str(&format!("\r\nContent-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n", boundary));
+        message.push_str(&format!("--{}\r\n{}\r\n", boundary, html));
+        for attachment in &draft.attachments {
+            message.push_str(&format!(
+                "--{}\r\nContent-Type: {}; name=\"{}\"\r\nContent-Disposition: attachment; filename=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+                boundary,
+                attachment.mime_type,
+                encode_header(&attachment.filename),
+                encode_header(&attachment.filename),
+                wrap_base64(&read_attachment(attachment)?),
+            ));
+        }
+        message.push_str(&format!("--{}--\r\n", boundary));
+    }
+    Ok(message.into_bytes())
+}
+
+fn format_address(address: &EmailAddress) -> String {
+    match address.name.as_deref().filter(|name| !name.is_empty()) {
+        Some(name) if name.is_ascii() => format!("\"{}\" <{}>", name.replace('"', "'"), address.address),
+        Some(name) => format!("{} <{}>", encode_header(name), address.address),
+        None => address.address.clone(),
+    }
+}
+
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", general_purpose::STANDARD.encode(value))
+    }
+}
+
+/// Base64 in 76-column lines, as MIME requires
+fn wrap_base64(data: &[u8]) -> String {
+    let encoded = general_purpose::STANDARD.encode(data);
+    encoded
+        .as_bytes()
+        .chunks(76)
+        .map(|line| std::str::from_utf8(line).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Split an address header on the commas between addresses, not those
+/// inside quoted display names
+fn parse_address_list(value: &str) -> Vec<EmailAddress> {
+    let mut addresses = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => {
+                addresses.extend(parse_address(&current));
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    addresses.extend(parse_address(&current));
+    addresses
+}
+
+fn parse_address(value: &str) -> Option<EmailAddress> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    match (value.rfind('<'), value.rfind('>')) {
+        (Some(open), Some(close)) if open < close => {
+            let name = value[..open].trim().trim_matches('"').trim();
+            Some(EmailAddress {
+                name: (!name.is_empty()).then(|| name.to_string()),
+                address: value[open + 1..close].trim().to_string(),
+            })
+        }
+        _ => Some(EmailAddress { name: None, address: value.to_string() }),
+    }
+}
+
+// ============= Gmail API types =============
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailProfile {
+    history_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailMessageList {
+    #[serde(default)]
+    messages: Vec<GmailMessageRef>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GmailMessageRef {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistoryList {
+    #[serde(default)]
+    history: Vec<GmailHistory>,
+    history_id: String,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistory {
+    #[serde(default)]
+    messages_added: Vec<GmailMessageAdded>,
+}
+
+#[derive(Deserialize)]
+struct GmailMessageAdded {
+    message: GmailMessageRef,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailMessage {
+    id: String,
+    thread_id: Option<String>,
+    #[serde(default)]
+    label_ids: Vec<String>,
+    snippet: Option<String>,
+    /// Milliseconds since the epoch, as a string
+    internal_date: Option<String>,
+    payload: GmailPart,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailPart {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default)]
+    filename: String,
+    #[serde(default)]
+    headers: Vec<GmailHeader>,
+    body: Option<GmailBody>,
+    #[serde(default)]
+    parts: Vec<GmailPart>,
+}
+
+#[derive(Deserialize)]
+struct GmailHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailBody {
+    attachment_id: Option<String>,
+    #[serde(default)]
+    size: u64,
+    data: Option<String>,
+}
+
+impl GmailPart {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|h| h.name.eq_ignore_ascii_case(name)).map(|h| h.value.as_str())
+    }
+
+    fn decoded_body(&self) -> Option<String> {
+        let data = self.body.as_ref()?.data.as_deref()?;
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(data.trim_end_matches('=')).ok()?;
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Collect the first text and HTML bodies and every file part
+    fn walk(&self, text: &mut Option<String>, html: &mut Option<String>, attachments: &mut Vec<EmailAttachment>) {
+        let attachment_id = self.body.as_ref().and_then(|b| b.attachment_id.clone());
+        if let (false, Some(provider_attachment_id)) = (self.filename.is_empty(), attachment_id) {
+            let content_id = self.header("Content-ID").map(str::to_string);
+            let is_inline = self
+                .header("Content-Disposition")
+                .map(|d| d.trim_start().to_ascii_lowercase().starts_with("inline"))
+                .unwrap_or(content_id.is_some());
+            attachments.push(EmailAttachment {
+                id: Uuid::new_v4().to_string(),
+                filename: self.filename.clone(),
+                mime_type: self.mime_type.clone(),
+                size: self.body.as_ref().map(|b| b.size).unwrap_or(0),
+                content_id,
+                provider_attachment_id,
+                is_inline,
+                downloaded: false,
+                local_path: None,
+            });
+        } else if self.mime_type == "text/plain" && text.is_none() {
+            *text = self.decoded_body();
+        } else if self.mime_type == "text/html" && html.is_none() {
+            *html = self.decoded_body();
+        }
+        for part in &self.parts {
+            part.walk(text, html, attachments);
+        }
+    }
+}
+
+impl GmailMessage {
+    fn into_email(self, account_id: &str) -> Email {
+        let (mut text, mut html, mut attachments) = (None, None, Vec::new());
+        self.payload.walk(&mut text, &mut html, &mut attachments);
+        let header = |name: &str| self.payload.header(name).unwrap_or_default();
+        let date = self
+            .internal_date
+            .as_deref()
+            .and_then(|ms| ms.parse::<i64>().ok())
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+            .unwrap_or_else(Utc::now);
+
+        Email {
+            id: Uuid::new_v4().to_string(),
+            account_id: account_id.to_string(),
+            provider_message_id: self.id,
+            thread_id: self.thread_id,
+            from: parse_address(header("From"))
+                .unwrap_or(EmailAddress { name: None, address: String::new() }),
+            to: parse_address_list(header("To")),
+            cc: parse_address_list(header("Cc")),
+            bcc: parse_address_list(header("Bcc")),
+            reply_to: parse_address(header("Reply-To")),
+            subject: header("Subject").to_string(),
+            body_text: text,
+            body_html: html,
+            snippet: self.snippet,
+            date,
+            status: if self.label_ids.iter().any(|l| l == "UNREAD") { EmailStatus::Unread } else { EmailStatus::Read },
+            is_important: self.label_ids.iter().any(|l| l == "IMPORTANT"),
+            has_attachments: !attachments.is_empty(),
+            labels: self.label_ids,
+            matter_id: None,
+            matter_name: None,
+            is_client_communication: false,
+            confidence_score: None,
+            attachments,
+            synced_at: Utc::now(),
+            is_deleted: false,
+        }
+    }
+}
+
+// ============= Microsoft Graph types =============
+
+#[derive(Deserialize)]
+struct GraphDeltaPage {
+    #[serde(default)]
+    value: Vec<GraphMessage>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+    #[serde(rename = "@odata.deltaLink")]
+    delta_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GraphMessageRef {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphMessage {
+    id: String,
+    /// Present on messages deleted or moved out of the folder since the last sync
+    #[serde(rename = "@removed")]
+    removed: Option<serde_json::Value>,
+    conversation_id: Option<String>,
+    subject: Option<String>,
+    body_preview: Option<String>,
+    body: Option<GraphBody>,
+    from: Option<GraphRecipient>,
+    #[serde(default)]
+    to_recipients: Vec<GraphRecipient>,
+    #[serde(default)]
+    cc_recipients: Vec<GraphRecipient>,
+    #[serde(default)]
+    bcc_recipients: Vec<GraphRecipient>,
+    #[serde(default)]
+    reply_to: Vec<GraphRecipient>,
+    received_date_time: Option<DateTime<Utc>>,
+    is_read: Option<bool>,
+    importance: Option<String>,
+    has_attachments: Option<bool>,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphBody {
+    content_type: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphRecipient {
+    email_address: GraphEmailAddress,
+}
+
+#[derive(Deserialize)]
+struct GraphEmailAddress {
+    name: Option<String>,
+    address: Option<String>,
+}
+
+impl GraphRecipient {
+    fn into_address(self) -> EmailAddress {
+        EmailAddress {
+            name: self.email_address.name.filter(|name| !name.is_empty()),
+            address: self.email_address.address.unwrap_or_default(),
+        }
+    }
+}
+
+impl GraphMessage {
+    fn into_email(self, account_id: &str) -> Email {
+        let addresses = |recipients: Vec<GraphRecipient>| recipients.into_iter().map(GraphRecipient::into_address).collect();
+        let (body_text, body_html) = match self.body {
+            Some(body) if body.content_type.eq_ignore_ascii_case("html") => (None, Some(body.content)),
+            Some(body) => (Some(body.content), None),
+            None => (None, None),
+        };
+
+        Email {
+            id: Uuid::new_v4().to_string(),
+            account_id: account_id.to_string(),
+            provider_message_id: self.id,
+            thread_id: self.conversation_id,
+            from: self
+                .from
+                .map(GraphRecipient::into_address)
+                .unwrap_or(EmailAddress { name: None, address: String::new() }),
+            to: addresses(self.to_recipients),
+            cc: addresses(self.cc_recipients),
+            bcc: addresses(self.bcc_recipients),
+            reply_to: self.reply_to.into_iter().next().map(GraphRecipient::into_address),
+            subject: self.subject.unwrap_or_default(),
+            body_text,
+            body_html,
+            snippet: self.body_preview,
+            date: self.received_date_time.unwrap_or_else(Utc::now),
+            status: if self.is_read.unwrap_or(false) { EmailStatus::Read } else { EmailStatus::Unread },
+            is_important: self.importance.as_deref() == Some("high"),
+            // Graph lists attachments separately; they are fetched on download
+            has_attachments: self.has_attachments.unwrap_or(false),
+            labels: self.categories,
+            matter_id: None,
+            matter_name: None,
+            is_client_communication: false,
+            confidence_score: None,
+            attachments: Vec::new(),
+            synced_at: Utc::now(),
+            is_deleted: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gmail_message_maps_headers_bodies_and_attachments() {
+        let message: GmailMessage = serde_json::from_value(serde_json::json!({
+            "id": "18c1",
+            "threadId": "18c0",
+            "labelIds": ["INBOX", "UNREAD"],
+            "snippet": "Photos attached",
+            "internalDate": "1709566200000",
+            "payload": {
+                "mimeType": "multipart/mixed",
+                "headers": [
+                    { "name": "From", "value": "\"Client, Jane\" <jane@example.com>" },
+                    { "name": "To", "value": "\"Doe, Sam\" <sam@firm.example>, ops@firm.example" },
+                    { "name": "Subject", "value": "Intersection photos" }
+                ],
+                "parts": [
+                    { "mimeType": "text/html", "body": { "size": 11, "data": general_purpose::URL_SAFE_NO_PAD.encode("<p>Hi</p>") } },
+                    {
+                        "mimeType": "image/jpeg",
+                        "filename": "corner.jpg",
+                        "headers": [{ "name": "Content-Disposition", "value": "attachment; filename=corner.jpg" }],
+                        "body": { "attachmentId": "att-1", "size": 52113 }
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let email = message.into_email("account-1");
+        assert_eq!(email.from.name.as_deref(), Some("Client, Jane"));
+        assert_eq!(email.to.len(), 2);
+        assert_eq!(email.to[1].address, "ops@firm.example");
+        assert_eq!(email.body_html.as_deref(), Some("<p>Hi</p>"));
+        assert_eq!(email.status, EmailStatus::Unread);
+        assert_eq!(email.date.timestamp(), 1_709_566_200);
+        assert_eq!(email.attachments[0].provider_attachment_id, "att-1");
+        assert!(!email.attachments[0].is_inline);
+    }
+
+    #[test]
+    fn test_graph_delta_skips_removed_messages() {
+        let page: GraphDeltaPage = serde_json::from_value(serde_json::json!({
+            "value": [
+                {
+                    "id": "AAMk1",
+                    "subject": "Hearing moved",
+                    "from": { "emailAddress": { "name": "Clerk", "address": "clerk@court.example" } },
+                    "body": { "contentType": "html", "content": "<p>Now May 2</p>" },
+                    "receivedDateTime": "2024-03-04T15:30:00Z",
+                    "isRead": false
+                },
+                { "id": "AAMk2", "@removed": { "reason": "deleted" } }
+            ],
+            "@odata.deltaLink": "https://graph.microsoft.com/v1.0/me/mailFolders/inbox/messages/delta?$deltatoken=abc"
+        }))
+        .unwrap();
+
+        let kept: Vec<_> = page.value.into_iter().filter(|m| m.removed.is_none()).collect();
+        assert_eq!(kept.len(), 1);
+        let email = kept.into_iter().next().unwrap().into_email("account-1");
+        assert_eq!(email.from.address, "clerk@court.example");
+        assert_eq!(email.body_html.as_deref(), Some("<p>Now May 2</p>"));
+        assert!(page.delta_link.unwrap().contains("deltatoken"));
+    }
+
+    #[test]
+    fn test_non_ascii_subject_is_encoded() {
+        assert_eq!(encode_header("Status"), "Status");
+        assert_eq!(encode_header("Résumé"), format!("=?UTF-8?B?{}?=", general_purpose::STANDARD.encode("Résumé")));
+    }
+}
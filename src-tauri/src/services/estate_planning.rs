@@ -0,0 +1,260 @@
+// Estate Planning - Feature #23
+// Will generation with beneficiary and execution-formality validation
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Subscribing witnesses needed for a self-proving will (20 Pa.C.S. § 3132.1)
+pub const REQUIRED_WITNESSES: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Testator {
+    pub full_name: String,
+    pub county: String,
+    pub state: String,
+    pub executor: Option<String>,
+    pub alternate_executor: Option<String>,
+    pub witnesses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Gift {
+    /// Percentage of the residuary estate
+    Percentage(f64),
+    Cash(f64),
+    Specific(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bequest {
+    pub beneficiary: String,
+    pub relationship: Option<String>,
+    pub gift: Gift,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Check bequests and execution formalities before a will is generated
+pub fn validate_will(testator: &Testator, bequests: &[Bequest]) -> Result<()> {
+    if testator.full_name.trim().is_empty() {
+        anyhow::bail!("Testator name is required");
+    }
+
+    if !testator.executor.as_deref().is_some_and(|e| !e.trim().is_empty()) {
+        anyhow::bail!("An executor must be named");
+    }
+
+    let mut distinct: Vec<String> = testator
+        .witnesses
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    distinct.sort();
+    distinct.dedup();
+    if distinct.len() < REQUIRED_WITNESSES {
+        anyhow::bail!("At least {} distinct witnesses are required", REQUIRED_WITNESSES);
+    }
+
+    let mut shares: HashMap<String, f64> = HashMap::new();
+    let mut total = 0.0;
+    for bequest in bequests {
+        let name = bequest.beneficiary.trim();
+        if name.is_empty() {
+            anyhow::bail!("Every bequest must name a beneficiary");
+        }
+
+        match &bequest.gift {
+            Gift::Percentage(pct) => {
+                if !pct.is_finite() || *pct <= 0.0 {
+                    anyhow::bail!("Residuary share for {} must be positive", name);
+                }
+                if let Some(existing) = shares.insert(name.to_lowercase(), *pct) {
+                    if (existing - pct).abs() > f64::EPSILON {
+                        anyhow::bail!(
+                            "{} is named more than once with conflicting shares ({}% and {}%)",
+                            name, existing, pct
+                        );
+                    }
+                    anyhow::bail!("{} is named more than once for the same share", name);
+                }
+                total += pct;
+            }
+            Gift::Cash(amount) if !amount.is_finite() || *amount <= 0.0 => {
+                anyhow::bail!("Cash bequest to {} must be positive", name);
+            }
+            Gift::Specific(description) if description.trim().is_empty() => {
+                anyhow::bail!("Specific bequest to {} must describe the property", name);
+            }
+            _ => {}
+        }
+    }
+
+    if total > 100.0 + 1e-9 {
+        anyhow::bail!("Residuary shares total {}%, which exceeds 100%", total);
+    }
+
+    Ok(())
+}
+
+/// Validate and render a last will and testament
+pub fn generate_will(testator: &Testator, bequests: &[Bequest]) -> Result<Document> {
+    validate_will(testator, bequests)?;
+
+    let name = testator.full_name.trim();
+    let mut content = String::new();
+    content.push_str(&format!("LAST WILL AND TESTAMENT\nOF\n{}\n\n", name.to_uppercase()));
+    content.push_str(&format!(
+        "I, {}, of {} County, {}, being of sound mind, declare this to be my Last Will and \
+         Testament and revoke all wills and codicils previously made by me.\n\n",
+        name, testator.county, testator.state
+    ));
+
+    let described = |b: &Bequest| match &b.relationship {
+        Some(relationship) => format!("my {}, {}", relationship, b.beneficiary.trim()),
+        None => b.beneficiary.trim().to_string(),
+    };
+
+    let mut article = 1;
+    let specific: Vec<&Bequest> = bequests
+        .iter()
+        .filter(|b| !matches!(b.gift, Gift::Percentage(_)))
+        .collect();
+    if !specific.is_empty() {
+        content.push_str(&format!("ARTICLE {} - SPECIFIC BEQUESTS\n\n", article));
+        for bequest in specific {
+            match &bequest.gift {
+                Gift::Cash(amount) => content.push_str(&format!(
+                    "I give the sum of ${:.2} to {}, if he or she survives me.\n",
+                    amount,
+                    described(bequest)
+                )),
+                Gift::Specific(property) => content.push_str(&format!(
+                    "I give {} to {}, if he or she survives me.\n",
+                    property.trim(),
+                    described(bequest)
+                )),
+                Gift::Percentage(_) => {}
+            }
+        }
+        content.push('\n');
+        article += 1;
+    }
+
+    content.push_str(&format!("ARTICLE {} - RESIDUARY ESTATE\n\n", article));
+    let residuary: Vec<(&Bequest, f64)> = bequests
+        .iter()
+        .filter_map(|b| match b.gift {
+            Gift::Percentage(pct) => Some((b, pct)),
+            _ => None,
+        })
+        .collect();
+    if residuary.is_empty() {
+        content.push_str("I give the residue of my estate to my heirs at law.\n");
+    } else {
+        content.push_str("I give the residue of my estate as follows:\n");
+        for (bequest, pct) in &residuary {
+            content.push_str(&format!("    {}% to {};\n", pct, described(bequest)));
+        }
+        let total: f64 = residuary.iter().map(|(_, pct)| pct).sum();
+        if total < 100.0 - 1e-9 {
+            content.push_str(&format!(
+                "and the remaining {}% to my heirs at law.\n",
+                100.0 - total
+            ));
+        }
+    }
+    content.push('\n');
+    article += 1;
+
+    content.push_str(&format!("ARTICLE {} - EXECUTOR\n\n", article));
+    content.push_str(&format!(
+        "I appoint {} as Executor of this Will",
+        testator.executor.as_deref().unwrap_or_default().trim()
+    ));
+    match testator.alternate_executor.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(alternate) => content.push_str(&format!(
+            ". If my Executor is unable or unwilling to serve, I appoint {} as alternate Executor.\n\n",
+            alternate
+        )),
+        None => content.push_str(".\n\n"),
+    }
+
+    content.push_str(&format!(
+        "IN WITNESS WHEREOF, I have signed this Will at its end.\n\n\
+         ______________________________\n{}, Testator\n\n",
+        name
+    ));
+    content.push_str("Signed by the Testator in our presence:\n\n");
+    for witness in &testator.witnesses {
+        content.push_str(&format!("______________________________\n{}, Witness\n\n", witness.trim()));
+    }
+
+    Ok(Document {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: format!("Last Will and Testament of {}", name),
+        content,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testator() -> Testator {
+        Testator {
+            full_name: "Margaret A. Keller".to_string(),
+            county: "Allegheny".to_string(),
+            state: "Pennsylvania".to_string(),
+            executor: Some("Thomas Keller".to_string()),
+            alternate_executor: None,
+            witnesses: vec!["Alice Brown".to_string(), "Robert Green".to_string()],
+        }
+    }
+
+    fn share(beneficiary: &str, pct: f64) -> Bequest {
+        Bequest {
+            beneficiary: beneficiary.to_string(),
+            relationship: None,
+            gift: Gift::Percentage(pct),
+        }
+    }
+
+    #[test]
+    fn test_bequests_over_100_percent_error() {
+        let bequests = vec![share("Thomas Keller", 60.0), share("Anna Keller", 50.0)];
+
+        let err = generate_will(&testator(), &bequests).unwrap_err();
+        assert!(err.to_string().contains("exceeds 100%"));
+    }
+
+    #[test]
+    fn test_valid_will_renders_testator_name() {
+        let bequests = vec![
+            share("Thomas Keller", 50.0),
+            share("Anna Keller", 50.0),
+            Bequest {
+                beneficiary: "St. Paul's Food Pantry".to_string(),
+                relationship: None,
+                gift: Gift::Cash(5_000.0),
+            },
+        ];
+
+        let will = generate_will(&testator(), &bequests).unwrap();
+
+        assert_eq!(will.title, "Last Will and Testament of Margaret A. Keller");
+        assert!(will.content.contains("MARGARET A. KELLER"));
+        assert!(will.content.contains("I, Margaret A. Keller, of Allegheny County"));
+        assert!(will.content.contains("I appoint Thomas Keller as Executor"));
+    }
+}
@@ -1,8 +1,11 @@
 // Export service for PA eDocket Desktop
 
 use crate::domain::*;
+use crate::services::redaction::{redact_docket, redact_json, redact_search_result, RedactionPolicy};
+use crate::utils::file_utils::{hash_file, resolve_within};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use futures_util::stream::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -35,9 +38,23 @@ impl ExportService {
     }
 
     #[instrument(skip(self, data))]
-    pub async fn export_json(&self, data: &serde_json::Value, output_path: &str) -> Result<ExportManifest> {
+    pub async fn export_json(
+        &self,
+        data: &serde_json::Value,
+        output_path: &str,
+        redaction: Option<&RedactionPolicy>,
+    ) -> Result<ExportManifest> {
         info!("Exporting data to JSON: {}", output_path);
 
+        let redacted;
+        let data = match redaction {
+            Some(policy) => {
+                redacted = redact_json(data, policy);
+                &redacted
+            }
+            None => data,
+        };
+
         let full_path = self.resolve_output_path(output_path)?;
 
         // Write JSON data
@@ -63,6 +80,7 @@ impl ExportService {
                 let mut meta = HashMap::new();
                 meta.insert("record_count".to_string(), self.count_json_records(data).to_string());
                 meta.insert("format_version".to_string(), "1.0".to_string());
+                meta.insert("redacted".to_string(), redaction.is_some().to_string());
                 meta
             },
             audit_trail: vec![AuditEntry {
@@ -80,9 +98,23 @@ impl ExportService {
     }
 
     #[instrument(skip(self, data))]
-    pub async fn export_csv(&self, data: &[SearchResult], output_path: &str) -> Result<ExportManifest> {
+    pub async fn export_csv(
+        &self,
+        data: &[SearchResult],
+        output_path: &str,
+        redaction: Option<&RedactionPolicy>,
+    ) -> Result<ExportManifest> {
         info!("Exporting {} search results to CSV: {}", data.len(), output_path);
 
+        let redacted: Vec<SearchResult>;
+        let data = match redaction {
+            Some(policy) => {
+                redacted = data.iter().map(|r| redact_search_result(r, policy)).collect();
+                &redacted[..]
+            }
+            None => data,
+        };
+
         let full_path = self.resolve_output_path(output_path)?;
 
         // Create CSV content
@@ -133,6 +165,7 @@ impl ExportService {
                 meta.insert("record_count".to_string(), data.len().to_string());
                 meta.insert("format_version".to_string(), "1.0".to_string());
                 meta.insert("encoding".to_string(), "UTF-8".to_string());
+                meta.insert("redacted".to_string(), redaction.is_some().to_string());
                 meta
             },
             audit_trail: vec![AuditEntry {
@@ -149,10 +182,75 @@ impl ExportService {
         Ok(manifest)
     }
 
+    /// Stream records into `output_path` as they arrive, for exports too
+    /// large to hold in memory
+    #[instrument(skip(self, source))]
+    pub async fn export_stream_to_file<T: Serialize>(
+        &self,
+        source: impl Stream<Item = T>,
+        format: ExportType,
+        output_path: &str,
+    ) -> Result<ExportManifest> {
+        info!("Streaming {:?} export to {}", format, output_path);
+
+        let full_path = self.resolve_output_path(output_path)?;
+        let file = File::create(&full_path).with_context(|| format!("Failed to create {}", full_path.display()))?;
+        let record_count = export_stream(source, format.clone(), file).await?;
+
+        let size = fs::metadata(&full_path)?.len();
+        let hash = self.calculate_file_hash(&full_path)?;
+        let content_type = self.detect_content_type(&full_path);
+
+        let manifest = ExportManifest {
+            id: Uuid::new_v4(),
+            export_type: format,
+            created_at: Utc::now(),
+            files: vec![ExportFile {
+                path: full_path.to_string_lossy().to_string(),
+                filename: full_path.file_name().unwrap().to_string_lossy().to_string(),
+                size,
+                hash,
+                content_type,
+            }],
+            metadata: {
+                let mut meta = HashMap::new();
+                meta.insert("record_count".to_string(), record_count.to_string());
+                meta.insert("format_version".to_string(), "1.0".to_string());
+                meta.insert("encoding".to_string(), "UTF-8".to_string());
+                meta
+            },
+            audit_trail: vec![AuditEntry {
+                timestamp: Utc::now(),
+                action: "export_created".to_string(),
+                user: "system".to_string(),
+                details: format!("Streamed export of {} records to {}", record_count, output_path),
+            }],
+        };
+
+        self.save_manifest(&manifest).await?;
+
+        info!("Streamed export completed: {} records, {} bytes", record_count, size);
+        Ok(manifest)
+    }
+
     #[instrument(skip(self, docket))]
-    pub async fn export_pdf(&self, docket: &Docket, output_path: &str) -> Result<ExportManifest> {
+    pub async fn export_pdf(
+        &self,
+        docket: &Docket,
+        output_path: &str,
+        redaction: Option<&RedactionPolicy>,
+    ) -> Result<ExportManifest> {
         info!("Exporting docket to PDF: {}", output_path);
 
+        let redacted;
+        let docket = match redaction {
+            Some(policy) => {
+                redacted = redact_docket(docket, policy);
+                &redacted
+            }
+            None => docket,
+        };
+
         let full_path = self.resolve_output_path(output_path)?;
 
         // Generate HTML content for the docket
@@ -184,6 +282,7 @@ impl ExportService {
                 meta.insert("case_caption".to_string(), docket.caption.clone());
                 meta.insert("party_count".to_string(), docket.parties.len().to_string());
                 meta.insert("event_count".to_string(), docket.events.len().to_string());
+                meta.insert("redacted".to_string(), redaction.is_some().to_string());
                 meta
             },
             audit_trail: vec![AuditEntry {
@@ -290,7 +389,7 @@ impl ExportService {
         let path = if Path::new(output_path).is_absolute() {
             PathBuf::from(output_path)
         } else {
-            self.output_dir.join(output_path)
+            resolve_within(&self.output_dir, output_path)?
         };
 
         // Ensure parent directory exists
@@ -302,9 +401,7 @@ impl ExportService {
     }
 
     fn calculate_file_hash(&self, path: &Path) -> Result<String> {
-        let content = fs::read(path)?;
-        let hash = Sha256::digest(&content);
-        Ok(format!("{:x}", hash))
+        hash_file(path)
     }
 
     async fn save_manifest(&self, manifest: &ExportManifest) -> Result<()> {
@@ -454,6 +551,57 @@ impl ExportService {
     }
 }
 
+/// Records written between flushes of a streamed export
+const STREAM_FLUSH_EVERY: u64 = 1_000;
+
+/// Write each record from `source` to `writer` as CSV or a JSON array,
+/// flushing as it goes, so memory stays bounded however many records there
+/// are. CSV records must be flat; the header comes from the first record's
+/// field names. Returns the number of records written.
+pub async fn export_stream<T: Serialize>(
+    source: impl Stream<Item = T>,
+    format: ExportType,
+    writer: impl Write,
+) -> Result<u64> {
+    let mut source = std::pin::pin!(source);
+    let mut count = 0u64;
+
+    match format {
+        ExportType::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            while let Some(record) = source.next().await {
+                csv.serialize(&record).context("Failed to write CSV record")?;
+                count += 1;
+                if count % STREAM_FLUSH_EVERY == 0 {
+                    csv.flush()?;
+                }
+            }
+            csv.flush()?;
+        }
+        ExportType::Json => {
+            let mut out = BufWriter::new(writer);
+            out.write_all(b"[")?;
+            while let Some(record) = source.next().await {
+                if count > 0 {
+                    out.write_all(b",")?;
+                }
+                out.write_all(b"\n")?;
+                serde_json::to_writer(&mut out, &record).context("Failed to write JSON record")?;
+                count += 1;
+                if count % STREAM_FLUSH_EVERY == 0 {
+                    out.flush()?;
+                }
+            }
+            out.write_all(b"\n]\n")?;
+            out.flush()?;
+        }
+        other => anyhow::bail!("{:?} exports cannot be streamed", other),
+    }
+
+    debug!("Streamed {} records", count);
+    Ok(count)
+}
+
 // Data structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportManifest {
@@ -489,3 +637,107 @@ pub struct AuditEntry {
     pub user: String,
     pub details: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    const RECORDS: u64 = 100_000;
+
+    #[derive(Serialize, Deserialize)]
+    struct Row {
+        id: u64,
+        caption: String,
+        county: String,
+    }
+
+    /// Counts bytes as they reach the file
+    struct CountingWriter {
+        file: File,
+        written: Arc<AtomicU64>,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = self.file.write(buf)?;
+            self.written.fetch_add(n as u64, Ordering::SeqCst);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.file.flush()
+        }
+    }
+
+    /// Records generated on demand; by the last one, most of the output
+    /// must already have been written out
+    fn synthetic_rows(written: Arc<AtomicU64>) -> impl Stream<Item = Row> {
+        stream::iter(0..RECORDS).map(move |id| {
+            if id == RECORDS - 1 {
+                assert!(written.load(Ordering::SeqCst) > RECORDS * 20, "export buffered instead of streaming");
+            }
+            Row {
+                id,
+                caption: format!("Commonwealth v. Defendant {}", id),
+                county: "Philadelphia".to_string(),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_stream_100k_records_to_csv_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for (format, name) in [(ExportType::Csv, "cases.csv"), (ExportType::Json, "cases.json")] {
+            let path = dir.path().join(name);
+            let written = Arc::new(AtomicU64::new(0));
+            let writer = CountingWriter {
+                file: File::create(&path).unwrap(),
+                written: written.clone(),
+            };
+
+            let count = export_stream(synthetic_rows(written), format.clone(), writer).await.unwrap();
+            assert_eq!(count, RECORDS);
+
+            let parsed: Vec<Row> = match format {
+                ExportType::Csv => csv::Reader::from_path(&path)
+                    .unwrap()
+                    .deserialize()
+                    .collect::<Result<_, _>>()
+                    .unwrap(),
+                _ => serde_json::from_reader(File::open(&path).unwrap()).unwrap(),
+            };
+            assert_eq!(parsed.len() as u64, RECORDS);
+            assert_eq!(parsed[42].caption, "Commonwealth v. Defendant 42");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_export_writes_the_redacted_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = ExportService::new(dir.path().to_path_buf());
+        service.initialize().await.unwrap();
+        let data = serde_json::json!([{ "caption": "Commonwealth v. Smith", "sid": "123-45-678-9" }]);
+
+        let manifest = service
+            .export_json(&data, "dockets.json", Some(&RedactionPolicy::default()))
+            .await
+            .unwrap();
+
+        let file = &manifest.files[0];
+        let written = fs::read(&file.path).unwrap();
+        let exported: serde_json::Value = serde_json::from_slice(&written).unwrap();
+        assert_eq!(exported[0]["sid"], crate::services::redaction::REDACTED);
+        assert_eq!(file.hash, format!("{:x}", Sha256::digest(&written)));
+        assert_eq!(manifest.metadata["redacted"], "true");
+    }
+
+    #[tokio::test]
+    async fn test_pdf_cannot_be_streamed() {
+        let rows = stream::iter(Vec::<Row>::new());
+        assert!(export_stream(rows, ExportType::Pdf, Vec::new()).await.is_err());
+    }
+}
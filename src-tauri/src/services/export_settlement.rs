@@ -1,10 +1,43 @@
 // Settlement Export Service
 // Handles PDF, Excel, and Word export for settlement calculations
 
+use crate::services::export::ExportType;
 use crate::services::settlement_calculator::*;
 use anyhow::{Context, Result};
+use printpdf::{BuiltinFont, Mm, PdfDocument};
 use std::path::PathBuf;
 
+/// Tolerance used when reconciling worksheet line items to the calculated total
+const RECONCILIATION_TOLERANCE: f64 = 0.01;
+
+/// Present value horizon used by the calculator for future economic damages
+const PRESENT_VALUE_YEARS: u32 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+enum WorksheetRowKind {
+    Item,
+    Subtotal,
+    Total,
+}
+
+impl WorksheetRowKind {
+    fn label(&self) -> &'static str {
+        match self {
+            WorksheetRowKind::Item => "Item",
+            WorksheetRowKind::Subtotal => "Subtotal",
+            WorksheetRowKind::Total => "Total",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WorksheetRow {
+    kind: WorksheetRowKind,
+    category: &'static str,
+    description: String,
+    amount: f64,
+}
+
 pub struct SettlementExportService;
 
 impl SettlementExportService {
@@ -457,6 +490,202 @@ impl SettlementExportService {
         Ok(csv)
     }
 
+    // ============= DAMAGES WORKSHEET =============
+
+    /// Export an itemized damages worksheet that reconciles to the calculated total
+    pub fn export_damages_worksheet(
+        &self,
+        calc: &SettlementCalculation,
+        format: ExportType,
+    ) -> Result<Vec<u8>> {
+        let rows = self.build_worksheet_rows(calc)?;
+
+        match format {
+            ExportType::Csv => Ok(self.render_worksheet_csv(calc, &rows).into_bytes()),
+            ExportType::Pdf => self.render_worksheet_pdf(calc, &rows),
+            other => anyhow::bail!("Unsupported damages worksheet format: {:?}", other),
+        }
+    }
+
+    fn build_worksheet_rows(&self, calc: &SettlementCalculation) -> Result<Vec<WorksheetRow>> {
+        let econ = &calc.economic_damages;
+        let non_econ = &calc.non_economic_damages;
+        let mut rows = Vec::new();
+
+        let item = |category: &'static str, description: &str, amount: f64| WorksheetRow {
+            kind: WorksheetRowKind::Item,
+            category,
+            description: description.to_string(),
+            amount,
+        };
+
+        // Economic - past losses at face value
+        rows.push(item("Economic", "Past Medical Expenses", econ.past_medical_expenses));
+        rows.push(item("Economic", "Past Lost Wages", econ.past_lost_wages));
+        rows.push(item("Economic", "Property Damage", econ.property_damage));
+        rows.push(item("Economic", "Other Expenses", econ.other_expenses));
+
+        // Economic - future losses at nominal value, then discounted to present value
+        rows.push(item("Economic", "Future Medical Expenses", econ.future_medical_expenses));
+        rows.push(item("Economic", "Future Lost Earning Capacity", econ.future_lost_earning_capacity));
+        rows.push(item("Economic", "Rehabilitation Costs", econ.rehabilitation_costs));
+        rows.push(item("Economic", "Home Modification Costs", econ.home_modification_costs));
+        rows.push(item("Economic", "Assistive Device Costs", econ.assistive_device_costs));
+        rows.push(item("Economic", "Transportation Costs", econ.transportation_costs));
+        rows.push(item(
+            "Economic",
+            &format!(
+                "Present Value Discount (${:.2} / (1 + {:.2}%)^{})",
+                econ.total_future_economic,
+                econ.discount_rate * 100.0,
+                PRESENT_VALUE_YEARS
+            ),
+            econ.present_value_future_damages - econ.total_future_economic,
+        ));
+        let economic_subtotal = self.push_subtotal(&mut rows, "Economic", "Total Economic Damages");
+
+        // Non-economic
+        rows.push(item("Non-Economic", "Pain and Suffering", non_econ.pain_and_suffering));
+        rows.push(item("Non-Economic", "Emotional Distress", non_econ.emotional_distress));
+        rows.push(item("Non-Economic", "Loss of Consortium", non_econ.loss_of_consortium));
+        rows.push(item("Non-Economic", "Loss of Enjoyment of Life", non_econ.loss_of_enjoyment_of_life));
+        rows.push(item("Non-Economic", "Disfigurement", non_econ.disfigurement));
+        rows.push(item("Non-Economic", "Loss of Reputation", non_econ.loss_of_reputation));
+        let non_economic_subtotal = self.push_subtotal(&mut rows, "Non-Economic", "Total Non-Economic Damages");
+
+        // Punitive
+        let punitive = calc.punitive_damages.as_ref().map(|p| p.amount).unwrap_or(0.0);
+        rows.push(item("Punitive", "Punitive Damages", punitive));
+        self.push_subtotal(&mut rows, "Punitive", "Total Punitive Damages");
+
+        // Comparative fault reduction
+        let gross = economic_subtotal + non_economic_subtotal + punitive;
+        let defendant_share = calc.liability_analysis.defendant_liability_percentage / 100.0;
+        rows.push(item(
+            "Adjustments",
+            &format!(
+                "Comparative Fault Reduction ({:.0}% plaintiff fault)",
+                100.0 - calc.liability_analysis.defendant_liability_percentage
+            ),
+            gross * defendant_share - gross,
+        ));
+        self.push_subtotal(&mut rows, "Adjustments", "Total Adjustments");
+
+        let total: f64 = rows
+            .iter()
+            .filter(|r| r.kind == WorksheetRowKind::Item)
+            .map(|r| r.amount)
+            .sum();
+
+        if (total - calc.total_damages).abs() > RECONCILIATION_TOLERANCE {
+            anyhow::bail!(
+                "Damages worksheet total ${:.2} does not reconcile to calculated total ${:.2}",
+                total,
+                calc.total_damages
+            );
+        }
+
+        rows.push(WorksheetRow {
+            kind: WorksheetRowKind::Total,
+            category: "Total",
+            description: "TOTAL DAMAGES".to_string(),
+            amount: total,
+        });
+
+        Ok(rows)
+    }
+
+    fn push_subtotal(&self, rows: &mut Vec<WorksheetRow>, category: &'static str, description: &str) -> f64 {
+        let subtotal = rows
+            .iter()
+            .filter(|r| r.kind == WorksheetRowKind::Item && r.category == category)
+            .map(|r| r.amount)
+            .sum();
+
+        rows.push(WorksheetRow {
+            kind: WorksheetRowKind::Subtotal,
+            category,
+            description: description.to_string(),
+            amount: subtotal,
+        });
+
+        subtotal
+    }
+
+    fn render_worksheet_csv(&self, calc: &SettlementCalculation, rows: &[WorksheetRow]) -> String {
+        let mut csv = String::new();
+        csv.push_str(&format!(
+            "# Damages Worksheet: {} v. {} (Matter {})\n",
+            calc.plaintiff_name, calc.defendant_name, calc.matter_id
+        ));
+        csv.push_str("Type,Category,Description,Amount\n");
+
+        for row in rows {
+            csv.push_str(&format!(
+                "{},{},\"{}\",{:.2}\n",
+                row.kind.label(),
+                row.category,
+                row.description.replace('"', "\"\""),
+                row.amount
+            ));
+        }
+
+        csv
+    }
+
+    fn render_worksheet_pdf(&self, calc: &SettlementCalculation, rows: &[WorksheetRow]) -> Result<Vec<u8>> {
+        let page_width = Mm(215.9);
+        let page_height = Mm(279.4);
+        let margin = 20.0;
+        let line_height = 6.0;
+
+        let (doc, first_page, first_layer) =
+            PdfDocument::new("Damages Worksheet", page_width, page_height, "Worksheet");
+        let font = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .context("Failed to load PDF font")?;
+        let bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .context("Failed to load PDF font")?;
+
+        let mut layer = doc.get_page(first_page).get_layer(first_layer);
+        let mut y = page_height.0 - margin;
+
+        layer.use_text("DAMAGES WORKSHEET", 16.0, Mm(margin), Mm(y), &bold);
+        y -= line_height * 1.5;
+        layer.use_text(
+            format!("{} v. {} | Matter {}", calc.plaintiff_name, calc.defendant_name, calc.matter_id),
+            10.0,
+            Mm(margin),
+            Mm(y),
+            &font,
+        );
+        y -= line_height * 2.0;
+
+        for row in rows {
+            if y < margin {
+                let (page, page_layer) = doc.add_page(page_width, page_height, "Worksheet");
+                layer = doc.get_page(page).get_layer(page_layer);
+                y = page_height.0 - margin;
+            }
+
+            let (indent, row_font) = match row.kind {
+                WorksheetRowKind::Item => (margin + 8.0, &font),
+                WorksheetRowKind::Subtotal | WorksheetRowKind::Total => (margin, &bold),
+            };
+
+            layer.use_text(row.description.clone(), 10.0, Mm(indent), Mm(y), row_font);
+            layer.use_text(format!("${:.2}", row.amount), 10.0, Mm(page_width.0 - margin - 35.0), Mm(y), row_font);
+            y -= line_height;
+
+            if row.kind == WorksheetRowKind::Subtotal {
+                y -= line_height / 2.0;
+            }
+        }
+
+        doc.save_to_bytes().context("Failed to render damages worksheet PDF")
+    }
+
     // ============= WORD GENERATION =============
 
     /// Generate Word document
@@ -510,3 +739,160 @@ impl SettlementExportService {
         Ok(md)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample_calculation() -> SettlementCalculation {
+        let service_discount = 0.03;
+        let total_future_economic = 50_000.0 + 100_000.0;
+        let present_value_future_damages = total_future_economic / (1.0_f64 + service_discount).powi(30);
+        let total_past_economic = 40_000.0 + 20_000.0 + 5_000.0 + 1_000.0;
+        let total_economic = total_past_economic + present_value_future_damages;
+
+        let pain_and_suffering = total_economic * 2.5;
+        let emotional_distress = pain_and_suffering * 0.3;
+        let loss_of_enjoyment = total_economic * 0.5;
+        let total_non_economic = pain_and_suffering + emotional_distress + loss_of_enjoyment;
+
+        SettlementCalculation {
+            id: "calc-1".to_string(),
+            matter_id: "matter-1".to_string(),
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Doe".to_string(),
+            defendant_name: "Acme Corp.".to_string(),
+            incident_date: None,
+            economic_damages: EconomicDamages {
+                past_medical_expenses: 40_000.0,
+                future_medical_expenses: 50_000.0,
+                medical_expense_details: Vec::new(),
+                past_lost_wages: 20_000.0,
+                future_lost_earning_capacity: 100_000.0,
+                lost_benefits: 0.0,
+                property_damage: 5_000.0,
+                rehabilitation_costs: 0.0,
+                home_modification_costs: 0.0,
+                assistive_device_costs: 0.0,
+                transportation_costs: 0.0,
+                other_expenses: 1_000.0,
+                total_past_economic,
+                total_future_economic,
+                total_economic,
+                discount_rate: service_discount,
+                present_value_future_damages,
+            },
+            non_economic_damages: NonEconomicDamages {
+                pain_and_suffering,
+                emotional_distress,
+                loss_of_consortium: 0.0,
+                loss_of_enjoyment_of_life: loss_of_enjoyment,
+                disfigurement: 0.0,
+                loss_of_reputation: 0.0,
+                total_non_economic,
+                methodology: NonEconomicMethodology::Multiplier,
+                multiplier: 2.5,
+                per_diem_rate: None,
+                days_in_pain: None,
+            },
+            punitive_damages: None,
+            total_damages: (total_economic + total_non_economic) * 0.8,
+            settlement_range: SettlementRange {
+                low_estimate: 0.0,
+                mid_estimate: 0.0,
+                high_estimate: 0.0,
+                confidence_level: 0.0,
+                range_explanation: String::new(),
+            },
+            liability_analysis: LiabilityAnalysis {
+                plaintiff_liability_percentage: 20.0,
+                defendant_liability_percentage: 80.0,
+                comparative_negligence_applies: true,
+                jurisdiction: "Pennsylvania".to_string(),
+                liability_strength: LiabilityStrength::Strong,
+                key_liability_factors: Vec::new(),
+            },
+            defendant_apportionment: Vec::new(),
+            risk_assessment: RiskAssessment {
+                trial_risk_score: 0.2,
+                strengths: Vec::new(),
+                weaknesses: Vec::new(),
+                trial_cost_estimate: 0.0,
+                expected_trial_duration_months: 18,
+                probability_of_win: 0.8,
+                expected_trial_value: 0.0,
+            },
+            comparable_verdicts: Vec::new(),
+            jurisdiction_rules: None,
+            adjusted_for_caps: false,
+            cap_adjustments: None,
+            ai_analysis: None,
+            reconciled_estimate: None,
+            medical_timeline: None,
+            recommended_demand: 0.0,
+            minimum_settlement: 0.0,
+            target_settlement: 0.0,
+            rationale: String::new(),
+            negotiation_strategy: Vec::new(),
+            offers_received: Vec::new(),
+            counteroffers_made: Vec::new(),
+            current_negotiation_round: 0,
+            prejudgment_interest: None,
+            postjudgment_interest_rate: None,
+            structured_settlement_option: None,
+            estimated_attorney_fees: 0.0,
+            litigation_costs_to_date: 0.0,
+            projected_additional_costs: 0.0,
+            net_to_client: 0.0,
+            calculated_at: Utc::now(),
+            calculated_by: "tester".to_string(),
+            version: "2.0.0".to_string(),
+            last_updated: Utc::now(),
+            calculation_notes: Vec::new(),
+            revision: 1,
+            previous_calculation_id: None,
+            inputs: None,
+        }
+    }
+
+    #[test]
+    fn test_worksheet_csv_items_sum_to_total() {
+        let calc = sample_calculation();
+        let bytes = SettlementExportService::new()
+            .export_damages_worksheet(&calc, ExportType::Csv)
+            .unwrap();
+        let csv = String::from_utf8(bytes).unwrap();
+
+        let item_sum: f64 = csv
+            .lines()
+            .filter(|line| line.starts_with("Item,"))
+            .map(|line| line.rsplit(',').next().unwrap().parse::<f64>().unwrap())
+            .sum();
+
+        // Line items are rounded to cents, so allow a cent per row
+        let item_count = csv.lines().filter(|line| line.starts_with("Item,")).count();
+        assert!((item_sum - calc.total_damages).abs() <= 0.01 * item_count as f64);
+        assert!(csv.contains("Subtotal,Economic"));
+        assert!(csv.contains("Total,Total"));
+    }
+
+    #[test]
+    fn test_worksheet_rejects_unreconciled_total() {
+        let mut calc = sample_calculation();
+        calc.total_damages += 1_000.0;
+
+        let result = SettlementExportService::new().export_damages_worksheet(&calc, ExportType::Csv);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_worksheet_pdf_renders() {
+        let calc = sample_calculation();
+        let bytes = SettlementExportService::new()
+            .export_damages_worksheet(&calc, ExportType::Pdf)
+            .unwrap();
+
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+}
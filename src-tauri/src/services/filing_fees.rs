@@ -0,0 +1,154 @@
+// Filing fees - itemized court fees for an e-filing, from the per-court fee
+// schedule in courts.yaml. A document type's base fee covers its first
+// `included_pages`; each page past that adds the per-page fee, and the
+// court's service fees are charged once per filing.
+
+use crate::config::CourtsConfig;
+use crate::services::service_error::ServiceError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeItem {
+    pub description: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilingFee {
+    pub court_id: String,
+    pub document_type: String,
+    pub pages: u32,
+    pub items: Vec<FeeItem>,
+    pub total: f64,
+    /// Fee bill the amounts come from
+    pub source: String,
+}
+
+/// Itemized fee for filing a `pages`-page `doc_type` in `court_id`. Errors
+/// when the court is unknown, its schedule cites no source, or the schedule
+/// has no fee for the document type.
+pub fn calculate_filing_fee(courts: &CourtsConfig, court_id: &str, doc_type: &str, pages: u32) -> Result<FilingFee> {
+    let court = courts
+        .courts
+        .get(court_id)
+        .ok_or_else(|| ServiceError::not_found("Court", court_id))?;
+    let schedule = &court.fee_schedule;
+    let source = schedule
+        .source
+        .as_deref()
+        .filter(|source| !source.trim().is_empty())
+        .ok_or_else(|| ServiceError::validation(format!("{} has no fee schedule with a cited source", court.name)))?;
+    let fee = schedule
+        .documents
+        .iter()
+        .find(|(document_type, _)| document_type.eq_ignore_ascii_case(doc_type))
+        .map(|(_, fee)| fee)
+        .ok_or_else(|| ServiceError::validation(format!("{} has no filing fee for {} documents", court.name, doc_type)))?;
+
+    let mut items = vec![FeeItem {
+        description: format!("Filing fee: {}", doc_type),
+        amount: fee.base,
+    }];
+    let extra_pages = pages.saturating_sub(fee.included_pages);
+    if extra_pages > 0 && fee.per_page > 0.0 {
+        items.push(FeeItem {
+            description: format!("{} additional page(s) at ${:.2}", extra_pages, fee.per_page),
+            amount: round_cents(extra_pages as f64 * fee.per_page),
+        });
+    }
+    items.extend(schedule.service_fees.iter().map(|service| FeeItem {
+        description: service.name.clone(),
+        amount: service.amount,
+    }));
+
+    let total = round_cents(items.iter().map(|item| item.amount).sum());
+    Ok(FilingFee {
+        court_id: court_id.to_string(),
+        document_type: doc_type.to_string(),
+        pages,
+        items,
+        total,
+        source: source.to_string(),
+    })
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CourtConfig, DocumentFeeConfig, FeeScheduleConfig, ServiceFeeConfig};
+
+    fn courts() -> CourtsConfig {
+        let mut court: CourtConfig = serde_yaml::from_str(
+            r#"
+name: "Court of Common Pleas"
+level: "CP"
+jurisdiction: "Pennsylvania"
+formatting:
+  margins: { top: "1.0in", bottom: "1.0in", left: "1.0in", right: "1.0in" }
+  font: { family: "Times New Roman", size: "12pt", line_spacing: "double" }
+  caption: { format: "standard_pa", include_docket: true, include_court: true, include_county: true, include_judge: true }
+  signature: { attorney_name: true, attorney_id: true, firm_name: true, address: true, phone: true, email: true }
+  service_certificate: true
+  page_limits: {}
+efiling: null
+"#,
+        )
+        .unwrap();
+        court.fee_schedule = FeeScheduleConfig {
+            source: Some("Test fee bill".to_string()),
+            documents: [(
+                "motion".to_string(),
+                DocumentFeeConfig {
+                    base: 25.0,
+                    per_page: 0.5,
+                    included_pages: 10,
+                },
+            )]
+            .into(),
+            service_fees: vec![ServiceFeeConfig {
+                name: "PACFile convenience fee".to_string(),
+                amount: 5.0,
+            }],
+        };
+
+        let mut courts = CourtsConfig::default();
+        courts.courts.insert("cp".to_string(), court);
+        courts
+    }
+
+    #[test]
+    fn test_multi_page_filing_adds_per_page_fee() {
+        let fee = calculate_filing_fee(&courts(), "cp", "Motion", 23).unwrap();
+
+        let amounts: Vec<f64> = fee.items.iter().map(|item| item.amount).collect();
+        assert_eq!(amounts, vec![25.0, 6.5, 5.0]);
+        assert_eq!(fee.items[1].description, "13 additional page(s) at $0.50");
+        assert_eq!(fee.total, 36.5);
+
+        let short = calculate_filing_fee(&courts(), "cp", "motion", 4).unwrap();
+        assert_eq!(short.total, 30.0);
+    }
+
+    #[test]
+    fn test_unknown_document_type_errors() {
+        let err = calculate_filing_fee(&courts(), "cp", "appeal", 5).unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "validation");
+
+        let err = calculate_filing_fee(&courts(), "superior", "motion", 5).unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "not_found");
+    }
+
+    #[test]
+    fn test_schedule_without_a_source_is_not_used() {
+        let mut courts = courts();
+        courts.courts.get_mut("cp").unwrap().fee_schedule.source = None;
+
+        let err = calculate_filing_fee(&courts, "cp", "motion", 5).unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "validation");
+    }
+}
@@ -0,0 +1,285 @@
+// Global Search - one full-text index across matter notes, emails, contracts and dockets
+// Backed by the `global_search_index` FTS5 table; notes and cached dockets are
+// indexed by triggers, emails and contracts by their services on save
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+/// Maximum hits returned for a single query
+const MAX_HITS: i64 = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchScope {
+    MatterNote,
+    Email,
+    Contract,
+    Docket,
+}
+
+impl SearchScope {
+    pub const ALL: [SearchScope; 4] = [
+        SearchScope::MatterNote,
+        SearchScope::Email,
+        SearchScope::Contract,
+        SearchScope::Docket,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchScope::MatterNote => "matter_note",
+            SearchScope::Email => "email",
+            SearchScope::Contract => "contract",
+            SearchScope::Docket => "docket",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalHit {
+    pub scope: SearchScope,
+    pub entity_id: String,
+    pub matter_id: Option<String>,
+    pub title: String,
+    /// Matching excerpt with terms wrapped in `[` `]`
+    pub snippet: String,
+    /// Higher is more relevant
+    pub score: f64,
+}
+
+pub struct GlobalSearchService {
+    db: SqlitePool,
+}
+
+impl GlobalSearchService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Add or replace the indexed text for one record
+    pub async fn index(
+        &self,
+        scope: SearchScope,
+        entity_id: &str,
+        matter_id: Option<&str>,
+        title: &str,
+        body: &str,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query("DELETE FROM global_search_index WHERE scope = ? AND entity_id = ?")
+            .bind(scope.as_str())
+            .bind(entity_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO global_search_index (scope, entity_id, matter_id, title, body) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(scope.as_str())
+        .bind(entity_id)
+        .bind(matter_id)
+        .bind(title)
+        .bind(body)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await.context("Failed to update search index")?;
+        Ok(())
+    }
+
+    pub async fn remove(&self, scope: SearchScope, entity_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM global_search_index WHERE scope = ? AND entity_id = ?")
+            .bind(scope.as_str())
+            .bind(entity_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to remove from search index")?;
+        Ok(())
+    }
+
+    /// Search the given scopes (all scopes when empty), best matches first.
+    /// Every whitespace-separated term must match; FTS operators in the
+    /// query are treated as literal text.
+    pub async fn global_search(&self, query: &str, scopes: &[SearchScope]) -> Result<Vec<GlobalHit>> {
+        let Some(match_expr) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let scopes = if scopes.is_empty() { &SearchScope::ALL[..] } else { scopes };
+        let placeholders = vec!["?"; scopes.len()].join(", ");
+
+        let sql = format!(
+            r#"
+            SELECT scope, entity_id, matter_id, title,
+                   snippet(global_search_index, 4, '[', ']', '…', 12) AS snippet,
+                   bm25(global_search_index) AS rank
+            FROM global_search_index
+            WHERE global_search_index MATCH ? AND scope IN ({})
+            ORDER BY rank
+            LIMIT ?
+            "#,
+            placeholders
+        );
+
+        let mut q = sqlx::query(&sql).bind(match_expr);
+        for scope in scopes {
+            q = q.bind(scope.as_str());
+        }
+        let rows = q
+            .bind(MAX_HITS)
+            .fetch_all(&self.db)
+            .await
+            .context("Global search failed")?;
+
+        rows.iter()
+            .map(|row| {
+                let scope: String = row.try_get("scope")?;
+                let rank: f64 = row.try_get("rank")?;
+                Ok(GlobalHit {
+                    scope: SearchScope::parse(&scope)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown search scope: {}", scope))?,
+                    entity_id: row.try_get("entity_id")?,
+                    matter_id: row.try_get("matter_id")?,
+                    title: row.try_get("title")?,
+                    snippet: row.try_get("snippet")?,
+                    // bm25 is negative, more negative for better matches
+                    score: -rank,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Quote each term so user input can't inject FTS5 syntax
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn service() -> GlobalSearchService {
+        let db = test_database().await;
+        GlobalSearchService::new(db)
+    }
+
+    #[tokio::test]
+    async fn test_query_matches_email_and_contract() {
+        let search = service().await;
+        search
+            .index(
+                SearchScope::Email,
+                "email-1",
+                Some("matter-1"),
+                "Re: supply agreement",
+                "Opposing counsel wants to narrow the indemnification clause.",
+            )
+            .await
+            .unwrap();
+        search
+            .index(
+                SearchScope::Contract,
+                "contract-1",
+                Some("matter-1"),
+                "Supply Agreement",
+                "Supplier shall provide indemnification against third-party claims.",
+            )
+            .await
+            .unwrap();
+        search
+            .index(SearchScope::Email, "email-2", None, "Lunch", "See you at noon.")
+            .await
+            .unwrap();
+
+        let hits = search.global_search("indemnification", &[]).await.unwrap();
+
+        assert_eq!(hits.len(), 2);
+        let email = hits.iter().find(|h| h.entity_id == "email-1").expect("email hit");
+        assert_eq!(email.scope, SearchScope::Email);
+        assert!(email.snippet.contains("[indemnification]"));
+        let contract = hits.iter().find(|h| h.entity_id == "contract-1").expect("contract hit");
+        assert_eq!(contract.scope, SearchScope::Contract);
+        assert!(hits.iter().all(|h| h.score > 0.0));
+
+        let contracts_only = search
+            .global_search("indemnification", &[SearchScope::Contract])
+            .await
+            .unwrap();
+        assert_eq!(contracts_only.len(), 1);
+        assert_eq!(contracts_only[0].scope, SearchScope::Contract);
+    }
+
+    async fn service_with_note() -> GlobalSearchService {
+        let search = service().await;
+        sqlx::raw_sql(
+            "INSERT INTO clients (id, first_name, last_name, created_at, updated_at) VALUES ('c1', 'Acme', 'Corp', '2024-01-01', '2024-01-01');
+             INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+             VALUES ('m1', 'c1', '2024-001', 'Acme v. Beta', 'civil', '2024-01-01', '2024-01-01');
+             INSERT INTO case_notes (id, matter_id, title, content, created_at, updated_at)
+             VALUES ('n1', 'm1', 'Deposition', 'The witness recalled the forklift.', '2024-01-01', '2024-01-01');",
+        )
+        .execute(&search.db)
+        .await
+        .unwrap();
+        search
+    }
+
+    async fn hit_ids(search: &GlobalSearchService, query: &str) -> Vec<String> {
+        let hits = search.global_search(query, &[SearchScope::MatterNote]).await.unwrap();
+        hits.into_iter().map(|h| h.entity_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_note_edits_and_deletes_reach_the_index() {
+        let search = service_with_note().await;
+        assert_eq!(hit_ids(&search, "forklift").await, vec!["n1"]);
+
+        sqlx::query("UPDATE case_notes SET content = 'The witness recalled the crane.' WHERE id = 'n1'")
+            .execute(&search.db)
+            .await
+            .unwrap();
+        assert!(hit_ids(&search, "forklift").await.is_empty());
+        assert_eq!(hit_ids(&search, "crane").await, vec!["n1"]);
+
+        sqlx::query("DELETE FROM case_notes WHERE id = 'n1'")
+            .execute(&search.db)
+            .await
+            .unwrap();
+        assert!(hit_ids(&search, "crane").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_indexes_rows_from_before_the_triggers() {
+        let search = service_with_note().await;
+        // As if the note had been written before the index existed
+        sqlx::query("DELETE FROM global_search_index")
+            .execute(&search.db)
+            .await
+            .unwrap();
+        assert!(hit_ids(&search, "forklift").await.is_empty());
+
+        sqlx::raw_sql(include_str!("../../migrations/048_global_search_backfill.sql"))
+            .execute(&search.db)
+            .await
+            .unwrap();
+        assert_eq!(hit_ids(&search, "forklift").await, vec!["n1"]);
+
+        // Running it again does not index the note twice
+        sqlx::raw_sql(include_str!("../../migrations/048_global_search_backfill.sql"))
+            .execute(&search.db)
+            .await
+            .unwrap();
+        assert_eq!(hit_ids(&search, "forklift").await, vec!["n1"]);
+    }
+}
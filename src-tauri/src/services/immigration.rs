@@ -0,0 +1,354 @@
+// Immigration Law Toolkit - Feature #21
+// Tracks USCIS form filings per case: supporting-document checklists, priority
+// dates against the Visa Bulletin, and filing windows fed to the deadline calendar
+
+use crate::services::calendar_sync::{DeadlineType, LegalDeadline, Priority};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ImmigrationForm {
+    I130,
+    I140,
+    I485,
+    I765,
+    I751,
+    N400,
+}
+
+impl ImmigrationForm {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ImmigrationForm::I130 => "I-130 Petition for Alien Relative",
+            ImmigrationForm::I140 => "I-140 Immigrant Petition for Alien Worker",
+            ImmigrationForm::I485 => "I-485 Application to Adjust Status",
+            ImmigrationForm::I765 => "I-765 Application for Employment Authorization",
+            ImmigrationForm::I751 => "I-751 Petition to Remove Conditions on Residence",
+            ImmigrationForm::N400 => "N-400 Application for Naturalization",
+        }
+    }
+
+    /// Supporting documents that must be in the file before this form is filed
+    pub fn required_documents(&self) -> &'static [SupportingDocument] {
+        use SupportingDocument::*;
+        match self {
+            ImmigrationForm::I130 => &[ProofOfPetitionerStatus, RelationshipEvidence, PassportPhotos],
+            ImmigrationForm::I140 => &[LaborCertification, ProofOfAbilityToPay, EmploymentExperienceLetters],
+            ImmigrationForm::I485 => &[
+                BirthCertificate,
+                PassportPhotos,
+                MedicalExamination,
+                AffidavitOfSupport,
+                I94Record,
+                UnderlyingPetitionNotice,
+            ],
+            ImmigrationForm::I765 => &[PassportPhotos, GovernmentId, PendingApplicationReceipt],
+            ImmigrationForm::I751 => &[ConditionalResidentCard, BonaFideMarriageEvidence],
+            ImmigrationForm::N400 => &[PermanentResidentCard, GovernmentId],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportingDocument {
+    ProofOfPetitionerStatus,
+    RelationshipEvidence,
+    PassportPhotos,
+    BirthCertificate,
+    MedicalExamination,
+    AffidavitOfSupport,
+    I94Record,
+    UnderlyingPetitionNotice,
+    GovernmentId,
+    PendingApplicationReceipt,
+    ConditionalResidentCard,
+    BonaFideMarriageEvidence,
+    PermanentResidentCard,
+    LaborCertification,
+    ProofOfAbilityToPay,
+    EmploymentExperienceLetters,
+}
+
+/// Visa Bulletin preference categories; immediate relatives are never subject to a cutoff
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PreferenceCategory {
+    ImmediateRelative,
+    F1,
+    F2A,
+    F2B,
+    F3,
+    F4,
+    EB1,
+    EB2,
+    EB3,
+}
+
+/// A Visa Bulletin final action date; `None` means the category is current
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisaBulletinCutoff {
+    pub category: PreferenceCategory,
+    pub chargeability: String,
+    pub final_action_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilingStatus {
+    Preparing,
+    ReadyToFile,
+    Filed,
+    RequestForEvidence,
+    Approved,
+    Denied,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImmigrationCase {
+    pub id: String,
+    pub matter_id: String,
+    pub form: ImmigrationForm,
+    pub status: FilingStatus,
+    pub category: PreferenceCategory,
+    pub chargeability: String,
+    /// USCIS receipt date of the filed form
+    pub filed_on: Option<DateTime<Utc>>,
+    /// PERM labor certification filing date, for employment-based petitions
+    pub labor_certification_filed_on: Option<DateTime<Utc>>,
+    /// Priority date on the receipt notice of the I-130 or I-140 an I-485
+    /// is based on
+    #[serde(default)]
+    pub petition_priority_date: Option<DateTime<Utc>>,
+    /// Start of conditional or permanent residence, for I-751 and N-400 windows
+    pub resident_since: Option<DateTime<Utc>>,
+    /// Naturalization through marriage to a U.S. citizen (three-year rule)
+    pub married_to_citizen: bool,
+    pub rfe_response_due: Option<DateTime<Utc>>,
+    pub documents: Vec<SupportingDocument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub document: SupportingDocument,
+    pub provided: bool,
+}
+
+impl ImmigrationCase {
+    /// Required supporting documents for this case's form and whether each is on file
+    pub fn checklist(&self) -> Vec<ChecklistItem> {
+        self.form
+            .required_documents()
+            .iter()
+            .map(|document| ChecklistItem {
+                document: *document,
+                provided: self.documents.contains(document),
+            })
+            .collect()
+    }
+
+    pub fn missing_documents(&self) -> Vec<SupportingDocument> {
+        self.checklist()
+            .into_iter()
+            .filter(|item| !item.provided)
+            .map(|item| item.document)
+            .collect()
+    }
+
+    /// Move a case to ready-to-file once every required document is present
+    pub fn mark_ready_to_file(&mut self) -> Result<()> {
+        if self.status != FilingStatus::Preparing {
+            anyhow::bail!("Only cases in preparation can be marked ready to file");
+        }
+
+        let missing = self.missing_documents();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "{} is missing required documents: {:?}",
+                self.form.title(),
+                missing
+            );
+        }
+
+        self.status = FilingStatus::ReadyToFile;
+        Ok(())
+    }
+
+    /// The petition's priority date: the PERM filing date for labor-certified
+    /// employment petitions, otherwise the USCIS receipt date of the petition.
+    /// An I-485 takes the priority date of its underlying petition; other
+    /// applications (I-765, ...) have none.
+    pub fn priority_date(&self) -> Option<DateTime<Utc>> {
+        match self.form {
+            ImmigrationForm::I140 => self.labor_certification_filed_on.or(self.filed_on),
+            ImmigrationForm::I130 => self.filed_on,
+            ImmigrationForm::I485 => self.petition_priority_date,
+            _ => None,
+        }
+    }
+
+    /// Whether a visa is available under the given Visa Bulletin cutoff
+    pub fn priority_date_current(&self, cutoff: &VisaBulletinCutoff) -> bool {
+        if self.category == PreferenceCategory::ImmediateRelative {
+            return true;
+        }
+        if cutoff.category != self.category || cutoff.chargeability != self.chargeability {
+            return false;
+        }
+        match (self.priority_date(), cutoff.final_action_date) {
+            (Some(_), None) => true,
+            (Some(priority_date), Some(final_action)) => priority_date < final_action,
+            (None, _) => false,
+        }
+    }
+
+    /// Filing windows and response deadlines for this case, for the deadline calendar
+    pub fn deadlines(&self) -> Vec<LegalDeadline> {
+        let mut deadlines = Vec::new();
+
+        match (self.form, self.resident_since) {
+            (ImmigrationForm::I751, Some(since)) => {
+                if let Some(expires) = since.checked_add_months(Months::new(24)) {
+                    deadlines.push(self.deadline(
+                        "i751-window",
+                        DeadlineType::Filing,
+                        expires - Duration::days(90),
+                        "I-751 filing window opens (90 days before conditional card expires)",
+                        Priority::High,
+                    ));
+                    deadlines.push(self.deadline(
+                        "i751-deadline",
+                        DeadlineType::Filing,
+                        expires,
+                        "I-751 must be filed before conditional residence expires",
+                        Priority::Critical,
+                    ));
+                }
+            }
+            (ImmigrationForm::N400, Some(since)) => {
+                let years = if self.married_to_citizen { 3 } else { 5 };
+                if let Some(eligible) = since.checked_add_months(Months::new(12 * years)) {
+                    deadlines.push(self.deadline(
+                        "n400-earliest",
+                        DeadlineType::Filing,
+                        eligible - Duration::days(90),
+                        "Earliest N-400 filing date (90-day early filing window)",
+                        Priority::Medium,
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(due) = self.rfe_response_due {
+            deadlines.push(self.deadline(
+                "rfe-response",
+                DeadlineType::Response,
+                due,
+                &format!("Response to USCIS Request for Evidence on {}", self.form.title()),
+                Priority::Critical,
+            ));
+        }
+
+        deadlines
+    }
+
+    fn deadline(
+        &self,
+        key: &str,
+        deadline_type: DeadlineType,
+        deadline_date: DateTime<Utc>,
+        description: &str,
+        priority: Priority,
+    ) -> LegalDeadline {
+        LegalDeadline {
+            id: format!("{}-{}", self.id, key),
+            matter_id: self.matter_id.clone(),
+            docket_number: None,
+            deadline_type,
+            deadline_date,
+            calculated_from: self.resident_since.or(self.filed_on),
+            jurisdiction: Some("USCIS".to_string()),
+            court_rules: Vec::new(),
+            description: description.to_string(),
+            priority,
+            auto_calculated: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn case(form: ImmigrationForm, category: PreferenceCategory) -> ImmigrationCase {
+        ImmigrationCase {
+            id: "case-1".to_string(),
+            matter_id: "matter-1".to_string(),
+            form,
+            status: FilingStatus::Preparing,
+            category,
+            chargeability: "Mexico".to_string(),
+            filed_on: None,
+            labor_certification_filed_on: None,
+            petition_priority_date: None,
+            resident_since: None,
+            married_to_citizen: false,
+            rfe_response_due: None,
+            documents: Vec::new(),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_missing_document_blocks_ready_to_file() {
+        let mut i485 = case(ImmigrationForm::I485, PreferenceCategory::F2A);
+        i485.documents = ImmigrationForm::I485
+            .required_documents()
+            .iter()
+            .copied()
+            .filter(|d| *d != SupportingDocument::MedicalExamination)
+            .collect();
+
+        let err = i485.mark_ready_to_file().unwrap_err();
+        assert!(err.to_string().contains("MedicalExamination"));
+        assert_eq!(i485.status, FilingStatus::Preparing);
+
+        i485.documents.push(SupportingDocument::MedicalExamination);
+        i485.mark_ready_to_file().unwrap();
+        assert_eq!(i485.status, FilingStatus::ReadyToFile);
+    }
+
+    #[test]
+    fn test_priority_date_computation() {
+        let mut i140 = case(ImmigrationForm::I140, PreferenceCategory::EB3);
+        i140.filed_on = Some(date(2023, 3, 1));
+        i140.labor_certification_filed_on = Some(date(2021, 6, 15));
+        assert_eq!(i140.priority_date(), Some(date(2021, 6, 15)));
+
+        let mut i130 = case(ImmigrationForm::I130, PreferenceCategory::F4);
+        i130.filed_on = Some(date(2001, 5, 10));
+        assert_eq!(i130.priority_date(), Some(date(2001, 5, 10)));
+
+        let cutoff = |final_action| VisaBulletinCutoff {
+            category: PreferenceCategory::F4,
+            chargeability: "Mexico".to_string(),
+            final_action_date: final_action,
+        };
+        assert!(i130.priority_date_current(&cutoff(Some(date(2001, 6, 1)))));
+        assert!(!i130.priority_date_current(&cutoff(Some(date(2001, 5, 10)))));
+        assert!(i130.priority_date_current(&cutoff(None)));
+
+        // Adjustment waits on the visa bulletin with the petition's date
+        let mut i485 = case(ImmigrationForm::I485, PreferenceCategory::F4);
+        i485.filed_on = Some(date(2024, 2, 1));
+        assert_eq!(i485.priority_date(), None);
+        i485.petition_priority_date = i130.priority_date();
+        assert_eq!(i485.priority_date(), Some(date(2001, 5, 10)));
+        assert!(i485.priority_date_current(&cutoff(Some(date(2001, 6, 1)))));
+    }
+}
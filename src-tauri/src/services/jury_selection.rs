@@ -0,0 +1,304 @@
+// Jury Selection AI - Feature #17
+// Scores juror lean from demographics and questionnaire answers and recommends strikes.
+// Protected characteristics never contribute to the score (Batson v. Kentucky,
+// J.E.B. v. Alabama); citing them as a strike basis is flagged instead.
+
+use crate::services::settlement_calculator::{DemographicProfile, PoliticalLean, UrbanRural};
+use serde::{Deserialize, Serialize};
+
+/// Lean at or beyond which a juror is considered clearly against our side
+const STRIKE_THRESHOLD: f64 = 0.35;
+/// Lean toward our side at which a juror is worth keeping
+const KEEP_THRESHOLD: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepresentedSide {
+    Plaintiff,
+    Defense,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseProfile {
+    pub side: RepresentedSide,
+    pub case_type: String,
+    pub corporate_defendant: bool,
+    pub venue_demographics: DemographicProfile,
+}
+
+/// Questionnaire and voir dire answers with a known bearing on civil juries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BiasIndicator {
+    PriorPlaintiff,
+    PriorDefendant,
+    DistrustsCorporations,
+    SupportsDamageCaps,
+    InsuranceIndustryEmployment,
+    SimilarInjuryExperience,
+    HealthcareWorker,
+    SkepticalOfLawsuits,
+    KnowsParty,
+    CannotBeImpartial,
+}
+
+impl BiasIndicator {
+    /// Positive values lean plaintiff, negative lean defense
+    fn weight(&self) -> f64 {
+        match self {
+            BiasIndicator::PriorPlaintiff => 0.15,
+            BiasIndicator::PriorDefendant => -0.15,
+            BiasIndicator::DistrustsCorporations => 0.15,
+            BiasIndicator::SupportsDamageCaps => -0.2,
+            BiasIndicator::InsuranceIndustryEmployment => -0.25,
+            BiasIndicator::SimilarInjuryExperience => 0.2,
+            BiasIndicator::HealthcareWorker => -0.1,
+            BiasIndicator::SkepticalOfLawsuits => -0.2,
+            BiasIndicator::KnowsParty | BiasIndicator::CannotBeImpartial => 0.0,
+        }
+    }
+
+    fn is_cause(&self) -> bool {
+        matches!(self, BiasIndicator::KnowsParty | BiasIndicator::CannotBeImpartial)
+    }
+}
+
+/// Characteristics that may not be the basis of a peremptory strike
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectedCharacteristic {
+    Race,
+    Ethnicity,
+    Sex,
+    Religion,
+    NationalOrigin,
+}
+
+/// A reason counsel gives for striking a juror
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum StrikeBasis {
+    Protected(ProtectedCharacteristic),
+    Questionnaire(BiasIndicator),
+    Demeanor(String),
+    Other(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurorProfile {
+    pub juror_number: u32,
+    pub age: Option<u32>,
+    pub income: Option<f64>,
+    pub residence: Option<UrbanRural>,
+    pub political_lean: Option<PoliticalLean>,
+    pub occupation: Option<String>,
+    #[serde(default)]
+    pub answers: Vec<BiasIndicator>,
+    #[serde(default)]
+    pub proposed_strike_bases: Vec<StrikeBasis>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JurorLean {
+    Plaintiff,
+    Neutral,
+    Defense,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrikeRecommendation {
+    ForCause,
+    Peremptory,
+    Consider,
+    Keep,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurorScore {
+    pub juror_number: u32,
+    /// -1.0 (strongly defense) to 1.0 (strongly plaintiff)
+    pub lean_score: f64,
+    pub lean: JurorLean,
+    pub recommendation: StrikeRecommendation,
+    pub reasons: Vec<String>,
+    /// Protected characteristics cited in the proposed strike bases
+    pub impermissible_bases: Vec<ProtectedCharacteristic>,
+    /// True when every proposed strike basis is a protected characteristic
+    pub protected_basis_only: bool,
+}
+
+/// Estimate a juror's plaintiff/defense lean and recommend whether to strike
+pub fn score_juror(profile: &JurorProfile, case: &CaseProfile) -> JurorScore {
+    let venue = &case.venue_demographics;
+    let mut lean = 0.0;
+    let mut reasons = Vec::new();
+
+    match profile.political_lean {
+        Some(PoliticalLean::Liberal) => {
+            lean += 0.1;
+            reasons.push("Liberal political lean".to_string());
+        }
+        Some(PoliticalLean::Conservative) => {
+            lean -= 0.1;
+            reasons.push("Conservative political lean".to_string());
+        }
+        _ => {}
+    }
+
+    if let Some(income) = profile.income.filter(|_| venue.median_income > 0.0) {
+        if income > venue.median_income * 1.5 {
+            lean -= 0.1;
+            reasons.push("Income well above venue median".to_string());
+        } else if income < venue.median_income * 0.75 {
+            lean += 0.05;
+            reasons.push("Income below venue median".to_string());
+        }
+    }
+
+    if let Some(age) = profile.age {
+        let age = age as f64;
+        if age > venue.median_age + 15.0 {
+            lean -= 0.05;
+            reasons.push("Older than venue median".to_string());
+        } else if age < venue.median_age - 15.0 {
+            lean += 0.05;
+            reasons.push("Younger than venue median".to_string());
+        }
+    }
+
+    if profile.residence == Some(UrbanRural::Urban) && venue.urban_rural != UrbanRural::Urban {
+        lean += 0.05;
+        reasons.push("Urban resident in non-urban venue".to_string());
+    }
+
+    for answer in &profile.answers {
+        let mut weight = answer.weight();
+        if *answer == BiasIndicator::DistrustsCorporations && case.corporate_defendant {
+            weight *= 2.0;
+        }
+        if weight != 0.0 {
+            lean += weight;
+            reasons.push(format!("Questionnaire: {:?}", answer));
+        }
+    }
+
+    let lean_score = lean.clamp(-1.0, 1.0);
+    let lean = if lean_score >= KEEP_THRESHOLD {
+        JurorLean::Plaintiff
+    } else if lean_score <= -KEEP_THRESHOLD {
+        JurorLean::Defense
+    } else {
+        JurorLean::Neutral
+    };
+
+    // Lean relative to the side we represent: positive favors us
+    let favorable = match case.side {
+        RepresentedSide::Plaintiff => lean_score,
+        RepresentedSide::Defense => -lean_score,
+    };
+
+    let cause = profile.answers.iter().find(|a| a.is_cause());
+    let recommendation = if let Some(cause) = cause {
+        reasons.push(format!("Grounds for cause: {:?}", cause));
+        StrikeRecommendation::ForCause
+    } else if favorable <= -STRIKE_THRESHOLD {
+        StrikeRecommendation::Peremptory
+    } else if favorable >= KEEP_THRESHOLD {
+        StrikeRecommendation::Keep
+    } else {
+        StrikeRecommendation::Consider
+    };
+
+    let impermissible_bases: Vec<ProtectedCharacteristic> = profile
+        .proposed_strike_bases
+        .iter()
+        .filter_map(|basis| match basis {
+            StrikeBasis::Protected(characteristic) => Some(*characteristic),
+            _ => None,
+        })
+        .collect();
+    let protected_basis_only = !profile.proposed_strike_bases.is_empty()
+        && impermissible_bases.len() == profile.proposed_strike_bases.len();
+
+    if protected_basis_only {
+        reasons.push(
+            "Proposed strike rests solely on protected characteristics and is impermissible".to_string(),
+        );
+    } else if !impermissible_bases.is_empty() {
+        reasons.push("Protected characteristics cited; strike must rest on the other bases".to_string());
+    }
+
+    JurorScore {
+        juror_number: profile.juror_number,
+        lean_score,
+        lean,
+        recommendation,
+        reasons,
+        impermissible_bases,
+        protected_basis_only,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defense_case() -> CaseProfile {
+        CaseProfile {
+            side: RepresentedSide::Defense,
+            case_type: "products liability".to_string(),
+            corporate_defendant: true,
+            venue_demographics: DemographicProfile {
+                median_age: 42.0,
+                median_income: 65_000.0,
+                education_level: "some college".to_string(),
+                urban_rural: UrbanRural::Suburban,
+            },
+        }
+    }
+
+    fn juror(answers: Vec<BiasIndicator>, bases: Vec<StrikeBasis>) -> JurorProfile {
+        JurorProfile {
+            juror_number: 7,
+            age: Some(40),
+            income: Some(60_000.0),
+            residence: Some(UrbanRural::Suburban),
+            political_lean: Some(PoliticalLean::Moderate),
+            occupation: None,
+            answers,
+            proposed_strike_bases: bases,
+        }
+    }
+
+    #[test]
+    fn test_clear_plaintiff_bias_recommends_defense_strike() {
+        let mut profile = juror(
+            vec![
+                BiasIndicator::DistrustsCorporations,
+                BiasIndicator::PriorPlaintiff,
+                BiasIndicator::SimilarInjuryExperience,
+            ],
+            vec![],
+        );
+        profile.political_lean = Some(PoliticalLean::Liberal);
+
+        let score = score_juror(&profile, &defense_case());
+
+        assert_eq!(score.lean, JurorLean::Plaintiff);
+        assert_eq!(score.recommendation, StrikeRecommendation::Peremptory);
+        assert!(!score.protected_basis_only);
+    }
+
+    #[test]
+    fn test_protected_characteristic_only_rationale_is_flagged() {
+        let profile = juror(vec![], vec![StrikeBasis::Protected(ProtectedCharacteristic::Race)]);
+
+        let score = score_juror(&profile, &defense_case());
+
+        assert!(score.protected_basis_only);
+        assert_eq!(score.impermissible_bases, vec![ProtectedCharacteristic::Race]);
+        assert_ne!(score.recommendation, StrikeRecommendation::Peremptory);
+    }
+}
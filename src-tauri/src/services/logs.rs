@@ -0,0 +1,368 @@
+// Log retrieval and rotation for PA eDocket Desktop
+// Reads and writes the rotating JSON log files used by the tracing subscriber
+
+use crate::config::GlobalConfig;
+use crate::utils::file_utils::expand_home;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::{debug, Level};
+
+/// File name of the active log; rotated files append a numeric suffix
+pub const LOG_FILE_NAME: &str = "pa-edocket.log";
+
+/// Upper bound on lines returned when the caller does not set a limit
+pub const DEFAULT_LOG_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// Minimum severity, e.g. "warn" returns WARN and ERROR lines
+    pub level: Option<String>,
+    /// Target prefix, e.g. "pa_edocket_desktop_lib::providers"
+    pub target: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Case-insensitive substring matched against the message and target
+    pub contains: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: Map<String, Value>,
+}
+
+/// Read log lines from `log_dir`, most recent first.
+///
+/// Lines are returned exactly as written; PII redaction happens when the
+/// event is recorded, so nothing here can re-expose redacted values.
+pub fn get_logs(log_dir: &Path, filter: &LogFilter) -> Result<Vec<LogLine>> {
+    let min_level = match &filter.level {
+        Some(level) => Some(
+            level.parse::<Level>()
+                .map_err(|_| anyhow::anyhow!("Invalid log level: {}", level))?,
+        ),
+        None => None,
+    };
+    let needle = filter.contains.as_ref().map(|s| s.to_lowercase());
+
+    let mut lines = Vec::new();
+    for path in log_files(log_dir)? {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read log file {:?}", path))?;
+
+        for raw in content.lines() {
+            let Some(line) = parse_line(raw) else {
+                continue;
+            };
+
+            if let Some(min_level) = min_level {
+                match line.level.parse::<Level>() {
+                    Ok(level) if level <= min_level => {}
+                    _ => continue,
+                }
+            }
+            if let Some(target) = &filter.target {
+                if !line.target.starts_with(target.as_str()) {
+                    continue;
+                }
+            }
+            if filter.since.is_some_and(|since| line.timestamp < since) {
+                continue;
+            }
+            if filter.until.is_some_and(|until| line.timestamp > until) {
+                continue;
+            }
+            if let Some(needle) = &needle {
+                if !line.message.to_lowercase().contains(needle)
+                    && !line.target.to_lowercase().contains(needle)
+                {
+                    continue;
+                }
+            }
+
+            lines.push(line);
+        }
+    }
+
+    lines.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    lines.truncate(filter.limit.unwrap_or(DEFAULT_LOG_LIMIT));
+    Ok(lines)
+}
+
+/// The active log file plus its rotated siblings
+fn log_files(log_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !log_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(log_dir).context("Failed to read log directory")? {
+        let path = entry?.path();
+        let is_log = path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(LOG_FILE_NAME));
+        if is_log && path.is_file() {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Parse one line of tracing-subscriber JSON output
+fn parse_line(raw: &str) -> Option<LogLine> {
+    let value: Value = match serde_json::from_str(raw) {
+        Ok(value) => value,
+        Err(e) => {
+            debug!("Skipping unparseable log line: {}", e);
+            return None;
+        }
+    };
+
+    let timestamp = value.get("timestamp")?.as_str()?
+        .parse::<DateTime<Utc>>()
+        .ok()?;
+    let level = value.get("level")?.as_str()?.to_string();
+    let target = value.get("target").and_then(|t| t.as_str()).unwrap_or_default().to_string();
+
+    let mut fields = value.get("fields")
+        .and_then(|f| f.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let message = fields.remove("message")
+        .and_then(|m| m.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    Some(LogLine {
+        timestamp,
+        level,
+        target,
+        message,
+        fields,
+    })
+}
+
+/// Size-based rolling log writer.
+///
+/// Writes to `pa-edocket.log`; once the file would exceed `max_bytes` it is
+/// shifted to `.1` (older files move up one index) and files past
+/// `max_files` in total are deleted.
+pub struct RollingFileWriter {
+    log_dir: PathBuf,
+    max_bytes: u64,
+    max_files: u32,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RollingFileWriter {
+    pub fn new(log_dir: &Path, max_bytes: u64, max_files: u32) -> io::Result<Self> {
+        fs::create_dir_all(log_dir)?;
+        let path = log_dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            log_dir: log_dir.to_path_buf(),
+            max_bytes,
+            max_files: max_files.max(1),
+            file: Some(file),
+            size,
+        })
+    }
+
+    /// Build a writer from `GlobalConfig` limits
+    pub fn from_config(config: &GlobalConfig) -> io::Result<Self> {
+        Self::new(
+            &expand_home(&config.log_dir),
+            config.max_log_size_mb * 1024 * 1024,
+            config.max_log_files,
+        )
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        self.log_dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        // Close the active file first so it can be renamed on every platform
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+
+        // Drop the oldest file and anything past the retention count
+        let mut index = (self.max_files - 1).max(1);
+        while self.rotated_path(index).exists() {
+            fs::remove_file(self.rotated_path(index))?;
+            index += 1;
+        }
+
+        let active = self.log_dir.join(LOG_FILE_NAME);
+        if self.max_files == 1 {
+            fs::remove_file(&active)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = if index == 1 { active.clone() } else { self.rotated_path(index - 1) };
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(index))?;
+                }
+            }
+        }
+
+        self.file = Some(OpenOptions::new().create(true).append(true).open(&active)?);
+        self.size = 0;
+        Ok(())
+    }
+
+    fn active_file(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            let path = self.log_dir.join(LOG_FILE_NAME);
+            self.file = Some(OpenOptions::new().create(true).append(true).open(path)?);
+        }
+        Ok(self.file.as_mut().expect("log file opened above"))
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let written = self.active_file()?.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_fixture(dir: &Path) {
+        let current = [
+            r#"{"timestamp":"2024-03-01T10:00:00Z","level":"INFO","fields":{"message":"Starting search"},"target":"pa_edocket_desktop_lib::services"}"#,
+            r#"{"timestamp":"2024-03-01T10:05:00Z","level":"WARN","fields":{"message":"Provider slow","latency_ms":2500},"target":"pa_edocket_desktop_lib::providers"}"#,
+            "not json",
+            r#"{"timestamp":"2024-03-01T10:10:00Z","level":"ERROR","fields":{"message":"Provider unreachable"},"target":"pa_edocket_desktop_lib::providers"}"#,
+        ];
+        let rotated = [
+            r#"{"timestamp":"2024-02-28T09:00:00Z","level":"ERROR","fields":{"message":"Database locked"},"target":"pa_edocket_desktop_lib::services::database"}"#,
+            r#"{"timestamp":"2024-02-28T09:01:00Z","level":"DEBUG","fields":{"message":"Cache miss"},"target":"pa_edocket_desktop_lib::services"}"#,
+        ];
+        fs::write(dir.join(LOG_FILE_NAME), current.join("\n")).unwrap();
+        fs::write(dir.join(format!("{}.1", LOG_FILE_NAME)), rotated.join("\n")).unwrap();
+        fs::write(dir.join("unrelated.txt"), "ignored").unwrap();
+    }
+
+    #[test]
+    fn test_level_filter_returns_at_least_as_severe() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+
+        let filter = LogFilter { level: Some("warn".to_string()), ..Default::default() };
+        let lines = get_logs(dir.path(), &filter).unwrap();
+
+        let messages: Vec<_> = lines.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, vec!["Provider unreachable", "Provider slow", "Database locked"]);
+    }
+
+    #[test]
+    fn test_time_range_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+
+        let filter = LogFilter {
+            since: Some("2024-03-01T10:00:00Z".parse().unwrap()),
+            until: Some("2024-03-01T10:05:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        let lines = get_logs(dir.path(), &filter).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].message, "Provider slow");
+        assert_eq!(lines[0].fields.get("latency_ms"), Some(&Value::from(2500)));
+        assert_eq!(lines[1].message, "Starting search");
+    }
+
+    #[test]
+    fn test_text_filter_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+
+        let filter = LogFilter {
+            contains: Some("PROVIDER".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let lines = get_logs(dir.path(), &filter).unwrap();
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].message, "Provider unreachable");
+    }
+
+    #[test]
+    fn test_invalid_level_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let filter = LogFilter { level: Some("loud".to_string()), ..Default::default() };
+        assert!(get_logs(dir.path(), &filter).is_err());
+    }
+
+    #[test]
+    fn test_rolling_writer_rotates_past_size_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RollingFileWriter::new(dir.path(), 64, 3).unwrap();
+
+        writer.write_all(&[b'a'; 40]).unwrap();
+        assert!(!dir.path().join(format!("{}.1", LOG_FILE_NAME)).exists());
+
+        writer.write_all(&[b'b'; 40]).unwrap();
+        writer.flush().unwrap();
+
+        let rotated = fs::read(dir.path().join(format!("{}.1", LOG_FILE_NAME))).unwrap();
+        let active = fs::read(dir.path().join(LOG_FILE_NAME)).unwrap();
+        assert_eq!(rotated, vec![b'a'; 40]);
+        assert_eq!(active, vec![b'b'; 40]);
+    }
+
+    #[test]
+    fn test_rolling_writer_prunes_oldest_past_file_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RollingFileWriter::new(dir.path(), 16, 3).unwrap();
+
+        for byte in [b'1', b'2', b'3', b'4', b'5'] {
+            writer.write_all(&[byte; 16]).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut names: Vec<_> = fs::read_dir(dir.path()).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![
+            LOG_FILE_NAME.to_string(),
+            format!("{}.1", LOG_FILE_NAME),
+            format!("{}.2", LOG_FILE_NAME),
+        ]);
+
+        // Oldest surviving rotation holds the third write; "1" and "2" were deleted
+        let oldest = fs::read(dir.path().join(format!("{}.2", LOG_FILE_NAME))).unwrap();
+        assert_eq!(oldest, vec![b'3'; 16]);
+    }
+}
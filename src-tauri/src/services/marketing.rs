@@ -1,7 +1,16 @@
 // Legal Marketing Suite - Feature #13
-use anyhow::Result;
+use crate::services::email_integration::{EmailAddress, EmailIntegrationService};
+use crate::services::service_error::ServiceError;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub type ContactId = String;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketingCampaign {
@@ -16,8 +25,416 @@ pub struct MarketingCampaign {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CampaignType { Email, SocialMedia, SEO, PPC }
 
+/// One delivered campaign email, with engagement recorded as it is reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignSend {
+    pub id: String,
+    pub template_id: String,
+    pub contact_id: ContactId,
+    pub email: String,
+    pub provider_message_id: Option<String>,
+    pub sent_at: DateTime<Utc>,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub clicked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignFailure {
+    pub contact_id: ContactId,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CampaignResult {
+    pub template_id: String,
+    pub sent: Vec<CampaignSend>,
+    pub unsubscribed: Vec<ContactId>,
+    pub failed: Vec<CampaignFailure>,
+}
+
+/// Delivers a rendered campaign email and returns the provider message id
+#[async_trait]
+pub trait CampaignMailer: Send + Sync {
+    async fn send(&self, to: &EmailAddress, subject: &str, body_html: &str) -> Result<String>;
+}
+
+/// Sends campaign mail through a connected Gmail/Outlook account
+pub struct AccountMailer {
+    email: EmailIntegrationService,
+    account_id: String,
+}
+
+impl AccountMailer {
+    /// `email` must have a mailbox client to send through
+    pub fn new(email: EmailIntegrationService, account_id: &str) -> Self {
+        Self {
+            email,
+            account_id: account_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CampaignMailer for AccountMailer {
+    async fn send(&self, to: &EmailAddress, subject: &str, body_html: &str) -> Result<String> {
+        let draft = self.email
+            .create_draft(&self.account_id, vec![to.clone()], subject, body_html, None)
+            .await?;
+        let sent = self.email.send_email(&draft.id).await?;
+        Ok(sent.provider_message_id)
+    }
+}
+
+struct Recipient {
+    email: Option<String>,
+    variables: HashMap<String, String>,
+}
+
 pub struct MarketingService { db: SqlitePool }
 impl MarketingService {
     pub fn new(db: SqlitePool) -> Self { Self { db } }
-    pub async fn create_campaign(&self) -> Result<MarketingCampaign> { unimplemented!() }
+
+    /// Record a new campaign in draft status
+    pub async fn create_campaign(
+        &self,
+        name: &str,
+        campaign_type: CampaignType,
+        budget: f64,
+    ) -> Result<MarketingCampaign> {
+        if name.trim().is_empty() {
+            return Err(ServiceError::validation("Campaign name cannot be empty").into());
+        }
+        if !budget.is_finite() || budget < 0.0 {
+            return Err(ServiceError::validation("Campaign budget cannot be negative").into());
+        }
+
+        let campaign = MarketingCampaign {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            campaign_type,
+            status: "draft".to_string(),
+            budget,
+            roi: 0.0,
+        };
+        sqlx::query(
+            "INSERT INTO marketing_campaigns (id, name, campaign_type, status, budget, roi, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&campaign.id)
+        .bind(&campaign.name)
+        .bind(serde_json::to_string(&campaign.campaign_type)?)
+        .bind(&campaign.status)
+        .bind(campaign.budget)
+        .bind(campaign.roi)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await
+        .context("Failed to create campaign")?;
+
+        info!("Created {:?} campaign {}", campaign.campaign_type, campaign.name);
+        Ok(campaign)
+    }
+
+    /// Render `template_id` for each recipient and send it through `mailer`.
+    /// Opted-out contacts are never sent to; per-recipient failures are
+    /// collected rather than aborting the rest of the campaign.
+    pub async fn send_campaign(
+        &self,
+        mailer: &dyn CampaignMailer,
+        template_id: &str,
+        recipients: &[ContactId],
+    ) -> Result<CampaignResult> {
+        let template = EmailIntegrationService::new(self.db.clone())
+            .get_template(template_id)
+            .await?;
+
+        let mut result = CampaignResult {
+            template_id: template_id.to_string(),
+            ..Default::default()
+        };
+
+        for contact_id in recipients {
+            let recipient = match self.get_recipient(contact_id).await? {
+                Some(recipient) => recipient,
+                None => {
+                    result.failed.push(CampaignFailure {
+                        contact_id: contact_id.clone(),
+                        error: "Contact not found".to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            if self.is_unsubscribed(contact_id, recipient.email.as_deref()).await? {
+                result.unsubscribed.push(contact_id.clone());
+                continue;
+            }
+
+            let Some(address) = recipient.email.clone().filter(|e| !e.trim().is_empty()) else {
+                result.failed.push(CampaignFailure {
+                    contact_id: contact_id.clone(),
+                    error: "Contact has no email address".to_string(),
+                });
+                continue;
+            };
+
+            let (subject, body) = template.render(&recipient.variables);
+            let to = EmailAddress {
+                name: recipient.variables.get("name").cloned().filter(|n| !n.is_empty()),
+                address: address.clone(),
+            };
+
+            match mailer.send(&to, &subject, &body).await {
+                Ok(message_id) => {
+                    let send = CampaignSend {
+                        id: Uuid::new_v4().to_string(),
+                        template_id: template_id.to_string(),
+                        contact_id: contact_id.clone(),
+                        email: address,
+                        provider_message_id: Some(message_id),
+                        sent_at: Utc::now(),
+                        opened_at: None,
+                        clicked_at: None,
+                    };
+                    self.save_send(&send).await?;
+                    result.sent.push(send);
+                }
+                Err(e) => {
+                    warn!("Campaign email to contact {} failed: {}", contact_id, e);
+                    result.failed.push(CampaignFailure {
+                        contact_id: contact_id.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        info!(
+            "Campaign {}: {} sent, {} unsubscribed, {} failed",
+            template_id,
+            result.sent.len(),
+            result.unsubscribed.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+
+    /// Opt a contact out of all future campaign mail
+    pub async fn unsubscribe(&self, contact_id: &str) -> Result<()> {
+        let email = self.get_recipient(contact_id).await?.and_then(|r| r.email);
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO marketing_unsubscribes (contact_id, email, unsubscribed_at)
+             VALUES (?, ?, ?)",
+        )
+        .bind(contact_id)
+        .bind(email.map(|e| e.trim().to_lowercase()))
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await
+        .context("Failed to record unsubscribe")?;
+
+        Ok(())
+    }
+
+    /// Record an open reported by the provider or tracking pixel
+    pub async fn record_open(&self, send_id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE campaign_sends
+             SET opened_at = COALESCE(opened_at, ?), open_count = open_count + 1
+             WHERE id = ?",
+        )
+        .bind(Utc::now())
+        .bind(send_id)
+        .execute(&self.db)
+        .await
+        .context("Failed to record campaign open")?;
+        Ok(())
+    }
+
+    /// Record a link click; a click implies the message was opened
+    pub async fn record_click(&self, send_id: &str) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE campaign_sends
+             SET clicked_at = COALESCE(clicked_at, ?), opened_at = COALESCE(opened_at, ?),
+                 click_count = click_count + 1
+             WHERE id = ?",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(send_id)
+        .execute(&self.db)
+        .await
+        .context("Failed to record campaign click")?;
+        Ok(())
+    }
+
+    async fn get_recipient(&self, contact_id: &str) -> Result<Option<Recipient>> {
+        let row = sqlx::query(
+            "SELECT first_name, last_name, organization, email FROM contacts WHERE id = ?",
+        )
+        .bind(contact_id)
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to load contact")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let first_name: Option<String> = row.try_get("first_name")?;
+        let last_name: Option<String> = row.try_get("last_name")?;
+        let organization: Option<String> = row.try_get("organization")?;
+        let email: Option<String> = row.try_get("email")?;
+
+        let first_name = first_name.unwrap_or_default();
+        let last_name = last_name.unwrap_or_default();
+        let name = format!("{} {}", first_name, last_name).trim().to_string();
+
+        let mut variables = HashMap::new();
+        variables.insert("first_name".to_string(), first_name);
+        variables.insert("last_name".to_string(), last_name);
+        variables.insert("name".to_string(), name);
+        variables.insert("organization".to_string(), organization.unwrap_or_default());
+        variables.insert("email".to_string(), email.clone().unwrap_or_default());
+
+        Ok(Some(Recipient { email, variables }))
+    }
+
+    async fn is_unsubscribed(&self, contact_id: &str, email: Option<&str>) -> Result<bool> {
+        let email = email.map(|e| e.trim().to_lowercase());
+        let row = sqlx::query(
+            "SELECT 1 FROM marketing_unsubscribes
+             WHERE contact_id = ? OR (? IS NOT NULL AND email = ?)
+             LIMIT 1",
+        )
+        .bind(contact_id)
+        .bind(&email)
+        .bind(&email)
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to check unsubscribe list")?;
+        Ok(row.is_some())
+    }
+
+    async fn save_send(&self, send: &CampaignSend) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO campaign_sends
+            (id, template_id, contact_id, email, provider_message_id, sent_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&send.id)
+        .bind(&send.template_id)
+        .bind(&send.contact_id)
+        .bind(&send.email)
+        .bind(&send.provider_message_id)
+        .bind(send.sent_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to record campaign send")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use crate::services::email_integration::{EmailTemplate, EmailTemplateCategory};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingMailer {
+        sent: Mutex<Vec<(String, String, String)>>,
+    }
+
+    #[async_trait]
+    impl CampaignMailer for RecordingMailer {
+        async fn send(&self, to: &EmailAddress, subject: &str, body_html: &str) -> Result<String> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push((to.address.clone(), subject.to_string(), body_html.to_string()));
+            Ok(format!("msg-{}", sent.len()))
+        }
+    }
+
+    async fn marketing_db() -> SqlitePool {
+        let db = test_database().await;
+
+        for (id, first, last, email) in [
+            ("c1", "Jane", "Doe", "jane@example.com"),
+            ("c2", "John", "Roe", "john@example.com"),
+        ] {
+            sqlx::query(
+                "INSERT INTO contacts (id, contact_type, first_name, last_name, email, created_at, updated_at)
+                 VALUES (?, 'prospect', ?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(first)
+            .bind(last)
+            .bind(email)
+            .bind(Utc::now().to_rfc3339())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        EmailIntegrationService::new(db.clone())
+            .create_template(EmailTemplate {
+                id: "newsletter".to_string(),
+                name: "Newsletter".to_string(),
+                category: EmailTemplateCategory::FollowUp,
+                subject: "News for {{first_name}}".to_string(),
+                body_html: "<p>Dear {{name}},</p>".to_string(),
+                variables: vec!["first_name".to_string(), "name".to_string()],
+                attachments: Vec::new(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                usage_count: 0,
+            })
+            .await
+            .unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn test_send_campaign_fills_recipient_name() {
+        let db = marketing_db().await;
+        let service = MarketingService::new(db);
+        let mailer = RecordingMailer::default();
+
+        let result = service
+            .send_campaign(&mailer, "newsletter", &["c1".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(result.sent.len(), 1);
+        let sent = mailer.sent.lock().unwrap();
+        assert_eq!(sent[0].0, "jane@example.com");
+        assert_eq!(sent[0].1, "News for Jane");
+        assert_eq!(sent[0].2, "<p>Dear Jane Doe,</p>");
+    }
+
+    #[tokio::test]
+    async fn test_send_campaign_skips_unsubscribed_contact() {
+        let db = marketing_db().await;
+        let service = MarketingService::new(db);
+        let mailer = RecordingMailer::default();
+
+        service.unsubscribe("c2").await.unwrap();
+        let result = service
+            .send_campaign(&mailer, "newsletter", &["c1".to_string(), "c2".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(result.unsubscribed, vec!["c2".to_string()]);
+        assert_eq!(result.sent.len(), 1);
+        assert_eq!(result.sent[0].contact_id, "c1");
+        assert!(mailer.sent.lock().unwrap().iter().all(|(to, _, _)| to != "john@example.com"));
+    }
 }
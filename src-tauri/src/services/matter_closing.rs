@@ -0,0 +1,454 @@
+// Matter closing - the checklist a matter has to pass before it is closed.
+// A matter cannot close while the client owes money, while trust funds are
+// still held for it or not yet reconciled, or while a deadline is open.
+// Closing marks the matter closed, schedules its file for record retention
+// and, when configured, exports the full file first.
+
+use crate::domain::case_management::MatterStatus;
+use crate::services::service_error::ServiceError;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, SqliteConnection, SqlitePool, TypeInfo, ValueRef};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// How long a closed file is kept before it may be destroyed
+pub const DEFAULT_RETENTION_YEARS: u32 = 7;
+
+/// Balances below a cent are rounding, not money owed or held
+const BALANCE_EPSILON: f64 = 0.005;
+
+/// Records written to the exported file, one JSON file per table
+const FILE_TABLES: &[&str] = &[
+    "case_participants",
+    "case_events",
+    "tasks",
+    "case_notes",
+    "case_documents",
+    "time_entries",
+    "expenses",
+    "invoices",
+    "trust_transactions",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosingCheck {
+    OpenBalance,
+    TrustBalance,
+    UnreconciledTrust,
+    PendingDeadline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CloseBlocker {
+    pub check: ClosingCheck,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionSchedule {
+    pub id: String,
+    pub matter_id: String,
+    pub closed_at: DateTime<Utc>,
+    pub retain_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseReport {
+    pub matter_id: String,
+    pub closed: bool,
+    /// Why the matter was left open; empty when it closed
+    pub blockers: Vec<CloseBlocker>,
+    pub retention: Option<RetentionSchedule>,
+    pub export_path: Option<PathBuf>,
+}
+
+pub struct MatterClosingService {
+    db: SqlitePool,
+    retention_years: u32,
+    export_dir: Option<PathBuf>,
+}
+
+impl MatterClosingService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db, retention_years: DEFAULT_RETENTION_YEARS, export_dir: None }
+    }
+
+    pub fn with_retention_years(mut self, years: u32) -> Self {
+        self.retention_years = years;
+        self
+    }
+
+    /// Export the full file into `dir` as part of closing
+    pub fn with_file_export(mut self, dir: PathBuf) -> Self {
+        self.export_dir = Some(dir);
+        self
+    }
+
+    /// Run the closing checklist and close the matter when nothing blocks it.
+    /// A blocked matter is left as it was and the report lists the blockers.
+    pub async fn close_matter(&self, matter_id: &str) -> Result<CloseReport> {
+        // First pass without the write lock, so a blocked matter or a long
+        // export does not hold up other writers
+        let mut conn = self.db.acquire().await?;
+        let matter_number = open_matter_number(&mut *conn, matter_id).await?;
+        let blockers = closing_blockers(&mut *conn, matter_id).await?;
+        drop(conn);
+        if !blockers.is_empty() {
+            return Ok(blocked_report(matter_id, blockers));
+        }
+
+        // Export before closing so a failed export leaves the matter open
+        let export_path = match &self.export_dir {
+            Some(dir) => Some(self.export_file(matter_id, &matter_number, dir).await?),
+            None => None,
+        };
+
+        // Check again under the write lock: an invoice, trust deposit or
+        // deadline may have been added since, or another close may have won
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        open_matter_number(&mut *tx, matter_id).await?;
+        let blockers = closing_blockers(&mut *tx, matter_id).await?;
+        if !blockers.is_empty() {
+            tx.rollback().await?;
+            discard_export(export_path.as_deref());
+            return Ok(blocked_report(matter_id, blockers));
+        }
+
+        let closed_at = Utc::now();
+        let retention = RetentionSchedule {
+            id: Uuid::new_v4().to_string(),
+            matter_id: matter_id.to_string(),
+            closed_at,
+            retain_until: closed_at
+                .checked_add_months(Months::new(self.retention_years * 12))
+                .unwrap_or(closed_at),
+        };
+
+        sqlx::query("UPDATE matters SET status = ?, closed_at = ?, updated_at = ? WHERE id = ?")
+            .bind(serde_json::to_string(&MatterStatus::Closed)?)
+            .bind(closed_at.to_rfc3339())
+            .bind(closed_at.to_rfc3339())
+            .bind(matter_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to close matter")?;
+        sqlx::query(
+            "INSERT INTO record_retention (id, matter_id, closed_at, retain_until, status, created_at)
+             VALUES (?, ?, ?, ?, 'scheduled', ?)",
+        )
+        .bind(&retention.id)
+        .bind(&retention.matter_id)
+        .bind(retention.closed_at)
+        .bind(retention.retain_until)
+        .bind(closed_at)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to schedule record retention")?;
+        tx.commit().await?;
+
+        info!("Closed matter {}; file retained until {}", matter_id, retention.retain_until);
+        Ok(CloseReport {
+            matter_id: matter_id.to_string(),
+            closed: true,
+            blockers: Vec::new(),
+            retention: Some(retention),
+            export_path,
+        })
+    }
+
+    /// Zip the matter, its records and its documents into `dir`
+    async fn export_file(&self, matter_id: &str, matter_number: &str, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}_file.zip", matter_number.replace(['/', '\\'], "-")));
+        let mut zip = ZipWriter::new(File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?);
+        let options = SimpleFileOptions::default();
+
+        let matter = sqlx::query("SELECT * FROM matters WHERE id = ?")
+            .bind(matter_id)
+            .fetch_one(&self.db)
+            .await
+            .context("Failed to load matter")?;
+        zip.start_file("matter.json", options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&row_to_json(&matter)?)?)?;
+
+        for table in FILE_TABLES {
+            let rows = sqlx::query(&format!("SELECT * FROM {} WHERE matter_id = ?", table))
+                .bind(matter_id)
+                .fetch_all(&self.db)
+                .await
+                .with_context(|| format!("Failed to export {}", table))?;
+            let records = rows.iter().map(row_to_json).collect::<Result<Vec<_>>>()?;
+            zip.start_file(format!("{}.json", table), options)?;
+            zip.write_all(&serde_json::to_vec_pretty(&records)?)?;
+        }
+
+        let documents: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, file_path FROM case_documents WHERE matter_id = ?")
+                .bind(matter_id)
+                .fetch_all(&self.db)
+                .await
+                .context("Failed to list matter documents")?;
+        for (id, file_path) in documents {
+            let source = Path::new(&file_path);
+            let Ok(contents) = std::fs::read(source) else {
+                warn!("Document {} is missing from {}; not exported", id, file_path);
+                continue;
+            };
+            let name = source.file_name().and_then(|n| n.to_str()).unwrap_or("document");
+            zip.start_file(format!("documents/{}_{}", id, name), options)?;
+            zip.write_all(&contents)?;
+        }
+
+        zip.finish()?;
+        info!("Exported file for matter {} to {}", matter_id, path.display());
+        Ok(path)
+    }
+}
+
+/// The matter's number, if it exists and is not already closed
+async fn open_matter_number(conn: &mut SqliteConnection, matter_id: &str) -> Result<String> {
+    let row = sqlx::query("SELECT matter_number, status FROM matters WHERE id = ?")
+        .bind(matter_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to load matter")?
+        .ok_or_else(|| ServiceError::not_found("Matter", matter_id))?;
+    let status: Option<String> = row.try_get("status")?;
+    let closed = serde_json::to_string(&MatterStatus::Closed)?;
+    let archived = serde_json::to_string(&MatterStatus::Archived)?;
+    if status.as_deref() == Some(closed.as_str()) || status.as_deref() == Some(archived.as_str()) {
+        return Err(ServiceError::conflict(format!("Matter {} is already closed", matter_id)).into());
+    }
+    Ok(row.try_get("matter_number")?)
+}
+
+async fn closing_blockers(conn: &mut SqliteConnection, matter_id: &str) -> Result<Vec<CloseBlocker>> {
+    let mut blockers = Vec::new();
+
+    let open_balance: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(balance), 0.0) FROM invoices
+         WHERE matter_id = ? AND status NOT IN ('Cancelled', 'WriteOff')",
+    )
+    .bind(matter_id)
+    .fetch_one(&mut *conn)
+    .await
+    .context("Failed to check open invoices")?;
+    if open_balance > BALANCE_EPSILON {
+        blockers.push(CloseBlocker {
+            check: ClosingCheck::OpenBalance,
+            detail: format!("${:.2} is still owed on invoices", open_balance),
+        });
+    }
+
+    let trust = sqlx::query(
+        "SELECT COALESCE(SUM(amount), 0.0) AS balance,
+                COALESCE(SUM(CASE WHEN is_reconciled THEN 0 ELSE 1 END), 0) AS unreconciled
+         FROM trust_transactions WHERE matter_id = ?",
+    )
+    .bind(matter_id)
+    .fetch_one(&mut *conn)
+    .await
+    .context("Failed to check trust funds")?;
+    let trust_balance: f64 = trust.try_get("balance")?;
+    let unreconciled: i64 = trust.try_get("unreconciled")?;
+    if trust_balance.abs() > BALANCE_EPSILON {
+        blockers.push(CloseBlocker {
+            check: ClosingCheck::TrustBalance,
+            detail: format!("${:.2} is still held in trust", trust_balance),
+        });
+    }
+    if unreconciled > 0 {
+        blockers.push(CloseBlocker {
+            check: ClosingCheck::UnreconciledTrust,
+            detail: format!("{} trust transaction(s) are not reconciled", unreconciled),
+        });
+    }
+
+    let deadlines: Vec<(String, String)> = sqlx::query_as(
+        "SELECT title, event_date FROM case_events
+         WHERE matter_id = ? AND event_type = 'deadline' AND COALESCE(completed, 0) = 0
+         UNION ALL
+         SELECT title, due_date FROM tasks
+         WHERE matter_id = ? AND due_date IS NOT NULL AND status IN ('pending', 'in_progress')
+         ORDER BY 2",
+    )
+    .bind(matter_id)
+    .bind(matter_id)
+    .fetch_all(&mut *conn)
+    .await
+    .context("Failed to check pending deadlines")?;
+    for (title, due) in deadlines {
+        blockers.push(CloseBlocker {
+            check: ClosingCheck::PendingDeadline,
+            detail: format!("{} is due {}", title, due),
+        });
+    }
+
+    Ok(blockers)
+}
+
+fn blocked_report(matter_id: &str, blockers: Vec<CloseBlocker>) -> CloseReport {
+    warn!("Matter {} cannot close: {} blocker(s)", matter_id, blockers.len());
+    CloseReport {
+        matter_id: matter_id.to_string(),
+        closed: false,
+        blockers,
+        retention: None,
+        export_path: None,
+    }
+}
+
+/// Remove an export made for a close that did not go through
+fn discard_export(path: Option<&Path>) {
+    if let Some(path) = path {
+        if let Err(err) = std::fs::remove_file(path) {
+            warn!("Failed to remove export {}: {}", path.display(), err);
+        }
+    }
+}
+
+/// A row as a JSON object keyed by column name
+fn row_to_json(row: &SqliteRow) -> Result<serde_json::Value> {
+    let mut record = serde_json::Map::new();
+    for column in row.columns() {
+        let raw = row.try_get_raw(column.ordinal())?;
+        let value = if raw.is_null() {
+            serde_json::Value::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" | "BOOLEAN" => row.try_get::<i64, _>(column.ordinal())?.into(),
+                "REAL" => row.try_get::<f64, _>(column.ordinal())?.into(),
+                "BLOB" => serde_json::Value::Null,
+                _ => row.try_get::<String, _>(column.ordinal())?.into(),
+            }
+        };
+        record.insert(column.name().to_string(), value);
+    }
+    Ok(serde_json::Value::Object(record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::{test_database, test_database_at};
+
+    async fn service() -> MatterClosingService {
+        service_on(test_database().await).await
+    }
+
+    async fn service_on(db: SqlitePool) -> MatterClosingService {
+        sqlx::raw_sql(
+            r#"INSERT INTO clients (id, first_name, last_name, client_type, status, created_at, updated_at)
+               VALUES ('client-1', 'Ada', 'Byron', '"individual"', '"active"', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z');
+               INSERT INTO matters (id, client_id, matter_number, title, matter_type, status, created_at, updated_at)
+               VALUES ('matter-1', 'client-1', '2026-0001', 'Byron v. Lovelace', '"civil"', '"active"', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z');
+               INSERT INTO trust_accounts (id, account_name, account_number, bank_name, routing_number, opened_date)
+               VALUES ('iolta-1', 'IOLTA', '000123', 'First Bank', '031000000', '2026-01-01T00:00:00Z')"#,
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        MatterClosingService::new(db)
+    }
+
+    async fn post_trust(service: &MatterClosingService, amount: f64, reconciled: bool) {
+        sqlx::query(
+            "INSERT INTO trust_transactions (id, trust_account_id, matter_id, client_id, transaction_type,
+                 transaction_date, amount, description, is_reconciled, created_at, created_by)
+             VALUES (?, 'iolta-1', 'matter-1', 'client-1', 'deposit', '2026-02-01T00:00:00Z', ?, 'Retainer', ?,
+                 '2026-02-01T00:00:00Z', 'bookkeeper')",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(amount)
+        .bind(reconciled)
+        .execute(&service.db)
+        .await
+        .unwrap();
+    }
+
+    async fn matter_status(service: &MatterClosingService) -> String {
+        sqlx::query_scalar("SELECT status FROM matters WHERE id = 'matter-1'")
+            .fetch_one(&service.db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_open_trust_balance_blocks_closing() {
+        let service = service().await;
+        post_trust(&service, 2500.0, true).await;
+
+        let report = service.close_matter("matter-1").await.unwrap();
+
+        assert!(!report.closed);
+        assert_eq!(report.blockers.len(), 1);
+        assert_eq!(report.blockers[0].check, ClosingCheck::TrustBalance);
+        assert!(report.retention.is_none());
+        assert_eq!(matter_status(&service).await, "\"active\"");
+    }
+
+    #[tokio::test]
+    async fn test_clean_matter_closes_and_is_retained() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service().await.with_file_export(dir.path().to_path_buf());
+        // Funds received and fully disbursed, all reconciled
+        post_trust(&service, 2500.0, true).await;
+        post_trust(&service, -2500.0, true).await;
+        sqlx::query(
+            "INSERT INTO case_events (id, matter_id, event_type, title, event_date, completed, created_at, updated_at)
+             VALUES ('event-1', 'matter-1', 'deadline', 'Answer due', '2026-03-01', 1, '2026-01-01', '2026-01-01')",
+        )
+        .execute(&service.db)
+        .await
+        .unwrap();
+
+        let report = service.close_matter("matter-1").await.unwrap();
+
+        assert!(report.closed, "unexpected blockers: {:?}", report.blockers);
+        assert_eq!(matter_status(&service).await, "\"closed\"");
+        let retention = report.retention.unwrap();
+        assert_eq!(retention.retain_until, retention.closed_at.checked_add_months(Months::new(84)).unwrap());
+        let scheduled: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM record_retention WHERE matter_id = 'matter-1'")
+            .fetch_one(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(scheduled, 1);
+
+        let mut archive = zip::ZipArchive::new(File::open(report.export_path.unwrap()).unwrap()).unwrap();
+        assert!(archive.by_name("matter.json").is_ok());
+        assert!(archive.by_name("trust_transactions.json").is_ok());
+
+        // Closing twice is refused
+        let err = service.close_matter("matter-1").await.unwrap_err();
+        assert_eq!(ServiceError::from(err).code(), "conflict");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_closes_close_the_matter_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("closing.db").display());
+        let service = service_on(test_database_at(&url).await).await;
+
+        let results = futures_util::future::join_all((0..4).map(|_| service.close_matter("matter-1"))).await;
+
+        let closed = results.iter().filter(|r| matches!(r, Ok(report) if report.closed)).count();
+        assert_eq!(closed, 1);
+        for err in results.into_iter().filter_map(Result::err) {
+            assert_eq!(ServiceError::from(err).code(), "conflict");
+        }
+        let scheduled: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM record_retention WHERE matter_id = 'matter-1'")
+            .fetch_one(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(scheduled, 1);
+    }
+}
@@ -0,0 +1,251 @@
+// Mediation & ADR - Feature #26
+// Records each side's positions across mediation rounds and tracks how the gap closes
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediationParty {
+    Plaintiff,
+    Defense,
+}
+
+impl MediationParty {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MediationParty::Plaintiff => "plaintiff",
+            MediationParty::Defense => "defense",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediationPosition {
+    pub id: String,
+    pub matter_id: String,
+    pub party: MediationParty,
+    pub amount: f64,
+    pub round: u32,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Standing demand and offer after a round; a side that did not move
+/// carries its previous position forward
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapRound {
+    pub round: u32,
+    pub plaintiff_demand: f64,
+    pub defense_offer: f64,
+    pub gap: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapReport {
+    pub matter_id: String,
+    pub rounds: Vec<GapRound>,
+    pub current_gap: Option<f64>,
+    /// Fraction of the opening gap closed so far (0.0-1.0)
+    pub convergence: Option<f64>,
+    /// True when the gap narrowed in the most recent round
+    pub converging: bool,
+    pub suggested_proposal: Option<f64>,
+    /// Target settlement from the matter's latest settlement calculation
+    pub settlement_target: Option<f64>,
+}
+
+pub struct MediationService {
+    db: SqlitePool,
+}
+
+impl MediationService {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Record a party's demand or offer. Each party's positions are numbered
+    /// as successive rounds.
+    pub async fn record_mediation_position(
+        &self,
+        matter_id: &str,
+        party: MediationParty,
+        amount: f64,
+    ) -> Result<MediationPosition> {
+        if !amount.is_finite() || amount < 0.0 {
+            anyhow::bail!("Mediation position must be a non-negative amount");
+        }
+
+        let previous_round: Option<i64> = sqlx::query(
+            "SELECT MAX(round) AS round FROM mediation_positions WHERE matter_id = ? AND party = ?",
+        )
+        .bind(matter_id)
+        .bind(party.as_str())
+        .fetch_one(&self.db)
+        .await
+        .context("Failed to load mediation rounds")?
+        .try_get("round")?;
+
+        let position = MediationPosition {
+            id: Uuid::new_v4().to_string(),
+            matter_id: matter_id.to_string(),
+            party,
+            amount,
+            round: previous_round.unwrap_or(0) as u32 + 1,
+            recorded_at: Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO mediation_positions (id, matter_id, party, amount, round, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&position.id)
+        .bind(&position.matter_id)
+        .bind(party.as_str())
+        .bind(position.amount)
+        .bind(position.round as i64)
+        .bind(position.recorded_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to record mediation position")?;
+
+        info!("Recorded {} position {} (round {}) on matter {}",
+              party.as_str(), amount, position.round, matter_id);
+        Ok(position)
+    }
+
+    /// Gap between the parties over rounds, with a proposal between the
+    /// current positions weighted toward the settlement calculation's target
+    pub async fn mediation_gap(&self, matter_id: &str) -> Result<GapReport> {
+        let rows = sqlx::query(
+            "SELECT party, amount, round FROM mediation_positions WHERE matter_id = ? ORDER BY round, recorded_at",
+        )
+        .bind(matter_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load mediation positions")?;
+
+        let mut demands: Vec<(u32, f64)> = Vec::new();
+        let mut offers: Vec<(u32, f64)> = Vec::new();
+        for row in &rows {
+            let round = row.try_get::<i64, _>("round")? as u32;
+            let amount: f64 = row.try_get("amount")?;
+            match row.try_get::<String, _>("party")?.as_str() {
+                "plaintiff" => demands.push((round, amount)),
+                _ => offers.push((round, amount)),
+            }
+        }
+
+        let settlement_target = self.settlement_target(matter_id).await?;
+        Ok(build_gap_report(matter_id, &demands, &offers, settlement_target))
+    }
+
+    async fn settlement_target(&self, matter_id: &str) -> Result<Option<f64>> {
+        let row = sqlx::query(
+            "SELECT target_settlement FROM settlement_calculations
+             WHERE matter_id = ? ORDER BY calculated_at DESC LIMIT 1",
+        )
+        .bind(matter_id)
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to load settlement calculation")?;
+
+        Ok(row.map(|r| r.try_get("target_settlement")).transpose()?)
+    }
+}
+
+fn build_gap_report(
+    matter_id: &str,
+    demands: &[(u32, f64)],
+    offers: &[(u32, f64)],
+    settlement_target: Option<f64>,
+) -> GapReport {
+    let last_round = demands.iter().chain(offers).map(|(r, _)| *r).max().unwrap_or(0);
+    let position_at = |positions: &[(u32, f64)], round: u32| {
+        positions.iter().rev().find(|(r, _)| *r <= round).map(|(_, amount)| *amount)
+    };
+
+    let rounds: Vec<GapRound> = (1..=last_round)
+        .filter_map(|round| {
+            let plaintiff_demand = position_at(demands, round)?;
+            let defense_offer = position_at(offers, round)?;
+            Some(GapRound {
+                round,
+                plaintiff_demand,
+                defense_offer,
+                gap: (plaintiff_demand - defense_offer).max(0.0),
+            })
+        })
+        .collect();
+
+    let current = rounds.last();
+    let current_gap = current.map(|r| r.gap);
+    let convergence = match (rounds.first(), current) {
+        (Some(first), Some(last)) if first.gap > 0.0 => Some(((first.gap - last.gap) / first.gap).clamp(0.0, 1.0)),
+        (Some(_), Some(last)) => Some(if last.gap == 0.0 { 1.0 } else { 0.0 }),
+        _ => None,
+    };
+    let converging = rounds.len() >= 2 && rounds[rounds.len() - 1].gap < rounds[rounds.len() - 2].gap;
+
+    let suggested_proposal = current.map(|r| {
+        let midpoint = (r.plaintiff_demand + r.defense_offer) / 2.0;
+        let low = r.defense_offer.min(r.plaintiff_demand);
+        let high = r.defense_offer.max(r.plaintiff_demand);
+        match settlement_target {
+            Some(target) => ((midpoint + target) / 2.0).clamp(low, high),
+            None => midpoint,
+        }
+    });
+
+    GapReport {
+        matter_id: matter_id.to_string(),
+        rounds,
+        current_gap,
+        convergence,
+        converging,
+        suggested_proposal,
+        settlement_target,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn mediation_db() -> SqlitePool {
+        let db = test_database().await;
+        db
+    }
+
+    #[tokio::test]
+    async fn test_narrowing_positions_reduce_gap() {
+        let service = MediationService::new(mediation_db().await);
+
+        for (demand, offer) in [(500_000.0, 100_000.0), (400_000.0, 150_000.0), (325_000.0, 200_000.0)] {
+            service.record_mediation_position("m1", MediationParty::Plaintiff, demand).await.unwrap();
+            service.record_mediation_position("m1", MediationParty::Defense, offer).await.unwrap();
+        }
+
+        let report = service.mediation_gap("m1").await.unwrap();
+
+        let gaps: Vec<f64> = report.rounds.iter().map(|r| r.gap).collect();
+        assert_eq!(gaps, vec![400_000.0, 250_000.0, 125_000.0]);
+        assert_eq!(report.current_gap, Some(125_000.0));
+        assert!(report.converging);
+        assert!((report.convergence.unwrap() - 0.6875).abs() < 1e-9);
+        assert_eq!(report.suggested_proposal, Some(262_500.0));
+    }
+
+    #[test]
+    fn test_proposal_weighted_toward_settlement_target() {
+        let report = build_gap_report("m1", &[(1, 300_000.0)], &[(1, 100_000.0)], Some(260_000.0));
+
+        assert_eq!(report.suggested_proposal, Some(230_000.0));
+    }
+}
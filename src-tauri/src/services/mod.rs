@@ -2,36 +2,59 @@
 // Contains business logic and command handlers
 
 // Core Services
+pub mod audit_log;
+pub mod service_error;
 pub mod automation;
 pub mod citations;
 pub mod commands;
 pub mod court_rules;
 pub mod database;
+pub mod backup;
 pub mod drafting;
+pub mod draft_jobs;
+pub mod numbering;
 pub mod export;
+pub mod redaction;
+pub mod permissions;
+pub mod users;
 pub mod security;
+pub mod secret_store;
 pub mod task_runner;
 pub mod watchlist;
+pub mod webhooks;
 pub mod case_management;
+pub mod matter_closing;
 pub mod pleading_formatter;
 pub mod ai_citation_service;
+pub mod ai_suggestions;
 
 // Tier 1: Core Revenue Features (10 features)
 pub mod document_assembly;       // Feature #1 - AI Document Assembly
 pub mod conflict_checking;       // Feature #2 - Conflict Checking
 pub mod time_tracking;           // Feature #3 - Time Tracking
+pub mod activity_monitor;        // Automatic time capture from activity
 pub mod billing;                 // Feature #4 - Billing & Invoicing
+pub mod currency;                // Currencies and exchange rates for billing
+pub mod receipts;                // Expense receipt OCR
 pub mod email_integration;       // Feature #5 - Email Integration
+pub mod email_providers;         // Gmail API and Microsoft Graph mailbox access
+pub mod attachment_scanner;      // Attachment type and size checks
 pub mod contract_review;         // Feature #6 - Contract Review AI
 pub mod legal_research;          // Feature #7 - Legal Research
 pub mod settlement_calculator;   // Feature #8 - Settlement Calculator (FLAGSHIP)
+pub mod settlement_calculator_enhanced; // Jurisdiction rules, AI analysis, negotiation
 pub mod export_settlement;       // Settlement export utilities
 pub mod speech_to_text;          // Feature #9 - Speech-to-Text
 pub mod expert_witness;          // Feature #10 - Expert Witness Management
 pub mod discovery;               // Feature #11 - Discovery Management
+pub mod production;              // Discovery productions: scrubbing, Bates, log
+pub mod bates;                   // Bates numbering for PDF document sets
 
 // Tier 2: Competitive Advantage (10 features)
 pub mod court_filing;            // Feature #12 - Court E-Filing
+pub mod efiling_queue;           // E-filing retry / dead-letter queue
+pub mod efiling_receipts;        // E-filing acceptance receipts
+pub mod filing_fees;             // Court filing fee schedules
 pub mod crm;                     // Feature #13 - CRM & Client Intake
 pub mod marketing;               // Feature #14 - Legal Marketing Suite
 // court_rules already declared above  // Feature #15 - Court Rules Database
@@ -64,10 +87,17 @@ pub mod bulk_import_service;
 pub mod speech_recognition;
 pub mod ai_research_assistant;
 pub mod document_comparison;
+pub mod document_store;
+pub mod docx_import;
+pub mod global_search;
 pub mod ai_legal_research;
 pub mod esignature;
 pub mod calendar_sync;
 pub mod client_portal;
+pub mod system_health;
+pub mod system_info;
+pub mod shutdown;
+pub mod logs;
 
 // Re-export commonly used types
 pub use commands::*;
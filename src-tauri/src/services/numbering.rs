@@ -0,0 +1,114 @@
+// Document numbering - invoice and matter numbers from configurable schemes
+// Sequence values come from the number_sequences table, incremented atomically
+// so concurrent requests never receive the same number
+
+use crate::config::NumberingScheme;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use sqlx::SqliteExecutor;
+
+/// Next number in sequence `name` under `scheme`, e.g. "INV-2024-001".
+/// `segment` is inserted after the prefix (the matter type code for matters).
+pub async fn next_number(
+    db: impl SqliteExecutor<'_>,
+    scheme: &NumberingScheme,
+    name: &str,
+    segment: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    let year = now.year();
+    let period = if scheme.reset_yearly { year.to_string() } else { String::new() };
+
+    let value: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO number_sequences (name, period, last_value) VALUES (?, ?, 1)
+        ON CONFLICT (name, period) DO UPDATE SET last_value = last_value + 1
+        RETURNING last_value
+        "#,
+    )
+    .bind(name)
+    .bind(&period)
+    .fetch_one(db)
+    .await
+    .with_context(|| format!("Failed to advance number sequence {}", name))?;
+
+    Ok(format_number(scheme, segment, year, value))
+}
+
+pub fn format_number(scheme: &NumberingScheme, segment: Option<&str>, year: i32, value: i64) -> String {
+    let mut parts: Vec<String> = Vec::with_capacity(4);
+    if !scheme.prefix.is_empty() {
+        parts.push(scheme.prefix.clone());
+    }
+    if let Some(segment) = segment.filter(|s| !s.is_empty()) {
+        parts.push(segment.to_string());
+    }
+    if scheme.include_year {
+        parts.push(year.to_string());
+    }
+    parts.push(format!("{:0width$}", value, width = scheme.padding));
+    parts.join(&scheme.separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database_at;
+    use chrono::TimeZone;
+    use std::collections::HashSet;
+
+    async fn pool(dir: &tempfile::TempDir) -> SqlitePool {
+        // A file database, so every pooled connection sees the same sequences
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("numbers.db").display());
+        let db = test_database_at(&url).await;
+        db
+    }
+
+    fn year_scheme() -> NumberingScheme {
+        NumberingScheme {
+            prefix: String::new(),
+            separator: "-".to_string(),
+            include_year: true,
+            padding: 3,
+            reset_yearly: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_year_reset_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = pool(&dir).await;
+        let scheme = year_scheme();
+        let dec = Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap();
+        let jan = Utc.with_ymd_and_hms(2025, 1, 2, 9, 0, 0).unwrap();
+
+        assert_eq!(next_number(&db, &scheme, "invoice", None, dec).await.unwrap(), "2024-001");
+        assert_eq!(next_number(&db, &scheme, "invoice", None, dec).await.unwrap(), "2024-002");
+        assert_eq!(next_number(&db, &scheme, "invoice", None, jan).await.unwrap(), "2025-001");
+
+        let continuous = NumberingScheme { reset_yearly: false, prefix: "INV".to_string(), ..scheme };
+        assert_eq!(next_number(&db, &continuous, "invoice", None, jan).await.unwrap(), "INV-2025-001");
+        assert_eq!(next_number(&db, &continuous, "invoice", None, jan).await.unwrap(), "INV-2025-002");
+        assert_eq!(format_number(&continuous, Some("CIV"), 2025, 12345), "INV-CIV-2025-12345");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_generation_has_no_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = pool(&dir).await;
+        let now = Utc::now();
+
+        let handles: Vec<_> = (0..50)
+            .map(|_| {
+                let db = db.clone();
+                tokio::spawn(async move { next_number(&db, &year_scheme(), "invoice", None, now).await.unwrap() })
+            })
+            .collect();
+
+        let mut numbers = HashSet::new();
+        for handle in handles {
+            assert!(numbers.insert(handle.await.unwrap()));
+        }
+        assert_eq!(numbers.len(), 50);
+    }
+}
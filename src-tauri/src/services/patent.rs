@@ -0,0 +1,278 @@
+// Patent & Trademark - Feature #25
+// USPTO docketing: office-action responses, maintenance fees and statements of use,
+// computed from filing/issue dates and fed to the deadline calendar
+
+use crate::services::calendar_sync::{DeadlineType, LegalDeadline, Priority};
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Shortened statutory period for office-action responses
+const OFFICE_ACTION_RESPONSE_MONTHS: u32 = 3;
+/// Latest response date with extension fees (35 U.S.C. § 133, 37 C.F.R. § 2.62)
+const OFFICE_ACTION_MAX_MONTHS: u32 = 6;
+/// Statement of use is due six months after the notice of allowance,
+/// extendable in six-month increments to 36 months
+const STATEMENT_OF_USE_MONTHS: u32 = 6;
+const STATEMENT_OF_USE_MAX_MONTHS: u32 = 36;
+/// Maintenance fee windows open at 3, 7 and 11 years after issue; each may be
+/// paid without surcharge for six months and with surcharge for six more
+const MAINTENANCE_FEE_YEARS: [u32; 3] = [3, 7, 11];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpKind {
+    Patent,
+    Trademark,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpStatus {
+    Pending,
+    ResponseDue,
+    AbandonmentRisk,
+    Abandoned,
+    Allowed,
+    Issued,
+    Registered,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeAction {
+    pub mailed_on: DateTime<Utc>,
+    pub is_final: bool,
+    pub responded_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpMatter {
+    pub id: String,
+    pub matter_id: String,
+    pub kind: IpKind,
+    pub application_number: String,
+    pub title: String,
+    pub filing_date: DateTime<Utc>,
+    pub issue_date: Option<DateTime<Utc>>,
+    pub notice_of_allowance_date: Option<DateTime<Utc>>,
+    pub statement_of_use_filed: bool,
+    pub maintenance_fees_paid: Vec<u32>,
+    pub office_actions: Vec<OfficeAction>,
+    pub status: IpStatus,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum IpDeadlineKind {
+    OfficeActionResponse,
+    /// Maintenance fee stage, in years after issue (3.5, 7.5, 11.5 year fees)
+    MaintenanceFee(u32),
+    StatementOfUse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpDeadline {
+    pub kind: IpDeadlineKind,
+    /// Earliest date the action may be taken, for windowed deadlines
+    pub opens: Option<DateTime<Utc>>,
+    pub due: DateTime<Utc>,
+    /// Last date with extension or surcharge before rights are lost
+    pub final_due: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpReminder {
+    pub deadline: IpDeadline,
+    pub days_remaining: i64,
+}
+
+impl IpMatter {
+    /// Open deadlines for this matter, soonest first
+    pub fn deadlines(&self) -> Vec<IpDeadline> {
+        let mut deadlines: Vec<IpDeadline> = self
+            .office_actions
+            .iter()
+            .filter(|oa| oa.responded_on.is_none())
+            .map(|oa| IpDeadline {
+                kind: IpDeadlineKind::OfficeActionResponse,
+                opens: None,
+                due: add_months(oa.mailed_on, OFFICE_ACTION_RESPONSE_MONTHS),
+                final_due: add_months(oa.mailed_on, OFFICE_ACTION_MAX_MONTHS),
+            })
+            .collect();
+
+        if let (IpKind::Patent, Some(issued)) = (self.kind, self.issue_date) {
+            for years in MAINTENANCE_FEE_YEARS {
+                if self.maintenance_fees_paid.contains(&years) {
+                    continue;
+                }
+                let opens = add_months(issued, years * 12);
+                deadlines.push(IpDeadline {
+                    kind: IpDeadlineKind::MaintenanceFee(years),
+                    opens: Some(opens),
+                    due: add_months(opens, 6),
+                    final_due: add_months(opens, 12),
+                });
+                // Later fees only matter once the earlier one is paid
+                break;
+            }
+        }
+
+        if let (IpKind::Trademark, Some(allowed), false) =
+            (self.kind, self.notice_of_allowance_date, self.statement_of_use_filed)
+        {
+            deadlines.push(IpDeadline {
+                kind: IpDeadlineKind::StatementOfUse,
+                opens: Some(allowed),
+                due: add_months(allowed, STATEMENT_OF_USE_MONTHS),
+                final_due: add_months(allowed, STATEMENT_OF_USE_MAX_MONTHS),
+            });
+        }
+
+        deadlines.sort_by_key(|d| d.due);
+        deadlines
+    }
+
+    /// Re-derive status from outstanding office actions as of `today`. A
+    /// response past its due date is at risk of abandonment; past the
+    /// final extended date the application is abandoned.
+    pub fn refresh_status(&mut self, today: DateTime<Utc>) -> IpStatus {
+        if matches!(self.status, IpStatus::Abandoned | IpStatus::Issued | IpStatus::Registered) {
+            return self.status;
+        }
+
+        let outstanding: Vec<IpDeadline> = self
+            .deadlines()
+            .into_iter()
+            .filter(|d| d.kind == IpDeadlineKind::OfficeActionResponse)
+            .collect();
+
+        self.status = if outstanding.iter().any(|d| today > d.final_due) {
+            IpStatus::Abandoned
+        } else if outstanding.iter().any(|d| today > d.due) {
+            IpStatus::AbandonmentRisk
+        } else if !outstanding.is_empty() {
+            IpStatus::ResponseDue
+        } else if self.notice_of_allowance_date.is_some() {
+            IpStatus::Allowed
+        } else {
+            IpStatus::Pending
+        };
+
+        self.status
+    }
+
+    /// Overdue deadlines and those due within the longest `lead_days` offset of `today`
+    pub fn reminders(&self, today: DateTime<Utc>, lead_days: &[i64]) -> Vec<IpReminder> {
+        let horizon = lead_days.iter().copied().max().unwrap_or(0);
+        self.deadlines()
+            .into_iter()
+            .filter_map(|deadline| {
+                let days_remaining = (deadline.due - today).num_days();
+                (days_remaining <= horizon).then_some(IpReminder { deadline, days_remaining })
+            })
+            .collect()
+    }
+
+    /// Open deadlines in the calendar's deadline format
+    pub fn to_legal_deadlines(&self) -> Vec<LegalDeadline> {
+        self.deadlines()
+            .into_iter()
+            .enumerate()
+            .map(|(i, deadline)| {
+                let (deadline_type, description, priority) = match deadline.kind {
+                    IpDeadlineKind::OfficeActionResponse => (
+                        DeadlineType::Response,
+                        format!("Office action response due for {}", self.application_number),
+                        Priority::Critical,
+                    ),
+                    IpDeadlineKind::MaintenanceFee(years) => (
+                        DeadlineType::Filing,
+                        format!("{}.5-year maintenance fee for {}", years, self.application_number),
+                        Priority::High,
+                    ),
+                    IpDeadlineKind::StatementOfUse => (
+                        DeadlineType::Filing,
+                        format!("Statement of use (or extension) due for {}", self.application_number),
+                        Priority::High,
+                    ),
+                };
+
+                LegalDeadline {
+                    id: format!("{}-{}", self.id, i),
+                    matter_id: self.matter_id.clone(),
+                    docket_number: Some(self.application_number.clone()),
+                    deadline_type,
+                    deadline_date: deadline.due,
+                    calculated_from: deadline.opens.or(Some(self.filing_date)),
+                    jurisdiction: Some("USPTO".to_string()),
+                    court_rules: Vec::new(),
+                    description,
+                    priority,
+                    auto_calculated: true,
+                }
+            })
+            .collect()
+    }
+}
+
+fn add_months(date: DateTime<Utc>, months: u32) -> DateTime<Utc> {
+    date.checked_add_months(Months::new(months)).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    fn patent() -> IpMatter {
+        IpMatter {
+            id: "ip-1".to_string(),
+            matter_id: "matter-1".to_string(),
+            kind: IpKind::Patent,
+            application_number: "17/123,456".to_string(),
+            title: "Widget".to_string(),
+            filing_date: date(2019, 2, 1),
+            issue_date: None,
+            notice_of_allowance_date: None,
+            statement_of_use_filed: false,
+            maintenance_fees_paid: Vec::new(),
+            office_actions: Vec::new(),
+            status: IpStatus::Pending,
+        }
+    }
+
+    #[test]
+    fn test_maintenance_fee_window_from_issue_date() {
+        let mut issued = patent();
+        issued.issue_date = Some(date(2021, 3, 16));
+        issued.status = IpStatus::Issued;
+
+        let deadlines = issued.deadlines();
+        assert_eq!(deadlines.len(), 1);
+        assert_eq!(deadlines[0].kind, IpDeadlineKind::MaintenanceFee(3));
+        assert_eq!(deadlines[0].opens, Some(date(2024, 3, 16)));
+        assert_eq!(deadlines[0].due, date(2024, 9, 16));
+        assert_eq!(deadlines[0].final_due, date(2025, 3, 16));
+
+        issued.maintenance_fees_paid.push(3);
+        assert_eq!(issued.deadlines()[0].opens, Some(date(2028, 3, 16)));
+    }
+
+    #[test]
+    fn test_missed_office_action_response_flags_abandonment_risk() {
+        let mut pending = patent();
+        pending.office_actions.push(OfficeAction {
+            mailed_on: date(2024, 1, 10),
+            is_final: false,
+            responded_on: None,
+        });
+
+        assert_eq!(pending.refresh_status(date(2024, 3, 1)), IpStatus::ResponseDue);
+        assert_eq!(pending.refresh_status(date(2024, 4, 11)), IpStatus::AbandonmentRisk);
+        assert_eq!(pending.refresh_status(date(2024, 7, 11)), IpStatus::Abandoned);
+    }
+}
@@ -0,0 +1,170 @@
+// Role-based permissions for sensitive commands
+// Each role carries a fixed permission set; command handlers call
+// require_permission before touching money, configuration or court filings
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Attorney,
+    Paralegal,
+    ReadOnly,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ViewRecords,
+    EditRecords,
+    ProcessPayment,
+    TrustTransfer,
+    SubmitEFiling,
+    WaiveConflict,
+    UpdateConfig,
+}
+
+impl Role {
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::Admin => &[ViewRecords, EditRecords, ProcessPayment, TrustTransfer, SubmitEFiling, WaiveConflict, UpdateConfig],
+            Role::Attorney => &[ViewRecords, EditRecords, ProcessPayment, TrustTransfer, SubmitEFiling, WaiveConflict],
+            Role::Paralegal => &[ViewRecords, EditRecords],
+            Role::ReadOnly => &[ViewRecords],
+        }
+    }
+
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::Admin => "Admin",
+            Role::Attorney => "Attorney",
+            Role::Paralegal => "Paralegal",
+            Role::ReadOnly => "ReadOnly",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            Permission::ViewRecords => "view records",
+            Permission::EditRecords => "edit records",
+            Permission::ProcessPayment => "process payments",
+            Permission::TrustTransfer => "move trust funds",
+            Permission::SubmitEFiling => "submit e-filings",
+            Permission::WaiveConflict => "waive conflicts of interest",
+            Permission::UpdateConfig => "change configuration",
+        };
+        f.write_str(action)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserSession {
+    pub user_id: String,
+    pub role: Role,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum AuthorizationError {
+    #[error("Not signed in; sign in to {0}")]
+    NotSignedIn(Permission),
+    #[error("{user_id} ({role}) is not permitted to {permission}")]
+    Forbidden {
+        user_id: String,
+        role: Role,
+        permission: Permission,
+    },
+}
+
+/// Guard for the start of a sensitive command handler
+pub fn require_permission(session: &UserSession, permission: Permission) -> Result<(), AuthorizationError> {
+    if session.role.has(permission) {
+        return Ok(());
+    }
+
+    warn!("Denied {} to {} ({})", permission, session.user_id, session.role);
+    Err(AuthorizationError::Forbidden {
+        user_id: session.user_id.clone(),
+        role: session.role,
+        permission,
+    })
+}
+
+/// The signed-in user, managed as Tauri state
+#[derive(Debug, Default)]
+pub struct CurrentUser(RwLock<Option<UserSession>>);
+
+impl CurrentUser {
+    pub fn sign_in(&self, session: UserSession) {
+        info!("Signed in {} as {}", session.user_id, session.role);
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(session);
+    }
+
+    pub fn sign_out(&self) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub fn session(&self) -> Option<UserSession> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The current session, if it holds `permission`
+    pub fn require(&self, permission: Permission) -> Result<UserSession, AuthorizationError> {
+        let session = self.session().ok_or(AuthorizationError::NotSignedIn(permission))?;
+        require_permission(&session, permission)?;
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(role: Role) -> UserSession {
+        UserSession {
+            user_id: "jdoe".to_string(),
+            role,
+        }
+    }
+
+    #[test]
+    fn test_read_only_is_denied_trust_withdrawal_and_admin_is_allowed() {
+        let err = require_permission(&session(Role::ReadOnly), Permission::TrustTransfer).unwrap_err();
+        assert_eq!(err.to_string(), "jdoe (ReadOnly) is not permitted to move trust funds");
+
+        assert!(require_permission(&session(Role::Admin), Permission::TrustTransfer).is_ok());
+        assert!(require_permission(&session(Role::Paralegal), Permission::TrustTransfer).is_err());
+    }
+
+    #[test]
+    fn test_no_session_is_denied() {
+        let current = CurrentUser::default();
+        assert_eq!(
+            current.require(Permission::SubmitEFiling),
+            Err(AuthorizationError::NotSignedIn(Permission::SubmitEFiling))
+        );
+
+        current.sign_in(session(Role::Attorney));
+        assert_eq!(current.require(Permission::SubmitEFiling).unwrap().user_id, "jdoe");
+        assert!(current.require(Permission::UpdateConfig).is_err());
+    }
+
+    #[test]
+    fn test_only_attorneys_and_admins_waive_conflicts() {
+        assert!(require_permission(&session(Role::Attorney), Permission::WaiveConflict).is_ok());
+        assert!(require_permission(&session(Role::Admin), Permission::WaiveConflict).is_ok());
+        assert!(require_permission(&session(Role::Paralegal), Permission::WaiveConflict).is_err());
+    }
+}
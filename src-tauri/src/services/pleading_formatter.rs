@@ -5,6 +5,7 @@
 use crate::domain::case_management::*;
 use crate::domain::*;
 use crate::services::court_rules::CourtRulesService;
+use crate::services::docx_import::parse_docx;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -154,6 +155,19 @@ impl PleadingFormatter {
         })
     }
 
+    /// Reformat an existing Word pleading under the court's rules
+    pub async fn format_docx(
+        &self,
+        path: &std::path::Path,
+        matter: &Matter,
+        client: &Client,
+        document_type: &DocumentType,
+        court_rules: &CourtRules,
+    ) -> Result<FormattedDocument> {
+        let parsed = parse_docx(path)?;
+        self.format_pleading(&parsed.to_text(), matter, client, document_type, court_rules).await
+    }
+
     // ========================================================================
     // Court-Specific Format Rules
     // ========================================================================
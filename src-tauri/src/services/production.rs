@@ -0,0 +1,323 @@
+// Discovery productions - the copy of a document set that goes to opposing
+// counsel. Every page is scrubbed the same way under a `ScrubProfile`
+// (SSNs, dates of birth, account numbers and any custom patterns), numbered
+// with a Bates label, and listed in a production log. Documents withheld as
+// privileged keep their Bates number on a slip sheet so the ranges stay
+// contiguous.
+
+use crate::services::bates::bates_label;
+use crate::services::discovery::PrivilegeType;
+use crate::services::redaction::get_ssn_regex;
+use crate::services::service_error::ServiceError;
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+static DOB_REGEX: OnceLock<Regex> = OnceLock::new();
+static ACCOUNT_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_dob_regex() -> &'static Regex {
+    DOB_REGEX.get_or_init(|| {
+        // Only dates introduced as a birth date; other dates in a production
+        // are usually evidence
+        Regex::new(
+            r"(?i)(\b(?:DOB|D\.O\.B\.|date of birth|born(?: on)?)\s*:?\s*)(\d{1,2}/\d{1,2}/\d{2,4}|\d{4}-\d{2}-\d{2}|[A-Z][a-z]+\.? \d{1,2}, \d{4})",
+        )
+        .unwrap()
+    })
+}
+
+fn get_account_regex() -> &'static Regex {
+    ACCOUNT_REGEX.get_or_init(|| {
+        Regex::new(r"(?i)(\b(?:account|acct\.?|a/c)\s*(?:no\.?|number|#)?\s*:?\s*)(\d[\d -]{4,}\d)").unwrap()
+    })
+}
+
+/// Personal identifiers a profile can redact
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiEntity {
+    Ssn,
+    DateOfBirth,
+    AccountNumber,
+}
+
+impl PiiEntity {
+    pub fn label(self) -> &'static str {
+        match self {
+            PiiEntity::Ssn => "SSN",
+            PiiEntity::DateOfBirth => "DOB",
+            PiiEntity::AccountNumber => "Account Number",
+        }
+    }
+}
+
+/// A firm-specific pattern, e.g. a client's internal matter codes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrubPattern {
+    pub label: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrubProfile {
+    pub name: String,
+    pub bates_prefix: String,
+    pub bates_start: u64,
+    /// Zero-padded width of the Bates number
+    pub bates_digits: usize,
+    pub entities: Vec<PiiEntity>,
+    #[serde(default)]
+    pub patterns: Vec<ScrubPattern>,
+}
+
+impl Default for ScrubProfile {
+    fn default() -> Self {
+        Self {
+            name: "Standard".to_string(),
+            bates_prefix: "PROD".to_string(),
+            bates_start: 1,
+            bates_digits: 6,
+            entities: vec![PiiEntity::Ssn, PiiEntity::DateOfBirth, PiiEntity::AccountNumber],
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// A document to produce, as the text of each page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionDoc {
+    pub id: String,
+    pub title: String,
+    pub pages: Vec<String>,
+    /// Withheld from the production when set
+    #[serde(default)]
+    pub privilege: Option<PrivilegeType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProducedPage {
+    pub bates: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProducedDoc {
+    pub doc_id: String,
+    pub title: String,
+    pub pages: Vec<ProducedPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProductionLogEntry {
+    pub doc_id: String,
+    pub title: String,
+    pub bates_begin: String,
+    pub bates_end: String,
+    pub page_count: usize,
+    /// Redaction label -> count
+    pub redactions: BTreeMap<String, usize>,
+    pub withheld: Option<PrivilegeType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionSet {
+    pub profile: String,
+    pub documents: Vec<ProducedDoc>,
+    pub log: Vec<ProductionLogEntry>,
+    pub total_pages: usize,
+    pub total_redactions: usize,
+    /// Next unused Bates number, where a later production picks up
+    pub next_bates: u64,
+}
+
+/// Scrub, number and log `docs` under `profile`. Errors when a custom
+/// pattern does not compile or the Bates numbers outgrow `bates_digits`.
+pub fn export_production(docs: &[ProductionDoc], profile: &ScrubProfile) -> Result<ProductionSet> {
+    let custom: Vec<(String, Regex)> = profile
+        .patterns
+        .iter()
+        .map(|p| {
+            Regex::new(&p.pattern)
+                .map(|re| (p.label.clone(), re))
+                .map_err(|e| ServiceError::validation(format!("Invalid scrub pattern {}: {}", p.label, e)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut next = profile.bates_start;
+    let mut documents = Vec::with_capacity(docs.len());
+    let mut log = Vec::with_capacity(docs.len());
+
+    for doc in docs {
+        let mut redactions = BTreeMap::new();
+        let texts: Vec<String> = match &doc.privilege {
+            // One slip sheet stands in for the whole document
+            Some(privilege) => vec![format!("Document withheld: {:?} privilege", privilege)],
+            None => doc
+                .pages
+                .iter()
+                .map(|page| scrub_page(page, profile, &custom, &mut redactions))
+                .collect(),
+        };
+
+        let mut pages = Vec::with_capacity(texts.len());
+        for text in texts {
+            pages.push(ProducedPage {
+                bates: bates_label(&profile.bates_prefix, next, profile.bates_digits)?,
+                text,
+            });
+            next += 1;
+        }
+
+        let (bates_begin, bates_end) = match (pages.first(), pages.last()) {
+            (Some(first), Some(last)) => (first.bates.clone(), last.bates.clone()),
+            // A document with no pages takes no numbers
+            _ => (String::new(), String::new()),
+        };
+        log.push(ProductionLogEntry {
+            doc_id: doc.id.clone(),
+            title: doc.title.clone(),
+            bates_begin,
+            bates_end,
+            page_count: pages.len(),
+            redactions,
+            withheld: doc.privilege.clone(),
+        });
+        documents.push(ProducedDoc {
+            doc_id: doc.id.clone(),
+            title: doc.title.clone(),
+            pages,
+        });
+    }
+
+    Ok(ProductionSet {
+        profile: profile.name.clone(),
+        total_pages: log.iter().map(|e| e.page_count).sum(),
+        total_redactions: log.iter().flat_map(|e| e.redactions.values()).sum(),
+        documents,
+        log,
+        next_bates: next,
+    })
+}
+
+fn scrub_page(
+    text: &str,
+    profile: &ScrubProfile,
+    custom: &[(String, Regex)],
+    redactions: &mut BTreeMap<String, usize>,
+) -> String {
+    let mut text = text.to_string();
+
+    for entity in &profile.entities {
+        let label = entity.label();
+        let (regex, keep_lead_in) = match entity {
+            PiiEntity::Ssn => (get_ssn_regex(), false),
+            PiiEntity::DateOfBirth => (get_dob_regex(), true),
+            PiiEntity::AccountNumber => (get_account_regex(), true),
+        };
+        let count = regex.find_iter(&text).count();
+        if count == 0 {
+            continue;
+        }
+        let replacement = redaction_marker(label);
+        text = if keep_lead_in {
+            // "DOB: 01/02/1980" -> "DOB: [REDACTED - DOB]"
+            regex.replace_all(&text, format!("${{1}}{}", replacement)).into_owned()
+        } else {
+            regex.replace_all(&text, replacement.as_str()).into_owned()
+        };
+        *redactions.entry(label.to_string()).or_default() += count;
+    }
+
+    for (label, regex) in custom {
+        let count = regex.find_iter(&text).count();
+        if count > 0 {
+            text = regex.replace_all(&text, redaction_marker(label).as_str()).into_owned();
+            *redactions.entry(label.clone()).or_default() += count;
+        }
+    }
+    text
+}
+
+fn redaction_marker(label: &str) -> String {
+    format!("[REDACTED - {}]", label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(id: &str, pages: &[&str]) -> ProductionDoc {
+        ProductionDoc {
+            id: id.to_string(),
+            title: format!("Document {}", id),
+            pages: pages.iter().map(|p| p.to_string()).collect(),
+            privilege: None,
+        }
+    }
+
+    fn profile() -> ScrubProfile {
+        ScrubProfile {
+            bates_prefix: "SMITH".to_string(),
+            ..ScrubProfile::default()
+        }
+    }
+
+    #[test]
+    fn test_pii_is_redacted() {
+        let docs = [doc(
+            "intake",
+            &["Client SSN 123-45-6789, DOB: 04/12/1978. Checking account no. 0012-3456-78 at First Bank."],
+        )];
+
+        let set = export_production(&docs, &profile()).unwrap();
+
+        assert_eq!(
+            set.documents[0].pages[0].text,
+            "Client SSN [REDACTED - SSN], DOB: [REDACTED - DOB]. Checking account no. [REDACTED - Account Number] at First Bank."
+        );
+    }
+
+    #[test]
+    fn test_bates_numbers_are_sequential_across_documents() {
+        let mut withheld = doc("memo", &["Strategy memo", "page two"]);
+        withheld.privilege = Some(PrivilegeType::AttorneyClient);
+        let docs = [doc("a", &["one", "two", "three"]), withheld, doc("b", &["four", "five"])];
+
+        let set = export_production(&docs, &profile()).unwrap();
+
+        let bates: Vec<&str> = set
+            .documents
+            .iter()
+            .flat_map(|d| d.pages.iter().map(|p| p.bates.as_str()))
+            .collect();
+        assert_eq!(
+            bates,
+            vec!["SMITH000001", "SMITH000002", "SMITH000003", "SMITH000004", "SMITH000005", "SMITH000006"]
+        );
+        assert_eq!(set.log[1].bates_begin, "SMITH000004");
+        assert_eq!(set.log[1].bates_end, "SMITH000004");
+        assert_eq!(set.next_bates, 7);
+    }
+
+    #[test]
+    fn test_log_counts_match_production() {
+        let docs = [
+            doc("a", &["SSN 123-45-6789", "SSN 987-65-4321 and DOB 1980-01-02"]),
+            doc("b", &["Nothing sensitive"]),
+        ];
+
+        let set = export_production(&docs, &profile()).unwrap();
+
+        assert_eq!(set.log[0].page_count, 2);
+        assert_eq!(set.log[0].redactions.get("SSN"), Some(&2));
+        assert_eq!(set.log[0].redactions.get("DOB"), Some(&1));
+        assert!(set.log[1].redactions.is_empty());
+        assert_eq!(set.total_pages, 3);
+        assert_eq!(set.total_redactions, 3);
+        let produced_pages: usize = set.documents.iter().map(|d| d.pages.len()).sum();
+        assert_eq!(produced_pages, set.total_pages);
+    }
+}
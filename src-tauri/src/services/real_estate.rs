@@ -0,0 +1,282 @@
+// Real Estate Toolkit - Feature #22
+// Settlement statement preparation: prorations, transfer taxes and closing fees.
+// Proration follows the Pennsylvania convention that the buyer owns the day of closing.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Combined PA state (1%) and typical local (1%) realty transfer tax
+pub const PA_TRANSFER_TAX_RATE: f64 = 0.02;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealEstateDeal {
+    pub purchase_price: f64,
+    pub closing_date: NaiveDate,
+    pub earnest_money: f64,
+    pub loan_amount: f64,
+    pub seller_payoff: f64,
+
+    // Prorations
+    pub annual_property_tax: f64,
+    pub tax_period_start: NaiveDate,
+    /// True when the seller has already paid the current tax period
+    pub taxes_paid_in_advance: bool,
+    /// Dues for the closing month, already paid by the seller
+    pub monthly_hoa_dues: Option<f64>,
+
+    // Taxes and fees
+    pub transfer_tax_rate: f64,
+    /// Share of transfer tax paid by the buyer (customarily split 50/50 in PA)
+    pub buyer_transfer_tax_share: f64,
+    pub title_insurance_premium: f64,
+    pub settlement_fee: f64,
+    pub deed_recording_fee: f64,
+    pub mortgage_recording_fee: f64,
+    pub commission_rate: f64,
+    pub seller_concessions: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClosingLine {
+    pub description: String,
+    pub buyer_debit: f64,
+    pub buyer_credit: f64,
+    pub seller_debit: f64,
+    pub seller_credit: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosingStatement {
+    pub closing_date: NaiveDate,
+    pub lines: Vec<ClosingLine>,
+    pub cash_from_buyer: f64,
+    pub cash_to_seller: f64,
+    pub buyer_total: f64,
+    pub seller_total: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Proration {
+    pub seller_share: f64,
+    pub buyer_share: f64,
+    pub seller_days: i64,
+    pub buyer_days: i64,
+}
+
+/// Split an annual amount between seller and buyer for the one-year period
+/// starting `period_start`; the seller owns every day before `closing_date`.
+pub fn prorate_annual(amount: f64, period_start: NaiveDate, closing_date: NaiveDate) -> Proration {
+    let period_end = add_years(period_start, 1);
+    let total_days = (period_end - period_start).num_days().max(1);
+    let seller_days = (closing_date - period_start).num_days().clamp(0, total_days);
+    let buyer_days = total_days - seller_days;
+    let daily = amount / total_days as f64;
+
+    Proration {
+        seller_share: round_cents(daily * seller_days as f64),
+        buyer_share: round_cents(daily * buyer_days as f64),
+        seller_days,
+        buyer_days,
+    }
+}
+
+/// Build the buyer and seller columns of the settlement statement. The cash
+/// due from the buyer and to the seller are the balancing entries, so each
+/// side's debits equal its credits.
+pub fn compute_closing_statement(deal: &RealEstateDeal) -> ClosingStatement {
+    let mut lines = vec![
+        ClosingLine {
+            description: "Contract sales price".to_string(),
+            buyer_debit: round_cents(deal.purchase_price),
+            seller_credit: round_cents(deal.purchase_price),
+            ..Default::default()
+        },
+        ClosingLine {
+            description: "Deposit / earnest money".to_string(),
+            buyer_credit: round_cents(deal.earnest_money),
+            ..Default::default()
+        },
+        ClosingLine {
+            description: "Loan amount".to_string(),
+            buyer_credit: round_cents(deal.loan_amount),
+            ..Default::default()
+        },
+    ];
+
+    if deal.annual_property_tax > 0.0 {
+        let tax = prorate_annual(deal.annual_property_tax, deal.tax_period_start, deal.closing_date);
+        lines.push(if deal.taxes_paid_in_advance {
+            ClosingLine {
+                description: format!("Property taxes paid by seller ({} days to buyer)", tax.buyer_days),
+                buyer_debit: tax.buyer_share,
+                seller_credit: tax.buyer_share,
+                ..Default::default()
+            }
+        } else {
+            ClosingLine {
+                description: format!("Property taxes unpaid ({} days to seller)", tax.seller_days),
+                buyer_credit: tax.seller_share,
+                seller_debit: tax.seller_share,
+                ..Default::default()
+            }
+        });
+    }
+
+    if let Some(dues) = deal.monthly_hoa_dues.filter(|d| *d > 0.0) {
+        let month_days = days_in_month(deal.closing_date);
+        let buyer_days = month_days - deal.closing_date.day() as i64 + 1;
+        let reimbursement = round_cents(dues * buyer_days as f64 / month_days as f64);
+        lines.push(ClosingLine {
+            description: format!("HOA dues paid by seller ({} days to buyer)", buyer_days),
+            buyer_debit: reimbursement,
+            seller_credit: reimbursement,
+            ..Default::default()
+        });
+    }
+
+    let transfer_tax = round_cents(deal.purchase_price * deal.transfer_tax_rate);
+    let buyer_transfer_tax = round_cents(transfer_tax * deal.buyer_transfer_tax_share.clamp(0.0, 1.0));
+    lines.push(ClosingLine {
+        description: "Realty transfer tax".to_string(),
+        buyer_debit: buyer_transfer_tax,
+        seller_debit: round_cents(transfer_tax - buyer_transfer_tax),
+        ..Default::default()
+    });
+
+    for (description, amount) in [
+        ("Title insurance premium", deal.title_insurance_premium),
+        ("Settlement fee", deal.settlement_fee),
+        ("Deed recording fee", deal.deed_recording_fee),
+        ("Mortgage recording fee", deal.mortgage_recording_fee),
+    ] {
+        if amount > 0.0 {
+            lines.push(ClosingLine {
+                description: description.to_string(),
+                buyer_debit: round_cents(amount),
+                ..Default::default()
+            });
+        }
+    }
+
+    if deal.commission_rate > 0.0 {
+        lines.push(ClosingLine {
+            description: "Real estate commission".to_string(),
+            seller_debit: round_cents(deal.purchase_price * deal.commission_rate),
+            ..Default::default()
+        });
+    }
+
+    if deal.seller_payoff > 0.0 {
+        lines.push(ClosingLine {
+            description: "Payoff of seller's mortgage".to_string(),
+            seller_debit: round_cents(deal.seller_payoff),
+            ..Default::default()
+        });
+    }
+
+    if deal.seller_concessions > 0.0 {
+        lines.push(ClosingLine {
+            description: "Seller concessions".to_string(),
+            buyer_credit: round_cents(deal.seller_concessions),
+            seller_debit: round_cents(deal.seller_concessions),
+            ..Default::default()
+        });
+    }
+
+    let sum = |f: fn(&ClosingLine) -> f64| round_cents(lines.iter().map(f).sum());
+    let cash_from_buyer = round_cents(sum(|l| l.buyer_debit) - sum(|l| l.buyer_credit));
+    let cash_to_seller = round_cents(sum(|l| l.seller_credit) - sum(|l| l.seller_debit));
+
+    lines.push(ClosingLine {
+        description: "Cash due from buyer".to_string(),
+        buyer_credit: cash_from_buyer,
+        ..Default::default()
+    });
+    lines.push(ClosingLine {
+        description: "Cash due to seller".to_string(),
+        seller_debit: cash_to_seller,
+        ..Default::default()
+    });
+
+    let buyer_total = round_cents(lines.iter().map(|l| l.buyer_debit).sum());
+    let seller_total = round_cents(lines.iter().map(|l| l.seller_credit).sum());
+
+    ClosingStatement {
+        closing_date: deal.closing_date,
+        lines,
+        cash_from_buyer,
+        cash_to_seller,
+        buyer_total,
+        seller_total,
+    }
+}
+
+fn add_years(date: NaiveDate, years: i32) -> NaiveDate {
+    date.with_year(date.year() + years)
+        // Feb 29 rolls to Mar 1 in non-leap years
+        .unwrap_or_else(|| NaiveDate::from_ymd_opt(date.year() + years, 3, 1).unwrap())
+}
+
+fn days_in_month(date: NaiveDate) -> i64 {
+    let first = date.with_day(1).unwrap();
+    let next = first.checked_add_months(chrono::Months::new(1)).unwrap();
+    (next - first).num_days()
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal() -> RealEstateDeal {
+        RealEstateDeal {
+            purchase_price: 300_000.0,
+            closing_date: NaiveDate::from_ymd_opt(2023, 7, 15).unwrap(),
+            earnest_money: 10_000.0,
+            loan_amount: 240_000.0,
+            seller_payoff: 150_000.0,
+            annual_property_tax: 3_650.0,
+            tax_period_start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            taxes_paid_in_advance: true,
+            monthly_hoa_dues: Some(310.0),
+            transfer_tax_rate: PA_TRANSFER_TAX_RATE,
+            buyer_transfer_tax_share: 0.5,
+            title_insurance_premium: 1_850.0,
+            settlement_fee: 650.0,
+            deed_recording_fee: 256.75,
+            mortgage_recording_fee: 286.75,
+            commission_rate: 0.06,
+            seller_concessions: 3_000.0,
+        }
+    }
+
+    #[test]
+    fn test_buyer_and_seller_columns_reconcile() {
+        let statement = compute_closing_statement(&deal());
+
+        let total = |f: fn(&ClosingLine) -> f64| -> f64 { statement.lines.iter().map(f).sum() };
+        assert!((total(|l| l.buyer_debit) - total(|l| l.buyer_credit)).abs() < 0.005);
+        assert!((total(|l| l.seller_debit) - total(|l| l.seller_credit)).abs() < 0.005);
+
+        // 300,000 + 1,700 taxes + 170 HOA + 3,000 RTT + 3,043.50 fees
+        // - 10,000 deposit - 240,000 loan - 3,000 concessions
+        assert!((statement.cash_from_buyer - 54_913.50).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_mid_month_tax_proration() {
+        let closing = NaiveDate::from_ymd_opt(2023, 7, 15).unwrap();
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let tax = prorate_annual(3_650.0, start, closing);
+
+        // Jan 1 - Jul 14 is 195 seller days at $10/day
+        assert_eq!(tax.seller_days, 195);
+        assert_eq!(tax.buyer_days, 170);
+        assert_eq!(tax.seller_share, 1_950.0);
+        assert_eq!(tax.buyer_share, 1_700.0);
+    }
+}
@@ -0,0 +1,274 @@
+// Receipts - read the total, date and vendor off an expense receipt
+// Images and PDFs go through a pluggable OCR engine; plain-text receipts
+// (already OCRed or exported by a card provider) are read as-is
+
+use crate::utils::date::parse_date_flexible;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Below this, an extracted field should be checked by a person
+pub const RECEIPT_REVIEW_CONFIDENCE: f64 = 0.8;
+
+#[derive(Debug, Clone)]
+pub struct OcrText {
+    pub text: String,
+    /// Engine's confidence in the recognized text, 0.0-1.0
+    pub confidence: f64,
+}
+
+#[async_trait]
+pub trait OcrEngine: Send + Sync {
+    async fn recognize(&self, image: &Path) -> Result<OcrText>;
+}
+
+/// Tesseract through libtesseract. Only built with the `tesseract` feature;
+/// the language's traineddata must be installed.
+#[cfg(feature = "tesseract")]
+pub struct TesseractEngine {
+    /// Directory holding the traineddata; Tesseract's default when None
+    datapath: Option<String>,
+    language: String,
+}
+
+#[cfg(feature = "tesseract")]
+impl TesseractEngine {
+    pub fn new() -> Self {
+        Self { datapath: None, language: "eng".to_string() }
+    }
+
+    pub fn with_datapath(mut self, datapath: &str) -> Self {
+        self.datapath = Some(datapath.to_string());
+        self
+    }
+}
+
+#[cfg(feature = "tesseract")]
+impl Default for TesseractEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "tesseract")]
+#[async_trait]
+impl OcrEngine for TesseractEngine {
+    async fn recognize(&self, image: &Path) -> Result<OcrText> {
+        let image = image
+            .to_str()
+            .with_context(|| format!("Receipt path is not valid UTF-8: {}", image.display()))?
+            .to_string();
+        let datapath = self.datapath.clone();
+        let language = self.language.clone();
+
+        // libtesseract blocks for the whole recognition
+        tokio::task::spawn_blocking(move || {
+            let mut tesseract = tesseract::Tesseract::new(datapath.as_deref(), Some(&language))
+                .context("Failed to start Tesseract")?
+                .set_image(&image)
+                .with_context(|| format!("Failed to load receipt image {}", image))?
+                .recognize()
+                .context("Tesseract could not read the receipt")?;
+            let text = tesseract.get_text().context("Failed to read Tesseract output")?;
+            let confidence = f64::from(tesseract.mean_text_conf().clamp(0, 100)) / 100.0;
+            Ok::<_, anyhow::Error>(OcrText { text, confidence })
+        })
+        .await
+        .context("OCR task failed")?
+    }
+}
+
+/// The OCR engine this build ships with, if any
+pub fn default_ocr_engine() -> Option<std::sync::Arc<dyn OcrEngine>> {
+    #[cfg(feature = "tesseract")]
+    {
+        Some(std::sync::Arc::new(TesseractEngine::new()))
+    }
+    #[cfg(not(feature = "tesseract"))]
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedReceipt {
+    pub total: Option<f64>,
+    pub date: Option<DateTime<Utc>>,
+    pub vendor: Option<String>,
+    pub total_confidence: f64,
+    pub date_confidence: f64,
+    pub vendor_confidence: f64,
+    /// Fields that are missing or below the review confidence
+    pub needs_review: Vec<String>,
+    pub raw_text: String,
+}
+
+/// OCR `path` (or read it, for a text receipt) and extract its fields
+pub async fn parse_receipt(path: &Path, ocr: Option<&dyn OcrEngine>) -> Result<ParsedReceipt> {
+    let is_text = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+
+    let recognized = if is_text {
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read receipt {}", path.display()))?;
+        OcrText { text, confidence: 1.0 }
+    } else {
+        let ocr = ocr.context("No OCR engine configured for receipt images; build with the tesseract feature")?;
+        ocr.recognize(path).await?
+    };
+
+    Ok(extract_receipt_fields(&recognized.text, recognized.confidence))
+}
+
+/// Pull the total, date and vendor out of recognized receipt text. Each
+/// field's confidence is the OCR confidence scaled by how it was found.
+pub fn extract_receipt_fields(text: &str, ocr_confidence: f64) -> ParsedReceipt {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+
+    let (total, total_certainty) = find_total(&lines);
+    let (date, date_certainty) = find_date(&lines);
+    let (vendor, vendor_certainty) = find_vendor(&lines);
+
+    let mut receipt = ParsedReceipt {
+        total,
+        date,
+        vendor,
+        total_confidence: ocr_confidence * total_certainty,
+        date_confidence: ocr_confidence * date_certainty,
+        vendor_confidence: ocr_confidence * vendor_certainty,
+        needs_review: Vec::new(),
+        raw_text: text.to_string(),
+    };
+    for (field, confidence) in [
+        ("total", receipt.total_confidence),
+        ("date", receipt.date_confidence),
+        ("vendor", receipt.vendor_confidence),
+    ] {
+        if confidence < RECEIPT_REVIEW_CONFIDENCE {
+            receipt.needs_review.push(field.to_string());
+        }
+    }
+    receipt
+}
+
+fn amount_re() -> &'static Regex {
+    static AMOUNT_RE: OnceLock<Regex> = OnceLock::new();
+    AMOUNT_RE.get_or_init(|| Regex::new(r"\$?\s?([0-9]{1,3}(?:,[0-9]{3})*|[0-9]+)\.([0-9]{2})\b").unwrap())
+}
+
+fn line_amounts(line: &str) -> Vec<f64> {
+    amount_re()
+        .captures_iter(line)
+        .filter_map(|caps| format!("{}.{}", caps[1].replace(',', ""), &caps[2]).parse().ok())
+        .collect()
+}
+
+/// The amount on the last total line; failing that, the largest amount on
+/// the receipt, which is usually but not always the total
+fn find_total(lines: &[&str]) -> (Option<f64>, f64) {
+    let labelled = lines.iter().rev().find_map(|line| {
+        let lower = line.to_lowercase();
+        let is_total = ["grand total", "amount due", "balance due", "total"]
+            .iter()
+            .any(|label| lower.contains(label))
+            && !lower.contains("subtotal")
+            && !lower.contains("sub total");
+        is_total.then(|| line_amounts(line).last().copied()).flatten()
+    });
+    if let Some(total) = labelled {
+        return (Some(total), 1.0);
+    }
+
+    let largest = lines
+        .iter()
+        .flat_map(|line| line_amounts(line))
+        .fold(None, |max: Option<f64>, amount| Some(max.map_or(amount, |m| m.max(amount))));
+    (largest, if largest.is_some() { 0.5 } else { 0.0 })
+}
+
+fn find_date(lines: &[&str]) -> (Option<DateTime<Utc>>, f64) {
+    static DATE_RE: OnceLock<Regex> = OnceLock::new();
+    let date_re = DATE_RE.get_or_init(|| {
+        Regex::new(
+            r"\b(\d{4}-\d{2}-\d{2}|\d{1,2}[/-]\d{1,2}[/-]\d{2,4}|(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]* \d{1,2}, \d{4})\b",
+        )
+        .unwrap()
+    });
+
+    lines
+        .iter()
+        .flat_map(|line| date_re.find_iter(line))
+        .find_map(|m| parse_date_flexible(m.as_str()).ok())
+        .map_or((None, 0.0), |date| (Some(date), 1.0))
+}
+
+/// Receipts print the merchant first; skip lines that are only numbers,
+/// dates or punctuation
+fn find_vendor(lines: &[&str]) -> (Option<String>, f64) {
+    lines
+        .iter()
+        .take(3)
+        .find(|line| {
+            let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+            letters >= 3 && letters * 2 >= line.chars().filter(|c| !c.is_whitespace()).count()
+        })
+        .map_or((None, 0.0), |line| (Some(line.to_string()), 0.9))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct BlurryScan;
+
+    #[async_trait]
+    impl OcrEngine for BlurryScan {
+        async fn recognize(&self, _: &Path) -> Result<OcrText> {
+            Ok(OcrText {
+                text: "C0URT REP0RTERS INC\n3/14/2024\nTranscript 412.50\nCopies 37.50\n".to_string(),
+                confidence: 0.7,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receipt_fixture_total_extracted() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/receipt_filing_fee.txt");
+        let receipt = parse_receipt(&fixture, None).await.unwrap();
+
+        assert_eq!(receipt.total, Some(432.75));
+        assert_eq!(receipt.date, Some(Utc.with_ymd_and_hms(2024, 3, 14, 0, 0, 0).unwrap()));
+        assert_eq!(receipt.vendor.as_deref(), Some("Keystone Legal Supply"));
+        assert_eq!(receipt.needs_review, Vec::<String>::new());
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_scan_flagged_for_review() {
+        let receipt = parse_receipt(Path::new("scan.jpg"), Some(&BlurryScan)).await.unwrap();
+
+        // No total line, so the largest amount is a guess
+        assert_eq!(receipt.total, Some(412.5));
+        assert!(receipt.needs_review.contains(&"total".to_string()));
+        assert!(receipt.needs_review.contains(&"vendor".to_string()));
+        assert!(parse_receipt(Path::new("scan.jpg"), None).await.is_err());
+    }
+
+    #[cfg(feature = "tesseract")]
+    #[tokio::test]
+    async fn test_tesseract_reads_receipt_image() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/receipt_filing_fee.png");
+        let receipt = parse_receipt(&fixture, Some(&TesseractEngine::new())).await.unwrap();
+
+        assert_eq!(receipt.total, Some(387.75));
+        assert_eq!(receipt.date, Some(Utc.with_ymd_and_hms(2024, 3, 14, 0, 0, 0).unwrap()));
+        assert_eq!(receipt.vendor.as_deref(), Some("Keystone Legal Supply"));
+    }
+}
@@ -0,0 +1,310 @@
+// Redaction - scrub sensitive identifiers from dockets before they leave the firm
+// Exports run dockets through a `RedactionPolicy` when the user asks for a
+// shareable copy; the cached docket itself is never modified
+
+use crate::domain::{Docket, SearchResult};
+use crate::utils::crypto::calculate_sha256_string;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// Replacement text for masked values
+pub const REDACTED: &str = "[REDACTED]";
+
+static SSN_REGEX: OnceLock<Regex> = OnceLock::new();
+
+pub(crate) fn get_ssn_regex() -> &'static Regex {
+    SSN_REGEX.get_or_init(|| {
+        // ###-##-#### or ### ## ####; bare nine-digit runs are left alone so
+        // docket and OTN numbers are not mistaken for SSNs
+        Regex::new(r"\b\d{3}[- ]\d{2}[- ]\d{4}\b").unwrap()
+    })
+}
+
+/// Which identifiers to mask. The default masks everything except minor
+/// names, which must be listed explicitly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionPolicy {
+    pub mask_sid: bool,
+    pub mask_otn: bool,
+    /// SSN-like numbers anywhere in free text
+    pub mask_ssn: bool,
+    /// Party street address, city, ZIP, phone and email
+    pub mask_party_contact: bool,
+    /// Names of minors, replaced with initials wherever they appear
+    pub minor_names: Vec<String>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            mask_sid: true,
+            mask_otn: true,
+            mask_ssn: true,
+            mask_party_contact: true,
+            minor_names: Vec::new(),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    fn scrub(&self, text: &str) -> String {
+        let mut text = if self.mask_ssn {
+            get_ssn_regex().replace_all(text, REDACTED).into_owned()
+        } else {
+            text.to_string()
+        };
+        for name in self.minor_names.iter().filter(|n| !n.trim().is_empty()) {
+            text = replace_ignore_case(&text, name.trim(), &initials(name));
+        }
+        text
+    }
+
+    fn scrub_opt(&self, text: &Option<String>) -> Option<String> {
+        text.as_deref().map(|t| self.scrub(t))
+    }
+}
+
+/// A copy of `docket` with the identifiers selected by `policy` masked
+pub fn redact_docket(docket: &Docket, policy: &RedactionPolicy) -> Docket {
+    let mut redacted = docket.clone();
+
+    if policy.mask_sid {
+        redacted.sid = docket.sid.as_ref().map(|_| REDACTED.to_string());
+    }
+    if policy.mask_otn {
+        redacted.otn = docket.otn.as_ref().map(|_| REDACTED.to_string());
+    }
+
+    redacted.caption = policy.scrub(&docket.caption);
+
+    for party in &mut redacted.parties {
+        party.name = policy.scrub(&party.name);
+        if policy.mask_party_contact {
+            for field in [&mut party.address, &mut party.city, &mut party.zip_code, &mut party.phone, &mut party.email] {
+                if field.is_some() {
+                    *field = Some(REDACTED.to_string());
+                }
+            }
+        }
+    }
+
+    for charge in &mut redacted.charges {
+        charge.description = policy.scrub(&charge.description);
+    }
+
+    for event in &mut redacted.events {
+        event.description = policy.scrub_opt(&event.description);
+        event.notes = policy.scrub_opt(&event.notes);
+        event.result = policy.scrub_opt(&event.result);
+    }
+
+    for filing in &mut redacted.filings {
+        filing.title = policy.scrub(&filing.title);
+        filing.by = policy.scrub_opt(&filing.by);
+    }
+
+    // The original's hash would vouch for text the copy no longer has
+    redacted.hash = None;
+    redacted.hash = Some(calculate_sha256_string(
+        &serde_json::to_string(&redacted).unwrap_or_default(),
+    ));
+
+    redacted
+}
+
+/// Search results carry the same identifiers as dockets in CSV exports
+pub fn redact_search_result(result: &SearchResult, policy: &RedactionPolicy) -> SearchResult {
+    let mut redacted = result.clone();
+    if policy.mask_sid {
+        redacted.sid = result.sid.as_ref().map(|_| REDACTED.to_string());
+    }
+    if policy.mask_otn {
+        redacted.otn = result.otn.as_ref().map(|_| REDACTED.to_string());
+    }
+    redacted.caption = policy.scrub(&result.caption);
+    redacted
+}
+
+/// A copy of exported JSON with the identifiers selected by `policy` masked.
+/// Dockets and search results are redacted as such; in anything else, `sid`
+/// and `otn` fields are masked and every string is scrubbed.
+pub fn redact_json(value: &Value, policy: &RedactionPolicy) -> Value {
+    if let Ok(docket) = serde_json::from_value::<Docket>(value.clone()) {
+        if let Ok(redacted) = serde_json::to_value(redact_docket(&docket, policy)) {
+            return redacted;
+        }
+    }
+    if let Ok(result) = serde_json::from_value::<SearchResult>(value.clone()) {
+        if let Ok(redacted) = serde_json::to_value(redact_search_result(&result, policy)) {
+            return redacted;
+        }
+    }
+
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(|item| redact_json(item, policy)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| {
+                    let masked = match key.as_str() {
+                        "sid" => policy.mask_sid,
+                        "otn" => policy.mask_otn,
+                        _ => false,
+                    };
+                    let field = if masked && !field.is_null() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(field, policy)
+                    };
+                    (key.clone(), field)
+                })
+                .collect(),
+        ),
+        Value::String(text) => Value::String(policy.scrub(text)),
+        other => other.clone(),
+    }
+}
+
+/// "Jane Q. Doe" -> "J.Q.D."
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|part| part.chars().find(|c| c.is_alphabetic()))
+        .map(|c| format!("{}.", c.to_uppercase()))
+        .collect()
+}
+
+fn replace_ignore_case(text: &str, needle: &str, replacement: &str) -> String {
+    match Regex::new(&format!("(?i){}", regex::escape(needle))) {
+        Ok(re) => re.replace_all(text, replacement).into_owned(),
+        Err(_) => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CaseStatus, CourtLevel, Event, EventType, Party, PartyRole};
+
+    fn docket() -> Docket {
+        Docket {
+            id: "docket-1".to_string(),
+            caption: "Commonwealth v. Timmy Jones".to_string(),
+            status: CaseStatus::Active,
+            court: CourtLevel::Cp,
+            county: "Philadelphia".to_string(),
+            filed: chrono::Utc::now(),
+            docket_number: Some("CP-51-JV-0001234-2024".to_string()),
+            otn: Some("G1234567".to_string()),
+            sid: Some("123-45-678-9".to_string()),
+            judge: Some("Hon. A. Smith".to_string()),
+            courtroom: Some("1001".to_string()),
+            division: None,
+            parties: vec![Party {
+                id: None,
+                name: "Timmy Jones".to_string(),
+                role: PartyRole::Defendant,
+                address: Some("12 Elm St".to_string()),
+                city: Some("Philadelphia".to_string()),
+                state: Some("PA".to_string()),
+                zip_code: Some("19103".to_string()),
+                phone: None,
+                email: None,
+                attorney: Some("Jane Doe".to_string()),
+                attorney_id: Some("123456".to_string()),
+                attorney_phone: None,
+                attorney_email: None,
+                date_added: None,
+            }],
+            charges: vec![],
+            events: vec![Event {
+                description: Some("Hearing; SSN 123-45-6789 on file".to_string()),
+                time: None,
+                id: None,
+                event_type: EventType::Hearing,
+                when: chrono::Utc::now(),
+                location: None,
+                courtroom: None,
+                judge: None,
+                notes: None,
+                result: None,
+                next_date: None,
+            }],
+            filings: vec![],
+            financials: vec![],
+            attachments: None,
+            last_updated: None,
+            source_url: None,
+            source: None,
+            fetched_at: None,
+            hash: Some("abc123".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sid_and_sensitive_text_are_masked() {
+        let policy = RedactionPolicy {
+            minor_names: vec!["Timmy Jones".to_string()],
+            ..RedactionPolicy::default()
+        };
+
+        let redacted = redact_docket(&docket(), &policy);
+
+        assert_eq!(redacted.sid.as_deref(), Some(REDACTED));
+        assert_eq!(redacted.otn.as_deref(), Some(REDACTED));
+        assert_eq!(redacted.caption, "Commonwealth v. T.J.");
+        assert_eq!(redacted.parties[0].name, "T.J.");
+        assert_eq!(redacted.parties[0].address.as_deref(), Some(REDACTED));
+        assert_eq!(
+            redacted.events[0].description.as_deref(),
+            Some("Hearing; SSN [REDACTED] on file")
+        );
+    }
+
+    #[test]
+    fn test_non_sensitive_fields_are_preserved() {
+        let original = docket();
+        let redacted = redact_docket(&original, &RedactionPolicy::default());
+
+        assert_eq!(redacted.id, original.id);
+        assert_eq!(redacted.caption, original.caption);
+        assert_eq!(redacted.docket_number, original.docket_number);
+        assert_eq!(redacted.county, original.county);
+        assert_eq!(redacted.judge, original.judge);
+        assert_eq!(redacted.courtroom, original.courtroom);
+        assert_eq!(redacted.parties[0].state, original.parties[0].state);
+        assert_eq!(redacted.parties[0].attorney, original.parties[0].attorney);
+        assert_eq!(redacted.parties[0].phone, None);
+    }
+
+    #[test]
+    fn test_redacted_docket_is_hashed_as_redacted() {
+        let original = docket();
+        let redacted = redact_docket(&original, &RedactionPolicy::default());
+
+        let hash = redacted.hash.clone().expect("hash");
+        assert_ne!(original.hash.as_deref(), Some(hash.as_str()));
+        let unhashed = Docket { hash: None, ..redacted };
+        assert_eq!(hash, calculate_sha256_string(&serde_json::to_string(&unhashed).unwrap()));
+    }
+
+    #[test]
+    fn test_exported_json_is_redacted() {
+        let policy = RedactionPolicy::default();
+
+        let docket = redact_json(&serde_json::to_value(docket()).unwrap(), &policy);
+        assert_eq!(docket["sid"], REDACTED);
+        assert_eq!(docket["parties"][0]["address"], REDACTED);
+        assert_ne!(docket["hash"], "abc123");
+
+        let other = redact_json(
+            &serde_json::json!({ "notes": ["SSN 123-45-6789"], "otn": "G1234567", "sid": null }),
+            &policy,
+        );
+        assert_eq!(other["notes"][0], "SSN [REDACTED]");
+        assert_eq!(other["otn"], REDACTED);
+        assert!(other["sid"].is_null());
+    }
+}
@@ -0,0 +1,82 @@
+// Secrets kept out of the database: OAuth tokens, signing secrets
+// Rows store only a reference; the secret itself lives in the OS keychain
+
+use anyhow::{Context, Result};
+use keyring::{Entry, Error as KeyringError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub trait SecretStore: Send + Sync {
+    fn put(&self, key: &str, secret: &str) -> Result<()>;
+    /// None when nothing is stored under `key`
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    /// Removing a missing key is not an error
+    fn delete(&self, key: &str) -> Result<()>;
+}
+
+pub type SharedSecretStore = Arc<dyn SecretStore>;
+
+/// Stores secrets in the platform keychain under one service name
+pub struct KeychainSecretStore {
+    service: String,
+}
+
+impl KeychainSecretStore {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self { service: service.into() }
+    }
+
+    fn entry(&self, key: &str) -> Result<Entry> {
+        Entry::new(&self.service, key).context("Failed to create keyring entry")
+    }
+}
+
+impl SecretStore for KeychainSecretStore {
+    fn put(&self, key: &str, secret: &str) -> Result<()> {
+        self.entry(key)?
+            .set_password(secret)
+            .with_context(|| format!("Failed to store {} in keychain", key))
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match self.entry(key)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(KeyringError::NoEntry) => Ok(None),
+            Err(err) => Err(err).with_context(|| format!("Failed to read {} from keychain", key)),
+        }
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        match self.entry(key)?.delete_credential() {
+            Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+            Err(err) => Err(err).with_context(|| format!("Failed to delete {} from keychain", key)),
+        }
+    }
+}
+
+/// The app's keychain store
+pub fn keychain() -> SharedSecretStore {
+    Arc::new(KeychainSecretStore::new("PA eDocket Desktop"))
+}
+
+/// Process-local store for tests and machines without a keychain
+#[derive(Default)]
+pub struct MemorySecretStore {
+    secrets: Mutex<HashMap<String, String>>,
+}
+
+impl SecretStore for MemorySecretStore {
+    fn put(&self, key: &str, secret: &str) -> Result<()> {
+        self.secrets.lock().unwrap().insert(key.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.secrets.lock().unwrap().get(key).cloned())
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.secrets.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
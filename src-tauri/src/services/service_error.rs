@@ -0,0 +1,136 @@
+// Service errors - the kinds of failure a caller can act on
+// Services still return `anyhow::Result`; where the cause matters to the
+// caller they raise a `ServiceError`, and `ServiceError::from` recovers the
+// kind from any service error, including the typed errors services already
+// raise, so command handlers and the REST API can map it to a status or code.
+
+use crate::providers::ProviderError;
+use crate::services::attachment_scanner::AttachmentRejected;
+use crate::services::billing::InvalidExpenseTransition;
+use crate::services::database::Conflict;
+use crate::services::email_integration::{MissingTemplateVariables, SyncTokenExpired};
+use crate::services::permissions::AuthorizationError;
+use crate::services::time_tracking::OverlappingRate;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: &'static str, id: String },
+
+    /// The request itself is wrong; retrying it unchanged will fail again
+    #[error("{0}")]
+    Validation(String),
+
+    /// The request clashes with the record's current state
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    /// A court, payment or mail provider failed
+    #[error("{0}")]
+    External(String),
+
+    /// A provider is throttling requests; the same request may succeed later
+    #[error("{0}")]
+    RateLimited(String),
+
+    /// A provider is down, unreachable or too slow; the same request may
+    /// succeed later
+    #[error("{0}")]
+    Unavailable(String),
+
+    #[error(transparent)]
+    Internal(anyhow::Error),
+}
+
+impl ServiceError {
+    pub fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        ServiceError::NotFound { entity, id: id.into() }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        ServiceError::Validation(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        ServiceError::Conflict(message.into())
+    }
+
+    /// Stable code for the frontend and API clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServiceError::NotFound { .. } => "not_found",
+            ServiceError::Validation(_) => "validation",
+            ServiceError::Conflict(_) => "conflict",
+            ServiceError::Unauthorized(_) => "unauthorized",
+            ServiceError::External(_) => "external",
+            ServiceError::RateLimited(_) => "rate_limited",
+            ServiceError::Unavailable(_) => "unavailable",
+            ServiceError::Internal(_) => "internal",
+        }
+    }
+
+    /// Whether the caller should retry the same request later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ServiceError::RateLimited(_) | ServiceError::Unavailable(_))
+    }
+}
+
+impl From<anyhow::Error> for ServiceError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ServiceError>() {
+            Ok(service_error) => return service_error,
+            Err(err) => err,
+        };
+
+        let message = err.to_string();
+        if let Some(provider_error) = err.downcast_ref::<ProviderError>() {
+            return match provider_error {
+                ProviderError::RateLimited => ServiceError::RateLimited(message),
+                e if e.is_transient() => ServiceError::Unavailable(message),
+                _ => ServiceError::External(message),
+            };
+        }
+        if err.is::<Conflict>() || err.is::<OverlappingRate>() {
+            ServiceError::Conflict(message)
+        } else if err.is::<InvalidExpenseTransition>()
+            || err.is::<MissingTemplateVariables>()
+            || err.is::<AttachmentRejected>()
+        {
+            ServiceError::Validation(message)
+        } else if err.is::<AuthorizationError>() {
+            ServiceError::Unauthorized(message)
+        } else if err.is::<SyncTokenExpired>() {
+            ServiceError::External(message)
+        } else {
+            ServiceError::Internal(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_service_errors_keep_their_kind() {
+        let raised: anyhow::Error = ServiceError::not_found("Invoice", "inv-1").into();
+        assert!(matches!(
+            ServiceError::from(raised),
+            ServiceError::NotFound { entity: "Invoice", ref id } if id == "inv-1"
+        ));
+
+        let conflict: anyhow::Error = OverlappingRate { existing_id: "rate-1".to_string() }.into();
+        assert_eq!(ServiceError::from(conflict).code(), "conflict");
+
+        let throttled: anyhow::Error = ProviderError::RateLimited.into();
+        let throttled = ServiceError::from(throttled);
+        assert_eq!(throttled.code(), "rate_limited");
+        assert!(throttled.is_retryable());
+
+        let other = anyhow::anyhow!("disk full").context("Failed to save invoice");
+        assert_eq!(ServiceError::from(other).code(), "internal");
+    }
+}
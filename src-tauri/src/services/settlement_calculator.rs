@@ -3,12 +3,18 @@
 // Enhanced with jurisdiction-specific rules, AI analytics, and comprehensive automation
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc, Datelike, Duration};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{Row, SqliteConnection, SqliteExecutor, SqlitePool};
 use uuid::Uuid;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::services::document_store::DocumentStore;
+use crate::utils::clock::{system_clock, SharedClock};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CaseType {
@@ -56,6 +62,8 @@ pub struct SettlementCalculation {
     pub total_damages: f64,
     pub settlement_range: SettlementRange,
     pub liability_analysis: LiabilityAnalysis,
+    #[serde(default)]
+    pub defendant_apportionment: Vec<DefendantApportionment>,
     pub risk_assessment: RiskAssessment,
     pub comparable_verdicts: Vec<ComparableVerdict>,
 
@@ -66,6 +74,9 @@ pub struct SettlementCalculation {
 
     // AI Analysis
     pub ai_analysis: Option<AISettlementAnalysis>,
+    /// The AI prediction blended with the heuristic range
+    #[serde(default)]
+    pub reconciled_estimate: Option<ReconciledEstimate>,
 
     // Medical Analysis
     pub medical_timeline: Option<MedicalTreatmentTimeline>,
@@ -99,6 +110,88 @@ pub struct SettlementCalculation {
     pub version: String,
     pub last_updated: DateTime<Utc>,
     pub calculation_notes: Vec<CalculationNote>,
+
+    // Revisions
+    /// 1 for a new calculation, one more for each recalculation
+    #[serde(default = "first_revision")]
+    pub revision: u32,
+    /// The revision this one superseded
+    #[serde(default)]
+    pub previous_calculation_id: Option<String>,
+    /// What the calculation was computed from, for recalculating as facts
+    /// develop. Absent on calculations saved before revisions were kept.
+    #[serde(default)]
+    pub inputs: Option<SettlementInputs>,
+}
+
+fn first_revision() -> u32 {
+    1
+}
+
+/// The facts a settlement calculation is computed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementInputs {
+    pub case_type: CaseType,
+    pub plaintiff_name: String,
+    pub defendant_name: String,
+    pub economic_damages: EconomicDamages,
+    pub injury_details: Option<PersonalInjuryDetails>,
+    pub liability_percentage: f64,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub risk_profile: ClientRiskProfile,
+    /// Already paid by insurers and other collateral sources; deducted from
+    /// past medical expenses where the jurisdiction requires it
+    #[serde(default)]
+    pub collateral_source_payments: f64,
+    /// Every defendant's share of the total fault, summing to
+    /// `liability_percentage`. When empty, the named defendant carries it all.
+    #[serde(default)]
+    pub defendants: Vec<DefendantShare>,
+}
+
+/// How much uncertainty the client will accept in pursuit of a larger recovery
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ClientRiskProfile {
+    RiskAverse,
+    #[default]
+    Neutral,
+    Aggressive,
+}
+
+/// The negotiating stance for a client's risk profile
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct NegotiationParameters {
+    /// Opening demand as a multiple of the high estimate
+    pub opening_multiplier: f64,
+    /// Walk-away floor as a multiple of the low estimate
+    pub floor_multiplier: f64,
+    /// Rounds over which concessions close the gap from demand to floor
+    pub concession_rounds: u32,
+}
+
+impl ClientRiskProfile {
+    pub fn parameters(self) -> NegotiationParameters {
+        match self {
+            // Open closer to value and concede quickly for an early, certain recovery
+            ClientRiskProfile::RiskAverse => NegotiationParameters {
+                opening_multiplier: 1.05,
+                floor_multiplier: 0.8,
+                concession_rounds: 2,
+            },
+            ClientRiskProfile::Neutral => NegotiationParameters {
+                opening_multiplier: 1.2,
+                floor_multiplier: 0.9,
+                concession_rounds: 4,
+            },
+            // Anchor high and hold out, accepting the risk of trial
+            ClientRiskProfile::Aggressive => NegotiationParameters {
+                opening_multiplier: 1.3,
+                floor_multiplier: 1.0,
+                concession_rounds: 6,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -371,6 +464,21 @@ pub struct DemandLetter {
     pub created_at: DateTime<Utc>,
     pub created_by: String,
     pub sent_at: Option<DateTime<Utc>>,
+
+    // Response Tracking
+    #[serde(default)]
+    pub status: DemandStatus,
+    #[serde(default)]
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum DemandStatus {
+    #[default]
+    Pending,
+    Answered,
+    /// The deadline passed without a response
+    FollowUp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -378,6 +486,9 @@ pub struct DemandExhibit {
     pub exhibit_letter: String,
     pub description: String,
     pub file_path: String,
+    /// SHA-256 of the copy held in the document store
+    #[serde(default)]
+    pub content_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -435,6 +546,52 @@ pub enum OfferRecommendation {
     NeedsClientInput,
 }
 
+/// Offers on a calculation side by side, for the negotiation dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfferComparison {
+    pub calculation_id: String,
+    pub recommended_demand: f64,
+    pub calculated_value: f64,
+    pub target_settlement: f64,
+    pub minimum_settlement: f64,
+    /// Oldest first
+    pub offers: Vec<OfferComparisonRow>,
+    /// Absent until there are at least two offers
+    pub trend: Option<OfferTrend>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfferComparisonRow {
+    pub offer_id: String,
+    pub round: u32,
+    pub offer_from: String,
+    pub offer_date: DateTime<Utc>,
+    pub amount: f64,
+    pub percentage_of_demand: f64,
+    pub percentage_of_calculated_value: f64,
+    /// After attorney fees and costs
+    pub net_to_client: f64,
+    pub change_from_previous: Option<f64>,
+    pub status: OfferStatus,
+    pub recommendation: OfferRecommendation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfferTrend {
+    pub direction: TrendDirection,
+    pub change_since_first: f64,
+    pub average_change_per_round: f64,
+    /// How far the latest offer is below the target settlement
+    pub remaining_gap_to_target: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TrendDirection {
+    Rising,
+    Flat,
+    Falling,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersonalInjuryDetails {
     pub injury_type: InjuryType,
@@ -484,6 +641,9 @@ pub struct JurisdictionRules {
     pub punitive_damages_cap: Option<PunitiveCap>,
     pub prejudgment_interest: bool,
     pub prejudgment_interest_rate: Option<f64>,
+    #[serde(default)]
+    pub prejudgment_interest_method: InterestMethod,
+    pub postjudgment_interest_rate: Option<f64>,
     pub structured_settlement_allowed: bool,
     pub attorney_fee_rules: AttorneyFeeRules,
     pub expert_witness_limits: Option<u32>,
@@ -499,6 +659,13 @@ pub enum ComparativeNegligenceType {
     Contributory,            // Complete bar (e.g., Alabama, Maryland)
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum InterestMethod {
+    #[default]
+    Simple,
+    CompoundedAnnually,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DamageCaps {
     pub medical_malpractice_non_economic: Option<f64>,
@@ -523,6 +690,28 @@ pub struct JointSeveralLiability {
     pub threshold_percentage: Option<f64>, // Minimum % fault for joint liability
 }
 
+/// One defendant's share of fault, as a percentage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefendantShare {
+    pub name: String,
+    pub fault_percentage: f64,
+}
+
+/// What a single defendant can be made to pay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefendantApportionment {
+    pub name: String,
+    pub fault_percentage: f64,
+    /// The defendant's proportionate share of each category
+    pub several_economic: f64,
+    pub several_non_economic: f64,
+    /// Jointly liable defendants can be made to pay the whole category
+    pub jointly_liable_economic: bool,
+    pub jointly_liable_non_economic: bool,
+    pub max_economic_exposure: f64,
+    pub max_non_economic_exposure: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PunitiveCap {
     pub multiplier_of_compensatory: Option<f64>,  // e.g., 2x or 3x compensatory
@@ -536,6 +725,80 @@ pub struct AttorneyFeeRules {
     pub sliding_scale_required: bool,
     pub court_approval_required: bool,
     pub costs_advance_rules: String,
+    #[serde(default)]
+    pub sliding_scale_tiers: Vec<FeeTier>,  // Applied in order when sliding_scale_required
+    /// Case types the sliding scale governs, e.g. only medical malpractice
+    /// under NY Judiciary Law 474-a and California's MICRA; empty means all
+    #[serde(default)]
+    pub sliding_scale_case_types: Vec<CaseType>,
+}
+
+impl AttorneyFeeRules {
+    /// Whether the sliding scale governs a case of `case_type`. A case of
+    /// unknown type is only held to a scale that covers every case.
+    pub fn sliding_scale_applies_to(&self, case_type: Option<&CaseType>) -> bool {
+        self.sliding_scale_required
+            && (self.sliding_scale_case_types.is_empty()
+                || case_type.is_some_and(|t| self.sliding_scale_case_types.contains(t)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTier {
+    pub up_to: Option<f64>,  // Upper bound of this band of the recovery; None = no limit
+    pub percentage: f64,
+}
+
+// ============= NET TO CLIENT =============
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Costs {
+    pub litigation_costs_to_date: f64,
+    pub projected_additional_costs: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lien {
+    pub lienholder: String,
+    pub lien_type: LienType,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LienType {
+    MedicalProvider,
+    HealthInsurance,
+    Medicare,
+    Medicaid,
+    WorkersCompensation,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetToClientBreakdown {
+    pub gross_settlement: f64,
+    pub attorney_fee: f64,
+    pub effective_fee_percentage: f64,
+    pub fee_tiers_applied: Vec<FeeTierAmount>,
+    pub litigation_costs_to_date: f64,
+    pub projected_additional_costs: f64,
+    pub liens: Vec<Lien>,
+    pub total_liens: f64,
+    pub net_to_client: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FeeAgreementStatus {
+    Permitted,
+    RequiresCourtApproval,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTierAmount {
+    pub band_start: f64,
+    pub band_end: f64,
+    pub percentage: f64,
+    pub fee: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -560,6 +823,33 @@ pub struct AISettlementAnalysis {
     pub venue_statistics: Option<VenueStatistics>,
 }
 
+/// A settlement value model, e.g. a hosted ML service. Kept behind a trait
+/// so the calculator can run without one and tests can supply their own.
+#[async_trait]
+pub trait SettlementPredictor: Send + Sync {
+    async fn predict(
+        &self,
+        case_type: &CaseType,
+        damages: f64,
+        liability: &LiabilityAnalysis,
+        jurisdiction: &str,
+    ) -> Result<AISettlementAnalysis>;
+}
+
+/// Heuristic midpoint and AI prediction blended by their confidence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciledEstimate {
+    pub blended_value: f64,
+    pub heuristic_value: f64,
+    pub ai_value: f64,
+    pub heuristic_weight: f64,
+    pub ai_weight: f64,
+    /// |AI - heuristic| as a fraction of the heuristic midpoint
+    pub divergence: f64,
+    pub needs_attorney_review: bool,
+    pub explanation: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIFactor {
     pub factor_name: String,
@@ -745,13 +1035,42 @@ pub struct AssistiveDevice {
     pub lifetime_cost: f64,
 }
 
+/// Default time the recipient has to answer a demand
+pub const DEFAULT_DEMAND_DEADLINE_DAYS: u32 = 30;
+
+/// How often pending demands are checked for expiry
+const DEMAND_EXPIRY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Days given to prepare a complaint once a demand expires
+const LITIGATION_TASK_DUE_DAYS: i64 = 14;
+
 pub struct SettlementCalculatorService {
-    db: SqlitePool,
+    pub(crate) db: SqlitePool,
+    predictor: Option<Arc<dyn SettlementPredictor>>,
+    exhibit_store: Option<DocumentStore>,
+    clock: SharedClock,
 }
 
 impl SettlementCalculatorService {
     pub fn new(db: SqlitePool) -> Self {
-        Self { db }
+        Self { db, predictor: None, exhibit_store: None, clock: system_clock() }
+    }
+
+    /// Time source for demand dates, response deadlines and their expiry
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Keep copies of demand letter exhibits under `exhibits_dir`
+    pub fn with_exhibit_store(mut self, exhibits_dir: PathBuf) -> Self {
+        self.exhibit_store = Some(DocumentStore::new(self.db.clone(), exhibits_dir));
+        self
+    }
+
+    pub fn with_predictor(mut self, predictor: Arc<dyn SettlementPredictor>) -> Self {
+        self.predictor = Some(predictor);
+        self
     }
 
     // ============= Settlement Calculation =============
@@ -767,9 +1086,159 @@ impl SettlementCalculatorService {
         injury_details: Option<PersonalInjuryDetails>,
         liability_percentage: f64,
         jurisdiction: &str,
+        risk_profile: ClientRiskProfile,
+        calculated_by: &str,
+    ) -> Result<SettlementCalculation> {
+        let inputs = SettlementInputs {
+            case_type,
+            plaintiff_name: plaintiff_name.to_string(),
+            defendant_name: defendant_name.to_string(),
+            economic_damages,
+            injury_details,
+            liability_percentage,
+            jurisdiction: jurisdiction.to_string(),
+            risk_profile,
+            collateral_source_payments: 0.0,
+            defendants: Vec::new(),
+        };
+        self.calculate_settlement_from_inputs(matter_id, inputs, calculated_by).await
+    }
+
+    /// Calculate and save a first revision from the full set of inputs
+    pub async fn calculate_settlement_from_inputs(
+        &self,
+        matter_id: &str,
+        inputs: SettlementInputs,
+        calculated_by: &str,
+    ) -> Result<SettlementCalculation> {
+        let calculation = self.compute_settlement(matter_id, inputs, calculated_by).await?;
+
+        self.save_settlement_calculation(&calculation).await?;
+
+        Ok(calculation)
+    }
+
+    /// Rerun calculation `id` with `updated_inputs` and store the result as
+    /// its next revision. Earlier revisions are never modified, so the chain
+    /// shows how the estimate moved as the facts developed. Offers,
+    /// counteroffers and notes carry forward to the new revision.
+    pub async fn recalculate(
+        &self,
+        id: &str,
+        updated_inputs: SettlementInputs,
+        recalculated_by: &str,
+    ) -> Result<SettlementCalculation> {
+        let prior = self
+            .get_settlement_calculation(id)
+            .await?
+            .with_context(|| format!("Settlement calculation {} not found", id))?;
+        let mut calculation = self.compute_settlement(&prior.matter_id, updated_inputs, recalculated_by).await?;
+
+        // Read the prior revision again under the write lock, so offers
+        // recorded while the new figures were computed carry forward
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let prior = load_calculation(&mut *tx, id)
+            .await?
+            .with_context(|| format!("Settlement calculation {} not found", id))?;
+
+        // Only the latest revision may be recalculated, keeping each chain linear
+        if let Some(next_id) = superseding_calculation(&mut *tx, id).await? {
+            anyhow::bail!(
+                "Settlement calculation {} was already recalculated as {}; recalculate the latest revision",
+                id,
+                next_id
+            );
+        }
+
+        calculation.revision = prior.revision + 1;
+        calculation.previous_calculation_id = Some(prior.id);
+        calculation.offers_received = prior.offers_received;
+        calculation.counteroffers_made = prior.counteroffers_made;
+        calculation.current_negotiation_round = prior.current_negotiation_round;
+        calculation.structured_settlement_option = prior.structured_settlement_option;
+        calculation.calculation_notes = prior.calculation_notes;
+
+        save_calculation(&mut *tx, &calculation).await?;
+        tx.commit().await?;
+
+        Ok(calculation)
+    }
+
+    /// The revisions of the matter's current calculation, oldest first. The
+    /// current calculation is the most recent one not yet recalculated.
+    pub async fn get_calculation_history(&self, matter_id: &str) -> Result<Vec<SettlementCalculation>> {
+        let calculations = self.list_calculations(matter_id).await?;
+        let superseded: HashSet<&str> = calculations
+            .iter()
+            .filter_map(|c| c.previous_calculation_id.as_deref())
+            .collect();
+        let by_id: HashMap<&str, &SettlementCalculation> =
+            calculations.iter().map(|c| (c.id.as_str(), c)).collect();
+
+        // Newest first, so the first unsuperseded calculation is the current one
+        let mut chain = Vec::new();
+        let mut next = calculations.iter().find(|c| !superseded.contains(c.id.as_str()));
+        while let Some(calculation) = next {
+            chain.push(calculation.clone());
+            next = calculation.previous_calculation_id.as_deref().and_then(|id| by_id.get(id).copied());
+        }
+        chain.reverse();
+
+        Ok(chain)
+    }
+
+    /// Run the calculation as a new, unsaved first revision
+    async fn compute_settlement(
+        &self,
+        matter_id: &str,
+        inputs: SettlementInputs,
         calculated_by: &str,
     ) -> Result<SettlementCalculation> {
         let calc_id = Uuid::new_v4().to_string();
+        let recorded_inputs = inputs.clone();
+        let SettlementInputs {
+            case_type,
+            plaintiff_name,
+            defendant_name,
+            mut economic_damages,
+            injury_details,
+            liability_percentage,
+            jurisdiction,
+            risk_profile,
+            collateral_source_payments,
+            defendants,
+        } = inputs;
+        let jurisdiction = jurisdiction.as_str();
+        let now = self.clock.now();
+
+        let defendant_fault: f64 = defendants.iter().map(|d| d.fault_percentage).sum();
+        if !defendants.is_empty() && (defendant_fault - liability_percentage).abs() > 0.01 {
+            anyhow::bail!(
+                "Defendants' fault adds up to {}%, but their liability is {}%",
+                defendant_fault,
+                liability_percentage
+            );
+        }
+
+        let jurisdiction_rules = self.load_jurisdiction_rules(jurisdiction).await?;
+
+        let mut calculation_notes = Vec::new();
+        let collateral_deduction = apply_collateral_source(
+            &mut economic_damages,
+            collateral_source_payments,
+            &jurisdiction_rules.collateral_source_rule,
+        );
+        if collateral_deduction > 0.0 {
+            calculation_notes.push(CalculationNote {
+                timestamp: now,
+                author: calculated_by.to_string(),
+                note: format!(
+                    "Past medical expenses reduced by ${:.2} of collateral source payments under {} law",
+                    collateral_deduction, jurisdiction_rules.jurisdiction
+                ),
+                note_type: NoteType::Adjustment,
+            });
+        }
 
         // Calculate non-economic damages
         let non_economic_damages = self.calculate_non_economic_damages(
@@ -813,6 +1282,15 @@ impl SettlementCalculatorService {
             &case_type,
         ).await?;
 
+        // Blend in the AI prediction when a model is configured
+        let (ai_analysis, reconciled_estimate) = match self
+            .ai_estimate(matter_id, &case_type, adjusted_damages, &liability_analysis, jurisdiction, &settlement_range)
+            .await
+        {
+            Some((analysis, reconciled)) => (Some(analysis), Some(reconciled)),
+            None => (None, None),
+        };
+
         // Conduct risk assessment
         let risk_assessment = self.assess_trial_risk(
             &case_type,
@@ -826,6 +1304,7 @@ impl SettlementCalculatorService {
                 &settlement_range,
                 &risk_assessment,
                 &liability_analysis,
+                risk_profile,
             ).await?;
 
         let rationale = self.generate_rationale(
@@ -841,20 +1320,78 @@ impl SettlementCalculatorService {
             &risk_assessment,
             recommended_demand,
             minimum_settlement,
+            risk_profile,
         ).await?;
 
+        // Without a breakdown, the named defendant carries the defendants'
+        // whole share of fault
+        let defendants = if defendants.is_empty() {
+            vec![DefendantShare {
+                name: defendant_name.to_string(),
+                fault_percentage: liability_percentage,
+            }]
+        } else {
+            defendants
+        };
+        let defendant_apportionment = apportion_liability(
+            economic_damages.total_economic * (liability_percentage / 100.0),
+            non_economic_damages.total_non_economic * (liability_percentage / 100.0),
+            &defendants,
+            &jurisdiction_rules.joint_several_liability,
+        );
+
+        let medical_timeline = if economic_damages.medical_expense_details.is_empty() {
+            None
+        } else {
+            Some(build_medical_timeline(&economic_damages.medical_expense_details, now))
+        };
+
+        // Earliest treatment date approximates the incident date when none is supplied
+        let incident_date = economic_damages
+            .medical_expense_details
+            .iter()
+            .filter(|e| !e.is_future)
+            .map(|e| e.date)
+            .min();
+
+        let prejudgment_interest = match (
+            jurisdiction_rules.prejudgment_interest,
+            jurisdiction_rules.prejudgment_interest_rate,
+            incident_date,
+        ) {
+            (true, Some(rate), Some(from)) => Some(compute_interest(
+                economic_damages.total_past_economic,
+                rate,
+                from,
+                now,
+                &jurisdiction_rules.prejudgment_interest_method,
+            )),
+            _ => None,
+        };
+        let postjudgment_interest_rate = jurisdiction_rules.postjudgment_interest_rate;
+
+        // Estimate the client's net recovery at the target settlement
+        let net_breakdown = compute_net_to_client(
+            target_settlement,
+            &case_type,
+            &jurisdiction_rules.attorney_fee_rules,
+            Costs::default(),
+            &[],
+        );
+
         let calculation = SettlementCalculation {
             id: calc_id,
             matter_id: matter_id.to_string(),
             case_type,
-            plaintiff_name: plaintiff_name.to_string(),
-            defendant_name: defendant_name.to_string(),
+            plaintiff_name,
+            defendant_name,
             economic_damages,
             non_economic_damages,
             punitive_damages,
             total_damages: adjusted_damages,
             settlement_range,
             liability_analysis,
+            defendant_apportionment,
             risk_assessment,
             comparable_verdicts,
             recommended_demand,
@@ -862,31 +1399,33 @@ impl SettlementCalculatorService {
             target_settlement,
             rationale,
             negotiation_strategy,
-            calculated_at: Utc::now(),
+            calculated_at: now,
             calculated_by: calculated_by.to_string(),
             version: "2.0.0".to_string(),
-            incident_date: todo!(),
-            jurisdiction_rules: todo!(),
-            adjusted_for_caps: todo!(),
-            cap_adjustments: todo!(),
-            ai_analysis: todo!(),
-            medical_timeline: todo!(),
-            offers_received: todo!(),
-            counteroffers_made: todo!(),
-            current_negotiation_round: todo!(),
-            prejudgment_interest: todo!(),
-            postjudgment_interest_rate: todo!(),
-            structured_settlement_option: todo!(),
-            estimated_attorney_fees: todo!(),
-            litigation_costs_to_date: todo!(),
-            projected_additional_costs: todo!(),
-            net_to_client: todo!(),
-            last_updated: todo!(),
-            calculation_notes: todo!(),
+            revision: 1,
+            previous_calculation_id: None,
+            inputs: Some(recorded_inputs),
+            incident_date,
+            jurisdiction_rules: Some(jurisdiction_rules),
+            adjusted_for_caps: false,
+            cap_adjustments: None,
+            ai_analysis,
+            reconciled_estimate,
+            medical_timeline,
+            offers_received: Vec::new(),
+            counteroffers_made: Vec::new(),
+            current_negotiation_round: 0,
+            prejudgment_interest,
+            postjudgment_interest_rate,
+            structured_settlement_option: None,
+            estimated_attorney_fees: net_breakdown.attorney_fee,
+            litigation_costs_to_date: net_breakdown.litigation_costs_to_date,
+            projected_additional_costs: net_breakdown.projected_additional_costs,
+            net_to_client: net_breakdown.net_to_client,
+            last_updated: now,
+            calculation_notes,
         };
 
-        self.save_settlement_calculation(&calculation).await?;
-
         Ok(calculation)
     }
 
@@ -1152,6 +1691,124 @@ impl SettlementCalculatorService {
         })
     }
 
+    /// The configured model's prediction reconciled with the heuristic
+    /// range. A failing model is logged and the heuristic range stands alone.
+    async fn ai_estimate(
+        &self,
+        matter_id: &str,
+        case_type: &CaseType,
+        damages: f64,
+        liability: &LiabilityAnalysis,
+        jurisdiction: &str,
+        heuristic: &SettlementRange,
+    ) -> Option<(AISettlementAnalysis, ReconciledEstimate)> {
+        let predictor = self.predictor.as_ref()?;
+        match predictor.predict(case_type, damages, liability, jurisdiction).await {
+            Ok(mut analysis) => {
+                if let Err(e) = self.attach_court_statistics(matter_id, &mut analysis).await {
+                    warn!("Venue and judge statistics unavailable for matter {}: {}", matter_id, e);
+                }
+                let reconciled = reconcile_settlement_estimates(heuristic, &analysis);
+                Some((analysis, reconciled))
+            }
+            Err(e) => {
+                warn!("Settlement prediction failed, using heuristic range only: {}", e);
+                None
+            }
+        }
+    }
+
+    // ============= Venue & Judge Statistics =============
+
+    /// Fill in venue and judge statistics the model left out, from the
+    /// county and judge recorded on the matter
+    async fn attach_court_statistics(&self, matter_id: &str, analysis: &mut AISettlementAnalysis) -> Result<()> {
+        let row = sqlx::query("SELECT county, judge_name FROM matters WHERE id = ?")
+            .bind(matter_id)
+            .fetch_optional(&self.db)
+            .await
+            .context("Failed to load matter venue")?;
+        let Some(row) = row else {
+            return Ok(());
+        };
+
+        if analysis.venue_statistics.is_none() {
+            if let Some(county) = row.try_get::<Option<String>, _>("county")? {
+                analysis.venue_statistics = self.get_venue_statistics(&county).await?;
+            }
+        }
+        if analysis.judge_history.is_none() {
+            if let Some(judge) = row.try_get::<Option<String>, _>("judge_name")? {
+                analysis.judge_history = self.get_judge_history(&judge).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Verdict and jury statistics for a county ("Allegheny" or "Allegheny
+    /// County"); None if the venue has not been recorded
+    pub async fn get_venue_statistics(&self, county: &str) -> Result<Option<VenueStatistics>> {
+        let county = county.trim();
+        let county = match county.to_ascii_lowercase().strip_suffix(" county") {
+            Some(name) => county[..name.len()].trim_end(),
+            None => county,
+        };
+
+        let row = sqlx::query(
+            "SELECT county, average_plaintiff_verdict, plaintiff_win_rate, median_time_to_trial,
+                    median_age, median_income, education_level, urban_rural, political_lean, tort_reform_climate
+             FROM venue_statistics WHERE county = ?",
+        )
+        .bind(county)
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to load venue statistics")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(VenueStatistics {
+            county: row.try_get("county")?,
+            average_plaintiff_verdict: row.try_get("average_plaintiff_verdict")?,
+            plaintiff_win_rate: row.try_get("plaintiff_win_rate")?,
+            median_time_to_trial: row.try_get::<i64, _>("median_time_to_trial")? as u32,
+            jury_pool_demographics: DemographicProfile {
+                median_age: row.try_get("median_age")?,
+                median_income: row.try_get("median_income")?,
+                education_level: row.try_get("education_level")?,
+                urban_rural: parse_variant(&row.try_get::<String, _>("urban_rural")?)?,
+            },
+            political_lean: parse_variant(&row.try_get::<String, _>("political_lean")?)?,
+            tort_reform_climate: parse_variant(&row.try_get::<String, _>("tort_reform_climate")?)?,
+        }))
+    }
+
+    /// The firm's record of a judge's verdicts; None if the judge has not
+    /// been recorded
+    pub async fn get_judge_history(&self, judge_name: &str) -> Result<Option<JudgeHistory>> {
+        let row = sqlx::query(
+            "SELECT judge_name, average_plaintiff_verdict, plaintiff_win_rate, median_verdict_ratio,
+                    trials_presided, settlement_encouragement
+             FROM judge_histories WHERE judge_name = ?",
+        )
+        .bind(judge_name.trim())
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to load judge history")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(JudgeHistory {
+            judge_name: row.try_get("judge_name")?,
+            average_plaintiff_verdict: row.try_get("average_plaintiff_verdict")?,
+            plaintiff_win_rate: row.try_get("plaintiff_win_rate")?,
+            median_verdict_ratio: row.try_get("median_verdict_ratio")?,
+            trials_presided: row.try_get::<i64, _>("trials_presided")? as u32,
+            settlement_encouragement: parse_variant(&row.try_get::<String, _>("settlement_encouragement")?)?,
+        }))
+    }
+
     // ============= Liability Analysis =============
 
     async fn analyze_liability(
@@ -1263,12 +1920,15 @@ impl SettlementCalculatorService {
         range: &SettlementRange,
         risk: &RiskAssessment,
         liability: &LiabilityAnalysis,
+        profile: ClientRiskProfile,
     ) -> Result<(f64, f64, f64)> {
-        // Recommended demand: Start high (110-130% of high estimate)
-        let recommended_demand = range.high_estimate * 1.2;
+        let parameters = profile.parameters();
+
+        // Recommended demand: Start high (105-130% of high estimate, by risk tolerance)
+        let recommended_demand = range.high_estimate * parameters.opening_multiplier;
 
         // Minimum settlement: Must exceed trial costs + risk discount
-        let minimum_settlement = range.low_estimate * 0.9;
+        let minimum_settlement = range.low_estimate * parameters.floor_multiplier;
 
         // Target settlement: Realistic goal (mid-point)
         let target_settlement = range.mid_estimate;
@@ -1322,13 +1982,21 @@ impl SettlementCalculatorService {
         risk: &RiskAssessment,
         demand: f64,
         minimum: f64,
+        profile: ClientRiskProfile,
     ) -> Result<Vec<String>> {
+        let parameters = profile.parameters();
         let mut strategy = Vec::new();
 
-        strategy.push(format!(
-            "Open with demand of ${:.2} (justified by strong liability and damages evidence)",
-            demand
-        ));
+        strategy.push(match profile {
+            ClientRiskProfile::RiskAverse => format!(
+                "Open with demand of ${:.2}, close to case value, to invite an early resolution",
+                demand
+            ),
+            _ => format!(
+                "Open with demand of ${:.2} (justified by strong liability and damages evidence)",
+                demand
+            ),
+        });
 
         strategy.push(
             "Emphasize strength of medical evidence and expert testimony".to_string()
@@ -1347,13 +2015,28 @@ impl SettlementCalculatorService {
             minimum
         ));
 
-        strategy.push(
-            "Use anchoring: Start high, make strategic concessions to demonstrate reasonableness".to_string()
-        );
+        // Even steps from demand to floor over the profile's rounds
+        let step = (demand - minimum) / parameters.concession_rounds as f64;
+        strategy.push(format!(
+            "Concede about ${:.2} per round, reaching the floor by round {}",
+            step, parameters.concession_rounds
+        ));
 
-        strategy.push(
-            "Time pressure: Emphasize approaching trial date and increasing defense costs".to_string()
-        );
+        match profile {
+            ClientRiskProfile::RiskAverse => {
+                strategy.push(
+                    "Propose early mediation; a certain recovery now outweighs a larger uncertain verdict".to_string()
+                );
+            }
+            ClientRiskProfile::Neutral | ClientRiskProfile::Aggressive => {
+                strategy.push(
+                    "Use anchoring: Start high, make strategic concessions to demonstrate reasonableness".to_string()
+                );
+                strategy.push(
+                    "Time pressure: Emphasize approaching trial date and increasing defense costs".to_string()
+                );
+            }
+        }
 
         if liability.liability_strength == LiabilityStrength::Clear {
             strategy.push(
@@ -1361,9 +2044,17 @@ impl SettlementCalculatorService {
             );
         }
 
-        strategy.push(
-            "Final offer: Present as 'take it or see you in court' with trial date imminent".to_string()
-        );
+        strategy.push(match profile {
+            ClientRiskProfile::RiskAverse => {
+                "Final offer: Accept any offer at or above the floor rather than proceed to trial".to_string()
+            }
+            ClientRiskProfile::Neutral => {
+                "Final offer: Present a last number with the trial date approaching".to_string()
+            }
+            ClientRiskProfile::Aggressive => {
+                "Final offer: Present as 'take it or see you in court' with trial date imminent".to_string()
+            }
+        });
 
         Ok(strategy)
     }
@@ -1377,10 +2068,12 @@ impl SettlementCalculatorService {
         recipient_name: &str,
         recipient_address: &str,
         facts: &str,
+        deadline_days: u32,
         created_by: &str,
     ) -> Result<DemandLetter> {
         let letter_id = Uuid::new_v4().to_string();
-        let deadline = Utc::now() + chrono::Duration::days(30);
+        let now = self.clock.now();
+        let deadline = now + chrono::Duration::days(deadline_days as i64);
 
         let subject = format!(
             "Settlement Demand - {} v. {}",
@@ -1435,9 +2128,11 @@ impl SettlementCalculatorService {
             exhibits: Vec::new(),
             letter_html,
             letter_pdf_path: None,
-            created_at: Utc::now(),
+            created_at: now,
             created_by: created_by.to_string(),
             sent_at: None,
+            status: DemandStatus::Pending,
+            responded_at: None,
         };
 
         self.save_demand_letter(&letter).await?;
@@ -1445,6 +2140,66 @@ impl SettlementCalculatorService {
         Ok(letter)
     }
 
+    /// Attach a supporting document to a letter as its next exhibit. The file
+    /// is copied into the exhibit store, hashed, and listed in the letter's
+    /// damages section.
+    pub async fn attach_exhibit(&self, letter_id: &str, file: &Path, description: &str) -> Result<DemandExhibit> {
+        let store = self
+            .exhibit_store
+            .as_ref()
+            .context("No exhibit store configured")?;
+        if self.get_demand_letter(letter_id).await?.is_none() {
+            anyhow::bail!("Demand letter {} not found", letter_id);
+        }
+
+        let bytes = tokio::fs::read(file)
+            .await
+            .with_context(|| format!("Failed to read exhibit {}", file.display()))?;
+        let blob = store.put(&bytes, &format!("demand_letter:{}", letter_id)).await?;
+
+        // Letter and exhibits are read and written under one write lock, so
+        // two exhibits attached at once cannot take the same letter
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let mut letter = load_letter(&mut *tx, letter_id)
+            .await?
+            .with_context(|| format!("Demand letter {} not found", letter_id))?;
+
+        // The letter after the highest one in use, so existing exhibits keep
+        // their letters; a letter freed by removing the last exhibit is reused
+        let next = letter
+            .exhibits
+            .iter()
+            .filter_map(|e| exhibit_index(&e.exhibit_letter))
+            .max()
+            .map_or(0, |i| i + 1);
+        let exhibit = DemandExhibit {
+            exhibit_letter: exhibit_letter(next),
+            description: description.to_string(),
+            file_path: blob.path.to_string_lossy().to_string(),
+            content_hash: Some(blob.hash),
+        };
+
+        if letter.exhibits.is_empty() {
+            letter.damages_section.push_str("\nEnclosed Exhibits:\n\n");
+        }
+        letter
+            .damages_section
+            .push_str(&format!("  Exhibit {}: {}\n", exhibit.exhibit_letter, exhibit.description));
+        letter.exhibits.push(exhibit.clone());
+        letter.letter_html = self.format_letter_html(
+            &letter.subject,
+            &letter.opening_paragraph,
+            &letter.facts_section,
+            &letter.liability_section,
+            &letter.damages_section,
+            &letter.closing_paragraph,
+        ).await?;
+
+        save_letter(&mut *tx, &letter).await?;
+        tx.commit().await?;
+        Ok(exhibit)
+    }
+
     async fn format_facts_section(&self, facts: &str) -> Result<String> {
         Ok(format!("FACTS\n\n{}", facts))
     }
@@ -1519,7 +2274,7 @@ impl SettlementCalculatorService {
             </body>
             </html>
             "#,
-            Utc::now().format("%B %d, %Y"),
+            self.clock.now().format("%B %d, %Y"),
             subject,
             opening,
             facts,
@@ -1569,13 +2324,1801 @@ impl SettlementCalculatorService {
 
     // ============= Helper Methods =============
 
-    async fn save_settlement_calculation(&self, calc: &SettlementCalculation) -> Result<()> {
-        // Stub - would save to database
+    /// Insert or update a calculation. The summary columns are for queries;
+    /// the full calculation, offers and notes included, is stored as JSON.
+    /// A revision's place in its chain is fixed when it is first inserted,
+    /// and a revision that has been recalculated can no longer be changed.
+    pub async fn save_settlement_calculation(&self, calc: &SettlementCalculation) -> Result<()> {
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        save_calculation(&mut *tx, calc).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_settlement_calculation(&self, id: &str) -> Result<Option<SettlementCalculation>> {
+        load_calculation(&self.db, id).await
+    }
+
+    /// Every calculation for a matter, newest first
+    pub async fn list_calculations(&self, matter_id: &str) -> Result<Vec<SettlementCalculation>> {
+        let rows = sqlx::query(
+            "SELECT calculation_json FROM settlement_calculations WHERE matter_id = ? ORDER BY calculated_at DESC",
+        )
+        .bind(matter_id)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to list settlement calculations")?;
+
+        rows.iter()
+            .map(|row| {
+                let json: String = row.try_get("calculation_json")?;
+                serde_json::from_str(&json).context("Stored settlement calculation is malformed")
+            })
+            .collect()
+    }
+
+    /// Insert or update a letter and replace its exhibits
+    pub async fn save_demand_letter(&self, letter: &DemandLetter) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        save_letter(&mut *tx, letter).await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    async fn save_demand_letter(&self, letter: &DemandLetter) -> Result<()> {
-        // Stub - would save to database
+    pub async fn get_demand_letter(&self, id: &str) -> Result<Option<DemandLetter>> {
+        let mut conn = self.db.acquire().await?;
+        load_letter(&mut conn, id).await
+    }
+
+    // ============= Demand Expiration =============
+
+    /// Record the recipient's response so the demand no longer expires
+    pub async fn mark_demand_answered(&self, letter_id: &str, responded_at: DateTime<Utc>) -> Result<()> {
+        let updated = sqlx::query("UPDATE demand_letters SET status = 'Answered', responded_at = ? WHERE id = ?")
+            .bind(responded_at)
+            .bind(letter_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to record demand response")?
+            .rows_affected();
+        if updated == 0 {
+            anyhow::bail!("Demand letter {} not found", letter_id);
+        }
         Ok(())
     }
+
+    /// Move pending demands whose deadline passed before `now` to follow-up,
+    /// optionally opening a litigation task on each matter. Returns the ids
+    /// of the demands that expired.
+    pub async fn process_expired_demands(&self, now: DateTime<Utc>, schedule_litigation_task: bool) -> Result<Vec<String>> {
+        let expired = sqlx::query(
+            "SELECT id, matter_id, subject FROM demand_letters WHERE status = 'Pending' AND deadline < ?",
+        )
+        .bind(now)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to find expired demands")?;
+
+        let mut ids = Vec::with_capacity(expired.len());
+        for row in &expired {
+            let id: String = row.try_get("id")?;
+            let matter_id: String = row.try_get("matter_id")?;
+            let subject: String = row.try_get("subject")?;
+
+            let mut tx = self.db.begin().await?;
+            sqlx::query("UPDATE demand_letters SET status = 'FollowUp' WHERE id = ? AND status = 'Pending'")
+                .bind(&id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to flag expired demand")?;
+
+            if schedule_litigation_task {
+                let due = now + Duration::days(LITIGATION_TASK_DUE_DAYS);
+                sqlx::query(
+                    "INSERT INTO tasks (id, matter_id, title, description, priority, due_date, status, category, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, 'high', ?, 'pending', 'litigation', ?, ?)",
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(&matter_id)
+                .bind("Prepare complaint: settlement demand expired")
+                .bind(format!("No response to \"{}\" by its deadline", subject))
+                .bind(due.format("%Y-%m-%d").to_string())
+                .bind(now.to_rfc3339())
+                .bind(now.to_rfc3339())
+                .execute(&mut *tx)
+                .await
+                .context("Failed to schedule litigation task")?;
+            }
+            tx.commit().await?;
+
+            info!("Demand {} expired without a response", id);
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Check for expired demands until `shutdown` is cancelled
+    pub async fn run_expiration_loop(self: Arc<Self>, schedule_litigation_task: bool, shutdown: CancellationToken) {
+        let mut interval = tokio::time::interval(DEMAND_EXPIRY_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.process_expired_demands(self.clock.now(), schedule_litigation_task).await {
+                        error!("Demand expiration pass failed: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============= Medical Timeline =============
+
+/// Treatment within this many days of today is considered ongoing
+const ONGOING_TREATMENT_WINDOW_DAYS: i64 = 30;
+
+/// Build a chronological treatment timeline from itemized medical expenses,
+/// as of `now`
+pub fn build_medical_timeline(expenses: &[MedicalExpense], now: DateTime<Utc>) -> MedicalTreatmentTimeline {
+    let (future, past): (Vec<&MedicalExpense>, Vec<&MedicalExpense>) =
+        expenses.iter().partition(|e| e.is_future);
+
+    let mut events: Vec<TreatmentEvent> = past
+        .iter()
+        .map(|e| TreatmentEvent {
+            date: e.date,
+            event_type: classify_treatment_event(e),
+            provider: e.provider.clone(),
+            description: e.description.clone(),
+            cost: e.amount,
+            was_emergency: e.category == MedicalCategory::Emergency,
+            related_to_incident: true,
+        })
+        .collect();
+    events.sort_by_key(|e| e.date);
+
+    let total_treatment_days = match (events.first(), events.last()) {
+        (Some(first), Some(last)) => (last.date - first.date).num_days().max(0) as u32,
+        _ => 0,
+    };
+
+    let recently_treated = events
+        .last()
+        .map(|e| (now - e.date).num_days() < ONGOING_TREATMENT_WINDOW_DAYS)
+        .unwrap_or(false);
+
+    let future_treatment_plan = build_future_treatment_plan(&future);
+    let ongoing_treatment = future_treatment_plan.is_some() || recently_treated;
+
+    MedicalTreatmentTimeline {
+        events,
+        total_treatment_days,
+        ongoing_treatment,
+        future_treatment_plan,
+    }
+}
+
+fn classify_treatment_event(expense: &MedicalExpense) -> TreatmentEventType {
+    let description = expense.description.to_lowercase();
+    let is_mental_health = ["psych", "counsel", "behavioral"]
+        .iter()
+        .any(|k| description.contains(k));
+
+    match expense.category {
+        MedicalCategory::Emergency => TreatmentEventType::InitialEmergency,
+        MedicalCategory::Hospital => TreatmentEventType::Hospitalization,
+        MedicalCategory::Surgery => TreatmentEventType::Surgery,
+        MedicalCategory::PhysicalTherapy => TreatmentEventType::PhysicalTherapy,
+        MedicalCategory::Medication => TreatmentEventType::Medication,
+        MedicalCategory::Diagnostic => TreatmentEventType::DiagnosticTest,
+        MedicalCategory::Physician | MedicalCategory::Specialist if is_mental_health => {
+            TreatmentEventType::MentalHealthTreatment
+        }
+        MedicalCategory::Specialist => TreatmentEventType::SpecialistConsult,
+        MedicalCategory::Physician | MedicalCategory::MedicalEquipment | MedicalCategory::HomeCare => {
+            TreatmentEventType::FollowUp
+        }
+    }
+}
+
+fn build_future_treatment_plan(future: &[&MedicalExpense]) -> Option<FutureTreatmentPlan> {
+    if future.is_empty() {
+        return None;
+    }
+
+    let span_years = |category: MedicalCategory| -> Option<u32> {
+        let dates: Vec<DateTime<Utc>> = future
+            .iter()
+            .filter(|e| e.category == category)
+            .map(|e| e.date)
+            .collect();
+        let first = dates.iter().min()?;
+        let last = dates.iter().max()?;
+        Some(((*last - *first).num_days() as f64 / 365.0).ceil().max(1.0) as u32)
+    };
+
+    let surgeries_needed = future
+        .iter()
+        .filter(|e| e.category == MedicalCategory::Surgery)
+        .map(|e| PlannedSurgery {
+            procedure_name: e.description.clone(),
+            estimated_cost: e.amount,
+            timeline: e.date.format("%B %Y").to_string(),
+            necessity: SurgeryNecessity::Future,
+        })
+        .collect();
+
+    let assistive_devices_needed = future
+        .iter()
+        .filter(|e| e.category == MedicalCategory::MedicalEquipment)
+        .map(|e| AssistiveDevice {
+            device_type: e.description.clone(),
+            initial_cost: e.amount,
+            replacement_years: 0,
+            lifetime_cost: e.amount,
+        })
+        .collect();
+
+    let medication_duration = match span_years(MedicalCategory::Medication) {
+        Some(years) if years > 20 => MedicationDuration::Lifelong,
+        Some(years) if years > 5 => MedicationDuration::LongTerm,
+        Some(years) if years > 1 => MedicationDuration::MediumTerm,
+        _ => MedicationDuration::ShortTerm,
+    };
+
+    Some(FutureTreatmentPlan {
+        surgeries_needed,
+        ongoing_therapy_years: span_years(MedicalCategory::PhysicalTherapy).unwrap_or(0),
+        medication_duration,
+        assistive_devices_needed,
+        home_health_care_years: span_years(MedicalCategory::HomeCare),
+        total_estimated_cost: future.iter().map(|e| e.amount).sum(),
+    })
+}
+
+// ============= Storage =============
+
+/// Insert or update `calc` on `conn`; see
+/// [`SettlementCalculatorService::save_settlement_calculation`]
+pub(crate) async fn save_calculation(conn: &mut SqliteConnection, calc: &SettlementCalculation) -> Result<()> {
+    if let Some(next_id) = superseding_calculation(&mut *conn, &calc.id).await? {
+        anyhow::bail!(
+            "Settlement calculation {} was recalculated as {} and can no longer be changed",
+            calc.id,
+            next_id
+        );
+    }
+
+    let (jurisdiction, state_code) = match &calc.jurisdiction_rules {
+        Some(rules) => (rules.jurisdiction.clone(), rules.state_code.clone()),
+        None => (calc.liability_analysis.jurisdiction.clone(), String::new()),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO settlement_calculations (
+            id, matter_id, case_type, plaintiff_name, defendant_name, incident_date,
+            total_economic_damages, total_non_economic_damages, total_punitive_damages, total_damages,
+            recommended_demand, minimum_settlement, target_settlement,
+            jurisdiction, state_code, adjusted_for_caps,
+            estimated_attorney_fees, litigation_costs_to_date, projected_additional_costs, net_to_client,
+            current_negotiation_round, calculated_at, calculated_by, last_updated, version,
+            revision, previous_calculation_id, calculation_json
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (id) DO UPDATE SET
+            total_economic_damages = excluded.total_economic_damages,
+            total_non_economic_damages = excluded.total_non_economic_damages,
+            total_punitive_damages = excluded.total_punitive_damages,
+            total_damages = excluded.total_damages,
+            recommended_demand = excluded.recommended_demand,
+            minimum_settlement = excluded.minimum_settlement,
+            target_settlement = excluded.target_settlement,
+            adjusted_for_caps = excluded.adjusted_for_caps,
+            estimated_attorney_fees = excluded.estimated_attorney_fees,
+            litigation_costs_to_date = excluded.litigation_costs_to_date,
+            projected_additional_costs = excluded.projected_additional_costs,
+            net_to_client = excluded.net_to_client,
+            current_negotiation_round = excluded.current_negotiation_round,
+            last_updated = excluded.last_updated,
+            version = excluded.version,
+            calculation_json = excluded.calculation_json
+        "#,
+    )
+    .bind(&calc.id)
+    .bind(&calc.matter_id)
+    .bind(format!("{:?}", calc.case_type))
+    .bind(&calc.plaintiff_name)
+    .bind(&calc.defendant_name)
+    .bind(calc.incident_date)
+    .bind(calc.economic_damages.total_economic)
+    .bind(calc.non_economic_damages.total_non_economic)
+    .bind(calc.punitive_damages.as_ref().map(|p| p.amount))
+    .bind(calc.total_damages)
+    .bind(calc.recommended_demand)
+    .bind(calc.minimum_settlement)
+    .bind(calc.target_settlement)
+    .bind(jurisdiction)
+    .bind(state_code)
+    .bind(calc.adjusted_for_caps)
+    .bind(calc.estimated_attorney_fees)
+    .bind(calc.litigation_costs_to_date)
+    .bind(calc.projected_additional_costs)
+    .bind(calc.net_to_client)
+    .bind(calc.current_negotiation_round as i64)
+    .bind(calc.calculated_at)
+    .bind(&calc.calculated_by)
+    .bind(calc.last_updated)
+    .bind(&calc.version)
+    .bind(calc.revision as i64)
+    .bind(&calc.previous_calculation_id)
+    .bind(serde_json::to_string(calc)?)
+    .execute(conn)
+    .await
+    .context("Failed to save settlement calculation")?;
+
+    Ok(())
+}
+
+pub(crate) async fn load_calculation(conn: impl SqliteExecutor<'_>, id: &str) -> Result<Option<SettlementCalculation>> {
+    let row = sqlx::query("SELECT calculation_json FROM settlement_calculations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .context("Failed to load settlement calculation")?;
+
+    row.map(|row| {
+        let json: String = row.try_get("calculation_json")?;
+        serde_json::from_str(&json).context("Stored settlement calculation is malformed")
+    })
+    .transpose()
+}
+
+/// The revision calculation `id` was recalculated as, if any
+pub(crate) async fn superseding_calculation(conn: impl SqliteExecutor<'_>, id: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT id FROM settlement_calculations WHERE previous_calculation_id = ?")
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .context("Failed to look up settlement calculation revisions")?;
+
+    Ok(row.map(|row| row.try_get("id")).transpose()?)
+}
+
+/// [`SettlementCalculatorService::save_demand_letter`] on `conn`
+async fn save_letter(conn: &mut SqliteConnection, letter: &DemandLetter) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO demand_letters (
+            id, settlement_calculation_id, matter_id, recipient_name, recipient_address, subject,
+            opening_paragraph, facts_section, liability_section, damages_section, settlement_demand,
+            deadline, closing_paragraph, letter_html, letter_pdf_path, created_at, created_by, sent_at,
+            status, responded_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (id) DO UPDATE SET
+            recipient_name = excluded.recipient_name,
+            recipient_address = excluded.recipient_address,
+            subject = excluded.subject,
+            opening_paragraph = excluded.opening_paragraph,
+            facts_section = excluded.facts_section,
+            liability_section = excluded.liability_section,
+            damages_section = excluded.damages_section,
+            settlement_demand = excluded.settlement_demand,
+            deadline = excluded.deadline,
+            closing_paragraph = excluded.closing_paragraph,
+            letter_html = excluded.letter_html,
+            letter_pdf_path = excluded.letter_pdf_path,
+            sent_at = excluded.sent_at,
+            status = excluded.status,
+            responded_at = excluded.responded_at
+        "#,
+    )
+    .bind(&letter.id)
+    .bind(&letter.settlement_calculation_id)
+    .bind(&letter.matter_id)
+    .bind(&letter.recipient_name)
+    .bind(&letter.recipient_address)
+    .bind(&letter.subject)
+    .bind(&letter.opening_paragraph)
+    .bind(&letter.facts_section)
+    .bind(&letter.liability_section)
+    .bind(&letter.damages_section)
+    .bind(letter.settlement_demand)
+    .bind(letter.deadline)
+    .bind(&letter.closing_paragraph)
+    .bind(&letter.letter_html)
+    .bind(&letter.letter_pdf_path)
+    .bind(letter.created_at)
+    .bind(&letter.created_by)
+    .bind(letter.sent_at)
+    .bind(format!("{:?}", letter.status))
+    .bind(letter.responded_at)
+    .execute(&mut *conn)
+    .await
+    .context("Failed to save demand letter")?;
+
+    sqlx::query("DELETE FROM demand_exhibits WHERE demand_letter_id = ?")
+        .bind(&letter.id)
+        .execute(&mut *conn)
+        .await?;
+    for exhibit in &letter.exhibits {
+        sqlx::query(
+            "INSERT INTO demand_exhibits (id, demand_letter_id, exhibit_letter, description, file_path, content_hash)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&letter.id)
+        .bind(&exhibit.exhibit_letter)
+        .bind(&exhibit.description)
+        .bind(&exhibit.file_path)
+        .bind(&exhibit.content_hash)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to save demand exhibit")?;
+    }
+
+    Ok(())
+}
+
+async fn load_letter(conn: &mut SqliteConnection, id: &str) -> Result<Option<DemandLetter>> {
+    let Some(row) = sqlx::query("SELECT * FROM demand_letters WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to load demand letter")?
+    else {
+        return Ok(None);
+    };
+
+    let exhibits = sqlx::query(
+        "SELECT exhibit_letter, description, file_path, content_hash FROM demand_exhibits
+         WHERE demand_letter_id = ? ORDER BY length(exhibit_letter), exhibit_letter",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|row| {
+        Ok(DemandExhibit {
+            exhibit_letter: row.try_get("exhibit_letter")?,
+            description: row.try_get("description")?,
+            file_path: row.try_get("file_path")?,
+            content_hash: row.try_get("content_hash")?,
+        })
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(DemandLetter {
+        id: row.try_get("id")?,
+        settlement_calculation_id: row.try_get("settlement_calculation_id")?,
+        matter_id: row.try_get("matter_id")?,
+        recipient_name: row.try_get("recipient_name")?,
+        recipient_address: row.try_get("recipient_address")?,
+        subject: row.try_get("subject")?,
+        opening_paragraph: row.try_get("opening_paragraph")?,
+        facts_section: row.try_get("facts_section")?,
+        liability_section: row.try_get("liability_section")?,
+        damages_section: row.try_get("damages_section")?,
+        settlement_demand: row.try_get("settlement_demand")?,
+        deadline: row.try_get("deadline")?,
+        closing_paragraph: row.try_get("closing_paragraph")?,
+        exhibits,
+        letter_html: row.try_get("letter_html")?,
+        letter_pdf_path: row.try_get("letter_pdf_path")?,
+        created_at: row.try_get("created_at")?,
+        created_by: row.try_get("created_by")?,
+        sent_at: row.try_get("sent_at")?,
+        status: parse_variant(&row.try_get::<String, _>("status")?)?,
+        responded_at: row.try_get("responded_at")?,
+    }))
+}
+
+// ============= Exhibits =============
+
+/// Exhibit letter for a zero-based position: A..Z, then AA, AB, ...
+fn exhibit_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Inverse of `exhibit_letter`; None for anything that is not an exhibit letter
+fn exhibit_index(letter: &str) -> Option<usize> {
+    if letter.is_empty() || !letter.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    letter
+        .bytes()
+        .try_fold(0usize, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A') as usize + 1))
+        .map(|n| n - 1)
+}
+
+// ============= Multiple Defendants =============
+
+/// Split damages already reduced for the plaintiff's own fault among the
+/// defendants in proportion to fault. A defendant at or above the rule's
+/// threshold is jointly liable for the whole of each category the rule
+/// covers; non-economic damages stay several-only when the rule is limited
+/// to economic damages.
+pub fn apportion_liability(
+    economic: f64,
+    non_economic: f64,
+    defendants: &[DefendantShare],
+    rule: &JointSeveralLiability,
+) -> Vec<DefendantApportionment> {
+    let total_fault: f64 = defendants.iter().map(|d| d.fault_percentage.max(0.0)).sum();
+
+    defendants
+        .iter()
+        .map(|defendant| {
+            let share = if total_fault > 0.0 {
+                defendant.fault_percentage.max(0.0) / total_fault
+            } else {
+                1.0 / defendants.len() as f64
+            };
+            let joint = rule.applies
+                && rule
+                    .threshold_percentage
+                    .map_or(true, |threshold| defendant.fault_percentage >= threshold);
+            let jointly_liable_economic = joint;
+            let jointly_liable_non_economic = joint && !rule.economic_only;
+
+            let several_economic = economic * share;
+            let several_non_economic = non_economic * share;
+            DefendantApportionment {
+                name: defendant.name.clone(),
+                fault_percentage: defendant.fault_percentage,
+                several_economic,
+                several_non_economic,
+                jointly_liable_economic,
+                jointly_liable_non_economic,
+                max_economic_exposure: if jointly_liable_economic { economic } else { several_economic },
+                max_non_economic_exposure: if jointly_liable_non_economic {
+                    non_economic
+                } else {
+                    several_non_economic
+                },
+            }
+        })
+        .collect()
+}
+
+// ============= Collateral Source =============
+
+/// Apply the jurisdiction's collateral source rule to economic damages.
+/// Where reduction is mandatory, payments already made by insurers and other
+/// collateral sources come off past medical expenses (never below zero) and
+/// the totals are adjusted to match; otherwise damages are left intact.
+/// Returns the amount deducted.
+pub fn apply_collateral_source(
+    economic: &mut EconomicDamages,
+    collateral_payments: f64,
+    rule: &CollateralSourceRule,
+) -> f64 {
+    match rule {
+        // Admitted payments are evidence for the jury, not an automatic offset
+        CollateralSourceRule::Excluded | CollateralSourceRule::Admitted => 0.0,
+        CollateralSourceRule::ReduceMandatory => {
+            let reduction = collateral_payments.clamp(0.0, economic.past_medical_expenses.max(0.0));
+            economic.past_medical_expenses -= reduction;
+            economic.total_past_economic -= reduction;
+            economic.total_economic -= reduction;
+            reduction
+        }
+    }
+}
+
+// ============= Interest =============
+
+/// Compute simple prejudgment interest on a principal between two dates
+pub fn compute_prejudgment_interest(
+    principal: f64,
+    rate: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> f64 {
+    compute_interest(principal, rate, from, to, &InterestMethod::Simple)
+}
+
+/// Compute interest using the jurisdiction's accrual method
+pub fn compute_interest(
+    principal: f64,
+    rate: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    method: &InterestMethod,
+) -> f64 {
+    if to <= from || principal <= 0.0 {
+        return 0.0;
+    }
+
+    let years = years_between(from, to);
+
+    match method {
+        InterestMethod::Simple => principal * rate * years,
+        InterestMethod::CompoundedAnnually => principal * ((1.0 + rate).powf(years) - 1.0),
+    }
+}
+
+/// Elapsed time in years, counting whole calendar months and then remaining days
+fn years_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    let mut months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    if to.day() < from.day() {
+        months -= 1;
+    }
+    let months = months.max(0);
+
+    let anniversary = from
+        .checked_add_months(chrono::Months::new(months as u32))
+        .unwrap_or(from);
+    let remaining_days = (to - anniversary).num_days().max(0);
+
+    months as f64 / 12.0 + remaining_days as f64 / 365.0
+}
+
+/// A unit enum variant from the name stored for it in a lookup table
+fn parse_variant<T: serde::de::DeserializeOwned>(name: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .with_context(|| format!("Unrecognized value '{}'", name))
+}
+
+// ============= AI Reconciliation =============
+
+/// Divergence between the estimates above which an attorney should review
+pub const ESTIMATE_DIVERGENCE_REVIEW_THRESHOLD: f64 = 0.25;
+
+/// Blend the heuristic midpoint with the AI prediction, each weighted by its
+/// own confidence, and flag the result when the two disagree widely
+pub fn reconcile_settlement_estimates(heuristic: &SettlementRange, ai: &AISettlementAnalysis) -> ReconciledEstimate {
+    let heuristic_confidence = heuristic.confidence_level.clamp(0.0, 1.0);
+    let ai_confidence = ai.confidence_score.clamp(0.0, 1.0);
+    let total = heuristic_confidence + ai_confidence;
+    let ai_weight = if total > 0.0 { ai_confidence / total } else { 0.5 };
+    let heuristic_weight = 1.0 - ai_weight;
+
+    let heuristic_value = heuristic.mid_estimate;
+    let ai_value = ai.predicted_settlement_value;
+    let blended_value = heuristic_value * heuristic_weight + ai_value * ai_weight;
+
+    let divergence = if heuristic_value > 0.0 {
+        (ai_value - heuristic_value).abs() / heuristic_value
+    } else if ai_value > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+    let needs_attorney_review = divergence > ESTIMATE_DIVERGENCE_REVIEW_THRESHOLD;
+
+    let mut explanation = format!(
+        "Weighted {:.0}% to the AI prediction of ${:.2} (confidence {:.2}) and {:.0}% to the heuristic \
+         midpoint of ${:.2} (confidence {:.2}), giving ${:.2}.",
+        ai_weight * 100.0,
+        ai_value,
+        ai_confidence,
+        heuristic_weight * 100.0,
+        heuristic_value,
+        heuristic_confidence,
+        blended_value
+    );
+    if needs_attorney_review {
+        explanation.push_str(&format!(
+            " The estimates differ by {:.0}%, more than the {:.0}% review threshold; attorney review recommended.",
+            divergence * 100.0,
+            ESTIMATE_DIVERGENCE_REVIEW_THRESHOLD * 100.0
+        ));
+    }
+
+    ReconciledEstimate {
+        blended_value,
+        heuristic_value,
+        ai_value,
+        heuristic_weight,
+        ai_weight,
+        divergence,
+        needs_attorney_review,
+        explanation,
+    }
+}
+
+// ============= Attorney Fees & Net Recovery =============
+
+/// Default contingency percentage when the jurisdiction imposes no cap
+const DEFAULT_CONTINGENCY_PERCENTAGE: f64 = 1.0 / 3.0;
+
+/// Compute the client's net recovery after attorney fees, costs, and liens
+pub fn compute_net_to_client(
+    gross: f64,
+    case_type: &CaseType,
+    fee_rules: &AttorneyFeeRules,
+    costs: Costs,
+    liens: &[Lien],
+) -> NetToClientBreakdown {
+    let sliding_scale = fee_rules.sliding_scale_applies_to(Some(case_type));
+    let fee_tiers_applied = if sliding_scale && !fee_rules.sliding_scale_tiers.is_empty() {
+        apply_sliding_scale(gross, &fee_rules.sliding_scale_tiers)
+    } else {
+        let percentage = fee_rules.contingency_fee_max.unwrap_or(DEFAULT_CONTINGENCY_PERCENTAGE);
+        vec![FeeTierAmount {
+            band_start: 0.0,
+            band_end: gross,
+            percentage,
+            fee: gross * percentage,
+        }]
+    };
+
+    let mut attorney_fee: f64 = fee_tiers_applied.iter().map(|t| t.fee).sum();
+
+    // The sliding scale never allows more than the flat cap
+    if let Some(max) = fee_rules.contingency_fee_max {
+        attorney_fee = attorney_fee.min(gross * max);
+    }
+
+    let effective_fee_percentage = if gross > 0.0 { attorney_fee / gross } else { 0.0 };
+    let total_liens: f64 = liens.iter().map(|l| l.amount).sum();
+
+    let net_to_client = gross
+        - attorney_fee
+        - costs.litigation_costs_to_date
+        - costs.projected_additional_costs
+        - total_liens;
+
+    NetToClientBreakdown {
+        gross_settlement: gross,
+        attorney_fee,
+        effective_fee_percentage,
+        fee_tiers_applied,
+        litigation_costs_to_date: costs.litigation_costs_to_date,
+        projected_additional_costs: costs.projected_additional_costs,
+        liens: liens.to_vec(),
+        total_liens,
+        net_to_client,
+    }
+}
+
+/// Validate a contingency fee agreement against the fee rules of the forum
+/// the case is brought in
+pub fn validate_fee_agreement(
+    percentage: f64,
+    case_type: Option<&CaseType>,
+    rules: &AttorneyFeeRules,
+) -> Result<FeeAgreementStatus> {
+    if !(0.0..=1.0).contains(&percentage) {
+        anyhow::bail!("Contingency percentage must be between 0% and 100%, got {:.2}%", percentage * 100.0);
+    }
+
+    if let Some(max) = rules.contingency_fee_max {
+        if basis_points(percentage) > basis_points(max) {
+            anyhow::bail!(
+                "Contingency fee of {:.2}% exceeds the jurisdiction maximum of {:.2}%",
+                percentage * 100.0,
+                max * 100.0
+            );
+        }
+    }
+
+    // A flat percentage only complies with a sliding scale if it never exceeds any band's rate
+    if rules.sliding_scale_applies_to(case_type) {
+        if let Some(lowest) = rules
+            .sliding_scale_tiers
+            .iter()
+            .map(|t| t.percentage)
+            .min_by(|a, b| a.total_cmp(b))
+        {
+            if basis_points(percentage) > basis_points(lowest) {
+                anyhow::bail!(
+                    "Jurisdiction requires a sliding-scale fee; a flat {:.2}% exceeds the {:.2}% rate of the highest band",
+                    percentage * 100.0,
+                    lowest * 100.0
+                );
+            }
+        }
+    }
+
+    if rules.court_approval_required {
+        return Ok(FeeAgreementStatus::RequiresCourtApproval);
+    }
+
+    Ok(FeeAgreementStatus::Permitted)
+}
+
+/// Fee rates compare at basis-point precision, so a one-third fee written as
+/// 0.3333 and one computed as 1/3 are the same rate
+fn basis_points(rate: f64) -> i64 {
+    (rate * 10_000.0).round() as i64
+}
+
+fn apply_sliding_scale(gross: f64, tiers: &[FeeTier]) -> Vec<FeeTierAmount> {
+    let mut applied = Vec::new();
+    let mut band_start = 0.0;
+
+    for tier in tiers {
+        if band_start >= gross {
+            break;
+        }
+
+        let band_end = tier.up_to.map_or(gross, |limit| limit.min(gross));
+        if band_end <= band_start {
+            continue;
+        }
+
+        applied.push(FeeTierAmount {
+            band_start,
+            band_end,
+            percentage: tier.percentage,
+            fee: (band_end - band_start) * tier.percentage,
+        });
+        band_start = band_end;
+    }
+
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::{test_database, test_database_at};
+
+    fn flat_fee_rules(max: f64) -> AttorneyFeeRules {
+        AttorneyFeeRules {
+            contingency_fee_max: Some(max),
+            sliding_scale_required: false,
+            court_approval_required: false,
+            costs_advance_rules: String::new(),
+            sliding_scale_tiers: Vec::new(),
+            sliding_scale_case_types: Vec::new(),
+        }
+    }
+
+    async fn service_with_matter() -> SettlementCalculatorService {
+        let db = test_database().await;
+        sqlx::raw_sql(
+            "INSERT INTO clients (id, first_name, last_name, created_at, updated_at) VALUES ('c1', 'Jane', 'Roe', '2024-01-01', '2024-01-01');
+             INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+             VALUES ('m1', 'c1', '2024-001', 'Roe v. Acme', 'civil', '2024-01-01', '2024-01-01');",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        SettlementCalculatorService::new(db)
+    }
+
+    fn sample_calculation() -> SettlementCalculation {
+        use chrono::TimeZone;
+        let at = |m, d| Utc.with_ymd_and_hms(2024, m, d, 12, 0, 0).unwrap();
+
+        SettlementCalculation {
+            id: "calc-1".to_string(),
+            matter_id: "m1".to_string(),
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Roe".to_string(),
+            defendant_name: "Acme Trucking".to_string(),
+            incident_date: Some(at(1, 10)),
+            economic_damages: EconomicDamages {
+                past_medical_expenses: 45_000.0,
+                future_medical_expenses: 10_000.0,
+                medical_expense_details: vec![MedicalExpense {
+                    date: at(1, 10),
+                    provider: "County ER".to_string(),
+                    description: "Emergency visit".to_string(),
+                    amount: 5_000.0,
+                    category: MedicalCategory::Emergency,
+                    is_future: false,
+                }],
+                past_lost_wages: 12_000.0,
+                future_lost_earning_capacity: 0.0,
+                lost_benefits: 0.0,
+                property_damage: 8_000.0,
+                rehabilitation_costs: 0.0,
+                home_modification_costs: 0.0,
+                assistive_device_costs: 0.0,
+                transportation_costs: 500.0,
+                other_expenses: 0.0,
+                total_past_economic: 65_500.0,
+                total_future_economic: 10_000.0,
+                total_economic: 75_500.0,
+                discount_rate: 0.03,
+                present_value_future_damages: 9_500.0,
+            },
+            non_economic_damages: NonEconomicDamages {
+                pain_and_suffering: 150_000.0,
+                emotional_distress: 25_000.0,
+                loss_of_consortium: 0.0,
+                loss_of_enjoyment_of_life: 20_000.0,
+                disfigurement: 0.0,
+                loss_of_reputation: 0.0,
+                total_non_economic: 195_000.0,
+                methodology: NonEconomicMethodology::Multiplier,
+                multiplier: 2.5,
+                per_diem_rate: None,
+                days_in_pain: Some(120),
+            },
+            punitive_damages: None,
+            total_damages: 270_500.0,
+            settlement_range: SettlementRange {
+                low_estimate: 150_000.0,
+                mid_estimate: 200_000.0,
+                high_estimate: 270_500.0,
+                confidence_level: 0.75,
+                range_explanation: "Comparable verdicts".to_string(),
+            },
+            liability_analysis: LiabilityAnalysis {
+                plaintiff_liability_percentage: 0.0,
+                defendant_liability_percentage: 100.0,
+                comparative_negligence_applies: true,
+                jurisdiction: "PA".to_string(),
+                liability_strength: LiabilityStrength::Strong,
+                key_liability_factors: vec![LiabilityFactor {
+                    factor: "Rear-end collision".to_string(),
+                    favors: "Plaintiff".to_string(),
+                    weight: 0.75,
+                }],
+            },
+            defendant_apportionment: Vec::new(),
+            risk_assessment: RiskAssessment {
+                trial_risk_score: 0.25,
+                strengths: vec![CaseStrength { description: "Clear liability".to_string(), impact: ImpactLevel::Major }],
+                weaknesses: Vec::new(),
+                trial_cost_estimate: 40_000.0,
+                expected_trial_duration_months: 18,
+                probability_of_win: 0.75,
+                expected_trial_value: 202_875.0,
+            },
+            comparable_verdicts: Vec::new(),
+            jurisdiction_rules: None,
+            adjusted_for_caps: false,
+            cap_adjustments: None,
+            ai_analysis: None,
+            reconciled_estimate: None,
+            medical_timeline: None,
+            recommended_demand: 350_000.0,
+            minimum_settlement: 150_000.0,
+            target_settlement: 225_000.0,
+            rationale: "Strong liability".to_string(),
+            negotiation_strategy: vec!["Open at demand".to_string()],
+            offers_received: vec![SettlementOffer {
+                id: "offer-1".to_string(),
+                matter_id: "m1".to_string(),
+                settlement_calculation_id: "calc-1".to_string(),
+                offer_from: "Defendant".to_string(),
+                offer_amount: 100_000.0,
+                offer_date: at(3, 1),
+                expiration_date: Some(at(3, 31)),
+                terms: vec![SettlementTerm { term: "Confidentiality".to_string(), value: None }],
+                conditions: vec!["Full release".to_string()],
+                status: OfferStatus::Countered,
+                response: Some("Countered at $300,000".to_string()),
+                response_date: Some(at(3, 5)),
+                analysis: OfferAnalysis {
+                    percentage_of_demand: 28.5,
+                    percentage_of_calculated_value: 37.0,
+                    comparison_to_verdict_range: "Below acceptable range".to_string(),
+                    net_recovery_after_costs: 60_000.0,
+                    time_value_analysis: "18-month delay".to_string(),
+                },
+                recommendation: OfferRecommendation::Counter,
+            }],
+            counteroffers_made: vec![CounterOffer {
+                id: "counter-1".to_string(),
+                amount: 300_000.0,
+                date: at(3, 5),
+                rationale: "Medical specials support a higher figure".to_string(),
+                status: OfferStatus::Pending,
+            }],
+            current_negotiation_round: 1,
+            prejudgment_interest: Some(4_500.0),
+            postjudgment_interest_rate: Some(0.06),
+            structured_settlement_option: None,
+            estimated_attorney_fees: 75_000.0,
+            litigation_costs_to_date: 5_000.0,
+            projected_additional_costs: 10_000.0,
+            net_to_client: 135_000.0,
+            calculated_at: at(2, 1),
+            calculated_by: "jdoe".to_string(),
+            version: "2.0.0".to_string(),
+            last_updated: at(3, 5),
+            calculation_notes: vec![CalculationNote {
+                timestamp: at(2, 2),
+                author: "jdoe".to_string(),
+                note: "Client confirmed wage loss with employer letter".to_string(),
+                note_type: NoteType::ClientInput,
+            }],
+            revision: 1,
+            previous_calculation_id: None,
+            inputs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settlement_calculation_round_trip() {
+        let service = service_with_matter().await;
+        let calc = sample_calculation();
+
+        service.save_settlement_calculation(&calc).await.unwrap();
+        let loaded = service.get_settlement_calculation("calc-1").await.unwrap().unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&calc).unwrap());
+        assert_eq!(loaded.offers_received[0].terms[0].term, "Confidentiality");
+        assert_eq!(loaded.calculation_notes[0].note_type, NoteType::ClientInput);
+
+        // Saving again updates in place
+        let mut updated = calc.clone();
+        updated.current_negotiation_round = 2;
+        service.save_settlement_calculation(&updated).await.unwrap();
+        let listed = service.list_calculations("m1").await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].current_negotiation_round, 2);
+        assert!(service.get_settlement_calculation("missing").await.unwrap().is_none());
+
+        let mut letter = service
+            .generate_demand_letter(&calc, "Claims Adjuster", "1 Insurance Way", "Rear-end collision.", 30, "jdoe")
+            .await
+            .unwrap();
+        letter.exhibits.push(DemandExhibit {
+            exhibit_letter: "A".to_string(),
+            description: "ER records".to_string(),
+            file_path: "/records/er.pdf".to_string(),
+            content_hash: None,
+        });
+        service.save_demand_letter(&letter).await.unwrap();
+        let loaded_letter = service.get_demand_letter(&letter.id).await.unwrap().unwrap();
+        assert_eq!(serde_json::to_value(&loaded_letter).unwrap(), serde_json::to_value(&letter).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recalculation_stores_linked_revision() {
+        let service = service_with_matter().await;
+        let damages = sample_calculation().economic_damages;
+        let inputs = SettlementInputs {
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Roe".to_string(),
+            defendant_name: "Acme Trucking".to_string(),
+            economic_damages: service.calculate_total_economic_damages(damages.clone()).unwrap(),
+            injury_details: None,
+            liability_percentage: 100.0,
+            jurisdiction: "PA".to_string(),
+            risk_profile: ClientRiskProfile::Neutral,
+            collateral_source_payments: 0.0,
+            defendants: Vec::new(),
+        };
+        let v1 = service
+            .calculate_settlement(
+                "m1",
+                inputs.case_type.clone(),
+                &inputs.plaintiff_name,
+                &inputs.defendant_name,
+                inputs.economic_damages.clone(),
+                None,
+                inputs.liability_percentage,
+                &inputs.jurisdiction,
+                inputs.risk_profile,
+                "jdoe",
+            )
+            .await
+            .unwrap();
+        assert_eq!(v1.revision, 1);
+
+        // Further treatment raises the medical specials
+        let raised = EconomicDamages { past_medical_expenses: damages.past_medical_expenses + 30_000.0, ..damages };
+        let updated = SettlementInputs {
+            economic_damages: service.calculate_total_economic_damages(raised).unwrap(),
+            ..v1.inputs.clone().unwrap()
+        };
+        let v2 = service.recalculate(&v1.id, updated.clone(), "jdoe").await.unwrap();
+
+        assert_eq!(v2.revision, 2);
+        assert_eq!(v2.previous_calculation_id.as_deref(), Some(v1.id.as_str()));
+        assert!(v2.total_damages > v1.total_damages, "{} <= {}", v2.total_damages, v1.total_damages);
+
+        // v1 is unchanged and the history runs v1 -> v2
+        let stored_v1 = service.get_settlement_calculation(&v1.id).await.unwrap().unwrap();
+        assert_eq!(stored_v1.total_damages, v1.total_damages);
+        let history = service.get_calculation_history("m1").await.unwrap();
+        let ids: Vec<&str> = history.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, [v1.id.as_str(), v2.id.as_str()]);
+
+        // A superseded revision cannot be recalculated again
+        assert!(service.recalculate(&v1.id, updated, "jdoe").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collateral_payments_reduce_specials_only_where_mandatory() {
+        let service = service_with_matter().await;
+        let damages = service.calculate_total_economic_damages(sample_calculation().economic_damages).unwrap();
+        let inputs = |jurisdiction: &str| SettlementInputs {
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Roe".to_string(),
+            defendant_name: "Acme Trucking".to_string(),
+            economic_damages: damages.clone(),
+            injury_details: None,
+            liability_percentage: 100.0,
+            jurisdiction: jurisdiction.to_string(),
+            risk_profile: ClientRiskProfile::Neutral,
+            collateral_source_payments: 10_000.0,
+            defendants: Vec::new(),
+        };
+
+        // Florida requires the offset
+        let florida = service.compute_settlement("m1", inputs("FL"), "jdoe").await.unwrap();
+        assert!((florida.economic_damages.total_economic - (damages.total_economic - 10_000.0)).abs() < 1e-6);
+        assert!((florida.economic_damages.past_medical_expenses - (damages.past_medical_expenses - 10_000.0)).abs() < 1e-6);
+        assert_eq!(florida.calculation_notes.len(), 1);
+        assert_eq!(florida.calculation_notes[0].note_type, NoteType::Adjustment);
+        assert_eq!(florida.inputs.as_ref().unwrap().collateral_source_payments, 10_000.0);
+
+        // Pennsylvania excludes collateral sources, so damages are untouched
+        let pennsylvania = service.compute_settlement("m1", inputs("PA"), "jdoe").await.unwrap();
+        assert!((pennsylvania.economic_damages.total_economic - damages.total_economic).abs() < 1e-6);
+        assert!(pennsylvania.calculation_notes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_risk_averse_client_opens_lower_and_settles_sooner() {
+        let averse = ClientRiskProfile::RiskAverse.parameters();
+        let aggressive = ClientRiskProfile::Aggressive.parameters();
+        assert!(averse.opening_multiplier < aggressive.opening_multiplier);
+        assert!(averse.floor_multiplier < aggressive.floor_multiplier);
+        assert!(averse.concession_rounds < aggressive.concession_rounds);
+
+        let service = service_with_matter().await;
+        let calc = sample_calculation();
+        let (range, risk, liability) = (&calc.settlement_range, &calc.risk_assessment, &calc.liability_analysis);
+        let (averse_demand, averse_floor, _) = service
+            .generate_settlement_recommendations(range, risk, liability, ClientRiskProfile::RiskAverse)
+            .await
+            .unwrap();
+        let (aggressive_demand, aggressive_floor, _) = service
+            .generate_settlement_recommendations(range, risk, liability, ClientRiskProfile::Aggressive)
+            .await
+            .unwrap();
+        assert!(averse_demand < aggressive_demand);
+        assert!(averse_floor < aggressive_floor);
+
+        let strategy = service
+            .generate_negotiation_strategy(
+                &calc.case_type,
+                &calc.liability_analysis,
+                &calc.risk_assessment,
+                averse_demand,
+                averse_floor,
+                ClientRiskProfile::RiskAverse,
+            )
+            .await
+            .unwrap();
+        assert!(strategy.iter().any(|line| line.contains("reaching the floor by round 2")));
+        assert!(!strategy.iter().any(|line| line.contains("see you in court")));
+    }
+
+    #[tokio::test]
+    async fn test_offer_comparison_orders_offers_by_date() {
+        use chrono::TimeZone;
+        let at = |m, d| Utc.with_ymd_and_hms(2024, m, d, 12, 0, 0).unwrap();
+        let service = service_with_matter().await;
+
+        let mut calc = sample_calculation();
+        let first = calc.offers_received[0].clone();
+        let offer = |id: &str, amount: f64, date| SettlementOffer {
+            id: id.to_string(),
+            offer_amount: amount,
+            offer_date: date,
+            ..first.clone()
+        };
+        // Recorded out of order
+        calc.offers_received = vec![
+            offer("offer-1", 100_000.0, at(3, 1)),
+            offer("offer-3", 180_000.0, at(4, 15)),
+            offer("offer-2", 140_000.0, at(3, 20)),
+        ];
+        service.save_settlement_calculation(&calc).await.unwrap();
+
+        let comparison = service.build_offer_comparison("calc-1").await.unwrap();
+
+        let ids: Vec<&str> = comparison.offers.iter().map(|o| o.offer_id.as_str()).collect();
+        assert_eq!(ids, ["offer-1", "offer-2", "offer-3"]);
+        assert_eq!(comparison.offers.iter().map(|o| o.round).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+        let demand_pcts: Vec<f64> = comparison.offers.iter().map(|o| o.percentage_of_demand).collect();
+        assert!(close(demand_pcts[0], 28.57) && close(demand_pcts[1], 40.0) && close(demand_pcts[2], 51.43), "{:?}", demand_pcts);
+        let value_pcts: Vec<f64> = comparison.offers.iter().map(|o| o.percentage_of_calculated_value).collect();
+        assert!(close(value_pcts[0], 36.97) && close(value_pcts[1], 51.76) && close(value_pcts[2], 66.54), "{:?}", value_pcts);
+
+        let fee_rules = service.load_jurisdiction_rules("PA").await.unwrap().attorney_fee_rules;
+        let costs = Costs { litigation_costs_to_date: 5_000.0, projected_additional_costs: 10_000.0 };
+        let expected_net = compute_net_to_client(180_000.0, &CaseType::PersonalInjury, &fee_rules, costs, &[]).net_to_client;
+        assert!(close(comparison.offers[2].net_to_client, expected_net));
+
+        assert_eq!(comparison.offers[1].change_from_previous, Some(40_000.0));
+        assert_eq!(comparison.offers[0].recommendation, OfferRecommendation::Reject);
+        assert_eq!(comparison.offers[2].recommendation, OfferRecommendation::Counter);
+
+        let trend = comparison.trend.unwrap();
+        assert_eq!(trend.direction, TrendDirection::Rising);
+        assert_eq!(trend.change_since_first, 80_000.0);
+        assert_eq!(trend.average_change_per_round, 40_000.0);
+        assert_eq!(trend.remaining_gap_to_target, 45_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_offers_are_compared_and_superseded_revisions_rejected() {
+        let service = service_with_matter().await;
+        let calc = SettlementCalculation { offers_received: Vec::new(), ..sample_calculation() };
+        service.save_settlement_calculation(&calc).await.unwrap();
+
+        let first = service.record_offer("calc-1", 100_000.0, "Acme Insurance", Vec::new(), Vec::new()).await.unwrap();
+        let second = service.record_offer("calc-1", 140_000.0, "Acme Insurance", Vec::new(), Vec::new()).await.unwrap();
+        assert_eq!(first.settlement_calculation_id, "calc-1");
+
+        let comparison = service.build_offer_comparison("calc-1").await.unwrap();
+        let ids: Vec<&str> = comparison.offers.iter().map(|o| o.offer_id.as_str()).collect();
+        assert_eq!(ids, [first.id.as_str(), second.id.as_str()]);
+        assert_eq!(comparison.offers[1].change_from_previous, Some(40_000.0));
+
+        // Once recalculated, calc-1 is history and takes no further offers
+        let revision = SettlementCalculation {
+            id: "calc-2".to_string(),
+            revision: 2,
+            previous_calculation_id: Some("calc-1".to_string()),
+            ..sample_calculation()
+        };
+        service.save_settlement_calculation(&revision).await.unwrap();
+        let err = service
+            .record_offer("calc-1", 160_000.0, "Acme Insurance", Vec::new(), Vec::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("calc-2"), "{}", err);
+        let stored = service.get_settlement_calculation("calc-1").await.unwrap().unwrap();
+        assert_eq!(stored.offers_received.len(), 2);
+
+        // Nor can it be overwritten directly
+        let mut edited = stored.clone();
+        edited.current_negotiation_round = 5;
+        assert!(service.save_settlement_calculation(&edited).await.is_err());
+        let stored = service.get_settlement_calculation("calc-1").await.unwrap().unwrap();
+        assert_eq!(stored.current_negotiation_round, sample_calculation().current_negotiation_round);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_offers_are_all_kept() {
+        // A file database, so the offers are recorded on separate connections
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("settlement.db").display());
+        let db = test_database_at(&url).await;
+        sqlx::raw_sql(
+            "INSERT INTO clients (id, first_name, last_name, created_at, updated_at) VALUES ('c1', 'Jane', 'Roe', '2024-01-01', '2024-01-01');
+             INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+             VALUES ('m1', 'c1', '2024-001', 'Roe v. Acme', 'civil', '2024-01-01', '2024-01-01');",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        let service = SettlementCalculatorService::new(db);
+        let calc = SettlementCalculation { offers_received: Vec::new(), ..sample_calculation() };
+        service.save_settlement_calculation(&calc).await.unwrap();
+
+        let offers = (0..6).map(|i| {
+            service.record_offer("calc-1", 100_000.0 + i as f64, "Acme Insurance", Vec::new(), Vec::new())
+        });
+        for offer in futures_util::future::join_all(offers).await {
+            offer.unwrap();
+        }
+
+        let stored = service.get_settlement_calculation("calc-1").await.unwrap().unwrap();
+        assert_eq!(stored.offers_received.len(), 6);
+    }
+
+    struct FixedPredictor {
+        value: f64,
+        confidence: f64,
+    }
+
+    #[async_trait]
+    impl SettlementPredictor for FixedPredictor {
+        async fn predict(&self, _: &CaseType, _: f64, _: &LiabilityAnalysis, _: &str) -> Result<AISettlementAnalysis> {
+            Ok(AISettlementAnalysis {
+                predicted_settlement_value: self.value,
+                confidence_score: self.confidence,
+                prediction_model_version: "fixed".to_string(),
+                factors_considered: Vec::new(),
+                similar_cases_analyzed: 0,
+                judge_history: None,
+                opposing_counsel_history: None,
+                insurance_company_behavior: None,
+                venue_statistics: None,
+            })
+        }
+    }
+
+    /// Reconcile a $240,000 prediction at `confidence` against a $200,000
+    /// heuristic midpoint held at 0.5 confidence
+    async fn estimate_with_ai_confidence(confidence: f64) -> ReconciledEstimate {
+        let calc = sample_calculation();
+        let heuristic = SettlementRange { confidence_level: 0.5, ..calc.settlement_range };
+        let service = service_with_matter()
+            .await
+            .with_predictor(Arc::new(FixedPredictor { value: 240_000.0, confidence }));
+
+        let (_, reconciled) = service
+            .ai_estimate("m1", &CaseType::PersonalInjury, 300_000.0, &calc.liability_analysis, "PA", &heuristic)
+            .await
+            .unwrap();
+        reconciled
+    }
+
+    #[tokio::test]
+    async fn test_high_confidence_ai_pulls_blend_toward_prediction() {
+        let confident = estimate_with_ai_confidence(0.95).await;
+        assert!(confident.ai_weight > confident.heuristic_weight);
+        assert!(confident.blended_value > 220_000.0, "{}", confident.blended_value);
+        assert!(!confident.needs_attorney_review);
+
+        let unsure = estimate_with_ai_confidence(0.1).await;
+        assert!(unsure.blended_value < 220_000.0, "{}", unsure.blended_value);
+        assert!(confident.blended_value > unsure.blended_value);
+    }
+
+    #[test]
+    fn test_large_divergence_flagged_for_review() {
+        let heuristic = sample_calculation().settlement_range;
+        let ai = |value| AISettlementAnalysis {
+            predicted_settlement_value: value,
+            confidence_score: 0.8,
+            prediction_model_version: "fixed".to_string(),
+            factors_considered: Vec::new(),
+            similar_cases_analyzed: 0,
+            judge_history: None,
+            opposing_counsel_history: None,
+            insurance_company_behavior: None,
+            venue_statistics: None,
+        };
+
+        // Heuristic midpoint is $200,000
+        let close = reconcile_settlement_estimates(&heuristic, &ai(230_000.0));
+        assert!((close.divergence - 0.15).abs() < 1e-9);
+        assert!(!close.needs_attorney_review);
+
+        let far = reconcile_settlement_estimates(&heuristic, &ai(400_000.0));
+        assert!((far.divergence - 1.0).abs() < 1e-9);
+        assert!(far.needs_attorney_review);
+        assert!(far.explanation.contains("attorney review"));
+    }
+
+    #[test]
+    fn test_net_to_client_one_third_fee_with_lien() {
+        let rules = flat_fee_rules(1.0 / 3.0);
+        let costs = Costs {
+            litigation_costs_to_date: 5_000.0,
+            projected_additional_costs: 0.0,
+        };
+        let liens = vec![Lien {
+            lienholder: "County General Hospital".to_string(),
+            lien_type: LienType::MedicalProvider,
+            amount: 10_000.0,
+        }];
+
+        let breakdown = compute_net_to_client(300_000.0, &CaseType::PersonalInjury, &rules, costs, &liens);
+
+        assert!((breakdown.attorney_fee - 100_000.0).abs() < 0.01);
+        assert!((breakdown.total_liens - 10_000.0).abs() < 0.01);
+        assert!((breakdown.net_to_client - 185_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_net_to_client_sliding_scale() {
+        // California MICRA-style schedule
+        let rules = AttorneyFeeRules {
+            contingency_fee_max: Some(0.40),
+            sliding_scale_required: true,
+            court_approval_required: false,
+            costs_advance_rules: String::new(),
+            sliding_scale_tiers: vec![
+                FeeTier { up_to: Some(50_000.0), percentage: 0.40 },
+                FeeTier { up_to: Some(100_000.0), percentage: 1.0 / 3.0 },
+                FeeTier { up_to: Some(600_000.0), percentage: 0.25 },
+                FeeTier { up_to: None, percentage: 0.15 },
+            ],
+            sliding_scale_case_types: vec![CaseType::MedicalMalpractice],
+        };
+
+        let breakdown = compute_net_to_client(700_000.0, &CaseType::MedicalMalpractice, &rules, Costs::default(), &[]);
+
+        // 20,000 + 16,666.67 + 125,000 + 15,000
+        assert_eq!(breakdown.fee_tiers_applied.len(), 4);
+        assert!((breakdown.attorney_fee - 176_666.67).abs() < 0.01);
+        assert!((breakdown.net_to_client - 523_333.33).abs() < 0.01);
+
+        // Other cases pay the flat cap
+        let breakdown = compute_net_to_client(700_000.0, &CaseType::PersonalInjury, &rules, Costs::default(), &[]);
+        assert_eq!(breakdown.fee_tiers_applied.len(), 1);
+        assert!((breakdown.attorney_fee - 280_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_prejudgment_interest_simple_eighteen_months() {
+        use chrono::TimeZone;
+
+        let from = Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 7, 15, 0, 0, 0).unwrap();
+
+        let interest = compute_prejudgment_interest(100_000.0, 0.06, from, to);
+        assert!((interest - 9_000.0).abs() < 0.01);
+
+        let compound = compute_interest(100_000.0, 0.06, from, to, &InterestMethod::CompoundedAnnually);
+        assert!(compound > interest);
+        assert_eq!(compute_prejudgment_interest(100_000.0, 0.06, to, from), 0.0);
+    }
+
+    #[test]
+    fn test_medical_timeline_er_surgery_pt() {
+        use chrono::TimeZone;
+
+        let expense = |y, m, d, category, amount, is_future| MedicalExpense {
+            date: Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap(),
+            provider: "Provider".to_string(),
+            description: format!("{:?}", category),
+            amount,
+            category,
+            is_future,
+        };
+
+        // Deliberately out of order
+        let expenses = vec![
+            expense(2023, 4, 1, MedicalCategory::PhysicalTherapy, 2_000.0, false),
+            expense(2023, 1, 10, MedicalCategory::Emergency, 5_000.0, false),
+            expense(2023, 1, 20, MedicalCategory::Surgery, 40_000.0, false),
+            expense(2030, 1, 1, MedicalCategory::PhysicalTherapy, 3_000.0, true),
+        ];
+
+        let timeline = build_medical_timeline(&expenses, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+
+        let types: Vec<TreatmentEventType> = timeline.events.iter().map(|e| e.event_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TreatmentEventType::InitialEmergency,
+                TreatmentEventType::Surgery,
+                TreatmentEventType::PhysicalTherapy,
+            ]
+        );
+        assert!(timeline.events[0].was_emergency);
+        assert_eq!(timeline.total_treatment_days, 81);
+        assert!(timeline.ongoing_treatment);
+
+        let plan = timeline.future_treatment_plan.unwrap();
+        assert_eq!(plan.total_estimated_cost, 3_000.0);
+        assert_eq!(plan.ongoing_therapy_years, 1);
+    }
+
+    #[test]
+    fn test_medical_timeline_completed_treatment_not_ongoing() {
+        use chrono::TimeZone;
+
+        let expenses = vec![MedicalExpense {
+            date: Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap(),
+            provider: "ER".to_string(),
+            description: "Emergency visit".to_string(),
+            amount: 1_500.0,
+            category: MedicalCategory::Emergency,
+            is_future: false,
+        }];
+
+        let timeline = build_medical_timeline(&expenses, Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(timeline.total_treatment_days, 0);
+        assert!(!timeline.ongoing_treatment);
+        assert!(timeline.future_treatment_plan.is_none());
+
+        // Two weeks after the visit the same treatment is still ongoing
+        let timeline = build_medical_timeline(&expenses, Utc.with_ymd_and_hms(2023, 1, 24, 0, 0, 0).unwrap());
+        assert!(timeline.ongoing_treatment);
+    }
+
+    #[tokio::test]
+    async fn test_fee_agreement_over_the_pennsylvania_cap_rejected() {
+        let service = service_with_matter().await;
+        let rules = service.load_jurisdiction_rules("PA").await.unwrap().attorney_fee_rules;
+
+        assert!(validate_fee_agreement(0.40, None, &rules).is_err());
+        assert_eq!(
+            validate_fee_agreement(1.0 / 3.0, None, &rules).unwrap(),
+            FeeAgreementStatus::Permitted
+        );
+        assert_eq!(validate_fee_agreement(0.3333, None, &rules).unwrap(), FeeAgreementStatus::Permitted);
+    }
+
+    #[test]
+    fn test_fee_agreement_written_to_four_places_meets_an_exact_cap() {
+        let rules = flat_fee_rules(0.3333);
+
+        assert!(validate_fee_agreement(1.0 / 3.0, None, &rules).is_ok());
+        assert!(validate_fee_agreement(0.3334, None, &rules).is_err());
+    }
+
+    #[test]
+    fn test_fee_agreement_court_approval_flagged() {
+        let mut rules = flat_fee_rules(0.40);
+        rules.court_approval_required = true;
+
+        assert_eq!(
+            validate_fee_agreement(0.30, None, &rules).unwrap(),
+            FeeAgreementStatus::RequiresCourtApproval
+        );
+    }
+
+    #[test]
+    fn test_medical_malpractice_sliding_scale_only_binds_med_mal() {
+        let mut rules = flat_fee_rules(0.40);
+        rules.sliding_scale_required = true;
+        rules.sliding_scale_tiers = vec![
+            FeeTier { up_to: Some(250_000.0), percentage: 0.30 },
+            FeeTier { up_to: None, percentage: 0.10 },
+        ];
+        rules.sliding_scale_case_types = vec![CaseType::MedicalMalpractice];
+
+        assert!(validate_fee_agreement(1.0 / 3.0, Some(&CaseType::MedicalMalpractice), &rules).is_err());
+        assert_eq!(
+            validate_fee_agreement(1.0 / 3.0, Some(&CaseType::PersonalInjury), &rules).unwrap(),
+            FeeAgreementStatus::Permitted
+        );
+        assert!(validate_fee_agreement(1.0 / 3.0, None, &rules).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_known_county_returns_venue_statistics() {
+        let service = service_with_matter().await;
+
+        let venue = service.get_venue_statistics("Philadelphia County").await.unwrap().unwrap();
+        assert_eq!(venue.county, "Philadelphia");
+        assert_eq!(venue.plaintiff_win_rate, 0.64);
+        assert_eq!(venue.tort_reform_climate, TortReformClimate::ProPlaintiff);
+
+        assert!(service.get_venue_statistics("Nowhere").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_judge_returns_none() {
+        let service = service_with_matter().await;
+        assert!(service.get_judge_history("Hon. Nobody").await.unwrap().is_none());
+
+        sqlx::raw_sql(
+            "UPDATE matters SET county = 'Allegheny', judge_name = 'Hon. Nobody' WHERE id = 'm1'",
+        )
+        .execute(&service.db)
+        .await
+        .unwrap();
+        let service = service.with_predictor(Arc::new(FixedPredictor { value: 240_000.0, confidence: 0.5 }));
+        let calc = sample_calculation();
+        let (analysis, _) = service
+            .ai_estimate("m1", &CaseType::PersonalInjury, 300_000.0, &calc.liability_analysis, "PA", &calc.settlement_range)
+            .await
+            .unwrap();
+
+        assert_eq!(analysis.venue_statistics.unwrap().plaintiff_win_rate, 0.57);
+        assert!(analysis.judge_history.is_none());
+    }
+
+    fn economic_with_medical(past_medical: f64) -> EconomicDamages {
+        let mut economic = sample_calculation().economic_damages;
+        let other = economic.total_past_economic - economic.past_medical_expenses;
+        economic.past_medical_expenses = past_medical;
+        economic.total_past_economic = past_medical + other;
+        economic.total_economic = economic.total_past_economic + economic.present_value_future_damages;
+        economic
+    }
+
+    #[test]
+    fn test_collateral_source_mandatory_reduction() {
+        let mut economic = economic_with_medical(40_000.0);
+        let total = economic.total_economic;
+
+        let deducted = apply_collateral_source(&mut economic, 15_000.0, &CollateralSourceRule::ReduceMandatory);
+        assert_eq!(deducted, 15_000.0);
+        assert_eq!(economic.past_medical_expenses, 25_000.0);
+        assert_eq!(economic.total_economic, total - 15_000.0);
+
+        // Payments beyond the medical bills do not reduce other losses
+        let deducted = apply_collateral_source(&mut economic, 100_000.0, &CollateralSourceRule::ReduceMandatory);
+        assert_eq!(deducted, 25_000.0);
+        assert_eq!(economic.past_medical_expenses, 0.0);
+        assert_eq!(economic.total_economic, total - 40_000.0);
+    }
+
+    #[test]
+    fn test_collateral_source_excluded_and_admitted_leave_damages_intact() {
+        for rule in [CollateralSourceRule::Excluded, CollateralSourceRule::Admitted] {
+            let mut economic = economic_with_medical(40_000.0);
+            let total = economic.total_economic;
+
+            assert_eq!(apply_collateral_source(&mut economic, 15_000.0, &rule), 0.0);
+            assert_eq!(economic.past_medical_expenses, 40_000.0);
+            assert_eq!(economic.total_economic, total);
+        }
+    }
+
+    #[test]
+    fn test_two_defendants_several_for_non_economic() {
+        let rule = JointSeveralLiability {
+            applies: true,
+            economic_only: true,
+            threshold_percentage: Some(60.0),
+        };
+        let defendants = [
+            DefendantShare { name: "Acme Trucking".to_string(), fault_percentage: 70.0 },
+            DefendantShare { name: "Road Contractor".to_string(), fault_percentage: 30.0 },
+        ];
+
+        let shares = apportion_liability(100_000.0, 200_000.0, &defendants, &rule);
+        let (major, minor) = (&shares[0], &shares[1]);
+
+        assert!((major.several_economic - 70_000.0).abs() < 0.01);
+        assert!((minor.several_non_economic - 60_000.0).abs() < 0.01);
+
+        // Above the threshold: the whole economic award, but only its own non-economic share
+        assert!(major.jointly_liable_economic && !major.jointly_liable_non_economic);
+        assert_eq!(major.max_economic_exposure, 100_000.0);
+        assert!((major.max_non_economic_exposure - 140_000.0).abs() < 0.01);
+
+        // Below the threshold: several-only for everything
+        assert!(!minor.jointly_liable_economic);
+        assert!((minor.max_economic_exposure - 30_000.0).abs() < 0.01);
+        assert!((minor.max_non_economic_exposure - 60_000.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_calculation_apportions_among_every_defendant() {
+        let service = service_with_matter().await;
+        let damages = service.calculate_total_economic_damages(sample_calculation().economic_damages).unwrap();
+        let mut inputs = SettlementInputs {
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Roe".to_string(),
+            defendant_name: "Acme Trucking".to_string(),
+            economic_damages: damages.clone(),
+            injury_details: None,
+            liability_percentage: 90.0,
+            jurisdiction: "PA".to_string(),
+            risk_profile: ClientRiskProfile::Neutral,
+            collateral_source_payments: 0.0,
+            defendants: vec![
+                DefendantShare { name: "Acme Trucking".to_string(), fault_percentage: 63.0 },
+                DefendantShare { name: "Road Contractor".to_string(), fault_percentage: 27.0 },
+            ],
+        };
+
+        let calc = service.compute_settlement("m1", inputs.clone(), "jdoe").await.unwrap();
+        let shares = &calc.defendant_apportionment;
+        assert_eq!(shares.len(), 2);
+        let economic = damages.total_economic * 0.9;
+        assert!((shares[0].several_economic - economic * 0.7).abs() < 0.01);
+        assert!((shares[1].several_economic - economic * 0.3).abs() < 0.01);
+        // Pennsylvania's 60% threshold: only the major defendant is jointly liable
+        assert!(shares[0].jointly_liable_economic);
+        assert!(!shares[1].jointly_liable_economic);
+
+        // Shares that do not add up to the defendants' liability are refused
+        inputs.defendants[1].fault_percentage = 20.0;
+        assert!(service.compute_settlement("m1", inputs, "jdoe").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calculation_is_stamped_on_the_service_clock() {
+        use crate::utils::clock::MockClock;
+        use chrono::TimeZone;
+
+        let today = Utc.with_ymd_and_hms(2024, 5, 1, 15, 30, 0).unwrap();
+        let service = service_with_matter().await.with_clock(Arc::new(MockClock::new(today)));
+        let damages = service.calculate_total_economic_damages(sample_calculation().economic_damages).unwrap();
+        let inputs = SettlementInputs {
+            case_type: CaseType::PersonalInjury,
+            plaintiff_name: "Jane Roe".to_string(),
+            defendant_name: "Acme Trucking".to_string(),
+            economic_damages: damages,
+            injury_details: None,
+            liability_percentage: 100.0,
+            jurisdiction: "PA".to_string(),
+            risk_profile: ClientRiskProfile::Neutral,
+            collateral_source_payments: 0.0,
+            defendants: Vec::new(),
+        };
+
+        let calc = service.compute_settlement("m1", inputs, "jdoe").await.unwrap();
+
+        assert_eq!(calc.calculated_at, today);
+        assert_eq!(calc.last_updated, today);
+    }
+
+    #[tokio::test]
+    async fn test_attached_exhibits_lettered_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service_with_matter().await.with_exhibit_store(dir.path().join("exhibits"));
+        let calc = sample_calculation();
+        service.save_settlement_calculation(&calc).await.unwrap();
+        let letter = service
+            .generate_demand_letter(&calc, "Claims Adjuster", "1 Insurance Way", "Rear-end collision.", 30, "jdoe")
+            .await
+            .unwrap();
+
+        let mut letters = Vec::new();
+        for (name, description) in [("er.pdf", "ER records"), ("pt.pdf", "Physical therapy bills"), ("wages.pdf", "Employer wage letter")] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, description).unwrap();
+            let exhibit = service.attach_exhibit(&letter.id, &path, description).await.unwrap();
+            assert!(std::path::Path::new(&exhibit.file_path).exists());
+            letters.push(exhibit.exhibit_letter);
+        }
+        assert_eq!(letters, ["A", "B", "C"]);
+
+        let saved = service.get_demand_letter(&letter.id).await.unwrap().unwrap();
+        assert_eq!(saved.exhibits.len(), 3);
+        assert!(saved.exhibits.iter().all(|e| e.content_hash.is_some()));
+        assert!(saved.letter_html.contains("Exhibit A: ER records"));
+        assert!(saved.letter_html.contains("Exhibit B: Physical therapy bills"));
+        assert!(saved.letter_html.contains("Exhibit C: Employer wage letter"));
+
+        assert_eq!(exhibit_letter(25), "Z");
+        assert_eq!(exhibit_letter(26), "AA");
+        assert_eq!(exhibit_index("AA"), Some(26));
+    }
+
+    #[tokio::test]
+    async fn test_custom_demand_deadline_honored() {
+        use crate::utils::clock::MockClock;
+        use chrono::TimeZone;
+
+        let today = Utc.with_ymd_and_hms(2024, 5, 1, 15, 30, 0).unwrap();
+        let service = service_with_matter().await.with_clock(Arc::new(MockClock::new(today)));
+        let calc = sample_calculation();
+        service.save_settlement_calculation(&calc).await.unwrap();
+
+        let letter = service
+            .generate_demand_letter(&calc, "Claims Adjuster", "1 Insurance Way", "Rear-end collision.", 14, "jdoe")
+            .await
+            .unwrap();
+        assert_eq!(letter.deadline, today + Duration::days(14));
+        assert!(letter.closing_paragraph.contains("This offer expires on May 15, 2024."));
+        assert!(letter.letter_html.contains("May 01, 2024"));
+
+        let saved = service.get_demand_letter(&letter.id).await.unwrap().unwrap();
+        assert_eq!(saved.deadline, letter.deadline);
+        assert_eq!(saved.status, DemandStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_expired_demand_triggers_follow_up() {
+        let service = service_with_matter().await;
+        let calc = sample_calculation();
+        service.save_settlement_calculation(&calc).await.unwrap();
+        let expiring = service
+            .generate_demand_letter(&calc, "Claims Adjuster", "1 Insurance Way", "Rear-end collision.", 14, "jdoe")
+            .await
+            .unwrap();
+        let answered = service
+            .generate_demand_letter(&calc, "Claims Adjuster", "1 Insurance Way", "Rear-end collision.", 14, "jdoe")
+            .await
+            .unwrap();
+        service.mark_demand_answered(&answered.id, Utc::now()).await.unwrap();
+
+        // Nothing is due before the deadline
+        assert!(service.process_expired_demands(Utc::now(), true).await.unwrap().is_empty());
+
+        let later = Utc::now() + Duration::days(15);
+        let expired = service.process_expired_demands(later, true).await.unwrap();
+        assert_eq!(expired, vec![expiring.id.clone()]);
+
+        let letter = service.get_demand_letter(&expiring.id).await.unwrap().unwrap();
+        assert_eq!(letter.status, DemandStatus::FollowUp);
+        let answered = service.get_demand_letter(&answered.id).await.unwrap().unwrap();
+        assert_eq!(answered.status, DemandStatus::Answered);
+
+        let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE matter_id = 'm1' AND category = 'litigation'")
+            .fetch_one(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(tasks, 1);
+
+        // A demand is only flagged once
+        assert!(service.process_expired_demands(later, true).await.unwrap().is_empty());
+    }
 }
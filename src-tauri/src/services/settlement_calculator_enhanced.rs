@@ -54,12 +54,16 @@ impl SettlementCalculatorService {
             punitive_damages_cap: None,
             prejudgment_interest: true,
             prejudgment_interest_rate: Some(0.06),
+            prejudgment_interest_method: InterestMethod::Simple,
+            postjudgment_interest_rate: Some(0.06),
             structured_settlement_allowed: true,
             attorney_fee_rules: AttorneyFeeRules {
-                contingency_fee_max: Some(0.3333),
+                contingency_fee_max: Some(1.0 / 3.0),
                 sliding_scale_required: false,
                 court_approval_required: false,
                 costs_advance_rules: "Attorney may advance costs".to_string(),
+                sliding_scale_tiers: Vec::new(),
+                sliding_scale_case_types: Vec::new(),
             },
             expert_witness_limits: None,
             mediation_required: false,
@@ -99,12 +103,22 @@ impl SettlementCalculatorService {
             punitive_damages_cap: None,
             prejudgment_interest: true,
             prejudgment_interest_rate: Some(0.09),
+            prejudgment_interest_method: InterestMethod::Simple,
+            postjudgment_interest_rate: Some(0.09),
             structured_settlement_allowed: true,
             attorney_fee_rules: AttorneyFeeRules {
-                contingency_fee_max: Some(0.3333),
+                contingency_fee_max: Some(1.0 / 3.0),
                 sliding_scale_required: true,
                 court_approval_required: true,
                 costs_advance_rules: "Attorney may advance reasonable costs".to_string(),
+                sliding_scale_tiers: vec![
+                    FeeTier { up_to: Some(250_000.0), percentage: 0.30 },
+                    FeeTier { up_to: Some(500_000.0), percentage: 0.25 },
+                    FeeTier { up_to: Some(1_000_000.0), percentage: 0.20 },
+                    FeeTier { up_to: Some(1_250_000.0), percentage: 0.15 },
+                    FeeTier { up_to: None, percentage: 0.10 },
+                ],
+                sliding_scale_case_types: vec![CaseType::MedicalMalpractice],
             },
             expert_witness_limits: Some(3),
             mediation_required: false,
@@ -144,12 +158,21 @@ impl SettlementCalculatorService {
             punitive_damages_cap: None,
             prejudgment_interest: true,
             prejudgment_interest_rate: Some(0.10),
+            prejudgment_interest_method: InterestMethod::Simple,
+            postjudgment_interest_rate: Some(0.10),
             structured_settlement_allowed: true,
             attorney_fee_rules: AttorneyFeeRules {
                 contingency_fee_max: Some(0.40),
                 sliding_scale_required: true,
                 court_approval_required: true,
                 costs_advance_rules: "Attorney may advance costs".to_string(),
+                sliding_scale_tiers: vec![
+                    FeeTier { up_to: Some(50_000.0), percentage: 0.40 },
+                    FeeTier { up_to: Some(100_000.0), percentage: 1.0 / 3.0 },
+                    FeeTier { up_to: Some(600_000.0), percentage: 0.25 },
+                    FeeTier { up_to: None, percentage: 0.15 },
+                ],
+                sliding_scale_case_types: vec![CaseType::MedicalMalpractice],
             },
             expert_witness_limits: None,
             mediation_required: false,
@@ -193,12 +216,16 @@ impl SettlementCalculatorService {
             }),
             prejudgment_interest: true,
             prejudgment_interest_rate: Some(0.05),
+            prejudgment_interest_method: InterestMethod::Simple,
+            postjudgment_interest_rate: Some(0.05),
             structured_settlement_allowed: true,
             attorney_fee_rules: AttorneyFeeRules {
                 contingency_fee_max: Some(0.40),
                 sliding_scale_required: false,
                 court_approval_required: false,
                 costs_advance_rules: "Attorney may advance costs".to_string(),
+                sliding_scale_tiers: Vec::new(),
+                sliding_scale_case_types: Vec::new(),
             },
             expert_witness_limits: None,
             mediation_required: false,
@@ -242,12 +269,16 @@ impl SettlementCalculatorService {
             }),
             prejudgment_interest: true,
             prejudgment_interest_rate: Some(0.04),
+            prejudgment_interest_method: InterestMethod::Simple,
+            postjudgment_interest_rate: Some(0.04),
             structured_settlement_allowed: true,
             attorney_fee_rules: AttorneyFeeRules {
                 contingency_fee_max: Some(0.40),
                 sliding_scale_required: false,
                 court_approval_required: false,
                 costs_advance_rules: "Attorney may advance costs".to_string(),
+                sliding_scale_tiers: Vec::new(),
+                sliding_scale_case_types: Vec::new(),
             },
             expert_witness_limits: None,
             mediation_required: true,
@@ -378,10 +409,9 @@ impl SettlementCalculatorService {
         let base_prediction = damages * 0.78;
         let confidence = 0.82;
 
-        let judge_history = if let Some(judge) = judge_name {
-            Some(self.get_judge_history(judge).await?)
-        } else {
-            None
+        let judge_history = match judge_name {
+            Some(judge) => self.get_judge_history(judge).await?,
+            None => None,
         };
 
         let counsel_history = if let Some(counsel) = opposing_counsel {
@@ -396,7 +426,7 @@ impl SettlementCalculatorService {
             None
         };
 
-        let venue_stats = Some(self.get_venue_statistics(jurisdiction).await?);
+        let venue_stats = self.get_venue_statistics(jurisdiction).await?;
 
         Ok(AISettlementAnalysis {
             predicted_settlement_value: base_prediction,
@@ -411,17 +441,6 @@ impl SettlementCalculatorService {
         })
     }
 
-    async fn get_judge_history(&self, judge_name: &str) -> Result<JudgeHistory> {
-        Ok(JudgeHistory {
-            judge_name: judge_name.to_string(),
-            average_plaintiff_verdict: 425_000.0,
-            plaintiff_win_rate: 0.62,
-            median_verdict_ratio: 0.85,
-            trials_presided: 147,
-            settlement_encouragement: SettlementTendency::Encourages,
-        })
-    }
-
     async fn get_counsel_history(&self, attorney_name: &str) -> Result<CounselHistory> {
         Ok(CounselHistory {
             firm_name: "Hypothetical Defense Firm LLP".to_string(),
@@ -444,23 +463,6 @@ impl SettlementCalculatorService {
         })
     }
 
-    async fn get_venue_statistics(&self, jurisdiction: &str) -> Result<VenueStatistics> {
-        Ok(VenueStatistics {
-            county: jurisdiction.to_string(),
-            average_plaintiff_verdict: 387_000.0,
-            plaintiff_win_rate: 0.58,
-            median_time_to_trial: 24,
-            jury_pool_demographics: DemographicProfile {
-                median_age: 45.5,
-                median_income: 67_500.0,
-                education_level: "Some college".to_string(),
-                urban_rural: UrbanRural::Suburban,
-            },
-            political_lean: PoliticalLean::Moderate,
-            tort_reform_climate: TortReformClimate::Balanced,
-        })
-    }
-
     // ============= MEDICAL TREATMENT ANALYSIS =============
 
     /// Analyze medical treatment timeline and costs
@@ -500,33 +502,120 @@ impl SettlementCalculatorService {
         terms: Vec<SettlementTerm>,
         conditions: Vec<String>,
     ) -> Result<SettlementOffer> {
+        // The write lock is held from the read to the save, so an offer
+        // cannot be lost to a concurrent offer or recalculation
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let mut calc = load_calculation(&mut *tx, calc_id)
+            .await?
+            .with_context(|| format!("Settlement calculation {} not found", calc_id))?;
+
+        // Offers belong to the current revision; recording on an older one
+        // would fork the negotiation history
+        if let Some(next_id) = superseding_calculation(&mut *tx, calc_id).await? {
+            anyhow::bail!(
+                "Settlement calculation {} was recalculated as {}; record the offer on the latest revision",
+                calc_id,
+                next_id
+            );
+        }
+        let now = Utc::now();
+
         let offer = SettlementOffer {
             id: Uuid::new_v4().to_string(),
-            matter_id: calc_id.to_string(),
+            matter_id: calc.matter_id.clone(),
             settlement_calculation_id: calc_id.to_string(),
             offer_from: offer_from.to_string(),
             offer_amount,
-            offer_date: Utc::now(),
-            expiration_date: Some(Utc::now() + Duration::days(30)),
+            offer_date: now,
+            expiration_date: Some(now + Duration::days(30)),
             terms,
             conditions,
             status: OfferStatus::Pending,
             response: None,
             response_date: None,
-            analysis: OfferAnalysis {
-                percentage_of_demand: 0.0,
-                percentage_of_calculated_value: 0.0,
-                comparison_to_verdict_range: String::new(),
-                net_recovery_after_costs: 0.0,
-                time_value_analysis: String::new(),
-            },
+            analysis: self.analyze_offer(&calc, offer_amount).await?,
             recommendation: OfferRecommendation::NeedsClientInput,
         };
 
-        self.save_offer(&offer).await?;
+        // Offers are stored with the calculation they were weighed against
+        calc.offers_received.push(offer.clone());
+        calc.last_updated = now;
+        save_calculation(&mut *tx, &calc).await?;
+        tx.commit().await?;
+
         Ok(offer)
     }
 
+    /// Every offer on a calculation side by side, oldest first, measured
+    /// against the calculation's demand, value and floor, with the trend
+    /// across rounds
+    pub async fn build_offer_comparison(&self, calc_id: &str) -> Result<OfferComparison> {
+        let calc = self
+            .get_settlement_calculation(calc_id)
+            .await?
+            .with_context(|| format!("Settlement calculation {} not found", calc_id))?;
+
+        let fee_rules = match &calc.jurisdiction_rules {
+            Some(rules) => rules.attorney_fee_rules.clone(),
+            None => self.load_jurisdiction_rules(&calc.liability_analysis.jurisdiction).await?.attorney_fee_rules,
+        };
+        let costs = Costs {
+            litigation_costs_to_date: calc.litigation_costs_to_date,
+            projected_additional_costs: calc.projected_additional_costs,
+        };
+
+        let mut offers = calc.offers_received.clone();
+        offers.sort_by_key(|offer| offer.offer_date);
+
+        let mut rows: Vec<OfferComparisonRow> = Vec::with_capacity(offers.len());
+        for (i, offer) in offers.into_iter().enumerate() {
+            let amount = offer.offer_amount;
+            let change_from_previous = rows.last().map(|previous| amount - previous.amount);
+            rows.push(OfferComparisonRow {
+                offer_id: offer.id,
+                round: i as u32 + 1,
+                offer_from: offer.offer_from,
+                offer_date: offer.offer_date,
+                amount,
+                percentage_of_demand: percentage_of(amount, calc.recommended_demand),
+                percentage_of_calculated_value: percentage_of(amount, calc.total_damages),
+                net_to_client: compute_net_to_client(amount, &calc.case_type, &fee_rules, costs.clone(), &[]).net_to_client,
+                change_from_previous,
+                status: offer.status,
+                recommendation: recommend_offer(&calc, amount),
+            });
+        }
+
+        let trend = match (rows.first(), rows.last()) {
+            (Some(first), Some(last)) if rows.len() > 1 => {
+                let change = last.amount - first.amount;
+                Some(OfferTrend {
+                    direction: if change > 0.0 {
+                        TrendDirection::Rising
+                    } else if change < 0.0 {
+                        TrendDirection::Falling
+                    } else {
+                        TrendDirection::Flat
+                    },
+                    change_since_first: change,
+                    average_change_per_round: change / (rows.len() - 1) as f64,
+                    remaining_gap_to_target: (calc.target_settlement - last.amount).max(0.0),
+                })
+            }
+            _ => None,
+        };
+
+        Ok(OfferComparison {
+            calculation_id: calc.id,
+            recommended_demand: calc.recommended_demand,
+            calculated_value: calc.total_damages,
+            target_settlement: calc.target_settlement,
+            minimum_settlement: calc.minimum_settlement,
+            offers: rows,
+            trend,
+        })
+    }
+
     /// Generate counter-offer recommendation
     pub async fn generate_counteroffer(
         &self,
@@ -594,9 +683,20 @@ impl SettlementCalculatorService {
 
         (attorney_fees, costs_advanced, net_to_client)
     }
+}
+
+fn percentage_of(amount: f64, of: f64) -> f64 {
+    if of > 0.0 { amount / of * 100.0 } else { 0.0 }
+}
 
-    async fn save_offer(&self, offer: &SettlementOffer) -> Result<()> {
-        // TODO: Implement database save
-        Ok(())
+/// Accept at or above target, counter between the floor and target, and
+/// reject below the floor
+fn recommend_offer(calc: &SettlementCalculation, amount: f64) -> OfferRecommendation {
+    if amount >= calc.target_settlement {
+        OfferRecommendation::Accept
+    } else if amount >= calc.minimum_settlement {
+        OfferRecommendation::Counter
+    } else {
+        OfferRecommendation::Reject
     }
 }
@@ -0,0 +1,119 @@
+// Graceful shutdown for PA eDocket Desktop
+// Stops background work and persists in-flight state before the app exits
+
+use crate::services::time_tracking::pause_running_timers;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub timers_paused: usize,
+    pub tasks_cancelled: bool,
+    pub database_flushed: bool,
+}
+
+/// Owns the cancellation token shared by background tasks (watchlist polls,
+/// task runner). Tasks take a child token and stop when it is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownCoordinator {
+    token: CancellationToken,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn child_token(&self) -> CancellationToken {
+        self.token.child_token()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Cancel background tasks, pause running timers and flush the database.
+    /// Each step runs even if an earlier one fails so one error cannot leave
+    /// the rest of the state unsaved.
+    pub async fn on_shutdown(&self, db: &SqlitePool) -> ShutdownReport {
+        info!("Shutting down: cancelling background tasks");
+        self.token.cancel();
+
+        let mut report = ShutdownReport {
+            tasks_cancelled: true,
+            ..Default::default()
+        };
+
+        match pause_running_timers(db, Utc::now()).await {
+            Ok(count) => {
+                info!("Paused {} running timer(s)", count);
+                report.timers_paused = count;
+            }
+            Err(e) => warn!("Failed to pause running timers: {}", e),
+        }
+
+        match flush_database(db).await {
+            Ok(()) => report.database_flushed = true,
+            Err(e) => warn!("Failed to flush database: {}", e),
+        }
+
+        report
+    }
+}
+
+/// Checkpoint the write-ahead log so buffered writes land in the main file
+async fn flush_database(db: &SqlitePool) -> Result<()> {
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(db)
+        .await
+        .context("Failed to checkpoint database")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use chrono::{Duration, Utc};
+    use sqlx::Row;
+
+    async fn timers_db() -> SqlitePool {
+        let db = test_database().await;
+        db
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_persists_running_timer_elapsed_minutes() {
+        let db = timers_db().await;
+        let started_at = Utc::now() - Duration::minutes(95);
+
+        sqlx::query(
+            "INSERT INTO timers (id, time_entry_id, matter_id, attorney_id, started_at,
+                                 total_pause_duration_minutes, is_running)
+             VALUES ('t1', 'e1', 'm1', 'a1', ?, 5, 1)",
+        )
+        .bind(started_at)
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let coordinator = ShutdownCoordinator::new();
+        let background = coordinator.child_token();
+        let report = coordinator.on_shutdown(&db).await;
+
+        assert_eq!(report.timers_paused, 1);
+        assert!(background.is_cancelled());
+
+        let row = sqlx::query("SELECT is_running, elapsed_minutes, paused_at FROM timers WHERE id = 't1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert!(!row.get::<bool, _>("is_running"));
+        assert_eq!(row.get::<i64, _>("elapsed_minutes"), 90);
+        assert!(row.get::<Option<chrono::DateTime<Utc>>, _>("paused_at").is_some());
+    }
+}
@@ -0,0 +1,313 @@
+// System health checks for PA eDocket Desktop
+// Probes the local database, configured providers, configuration and disk
+
+use crate::config::{AppConfig, ConfigManager};
+use crate::utils::file_utils::expand_home;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Timeout applied to each provider reachability probe
+pub const PROVIDER_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Free space below which the data directory is reported as degraded
+pub const LOW_DISK_THRESHOLD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Free space below which the data directory is reported as down
+pub const CRITICAL_DISK_THRESHOLD_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub checked_at: DateTime<Utc>,
+    pub components: Vec<ComponentHealth>,
+}
+
+impl HealthReport {
+    pub fn component(&self, name: &str) -> Option<&ComponentHealth> {
+        self.components.iter().find(|c| c.name == name)
+    }
+}
+
+/// Reachability check for an external provider endpoint
+#[async_trait]
+pub trait ProviderProbe: Send + Sync {
+    async fn probe(&self, base_url: &str) -> Result<(), String>;
+}
+
+/// Probes providers with an HTTP HEAD request; any response counts as reachable
+pub struct HttpProviderProbe {
+    client: reqwest::Client,
+}
+
+impl HttpProviderProbe {
+    pub fn new(timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ProviderProbe for HttpProviderProbe {
+    async fn probe(&self, base_url: &str) -> Result<(), String> {
+        let response = self.client
+            .head(base_url)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status().is_server_error() {
+            return Err(format!("Server error: {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Run every health check using the configuration stored in `config_dir`
+pub async fn system_health(db: &SqlitePool, config_dir: &Path) -> HealthReport {
+    let mut manager = ConfigManager::new(config_dir.to_path_buf());
+    let config = manager.load_config().await.map(|c| c.clone());
+    let probe = HttpProviderProbe::new(PROVIDER_PROBE_TIMEOUT);
+
+    check_system_health(db, config, &probe).await
+}
+
+/// Run every health check against an already-loaded configuration
+pub async fn check_system_health(
+    db: &SqlitePool,
+    config: Result<AppConfig>,
+    probe: &dyn ProviderProbe,
+) -> HealthReport {
+    info!("Running system health checks");
+
+    let mut components = vec![check_database(db).await];
+
+    match config {
+        Ok(config) => {
+            components.push(ComponentHealth {
+                name: "config".to_string(),
+                status: HealthStatus::Healthy,
+                latency_ms: 0,
+                message: None,
+            });
+
+            let mut providers: Vec<_> = config.providers.providers.iter()
+                .filter(|(_, provider)| provider.enabled)
+                .collect();
+            providers.sort_by(|a, b| a.0.cmp(b.0));
+
+            for (id, provider) in providers {
+                components.push(check_provider(id, &provider.base_url, probe).await);
+            }
+
+            components.push(check_disk(&expand_home(&config.global.data_dir)));
+        }
+        Err(e) => {
+            warn!("Configuration failed to load: {}", e);
+            components.push(ComponentHealth {
+                name: "config".to_string(),
+                status: HealthStatus::Down,
+                latency_ms: 0,
+                message: Some(e.to_string()),
+            });
+        }
+    }
+
+    HealthReport {
+        status: overall_status(&components),
+        checked_at: Utc::now(),
+        components,
+    }
+}
+
+/// The database is critical; any other failing component only degrades the app
+fn overall_status(components: &[ComponentHealth]) -> HealthStatus {
+    let database_down = components.iter()
+        .any(|c| c.name == "database" && c.status == HealthStatus::Down);
+    if database_down {
+        return HealthStatus::Down;
+    }
+
+    if components.iter().any(|c| c.status != HealthStatus::Healthy) {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
+
+pub async fn check_database(db: &SqlitePool) -> ComponentHealth {
+    let started = Instant::now();
+    let result = sqlx::query("SELECT 1").execute(db).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (status, message) = match result {
+        Ok(_) => (HealthStatus::Healthy, None),
+        Err(e) => (HealthStatus::Down, Some(e.to_string())),
+    };
+
+    ComponentHealth {
+        name: "database".to_string(),
+        status,
+        latency_ms,
+        message,
+    }
+}
+
+async fn check_provider(id: &str, base_url: &str, probe: &dyn ProviderProbe) -> ComponentHealth {
+    let started = Instant::now();
+    let result = probe.probe(base_url).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (status, message) = match result {
+        Ok(()) => (HealthStatus::Healthy, None),
+        Err(e) => {
+            warn!("Provider {} unreachable: {}", id, e);
+            (HealthStatus::Down, Some(e))
+        }
+    };
+
+    ComponentHealth {
+        name: format!("provider:{}", id),
+        status,
+        latency_ms,
+        message,
+    }
+}
+
+fn check_disk(data_dir: &Path) -> ComponentHealth {
+    let started = Instant::now();
+    let result = fs2::available_space(data_dir);
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (status, message) = match result {
+        Ok(free) if free < CRITICAL_DISK_THRESHOLD_BYTES => {
+            (HealthStatus::Down, Some(format!("{} MB free", free / (1024 * 1024))))
+        }
+        Ok(free) if free < LOW_DISK_THRESHOLD_BYTES => {
+            (HealthStatus::Degraded, Some(format!("{} MB free", free / (1024 * 1024))))
+        }
+        Ok(free) => (HealthStatus::Healthy, Some(format!("{} MB free", free / (1024 * 1024)))),
+        Err(e) => (HealthStatus::Degraded, Some(format!("Unable to read free space: {}", e))),
+    };
+
+    ComponentHealth {
+        name: "disk".to_string(),
+        status,
+        latency_ms,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::*;
+    use std::collections::HashMap;
+
+    struct MockProbe {
+        down: Vec<String>,
+    }
+
+    #[async_trait]
+    impl ProviderProbe for MockProbe {
+        async fn probe(&self, base_url: &str) -> Result<(), String> {
+            if self.down.iter().any(|url| url == base_url) {
+                Err("connection refused".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn provider(name: &str, base_url: &str) -> ProviderConfig {
+        ProviderConfig {
+            name: name.to_string(),
+            enabled: true,
+            factory: None,
+            base_url: base_url.to_string(),
+            rate_limit: RateLimitConfig {
+                requests_per_minute: 60,
+                requests_per_hour: 1000,
+                burst_limit: 10,
+            },
+            retry: RetryConfig {
+                max_attempts: 3,
+                backoff_multiplier: 2.0,
+                initial_delay_ms: 100,
+                max_delay_ms: 1000,
+            },
+            endpoints: HashMap::new(),
+            headers: HashMap::new(),
+            auth: None,
+            cache: CacheConfig {
+                ttl_seconds: 60,
+                max_entries: 100,
+            },
+        }
+    }
+
+    fn test_config(data_dir: &Path) -> AppConfig {
+        let mut providers = ProvidersConfig::default();
+        providers.providers.insert("ujs".to_string(), provider("UJS Portal", "https://ujs.test"));
+        providers.providers.insert("recap".to_string(), provider("RECAP", "https://recap.test"));
+
+        let mut global = GlobalConfig::default();
+        global.data_dir = data_dir.to_string_lossy().to_string();
+
+        AppConfig {
+            courts: CourtsConfig::default(),
+            providers,
+            global,
+            security: SecurityConfig::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_down_provider_degrades_overall_status() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let probe = MockProbe { down: vec!["https://recap.test".to_string()] };
+
+        let report = check_system_health(&db, Ok(test_config(data_dir.path())), &probe).await;
+
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.component("database").unwrap().status, HealthStatus::Healthy);
+        assert_eq!(report.component("provider:recap").unwrap().status, HealthStatus::Down);
+        assert_eq!(report.component("provider:ujs").unwrap().status, HealthStatus::Healthy);
+        assert_eq!(report.component("config").unwrap().status, HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_config_failure_reported_as_down() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let probe = MockProbe { down: vec![] };
+
+        let report = check_system_health(&db, Err(anyhow::anyhow!("bad yaml")), &probe).await;
+
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.component("config").unwrap().status, HealthStatus::Down);
+    }
+}
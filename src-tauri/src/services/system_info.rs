@@ -0,0 +1,94 @@
+// System information for PA eDocket Desktop
+// Collects version, platform and storage details for support tickets
+
+use crate::config::GlobalConfig;
+use crate::utils::file_utils::{dir_size, expand_home};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::Path;
+use tracing::warn;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryInfo {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub app_name: String,
+    pub version: String,
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    pub config_dir: DirectoryInfo,
+    pub data_dir: DirectoryInfo,
+    pub cache_dir: DirectoryInfo,
+    pub log_dir: DirectoryInfo,
+    /// Latest applied migration, `None` if migrations have not run
+    pub schema_version: Option<i64>,
+}
+
+pub async fn system_info(db: &SqlitePool, config: &GlobalConfig, config_dir: &Path) -> SystemInfo {
+    let schema_version = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1",
+    )
+    .fetch_one(db)
+    .await
+    .unwrap_or_else(|e| {
+        warn!("Unable to read schema version: {}", e);
+        None
+    });
+
+    SystemInfo {
+        app_name: config.app_name.clone(),
+        version: config.version.clone(),
+        os: tauri_plugin_os::platform().to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: tauri_plugin_os::arch().to_string(),
+        config_dir: directory_info(config_dir),
+        data_dir: directory_info(&expand_home(&config.data_dir)),
+        cache_dir: directory_info(&expand_home(&config.cache_dir)),
+        log_dir: directory_info(&expand_home(&config.log_dir)),
+        schema_version,
+    }
+}
+
+fn directory_info(path: &Path) -> DirectoryInfo {
+    let size_bytes = dir_size(path).unwrap_or_else(|e| {
+        warn!("Unable to measure {:?}: {}", path, e);
+        0
+    });
+
+    DirectoryInfo {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_system_info_reports_version_and_paths() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("global.yaml"), "app_name: test").unwrap();
+
+        let mut config = GlobalConfig::default();
+        config.data_dir = dir.path().join("data").to_string_lossy().to_string();
+
+        let info = system_info(&db, &config, dir.path()).await;
+
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.os.is_empty());
+        assert!(!info.arch.is_empty());
+        assert_eq!(info.config_dir.path, dir.path().to_string_lossy());
+        assert_eq!(info.config_dir.size_bytes, 14);
+        assert!(!info.data_dir.path.is_empty());
+        assert!(!info.cache_dir.path.is_empty());
+        assert!(!info.log_dir.path.is_empty());
+        assert_eq!(info.schema_version, None);
+    }
+}
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock, Semaphore};
 use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
@@ -77,6 +78,7 @@ pub struct TaskRunner {
     task_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<Task>>>>,
     semaphore: Arc<Semaphore>,
     max_concurrent_tasks: usize,
+    shutdown: CancellationToken,
 }
 
 impl TaskRunner {
@@ -92,9 +94,16 @@ impl TaskRunner {
             task_receiver: Arc::new(RwLock::new(Some(task_receiver))),
             semaphore: Arc::new(Semaphore::new(max_concurrent_tasks)),
             max_concurrent_tasks,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Stop accepting queued tasks once `token` is cancelled
+    pub fn with_shutdown_token(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
     #[instrument(skip(self))]
     pub async fn start(&self) -> Result<()> {
         info!("Starting task runner with {} max concurrent tasks", self.max_concurrent_tasks);
@@ -106,9 +115,21 @@ impl TaskRunner {
         let running_tasks = self.running_tasks.clone();
         let completed_tasks = self.completed_tasks.clone();
         let semaphore = self.semaphore.clone();
+        let shutdown = self.shutdown.clone();
 
         tokio::spawn(async move {
-            while let Some(task) = receiver.recv().await {
+            loop {
+                let task = tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        info!("Task runner stopping for shutdown");
+                        break;
+                    }
+                    task = receiver.recv() => match task {
+                        Some(task) => task,
+                        None => break,
+                    },
+                };
+
                 let permit = semaphore.clone().acquire_owned().await.unwrap();
                 let database_clone = database.clone();
                 let running_tasks_clone = running_tasks.clone();
@@ -1,12 +1,22 @@
 // Time Tracking Service - Automatic time tracking and billing integration
 // Supports timer-based tracking, manual entry, automatic detection, and billing rate management
 
+use crate::config::BillingRatesConfig;
+use crate::services::activity_monitor::SuggestedTimeEntry;
+use crate::services::bulk_import_service::ImportSummary;
+use crate::utils::date::parse_date_flexible;
+use crate::services::database::Conflict;
+use crate::services::service_error::ServiceError;
+use crate::utils::clock::{system_clock, SharedClock};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments};
+use sqlx::{Row, SqliteExecutor, SqlitePool};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TimeEntryStatus {
@@ -91,6 +101,9 @@ pub struct TimeEntry {
     pub approved_by: Option<String>,
     pub billed_at: Option<DateTime<Utc>>,
     pub invoice_id: Option<String>,
+    /// Bumped on every save; a save against a stale version is rejected
+    #[serde(default)]
+    pub version: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,11 +236,115 @@ pub struct AutomaticTimeDetection {
     pub detect_research_activity: bool,
     pub min_activity_duration_minutes: i64,
     pub idle_timeout_minutes: i64,
+    /// Blocks on the same matter this close together are suggested as one;
+    /// the idle time between them is not counted
+    #[serde(default)]
+    pub merge_within_minutes: i64,
+    /// Suggested durations are rounded up to a multiple of this (6 for
+    /// tenths of an hour)
+    #[serde(default = "default_billing_increment_minutes")]
+    pub billing_increment_minutes: i64,
+}
+
+fn default_billing_increment_minutes() -> i64 {
+    1
+}
+
+impl Default for AutomaticTimeDetection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            detect_document_editing: true,
+            detect_email_activity: false,
+            detect_research_activity: true,
+            min_activity_duration_minutes: 6,
+            idle_timeout_minutes: 15,
+            merge_within_minutes: 0,
+            billing_increment_minutes: default_billing_increment_minutes(),
+        }
+    }
+}
+
+/// A new rate's effective range overlaps an existing rate for the same
+/// attorney and scope, so an entry could match either
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("Billing rate overlaps rate {existing_id} for the same attorney and scope")]
+pub struct OverlappingRate {
+    pub existing_id: String,
+}
+
+/// Attorney id under which firm-wide default rates are stored
+pub const FIRM_RATE_ATTORNEY: &str = "firm";
+
+/// Firm-wide default rates from configuration, effective from `effective_from`
+pub fn firm_default_rates(config: &BillingRatesConfig, effective_from: DateTime<Utc>) -> Result<Vec<BillingRate>> {
+    let rate = |activity_type, hourly_rate| BillingRate {
+        id: Uuid::new_v4().to_string(),
+        attorney_id: FIRM_RATE_ATTORNEY.to_string(),
+        activity_type,
+        matter_id: None,
+        client_id: None,
+        rate_type: RateType::Standard,
+        hourly_rate,
+        effective_from,
+        effective_to: None,
+        is_default: true,
+    };
+
+    let mut rates: Vec<BillingRate> = config.firm_hourly_rate.map(|r| rate(None, r)).into_iter().collect();
+    for (activity, hourly_rate) in &config.activity_rates {
+        let activity: ActivityType = serde_json::from_value(serde_json::json!(activity))
+            .with_context(|| format!("Unknown activity '{}' in billing rates", activity))?;
+        rates.push(rate(Some(activity), *hourly_rate));
+    }
+    Ok(rates)
+}
+
+async fn insert_billing_rate(conn: impl SqliteExecutor<'_>, rate: &BillingRate) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO billing_rates
+        (id, attorney_id, activity_type, matter_id, client_id, rate_type, hourly_rate,
+         effective_from, effective_to, is_default)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&rate.id)
+    .bind(&rate.attorney_id)
+    .bind(rate.activity_type.as_ref().map(|a| format!("{:?}", a)))
+    .bind(&rate.matter_id)
+    .bind(&rate.client_id)
+    .bind(format!("{:?}", rate.rate_type))
+    .bind(rate.hourly_rate)
+    .bind(rate.effective_from)
+    .bind(rate.effective_to)
+    .bind(rate.is_default)
+    .execute(conn)
+    .await
+    .context("Failed to save billing rate")?;
+    Ok(())
+}
+
+/// One row of a time entry export from another practice management system
+#[derive(Debug, Deserialize)]
+struct CsvTimeEntryRow {
+    date: String,
+    matter_id: String,
+    /// The other system's attorney identifier
+    attorney: String,
+    hours: f64,
+    #[serde(default)]
+    activity: Option<String>,
+    description: String,
+    /// "No", "false" or "0" for non-billable time
+    #[serde(default)]
+    billable: Option<String>,
 }
 
 pub struct TimeTrackingService {
     db: SqlitePool,
     active_timers: HashMap<String, Timer>, // attorney_id -> Timer
+    clock: SharedClock,
 }
 
 impl TimeTrackingService {
@@ -235,9 +352,16 @@ impl TimeTrackingService {
         Self {
             db,
             active_timers: HashMap::new(),
+            clock: system_clock(),
         }
     }
 
+    /// Time source for timers and entry timestamps
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     // ============= Timer Management =============
 
     /// Start a new timer for time tracking
@@ -251,20 +375,20 @@ impl TimeTrackingService {
         // Check if attorney already has a running timer
         if let Some(existing) = self.active_timers.get(attorney_id) {
             if existing.is_running {
-                return Err(anyhow::anyhow!(
+                return Err(ServiceError::conflict(format!(
                     "Attorney {} already has a running timer for matter {}",
-                    attorney_id,
-                    existing.matter_id
-                ));
+                    attorney_id, existing.matter_id
+                ))
+                .into());
             }
         }
 
         let timer_id = Uuid::new_v4().to_string();
         let entry_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+        let now = self.clock.now();
 
         // Create time entry
-        let time_entry = TimeEntry {
+        let mut time_entry = TimeEntry {
             id: entry_id.clone(),
             matter_id: matter_id.to_string(),
             attorney_id: attorney_id.to_string(),
@@ -279,7 +403,7 @@ impl TimeTrackingService {
             status: TimeEntryStatus::Running,
             entry_type: TimeEntryType::Timer,
             billable_status: BillableStatus::Billable,
-            hourly_rate: self.get_billing_rate(attorney_id, matter_id, &activity_type).await?,
+            hourly_rate: self.get_billing_rate(attorney_id, matter_id, &activity_type, now).await?,
             amount: None,
             discount_percent: None,
             discount_amount: None,
@@ -291,10 +415,11 @@ impl TimeTrackingService {
             approved_by: None,
             billed_at: None,
             invoice_id: None,
+            version: 0,
         };
 
         // Save time entry
-        self.save_time_entry(&time_entry).await?;
+        self.save_time_entry(&mut time_entry).await?;
 
         // Create timer
         let timer = Timer {
@@ -336,13 +461,13 @@ impl TimeTrackingService {
     /// Pause a running timer
     pub async fn pause_timer(&mut self, attorney_id: &str) -> Result<Timer> {
         let timer = self.active_timers.get_mut(attorney_id)
-            .ok_or_else(|| anyhow::anyhow!("No active timer for attorney {}", attorney_id))?;
+            .ok_or_else(|| ServiceError::not_found("Active timer", attorney_id))?;
 
         if !timer.is_running {
-            return Err(anyhow::anyhow!("Timer is not running"));
+            return Err(ServiceError::conflict("Timer is not running").into());
         }
 
-        let now = Utc::now();
+        let now = self.clock.now();
         timer.paused_at = Some(now);
         timer.is_running = false;
 
@@ -367,13 +492,13 @@ impl TimeTrackingService {
     /// Resume a paused timer
     pub async fn resume_timer(&mut self, attorney_id: &str) -> Result<Timer> {
         let timer = self.active_timers.get_mut(attorney_id)
-            .ok_or_else(|| anyhow::anyhow!("No active timer for attorney {}", attorney_id))?;
+            .ok_or_else(|| ServiceError::not_found("Active timer", attorney_id))?;
 
         if timer.is_running {
-            return Err(anyhow::anyhow!("Timer is already running"));
+            return Err(ServiceError::conflict("Timer is already running").into());
         }
 
-        let now = Utc::now();
+        let now = self.clock.now();
 
         // Calculate pause duration
         if let Some(paused_at) = timer.paused_at {
@@ -410,9 +535,9 @@ impl TimeTrackingService {
         notes: Option<String>,
     ) -> Result<TimeEntry> {
         let timer = self.active_timers.remove(attorney_id)
-            .ok_or_else(|| anyhow::anyhow!("No active timer for attorney {}", attorney_id))?;
+            .ok_or_else(|| ServiceError::not_found("Active timer", attorney_id))?;
 
-        let now = Utc::now();
+        let now = self.clock.now();
 
         // Calculate total duration
         let total_duration = now.signed_duration_since(timer.started_at);
@@ -442,7 +567,7 @@ impl TimeTrackingService {
         time_entry.updated_at = now;
 
         // Save updated time entry
-        self.save_time_entry(&time_entry).await?;
+        self.save_time_entry(&mut time_entry).await?;
 
         // Delete timer
         sqlx::query!(
@@ -463,6 +588,20 @@ impl TimeTrackingService {
         self.active_timers.get(attorney_id)
     }
 
+    /// Pause every running timer, recording elapsed minutes. Used on shutdown
+    /// so timers are not left marked running after the app exits.
+    pub async fn pause_running_timers(&mut self) -> Result<usize> {
+        let now = self.clock.now();
+        let paused = pause_running_timers(&self.db, now).await?;
+
+        for timer in self.active_timers.values_mut().filter(|t| t.is_running) {
+            timer.paused_at = Some(now);
+            timer.is_running = false;
+        }
+
+        Ok(paused)
+    }
+
     // ============= Time Entry Management =============
 
     /// Create a manual time entry
@@ -476,11 +615,138 @@ impl TimeTrackingService {
         duration_minutes: i64,
         billable_status: BillableStatus,
         notes: Option<String>,
+    ) -> Result<TimeEntry> {
+        self.create_entry(
+            TimeEntryType::Manual,
+            matter_id,
+            attorney_id,
+            activity_type,
+            description,
+            start_time,
+            duration_minutes,
+            billable_status,
+            notes,
+        )
+        .await
+    }
+
+    /// Import historical time entries from another system's CSV export.
+    /// `attorney_map` maps that system's attorney identifiers to ours.
+    /// Amounts come from the rates in effect on each entry's date; rows that
+    /// fail validation are reported and skipped rather than aborting.
+    pub async fn import_time_entries_csv(
+        &self,
+        path: &Path,
+        attorney_map: &HashMap<String, String>,
+    ) -> Result<ImportSummary> {
+        let mut reader = csv::Reader::from_path(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut summary = ImportSummary {
+            path: path.to_path_buf(),
+            ..Default::default()
+        };
+
+        for (index, record) in reader.deserialize::<CsvTimeEntryRow>().enumerate() {
+            let row = index + 1;
+            summary.total_rows += 1;
+
+            let imported = match record {
+                Ok(record) => self.import_time_entry_row(record, attorney_map).await,
+                Err(e) => Err(format!("Malformed row: {}", e)),
+            };
+            match imported {
+                Ok(_) => summary.imported += 1,
+                Err(message) => summary.record_error(row, message),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn import_time_entry_row(
+        &self,
+        record: CsvTimeEntryRow,
+        attorney_map: &HashMap<String, String>,
+    ) -> std::result::Result<TimeEntry, String> {
+        let attorney_id = attorney_map
+            .get(record.attorney.trim())
+            .ok_or_else(|| format!("Unknown attorney '{}'", record.attorney))?;
+        let start_time = parse_date_flexible(&record.date).map_err(|e| format!("Invalid date: {}", e))?;
+        if !(record.hours > 0.0 && record.hours <= 24.0) {
+            return Err(format!("Hours must be between 0 and 24, got {}", record.hours));
+        }
+        let activity_type = match record.activity.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+            Some(activity) => serde_json::from_value(serde_json::json!(activity))
+                .map_err(|_| format!("Unknown activity '{}'", activity))?,
+            None => ActivityType::Other,
+        };
+        let billable_status = match record.billable.as_deref().map(|b| b.trim().to_ascii_lowercase()) {
+            Some(b) if matches!(b.as_str(), "no" | "n" | "false" | "0") => BillableStatus::NonBillable,
+            _ => BillableStatus::Billable,
+        };
+
+        let matter_exists = sqlx::query("SELECT 1 FROM matters WHERE id = ?")
+            .bind(&record.matter_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !matter_exists {
+            return Err(format!("Unknown matter '{}'", record.matter_id));
+        }
+
+        self.create_entry(
+            TimeEntryType::Imported,
+            &record.matter_id,
+            attorney_id,
+            activity_type,
+            &record.description,
+            start_time,
+            (record.hours * 60.0).round() as i64,
+            billable_status,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Turn an activity-monitor suggestion the attorney has confirmed into a
+    /// billable entry, optionally with their own description
+    pub async fn confirm_suggested_entry(
+        &self,
+        suggestion: &SuggestedTimeEntry,
+        description: Option<String>,
+    ) -> Result<TimeEntry> {
+        self.create_entry(
+            TimeEntryType::Automatic,
+            &suggestion.matter_id,
+            &suggestion.attorney_id,
+            suggestion.activity_type.clone(),
+            description.as_deref().unwrap_or(&suggestion.description),
+            suggestion.start_time,
+            suggestion.duration_minutes,
+            BillableStatus::Billable,
+            None,
+        )
+        .await
+    }
+
+    async fn create_entry(
+        &self,
+        entry_type: TimeEntryType,
+        matter_id: &str,
+        attorney_id: &str,
+        activity_type: ActivityType,
+        description: &str,
+        start_time: DateTime<Utc>,
+        duration_minutes: i64,
+        billable_status: BillableStatus,
+        notes: Option<String>,
     ) -> Result<TimeEntry> {
         let entry_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+        let now = self.clock.now();
 
-        let hourly_rate = self.get_billing_rate(attorney_id, matter_id, &activity_type).await?;
+        let hourly_rate = self.get_billing_rate(attorney_id, matter_id, &activity_type, start_time).await?;
 
         let amount = if let Some(rate) = hourly_rate {
             let hours = duration_minutes as f64 / 60.0;
@@ -489,7 +755,7 @@ impl TimeTrackingService {
             None
         };
 
-        let time_entry = TimeEntry {
+        let mut time_entry = TimeEntry {
             id: entry_id,
             matter_id: matter_id.to_string(),
             attorney_id: attorney_id.to_string(),
@@ -502,7 +768,7 @@ impl TimeTrackingService {
             description: description.to_string(),
             notes,
             status: TimeEntryStatus::Stopped,
-            entry_type: TimeEntryType::Manual,
+            entry_type,
             billable_status,
             hourly_rate,
             amount,
@@ -516,9 +782,10 @@ impl TimeTrackingService {
             approved_by: None,
             billed_at: None,
             invoice_id: None,
+            version: 0,
         };
 
-        self.save_time_entry(&time_entry).await?;
+        self.save_time_entry(&mut time_entry).await?;
 
         Ok(time_entry)
     }
@@ -537,7 +804,7 @@ impl TimeTrackingService {
 
         // Can only update entries that haven't been billed
         if entry.status == TimeEntryStatus::Billed {
-            return Err(anyhow::anyhow!("Cannot update billed time entry"));
+            return Err(ServiceError::conflict("Cannot update billed time entry").into());
         }
 
         if let Some(desc) = description {
@@ -567,9 +834,9 @@ impl TimeTrackingService {
             entry.notes = notes;
         }
 
-        entry.updated_at = Utc::now();
+        entry.updated_at = self.clock.now();
 
-        self.save_time_entry(&entry).await?;
+        self.save_time_entry(&mut entry).await?;
 
         Ok(entry)
     }
@@ -580,7 +847,7 @@ impl TimeTrackingService {
 
         // Can only delete entries that haven't been billed
         if entry.status == TimeEntryStatus::Billed {
-            return Err(anyhow::anyhow!("Cannot delete billed time entry"));
+            return Err(ServiceError::conflict("Cannot delete billed time entry").into());
         }
 
         sqlx::query!(
@@ -598,7 +865,7 @@ impl TimeTrackingService {
 
     /// Submit time entries for approval
     pub async fn submit_entries(&self, entry_ids: Vec<String>) -> Result<Vec<TimeEntry>> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let mut updated_entries = Vec::new();
 
         for entry_id in entry_ids {
@@ -612,7 +879,7 @@ impl TimeTrackingService {
             entry.submitted_at = Some(now);
             entry.updated_at = now;
 
-            self.save_time_entry(&entry).await?;
+            self.save_time_entry(&mut entry).await?;
             updated_entries.push(entry);
         }
 
@@ -625,7 +892,7 @@ impl TimeTrackingService {
         entry_ids: Vec<String>,
         approved_by: &str,
     ) -> Result<Vec<TimeEntry>> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let mut updated_entries = Vec::new();
 
         for entry_id in entry_ids {
@@ -640,7 +907,7 @@ impl TimeTrackingService {
             entry.approved_by = Some(approved_by.to_string());
             entry.updated_at = now;
 
-            self.save_time_entry(&entry).await?;
+            self.save_time_entry(&mut entry).await?;
             updated_entries.push(entry);
         }
 
@@ -649,54 +916,160 @@ impl TimeTrackingService {
 
     // ============= Billing Rate Management =============
 
-    /// Get billing rate for attorney/matter/activity
+    /// Get the billing rate for attorney/matter/activity in effect at `at`
     async fn get_billing_rate(
         &self,
         attorney_id: &str,
         matter_id: &str,
         activity_type: &ActivityType,
+        at: DateTime<Utc>,
     ) -> Result<Option<f64>> {
         // Try to find most specific rate first
 
         // 1. Matter-specific + activity-specific rate
-        if let Some(rate) = self.find_rate(Some(attorney_id), Some(matter_id), Some(activity_type), None).await? {
+        if let Some(rate) = self.find_rate(Some(attorney_id), Some(matter_id), Some(activity_type), None, at).await? {
             return Ok(Some(rate.hourly_rate));
         }
 
         // 2. Matter-specific rate
-        if let Some(rate) = self.find_rate(Some(attorney_id), Some(matter_id), None, None).await? {
+        if let Some(rate) = self.find_rate(Some(attorney_id), Some(matter_id), None, None, at).await? {
             return Ok(Some(rate.hourly_rate));
         }
 
         // 3. Client-specific rate
         let client_id = self.get_client_id_for_matter(matter_id).await?;
         if let Some(client) = client_id {
-            if let Some(rate) = self.find_rate(Some(attorney_id), None, None, Some(&client)).await? {
+            if let Some(rate) = self.find_rate(Some(attorney_id), None, None, Some(&client), at).await? {
                 return Ok(Some(rate.hourly_rate));
             }
         }
 
         // 4. Activity-specific default rate
-        if let Some(rate) = self.find_rate(Some(attorney_id), None, Some(activity_type), None).await? {
+        if let Some(rate) = self.find_rate(Some(attorney_id), None, Some(activity_type), None, at).await? {
             return Ok(Some(rate.hourly_rate));
         }
 
         // 5. Attorney default rate
-        if let Some(rate) = self.find_rate(Some(attorney_id), None, None, None).await? {
+        if let Some(rate) = self.find_rate(Some(attorney_id), None, None, None, at).await? {
+            return Ok(Some(rate.hourly_rate));
+        }
+
+        // 6. Firm default rate, for the activity if one is set
+        if let Some(rate) = self.find_rate(Some(FIRM_RATE_ATTORNEY), None, Some(activity_type), None, at).await? {
             return Ok(Some(rate.hourly_rate));
         }
 
         Ok(None)
     }
 
+    /// Add a rate. Rates are effective from `effective_from` up to but not
+    /// including `effective_to`; a rate whose range overlaps another rate for
+    /// the same attorney and scope is rejected with [`OverlappingRate`].
+    pub async fn create_billing_rate(&self, rate: BillingRate) -> Result<BillingRate> {
+        if rate.effective_to.is_some_and(|to| to <= rate.effective_from) {
+            return Err(ServiceError::validation("Rate must end after it takes effect").into());
+        }
+        if let Some(existing_id) = self.find_overlapping_rate(&rate).await? {
+            return Err(OverlappingRate { existing_id }.into());
+        }
+        insert_billing_rate(&self.db, &rate).await?;
+        Ok(rate)
+    }
+
+    /// Store default rates for `attorney_id` (or [`FIRM_RATE_ATTORNEY`]) at
+    /// setup. Rates that would overlap one already on file are skipped, so
+    /// seeding twice is harmless. Returns how many were added.
+    pub async fn seed_default_rates(&self, attorney_id: &str, rates: Vec<BillingRate>) -> Result<usize> {
+        let mut seeded = 0;
+        for mut rate in rates {
+            rate.attorney_id = attorney_id.to_string();
+            rate.is_default = true;
+            if self.find_overlapping_rate(&rate).await?.is_none() {
+                insert_billing_rate(&self.db, &rate).await?;
+                seeded += 1;
+            }
+        }
+        Ok(seeded)
+    }
+
+    /// Bring the firm-wide rates in line with configuration. A firm rate
+    /// that was changed or removed is ended at `now` and its replacement
+    /// takes effect from then, so work already done keeps the rate that was
+    /// in effect. Returns how many rates were ended or added.
+    pub async fn sync_firm_rates(&self, config: &BillingRatesConfig, now: DateTime<Utc>) -> Result<usize> {
+        let wanted = firm_default_rates(config, now)?;
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let current: Vec<(String, Option<String>, f64)> = sqlx::query_as(
+            "SELECT id, activity_type, hourly_rate FROM billing_rates
+             WHERE attorney_id = ? AND matter_id IS NULL AND client_id IS NULL
+               AND julianday(effective_from) <= julianday(?)
+               AND (effective_to IS NULL OR julianday(?) < julianday(effective_to))",
+        )
+        .bind(FIRM_RATE_ATTORNEY)
+        .bind(now)
+        .bind(now)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to load firm billing rates")?;
+
+        let same = |rate: &BillingRate, activity: &Option<String>, hourly_rate: f64| {
+            rate.activity_type.as_ref().map(|a| format!("{:?}", a)) == *activity && rate.hourly_rate == hourly_rate
+        };
+        let mut changed = 0;
+        for (id, activity, hourly_rate) in &current {
+            if !wanted.iter().any(|rate| same(rate, activity, *hourly_rate)) {
+                sqlx::query("UPDATE billing_rates SET effective_to = ? WHERE id = ?")
+                    .bind(now)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to end firm billing rate")?;
+                changed += 1;
+            }
+        }
+        for rate in &wanted {
+            if !current.iter().any(|(_, activity, hourly_rate)| same(rate, activity, *hourly_rate)) {
+                insert_billing_rate(&mut *tx, rate).await?;
+                changed += 1;
+            }
+        }
+        tx.commit().await?;
+        Ok(changed)
+    }
+
+    async fn find_overlapping_rate(&self, rate: &BillingRate) -> Result<Option<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT id FROM billing_rates
+            WHERE attorney_id = ? AND activity_type IS ? AND matter_id IS ? AND client_id IS ?
+              AND (? IS NULL OR julianday(effective_from) < julianday(?))
+              AND (effective_to IS NULL OR julianday(?) < julianday(effective_to))
+            ORDER BY effective_from
+            LIMIT 1
+            "#,
+        )
+        .bind(&rate.attorney_id)
+        .bind(rate.activity_type.as_ref().map(|a| format!("{:?}", a)))
+        .bind(&rate.matter_id)
+        .bind(&rate.client_id)
+        .bind(rate.effective_to)
+        .bind(rate.effective_to)
+        .bind(rate.effective_from)
+        .fetch_optional(&self.db)
+        .await
+        .context("Failed to check for overlapping billing rates")
+    }
+
+    /// The most specific rate in effect at `at`. Rates for one scope never
+    /// overlap, so at most one per scope can match.
     async fn find_rate(
         &self,
         attorney_id: Option<&str>,
         matter_id: Option<&str>,
         activity_type: Option<&ActivityType>,
         client_id: Option<&str>,
+        at: DateTime<Utc>,
     ) -> Result<Option<BillingRate>> {
-        // This is a simplified version - real implementation would use complex SQL query
         let activity_str = activity_type.map(|a| format!("{:?}", a));
 
         let result = sqlx::query_as!(
@@ -709,18 +1082,21 @@ impl TimeTrackingService {
               AND (matter_id = ? OR matter_id IS NULL)
               AND (activity_type = ? OR activity_type IS NULL)
               AND (client_id = ? OR client_id IS NULL)
-              AND effective_from <= datetime('now')
-              AND (effective_to IS NULL OR effective_to >= datetime('now'))
+              AND julianday(effective_from) <= julianday(?)
+              AND (effective_to IS NULL OR julianday(?) < julianday(effective_to))
             ORDER BY
               CASE WHEN matter_id IS NOT NULL THEN 1 ELSE 2 END,
               CASE WHEN activity_type IS NOT NULL THEN 1 ELSE 2 END,
-              CASE WHEN client_id IS NOT NULL THEN 1 ELSE 2 END
+              CASE WHEN client_id IS NOT NULL THEN 1 ELSE 2 END,
+              id
             LIMIT 1
             "#,
             attorney_id,
             matter_id,
             activity_str,
-            client_id
+            client_id,
+            at,
+            at
         )
         .fetch_optional(&self.db)
         .await
@@ -895,53 +1271,69 @@ impl TimeTrackingService {
 
     // ============= Helper Methods =============
 
-    async fn save_time_entry(&self, entry: &TimeEntry) -> Result<()> {
-        let activity_type_str = format!("{:?}", entry.activity_type);
-        let status_str = format!("{:?}", entry.status);
-        let entry_type_str = format!("{:?}", entry.entry_type);
-        let billable_status_str = format!("{:?}", entry.billable_status);
-
-        sqlx::query!(
-            r#"
-            INSERT OR REPLACE INTO time_entries
-            (id, matter_id, attorney_id, attorney_name, start_time, end_time, duration_minutes,
-             billable_minutes, activity_type, description, notes, status, entry_type,
-             billable_status, hourly_rate, amount, discount_percent, discount_amount,
-             final_amount, created_at, updated_at, submitted_at, approved_at, approved_by,
-             billed_at, invoice_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-            entry.id,
-            entry.matter_id,
-            entry.attorney_id,
-            entry.attorney_name,
-            entry.start_time,
-            entry.end_time,
-            entry.duration_minutes,
-            entry.billable_minutes,
-            activity_type_str,
-            entry.description,
-            entry.notes,
-            status_str,
-            entry_type_str,
-            billable_status_str,
-            entry.hourly_rate,
-            entry.amount,
-            entry.discount_percent,
-            entry.discount_amount,
-            entry.final_amount,
-            entry.created_at,
-            entry.updated_at,
-            entry.submitted_at,
-            entry.approved_at,
-            entry.approved_by,
-            entry.billed_at,
-            entry.invoice_id
+    /// Insert a new entry or update an existing one at the version it was
+    /// loaded with. Fails with [`Conflict`] when another writer saved first.
+    async fn save_time_entry(&self, entry: &mut TimeEntry) -> Result<()> {
+        let updated = bind_time_entry_fields(
+            sqlx::query(
+                r#"
+                UPDATE time_entries SET
+                    matter_id = ?, attorney_id = ?, attorney_name = ?, start_time = ?, end_time = ?,
+                    duration_minutes = ?, billable_minutes = ?, activity_type = ?, description = ?,
+                    notes = ?, status = ?, entry_type = ?, billable_status = ?, hourly_rate = ?,
+                    amount = ?, discount_percent = ?, discount_amount = ?, final_amount = ?,
+                    created_at = ?, updated_at = ?, submitted_at = ?, approved_at = ?, approved_by = ?,
+                    billed_at = ?, invoice_id = ?,
+                    version = version + 1
+                WHERE id = ? AND version = ?
+                "#,
+            ),
+            entry,
         )
+        .bind(&entry.id)
+        .bind(entry.version)
         .execute(&self.db)
         .await
-        .context("Failed to save time entry")?;
+        .context("Failed to save time entry")?
+        .rows_affected();
+
+        if updated == 0 {
+            let exists = sqlx::query("SELECT 1 FROM time_entries WHERE id = ?")
+                .bind(&entry.id)
+                .fetch_optional(&self.db)
+                .await?
+                .is_some();
+            if exists {
+                return Err(Conflict {
+                    entity: "TimeEntry",
+                    id: entry.id.clone(),
+                    expected_version: entry.version,
+                }
+                .into());
+            }
+
+            bind_time_entry_fields(
+                sqlx::query(
+                    r#"
+                    INSERT INTO time_entries
+                    (matter_id, attorney_id, attorney_name, start_time, end_time, duration_minutes,
+                     billable_minutes, activity_type, description, notes, status, entry_type,
+                     billable_status, hourly_rate, amount, discount_percent, discount_amount,
+                     final_amount, created_at, updated_at, submitted_at, approved_at, approved_by,
+                     billed_at, invoice_id, id, version)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                ),
+                entry,
+            )
+            .bind(&entry.id)
+            .bind(entry.version + 1)
+            .execute(&self.db)
+            .await
+            .context("Failed to save time entry")?;
+        }
 
+        entry.version += 1;
         Ok(())
     }
 
@@ -958,15 +1350,16 @@ impl TimeTrackingService {
                    billable_status as "billable_status: _",
                    hourly_rate, amount, discount_percent, discount_amount, final_amount,
                    created_at, updated_at, submitted_at, approved_at, approved_by,
-                   billed_at, invoice_id
+                   billed_at, invoice_id, version
             FROM time_entries
             WHERE id = ?
             "#,
             entry_id
         )
-        .fetch_one(&self.db)
+        .fetch_optional(&self.db)
         .await
-        .context("Failed to fetch time entry")?;
+        .context("Failed to fetch time entry")?
+        .ok_or_else(|| ServiceError::not_found("Time entry", entry_id))?;
 
         Ok(result)
     }
@@ -990,7 +1383,7 @@ impl TimeTrackingService {
                    billable_status as "billable_status: _",
                    hourly_rate, amount, discount_percent, discount_amount, final_amount,
                    created_at, updated_at, submitted_at, approved_at, approved_by,
-                   billed_at, invoice_id
+                   billed_at, invoice_id, version
             FROM time_entries
             WHERE start_time >= ? AND start_time <= ?
             ORDER BY start_time DESC
@@ -1025,3 +1418,256 @@ impl TimeTrackingService {
         Ok("Client Name".to_string())
     }
 }
+
+/// Bind every time entry column except `id` and `version`, in table order
+fn bind_time_entry_fields<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    entry: &'q TimeEntry,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(&entry.matter_id)
+        .bind(&entry.attorney_id)
+        .bind(&entry.attorney_name)
+        .bind(entry.start_time)
+        .bind(entry.end_time)
+        .bind(entry.duration_minutes)
+        .bind(entry.billable_minutes)
+        .bind(format!("{:?}", entry.activity_type))
+        .bind(&entry.description)
+        .bind(&entry.notes)
+        .bind(format!("{:?}", entry.status))
+        .bind(format!("{:?}", entry.entry_type))
+        .bind(format!("{:?}", entry.billable_status))
+        .bind(entry.hourly_rate)
+        .bind(entry.amount)
+        .bind(entry.discount_percent)
+        .bind(entry.discount_amount)
+        .bind(entry.final_amount)
+        .bind(entry.created_at)
+        .bind(entry.updated_at)
+        .bind(entry.submitted_at)
+        .bind(entry.approved_at)
+        .bind(&entry.approved_by)
+        .bind(entry.billed_at)
+        .bind(&entry.invoice_id)
+}
+
+/// Pause all timers marked running in the database as of `now`, persisting elapsed minutes.
+/// Returns the number of timers paused.
+pub async fn pause_running_timers(db: &SqlitePool, now: DateTime<Utc>) -> Result<usize> {
+    let rows = sqlx::query(
+        "SELECT id, started_at, total_pause_duration_minutes FROM timers WHERE is_running = 1",
+    )
+    .fetch_all(db)
+    .await
+    .context("Failed to load running timers")?;
+
+    for row in &rows {
+        let id: String = row.try_get("id")?;
+        let started_at: DateTime<Utc> = row.try_get("started_at")?;
+        let paused_minutes: i64 = row.try_get("total_pause_duration_minutes")?;
+        let elapsed_minutes = (now.signed_duration_since(started_at).num_minutes() - paused_minutes).max(0);
+
+        sqlx::query(
+            "UPDATE timers SET paused_at = ?, is_running = 0, elapsed_minutes = ? WHERE id = ?",
+        )
+        .bind(now)
+        .bind(elapsed_minutes)
+        .bind(&id)
+        .execute(db)
+        .await
+        .context("Failed to pause timer")?;
+    }
+
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    async fn service() -> TimeTrackingService {
+        let db = test_database().await;
+        TimeTrackingService::new(db)
+    }
+
+    #[tokio::test]
+    async fn test_timer_bills_elapsed_clock_time() {
+        use crate::utils::clock::MockClock;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap()));
+        let mut tracking = service().await.with_clock(clock.clone());
+
+        let timer = tracking
+            .start_timer("matter-1", "atty-1", ActivityType::Research, "Statute of limitations research")
+            .await
+            .unwrap();
+        clock.advance(Duration::minutes(90));
+        let entry = tracking.stop_timer("atty-1", None, None).await.unwrap();
+
+        assert_eq!(entry.start_time, timer.started_at);
+        assert_eq!(entry.end_time, Some(timer.started_at + Duration::minutes(90)));
+        assert_eq!(entry.duration_minutes, Some(90));
+        assert_eq!(entry.billable_minutes, Some(90));
+    }
+
+    fn rate(matter_id: Option<&str>, activity_type: Option<ActivityType>, hourly_rate: f64) -> BillingRate {
+        BillingRate {
+            id: Uuid::new_v4().to_string(),
+            attorney_id: String::new(),
+            activity_type,
+            matter_id: matter_id.map(str::to_string),
+            client_id: None,
+            rate_type: RateType::Standard,
+            hourly_rate,
+            effective_from: Utc::now() - Duration::days(30),
+            effective_to: None,
+            is_default: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_matter_rate_overrides_attorney_default() {
+        let tracking = service().await;
+        tracking
+            .seed_default_rates("atty-1", vec![rate(None, None, 300.0), rate(Some("matter-1"), None, 250.0)])
+            .await
+            .unwrap();
+
+        let research = ActivityType::Research;
+        let now = Utc::now();
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, now).await.unwrap(), Some(250.0));
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-2", &research, now).await.unwrap(), Some(300.0));
+
+        // Seeding the same scopes again adds nothing
+        assert_eq!(tracking.seed_default_rates("atty-1", vec![rate(None, None, 999.0)]).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_firm_default_applies_when_nothing_else_matches() {
+        let tracking = service().await;
+        let config = BillingRatesConfig {
+            firm_hourly_rate: Some(200.0),
+            activity_rates: HashMap::from([("Travel".to_string(), 100.0)]),
+        };
+        let firm_rates = firm_default_rates(&config, Utc::now() - Duration::days(1)).unwrap();
+        assert_eq!(tracking.seed_default_rates(FIRM_RATE_ATTORNEY, firm_rates).await.unwrap(), 2);
+
+        let now = Utc::now();
+        assert_eq!(tracking.get_billing_rate("new-associate", "matter-1", &ActivityType::Drafting, now).await.unwrap(), Some(200.0));
+        assert_eq!(tracking.get_billing_rate("new-associate", "matter-1", &ActivityType::Travel, now).await.unwrap(), Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_changed_firm_rate_applies_from_the_change() {
+        use chrono::TimeZone;
+        let tracking = service().await;
+        let january = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let june = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let mut config = BillingRatesConfig { firm_hourly_rate: Some(200.0), activity_rates: HashMap::new() };
+
+        assert_eq!(tracking.sync_firm_rates(&config, january).await.unwrap(), 1);
+        assert_eq!(tracking.sync_firm_rates(&config, january + Duration::days(1)).await.unwrap(), 0);
+
+        config.firm_hourly_rate = Some(275.0);
+        assert_eq!(tracking.sync_firm_rates(&config, june).await.unwrap(), 2);
+        let research = ActivityType::Research;
+        let march = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, march).await.unwrap(), Some(200.0));
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, june).await.unwrap(), Some(275.0));
+
+        // Without a configured rate, nothing is guessed
+        config.firm_hourly_rate = None;
+        let july = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+        assert_eq!(tracking.sync_firm_rates(&config, july).await.unwrap(), 1);
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, july).await.unwrap(), None);
+    }
+
+    fn dated_rate(hourly_rate: f64, from: (i32, u32, u32), to: Option<(i32, u32, u32)>) -> BillingRate {
+        use chrono::TimeZone;
+        let day = |(y, m, d)| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        BillingRate {
+            attorney_id: "atty-1".to_string(),
+            effective_from: day(from),
+            effective_to: to.map(day),
+            ..rate(None, None, hourly_rate)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_rate_rejected() {
+        let tracking = service().await;
+        let existing = tracking.create_billing_rate(dated_rate(300.0, (2023, 1, 1), Some((2024, 1, 1)))).await.unwrap();
+
+        let err = tracking.create_billing_rate(dated_rate(325.0, (2023, 7, 1), None)).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<OverlappingRate>(), Some(&OverlappingRate { existing_id: existing.id }));
+
+        // Back-to-back ranges do not overlap
+        tracking.create_billing_rate(dated_rate(325.0, (2024, 1, 1), None)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_historical_entry_uses_rate_effective_then() {
+        use chrono::TimeZone;
+        let tracking = service().await;
+        tracking.create_billing_rate(dated_rate(300.0, (2023, 1, 1), Some((2024, 1, 1)))).await.unwrap();
+        tracking.create_billing_rate(dated_rate(350.0, (2024, 1, 1), None)).await.unwrap();
+
+        let last_year = Utc.with_ymd_and_hms(2023, 11, 15, 14, 0, 0).unwrap();
+        let research = ActivityType::Research;
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, last_year).await.unwrap(), Some(300.0));
+        assert_eq!(tracking.get_billing_rate("atty-1", "matter-1", &research, Utc::now()).await.unwrap(), Some(350.0));
+    }
+
+    #[tokio::test]
+    async fn test_csv_import_keeps_valid_rows_and_reports_bad_ones() {
+        let tracking = service().await;
+        sqlx::raw_sql(
+            "INSERT INTO clients (id, first_name, last_name, client_type, created_at, updated_at)
+             VALUES ('client-1', 'Jane', 'Smith', 'individual', '2024-01-01', '2024-01-01');
+             INSERT INTO matters (id, client_id, matter_number, title, matter_type, created_at, updated_at)
+             VALUES ('matter-1', 'client-1', '2024-CIV-0001', 'Smith v. Jones', 'civil', '2024-01-01', '2024-01-01');",
+        )
+        .execute(&tracking.db)
+        .await
+        .unwrap();
+        tracking.seed_default_rates("atty-1", vec![rate(None, None, 300.0)]).await.unwrap();
+        tracking
+            .create_billing_rate(dated_rate(200.0, (2020, 1, 1), Some((2024, 1, 1))))
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("time.csv");
+        std::fs::write(
+            &path,
+            "date,matter_id,attorney,hours,activity,description,billable\n\
+             2023-06-01,matter-1,JSMITH,1.5,Research,Research statute of limitations,Yes\n\
+             2023-06-02,matter-9,JSMITH,1.0,Drafting,Draft complaint,Yes\n\
+             2023-06-03,matter-1,NOBODY,0.5,Phone,Call with client,Yes\n\
+             2023-06-04,matter-1,JSMITH,30,Review,Review production,Yes\n\
+             2023-06-05,matter-1,JSMITH,0.25,Phone,Call with opposing counsel,No\n",
+        )
+        .unwrap();
+        let attorney_map = HashMap::from([("JSMITH".to_string(), "atty-1".to_string())]);
+
+        let summary = tracking.import_time_entries_csv(&path, &attorney_map).await.unwrap();
+
+        assert_eq!(summary.total_rows, 5);
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.errors.iter().map(|e| e.row).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert!(summary.errors[0].message.contains("matter-9"));
+
+        let amounts: Vec<(f64, String)> = sqlx::query_as(
+            "SELECT amount, entry_type FROM time_entries ORDER BY start_time",
+        )
+        .fetch_all(&tracking.db)
+        .await
+        .unwrap();
+        // Priced at the rate in effect in 2023
+        assert_eq!(amounts, vec![(300.0, "Imported".to_string()), (50.0, "Imported".to_string())]);
+    }
+}
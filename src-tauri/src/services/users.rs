@@ -0,0 +1,150 @@
+// Firm staff accounts
+// Sign-in looks the role up from the stored account and verifies the
+// password, so a session's role never comes from the caller
+
+use crate::services::permissions::{require_permission, Permission, Role, UserSession};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserAccount {
+    pub user_id: String,
+    pub display_name: String,
+    pub role: Role,
+    pub is_active: bool,
+}
+
+pub struct UserDirectory {
+    db: SqlitePool,
+}
+
+impl UserDirectory {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Add a staff account. `creator` must be allowed to change configuration,
+    /// except on a fresh install, where the first account may be created
+    /// without a session but must be an Admin.
+    pub async fn create_user(
+        &self,
+        creator: Option<&UserSession>,
+        user_id: &str,
+        display_name: &str,
+        role: Role,
+        password: &str,
+    ) -> Result<UserAccount> {
+        if user_id.trim().is_empty() {
+            bail!("User ID cannot be empty");
+        }
+        if password.is_empty() {
+            bail!("Password cannot be empty");
+        }
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| anyhow!("Failed to hash password: {}", e))?
+            .to_string();
+
+        // Immediate, so two first-run setups cannot both see an empty table
+        let mut tx = self.db.begin_with("BEGIN IMMEDIATE").await?;
+        let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&mut *tx)
+            .await?;
+        if existing == 0 {
+            if role != Role::Admin {
+                bail!("The first account must be an Admin");
+            }
+        } else {
+            let creator = creator.ok_or_else(|| anyhow!("Sign in to add accounts"))?;
+            require_permission(creator, Permission::UpdateConfig)?;
+        }
+
+        let account = UserAccount {
+            user_id: user_id.trim().to_string(),
+            display_name: display_name.to_string(),
+            role,
+            is_active: true,
+        };
+        sqlx::query(
+            "INSERT INTO users (id, display_name, role, password_hash, is_active, created_at)
+             VALUES (?, ?, ?, ?, 1, ?)",
+        )
+        .bind(&account.user_id)
+        .bind(&account.display_name)
+        .bind(serde_json::to_string(&account.role)?)
+        .bind(&password_hash)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("Failed to create account {}", account.user_id))?;
+        tx.commit().await?;
+
+        info!("Created account {} as {}", account.user_id, account.role);
+        Ok(account)
+    }
+
+    /// The session for `user_id` if `password` matches its stored hash
+    pub async fn authenticate(&self, user_id: &str, password: &str) -> Result<UserSession> {
+        let row = sqlx::query("SELECT role, password_hash, is_active FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| anyhow!("Invalid user ID or password"))?;
+
+        let stored: String = row.get("password_hash");
+        let parsed = PasswordHash::new(&stored).map_err(|e| anyhow!("Invalid password hash: {}", e))?;
+        if Argon2::default().verify_password(password.as_bytes(), &parsed).is_err() {
+            warn!("Failed sign-in for {}", user_id);
+            bail!("Invalid user ID or password");
+        }
+        if !row.get::<bool, _>("is_active") {
+            bail!("Account is disabled");
+        }
+
+        let role: Role = serde_json::from_str(&row.get::<String, _>("role"))
+            .with_context(|| format!("Account {} has an unknown role", user_id))?;
+        Ok(UserSession { user_id: user_id.to_string(), role })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+
+    #[tokio::test]
+    async fn test_sign_in_takes_role_from_the_stored_account() {
+        let users = UserDirectory::new(test_database().await);
+        users.create_user(None, "admin", "Office Admin", Role::Admin, "correct horse").await.unwrap();
+        let admin = users.authenticate("admin", "correct horse").await.unwrap();
+        users
+            .create_user(Some(&admin), "pat", "Pat Paralegal", Role::Paralegal, "battery staple")
+            .await
+            .unwrap();
+
+        let session = users.authenticate("pat", "battery staple").await.unwrap();
+        assert_eq!(session.role, Role::Paralegal);
+        assert!(users.authenticate("pat", "wrong").await.is_err());
+        assert!(users.authenticate("nobody", "battery staple").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_the_first_account_skips_the_config_permission() {
+        let users = UserDirectory::new(test_database().await);
+        assert!(users.create_user(None, "pat", "Pat", Role::Paralegal, "pw").await.is_err());
+        users.create_user(None, "admin", "Admin", Role::Admin, "pw").await.unwrap();
+
+        assert!(users.create_user(None, "eve", "Eve", Role::Admin, "pw").await.is_err());
+        let paralegal = UserSession { user_id: "pat".to_string(), role: Role::Paralegal };
+        assert!(users.create_user(Some(&paralegal), "eve", "Eve", Role::Admin, "pw").await.is_err());
+    }
+}
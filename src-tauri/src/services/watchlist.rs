@@ -1,59 +1,431 @@
 // Watchlist service for PA eDocket Desktop
+// Watched dockets are re-fetched on their check interval; a docket whose
+// contents hash differs from the last check is reported as changed
 
 use crate::domain::*;
-use anyhow::Result;
-use tracing::{info, instrument};
+use crate::providers::fetch_dockets;
+use crate::providers::registry::ProviderRegistry;
+use crate::services::webhooks::{WebhookDispatcher, WebhookEvent};
+use crate::utils::crypto::calculate_sha256_string;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
-pub struct WatchlistService;
+/// Watchlist that items added from the app belong to
+const DEFAULT_WATCHLIST_ID: &str = "default";
+
+/// How often the background loop looks for dockets due a check
+pub const WATCHLIST_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Watched dockets fetched at once from one provider
+const WATCHLIST_FETCH_CONCURRENCY: usize = 4;
+
+pub struct WatchlistService {
+    db: SqlitePool,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+}
 
 impl WatchlistService {
-    pub fn new() -> Self {
-        Self
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db, webhooks: None }
     }
-    
+
+    /// Send a watchlist_change webhook for each changed docket that asked to be notified
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Watch a docket in `court_id`, checking it every `check_interval` minutes.
+    /// Caption and court are filled in by the first check.
     #[instrument(skip(self, docket_id))]
-    pub async fn add_to_watchlist(&self, docket_id: &str, notify_on_change: bool, check_interval: u32) -> Result<WatchlistItem> {
+    pub async fn add_to_watchlist(
+        &self,
+        docket_id: &str,
+        court_id: &str,
+        notify_on_change: bool,
+        check_interval: u32,
+    ) -> Result<WatchlistItem> {
         info!("Adding docket to watchlist: {}", docket_id);
-        
-        // TODO: Implement watchlist add
+
         let item = WatchlistItem {
             id: Uuid::new_v4(),
             docket_id: docket_id.to_string(),
-            caption: "Unknown".to_string(),
+            caption: docket_id.to_string(),
             court: CourtLevel::Cp,
-            county: "Unknown".to_string(),
-            added_at: chrono::Utc::now(),
+            county: court_id.to_string(),
+            added_at: Utc::now(),
             last_checked: None,
             last_changed: None,
             notify_on_change,
-            check_interval,
+            check_interval: check_interval.max(1),
         };
-        
-        Ok(item)
+
+        sqlx::query(
+            r#"
+            INSERT INTO watchlist_items (
+                id, watchlist_id, docket_number, court_id, caption, county,
+                notify_on_change, check_interval, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (watchlist_id, docket_number) DO UPDATE SET
+                court_id = excluded.court_id,
+                notify_on_change = excluded.notify_on_change,
+                check_interval = excluded.check_interval
+            "#,
+        )
+        .bind(item.id.to_string())
+        .bind(DEFAULT_WATCHLIST_ID)
+        .bind(&item.docket_id)
+        .bind(court_id)
+        .bind(&item.caption)
+        .bind(&item.county)
+        .bind(item.notify_on_change)
+        .bind(item.check_interval)
+        .bind(item.added_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to add docket to watchlist")?;
+
+        self.get_item(docket_id)
+            .await?
+            .with_context(|| format!("Watchlist item for {} was not saved", docket_id))
     }
-    
+
     #[instrument(skip(self, docket_id))]
     pub async fn remove_from_watchlist(&self, docket_id: &str) -> Result<()> {
         info!("Removing docket from watchlist: {}", docket_id);
-        
-        // TODO: Implement watchlist remove
+
+        sqlx::query("DELETE FROM watchlist_items WHERE watchlist_id = ? AND docket_number = ?")
+            .bind(DEFAULT_WATCHLIST_ID)
+            .bind(docket_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to remove docket from watchlist")?;
         Ok(())
     }
-    
+
     #[instrument(skip(self))]
     pub async fn get_watchlist(&self) -> Result<Vec<WatchlistItem>> {
         info!("Fetching watchlist");
-        
-        // TODO: Implement watchlist retrieval
-        Ok(vec![])
+
+        Ok(self.load_items().await?.into_iter().map(|(item, _)| item).collect())
     }
-    
-    #[instrument(skip(self))]
-    pub async fn check_for_updates(&self) -> Result<Vec<WatchlistItem>> {
+
+    /// Re-fetch every docket whose check interval has elapsed as of `now`
+    /// and return the ones whose contents changed since their last check.
+    /// A docket's first check only records its contents. Due dockets are
+    /// fetched a few at a time from their court's provider.
+    #[instrument(skip(self, registry))]
+    pub async fn check_for_updates(&self, registry: &ProviderRegistry, now: DateTime<Utc>) -> Result<Vec<WatchlistItem>> {
         info!("Checking watchlist for updates");
-        
-        // TODO: Implement update checking
-        Ok(vec![])
+
+        // Due items grouped by court, so each court's provider fetches its dockets together
+        let mut due_by_court: Vec<(String, Vec<(WatchlistItem, TrackedState)>)> = Vec::new();
+        for (item, tracked) in self.load_items().await? {
+            let due = item
+                .last_checked
+                .map_or(true, |checked| checked + Duration::minutes(item.check_interval as i64) <= now);
+            if !due {
+                continue;
+            }
+            match due_by_court.iter_mut().find(|(court_id, _)| *court_id == tracked.court_id) {
+                Some((_, items)) => items.push((item, tracked)),
+                None => due_by_court.push((tracked.court_id.clone(), vec![(item, tracked)])),
+            }
+        }
+
+        let mut changed = Vec::new();
+        for (court_id, items) in due_by_court {
+            let Some(provider) = registry.search_provider_for(&court_id) else {
+                warn!("No search provider for {}, skipping {} watched dockets", court_id, items.len());
+                continue;
+            };
+            let ids: Vec<String> = items.iter().map(|(item, _)| item.docket_id.clone()).collect();
+            let dockets = fetch_dockets(&ids, provider.as_ref(), WATCHLIST_FETCH_CONCURRENCY).await;
+
+            for ((mut item, tracked), docket) in items.into_iter().zip(dockets) {
+                let docket = match docket {
+                    Ok(docket) => docket,
+                    Err(e) => {
+                        warn!("Failed to check watched docket {}: {}", item.docket_id, e);
+                        continue;
+                    }
+                };
+
+                let hash = docket_contents_hash(&docket)?;
+                let is_changed = tracked.docket_hash.as_deref().is_some_and(|previous| previous != hash);
+                item.caption = docket.caption;
+                item.court = docket.court;
+                item.county = docket.county;
+                item.last_checked = Some(now);
+                if is_changed {
+                    item.last_changed = Some(now);
+                }
+                self.save_check(&item, &hash).await?;
+
+                if is_changed {
+                    info!("Watched docket {} changed", item.docket_id);
+                    changed.push(item);
+                }
+            }
+        }
+
+        self.notify_changes(&changed).await;
+        Ok(changed)
+    }
+
+    /// Check for updates every poll interval until `shutdown` is cancelled
+    pub async fn run_check_loop(self: Arc<Self>, registry: Arc<ProviderRegistry>, shutdown: CancellationToken) {
+        info!("Starting watchlist check loop");
+        let mut interval = tokio::time::interval(WATCHLIST_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.check_for_updates(&registry, Utc::now()).await {
+                        error!("Watchlist check failed: {}", e);
+                    }
+                }
+            }
+        }
+
+        info!("Watchlist check loop stopped");
+    }
+
+    async fn notify_changes(&self, changed: &[WatchlistItem]) {
+        let Some(webhooks) = &self.webhooks else {
+            return;
+        };
+        for item in changed.iter().filter(|item| item.notify_on_change) {
+            if let Err(e) = webhooks.dispatch(&WebhookEvent::watchlist_change(item)).await {
+                warn!("Failed to dispatch watchlist webhook for {}: {}", item.docket_id, e);
+            }
+        }
+    }
+
+    // ============= Helpers =============
+
+    async fn get_item(&self, docket_id: &str) -> Result<Option<WatchlistItem>> {
+        Ok(self
+            .load_items()
+            .await?
+            .into_iter()
+            .map(|(item, _)| item)
+            .find(|item| item.docket_id == docket_id))
+    }
+
+    async fn load_items(&self) -> Result<Vec<(WatchlistItem, TrackedState)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM watchlist_items
+            WHERE watchlist_id = ?
+            ORDER BY created_at
+            "#,
+        )
+        .bind(DEFAULT_WATCHLIST_ID)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load watchlist")?;
+
+        rows.iter().map(row_to_item).collect()
+    }
+
+    async fn save_check(&self, item: &WatchlistItem, hash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE watchlist_items
+            SET caption = ?, court = ?, county = ?, last_checked = ?, last_changed = ?, docket_hash = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&item.caption)
+        .bind(serde_json::to_string(&item.court)?)
+        .bind(&item.county)
+        .bind(item.last_checked)
+        .bind(item.last_changed)
+        .bind(hash)
+        .bind(item.id.to_string())
+        .execute(&self.db)
+        .await
+        .context("Failed to record watchlist check")?;
+        Ok(())
+    }
+}
+
+/// Columns used to check an item that are not part of `WatchlistItem`
+struct TrackedState {
+    court_id: String,
+    docket_hash: Option<String>,
+}
+
+fn row_to_item(row: &sqlx::sqlite::SqliteRow) -> Result<(WatchlistItem, TrackedState)> {
+    let id: String = row.try_get("id")?;
+    let court: Option<String> = row.try_get("court")?;
+    let docket_number: String = row.try_get("docket_number")?;
+    let court_id: String = row.try_get("court_id")?;
+
+    let item = WatchlistItem {
+        id: Uuid::parse_str(&id).context("Invalid watchlist item id")?,
+        caption: row.try_get::<Option<String>, _>("caption")?.unwrap_or_else(|| docket_number.clone()),
+        court: court.map(|c| serde_json::from_str(&c)).transpose()?.unwrap_or(CourtLevel::Cp),
+        county: row.try_get::<Option<String>, _>("county")?.unwrap_or_else(|| court_id.clone()),
+        added_at: row.try_get("created_at")?,
+        last_checked: row.try_get("last_checked")?,
+        last_changed: row.try_get("last_changed")?,
+        notify_on_change: row.try_get("notify_on_change")?,
+        check_interval: row.try_get("check_interval")?,
+        docket_id: docket_number,
+    };
+    let tracked = TrackedState { court_id, docket_hash: row.try_get("docket_hash")? };
+
+    Ok((item, tracked))
+}
+
+/// Hash of the docket's contents, leaving out fields that change on every fetch
+fn docket_contents_hash(docket: &Docket) -> Result<String> {
+    let contents = Docket {
+        fetched_at: None,
+        hash: None,
+        source: None,
+        ..docket.clone()
+    };
+    Ok(calculate_sha256_string(&serde_json::to_string(&contents)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ProviderError, SearchProvider};
+    use crate::services::database::test_database;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Serves dockets under one caption the test can change between checks,
+    /// recording how many fetches were in flight at once
+    struct StubSearch {
+        caption: Mutex<String>,
+        in_flight: AtomicUsize,
+        peak_in_flight: AtomicUsize,
+    }
+
+    impl StubSearch {
+        fn new(caption: &str) -> Self {
+            Self {
+                caption: Mutex::new(caption.to_string()),
+                in_flight: AtomicUsize::new(0),
+                peak_in_flight: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SearchProvider for StubSearch {
+        async fn search(&self, _params: &SearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        async fn get_docket(&self, id: &str) -> Result<Docket, ProviderError> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(Docket {
+                id: id.to_string(),
+                caption: self.caption.lock().unwrap().clone(),
+                status: CaseStatus::Active,
+                court: CourtLevel::Cp,
+                county: "Philadelphia".to_string(),
+                filed: Utc::now(),
+                docket_number: Some(id.to_string()),
+                otn: None,
+                sid: None,
+                judge: None,
+                courtroom: None,
+                division: None,
+                parties: vec![],
+                charges: vec![],
+                events: vec![],
+                filings: vec![],
+                financials: vec![],
+                attachments: None,
+                last_updated: None,
+                source_url: None,
+                source: None,
+                // Differs on every fetch and must not count as a change
+                fetched_at: Some(Utc::now()),
+                hash: None,
+            })
+        }
+
+        async fn get_attachments(&self, _docket_id: &str) -> Result<Vec<Attachment>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    fn registry_with(search: Arc<StubSearch>) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::default();
+        registry.register_search_provider("ujs_portal", search);
+        let courts = r#"
+courts: {}
+counties:
+  philadelphia: { name: "Philadelphia County", cp_court_id: "51", efiling: null, local_rules: {} }
+templates: {}
+"#;
+        registry.add_routes(&serde_yaml::from_str(courts).unwrap());
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_changed_docket_is_reported_once_its_interval_elapses() {
+        let db = test_database().await;
+        let search = Arc::new(StubSearch::new("Commonwealth v. Smith"));
+        let registry = registry_with(search.clone());
+
+        let service = WatchlistService::new(db);
+        service.add_to_watchlist("CP-51-CR-0001234-2024", "philadelphia", true, 30).await.unwrap();
+
+        // The first check records the docket without reporting it
+        let start = Utc::now();
+        assert!(service.check_for_updates(&registry, start).await.unwrap().is_empty());
+        let item = &service.get_watchlist().await.unwrap()[0];
+        assert_eq!(item.caption, "Commonwealth v. Smith");
+        assert_eq!(item.county, "Philadelphia");
+
+        // Refetching unchanged contents is not a change
+        let later = start + Duration::minutes(30);
+        assert!(service.check_for_updates(&registry, later).await.unwrap().is_empty());
+
+        *search.caption.lock().unwrap() = "Commonwealth v. Smith (amended)".to_string();
+        // Not due yet
+        assert!(service.check_for_updates(&registry, later + Duration::minutes(10)).await.unwrap().is_empty());
+
+        let changed = service.check_for_updates(&registry, later + Duration::minutes(30)).await.unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].caption, "Commonwealth v. Smith (amended)");
+        assert_eq!(changed[0].last_changed, Some(later + Duration::minutes(30)));
+    }
+
+    #[tokio::test]
+    async fn test_due_dockets_are_fetched_concurrently() {
+        let search = Arc::new(StubSearch::new("Commonwealth v. Smith"));
+        let registry = registry_with(search.clone());
+        let service = WatchlistService::new(test_database().await);
+        for n in 1..=6 {
+            let docket_id = format!("CP-51-CR-000000{}-2024", n);
+            service.add_to_watchlist(&docket_id, "philadelphia", false, 30).await.unwrap();
+        }
+
+        service.check_for_updates(&registry, Utc::now()).await.unwrap();
+
+        assert!(service.get_watchlist().await.unwrap().iter().all(|item| item.last_checked.is_some()));
+        let peak = search.peak_in_flight.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= WATCHLIST_FETCH_CONCURRENCY, "peak of {} fetches in flight", peak);
     }
 }
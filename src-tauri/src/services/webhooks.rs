@@ -0,0 +1,592 @@
+// Outbound webhooks - push watchlist changes and e-filing status to firm systems
+// Each delivery is JSON signed with the endpoint's secret (HMAC-SHA256 in the
+// X-Edocket-Signature header), retried with backoff and recorded in a delivery log.
+// Secrets live in the secret store, not the endpoints table.
+
+use crate::domain::{CourtLevel, EFilingSubmission, SubmissionStatus, WatchlistItem};
+use crate::services::secret_store::{keychain, SharedSecretStore};
+use crate::utils::crypto::hmac_sha256_hex;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub const SIGNATURE_HEADER: &str = "X-Edocket-Signature";
+pub const EVENT_HEADER: &str = "X-Edocket-Event";
+pub const DELIVERY_HEADER: &str = "X-Edocket-Delivery";
+
+/// Attempts per delivery, including the first
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubles on each subsequent failure
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    WatchlistChange,
+    EfilingStatus,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::WatchlistChange => "watchlist_change",
+            WebhookEventType::EfilingStatus => "efiling_status",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "watchlist_change" => Some(WebhookEventType::WatchlistChange),
+            "efiling_status" => Some(WebhookEventType::EfilingStatus),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    WatchlistChange {
+        docket_id: String,
+        caption: String,
+        court: CourtLevel,
+        county: String,
+        changed_at: DateTime<Utc>,
+    },
+    EfilingStatus {
+        submission_id: Uuid,
+        docket_id: Option<String>,
+        document_type: String,
+        status: SubmissionStatus,
+        confirmation: Option<String>,
+        error_message: Option<String>,
+    },
+}
+
+impl WebhookEvent {
+    pub fn watchlist_change(item: &WatchlistItem) -> Self {
+        WebhookEvent::WatchlistChange {
+            docket_id: item.docket_id.clone(),
+            caption: item.caption.clone(),
+            court: item.court.clone(),
+            county: item.county.clone(),
+            changed_at: item.last_changed.unwrap_or_else(Utc::now),
+        }
+    }
+
+    pub fn efiling_status(submission: &EFilingSubmission) -> Self {
+        WebhookEvent::EfilingStatus {
+            submission_id: submission.id,
+            docket_id: submission.docket_id.clone(),
+            document_type: submission.document_type.clone(),
+            status: submission.status.clone(),
+            confirmation: submission.submission_id.clone(),
+            error_message: submission.error_message.clone(),
+        }
+    }
+
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::WatchlistChange { .. } => WebhookEventType::WatchlistChange,
+            WebhookEvent::EfilingStatus { .. } => WebhookEventType::EfilingStatus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    /// Signing secret; never sent back to the UI
+    #[serde(default, skip_serializing)]
+    pub secret: String,
+    /// Subscribed events; empty means every event
+    pub events: Vec<WebhookEventType>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    fn subscribes_to(&self, event_type: WebhookEventType) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event_type))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Delivered,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub endpoint_id: String,
+    pub event_type: WebhookEventType,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Body POSTed to every endpoint
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    delivery_id: &'a str,
+    occurred_at: DateTime<Utc>,
+    event: &'a WebhookEvent,
+}
+
+pub struct WebhookDispatcher {
+    db: SqlitePool,
+    /// Holds endpoint signing secrets
+    secrets: SharedSecretStore,
+    client: reqwest::Client,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: SqlitePool) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent("PA-eDocket-Desktop/1.0")
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            db,
+            secrets: keychain(),
+            client,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    pub fn with_retry(mut self, max_attempts: u32, retry_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Where endpoint signing secrets are kept; the OS keychain by default
+    pub fn with_secret_store(mut self, secrets: SharedSecretStore) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    // ============= Endpoints =============
+
+    pub async fn add_endpoint(
+        &self,
+        url: &str,
+        secret: &str,
+        events: Vec<WebhookEventType>,
+    ) -> Result<WebhookEndpoint> {
+        let parsed = url::Url::parse(url).context("Invalid webhook URL")?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            anyhow::bail!("Webhook URL must use http or https: {}", url);
+        }
+        if secret.is_empty() {
+            anyhow::bail!("Webhook secret is required");
+        }
+
+        let endpoint = WebhookEndpoint {
+            id: Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            secret: secret.to_string(),
+            events,
+            enabled: true,
+            created_at: Utc::now(),
+        };
+
+        self.secrets.put(&secret_key(&endpoint.id), &endpoint.secret)?;
+        sqlx::query(
+            "INSERT INTO webhook_endpoints (id, url, secret, events, enabled, created_at) VALUES (?, ?, '', ?, ?, ?)",
+        )
+        .bind(&endpoint.id)
+        .bind(&endpoint.url)
+        .bind(join_events(&endpoint.events))
+        .bind(endpoint.enabled)
+        .bind(endpoint.created_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to save webhook endpoint")?;
+
+        info!("Registered webhook endpoint {} -> {}", endpoint.id, endpoint.url);
+        Ok(endpoint)
+    }
+
+    pub async fn list_endpoints(&self) -> Result<Vec<WebhookEndpoint>> {
+        let rows = sqlx::query("SELECT * FROM webhook_endpoints ORDER BY created_at")
+            .fetch_all(&self.db)
+            .await
+            .context("Failed to load webhook endpoints")?;
+
+        let mut endpoints = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let id: String = row.try_get("id")?;
+            // Endpoints registered before secrets moved out of the table
+            let legacy_secret: String = row.try_get("secret")?;
+            if !legacy_secret.is_empty() {
+                self.move_secret_to_secret_store(&id, &legacy_secret).await?;
+            }
+
+            let events: String = row.try_get("events")?;
+            endpoints.push(WebhookEndpoint {
+                secret: self.secrets.get(&secret_key(&id))?.unwrap_or_default(),
+                id,
+                url: row.try_get("url")?,
+                events: events.split(',').filter_map(WebhookEventType::parse).collect(),
+                enabled: row.try_get("enabled")?,
+                created_at: row.try_get("created_at")?,
+            });
+        }
+        Ok(endpoints)
+    }
+
+    async fn move_secret_to_secret_store(&self, endpoint_id: &str, secret: &str) -> Result<()> {
+        self.secrets.put(&secret_key(endpoint_id), secret)?;
+        sqlx::query("UPDATE webhook_endpoints SET secret = '' WHERE id = ?")
+            .bind(endpoint_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to clear stored webhook secret")?;
+        info!("Moved the signing secret for webhook endpoint {} to the secret store", endpoint_id);
+        Ok(())
+    }
+
+    pub async fn remove_endpoint(&self, endpoint_id: &str) -> Result<bool> {
+        let removed = sqlx::query("DELETE FROM webhook_endpoints WHERE id = ?")
+            .bind(endpoint_id)
+            .execute(&self.db)
+            .await
+            .context("Failed to remove webhook endpoint")?
+            .rows_affected();
+        self.secrets.delete(&secret_key(endpoint_id))?;
+        Ok(removed > 0)
+    }
+
+    // ============= Delivery =============
+
+    /// Deliver `event` to every subscribed endpoint. A failing endpoint does
+    /// not stop delivery to the others; its outcome is in the returned log.
+    pub async fn dispatch(&self, event: &WebhookEvent) -> Result<Vec<WebhookDelivery>> {
+        let event_type = event.event_type();
+        let mut deliveries = Vec::new();
+
+        for endpoint in self.list_endpoints().await? {
+            if !endpoint.subscribes_to(event_type) {
+                continue;
+            }
+            if endpoint.secret.is_empty() {
+                // Unsigned deliveries could not be told apart from forgeries
+                warn!("Webhook endpoint {} has no signing secret; register it again", endpoint.id);
+                continue;
+            }
+            deliveries.push(self.deliver(&endpoint, event).await?);
+        }
+
+        Ok(deliveries)
+    }
+
+    async fn deliver(&self, endpoint: &WebhookEndpoint, event: &WebhookEvent) -> Result<WebhookDelivery> {
+        let delivery_id = Uuid::new_v4().to_string();
+        let body = serde_json::to_string(&WebhookPayload {
+            delivery_id: &delivery_id,
+            occurred_at: Utc::now(),
+            event,
+        })?;
+        let signature = format!("sha256={}", hmac_sha256_hex(endpoint.secret.as_bytes(), body.as_bytes()));
+
+        let mut delivery = WebhookDelivery {
+            id: delivery_id,
+            endpoint_id: endpoint.id.clone(),
+            event_type: event.event_type(),
+            status: DeliveryStatus::Failed,
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        };
+
+        let mut delay = self.retry_delay;
+        while delivery.attempts < self.max_attempts as i64 {
+            if delivery.attempts > 0 {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            delivery.attempts += 1;
+
+            let result = self
+                .client
+                .post(&endpoint.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(EVENT_HEADER, delivery.event_type.as_str())
+                .header(DELIVERY_HEADER, &delivery.id)
+                .body(body.clone())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    delivery.status = DeliveryStatus::Delivered;
+                    delivery.response_status = Some(response.status().as_u16() as i64);
+                    delivery.last_error = None;
+                    delivery.delivered_at = Some(Utc::now());
+                    break;
+                }
+                Ok(response) => {
+                    delivery.response_status = Some(response.status().as_u16() as i64);
+                    delivery.last_error = Some(format!("HTTP {}", response.status()));
+                }
+                Err(e) => {
+                    delivery.response_status = None;
+                    delivery.last_error = Some(e.to_string());
+                }
+            }
+
+            warn!(
+                "Webhook delivery {} to {} failed (attempt {}/{}): {}",
+                delivery.id,
+                endpoint.url,
+                delivery.attempts,
+                self.max_attempts,
+                delivery.last_error.as_deref().unwrap_or_default()
+            );
+        }
+
+        self.record(&delivery, &body, &signature).await?;
+        Ok(delivery)
+    }
+
+    async fn record(&self, delivery: &WebhookDelivery, payload: &str, signature: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries
+            (id, endpoint_id, event_type, payload, signature, status, attempts,
+             response_status, last_error, created_at, delivered_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&delivery.id)
+        .bind(&delivery.endpoint_id)
+        .bind(delivery.event_type.as_str())
+        .bind(payload)
+        .bind(signature)
+        .bind(delivery_status_str(delivery.status))
+        .bind(delivery.attempts)
+        .bind(delivery.response_status)
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.db)
+        .await
+        .context("Failed to record webhook delivery")?;
+        Ok(())
+    }
+
+    /// Delivery log, newest first, optionally for one endpoint
+    pub async fn list_deliveries(&self, endpoint_id: Option<&str>, limit: i64) -> Result<Vec<WebhookDelivery>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE (?1 IS NULL OR endpoint_id = ?1)
+            ORDER BY created_at DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load webhook deliveries")?;
+
+        rows.iter()
+            .map(|row| {
+                let event_type: String = row.try_get("event_type")?;
+                let status: String = row.try_get("status")?;
+                Ok(WebhookDelivery {
+                    id: row.try_get("id")?,
+                    endpoint_id: row.try_get("endpoint_id")?,
+                    event_type: WebhookEventType::parse(&event_type)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown webhook event type: {}", event_type))?,
+                    status: if status == "delivered" { DeliveryStatus::Delivered } else { DeliveryStatus::Failed },
+                    attempts: row.try_get("attempts")?,
+                    response_status: row.try_get("response_status")?,
+                    last_error: row.try_get("last_error")?,
+                    created_at: row.try_get("created_at")?,
+                    delivered_at: row.try_get("delivered_at")?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn delivery_status_str(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Delivered => "delivered",
+        DeliveryStatus::Failed => "failed",
+    }
+}
+
+fn secret_key(endpoint_id: &str) -> String {
+    format!("webhook-endpoint:{}:secret", endpoint_id)
+}
+
+fn join_events(events: &[WebhookEventType]) -> String {
+    events.iter().map(|e| e.as_str()).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::test_database;
+    use crate::services::secret_store::MemorySecretStore;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+        failures_left: Arc<AtomicUsize>,
+    }
+
+    async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+        receiver.requests.lock().unwrap().push((headers, body));
+        let failing = receiver
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failing {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    /// Local endpoint that fails the first `failures` requests
+    async fn receiver(failures: usize) -> (String, Receiver) {
+        let receiver = Receiver {
+            failures_left: Arc::new(AtomicUsize::new(failures)),
+            ..Receiver::default()
+        };
+        let app = Router::new().route("/hook", post(receive)).with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, receiver)
+    }
+
+    async fn dispatcher() -> WebhookDispatcher {
+        let db = test_database().await;
+        WebhookDispatcher::new(db)
+            .with_retry(3, Duration::from_millis(10))
+            .with_secret_store(Arc::new(MemorySecretStore::default()))
+    }
+
+    fn change_event() -> WebhookEvent {
+        WebhookEvent::WatchlistChange {
+            docket_id: "CP-51-CR-0001234-2024".to_string(),
+            caption: "Commonwealth v. Smith".to_string(),
+            court: CourtLevel::Cp,
+            county: "Philadelphia".to_string(),
+            changed_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_event_is_signed_with_endpoint_secret() {
+        let dispatcher = dispatcher().await;
+        let (url, receiver) = receiver(0).await;
+        dispatcher
+            .add_endpoint(&url, "s3cret", vec![WebhookEventType::WatchlistChange])
+            .await
+            .unwrap();
+        dispatcher
+            .add_endpoint(&url, "other", vec![WebhookEventType::EfilingStatus])
+            .await
+            .unwrap();
+
+        let deliveries = dispatcher.dispatch(&change_event()).await.unwrap();
+
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+
+        let requests = receiver.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let (headers, body) = &requests[0];
+        let expected = format!("sha256={}", hmac_sha256_hex(b"s3cret", body.as_bytes()));
+        assert_eq!(headers[SIGNATURE_HEADER], expected.as_str());
+        assert_eq!(headers[EVENT_HEADER], "watchlist_change");
+
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["event"]["type"], "watchlist_change");
+        assert_eq!(payload["event"]["docket_id"], "CP-51-CR-0001234-2024");
+        assert_eq!(payload["delivery_id"], deliveries[0].id.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_failing_endpoint_is_retried_and_logged() {
+        let dispatcher = dispatcher().await;
+        let (url, receiver) = receiver(2).await;
+        let endpoint = dispatcher.add_endpoint(&url, "s3cret", vec![]).await.unwrap();
+
+        let deliveries = dispatcher.dispatch(&change_event()).await.unwrap();
+
+        assert_eq!(receiver.requests.lock().unwrap().len(), 3);
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+        assert_eq!(deliveries[0].attempts, 3);
+
+        let log = dispatcher.list_deliveries(Some(&endpoint.id), 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].attempts, 3);
+        assert_eq!(log[0].response_status, Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_secrets_are_kept_out_of_the_endpoints_table() {
+        let dispatcher = dispatcher().await;
+        let (url, receiver) = receiver(0).await;
+        let endpoint = dispatcher.add_endpoint(&url, "s3cret", vec![]).await.unwrap();
+        let stored: String = sqlx::query_scalar("SELECT secret FROM webhook_endpoints WHERE id = ?")
+            .bind(&endpoint.id)
+            .fetch_one(&dispatcher.db)
+            .await
+            .unwrap();
+        assert_eq!(stored, "");
+
+        // A secret left in the table by an older version moves out on first use
+        sqlx::query("UPDATE webhook_endpoints SET secret = 'legacy' WHERE id = ?")
+            .bind(&endpoint.id)
+            .execute(&dispatcher.db)
+            .await
+            .unwrap();
+        dispatcher.dispatch(&change_event()).await.unwrap();
+
+        let requests = receiver.requests.lock().unwrap();
+        let (headers, body) = &requests[0];
+        let expected = format!("sha256={}", hmac_sha256_hex(b"legacy", body.as_bytes()));
+        assert_eq!(headers[SIGNATURE_HEADER], expected.as_str());
+        let stored: String = sqlx::query_scalar("SELECT secret FROM webhook_endpoints WHERE id = ?")
+            .bind(&endpoint.id)
+            .fetch_one(&dispatcher.db)
+            .await
+            .unwrap();
+        assert_eq!(stored, "");
+    }
+}
@@ -0,0 +1,211 @@
+// Workers' Compensation - Feature #24
+// Weekly benefit calculation from the average weekly wage (AWW) using per-state parameters
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DisabilityType {
+    TemporaryTotal,
+    /// Partial wage loss while earning `current_weekly_earnings`
+    TemporaryPartial { current_weekly_earnings: f64 },
+    /// Scheduled (specific) loss paid for a fixed number of weeks
+    PermanentPartial { scheduled_weeks: u32 },
+    PermanentTotal,
+}
+
+/// Statutory benefit parameters for one state and benefit year
+#[derive(Debug, Clone, Serialize)]
+pub struct StateWcParameters {
+    pub state: &'static str,
+    pub benefit_year: u16,
+    /// Maximum weekly compensation rate
+    pub max_weekly_rate: f64,
+    /// Minimum weekly rate, never more than the claimant's AWW
+    pub min_weekly_rate: Option<f64>,
+    /// Share of AWW (or of lost wages for partial disability)
+    pub compensation_rate: f64,
+    /// Low-wage floor as (share of max rate, cap as share of AWW), e.g. PA's
+    /// 50% of SAWW or 90% of AWW, whichever is less
+    pub low_wage_floor: Option<(f64, f64)>,
+    pub ttd_max_weeks: Option<u32>,
+    pub tpd_max_weeks: Option<u32>,
+}
+
+/// Seeded parameter table; update each benefit year as states publish new maximums
+pub const STATE_WC_PARAMETERS: &[StateWcParameters] = &[
+    StateWcParameters {
+        state: "PA",
+        benefit_year: 2024,
+        max_weekly_rate: 1_325.00,
+        min_weekly_rate: None,
+        compensation_rate: 2.0 / 3.0,
+        low_wage_floor: Some((0.5, 0.9)),
+        ttd_max_weeks: None,
+        tpd_max_weeks: Some(500),
+    },
+    StateWcParameters {
+        state: "NJ",
+        benefit_year: 2024,
+        max_weekly_rate: 1_131.00,
+        min_weekly_rate: Some(302.00),
+        compensation_rate: 0.70,
+        low_wage_floor: None,
+        ttd_max_weeks: Some(400),
+        tpd_max_weeks: Some(600),
+    },
+    StateWcParameters {
+        state: "NY",
+        benefit_year: 2024,
+        max_weekly_rate: 1_171.46,
+        min_weekly_rate: Some(275.00),
+        compensation_rate: 2.0 / 3.0,
+        low_wage_floor: None,
+        ttd_max_weeks: None,
+        tpd_max_weeks: Some(525),
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WcBenefits {
+    pub state: String,
+    pub disability: DisabilityType,
+    pub average_weekly_wage: f64,
+    pub weekly_rate: f64,
+    /// The state maximum limited the weekly rate
+    pub cap_applied: bool,
+    /// A minimum or low-wage floor raised the weekly rate
+    pub floor_applied: bool,
+    pub max_weeks: Option<u32>,
+    pub max_total: Option<f64>,
+    pub notes: Vec<String>,
+}
+
+pub fn state_parameters(state: &str) -> Option<&'static StateWcParameters> {
+    STATE_WC_PARAMETERS
+        .iter()
+        .find(|p| p.state.eq_ignore_ascii_case(state.trim()))
+}
+
+/// Weekly benefit for an injured worker with average weekly wage `awer`
+pub fn compute_wc_benefits(awer: f64, disability: DisabilityType, state: &str) -> WcBenefits {
+    let awer = awer.max(0.0);
+    let mut notes = Vec::new();
+
+    let Some(params) = state_parameters(state) else {
+        notes.push(format!(
+            "No benefit parameters for {}; using 66⅔% of AWW without a state maximum",
+            state
+        ));
+        let weekly_rate = round_cents(wage_base(awer, disability) * 2.0 / 3.0);
+        return WcBenefits {
+            state: state.to_string(),
+            disability,
+            average_weekly_wage: awer,
+            weekly_rate,
+            cap_applied: false,
+            floor_applied: false,
+            max_weeks: scheduled_weeks(disability),
+            max_total: scheduled_weeks(disability).map(|w| round_cents(weekly_rate * w as f64)),
+            notes,
+        };
+    };
+
+    let mut rate = wage_base(awer, disability) * params.compensation_rate;
+    let mut cap_applied = false;
+    let mut floor_applied = false;
+
+    if rate > params.max_weekly_rate {
+        rate = params.max_weekly_rate;
+        cap_applied = true;
+        notes.push(format!(
+            "Limited to {} {} maximum of ${:.2}",
+            params.state, params.benefit_year, params.max_weekly_rate
+        ));
+    }
+
+    // Floors protect low-wage claimants on total disability only
+    if matches!(disability, DisabilityType::TemporaryTotal | DisabilityType::PermanentTotal) {
+        if let Some((share_of_max, share_of_aww)) = params.low_wage_floor {
+            let threshold = params.max_weekly_rate * share_of_max;
+            if rate < threshold {
+                let floor = threshold.min(awer * share_of_aww);
+                if floor > rate {
+                    rate = floor;
+                    floor_applied = true;
+                    notes.push("Low-wage floor applied".to_string());
+                }
+            }
+        }
+        if let Some(min) = params.min_weekly_rate {
+            let floor = min.min(awer);
+            if rate < floor {
+                rate = floor;
+                floor_applied = true;
+                notes.push(format!("Raised to minimum weekly rate of ${:.2}", floor));
+            }
+        }
+    }
+
+    let max_weeks = match disability {
+        DisabilityType::TemporaryTotal => params.ttd_max_weeks,
+        DisabilityType::TemporaryPartial { .. } => params.tpd_max_weeks,
+        DisabilityType::PermanentPartial { scheduled_weeks } => Some(scheduled_weeks),
+        DisabilityType::PermanentTotal => None,
+    };
+
+    let weekly_rate = round_cents(rate);
+    WcBenefits {
+        state: params.state.to_string(),
+        disability,
+        average_weekly_wage: awer,
+        weekly_rate,
+        cap_applied,
+        floor_applied,
+        max_weeks,
+        max_total: max_weeks.map(|w| round_cents(weekly_rate * w as f64)),
+        notes,
+    }
+}
+
+/// Wages the compensation rate applies to: lost wages for partial disability, AWW otherwise
+fn wage_base(awer: f64, disability: DisabilityType) -> f64 {
+    match disability {
+        DisabilityType::TemporaryPartial { current_weekly_earnings } => (awer - current_weekly_earnings).max(0.0),
+        _ => awer,
+    }
+}
+
+fn scheduled_weeks(disability: DisabilityType) -> Option<u32> {
+    match disability {
+        DisabilityType::PermanentPartial { scheduled_weeks } => Some(scheduled_weeks),
+        _ => None,
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pa_ttd_at_statutory_rate() {
+        let benefits = compute_wc_benefits(1_200.0, DisabilityType::TemporaryTotal, "PA");
+
+        assert_eq!(benefits.weekly_rate, 800.0);
+        assert!(!benefits.cap_applied);
+        assert!(!benefits.floor_applied);
+        assert_eq!(benefits.max_weeks, None);
+    }
+
+    #[test]
+    fn test_pa_ttd_limited_by_maximum_rate() {
+        let benefits = compute_wc_benefits(3_000.0, DisabilityType::TemporaryTotal, "pa");
+
+        assert_eq!(benefits.weekly_rate, 1_325.0);
+        assert!(benefits.cap_applied);
+    }
+}
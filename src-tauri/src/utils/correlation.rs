@@ -0,0 +1,99 @@
+// Correlation ids - one id per command or REST request, carried in the
+// tracing span, sent to court portals as a header and logged with errors,
+// so a failure can be followed across services and into provider logs.
+// Commands get theirs from a `correlation_id` field on their instrument
+// span; REST requests from the middleware, which also returns it.
+
+use std::fmt;
+use std::future::Future;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/// Header carrying the id on outbound provider requests and REST responses
+pub const CORRELATION_HEADER: &str = "x-correlation-id";
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+pub fn new_correlation_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Id of the command or request the current task is serving
+pub fn current_correlation_id() -> Option<String> {
+    CORRELATION_ID.try_with(|id| id.clone()).ok().or_else(span_correlation_id)
+}
+
+/// The nearest `correlation_id` recorded on the current span or its parents
+fn span_correlation_id() -> Option<String> {
+    tracing::Span::current()
+        .with_subscriber(|(id, dispatch)| {
+            let registry = dispatch.downcast_ref::<Registry>()?;
+            let span = registry.span(id)?;
+            span.scope()
+                .find_map(|s| s.extensions().get::<SpanCorrelationId>().map(|c| c.0.clone()))
+        })
+        .flatten()
+}
+
+/// Run `fut` with `id` as the current correlation id
+pub async fn with_correlation_id<F: Future>(id: String, fut: F) -> F::Output {
+    CORRELATION_ID.scope(id, fut).await
+}
+
+/// Correlation id recorded on a span
+struct SpanCorrelationId(String);
+
+/// Keeps the `correlation_id` field of each new span with the span, so calls
+/// made while a command runs can find the id its instrument span was given
+pub struct CorrelationLayer;
+
+impl<S> Layer<S> for CorrelationLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut field = CorrelationField(None);
+        attrs.record(&mut field);
+        if let (Some(value), Some(span)) = (field.0, ctx.span(id)) {
+            span.extensions_mut().insert(SpanCorrelationId(value));
+        }
+    }
+}
+
+struct CorrelationField(Option<String>);
+
+impl Visit for CorrelationField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "correlation_id" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "correlation_id" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// Add the current correlation id, if any, to an outbound request
+pub fn with_correlation_header(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match current_correlation_id() {
+        Some(id) => request.header(CORRELATION_HEADER, id),
+        None => request,
+    }
+}
+
+/// An id a client sent with its request, if it is safe to reuse and log
+pub fn accept_correlation_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value.len() <= 64
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| value.to_string())
+}
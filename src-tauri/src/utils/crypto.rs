@@ -1,6 +1,7 @@
 // Cryptographic utilities for PA eDocket Desktop
 
 use anyhow::Result;
+use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
 
 /// Calculate SHA-256 hash of data
@@ -21,6 +22,13 @@ pub async fn calculate_file_hash(file_path: &str) -> Result<String> {
     Ok(calculate_sha256(&data))
 }
 
+/// HMAC-SHA256 (RFC 2104) of `message` under `key`, hex encoded
+pub fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
 /// Generate a secure random string for IDs
 pub fn generate_secure_id() -> String {
     uuid::Uuid::new_v4().to_string()
@@ -37,6 +45,12 @@ mod tests {
         assert_eq!(hash, "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f");
     }
     
+    #[test]
+    fn test_hmac_sha256_rfc4231_vector() {
+        let mac = hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(mac, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+    
     #[test]
     fn test_secure_id_generation() {
         let id1 = generate_secure_id();
@@ -1,7 +1,8 @@
 // Date utilities for PA eDocket Desktop
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 /// Parse a date string in various common formats
 pub fn parse_date_flexible(date_str: &str) -> Result<DateTime<Utc>> {
@@ -61,6 +62,232 @@ pub fn is_date_in_range(
     true
 }
 
+/// Parse a search date: any format accepted by `parse_date_flexible`, an
+/// RFC 3339 timestamp, "today", "yesterday", or a relative shorthand such as
+/// "last 30 days" / "past 6 months", resolved against `now`
+pub fn parse_search_date(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    let today = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+    match value.to_lowercase().as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some((amount, unit)) = parse_relative(value) {
+        let start = match unit {
+            "day" => today.checked_sub_signed(Duration::days(amount.into())),
+            "week" => today.checked_sub_signed(Duration::weeks(amount.into())),
+            "month" => today.checked_sub_months(Months::new(amount)),
+            _ => today.checked_sub_months(Months::new(amount.saturating_mul(12))),
+        };
+        return start.ok_or_else(|| anyhow::anyhow!("Date out of range: {}", value));
+    }
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    parse_date_flexible(value)
+}
+
+/// "last 30 days" -> (30, "day")
+fn parse_relative(value: &str) -> Option<(u32, &'static str)> {
+    let lower = value.to_lowercase();
+    let mut words = lower.split_whitespace();
+    if !matches!(words.next()?, "last" | "past") {
+        return None;
+    }
+    let amount: u32 = words.next()?.parse().ok()?;
+    let unit = match words.next()?.trim_end_matches('s') {
+        "day" => "day",
+        "week" => "week",
+        "month" => "month",
+        "year" => "year",
+        _ => return None,
+    };
+    words.next().is_none().then_some((amount, unit))
+}
+
+/// Parse an optional `from`/`to` pair, rejecting a range that ends before it starts
+pub fn parse_date_range(
+    from: Option<&str>,
+    to: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>)> {
+    let parse = |value: Option<&str>| {
+        value
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| parse_search_date(v, now))
+            .transpose()
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+
+    if let (Some(start), Some(end)) = (from, to) {
+        if start > end {
+            anyhow::bail!(
+                "Date range starts after it ends: {} > {}",
+                format_date_api(&start),
+                format_date_api(&end)
+            );
+        }
+    }
+
+    Ok((from, to))
+}
+
+/// A court holiday rule, resolved to a concrete date for a given year
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CourtHoliday {
+    /// Fixed calendar date, observed on Friday/Monday when it falls on a weekend
+    Fixed { name: String, month: u32, day: u32 },
+    /// The nth occurrence of a weekday in a month (e.g., third Monday of January)
+    NthWeekday { name: String, month: u32, weekday: Weekday, n: u8 },
+    /// The last occurrence of a weekday in a month (e.g., Memorial Day)
+    LastWeekday { name: String, month: u32, weekday: Weekday },
+    /// A fixed offset in days from another nth-weekday holiday (e.g., day after Thanksgiving)
+    DayAfterNthWeekday { name: String, month: u32, weekday: Weekday, n: u8, offset: i64 },
+    /// Good Friday, two days before Western Easter
+    GoodFriday,
+}
+
+impl CourtHoliday {
+    /// Resolve the observed date of this holiday in the given year
+    pub fn observed_date(&self, year: i32) -> Option<NaiveDate> {
+        match self {
+            CourtHoliday::Fixed { month, day, .. } => {
+                let date = NaiveDate::from_ymd_opt(year, *month, *day)?;
+                Some(match date.weekday() {
+                    Weekday::Sat => date - Duration::days(1),
+                    Weekday::Sun => date + Duration::days(1),
+                    _ => date,
+                })
+            }
+            CourtHoliday::NthWeekday { month, weekday, n, .. } => {
+                NaiveDate::from_weekday_of_month_opt(year, *month, *weekday, *n)
+            }
+            CourtHoliday::LastWeekday { month, weekday, .. } => {
+                let first_of_next = if *month == 12 {
+                    NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(year, month + 1, 1)?
+                };
+                let mut date = first_of_next - Duration::days(1);
+                while date.weekday() != *weekday {
+                    date -= Duration::days(1);
+                }
+                Some(date)
+            }
+            CourtHoliday::DayAfterNthWeekday { month, weekday, n, offset, .. } => {
+                NaiveDate::from_weekday_of_month_opt(year, *month, *weekday, *n)
+                    .map(|date| date + Duration::days(*offset))
+            }
+            CourtHoliday::GoodFriday => easter_sunday(year).map(|easter| easter - Duration::days(2)),
+        }
+    }
+}
+
+/// Holidays observed by a court system, used for deadline computation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourtCalendar {
+    pub jurisdiction: String,
+    pub holidays: Vec<CourtHoliday>,
+}
+
+impl CourtCalendar {
+    /// Holidays observed by the Pennsylvania Unified Judicial System
+    pub fn pennsylvania() -> Self {
+        let fixed = |name: &str, month, day| CourtHoliday::Fixed { name: name.to_string(), month, day };
+        let nth = |name: &str, month, weekday, n| CourtHoliday::NthWeekday { name: name.to_string(), month, weekday, n };
+
+        Self {
+            jurisdiction: "PA".to_string(),
+            holidays: vec![
+                fixed("New Year's Day", 1, 1),
+                nth("Martin Luther King Jr. Day", 1, Weekday::Mon, 3),
+                nth("Presidents' Day", 2, Weekday::Mon, 3),
+                CourtHoliday::GoodFriday,
+                CourtHoliday::LastWeekday { name: "Memorial Day".to_string(), month: 5, weekday: Weekday::Mon },
+                fixed("Juneteenth", 6, 19),
+                fixed("Independence Day", 7, 4),
+                nth("Labor Day", 9, Weekday::Mon, 1),
+                nth("Columbus Day", 10, Weekday::Mon, 2),
+                fixed("Veterans Day", 11, 11),
+                nth("Thanksgiving Day", 11, Weekday::Thu, 4),
+                CourtHoliday::DayAfterNthWeekday {
+                    name: "Day after Thanksgiving".to_string(),
+                    month: 11,
+                    weekday: Weekday::Thu,
+                    n: 4,
+                    offset: 1,
+                },
+                fixed("Christmas Day", 12, 25),
+            ],
+        }
+    }
+
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        // A holiday observed on Dec 31 belongs to the following year's New Year's Day
+        [date.year(), date.year() + 1]
+            .iter()
+            .any(|year| self.holidays.iter().any(|h| h.observed_date(*year) == Some(date)))
+    }
+}
+
+/// Compute Western Easter Sunday (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Check if a date is a holiday on the given court calendar
+pub fn is_court_holiday(date: DateTime<Utc>, calendar: &CourtCalendar) -> bool {
+    calendar.is_holiday(date.date_naive())
+}
+
+/// Check if a date is a weekday that is not a court holiday
+pub fn is_business_day(date: DateTime<Utc>, calendar: &CourtCalendar) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !is_court_holiday(date, calendar)
+}
+
+/// Roll a date forward to the next business day if it falls on a weekend or court holiday
+pub fn roll_forward_to_business_day(date: DateTime<Utc>, calendar: &CourtCalendar) -> DateTime<Utc> {
+    let mut current = date;
+    while !is_business_day(current, calendar) {
+        current += Duration::days(1);
+    }
+    current
+}
+
+/// Add business days to a date, skipping weekends and court holidays
+pub fn add_business_days(start: DateTime<Utc>, n: u32, calendar: &CourtCalendar) -> DateTime<Utc> {
+    let mut current = start;
+    let mut remaining = n;
+
+    while remaining > 0 {
+        current += Duration::days(1);
+        if is_business_day(current, calendar) {
+            remaining -= 1;
+        }
+    }
+
+    current
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +329,73 @@ mod tests {
         assert!(is_date_in_range(&date, Some(&start), None));
         assert!(is_date_in_range(&date, None, None));
     }
+    
+    #[test]
+    fn test_friday_plus_one_business_day_is_monday() {
+        let calendar = CourtCalendar::pennsylvania();
+        let friday = Utc.with_ymd_and_hms(2024, 3, 8, 12, 0, 0).unwrap();
+        
+        let next = add_business_days(friday, 1, &calendar);
+        assert_eq!(next.weekday(), Weekday::Mon);
+        assert_eq!(format_date_api(&next), "2024-03-11");
+    }
+    
+    #[test]
+    fn test_holiday_deadline_rolls_forward() {
+        let calendar = CourtCalendar::pennsylvania();
+        let independence_day = Utc.with_ymd_and_hms(2024, 7, 4, 0, 0, 0).unwrap();
+        
+        assert!(is_court_holiday(independence_day, &calendar));
+        assert_eq!(format_date_api(&roll_forward_to_business_day(independence_day, &calendar)), "2024-07-05");
+        
+        let wednesday = Utc.with_ymd_and_hms(2024, 7, 3, 0, 0, 0).unwrap();
+        assert_eq!(format_date_api(&add_business_days(wednesday, 1, &calendar)), "2024-07-05");
+    }
+    
+    #[test]
+    fn test_pennsylvania_movable_holidays() {
+        let calendar = CourtCalendar::pennsylvania();
+        let good_friday = Utc.with_ymd_and_hms(2024, 3, 29, 0, 0, 0).unwrap();
+        let day_after_thanksgiving = Utc.with_ymd_and_hms(2024, 11, 29, 0, 0, 0).unwrap();
+        let observed_juneteenth = Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap();
+        
+        assert!(is_court_holiday(good_friday, &calendar));
+        assert!(is_court_holiday(day_after_thanksgiving, &calendar));
+        assert!(is_court_holiday(observed_juneteenth, &calendar));
+        assert!(!is_court_holiday(Utc.with_ymd_and_hms(2024, 3, 28, 0, 0, 0).unwrap(), &calendar));
+    }
+
+    #[test]
+    fn test_valid_search_date_range() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 15, 9, 30, 0).unwrap();
+        let (from, to) = parse_date_range(Some("2024-01-01"), Some("06/30/2024"), now).unwrap();
+
+        assert_eq!(from, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+        assert_eq!(to, Some(Utc.with_ymd_and_hms(2024, 6, 30, 0, 0, 0).unwrap()));
+        assert_eq!(parse_date_range(None, Some(""), now).unwrap(), (None, None));
+        assert!(parse_date_range(Some("2024-13-40"), None, now).is_err());
+    }
+
+    #[test]
+    fn test_inverted_search_date_range_is_rejected() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 15, 9, 30, 0).unwrap();
+        let err = parse_date_range(Some("2024-06-30"), Some("2024-01-01"), now).unwrap_err();
+
+        assert!(err.to_string().contains("starts after it ends"));
+    }
+
+    #[test]
+    fn test_relative_search_date_expands_from_now() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 15, 9, 30, 0).unwrap();
+
+        assert_eq!(
+            parse_search_date("last 30 days", now).unwrap(),
+            Utc.with_ymd_and_hms(2024, 5, 16, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_search_date("Past 6 Months", now).unwrap(),
+            Utc.with_ymd_and_hms(2023, 12, 15, 0, 0, 0).unwrap()
+        );
+        assert!(parse_search_date("last few days", now).is_err());
+    }
 }
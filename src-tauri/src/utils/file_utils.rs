@@ -1,9 +1,18 @@
 // File utilities for PA eDocket Desktop
 
 use anyhow::Result;
-use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
+/// Device names Windows refuses to use as file names, regardless of extension
+const RESERVED_WINDOWS_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
 /// Ensure a directory exists, creating it if necessary
 pub async fn ensure_dir_exists(path: &Path) -> Result<()> {
     if !path.exists() {
@@ -59,10 +68,14 @@ pub fn generate_unique_filename(base_path: &Path, filename: &str) -> PathBuf {
 /// Sanitize filename for safe filesystem usage
 pub fn sanitize_filename(filename: &str) -> String {
     let invalid_chars = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
-    let mut sanitized = filename.to_string();
+    let mut sanitized: String = filename
+        .chars()
+        .map(|c| if invalid_chars.contains(&c) || c.is_control() { '_' } else { c })
+        .collect();
     
-    for ch in invalid_chars {
-        sanitized = sanitized.replace(ch, "_");
+    // Collapse parent-directory references
+    while sanitized.contains("..") {
+        sanitized = sanitized.replace("..", ".");
     }
     
     // Remove leading/trailing whitespace and dots
@@ -73,14 +86,88 @@ pub fn sanitize_filename(filename: &str) -> String {
         sanitized = "document".to_string();
     }
     
+    // Avoid reserved device names such as CON or LPT1.txt
+    let stem = sanitized.split('.').next().unwrap_or("").trim_end().to_uppercase();
+    if RESERVED_WINDOWS_NAMES.contains(&stem.as_str()) {
+        sanitized = format!("_{}", sanitized);
+    }
+    
     // Limit length
     if sanitized.len() > 255 {
-        sanitized.truncate(255);
+        let mut end = 255;
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        sanitized.truncate(end);
     }
     
     sanitized
 }
 
+/// Resolve a relative path inside a base directory, rejecting traversal outside of it
+pub fn resolve_within(base: &Path, relative: &str) -> Result<PathBuf> {
+    let relative_path = Path::new(relative);
+    let mut resolved = base.to_path_buf();
+    
+    for component in relative_path.components() {
+        match component {
+            Component::Normal(part) => resolved.push(sanitize_filename(&part.to_string_lossy())),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(anyhow::anyhow!("Path escapes base directory: {}", relative));
+            }
+        }
+    }
+    
+    Ok(resolved)
+}
+
+/// Calculate the SHA-256 digest of a file, streaming its contents
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Expand a leading `~` in configured paths to the user's home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Total size in bytes of all files under a directory; missing directories count as empty
+pub fn dir_size(path: &Path) -> Result<u64> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            total += dir_size(&entry.path())?;
+        } else {
+            total += metadata.len();
+        }
+    }
+    
+    Ok(total)
+}
+
 /// Get MIME type from file extension
 pub fn get_mime_type(path: &Path) -> &'static str {
     match get_file_extension(path).as_deref() {
@@ -167,6 +254,41 @@ mod tests {
         assert_eq!(sanitize_filename(""), "document");
     }
     
+    #[test]
+    fn test_sanitize_filename_blocks_traversal_and_reserved_names() {
+        let sanitized = sanitize_filename("../../etc/passwd");
+        assert!(!sanitized.contains('/'));
+        assert!(!sanitized.contains(".."));
+        
+        assert_eq!(sanitize_filename("CON"), "_CON");
+        assert_eq!(sanitize_filename("lpt1.txt"), "_lpt1.txt");
+        assert_eq!(sanitize_filename("report\u{0007}.pdf"), "report_.pdf");
+    }
+    
+    #[test]
+    fn test_resolve_within_rejects_escape() {
+        let base = Path::new("/cases/matter-1");
+        
+        assert_eq!(
+            resolve_within(base, "exports/docket.pdf").unwrap(),
+            PathBuf::from("/cases/matter-1/exports/docket.pdf")
+        );
+        assert!(resolve_within(base, "../../etc/passwd").is_err());
+        assert!(resolve_within(base, "/etc/passwd").is_err());
+    }
+    
+    #[test]
+    fn test_hash_file_matches_known_digest() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("fixture.txt");
+        std::fs::write(&path, "Hello, World!").unwrap();
+        
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f"
+        );
+    }
+    
     #[test]
     fn test_get_mime_type() {
         assert_eq!(get_mime_type(Path::new("test.pdf")), "application/pdf");
@@ -1,5 +1,7 @@
 // Utility modules for PA eDocket Desktop
 
+pub mod clock;
+pub mod correlation;
 pub mod crypto;
 pub mod date;
 pub mod validation;
@@ -1,5 +1,6 @@
 // Validation utilities for PA eDocket Desktop
 
+use crate::domain::{CourtLevel, SearchParams};
 use regex::Regex;
 use std::sync::OnceLock;
 
@@ -7,6 +8,8 @@ use std::sync::OnceLock;
 static EMAIL_REGEX: OnceLock<Regex> = OnceLock::new();
 static PHONE_REGEX: OnceLock<Regex> = OnceLock::new();
 static DOCKET_REGEX: OnceLock<Regex> = OnceLock::new();
+static MDJ_DOCKET_REGEX: OnceLock<Regex> = OnceLock::new();
+static APPELLATE_DOCKET_REGEX: OnceLock<Regex> = OnceLock::new();
 static OTN_REGEX: OnceLock<Regex> = OnceLock::new();
 static SID_REGEX: OnceLock<Regex> = OnceLock::new();
 
@@ -29,6 +32,20 @@ fn get_docket_regex() -> &'static Regex {
     })
 }
 
+fn get_mdj_docket_regex() -> &'static Regex {
+    MDJ_DOCKET_REGEX.get_or_init(|| {
+        // PA MDJ docket format: MJ-#####-XX-#######-YYYY
+        Regex::new(r"^MJ-\d{5}-[A-Z]{2}-\d{7}-\d{4}$").unwrap()
+    })
+}
+
+fn get_appellate_docket_regex() -> &'static Regex {
+    APPELLATE_DOCKET_REGEX.get_or_init(|| {
+        // PA appellate formats: #### EDA YYYY (Superior), ### MAP YYYY (Supreme), #### CD YYYY (Commonwealth)
+        Regex::new(r"^\d{1,4} (EDA|MDA|WDA|EAP|MAP|WAP|EAL|MAL|WAL|CD|MD) \d{4}$").unwrap()
+    })
+}
+
 fn get_otn_regex() -> &'static Regex {
     OTN_REGEX.get_or_init(|| {
         // PA OTN format: A ########-#
@@ -68,6 +85,75 @@ pub fn is_valid_sid(sid: &str) -> bool {
     get_sid_regex().is_match(sid)
 }
 
+/// Validate a PA docket number against the format used by the given court level
+pub fn validate_docket_number(docket: &str, court: CourtLevel) -> Result<(), String> {
+    let normalized = normalize_docket_number(docket.trim());
+
+    let (valid, expected) = match court {
+        CourtLevel::Mdj => (get_mdj_docket_regex().is_match(&normalized), "MJ-#####-CR-#######-YYYY"),
+        CourtLevel::Cp => (
+            normalized.starts_with("CP-") && get_docket_regex().is_match(&normalized),
+            "CP-##-CR-#######-YYYY",
+        ),
+        CourtLevel::App => (
+            get_appellate_docket_regex().is_match(&docket.trim().to_uppercase().replace('.', "")),
+            "#### EDA YYYY",
+        ),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid {:?} docket number '{}': expected format {}",
+            court, docket, expected
+        ))
+    }
+}
+
+/// Validate a PA OTN (Originating Tracking Number)
+pub fn validate_otn(otn: &str) -> Result<(), String> {
+    match normalize_otn(otn.trim()) {
+        Some(_) => Ok(()),
+        None => Err(format!("Invalid OTN '{}': expected format A ########-#", otn)),
+    }
+}
+
+/// Validate a PA SID (State ID Number)
+pub fn validate_sid(sid: &str) -> Result<(), String> {
+    match normalize_sid(sid.trim()) {
+        Some(_) => Ok(()),
+        None => Err(format!("Invalid SID '{}': expected format A#######", sid)),
+    }
+}
+
+/// Validate docket, OTN, and SID identifiers on search parameters before querying providers
+pub fn validate_search_identifiers(params: &SearchParams) -> Result<(), String> {
+    if let Some(docket) = params.docket.as_deref().filter(|d| !d.trim().is_empty()) {
+        match &params.court {
+            Some(court) => validate_docket_number(docket, court.clone())?,
+            None => {
+                let matches_any = [CourtLevel::Mdj, CourtLevel::Cp, CourtLevel::App]
+                    .into_iter()
+                    .any(|court| validate_docket_number(docket, court).is_ok());
+                if !matches_any {
+                    return Err(format!("Invalid docket number '{}'", docket));
+                }
+            }
+        }
+    }
+
+    if let Some(otn) = params.otn.as_deref().filter(|o| !o.trim().is_empty()) {
+        validate_otn(otn)?;
+    }
+
+    if let Some(sid) = params.sid.as_deref().filter(|s| !s.trim().is_empty()) {
+        validate_sid(sid)?;
+    }
+
+    Ok(())
+}
+
 /// Normalize phone number to standard format
 pub fn normalize_phone(phone: &str) -> Option<String> {
     let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
@@ -164,6 +250,77 @@ where
     Ok(())
 }
 
+/// Formatting tags kept by `sanitize_html`; attributes are always dropped
+const ALLOWED_HTML_TAGS: &[&str] = &[
+    "p", "br", "b", "strong", "i", "em", "u", "ul", "ol", "li", "blockquote",
+];
+
+/// Tags whose content is removed along with the tag
+const STRIPPED_HTML_CONTENT: &[&str] = &["script", "style", "iframe", "object", "embed", "template"];
+
+/// Sanitize user-supplied HTML for display. Allowlisted formatting tags are
+/// kept without attributes, script-like elements are removed with their
+/// content, and any other markup is dropped.
+pub fn sanitize_html(input: &str) -> String {
+    let lower = input.to_ascii_lowercase();
+    let mut output = String::with_capacity(input.len());
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let rest = &input[pos..];
+        let Some(ch) = rest.chars().next() else { break };
+
+        if ch != '<' {
+            match ch {
+                '>' => output.push_str("&gt;"),
+                '"' => output.push_str("&quot;"),
+                '\'' => output.push_str("&#x27;"),
+                _ => output.push(ch),
+            }
+            pos += ch.len_utf8();
+            continue;
+        }
+
+        let starts_markup = rest[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        let Some(end) = rest.find('>').filter(|_| starts_markup) else {
+            output.push_str("&lt;");
+            pos += 1;
+            continue;
+        };
+
+        let tag = &lower[pos + 1..pos + end];
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        pos += end + 1;
+
+        if ALLOWED_HTML_TAGS.contains(&name.as_str()) {
+            if closing {
+                output.push_str(&format!("</{}>", name));
+            } else {
+                output.push_str(&format!("<{}>", name));
+            }
+        } else if !closing && STRIPPED_HTML_CONTENT.contains(&name.as_str()) {
+            let close_tag = format!("</{}", name);
+            pos = match lower[pos..].find(&close_tag) {
+                Some(close) => {
+                    let close_start = pos + close;
+                    lower[close_start..].find('>').map_or(input.len(), |gt| close_start + gt + 1)
+                }
+                None => input.len(),
+            };
+        }
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,6 +352,34 @@ mod tests {
         assert!(!is_valid_docket_number("CP-51-CR-123-24"));
     }
     
+    #[test]
+    fn test_docket_validation_by_court_level() {
+        assert!(validate_docket_number("MJ-05201-CR-0000123-2024", CourtLevel::Mdj).is_ok());
+        assert!(validate_docket_number("mj-05201-tr-0000123-2024", CourtLevel::Mdj).is_ok());
+        assert!(validate_docket_number("CP-51-CR-1234567-2024", CourtLevel::Mdj).is_err());
+        
+        assert!(validate_docket_number("CP-51-CR-1234567-2024", CourtLevel::Cp).is_ok());
+        assert!(validate_docket_number("MJ-05201-CR-0000123-2024", CourtLevel::Cp).is_err());
+        assert!(validate_docket_number("CP-51-CR-123-2024", CourtLevel::Cp).is_err());
+        
+        assert!(validate_docket_number("1234 EDA 2023", CourtLevel::App).is_ok());
+        assert!(validate_docket_number("45 MAP 2024", CourtLevel::App).is_ok());
+        assert!(validate_docket_number("1234 C.D. 2022", CourtLevel::App).is_ok());
+        assert!(validate_docket_number("CP-51-CR-1234567-2024", CourtLevel::App).is_err());
+    }
+    
+    #[test]
+    fn test_otn_and_sid_validation() {
+        assert!(validate_otn("T 12345678-1").is_ok());
+        assert!(validate_otn("t 12345678-1").is_ok());
+        assert!(validate_otn("12345678-1").is_err());
+        assert!(validate_otn("T 1234-1").is_err());
+        
+        assert!(validate_sid("A1234567").is_ok());
+        assert!(validate_sid("a 1234567").is_ok());
+        assert!(validate_sid("1234567").is_err());
+    }
+    
     #[test]
     fn test_phone_normalization() {
         assert_eq!(normalize_phone("5551234567"), Some("(555) 123-4567".to_string()));
@@ -541,19 +541,27 @@ const CitationPanel: React.FC<{ onInsert: (citation: string) => void }> = ({ onI
 };
 
 // AI Assistant Component
+interface AISuggestion {
+  kind: 'clause_improvement' | 'citation_fix' | 'tone';
+  message: string;
+  replacement?: string;
+  start: number;
+  end: number;
+}
+
 const AIAssistant: React.FC<{ editor: any; matterId: string }> = ({ editor, matterId }) => {
-  const [suggestions, setSuggestions] = useState<string[]>([]);
+  const [suggestions, setSuggestions] = useState<AISuggestion[]>([]);
 
   useEffect(() => {
     const getSuggestions = async () => {
       const content = editor.getText().slice(-500); // Last 500 chars
 
       try {
-        const aiSuggestions = await invoke('cmd_get_ai_suggestions', {
+        const aiSuggestions = await invoke<AISuggestion[]>('cmd_get_ai_suggestions', {
           matterId,
           context: content,
         });
-        setSuggestions(aiSuggestions as string[]);
+        setSuggestions(aiSuggestions);
       } catch (error) {
         console.error('AI suggestions failed:', error);
       }
@@ -573,7 +581,10 @@ const AIAssistant: React.FC<{ editor: any; matterId: string }> = ({ editor, matt
       <div className="space-y-2">
         {suggestions.map((suggestion, index) => (
           <div key={index} className="p-3 bg-blue-50 rounded-md text-sm">
-            {suggestion}
+            {suggestion.message}
+            {suggestion.replacement && (
+              <div className="mt-1 text-gray-600">Suggested: "{suggestion.replacement}"</div>
+            )}
           </div>
         ))}
       </div>
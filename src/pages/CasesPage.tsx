@@ -9,6 +9,7 @@ import {
   FolderOpen,
   ChevronRight,
   ChevronDown,
+  ChevronLeft,
   Plus,
   Search,
   Filter,
@@ -39,6 +40,18 @@ interface Matter {
   total_expenses: number;
 }
 
+interface MatterPage {
+  items: {
+    matter: Omit<Matter, 'next_deadline' | 'total_time' | 'total_expenses'>;
+    next_deadline?: { event_date: string };
+    total_time: number;
+    total_expenses: number;
+  }[];
+  page: number;
+  limit: number;
+  total: number;
+}
+
 interface CaseFolder {
   id: string;
   name: string;
@@ -57,6 +70,8 @@ interface PracticeArea {
   children?: PracticeArea[];
 }
 
+const PAGE_SIZE = 50;
+
 export const CasesPage: React.FC = () => {
   const navigate = useNavigate();
   const [matters, setMatters] = useState<Matter[]>([]);
@@ -69,20 +84,36 @@ export const CasesPage: React.FC = () => {
   const [expandedFolders, setExpandedFolders] = useState<Set<string>>(new Set());
   const [expandedAreas, setExpandedAreas] = useState<Set<string>>(new Set());
   const [loading, setLoading] = useState(true);
+  const [page, setPage] = useState(1);
+  const [totalMatters, setTotalMatters] = useState(0);
 
   useEffect(() => {
     loadData();
-  }, [selectedFolder, selectedPracticeArea]);
+  }, [selectedFolder, selectedPracticeArea, page]);
+
+  const totalPages = Math.max(1, Math.ceil(totalMatters / PAGE_SIZE));
 
   const loadData = async () => {
     setLoading(true);
     try {
       // Load matters
-      const mattersData = await invoke<Matter[]>('cmd_list_matters', {
-        folderId: selectedFolder,
-        practiceAreaId: selectedPracticeArea,
+      const mattersPage = await invoke<MatterPage>('cmd_list_matters', {
+        filter: {
+          folder_id: selectedFolder,
+          practice_area_id: selectedPracticeArea,
+        },
+        page,
+        limit: PAGE_SIZE,
       });
-      setMatters(mattersData);
+      setTotalMatters(mattersPage.total);
+      setMatters(
+        mattersPage.items.map(summary => ({
+          ...summary.matter,
+          next_deadline: summary.next_deadline?.event_date,
+          total_time: summary.total_time,
+          total_expenses: summary.total_expenses,
+        }))
+      );
 
       // Load folders hierarchy
       const foldersData = await invoke<CaseFolder[]>('cmd_get_case_folders');
@@ -172,6 +203,7 @@ export const CasesPage: React.FC = () => {
           style={{ paddingLeft: `${level * 16 + 12}px` }}
           onClick={() => {
             setSelectedFolder(folder.id);
+            setPage(1);
             if (hasChildren) toggleFolder(folder.id);
           }}
         >
@@ -202,6 +234,7 @@ export const CasesPage: React.FC = () => {
           style={{ paddingLeft: `${level * 16 + 12}px` }}
           onClick={() => {
             setSelectedPracticeArea(area.id);
+            setPage(1);
             if (hasChildren) togglePracticeArea(area.id);
           }}
         >
@@ -382,6 +415,36 @@ export const CasesPage: React.FC = () => {
             </div>
           )}
         </div>
+
+        {/* Pagination */}
+        {totalMatters > 0 && (
+          <div className="flex items-center justify-between px-6 py-3 border-t border-gray-200 bg-white text-sm text-gray-600">
+            <span>
+              Showing {(page - 1) * PAGE_SIZE + 1}–{Math.min(page * PAGE_SIZE, totalMatters)} of {totalMatters} cases
+            </span>
+            <div className="flex items-center gap-2">
+              <button
+                onClick={() => setPage(p => p - 1)}
+                disabled={page <= 1 || loading}
+                className="flex items-center gap-1 px-3 py-1 border rounded hover:bg-gray-100 disabled:opacity-50"
+              >
+                <ChevronLeft className="w-4 h-4" />
+                Previous
+              </button>
+              <span>
+                Page {page} of {totalPages}
+              </span>
+              <button
+                onClick={() => setPage(p => p + 1)}
+                disabled={page >= totalPages || loading}
+                className="flex items-center gap-1 px-3 py-1 border rounded hover:bg-gray-100 disabled:opacity-50"
+              >
+                Next
+                <ChevronRight className="w-4 h-4" />
+              </button>
+            </div>
+          </div>
+        )}
       </div>
     </div>
   );
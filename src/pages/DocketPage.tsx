@@ -64,7 +64,7 @@ export const DocketPage: React.FC = () => {
     try {
       await invoke('cmd_export', {
         exportType: format,
-        payload: format === 'json' ? docket : { docket_id: docket.id }
+        payload: docket
       });
     } catch (err) {
       console.error('Export failed:', err);
@@ -130,6 +130,7 @@ export default function SettlementCalculatorWizard() {
   const [opposingCounsel, setOpposingCounsel] = useState('');
   const [insuranceCompany, setInsuranceCompany] = useState('');
   const [calculatedBy, setCalculatedBy] = useState('');
+  const [riskProfile, setRiskProfile] = useState('Neutral');
 
   const steps = [
     { number: 1, title: 'Case Information', icon: Scale },
@@ -189,6 +190,12 @@ export default function SettlementCalculatorWizard() {
 
   const injurySeverities = ['Catastrophic', 'Severe', 'Moderate', 'Minor'];
 
+  const riskProfiles = [
+    { value: 'RiskAverse', name: 'Risk Averse - settle early for a certain recovery' },
+    { value: 'Neutral', name: 'Neutral' },
+    { value: 'Aggressive', name: 'Aggressive - hold out for a larger recovery' },
+  ];
+
   const medicalCategories = [
     'Emergency',
     'Hospital',
@@ -316,16 +323,19 @@ export default function SettlementCalculatorWizard() {
       };
 
       // Call Tauri command to calculate settlement
+      // The calculating user comes from the signed-in session
       const result = await invoke('cmd_calculate_settlement', {
-        matterId,
-        caseType,
-        plaintiffName,
-        defendantName,
-        economicDamages: economicDamagesPayload,
-        injuryDetails,
-        liabilityPercentage,
-        jurisdiction,
-        calculatedBy,
+        request: {
+          matter_id: matterId,
+          case_type: caseType,
+          plaintiff_name: plaintiffName,
+          defendant_name: defendantName,
+          economic_damages: economicDamagesPayload,
+          injury_details: injuryDetails,
+          liability_percentage: liabilityPercentage,
+          jurisdiction,
+          risk_profile: riskProfile,
+        },
       });
 
       // Navigate to results page
@@ -1231,6 +1241,20 @@ export default function SettlementCalculatorWizard() {
                   />
                 </FormField>
 
+                <FormField label="Client Risk Profile" required>
+                  <select
+                    value={riskProfile}
+                    onChange={(e) => setRiskProfile(e.target.value)}
+                    className="form-select"
+                  >
+                    {riskProfiles.map((profile) => (
+                      <option key={profile.value} value={profile.value}>
+                        {profile.name}
+                      </option>
+                    ))}
+                  </select>
+                </FormField>
+
                 <FormField label="Calculated By" required>
                   <input
                     type="text"
@@ -1340,6 +1364,10 @@ export default function SettlementCalculatorWizard() {
                     label="Liability Factors"
                     value={`${liabilityFactors.length} factors documented`}
                   />
+                  <ReviewItem
+                    label="Client Risk Profile"
+                    value={riskProfile.replace(/([A-Z])/g, ' $1').trim()}
+                  />
                 </ReviewSection>
 
                 {/* Advanced Options Summary */}